@@ -6,26 +6,189 @@ mod test_helpers;
 
 use std::sync::Arc;
 use tauri::{Listener, Manager};
-use tokio::sync::mpsc;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use tokio::sync::{mpsc, watch};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt};
 
-use twitch_backend::{AuthCommand, BackendEvent};
+use twitch_backend::{
+    app_services::AppServices,
+    config::GlobalShortcutAction,
+    log_file::{self, DailyLogFile},
+    log_scrub::RedactingMakeWriter,
+    notification_snooze::SnoozeDuration,
+    support, AuthCommand, BackendEvent, LoginProgress, RawDisplayData,
+};
+use twitch_menu_tauri::dashboard::{
+    open_dashboard_window, open_quick_list_window, serve_thumbnail, start_dashboard_listener,
+    Dashboard, THUMBNAIL_SCHEME,
+};
 use twitch_menu_tauri::display::DisplayBackend;
 use twitch_menu_tauri::display_state::DisplayState;
-use twitch_menu_tauri::tray::{handle_menu_event, TrayBackend};
-use twitch_settings_tauri::window::open_streamer_settings_window;
+use twitch_menu_tauri::tray::{
+    handle_menu_event, TrayBackend, MENU_OPENED_EVENT, MENU_OPENING_EVENT,
+};
+use twitch_menu_tauri::window_menu::WindowMenuBackend;
+use twitch_settings_tauri::auth_state::spawn_auth_state_emitter;
+use twitch_settings_tauri::window::{
+    close_login_window, open_changelog_window, open_login_window, open_settings_window,
+    open_streamer_settings_window,
+};
+
+/// Set to any value to use the menu window even when a system tray is available.
+const WINDOW_MODE_ENV: &str = "TWITCH_TRAY_WINDOW_MODE";
+
+/// Daily log files are named `twitch-tray.<date>.log`.
+const LOG_FILE_PREFIX: &str = "twitch-tray";
+
+/// Shows the menu in the system tray, or in a window when there is no tray.
+fn setup_menu(
+    app: &tauri::AppHandle,
+    display_rx: watch::Receiver<RawDisplayData>,
+) -> anyhow::Result<()> {
+    // The dashboard follows the same snapshots whichever surface shows the menu
+    let dashboard = Arc::new(Dashboard::default());
+    app.manage(Arc::clone(&dashboard));
+    start_dashboard_listener(display_rx.clone(), dashboard, app.clone());
+
+    // Create the tray backend (holds AppHandle — only Tauri-coupled display type)
+    let tray_backend = Arc::new(TrayBackend::new(app.clone()));
+
+    let tray = if std::env::var_os(WINDOW_MODE_ENV).is_some() {
+        tracing::info!("{} set, using the menu window", WINDOW_MODE_ENV);
+        None
+    } else if twitch_menu_tauri::tray_host::tray_host_missing() {
+        tracing::warn!("No system tray host is running, falling back to the menu window");
+        None
+    } else {
+        match tray_backend.create_tray() {
+            Ok(tray) => Some(tray),
+            Err(e) => {
+                tracing::warn!(
+                    "System tray unavailable ({}), falling back to the menu window",
+                    e
+                );
+                None
+            }
+        }
+    };
+
+    let Some(tray) = tray else {
+        let window_backend = Arc::new(WindowMenuBackend::new(app.clone()));
+        app.manage(Arc::clone(&window_backend));
+        window_backend.update(DisplayState::unauthenticated())?;
+        window_backend.open()?;
+        twitch_menu_tauri::start_listener(display_rx, window_backend);
+        return Ok(());
+    };
+
+    // Set initial menu (unauthenticated state — no network needed)
+    if let Err(e) = tray_backend.update(DisplayState::unauthenticated()) {
+        tracing::error!("Failed to build initial menu: {}", e);
+    }
+
+    // Set up menu event handler
+    tray.on_menu_event(|app, event| {
+        handle_menu_event(app, event.id().as_ref());
+    });
+
+    // Start display listener: converts RawDisplayData → DisplayState → tray update
+    twitch_menu_tauri::start_listener(display_rx, Arc::clone(&tray_backend));
+
+    // Keep the schedule header countdown current between rebuilds
+    twitch_menu_tauri::start_schedule_header_timer(tray_backend);
+
+    Ok(())
+}
+
+/// Keeps `Config::global_shortcut` registered, re-registering whenever it
+/// changes. The action is read when the shortcut is pressed, so changing
+/// only that doesn't need a new registration.
+fn spawn_global_shortcut_watcher(
+    app: tauri::AppHandle,
+    mut display_rx: watch::Receiver<RawDisplayData>,
+) {
+    tauri::async_runtime::spawn(async move {
+        // What the config last asked for, and what actually got registered
+        let mut wanted: Option<String> = None;
+        let mut registered: Option<String> = None;
+        loop {
+            let shortcut = display_rx
+                .borrow_and_update()
+                .config
+                .global_shortcut
+                .clone();
+            if shortcut != wanted {
+                wanted = shortcut;
+                if let Some(old) = registered.take() {
+                    if let Err(e) = app.global_shortcut().unregister(old.as_str()) {
+                        tracing::warn!("Failed to unregister global shortcut {}: {}", old, e);
+                    }
+                }
+                if let Some(new) = &wanted {
+                    let rx = display_rx.clone();
+                    let result = app.global_shortcut().on_shortcut(
+                        new.as_str(),
+                        move |app, _shortcut, event| {
+                            if event.state == ShortcutState::Pressed {
+                                run_shortcut_action(app, rx.borrow().config.global_shortcut_action);
+                            }
+                        },
+                    );
+                    match result {
+                        Ok(()) => {
+                            tracing::info!("Registered global shortcut {}", new);
+                            registered = wanted.clone();
+                        }
+                        // Usually another app already has the combination
+                        Err(e) => {
+                            tracing::warn!("Failed to register global shortcut {}: {}", new, e)
+                        }
+                    }
+                }
+            }
+            if display_rx.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+fn run_shortcut_action(app: &tauri::AppHandle, action: GlobalShortcutAction) {
+    match action {
+        GlobalShortcutAction::Dashboard => open_dashboard_window(app),
+        GlobalShortcutAction::QuickList => open_quick_list_window(app),
+        GlobalShortcutAction::Settings => open_settings_window(app),
+    }
+}
 
 fn main() {
-    // Initialize logging
+    // Initialize logging to stdout and, if it can be opened, today's log file
+    let log_file = support::log_dir()
+        .and_then(|dir| Ok(DailyLogFile::open(&dir, LOG_FILE_PREFIX)?))
+        .map_err(|e| eprintln!("Failed to open log file: {e:#}"))
+        .ok();
+    let (log_filter, log_filter_handle) = reload::Layer::new(log_file::initial_filter());
     tracing_subscriber::registry()
-        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
-        .with(tracing_subscriber::fmt::layer())
+        .with(log_filter)
+        .with(
+            tracing_subscriber::fmt::layer().with_writer(RedactingMakeWriter::new(std::io::stdout)),
+        )
+        .with(log_file.map(|file| {
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(RedactingMakeWriter::new(std::sync::Mutex::new(file)))
+        }))
         .init();
 
     tracing::info!("Starting Twitch Tray");
 
     // Build the Tauri application
     tauri::Builder::default()
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        // Saved stream thumbnails for the dashboard page
+        .register_uri_scheme_protocol(THUMBNAIL_SCHEME, |_ctx, request| serve_thumbnail(&request))
         .invoke_handler(tauri::generate_handler![
             twitch_settings_tauri::commands::get_config,
             twitch_settings_tauri::commands::save_config,
@@ -33,10 +196,37 @@ fn main() {
             twitch_settings_tauri::commands::get_followed_categories,
             twitch_settings_tauri::commands::get_followed_channels_list,
             twitch_settings_tauri::commands::is_debug_build,
+            twitch_settings_tauri::commands::get_changelog,
             twitch_settings_tauri::commands::get_debug_schedule_data,
             twitch_settings_tauri::commands::get_debug_hotness_data,
+            twitch_settings_tauri::commands::create_diagnostics_bundle,
+            twitch_settings_tauri::commands::get_diagnostics,
+            twitch_settings_tauri::commands::get_env_proxy,
+            twitch_settings_tauri::commands::test_proxy_connection,
+            twitch_settings_tauri::commands::open_log_dir,
+            twitch_settings_tauri::commands::export_data,
+            twitch_settings_tauri::commands::import_data,
+            twitch_settings_tauri::commands::get_live_at,
+            twitch_settings_tauri::commands::get_history_summary,
+            twitch_settings_tauri::commands::get_streamer_history,
+            twitch_settings_tauri::commands::get_followed_category_suggestions,
+            twitch_settings_tauri::commands::accept_category_suggestion,
+            twitch_settings_tauri::commands::dismiss_category_suggestion,
+            twitch_settings_tauri::commands::set_category_notifications,
+            twitch_settings_tauri::commands::set_importance_bulk,
+            twitch_settings_tauri::commands::import_streamer_list,
+            twitch_settings_tauri::commands::undo_settings_change,
+            twitch_settings_tauri::commands::get_autostart,
+            twitch_settings_tauri::commands::set_autostart,
+            twitch_settings_tauri::commands::get_about_info,
+            twitch_settings_tauri::commands::get_auth_state,
+            twitch_menu_tauri::window_menu::get_menu_rows,
+            twitch_menu_tauri::window_menu::activate_menu_row,
+            twitch_menu_tauri::dashboard::get_menu_data,
+            twitch_menu_tauri::dashboard::set_dashboard_pinned,
+            twitch_menu_tauri::dashboard::get_category_streams,
         ])
-        .setup(|app| {
+        .setup(move |app| {
             // Enter the Tauri-managed tokio runtime so tokio::spawn works
             // throughout setup (needed by twitch_backend::start)
             let _guard = tauri::async_runtime::handle().inner().enter();
@@ -44,33 +234,59 @@ fn main() {
             // Start the backend (spawns all polling/notification tasks)
             let handle = twitch_backend::start().expect("Failed to start backend");
 
+            // Show "What's New" once after an update
+            let services = handle.services.clone();
+            let version = app.package_info().version.to_string();
+            let app_handle_for_changelog = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                match services.note_app_version(&version).await {
+                    Ok(true) => open_changelog_window(&app_handle_for_changelog),
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!("Failed to record app version: {}", e),
+                }
+            });
+
+            // Keep the settings window's auth banner current
+            spawn_auth_state_emitter(
+                app.handle().clone(),
+                handle.services.clone(),
+                handle.login_progress_rx.clone(),
+                handle.event_tx.subscribe(),
+            );
+
+            // Show the device code while a login waits on it
+            let mut login_progress_rx = handle.login_progress_rx.clone();
+            let app_handle_login = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                while login_progress_rx.changed().await.is_ok() {
+                    let pending = matches!(
+                        *login_progress_rx.borrow_and_update(),
+                        Some(LoginProgress::PendingCode { .. })
+                    );
+                    if pending {
+                        open_login_window(&app_handle_login);
+                    } else {
+                        close_login_window(&app_handle_login);
+                    }
+                }
+            });
+
             // Store services for Tauri commands
             app.manage(handle.services);
 
             // Store auth sender so the run() callback can route login/logout
             app.manage(handle.auth_cmd_tx);
 
-            // Create the tray backend (holds AppHandle — only Tauri-coupled display type)
-            let tray_backend = Arc::new(TrayBackend::new(app.handle().clone()));
+            spawn_global_shortcut_watcher(app.handle().clone(), handle.display_rx.clone());
+            tauri::async_runtime::spawn(log_file::follow_log_level(
+                log_filter_handle,
+                handle.display_rx.clone(),
+            ));
 
-            // Create the tray icon
-            let tray = tray_backend
-                .create_tray()
-                .expect("Failed to create tray icon");
-
-            // Set initial menu (unauthenticated state — no network needed)
-            if let Err(e) = tray_backend.update(DisplayState::unauthenticated()) {
-                tracing::error!("Failed to build initial menu: {}", e);
+            if let Err(e) = setup_menu(app.handle(), handle.display_rx) {
+                tracing::error!("Failed to set up menu: {}", e);
             }
 
-            // Set up menu event handler
-            tray.on_menu_event(|app, event| {
-                handle_menu_event(app, event.id().as_ref());
-            });
-
-            // Start display listener: converts RawDisplayData → DisplayState → tray update
-            twitch_menu_tauri::start_listener(handle.display_rx, tray_backend);
-
             // Event listener: open streamer settings window on request
             let mut event_rx = handle.event_tx.subscribe();
             let app_handle_for_events = app.handle().clone();
@@ -106,7 +322,7 @@ fn main() {
                 }
             }
 
-            // Wire login / logout menu events to backend auth commands
+            // Wire login / logout / switch user menu events to backend auth commands
             if let tauri::RunEvent::Ready = event {
                 let app_handle = app.clone();
                 app.listen("login-requested", move |_| {
@@ -115,6 +331,15 @@ fn main() {
                     }
                 });
 
+                let app_handle_cancel = app.clone();
+                app.listen("login-cancel-requested", move |_| {
+                    if let Some(tx) =
+                        app_handle_cancel.try_state::<mpsc::UnboundedSender<AuthCommand>>()
+                    {
+                        let _ = tx.send(AuthCommand::CancelLogin);
+                    }
+                });
+
                 let app_handle2 = app.clone();
                 app.listen("logout-requested", move |_| {
                     if let Some(tx) = app_handle2.try_state::<mpsc::UnboundedSender<AuthCommand>>()
@@ -122,6 +347,174 @@ fn main() {
                         let _ = tx.send(AuthCommand::Logout);
                     }
                 });
+
+                let app_handle_switch = app.clone();
+                app.listen("switch-user-requested", move |_| {
+                    if let Some(tx) =
+                        app_handle_switch.try_state::<mpsc::UnboundedSender<AuthCommand>>()
+                    {
+                        let _ = tx.send(AuthCommand::SwitchUser);
+                    }
+                });
+
+                // Record streams opened from the menu (feeds favourite watch-recency sorting)
+                let app_handle3 = app.clone();
+                app.listen("stream-opened", move |event| {
+                    let Ok(user_login) = serde_json::from_str::<String>(event.payload()) else {
+                        return;
+                    };
+                    if let Some(services) = app_handle3.try_state::<Arc<dyn AppServices>>() {
+                        let services = services.inner().clone();
+                        tauri::async_runtime::spawn(async move {
+                            services.record_stream_opened(&user_login).await;
+                        });
+                    }
+                });
+
+                // Undo the last streamer settings change from the menu
+                let app_handle_undo = app.clone();
+                app.listen("undo-settings-requested", move |_| {
+                    if let Some(services) = app_handle_undo.try_state::<Arc<dyn AppServices>>() {
+                        let services = services.inner().clone();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = services.undo_settings_change().await {
+                                tracing::error!("Failed to undo settings change: {}", e);
+                            }
+                        });
+                    }
+                });
+
+                // "Create Diagnostics Bundle" from the menu: write it, copy
+                // its path and show it in the file manager
+                let app_handle_diagnostics = app.clone();
+                app.listen("diagnostics-bundle-requested", move |_| {
+                    let app = app_handle_diagnostics.clone();
+                    let Some(services) = app.try_state::<Arc<dyn AppServices>>() else {
+                        return;
+                    };
+                    let services = services.inner().clone();
+                    tauri::async_runtime::spawn(async move {
+                        let path = match services.create_diagnostics_bundle().await {
+                            Ok(path) => path,
+                            Err(e) => {
+                                tracing::error!("Failed to create diagnostics bundle: {}", e);
+                                return;
+                            }
+                        };
+                        if let Err(e) = app.clipboard().write_text(path.display().to_string()) {
+                            tracing::warn!("Failed to copy diagnostics bundle path: {}", e);
+                        }
+                        if let Some(dir) = path.parent() {
+                            if let Err(e) = open::that(dir) {
+                                tracing::warn!("Failed to show diagnostics bundle: {}", e);
+                            }
+                        }
+                    });
+                });
+
+                // "Pin to top" / "Unpin" from the menu or dashboard
+                let app_handle_pin = app.clone();
+                app.listen("stream-pin-requested", move |event| {
+                    let Ok((user_login, pinned)) =
+                        serde_json::from_str::<(String, bool)>(event.payload())
+                    else {
+                        return;
+                    };
+                    if let Some(services) = app_handle_pin.try_state::<Arc<dyn AppServices>>() {
+                        let services = services.inner().clone();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = services.set_stream_pinned(&user_login, pinned).await {
+                                tracing::error!("Failed to update pinned streams: {}", e);
+                            }
+                        });
+                    }
+                });
+
+                // "Snooze notifications" from the menu, for everyone or one streamer
+                let app_handle_snooze = app.clone();
+                app.listen("snooze-requested", move |event| {
+                    let Ok((user_login, duration)) =
+                        serde_json::from_str::<(Option<String>, SnoozeDuration)>(event.payload())
+                    else {
+                        return;
+                    };
+                    if let Some(services) = app_handle_snooze.try_state::<Arc<dyn AppServices>>() {
+                        let services = services.inner().clone();
+                        tauri::async_runtime::spawn(async move {
+                            services.snooze_notifications(user_login, duration).await;
+                        });
+                    }
+                });
+
+                // "Resume notifications" from the menu
+                let app_handle_resume = app.clone();
+                app.listen("resume-notifications-requested", move |event| {
+                    let Ok(user_login) = serde_json::from_str::<Option<String>>(event.payload())
+                    else {
+                        return;
+                    };
+                    if let Some(services) = app_handle_resume.try_state::<Arc<dyn AppServices>>() {
+                        let services = services.inner().clone();
+                        tauri::async_runtime::spawn(async move {
+                            services.resume_notifications(user_login).await;
+                        });
+                    }
+                });
+
+                // "Refresh now" from the menu
+                let app_handle_refresh = app.clone();
+                app.listen("refresh-requested", move |_| {
+                    if let Some(services) = app_handle_refresh.try_state::<Arc<dyn AppServices>>() {
+                        let services = services.inner().clone();
+                        tauri::async_runtime::spawn(async move {
+                            services.refresh_now().await;
+                        });
+                    }
+                });
+
+                // Refresh live streams when the menu is about to show (rate-limited by the backend)
+                let app_handle5 = app.clone();
+                app.listen(MENU_OPENING_EVENT, move |_| {
+                    if let Some(services) = app_handle5.try_state::<Arc<dyn AppServices>>() {
+                        let services = services.inner().clone();
+                        tauri::async_runtime::spawn(async move {
+                            services.refresh_on_menu_open().await;
+                        });
+                    }
+                });
+
+                // Opening the menu clears the unread icon
+                let app_handle_seen = app.clone();
+                app.listen(MENU_OPENED_EVENT, move |_| {
+                    if let Some(services) = app_handle_seen.try_state::<Arc<dyn AppServices>>() {
+                        let services = services.inner().clone();
+                        tauri::async_runtime::spawn(async move {
+                            services.mark_menu_seen().await;
+                        });
+                    }
+                });
+
+                // Custom footer actions: the menu sends the action's key, which
+                // only matches while the action is unchanged in config.custom_actions
+                let app_handle4 = app.clone();
+                app.listen("custom-action-requested", move |event| {
+                    let Ok(key) = serde_json::from_str::<String>(event.payload()) else {
+                        return;
+                    };
+                    let Some(services) = app_handle4.try_state::<Arc<dyn AppServices>>() else {
+                        return;
+                    };
+                    let config = services.get_config();
+                    let Some(action) =
+                        twitch_backend::custom_action::find(&config.custom_actions, &key)
+                    else {
+                        tracing::warn!("Custom action {} changed since the menu was built", key);
+                        return;
+                    };
+                    if let Err(e) = twitch_backend::custom_action::run(action) {
+                        tracing::error!("Custom action '{}' failed: {}", action.label, e);
+                    }
+                });
             }
         });
 }
@@ -38,6 +38,7 @@ pub fn make_scheduled(broadcaster_name: &str, hours_from_now: i64) -> ScheduledS
         category_id: Some("123".to_string()),
         is_recurring: false,
         is_inferred: false,
+        confidence: None,
     }
 }
 
@@ -0,0 +1,43 @@
+//! Embeds build metadata for the About window (`src/about.rs`).
+//!
+//! - `TWITCH_TRAY_COMMIT`: short git commit hash, or `unknown` outside a checkout
+//! - `TWITCH_TRAY_BUILD_EPOCH`: build time in Unix seconds; `SOURCE_DATE_EPOCH`
+//!   wins when set, for reproducible builds
+
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    let git_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../.git");
+    // Re-run when HEAD moves: a checkout changes HEAD, a commit changes the branch ref
+    println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+    if let Ok(head) = std::fs::read_to_string(git_dir.join("HEAD")) {
+        if let Some(branch_ref) = head.trim().strip_prefix("ref: ") {
+            println!(
+                "cargo:rerun-if-changed={}",
+                git_dir.join(branch_ref).display()
+            );
+        }
+    }
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=TWITCH_TRAY_COMMIT={commit}");
+
+    let build_epoch = std::env::var("SOURCE_DATE_EPOCH").unwrap_or_else(|_| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs().to_string())
+            .unwrap_or_default()
+    });
+    println!("cargo:rustc-env=TWITCH_TRAY_BUILD_EPOCH={build_epoch}");
+}
@@ -0,0 +1,153 @@
+//! Version, build and license details for the About window.
+//!
+//! The commit hash and build time are embedded by `build.rs`.
+
+use chrono::DateTime;
+use serde::Serialize;
+
+/// A third-party project the app is built on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Acknowledgement {
+    pub name: &'static str,
+    pub license: &'static str,
+    pub url: &'static str,
+}
+
+/// The main libraries the app ships with. Their transitive dependencies are
+/// covered by the licenses allowed in `deny.toml`.
+pub const ACKNOWLEDGEMENTS: &[Acknowledgement] = &[
+    Acknowledgement {
+        name: "Tauri",
+        license: "MIT OR Apache-2.0",
+        url: "https://tauri.app",
+    },
+    Acknowledgement {
+        name: "Tokio",
+        license: "MIT",
+        url: "https://tokio.rs",
+    },
+    Acknowledgement {
+        name: "reqwest",
+        license: "MIT OR Apache-2.0",
+        url: "https://github.com/seanmonstar/reqwest",
+    },
+    Acknowledgement {
+        name: "rustls",
+        license: "Apache-2.0 OR ISC OR MIT",
+        url: "https://github.com/rustls/rustls",
+    },
+    Acknowledgement {
+        name: "Serde",
+        license: "MIT OR Apache-2.0",
+        url: "https://serde.rs",
+    },
+    Acknowledgement {
+        name: "chrono and chrono-tz",
+        license: "MIT OR Apache-2.0",
+        url: "https://github.com/chronotope/chrono",
+    },
+    Acknowledgement {
+        name: "rusqlite",
+        license: "MIT",
+        url: "https://github.com/rusqlite/rusqlite",
+    },
+    Acknowledgement {
+        name: "SQLite",
+        license: "Public Domain",
+        url: "https://sqlite.org",
+    },
+    Acknowledgement {
+        name: "keyring",
+        license: "MIT OR Apache-2.0",
+        url: "https://github.com/hwchen/keyring-rs",
+    },
+    Acknowledgement {
+        name: "notify-rust",
+        license: "MIT OR Apache-2.0",
+        url: "https://github.com/hoodie/notify-rust",
+    },
+    Acknowledgement {
+        name: "zbus",
+        license: "MIT",
+        url: "https://github.com/dbus2/zbus",
+    },
+    Acknowledgement {
+        name: "tracing",
+        license: "MIT",
+        url: "https://github.com/tokio-rs/tracing",
+    },
+    Acknowledgement {
+        name: "zip",
+        license: "MIT",
+        url: "https://github.com/zip-rs/zip2",
+    },
+    Acknowledgement {
+        name: "unicode-width",
+        license: "MIT OR Apache-2.0",
+        url: "https://github.com/unicode-rs/unicode-width",
+    },
+];
+
+/// Everything the About window shows.
+#[derive(Debug, Clone, Serialize)]
+pub struct AboutInfo {
+    pub version: String,
+    /// Short git commit hash, or `unknown` when built outside a checkout.
+    pub commit: String,
+    /// `"2026-01-31 14:05 UTC"`, or `unknown`.
+    pub build_date: String,
+    /// OS and CPU architecture, e.g. `linux x86_64`.
+    pub platform: String,
+    pub license: &'static str,
+    pub acknowledgements: &'static [Acknowledgement],
+}
+
+/// Details of the running build.
+pub fn about_info() -> AboutInfo {
+    AboutInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        commit: env!("TWITCH_TRAY_COMMIT").to_string(),
+        build_date: format_build_date(env!("TWITCH_TRAY_BUILD_EPOCH")),
+        platform: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        license: "MIT",
+        acknowledgements: ACKNOWLEDGEMENTS,
+    }
+}
+
+/// Formats the Unix-seconds build time from `build.rs`.
+fn format_build_date(epoch: &str) -> String {
+    epoch
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map_or_else(
+            || "unknown".to_string(),
+            |t| t.format("%Y-%m-%d %H:%M UTC").to_string(),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_date_formats_epoch_seconds() {
+        assert_eq!(format_build_date("1767225600"), "2026-01-01 00:00 UTC");
+    }
+
+    #[test]
+    fn unparseable_build_date_is_unknown() {
+        assert_eq!(format_build_date(""), "unknown");
+        assert_eq!(format_build_date("yesterday"), "unknown");
+    }
+
+    #[test]
+    fn about_info_describes_this_build() {
+        let info = about_info();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.commit.is_empty());
+        assert_ne!(info.build_date, "unknown");
+        assert!(info.platform.contains(std::env::consts::OS));
+        assert!(!info.acknowledgements.is_empty());
+    }
+}
@@ -1,7 +1,17 @@
+use std::path::PathBuf;
+
 use async_trait::async_trait;
 
-use crate::config::{Config, FollowedCategory};
-use crate::twitch::{ApiError, Category, FollowedChannel};
+use crate::category_suggestions::CategorySuggestion;
+use crate::config::{CategoryNotifications, Config, FollowedCategory, StreamerImportance};
+use crate::data_export::ExportManifest;
+use crate::followed_list::{FollowedChannelsPage, FollowedQuery};
+use crate::handle::AuthStatus;
+use crate::notification_snooze::SnoozeDuration;
+use crate::stream_stats::StreamerStats;
+use crate::streamer_list::{StreamerListFormat, StreamerListImport};
+use crate::support::AppDiagnostics;
+use crate::twitch::{ApiError, Category, Stream};
 
 #[derive(serde::Serialize, Clone, Debug, PartialEq)]
 pub struct DebugStreamEntry {
@@ -11,6 +21,24 @@ pub struct DebugStreamEntry {
     pub started_at: i64, // Unix timestamp (seconds)
 }
 
+/// A followed broadcast that was live at a queried time (settings History view).
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct LiveAtEntry {
+    pub broadcaster_name: String,
+    pub broadcaster_login: String,
+    pub started_at: i64, // Unix timestamp (seconds)
+    /// `None` when the end wasn't seen: still live, or the app wasn't running.
+    pub ended_at: Option<i64>,
+}
+
+/// Streams for a category's "Show more…" window.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CategoryStreamsPage {
+    pub streams: Vec<Stream>,
+    /// Where the next load starts; `None` once there are no more streams.
+    pub cursor: Option<String>,
+}
+
 /// Debug view entry for hotness detection state.
 #[derive(serde::Serialize, Clone, Debug, PartialEq)]
 pub struct DebugHotnessEntry {
@@ -36,19 +64,116 @@ pub struct DebugHotnessEntry {
 #[async_trait]
 pub trait AppServices: Send + Sync {
     fn get_config(&self) -> Config;
+    /// Saves a config from the settings window. Fails with
+    /// `config::ValidationErrors` when `Config::validated` rejects it.
     async fn save_config(&self, config: Config) -> anyhow::Result<()>;
     async fn search_categories(&self, query: &str) -> Result<Vec<Category>, ApiError>;
     fn get_followed_categories(&self) -> Vec<FollowedCategory>;
-    async fn get_followed_channels(&self) -> Vec<FollowedChannel>;
+    /// `Config::category_more_pages` pages of a category's top streams,
+    /// after `cursor` or from the top.
+    async fn get_category_streams_page(
+        &self,
+        category_id: &str,
+        cursor: Option<String>,
+    ) -> Result<CategoryStreamsPage, ApiError>;
+    /// Followed channels matching `query`, with live status, profile image
+    /// and importance.
+    async fn list_followed_channels(&self, query: &FollowedQuery) -> FollowedChannelsPage;
     async fn refresh_category_streams(&self);
     async fn refresh_schedules_from_db(&self);
     async fn get_debug_schedule_data(&self, start: i64, end: i64) -> Vec<DebugStreamEntry>;
     async fn get_debug_hotness_data(&self) -> Vec<DebugHotnessEntry>;
+    /// Writes a redacted diagnostics zip and returns where it was saved.
+    async fn create_diagnostics_bundle(&self) -> anyhow::Result<PathBuf>;
+    /// Auth, refresh, database and platform health for the Diagnostics tab.
+    async fn get_diagnostics(&self) -> AppDiagnostics;
+    /// Exports settings, and stream history if `include_history`, to a zip at
+    /// `path` (default: the downloads folder) and returns where it was saved.
+    async fn export_data(
+        &self,
+        path: Option<PathBuf>,
+        include_history: bool,
+    ) -> anyhow::Result<PathBuf>;
+    /// Replaces settings, and history if included, with an export's.
+    async fn import_data(&self, path: PathBuf) -> anyhow::Result<ExportManifest>;
+    /// Records that the user opened `user_login`'s stream (feeds watch-recency sorting).
+    async fn record_stream_opened(&self, user_login: &str);
+    /// Pins a channel to the top of the menu, or unpins it, and saves the config.
+    async fn set_stream_pinned(&self, user_login: &str, pinned: bool) -> anyhow::Result<()>;
+    /// Snoozes notifications about `user_login`, or all of them for `None`,
+    /// for `duration`.
+    async fn snooze_notifications(&self, user_login: Option<String>, duration: SnoozeDuration);
+    /// Ends `user_login`'s snooze, or the one for everyone for `None`, early.
+    async fn resume_notifications(&self, user_login: Option<String>);
+    /// Gives every streamer in `logins` `importance` and saves the config
+    /// once. Returns how many streamers changed.
+    async fn set_importance_bulk(
+        &self,
+        logins: Vec<String>,
+        importance: StreamerImportance,
+    ) -> anyhow::Result<usize>;
+    /// Reads a CSV or JSON list of channels, looks them up on Twitch and
+    /// gives the ones found `importance`, saving the config once.
+    async fn import_streamer_list(
+        &self,
+        path: PathBuf,
+        format: StreamerListFormat,
+        importance: StreamerImportance,
+    ) -> anyhow::Result<StreamerListImport>;
+    /// Followed broadcasts that were live at the Unix timestamp `at`.
+    async fn get_live_at(&self, at: i64) -> Vec<LiveAtEntry>;
+    /// Every followed channel's recorded streaming habits, busiest first.
+    async fn get_history_summary(&self) -> Vec<StreamerStats>;
+    /// One followed channel's recorded streaming habits, if it was ever seen live.
+    async fn get_streamer_history(&self, user_login: &str) -> Option<StreamerStats>;
+    /// Categories the user watches often but doesn't follow yet.
+    async fn get_followed_category_suggestions(&self) -> Vec<CategorySuggestion>;
+    /// Follows a suggested category and saves the config.
+    async fn accept_category_suggestion(&self, category: FollowedCategory) -> anyhow::Result<()>;
+    /// Stops suggesting a category and saves the config.
+    async fn dismiss_category_suggestion(&self, category_id: &str) -> anyhow::Result<()>;
+    /// Sets a followed category's notification preferences and saves the
+    /// config. Unlike `save_config`, category streams aren't re-fetched.
+    async fn set_category_notifications(
+        &self,
+        category_id: &str,
+        notifications: CategoryNotifications,
+    ) -> anyhow::Result<()>;
+    /// Records the running app version. Returns true when it differs from the
+    /// last one recorded, so the "What's New" window should open.
+    async fn note_app_version(&self, version: &str) -> anyhow::Result<bool>;
+    /// Reverts the most recent streamer settings change made through
+    /// `save_config` or `set_importance_bulk` and returns that streamer's login, or `None` if there
+    /// is nothing left to undo.
+    async fn undo_settings_change(&self) -> anyhow::Result<Option<String>>;
+    /// Current authentication status, including any login in progress.
+    async fn get_auth_state(&self) -> AuthStatus;
+    /// Re-fetches followed live streams because the menu is about to show.
+    /// Skipped if they were refreshed in the last few seconds.
+    async fn refresh_on_menu_open(&self);
+    /// Re-fetches live, category and scheduled streams now, for the menu's
+    /// "Refresh now". Ignored while a previous one is still running.
+    async fn refresh_now(&self);
+    /// Clears the unread indicator because the user opened the menu.
+    async fn mark_menu_seen(&self);
+    /// Whether the app is registered with the OS to start on login.
+    fn get_autostart(&self) -> bool;
+    /// Registers or unregisters starting on login and saves `start_on_login`.
+    async fn set_autostart(&self, enabled: bool) -> anyhow::Result<()>;
 }
 
 #[cfg(test)]
 pub mod mock {
     use super::*;
+    use crate::bulk_importance::{normalize_logins, set_importance};
+    use crate::category_suggestions::{accept_suggestion, dismiss_suggestion, suggest_categories};
+    use crate::changelog::{mark_version_seen, SeenVersion};
+    use crate::config::PinnedStream;
+    use crate::followed_list::list_followed;
+    use crate::pinned_streams::{pin_stream, unpin_stream};
+    use crate::settings_undo::UndoBuffer;
+    use crate::streamer_list::{is_valid_login, parse_streamer_list};
+    use crate::twitch::FollowedChannel;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Mutex;
 
@@ -62,11 +187,17 @@ pub mod mock {
         channels: Mutex<Vec<FollowedChannel>>,
         debug_entries: Mutex<Vec<super::DebugStreamEntry>>,
         hotness_entries: Mutex<Vec<super::DebugHotnessEntry>>,
+        live_at_entries: Mutex<Vec<super::LiveAtEntry>>,
+        history_stats: Mutex<Vec<StreamerStats>>,
+        watched_categories: Mutex<Vec<CategorySuggestion>>,
         save_config_count: AtomicUsize,
         refresh_category_count: AtomicUsize,
         refresh_schedules_count: AtomicUsize,
         debug_call_count: AtomicUsize,
         hotness_call_count: AtomicUsize,
+        diagnostics_call_count: AtomicUsize,
+        opened_streams: Mutex<Vec<String>>,
+        settings_undo: Mutex<UndoBuffer>,
     }
 
     impl MockAppServices {
@@ -77,11 +208,17 @@ pub mod mock {
                 channels: Mutex::new(Vec::new()),
                 debug_entries: Mutex::new(Vec::new()),
                 hotness_entries: Mutex::new(Vec::new()),
+                live_at_entries: Mutex::new(Vec::new()),
+                history_stats: Mutex::new(Vec::new()),
+                watched_categories: Mutex::new(Vec::new()),
                 save_config_count: AtomicUsize::new(0),
                 refresh_category_count: AtomicUsize::new(0),
                 refresh_schedules_count: AtomicUsize::new(0),
                 debug_call_count: AtomicUsize::new(0),
                 hotness_call_count: AtomicUsize::new(0),
+                diagnostics_call_count: AtomicUsize::new(0),
+                opened_streams: Mutex::new(Vec::new()),
+                settings_undo: Mutex::new(UndoBuffer::default()),
             }
         }
 
@@ -90,7 +227,7 @@ pub mod mock {
             *self.search_results.lock().unwrap() = results;
         }
 
-        /// Pre-configure the channel list that `get_followed_channels` will return.
+        /// Pre-configure the channels that `list_followed_channels` lists.
         pub fn set_channels(&self, channels: Vec<FollowedChannel>) {
             *self.channels.lock().unwrap() = channels;
         }
@@ -105,6 +242,21 @@ pub mod mock {
             *self.hotness_entries.lock().unwrap() = entries;
         }
 
+        /// Pre-configure the entries that `get_live_at` will return.
+        pub fn set_live_at_entries(&self, entries: Vec<super::LiveAtEntry>) {
+            *self.live_at_entries.lock().unwrap() = entries;
+        }
+
+        /// Pre-configure the stats `get_history_summary` and `get_streamer_history` return.
+        pub fn set_history_stats(&self, stats: Vec<StreamerStats>) {
+            *self.history_stats.lock().unwrap() = stats;
+        }
+
+        /// Pre-configure the watch counts `get_followed_category_suggestions` filters.
+        pub fn set_watched_categories(&self, watched: Vec<CategorySuggestion>) {
+            *self.watched_categories.lock().unwrap() = watched;
+        }
+
         pub fn save_config_count(&self) -> usize {
             self.save_config_count.load(Ordering::SeqCst)
         }
@@ -124,6 +276,14 @@ pub mod mock {
         pub fn hotness_call_count(&self) -> usize {
             self.hotness_call_count.load(Ordering::SeqCst)
         }
+
+        pub fn diagnostics_call_count(&self) -> usize {
+            self.diagnostics_call_count.load(Ordering::SeqCst)
+        }
+
+        pub fn opened_streams(&self) -> Vec<String> {
+            self.opened_streams.lock().unwrap().clone()
+        }
     }
 
     #[async_trait]
@@ -134,7 +294,15 @@ pub mod mock {
 
         async fn save_config(&self, config: Config) -> anyhow::Result<()> {
             self.save_config_count.fetch_add(1, Ordering::SeqCst);
-            *self.config.lock().unwrap() = config;
+            let config = config.validated()?;
+            {
+                let mut current = self.config.lock().unwrap();
+                self.settings_undo
+                    .lock()
+                    .unwrap()
+                    .record(&current.streamer_settings, &config.streamer_settings);
+                *current = config;
+            }
             self.refresh_category_streams().await;
             self.refresh_schedules_from_db().await;
             Ok(())
@@ -148,8 +316,26 @@ pub mod mock {
             self.config.lock().unwrap().followed_categories.clone()
         }
 
-        async fn get_followed_channels(&self) -> Vec<FollowedChannel> {
-            self.channels.lock().unwrap().clone()
+        async fn get_category_streams_page(
+            &self,
+            _category_id: &str,
+            _cursor: Option<String>,
+        ) -> Result<super::CategoryStreamsPage, ApiError> {
+            Ok(super::CategoryStreamsPage {
+                streams: Vec::new(),
+                cursor: None,
+            })
+        }
+
+        async fn list_followed_channels(&self, query: &FollowedQuery) -> FollowedChannelsPage {
+            let settings = self.config.lock().unwrap().streamer_settings.clone();
+            list_followed(
+                &self.channels.lock().unwrap(),
+                &[],
+                &std::collections::HashMap::new(),
+                &settings,
+                query,
+            )
         }
 
         async fn refresh_category_streams(&self) {
@@ -173,5 +359,190 @@ pub mod mock {
             self.hotness_call_count.fetch_add(1, Ordering::SeqCst);
             self.hotness_entries.lock().unwrap().clone()
         }
+
+        async fn create_diagnostics_bundle(&self) -> anyhow::Result<PathBuf> {
+            self.diagnostics_call_count.fetch_add(1, Ordering::SeqCst);
+            Ok(PathBuf::from("/tmp/twitch-tray-diagnostics.zip"))
+        }
+
+        async fn get_diagnostics(&self) -> AppDiagnostics {
+            AppDiagnostics::default()
+        }
+
+        async fn export_data(
+            &self,
+            path: Option<PathBuf>,
+            _include_history: bool,
+        ) -> anyhow::Result<PathBuf> {
+            Ok(path.unwrap_or_else(|| PathBuf::from("/tmp/twitch-tray-export.zip")))
+        }
+
+        async fn import_data(&self, _path: PathBuf) -> anyhow::Result<ExportManifest> {
+            Ok(ExportManifest::new(chrono::Utc::now(), false))
+        }
+
+        async fn record_stream_opened(&self, user_login: &str) {
+            self.opened_streams
+                .lock()
+                .unwrap()
+                .push(user_login.to_string());
+        }
+
+        async fn set_stream_pinned(&self, user_login: &str, pinned: bool) -> anyhow::Result<()> {
+            let mut config = self.get_config();
+            if pinned {
+                pin_stream(
+                    &mut config,
+                    PinnedStream {
+                        user_login: user_login.to_string(),
+                        display_name: user_login.to_string(),
+                    },
+                );
+            } else {
+                unpin_stream(&mut config, user_login);
+            }
+            *self.config.lock().unwrap() = config;
+            Ok(())
+        }
+
+        async fn snooze_notifications(
+            &self,
+            _user_login: Option<String>,
+            _duration: SnoozeDuration,
+        ) {
+        }
+
+        async fn resume_notifications(&self, _user_login: Option<String>) {}
+
+        async fn set_importance_bulk(
+            &self,
+            logins: Vec<String>,
+            importance: StreamerImportance,
+        ) -> anyhow::Result<usize> {
+            let mut current = self.config.lock().unwrap();
+            let mut settings = current.streamer_settings.clone();
+            let logins = normalize_logins(&logins);
+            let changed = set_importance(&mut settings, &logins, importance, str::to_string);
+            self.settings_undo
+                .lock()
+                .unwrap()
+                .record(&current.streamer_settings, &settings);
+            current.streamer_settings = settings;
+            Ok(changed)
+        }
+
+        /// Treats every well-formed login as a channel, without asking Twitch.
+        async fn import_streamer_list(
+            &self,
+            path: PathBuf,
+            format: StreamerListFormat,
+            importance: StreamerImportance,
+        ) -> anyhow::Result<StreamerListImport> {
+            let logins = parse_streamer_list(&std::fs::read_to_string(path)?, format)?;
+            let (valid, unresolved): (Vec<String>, Vec<String>) =
+                logins.into_iter().partition(|l| is_valid_login(l));
+            let changed = self.set_importance_bulk(valid, importance).await?;
+            Ok(StreamerListImport {
+                changed,
+                unresolved,
+            })
+        }
+
+        async fn get_live_at(&self, _at: i64) -> Vec<super::LiveAtEntry> {
+            self.live_at_entries.lock().unwrap().clone()
+        }
+
+        async fn get_history_summary(&self) -> Vec<StreamerStats> {
+            self.history_stats.lock().unwrap().clone()
+        }
+
+        async fn get_streamer_history(&self, user_login: &str) -> Option<StreamerStats> {
+            self.history_stats
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|s| s.broadcaster_login == user_login)
+                .cloned()
+        }
+
+        async fn get_followed_category_suggestions(&self) -> Vec<CategorySuggestion> {
+            let watched = self.watched_categories.lock().unwrap().clone();
+            suggest_categories(watched, &self.get_config())
+        }
+
+        async fn accept_category_suggestion(
+            &self,
+            category: FollowedCategory,
+        ) -> anyhow::Result<()> {
+            let mut config = self.get_config();
+            if accept_suggestion(&mut config, category) {
+                self.save_config(config).await?;
+            }
+            Ok(())
+        }
+
+        async fn dismiss_category_suggestion(&self, category_id: &str) -> anyhow::Result<()> {
+            let mut config = self.get_config();
+            if dismiss_suggestion(&mut config, category_id) {
+                // Nothing to refresh: dismissals only affect suggestions
+                *self.config.lock().unwrap() = config;
+            }
+            Ok(())
+        }
+
+        async fn set_category_notifications(
+            &self,
+            category_id: &str,
+            notifications: CategoryNotifications,
+        ) -> anyhow::Result<()> {
+            let mut config = self.get_config();
+            let Some(category) = config
+                .followed_categories
+                .iter_mut()
+                .find(|c| c.id == category_id)
+            else {
+                anyhow::bail!("Category {category_id} is not followed");
+            };
+            category.notifications = notifications;
+            *self.config.lock().unwrap() = config;
+            Ok(())
+        }
+
+        async fn note_app_version(&self, version: &str) -> anyhow::Result<bool> {
+            let mut config = self.get_config();
+            let seen = mark_version_seen(&mut config, version);
+            if seen != SeenVersion::Same {
+                *self.config.lock().unwrap() = config;
+            }
+            Ok(seen == SeenVersion::Updated)
+        }
+
+        async fn undo_settings_change(&self) -> anyhow::Result<Option<String>> {
+            let Some(change) = self.settings_undo.lock().unwrap().pop() else {
+                return Ok(None);
+            };
+            let user_login = change.user_login.clone();
+            change.restore(&mut self.config.lock().unwrap().streamer_settings);
+            Ok(Some(user_login))
+        }
+
+        async fn get_auth_state(&self) -> AuthStatus {
+            AuthStatus::Unauthenticated
+        }
+
+        async fn refresh_on_menu_open(&self) {}
+
+        async fn refresh_now(&self) {}
+
+        async fn mark_menu_seen(&self) {}
+
+        fn get_autostart(&self) -> bool {
+            self.config.lock().unwrap().start_on_login
+        }
+
+        async fn set_autostart(&self, enabled: bool) -> anyhow::Result<()> {
+            self.config.lock().unwrap().start_on_login = enabled;
+            Ok(())
+        }
     }
 }
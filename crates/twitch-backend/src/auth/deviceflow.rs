@@ -1,7 +1,7 @@
 use super::store::Token;
 use crate::twitch::http::{HttpClient, ReqwestClient};
 use anyhow::{Context, Result};
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
 use serde::Deserialize;
 
@@ -9,8 +9,15 @@ const DEVICE_CODE_URL: &str = "https://id.twitch.tv/oauth2/device";
 const TOKEN_URL: &str = "https://id.twitch.tv/oauth2/token";
 const VALIDATE_URL: &str = "https://id.twitch.tv/oauth2/validate";
 
-/// Required OAuth scopes
-const REQUIRED_SCOPES: &str = "user:read:follows";
+/// Required OAuth scopes. Stored tokens missing any of these (from before
+/// one was added) are still used, but the user is asked to log in again.
+///
+/// Read-only: Helix dropped its follow/unfollow endpoints (and the scope
+/// for them) in 2021, so following a channel can only be done on twitch.tv.
+pub const REQUIRED_SCOPES: &[&str] = &["user:read:follows"];
+
+/// How many device codes are issued before giving up when the user lets each one expire.
+pub const MAX_DEVICE_CODE_ATTEMPTS: u32 = 3;
 
 /// Device flow errors
 #[derive(Debug, thiserror::Error)]
@@ -23,6 +30,8 @@ pub enum DeviceFlowError {
     AccessDenied,
     #[error("Device code expired")]
     ExpiredToken,
+    #[error("Authentication cancelled")]
+    Cancelled,
     #[error("Network error: {0}")]
     Network(String),
     #[error("API error: {0}")]
@@ -68,12 +77,9 @@ pub struct DeviceFlow<H: HttpClient = ReqwestClient> {
 }
 
 impl DeviceFlow<ReqwestClient> {
-    /// Creates a new device flow handler
-    pub fn new(client_id: String) -> Self {
-        Self {
-            client_id,
-            http: ReqwestClient::new(),
-        }
+    /// Creates a new device flow handler sending requests through `http`
+    pub fn new(client_id: String, http: ReqwestClient) -> Self {
+        Self { client_id, http }
     }
 }
 
@@ -88,7 +94,7 @@ impl<H: HttpClient> DeviceFlow<H> {
     pub async fn request_device_code(&self) -> Result<DeviceCodeResponse> {
         let params = vec![
             ("client_id".to_string(), self.client_id.clone()),
-            ("scopes".to_string(), REQUIRED_SCOPES.to_string()),
+            ("scopes".to_string(), REQUIRED_SCOPES.join(" ")),
         ];
 
         let response = self
@@ -157,7 +163,7 @@ impl<H: HttpClient> DeviceFlow<H> {
     async fn wait_for_token(
         &self,
         dcr: &DeviceCodeResponse,
-        cancel: tokio::sync::watch::Receiver<bool>,
+        mut cancel: tokio::sync::watch::Receiver<bool>,
     ) -> Result<TokenResponse, DeviceFlowError> {
         let mut interval = std::time::Duration::from_secs(dcr.interval as u64);
         if interval.is_zero() {
@@ -173,12 +179,18 @@ impl<H: HttpClient> DeviceFlow<H> {
         );
 
         loop {
-            // Check for cancellation
             if *cancel.borrow() {
-                return Err(DeviceFlowError::Api("Authentication cancelled".into()));
+                return Err(DeviceFlowError::Cancelled);
             }
 
-            tokio::time::sleep(interval).await;
+            tokio::select! {
+                () = tokio::time::sleep(interval) => {}
+                // Stop waiting as soon as the login is cancelled
+                Ok(()) = cancel.changed() => {}
+            }
+            if *cancel.borrow() {
+                return Err(DeviceFlowError::Cancelled);
+            }
 
             if Utc::now() > deadline {
                 tracing::warn!("Device code expired");
@@ -272,22 +284,42 @@ impl<H: HttpClient> DeviceFlow<H> {
 
     /// Performs the full device code flow
     ///
-    /// The `on_code` callback is called with the user code and verification URI
-    /// when the device code is obtained, so the caller can display them to the user.
+    /// The `on_code` callback is called with the user code, verification URI and
+    /// expiry time whenever a device code is obtained, so the caller can display
+    /// them to the user. If a code expires before the user authorizes it, a fresh
+    /// one is requested (up to [`MAX_DEVICE_CODE_ATTEMPTS`] codes in total) and
+    /// `on_code` is called again.
     pub async fn authenticate<F>(
         &self,
-        on_code: F,
+        mut on_code: F,
         cancel: tokio::sync::watch::Receiver<bool>,
     ) -> Result<Token>
     where
-        F: FnOnce(&str, &str),
+        F: FnMut(&str, &str, DateTime<Utc>),
     {
-        let dcr = self.request_device_code().await?;
-
-        // Notify the caller of the user code
-        on_code(&dcr.user_code, &dcr.verification_uri);
+        let mut attempt = 1;
+        let tr = loop {
+            let dcr = self.request_device_code().await?;
+
+            // Notify the caller of the user code
+            on_code(
+                &dcr.user_code,
+                &dcr.verification_uri,
+                Utc::now() + Duration::seconds(dcr.expires_in),
+            );
 
-        let tr = self.wait_for_token(&dcr, cancel).await?;
+            match self.wait_for_token(&dcr, cancel.clone()).await {
+                Err(DeviceFlowError::ExpiredToken) if attempt < MAX_DEVICE_CODE_ATTEMPTS => {
+                    attempt += 1;
+                    tracing::info!(
+                        "Requesting a new device code (attempt {}/{})",
+                        attempt,
+                        MAX_DEVICE_CODE_ATTEMPTS
+                    );
+                }
+                result => break result?,
+            }
+        };
 
         // Validate the token to get user info
         let vr = self.validate_token(&tr.access_token).await?;
@@ -318,6 +350,15 @@ mod tests {
         scope: Vec<String>,
     }
 
+    #[derive(Serialize)]
+    struct DeviceCodeBody {
+        device_code: String,
+        user_code: String,
+        verification_uri: String,
+        expires_in: i64,
+        interval: i64,
+    }
+
     #[derive(Serialize)]
     struct ValidateBody {
         login: String,
@@ -421,12 +462,58 @@ mod tests {
 
         let result = flow.wait_for_token(&dcr, cancel).await;
         assert!(
-            matches!(result, Err(DeviceFlowError::Api(ref msg)) if msg.contains("cancelled")),
+            matches!(result, Err(DeviceFlowError::Cancelled)),
             "expected cancellation error, got {:?}",
             result
         );
     }
 
+    #[tokio::test]
+    async fn wait_for_token_stops_as_soon_as_cancelled() {
+        let mock = MockHttpClient::new();
+        let flow = DeviceFlow::with_http_client("client_id".into(), mock);
+
+        let (tx, cancel) = watch::channel(false);
+        let dcr = DeviceCodeResponse {
+            device_code: "code".into(),
+            user_code: "USER-CODE".into(),
+            verification_uri: "https://twitch.tv/activate".into(),
+            expires_in: 600,
+            interval: 60,
+        };
+
+        let wait = tokio::spawn(async move { flow.wait_for_token(&dcr, cancel).await });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        tx.send(true).unwrap();
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), wait)
+            .await
+            .expect("cancel should end the wait before the poll interval")
+            .unwrap();
+        assert!(matches!(result, Err(DeviceFlowError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn authenticate_requests_new_code_when_previous_expires() {
+        let body = DeviceCodeBody {
+            device_code: "dev_code".into(),
+            user_code: "USER-CODE".into(),
+            verification_uri: "https://twitch.tv/activate".into(),
+            expires_in: 0,
+            interval: 1,
+        };
+        let mock = MockHttpClient::new()
+            .on_post_json(DEVICE_CODE_URL, &body)
+            .on_post(TOKEN_URL, 400, r#"{"message":"expired_token"}"#);
+        let flow = DeviceFlow::with_http_client("client_id".into(), mock);
+        let (_tx, cancel) = watch::channel(false);
+
+        let mut codes_shown = 0;
+        let result = flow.authenticate(|_, _, _| codes_shown += 1, cancel).await;
+
+        assert_eq!(codes_shown, MAX_DEVICE_CODE_ATTEMPTS);
+        assert!(result.unwrap_err().to_string().contains("expired"));
+    }
+
     #[tokio::test]
     async fn validate_token_returns_user_info() {
         let mock = MockHttpClient::new().on_get(VALIDATE_URL, 200, {
@@ -486,15 +573,6 @@ mod tests {
 
     #[tokio::test]
     async fn request_device_code_success() {
-        #[derive(Serialize)]
-        struct DeviceCodeBody {
-            device_code: String,
-            user_code: String,
-            verification_uri: String,
-            expires_in: i64,
-            interval: i64,
-        }
-
         let body = DeviceCodeBody {
             device_code: "dev_code_xyz".into(),
             user_code: "HELLO-WORLD".into(),
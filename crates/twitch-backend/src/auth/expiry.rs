@@ -0,0 +1,94 @@
+//! When to refresh the OAuth token ahead of its expiry, and when repeated
+//! failures are worth telling the user about.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Refresh this long before the token expires, leaving time for retries.
+pub const REFRESH_BEFORE_EXPIRY_MIN: i64 = 10;
+
+/// Refreshes that must fail in a row before the menu asks for a new login.
+pub const WARN_AFTER_FAILURES: u32 = 3;
+
+/// Wait after the first failed refresh; doubles with each further failure.
+const RETRY_BASE_SECS: i64 = 30;
+
+/// Longest wait between retries.
+const RETRY_MAX_SECS: i64 = 600;
+
+/// The session token's expiry and how refreshing it ahead of time is going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenExpiry {
+    pub expires_at: DateTime<Utc>,
+    /// Refreshes failed in a row
+    pub failures: u32,
+    last_failure: Option<DateTime<Utc>>,
+}
+
+impl TokenExpiry {
+    pub fn new(expires_at: DateTime<Utc>) -> Self {
+        Self {
+            expires_at,
+            failures: 0,
+            last_failure: None,
+        }
+    }
+
+    /// When to next try refreshing: [`REFRESH_BEFORE_EXPIRY_MIN`] before
+    /// expiry, then backing off after each failure.
+    pub fn next_attempt(&self) -> DateTime<Utc> {
+        match self.last_failure {
+            Some(at) => at + retry_delay(self.failures),
+            None => self.expires_at - Duration::minutes(REFRESH_BEFORE_EXPIRY_MIN),
+        }
+    }
+
+    pub fn record_failure(&mut self, at: DateTime<Utc>) {
+        self.failures += 1;
+        self.last_failure = Some(at);
+    }
+
+    /// Whether refreshing keeps failing, so the user should log in again.
+    pub fn failing(&self) -> bool {
+        self.failures >= WARN_AFTER_FAILURES
+    }
+}
+
+fn retry_delay(failures: u32) -> Duration {
+    let doublings = failures.saturating_sub(1).min(10);
+    Duration::seconds((RETRY_BASE_SECS << doublings).min(RETRY_MAX_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_attempt_is_ahead_of_expiry() {
+        let expires_at = Utc::now() + Duration::hours(4);
+        let expiry = TokenExpiry::new(expires_at);
+        assert_eq!(
+            expiry.next_attempt(),
+            expires_at - Duration::minutes(REFRESH_BEFORE_EXPIRY_MIN)
+        );
+        assert!(!expiry.failing());
+    }
+
+    #[test]
+    fn failures_back_off_and_eventually_warn() {
+        let now = Utc::now();
+        let mut expiry = TokenExpiry::new(now);
+
+        expiry.record_failure(now);
+        assert_eq!(expiry.next_attempt(), now + Duration::seconds(30));
+        expiry.record_failure(now);
+        assert_eq!(expiry.next_attempt(), now + Duration::seconds(60));
+        assert!(!expiry.failing());
+        expiry.record_failure(now);
+        assert!(expiry.failing());
+
+        for _ in 0..20 {
+            expiry.record_failure(now);
+        }
+        assert_eq!(expiry.next_attempt(), now + Duration::minutes(10));
+    }
+}
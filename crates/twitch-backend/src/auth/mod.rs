@@ -1,7 +1,10 @@
 mod deviceflow;
+mod expiry;
+mod sealed;
 pub mod store;
 
-pub use deviceflow::DeviceFlow;
+pub use deviceflow::{DeviceFlow, DeviceFlowError, MAX_DEVICE_CODE_ATTEMPTS, REQUIRED_SCOPES};
+pub use expiry::{TokenExpiry, REFRESH_BEFORE_EXPIRY_MIN, WARN_AFTER_FAILURES};
 pub use store::{Token, TokenStore};
 
 /// Twitch application client ID
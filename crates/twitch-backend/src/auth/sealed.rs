@@ -0,0 +1,171 @@
+//! Encryption of `token.json` with a key derived from this machine and user
+//! account, for when the token can't go in the OS keyring.
+//!
+//! This keeps the token out of backups, synced config dirs and diagnostics
+//! copied elsewhere. It doesn't stop other programs running as the same user
+//! on the same machine, which can derive the same key.
+
+use anyhow::{Context, Result};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::hkdf::{Salt, HKDF_SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+/// Format version written to the file, bumped if the key derivation changes.
+const SEALED_VERSION: u32 = 1;
+
+const KEY_SALT: &[u8] = b"twitch-tray token file";
+const KEY_INFO: &[u8] = b"twitch-tray token file v1";
+
+/// The token file's contents once encrypted.
+#[derive(Debug, Serialize, Deserialize)]
+struct SealedFile {
+    version: u32,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Encrypts `plaintext` into the token file format.
+pub(crate) fn seal(plaintext: &str) -> Result<String> {
+    seal_with(&machine_key(), plaintext)
+}
+
+/// Decrypts a token file. Files from before encryption hold the token JSON
+/// itself; those are returned unchanged with `true`, so they get rewritten.
+pub(crate) fn unseal(contents: &str) -> Result<(String, bool)> {
+    unseal_with(&machine_key(), contents)
+}
+
+fn seal_with(key: &LessSafeKey, plaintext: &str) -> Result<String> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| anyhow::anyhow!("Failed to generate a nonce"))?;
+    let mut data = plaintext.as_bytes().to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt token"))?;
+    serde_json::to_string(&SealedFile {
+        version: SEALED_VERSION,
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(data),
+    })
+    .context("Failed to serialize token file")
+}
+
+fn unseal_with(key: &LessSafeKey, contents: &str) -> Result<(String, bool)> {
+    let Ok(sealed) = serde_json::from_str::<SealedFile>(contents) else {
+        return Ok((contents.to_string(), true));
+    };
+    anyhow::ensure!(
+        sealed.version == SEALED_VERSION,
+        "Token file version {} isn't supported",
+        sealed.version
+    );
+    let nonce: [u8; NONCE_LEN] = hex::decode(&sealed.nonce)
+        .ok()
+        .and_then(|n| n.try_into().ok())
+        .context("Token file has an invalid nonce")?;
+    let mut data = hex::decode(&sealed.ciphertext).context("Token file is corrupt")?;
+    let plaintext = key
+        .open_in_place(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Token file was saved on another machine or user account, or is corrupt"
+            )
+        })?;
+    let plaintext = String::from_utf8(plaintext.to_vec()).context("Token file is corrupt")?;
+    Ok((plaintext, false))
+}
+
+fn machine_key() -> LessSafeKey {
+    derive_key(&key_material())
+}
+
+fn derive_key(material: &[u8]) -> LessSafeKey {
+    let prk = Salt::new(HKDF_SHA256, KEY_SALT).extract(material);
+    let okm = prk
+        .expand(&[KEY_INFO], &CHACHA20_POLY1305)
+        .expect("HKDF output length matches the AEAD key length");
+    LessSafeKey::new(UnboundKey::from(okm))
+}
+
+/// The machine ID plus the user's name and home dir.
+fn key_material() -> Vec<u8> {
+    let machine = machine_id().unwrap_or_else(|| {
+        tracing::debug!("No machine ID found; token file key uses the user account only");
+        String::new()
+    });
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    let home = dirs::home_dir().unwrap_or_default();
+    format!("{machine}\n{user}\n{}", home.display()).into_bytes()
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .find(|id| !id.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn machine_id() -> Option<String> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.lines().find(|l| l.contains("IOPlatformUUID"))?;
+    let uuid = line.split('=').nth(1)?.trim().trim_matches('"');
+    (!uuid.is_empty()).then(|| uuid.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn machine_id() -> Option<String> {
+    let output = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKLM\SOFTWARE\Microsoft\Cryptography",
+            "/v",
+            "MachineGuid",
+        ])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.lines().find(|l| l.contains("MachineGuid"))?;
+    line.split_whitespace().last().map(str::to_string)
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+fn machine_id() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_token_round_trips_and_hides_the_plaintext() {
+        let plaintext = r#"{"access_token":"secret"}"#;
+        let sealed = seal(plaintext).unwrap();
+
+        assert!(!sealed.contains("secret"));
+        assert_eq!(unseal(&sealed).unwrap(), (plaintext.to_string(), false));
+    }
+
+    #[test]
+    fn plaintext_file_is_passed_through_for_rewriting() {
+        let plaintext = r#"{"access_token":"secret","refresh_token":"r"}"#;
+        assert_eq!(unseal(plaintext).unwrap(), (plaintext.to_string(), true));
+    }
+
+    #[test]
+    fn file_from_another_machine_does_not_decrypt() {
+        let sealed = seal_with(&derive_key(b"other machine"), "{}").unwrap();
+        assert!(unseal_with(&derive_key(b"this machine"), &sealed).is_err());
+    }
+}
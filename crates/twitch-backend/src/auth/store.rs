@@ -2,7 +2,12 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
 
+use super::sealed;
+use crate::config::TokenStorage;
+
+#[cfg(feature = "keyring")]
 const SERVICE_NAME: &str = "twitch-tray";
 const TOKEN_FILE: &str = "token.json";
 
@@ -20,13 +25,27 @@ pub struct Token {
 impl Token {
     /// Checks if the token has expired
     pub fn is_expired(&self) -> bool {
-        Utc::now() > self.expires_at
+        self.is_expired_at(Utc::now())
+    }
+
+    /// Checks if the token has expired as of `now`
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        now > self.expires_at
     }
 
     /// Checks if the token exists and is not expired
     pub fn is_valid(&self) -> bool {
         !self.access_token.is_empty() && !self.is_expired()
     }
+
+    /// The `REQUIRED_SCOPES` this token wasn't granted
+    pub fn missing_scopes(&self) -> Vec<String> {
+        super::REQUIRED_SCOPES
+            .iter()
+            .filter(|scope| !self.scopes.iter().any(|s| s == *scope))
+            .map(|scope| (*scope).to_string())
+            .collect()
+    }
 }
 
 /// Token store errors
@@ -38,39 +57,211 @@ pub enum StoreError {
     Storage(#[from] anyhow::Error),
 }
 
-/// Secure token storage using file system with optional keyring backup
+/// Token storage in the OS keyring or an encrypted file in the config dir
+/// (see `sealed`), following `Config::token_storage`
 ///
-/// Uses a file-based storage via the keyring crate.
-/// Falls back to plain JSON file if keyring is unavailable.
+/// The keyring is only used when its store keeps credentials on disk; the
+/// keyring crate's in-memory fallback would lose the token on restart.
+/// Whichever place isn't in use is emptied when the token is next loaded or
+/// saved, so switching modes moves an existing token across.
 pub struct FileTokenStore {
+    #[cfg(feature = "keyring")]
     keyring_entry: Option<keyring::Entry>,
     fallback_path: PathBuf,
+    storage: Arc<RwLock<TokenStorage>>,
 }
 
 impl FileTokenStore {
     /// Creates a new token store
-    pub fn new() -> Result<Self> {
+    pub fn new(storage: TokenStorage) -> Result<Self> {
         let config_dir = crate::config::ConfigManager::config_dir()?;
         std::fs::create_dir_all(&config_dir)?;
-        let fallback_path = config_dir.join(TOKEN_FILE);
-
-        // Try to create a keyring entry
-        let keyring_entry = keyring::Entry::new(SERVICE_NAME, "oauth_token").ok();
+        Ok(Self::with_storage(
+            config_dir.join(TOKEN_FILE),
+            Arc::new(RwLock::new(storage)),
+        ))
+    }
 
-        Ok(Self {
-            keyring_entry,
+    fn with_storage(fallback_path: PathBuf, storage: Arc<RwLock<TokenStorage>>) -> Self {
+        Self {
+            #[cfg(feature = "keyring")]
+            keyring_entry: persistent_keyring()
+                .then(|| keyring::Entry::new(SERVICE_NAME, "oauth_token").ok())
+                .flatten(),
             fallback_path,
-        })
+            storage,
+        }
     }
 
     /// Creates a token store with a custom path (for testing)
     #[cfg(test)]
     pub fn with_path(path: PathBuf) -> Self {
         Self {
+            #[cfg(feature = "keyring")]
             keyring_entry: None,
             fallback_path: path,
+            storage: Arc::new(RwLock::new(TokenStorage::File)),
+        }
+    }
+
+    /// Creates a token store backed by an in-memory keyring entry (for testing)
+    #[cfg(all(test, feature = "keyring"))]
+    fn with_mock_keyring(path: PathBuf, storage: TokenStorage) -> Self {
+        let entry = keyring::mock::default_credential_builder()
+            .build(None, SERVICE_NAME, "oauth_token")
+            .unwrap();
+        Self {
+            keyring_entry: Some(keyring::Entry::new_with_credential(entry)),
+            fallback_path: path,
+            storage: Arc::new(RwLock::new(storage)),
+        }
+    }
+
+    fn storage(&self) -> TokenStorage {
+        *self.storage.read().unwrap()
+    }
+
+    /// Whether the token belongs in the keyring rather than the file
+    fn uses_keyring(&self) -> bool {
+        match self.storage() {
+            TokenStorage::Auto => self.keyring_available(),
+            TokenStorage::File => false,
+            TokenStorage::Keyring => true,
+        }
+    }
+
+    #[cfg(feature = "keyring")]
+    fn keyring_available(&self) -> bool {
+        self.keyring_entry.is_some()
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    #[allow(clippy::unused_self)]
+    fn keyring_available(&self) -> bool {
+        false
+    }
+
+    #[cfg(feature = "keyring")]
+    fn read_keyring(&self) -> Result<Option<String>> {
+        let Some(entry) = &self.keyring_entry else {
+            return Ok(None);
+        };
+        match entry.get_password() {
+            Ok(data) => Ok(Some(data)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e).context("Failed to read token from keyring"),
+        }
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    #[allow(clippy::unused_self, clippy::unnecessary_wraps)]
+    fn read_keyring(&self) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    #[cfg(feature = "keyring")]
+    fn write_keyring(&self, data: &str) -> Result<()> {
+        self.keyring_entry
+            .as_ref()
+            .context("No OS keyring is available to store the token")?
+            .set_password(data)
+            .context("Failed to save token to keyring")
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    #[allow(clippy::unused_self)]
+    fn write_keyring(&self, _data: &str) -> Result<()> {
+        anyhow::bail!("This build has no OS keyring support")
+    }
+
+    #[cfg(feature = "keyring")]
+    fn delete_keyring(&self) {
+        if let Some(entry) = &self.keyring_entry {
+            let _ = entry.delete_credential();
+        }
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    #[allow(clippy::unused_self)]
+    fn delete_keyring(&self) {}
+
+    /// Reads and decrypts the token file, and whether it's still in the
+    /// unencrypted format and needs rewriting
+    fn read_file(&self) -> Result<Option<(String, bool)>> {
+        if !self.fallback_path.exists() {
+            return Ok(None);
+        }
+        let contents =
+            std::fs::read_to_string(&self.fallback_path).context("Failed to read token file")?;
+        sealed::unseal(&contents).map(Some)
+    }
+
+    fn write_file(&self, data: &str) -> Result<()> {
+        std::fs::write(&self.fallback_path, sealed::seal(data)?)
+            .context("Failed to write token file")
+    }
+
+    fn delete_file(&self) -> Result<()> {
+        if self.fallback_path.exists() {
+            std::fs::remove_file(&self.fallback_path).context("Failed to delete token file")?;
+        }
+        Ok(())
+    }
+
+    /// Writes the token to the place the current mode uses and removes any
+    /// copy from the other.
+    fn write(&self, data: &str) -> Result<()> {
+        if self.uses_keyring() {
+            self.write_keyring(data)?;
+            self.delete_file()
+        } else {
+            self.write_file(data)?;
+            self.delete_keyring();
+            Ok(())
         }
     }
+
+    /// Reads the stored token and whether it needs writing back, because
+    /// it's in the place the current mode doesn't use or in the old
+    /// unencrypted file.
+    fn read(&self) -> Result<Option<(String, bool)>> {
+        if self.uses_keyring() {
+            if let Some(data) = self.read_keyring()? {
+                return Ok(Some((data, false)));
+            }
+            return Ok(self.read_file()?.map(|(data, _)| (data, true)));
+        }
+        if let Some(file) = self.read_file()? {
+            return Ok(Some(file));
+        }
+        Ok(self.read_keyring()?.map(|data| (data, true)))
+    }
+
+    /// Changes the storage mode and moves an already stored token to match.
+    /// The mode is left unchanged if the token can't be moved.
+    pub fn set_storage(&self, storage: TokenStorage) -> Result<()> {
+        let previous = std::mem::replace(&mut *self.storage.write().unwrap(), storage);
+        if previous == storage {
+            return Ok(());
+        }
+        let moved = match self.read() {
+            Ok(Some((data, true))) => self.write(&data),
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if moved.is_err() {
+            *self.storage.write().unwrap() = previous;
+        }
+        moved
+    }
+}
+
+#[cfg(feature = "keyring")]
+fn persistent_keyring() -> bool {
+    matches!(
+        keyring::default::default_credential_builder().persistence(),
+        keyring::credential::CredentialPersistence::UntilDelete
+    )
 }
 
 /// Test-only async methods for FileTokenStore
@@ -79,20 +270,15 @@ impl FileTokenStore {
     /// Saves the OAuth token
     pub async fn save(&self, token: &Token) -> Result<()> {
         let data = serde_json::to_string(token).context("Failed to serialize token")?;
-        std::fs::write(&self.fallback_path, &data).context("Failed to write token file")?;
-        Ok(())
+        self.write_file(&data)
     }
 
     /// Loads the stored OAuth token
     pub async fn load(&self) -> Result<Token, StoreError> {
-        if self.fallback_path.exists() {
-            let data = std::fs::read_to_string(&self.fallback_path)
-                .map_err(|e| StoreError::Storage(e.into()))?;
-            let token: Token =
-                serde_json::from_str(&data).map_err(|e| StoreError::Storage(e.into()))?;
-            return Ok(token);
-        }
-        Err(StoreError::NoToken)
+        let Some((data, _)) = self.read_file()? else {
+            return Err(StoreError::NoToken);
+        };
+        serde_json::from_str(&data).map_err(|e| StoreError::Storage(e.into()))
     }
 
     /// Deletes the stored token
@@ -119,64 +305,59 @@ pub struct TokenStore {
 
 impl TokenStore {
     /// Creates a new token store
-    pub fn new() -> Result<Self> {
+    pub fn new(storage: TokenStorage) -> Result<Self> {
         Ok(Self {
-            inner: FileTokenStore::new()?,
+            inner: FileTokenStore::new(storage)?,
         })
     }
 
     /// Saves the OAuth token
     pub fn save_token(&self, token: &Token) -> Result<()> {
         let data = serde_json::to_string(token).context("Failed to serialize token")?;
-
-        // Always save to file storage for reliability
-        std::fs::write(&self.inner.fallback_path, &data).context("Failed to write token file")?;
-
-        // Also try keyring as a secondary store
-        if let Some(ref entry) = self.inner.keyring_entry {
-            let _ = entry.set_password(&data);
-        }
-
-        Ok(())
+        self.inner.write(&data)
     }
 
     /// Loads the stored OAuth token
     pub fn load_token(&self) -> Result<Token, StoreError> {
-        // Try file storage first (more reliable)
-        if self.inner.fallback_path.exists() {
-            let data = std::fs::read_to_string(&self.inner.fallback_path)
-                .map_err(|e| StoreError::Storage(e.into()))?;
-            let token: Token =
-                serde_json::from_str(&data).map_err(|e| StoreError::Storage(e.into()))?;
-            return Ok(token);
-        }
-
-        // Fall back to keyring (for migration from old storage)
-        if let Some(ref entry) = self.inner.keyring_entry {
-            if let Ok(data) = entry.get_password() {
-                let token: Token =
-                    serde_json::from_str(&data).map_err(|e| StoreError::Storage(e.into()))?;
-                return Ok(token);
+        let Some((data, moved)) = self.inner.read()? else {
+            return Err(StoreError::NoToken);
+        };
+        let token: Token =
+            serde_json::from_str(&data).map_err(|e| StoreError::Storage(e.into()))?;
+        if moved {
+            if let Err(e) = self.inner.write(&data) {
+                tracing::warn!(
+                    "Failed to rewrite token in {:?} storage: {:#}",
+                    self.inner.storage(),
+                    e
+                );
             }
         }
-
-        Err(StoreError::NoToken)
+        Ok(token)
     }
 
     /// Deletes the stored token
     pub fn delete_token(&self) -> Result<()> {
-        // Delete file storage
-        if self.inner.fallback_path.exists() {
-            std::fs::remove_file(&self.inner.fallback_path)
-                .context("Failed to delete token file")?;
-        }
+        self.inner.delete_file()?;
+        self.inner.delete_keyring();
+        Ok(())
+    }
 
-        // Also try to delete from keyring
-        if let Some(ref entry) = self.inner.keyring_entry {
-            let _ = entry.delete_credential();
-        }
+    /// Changes where the token is kept, moving an already stored one
+    pub fn set_storage(&self, storage: TokenStorage) -> Result<()> {
+        self.inner.set_storage(storage)
+    }
+}
 
-        Ok(())
+/// Shares the storage mode, so changing it through one clone applies to all
+impl Clone for TokenStore {
+    fn clone(&self) -> Self {
+        Self {
+            inner: FileTokenStore::with_storage(
+                self.inner.fallback_path.clone(),
+                self.inner.storage.clone(),
+            ),
+        }
     }
 }
 
@@ -198,6 +379,15 @@ mod tests {
 
     // === Token tests ===
 
+    #[test]
+    fn token_reports_scopes_it_lacks() {
+        let mut token = make_token(1);
+        assert!(token.missing_scopes().is_empty());
+
+        token.scopes.clear();
+        assert_eq!(token.missing_scopes(), vec!["user:read:follows"]);
+    }
+
     #[test]
     fn token_is_expired_when_past_expiry() {
         let token = Token {
@@ -302,6 +492,105 @@ mod tests {
         assert!(store.has_token().await);
     }
 
+    #[test]
+    fn token_file_is_encrypted_and_old_plaintext_files_are_rewritten() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let token_path = temp_dir.path().join("token.json");
+        let plaintext = serde_json::to_string(&make_token(1)).unwrap();
+        std::fs::write(&token_path, &plaintext).unwrap();
+        let store = TokenStore {
+            inner: FileTokenStore::with_path(token_path.clone()),
+        };
+
+        assert_eq!(store.load_token().unwrap().access_token, "access_token_123");
+
+        let contents = std::fs::read_to_string(&token_path).unwrap();
+        assert!(!contents.contains("access_token_123"));
+        assert_eq!(store.load_token().unwrap().access_token, "access_token_123");
+    }
+
+    // === Storage mode tests ===
+
+    #[test]
+    fn keyring_mode_without_keyring_keeps_the_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let token_path = temp_dir.path().join("token.json");
+        let store = TokenStore {
+            inner: FileTokenStore::with_path(token_path.clone()),
+        };
+        store.save_token(&make_token(1)).unwrap();
+
+        assert!(store.set_storage(TokenStorage::Keyring).is_err());
+
+        assert!(token_path.exists());
+        assert_eq!(store.inner.storage(), TokenStorage::File);
+        assert!(store.load_token().is_ok());
+    }
+
+    #[cfg(feature = "keyring")]
+    #[test]
+    fn keyring_mode_moves_file_token_into_keyring() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let token_path = temp_dir.path().join("token.json");
+        let store = TokenStore {
+            inner: FileTokenStore::with_mock_keyring(token_path.clone(), TokenStorage::File),
+        };
+        store.save_token(&make_token(1)).unwrap();
+        assert!(token_path.exists());
+
+        store.set_storage(TokenStorage::Keyring).unwrap();
+
+        assert!(!token_path.exists());
+        assert_eq!(store.load_token().unwrap().user_id, "user123");
+    }
+
+    #[cfg(feature = "keyring")]
+    #[test]
+    fn keyring_feature_uses_a_persistent_os_store() {
+        // keyring falls back to its in-memory mock when no native store is enabled
+        assert!(matches!(
+            keyring::default::default_credential_builder().persistence(),
+            keyring::credential::CredentialPersistence::UntilDelete
+        ));
+        assert!(persistent_keyring());
+    }
+
+    #[cfg(feature = "keyring")]
+    #[test]
+    fn loading_moves_token_to_the_configured_place() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let token_path = temp_dir.path().join("token.json");
+        let data = serde_json::to_string(&make_token(1)).unwrap();
+        std::fs::write(&token_path, &data).unwrap();
+        let store = TokenStore {
+            inner: FileTokenStore::with_mock_keyring(token_path.clone(), TokenStorage::Auto),
+        };
+
+        assert!(store.load_token().is_ok());
+        assert!(!token_path.exists());
+        assert_eq!(store.inner.read_keyring().unwrap(), Some(data));
+
+        store.set_storage(TokenStorage::File).unwrap();
+        assert!(token_path.exists());
+        assert_eq!(store.inner.read_keyring().unwrap(), None);
+    }
+
+    #[cfg(feature = "keyring")]
+    #[test]
+    fn keyring_mode_never_writes_the_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let token_path = temp_dir.path().join("token.json");
+        let store = TokenStore {
+            inner: FileTokenStore::with_mock_keyring(token_path.clone(), TokenStorage::Keyring),
+        };
+
+        store.save_token(&make_token(1)).unwrap();
+        assert!(!token_path.exists());
+
+        store.delete_token().unwrap();
+        assert!(matches!(store.load_token(), Err(StoreError::NoToken)));
+    }
+
     // === Token serialization tests ===
 
     #[test]
@@ -0,0 +1,236 @@
+//! Starting the app when the user logs in.
+//!
+//! Each platform keeps its own entry pointing at the running executable (or
+//! the AppImage it runs from):
+//! - Linux: an XDG autostart `.desktop` file in `~/.config/autostart`
+//! - macOS: a LaunchAgent, listed under Login Items in System Settings
+//! - Windows: a value under the `HKCU\...\CurrentVersion\Run` registry key
+//!
+//! `Config::start_on_login` records the choice; the backend re-registers at
+//! startup so the entry follows the executable if the app moved.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// Name of the autostart entry (file stem, LaunchAgent label, registry value).
+#[cfg(target_os = "linux")]
+const ENTRY_NAME: &str = "twitch-tray";
+#[cfg(target_os = "macos")]
+const ENTRY_NAME: &str = "com.twitch-tray.app";
+#[cfg(target_os = "windows")]
+const ENTRY_NAME: &str = "Twitch Tray";
+
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+/// Whether the app is registered to start on login.
+pub fn is_enabled() -> bool {
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    {
+        entry_path().is_some_and(|path| path.exists())
+    }
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("reg")
+            .args(["query", RUN_KEY, "/v", ENTRY_NAME])
+            .output()
+            .is_ok_and(|output| output.status.success())
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        false
+    }
+}
+
+/// Registers or unregisters the running executable to start on login.
+pub fn set_enabled(enabled: bool) -> Result<()> {
+    if enabled {
+        register(&launch_path()?)
+    } else {
+        unregister()
+    }
+}
+
+/// What the entry should run: the AppImage itself when running from one
+/// (`current_exe` is inside its temporary mount, gone after the next login),
+/// otherwise the running executable.
+fn launch_path() -> Result<PathBuf> {
+    if let Some(appimage) = std::env::var_os("APPIMAGE").filter(|p| !p.is_empty()) {
+        return Ok(PathBuf::from(appimage));
+    }
+    std::env::current_exe().context("Could not find the app's executable")
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn entry_path() -> Option<PathBuf> {
+    #[cfg(target_os = "linux")]
+    let path = dirs::config_dir()?
+        .join("autostart")
+        .join(format!("{ENTRY_NAME}.desktop"));
+    #[cfg(target_os = "macos")]
+    let path = dirs::home_dir()?
+        .join("Library/LaunchAgents")
+        .join(format!("{ENTRY_NAME}.plist"));
+    Some(path)
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn register(exe: &Path) -> Result<()> {
+    let path = entry_path().context("Could not determine the autostart directory")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    #[cfg(target_os = "linux")]
+    let contents = desktop_entry(exe);
+    #[cfg(target_os = "macos")]
+    let contents = launch_agent_plist(exe);
+    std::fs::write(&path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn unregister() -> Result<()> {
+    let Some(path) = entry_path() else {
+        return Ok(());
+    };
+    match std::fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn register(exe: &Path) -> Result<()> {
+    let command = format!("\"{}\"", exe.display());
+    run_reg(&[
+        "add", RUN_KEY, "/v", ENTRY_NAME, "/t", "REG_SZ", "/d", &command, "/f",
+    ])
+}
+
+#[cfg(target_os = "windows")]
+fn unregister() -> Result<()> {
+    if !is_enabled() {
+        return Ok(());
+    }
+    run_reg(&["delete", RUN_KEY, "/v", ENTRY_NAME, "/f"])
+}
+
+#[cfg(target_os = "windows")]
+fn run_reg(args: &[&str]) -> Result<()> {
+    let output = std::process::Command::new("reg")
+        .args(args)
+        .output()
+        .context("Failed to run reg")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "reg {} failed: {}",
+        args[0],
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn register(_exe: &Path) -> Result<()> {
+    anyhow::bail!("Starting on login isn't supported on this platform")
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn unregister() -> Result<()> {
+    Ok(())
+}
+
+/// XDG autostart entry that runs `exe`.
+#[cfg(target_os = "linux")]
+fn desktop_entry(exe: &Path) -> String {
+    // Exec quoting: the path in double quotes, with `"`, `` ` ``, `$` and `\`
+    // escaped. The string escape then doubles every backslash (so a literal
+    // one becomes four), and `%` is doubled so it isn't a field code.
+    let mut quoted = String::new();
+    for c in exe.display().to_string().chars() {
+        match c {
+            '"' | '`' | '$' => quoted.push_str("\\\\"),
+            '\\' => quoted.push_str("\\\\\\"),
+            '%' => quoted.push('%'),
+            _ => {}
+        }
+        quoted.push(c);
+    }
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Twitch Tray\n\
+         Comment=Twitch live and scheduled stream notifications\n\
+         Exec=\"{quoted}\"\n\
+         Icon=twitch-tray\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n"
+    )
+}
+
+/// LaunchAgent that runs `exe` once at login.
+#[cfg(target_os = "macos")]
+fn launch_agent_plist(exe: &Path) -> String {
+    let program = exe
+        .display()
+        .to_string()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{ENTRY_NAME}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{program}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn desktop_entry_quotes_exec_path() {
+        let entry = desktop_entry(Path::new("/opt/Twitch Tray/twitch-tray"));
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("\nExec=\"/opt/Twitch Tray/twitch-tray\"\n"));
+        assert!(entry.contains("\nType=Application\n"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn desktop_entry_escapes_special_characters() {
+        let entry = desktop_entry(Path::new("/home/a$b/tray\"x"));
+        assert!(entry.contains("Exec=\"/home/a\\\\$b/tray\\\\\"x\"\n"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn desktop_entry_escapes_percent_and_backslash() {
+        let entry = desktop_entry(Path::new("/opt/My Apps/100%/tray\\x"));
+        assert!(entry.contains(r#"Exec="/opt/My Apps/100%%/tray\\\\x""#));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn launch_agent_runs_exe_at_load() {
+        let plist = launch_agent_plist(Path::new("/Applications/A&B.app/Contents/MacOS/tray"));
+        assert!(plist.contains("<string>/Applications/A&amp;B.app/Contents/MacOS/tray</string>"));
+        assert!(plist.contains("<key>RunAtLoad</key>\n    <true/>"));
+    }
+}
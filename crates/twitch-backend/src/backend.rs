@@ -1,25 +1,49 @@
-use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use anyhow::Context;
+use chrono::{DateTime, Local, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc, watch, Mutex};
 use tokio::time::{Duration, Instant};
 
-use crate::app_services::AppServices;
-use crate::auth::{TokenStore, CLIENT_ID};
-use crate::config::ConfigManager;
-use crate::db::Database;
+use crate::app_services::{AppServices, CategoryStreamsPage};
+use crate::auth::{DeviceFlowError, TokenStore, CLIENT_ID, MAX_DEVICE_CODE_ATTEMPTS};
+use crate::bulk_importance::{normalize_logins, set_importance};
+use crate::category_filters;
+use crate::category_suggestions::{
+    accept_suggestion, dismiss_suggestion, suggest_categories, CategorySuggestion,
+    SUGGESTION_LOOKBACK_DAYS,
+};
+use crate::changelog::{mark_version_seen, SeenVersion};
+use crate::config::{ConfigManager, FollowedCategory, PinnedStream, StreamerImportance};
+use crate::connectivity;
+use crate::db::{CachedUsers, Database};
 use crate::events::BackendEvent;
-use crate::handle::{AuthCommand, BackendHandle, LoginProgress, RawDisplayData};
+use crate::filtering;
+use crate::handle::{AuthCommand, AuthStatus, BackendHandle, LoginProgress, RawDisplayData};
 use crate::hotness_detection::{
     compute_hotness, compute_hotness_profile, find_nearest_bucket, BucketStats, HotnessConfig,
     HotnessInfo, ViewerObservation,
 };
 use crate::notification_dispatcher::NotificationDispatcher;
+use crate::notification_filter::{category_alerts, small_streams};
+use crate::notification_snooze::SnoozeDuration;
 use crate::notify::{DesktopNotifier, Notifier, SnoozeRequest, StreamerSettingsRequest};
+use crate::pinned_streams::{pin_stream, unpin_stream};
+use crate::power::{self, SleepTracker, Suspension};
+use crate::schedule_reminder::{ScheduleReminders, REMINDER_CHECK_SECS};
 use crate::schedule_walker::ScheduleWalker;
-use crate::session::SessionManager;
-use crate::state::AppState;
-use crate::twitch::TwitchClient;
+use crate::session::{login_cancelled, SessionManager};
+use crate::settings_undo::UndoBuffer;
+use crate::state::{AppState, CategoryStreamsDiff};
+use crate::streamer_list::{
+    is_valid_login, parse_streamer_list, unresolved_logins, StreamerListFormat, StreamerListImport,
+};
+use crate::thumbnail_cache::ThumbnailCache;
+use crate::title_alerts::TitleAlerts;
+use crate::twitch::http::ReqwestClient;
+use crate::twitch::{Stream, TwitchClient, BOX_ART_HEIGHT, BOX_ART_WIDTH};
+use crate::watch_streak::{streak_at_risk, STREAK_LOOKBACK_DAYS, STREAK_REMINDER_DELAY_MIN};
 use tokio::task::JoinHandle;
 
 /// Age points (in minutes) at which to precompute hotness bucket stats.
@@ -28,6 +52,21 @@ const HOTNESS_AGE_POINTS: &[i64] = &[0, 5, 10, 15, 30, 45, 60, 90, 120, 180, 240
 /// Retention period for viewer observations (30 days in seconds).
 const OBSERVATION_RETENTION_SECS: i64 = 30 * 24 * 3600;
 
+/// Broadcasts whose end was never seen count as live for this long in history lookups.
+const HISTORY_MAX_OPEN_STREAM_HOURS: i64 = 12;
+
+/// Opening the menu refreshes live streams at most this often.
+const MENU_OPEN_REFRESH_MIN_SECS: u64 = 10;
+
+/// Past broadcasts fetched for each Favourite.
+const RECENT_VIDEOS_PER_STREAMER: usize = 5;
+
+/// Saved users (profile images, display names) are fetched again after this many days.
+const USER_REFRESH_AFTER_DAYS: i64 = 7;
+
+/// Database file name in the config dir.
+const DB_FILE: &str = "data.db";
+
 /// Cached hotness profile for a single broadcaster.
 struct CachedHotnessProfile {
     profile: Vec<(i64, BucketStats)>,
@@ -57,34 +96,64 @@ pub(crate) struct Backend {
     settings_tx: mpsc::UnboundedSender<StreamerSettingsRequest>,
     settings_rx: Arc<Mutex<Option<mpsc::UnboundedReceiver<StreamerSettingsRequest>>>>,
 
-    /// In-memory cache for profile image URLs (user_id -> (url, fetched_at)).
-    profile_image_cache: Arc<std::sync::Mutex<HashMap<String, (String, Instant)>>>,
+    /// Twitch users (profile images, display names) by ID, with when they
+    /// were fetched. Loaded from the `users` table and saved back to it.
+    users: Arc<std::sync::Mutex<CachedUsers>>,
 
     /// In-memory cache for box art URLs (game_id -> (url, fetched_at)).
     box_art_cache: Arc<std::sync::Mutex<HashMap<String, (String, Instant)>>>,
 
+    /// Live stream thumbnails on disk, for notifications and the dashboard.
+    thumbnails: Arc<ThumbnailCache>,
+
     /// In-memory cache for hotness profiles (broadcaster user_id -> profile).
     /// Populated when a stream goes live, evicted when it goes offline.
     hotness_cache: Arc<std::sync::Mutex<HashMap<String, CachedHotnessProfile>>>,
+
+    /// Stream IDs that already got a watch streak reminder (one per stream).
+    streak_reminded: Arc<std::sync::Mutex<HashSet<String>>>,
+
+    /// Stream IDs that already got a title alert (one per stream).
+    title_alerted: Arc<std::sync::Mutex<HashSet<String>>>,
+
+    /// Recent streamer settings changes, for "Undo" in the menu.
+    settings_undo: Arc<std::sync::Mutex<UndoBuffer>>,
+
+    /// Set on resume from suspend so the next stream poll tick refreshes
+    /// (or probes, while offline) without waiting.
+    resumed: Arc<AtomicBool>,
 }
 
 impl Backend {
     fn new() -> anyhow::Result<Self> {
-        use std::sync::atomic::AtomicBool;
         use tokio::sync::RwLock;
 
         let config = Arc::new(ConfigManager::new()?);
         let state = AppState::new();
         let (snooze_tx, snooze_rx) = mpsc::unbounded_channel();
         let (settings_tx, settings_rx) = mpsc::unbounded_channel();
-        let notifier: Arc<dyn Notifier> =
-            Arc::new(DesktopNotifier::new(snooze_tx.clone(), settings_tx.clone()));
-        let client = TwitchClient::new(CLIENT_ID.to_string());
-        let db = Database::new(&ConfigManager::config_dir()?.join("data.db"))?;
+        let http = ReqwestClient::new(config.get().proxy_url.as_deref()).or_else(|e| {
+            tracing::warn!("Ignoring proxy_url: {:#}", e);
+            ReqwestClient::new(None)
+        })?;
+        let thumbnails = Arc::new(ThumbnailCache::new(http.clone())?);
+        match thumbnails.prune() {
+            Ok(0) => {}
+            Ok(n) => tracing::debug!("Removed {} old thumbnails", n),
+            Err(e) => tracing::warn!("Failed to remove old thumbnails: {}", e),
+        }
+        let notifier: Arc<dyn Notifier> = Arc::new(DesktopNotifier::new(
+            snooze_tx.clone(),
+            settings_tx.clone(),
+            config.clone(),
+            thumbnails.clone(),
+        ));
+        let client = TwitchClient::new(CLIENT_ID.to_string(), http);
+        let db = Database::new(&ConfigManager::config_dir()?.join(DB_FILE))?;
         let (auth_cancel_tx, auth_cancel_rx) = watch::channel(false);
 
         let (session, login_progress_rx) = SessionManager::new(
-            TokenStore::new()?,
+            TokenStore::new(config.get().token_storage)?,
             client.clone(),
             state.clone(),
             db.clone(),
@@ -101,11 +170,24 @@ impl Backend {
             session.clone(),
         ));
 
-        let dispatcher = Arc::new(NotificationDispatcher::new(
-            notifier.clone(),
-            config.clone(),
-            session.initial_load_done.clone(),
-        ));
+        let users = db.get_users().unwrap_or_else(|e| {
+            tracing::warn!("Failed to load saved users: {}", e);
+            HashMap::new()
+        });
+        let previously_live = db.get_live_streams().unwrap_or_else(|e| {
+            tracing::warn!("Failed to load live streams from the last run: {}", e);
+            HashSet::new()
+        });
+        let dispatcher = Arc::new(
+            NotificationDispatcher::new(
+                notifier.clone(),
+                config.clone(),
+                session.initial_load_done.clone(),
+            )
+            .with_previously_live(previously_live)
+            .with_state(state.clone())
+            .with_thumbnails(thumbnails.clone()),
+        );
 
         Ok(Self {
             state,
@@ -123,20 +205,17 @@ impl Backend {
             snooze_rx: Arc::new(Mutex::new(Some(snooze_rx))),
             settings_tx,
             settings_rx: Arc::new(Mutex::new(Some(settings_rx))),
-            profile_image_cache: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            users: Arc::new(std::sync::Mutex::new(users)),
             box_art_cache: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            thumbnails,
             hotness_cache: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            streak_reminded: Arc::new(std::sync::Mutex::new(HashSet::new())),
+            title_alerted: Arc::new(std::sync::Mutex::new(HashSet::new())),
+            settings_undo: Arc::new(std::sync::Mutex::new(UndoBuffer::default())),
+            resumed: Arc::new(AtomicBool::new(false)),
         })
     }
 
-    async fn with_retry<F, Fut, T>(&self, f: F) -> Result<T, crate::twitch::ApiError>
-    where
-        F: Fn() -> Fut,
-        Fut: std::future::Future<Output = Result<T, crate::twitch::ApiError>>,
-    {
-        crate::twitch::with_retry(f, || self.session.try_refresh_token()).await
-    }
-
     /// Starts all background tasks, wiring the display watch channel and event broadcast.
     fn start_tasks(
         self: &Arc<Self>,
@@ -146,14 +225,35 @@ impl Backend {
     ) -> Vec<JoinHandle<()>> {
         let mut handles = Vec::new();
 
+        // Re-register so the login entry points at this executable
+        if self.config.get().start_on_login {
+            if let Err(e) = crate::autostart::set_enabled(true) {
+                tracing::warn!("Failed to update start on login: {}", e);
+            }
+        }
+
         // Session restore + initial data fetch
         let backend = self.clone();
         let display_tx_init = display_tx.clone();
         let event_tx_init = event_tx.clone();
         handles.push(tokio::spawn(async move {
+            if backend.config.get().start_logged_out {
+                tracing::info!("Starting logged out (start_logged_out is set)");
+                backend.push_display_state(&display_tx_init).await;
+                return;
+            }
+            // Token validation and the first poll both hit the network; show
+            // what's already in the DB meanwhile.
+            backend.walker.load_cached_schedules().await;
             match backend.session.restore_session().await {
                 Ok(()) => {
                     tracing::info!("Session restored");
+                    if !backend.state.missing_scopes().await.is_empty() {
+                        let _ = backend.notifier.error(
+                            "Twitch Tray needs new Twitch permissions. \
+                             Choose \"Log in again\" from the tray menu to grant them.",
+                        );
+                    }
                     let _ = event_tx_init.send(BackendEvent::AuthStateChanged {
                         is_authenticated: true,
                     });
@@ -167,13 +267,28 @@ impl Backend {
             backend.push_display_state(&display_tx_init).await;
         }));
 
+        // Cancelling a login can't wait behind the login it cancels, so it's
+        // handled as it arrives and the rest are queued for the handler below
+        let backend = self.clone();
+        let (queued_tx, mut queued_rx) = mpsc::unbounded_channel();
+        handles.push(tokio::spawn(async move {
+            let mut rx = auth_cmd_rx;
+            while let Some(cmd) = rx.recv().await {
+                if matches!(cmd, AuthCommand::CancelLogin) {
+                    tracing::info!("Login cancelled by user");
+                    let _ = backend.auth_cancel_tx.send(true);
+                } else {
+                    let _ = queued_tx.send(cmd);
+                }
+            }
+        }));
+
         // Auth command handler (login / logout)
         let backend = self.clone();
         let event_tx_auth = event_tx.clone();
         let display_tx_auth = display_tx.clone();
         handles.push(tokio::spawn(async move {
-            let mut rx = auth_cmd_rx;
-            while let Some(cmd) = rx.recv().await {
+            while let Some(cmd) = queued_rx.recv().await {
                 match cmd {
                     AuthCommand::Login => {
                         backend.handle_login(&event_tx_auth, &display_tx_auth).await;
@@ -183,10 +298,26 @@ impl Backend {
                             .handle_logout(&event_tx_auth, &display_tx_auth)
                             .await;
                     }
+                    AuthCommand::SwitchUser => {
+                        backend
+                            .handle_switch_user(&event_tx_auth, &display_tx_auth)
+                            .await;
+                    }
+                    AuthCommand::CancelLogin => {}
                 }
             }
         }));
 
+        // Login progress task: the login menu shows the device code
+        let backend = self.clone();
+        let display_tx_progress = display_tx.clone();
+        handles.push(tokio::spawn(async move {
+            let mut rx = backend.login_progress_rx.clone();
+            while rx.changed().await.is_ok() {
+                backend.push_display_state(&display_tx_progress).await;
+            }
+        }));
+
         // Stream polling task
         let backend = self.clone();
         handles.push(tokio::spawn(async move {
@@ -197,6 +328,30 @@ impl Backend {
             }
         }));
 
+        // Suspend/resume task: refreshes on wake and marks the time suspended
+        let backend = self.clone();
+        handles.push(tokio::spawn(async move {
+            let mut rx = power::watch();
+            let mut sleep = SleepTracker::default();
+            while let Some(event) = rx.recv().await {
+                if let Some(suspension) = sleep.observe(event, Utc::now()) {
+                    backend.handle_resume(suspension).await;
+                }
+            }
+        }));
+
+        // Token refresh task: renews the OAuth token before it expires
+        let backend = self.clone();
+        let event_tx_token = event_tx.clone();
+        handles.push(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                backend
+                    .tick_token_refresh(Utc::now(), &event_tx_token)
+                    .await;
+            }
+        }));
+
         // Schedule queue walker
         handles.push(self.walker.clone().start());
 
@@ -275,6 +430,16 @@ impl Backend {
             }
         }));
 
+        // Scheduled stream reminder task
+        let backend = self.clone();
+        handles.push(tokio::spawn(async move {
+            let mut reminders = ScheduleReminders::default();
+            loop {
+                tokio::time::sleep(Duration::from_secs(REMINDER_CHECK_SECS)).await;
+                backend.send_schedule_reminders(&mut reminders).await;
+            }
+        }));
+
         // Settings request task — auto-adds streamer to config, then emits BackendEvent
         let backend = self.clone();
         let event_tx_settings = event_tx.clone();
@@ -300,6 +465,11 @@ impl Backend {
                             display_name: request.display_name.clone(),
                             importance: crate::config::StreamerImportance::Normal,
                             hotness_z_threshold_override: None,
+                            notification_open_mode: None,
+                            notification_sound: None,
+                            open_with: None,
+                            schedule_reminder_min: None,
+                            notify_on_category_change: true,
                         },
                     );
                     if let Err(e) = backend.config.save(cfg) {
@@ -351,9 +521,26 @@ impl Backend {
                         if let Err(e) = backend.db.record_streams(&event.streams) {
                             tracing::error!("Failed to record stream history: {}", e);
                         }
+                        if let Err(e) = backend.db.record_stream_ends(&event.ended, Utc::now()) {
+                            tracing::error!("Failed to record stream ends: {}", e);
+                        }
+                        if let Err(e) = backend.db.replace_live_streams(&event.streams) {
+                            tracing::error!("Failed to record live streams: {}", e);
+                        }
+                        if let Err(e) = backend.db.replace_cached_streams(None, &event.streams) {
+                            tracing::error!("Failed to cache live streams: {}", e);
+                        }
 
                         // Record viewer observations for hotness detection
                         backend.record_and_evaluate_hotness(&event);
+
+                        backend.remind_watch_streaks(&event.streams);
+                        backend.mark_unread_favourites(&event).await;
+                        backend.find_ended_videos(&event).await;
+                        // In the background; shown from the next display push on
+                        let thumbnails = backend.thumbnails.clone();
+                        let streams = event.streams.clone();
+                        tokio::spawn(async move { thumbnails.fetch_all(&streams).await });
                     }
                     Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
                         tracing::warn!("History listener lagged by {} events", n);
@@ -378,13 +565,13 @@ impl Backend {
             .iter()
             .map(|s| s.broadcaster_id.clone())
             .collect();
-        self.ensure_profile_images_cached(&sched_ids).await;
+        self.ensure_users_cached(&sched_ids).await;
 
         let profile_image_urls = {
-            let cache = self.profile_image_cache.lock().unwrap();
-            cache
+            let users = self.users.lock().unwrap();
+            users
                 .iter()
-                .map(|(id, (url, _))| (id.clone(), url.clone()))
+                .map(|(id, (user, _))| (id.clone(), user.profile_image_url.clone()))
                 .collect()
         };
 
@@ -404,69 +591,183 @@ impl Backend {
                 .collect()
         };
         let live_streams = self.state.get_followed_streams().await;
+        let thumbnail_paths = self.thumbnails.cached_paths(&live_streams);
 
         // Evaluate hotness for all live streams
         let hotness_results = self.evaluate_hotness(&live_streams);
-        let hot_stream_ids: std::collections::HashSet<String> = hotness_results
+        let hot_stream_ids: HashSet<String> = hotness_results
             .iter()
             .filter(|h| h.is_hot)
             .map(|h| h.broadcaster_id.clone())
             .collect();
+        let streaks_at_risk = self.streaks_at_risk(&live_streams);
+        let pinned_logins: Vec<String> = cfg
+            .pinned_streams
+            .iter()
+            .map(|p| p.user_login.clone())
+            .collect();
 
+        let login_progress = self.login_progress_rx.borrow().clone();
         let raw = RawDisplayData {
             is_authenticated: self.state.is_authenticated().await,
+            authenticating: self.state.is_authenticating().await,
+            login_progress,
             live_streams,
             scheduled_streams,
             schedules_loaded: self.state.schedules_loaded().await,
+            schedules_cached: self.state.schedules_cached().await,
+            followed_cached: self.state.followed_cached().await,
+            cached_categories: self.state.cached_categories().await,
             followed_channels: self.state.get_followed_channels().await,
             followed_categories: cfg.followed_categories.clone(),
             category_streams: self.state.get_category_streams().await,
             config: cfg,
             profile_image_urls,
             box_art_urls,
+            thumbnail_paths,
             hot_stream_ids,
+            streaks_at_risk,
+            last_watched: self.db.get_last_watched().unwrap_or_else(|e| {
+                tracing::warn!("Failed to load watch history: {}", e);
+                HashMap::new()
+            }),
+            last_seen: self.db.get_last_seen().unwrap_or_else(|e| {
+                tracing::warn!("Failed to load stream history: {}", e);
+                HashMap::new()
+            }),
+            last_notified: self.state.last_notified().await,
+            has_unread: self.state.has_unread().await,
+            refreshing: self.state.is_refreshing().await,
+            last_refresh: self.state.last_refresh().await,
+            connection_problem_since: self.state.connection_problem_since().await,
+            connectivity: self.state.connectivity().await,
+            token_refresh_failing: self.state.token_expiry().await.is_some_and(|t| t.failing()),
+            missing_scopes: self.state.missing_scopes().await,
+            recent_videos: self.state.get_recent_videos().await,
+            recently_ended: self.state.recently_ended(Utc::now()).await,
+            pinned_last_live: self.state.last_live(&pinned_logins).await,
+            notification_snoozes: self.state.notification_snoozes(Utc::now()).await,
+            undoable_change: self
+                .settings_undo
+                .lock()
+                .unwrap()
+                .latest()
+                .map(|change| change.user_login.clone()),
         };
         let _ = display_tx.send(raw);
     }
 
     async fn tick_stream_poll(&self, now: DateTime<Utc>) -> bool {
+        let resumed = self.resumed.swap(false, Ordering::SeqCst);
         if !self.state.is_authenticated().await {
             return false;
         }
 
+        // While offline, probe instead of polling and refresh as soon as
+        // Twitch answers again. A resume probes straight away.
+        let back_online = match self.state.outage().await {
+            Some(outage) if now < outage.next_probe() && !resumed => return false,
+            Some(_) => {
+                if !self.probe_connectivity(now).await {
+                    return false;
+                }
+                true
+            }
+            None => false,
+        };
+
         let last_refresh = self.session.last_live_refresh().await;
         let poll_interval_secs = self.config.get().poll_interval_sec;
 
-        let should_refresh = match last_refresh {
-            None => true,
-            Some(last) => (now - last).num_seconds() >= poll_interval_secs as i64,
-        };
+        let should_refresh =
+            back_online || resumed || refresh_due(now, last_refresh, poll_interval_secs);
 
         if should_refresh {
             self.refresh_followed_streams().await;
             self.refresh_category_streams().await;
             self.refresh_schedules_from_db().await;
+            self.send_title_alerts().await;
         }
 
         should_refresh
     }
 
+    /// Marks the time suspended for the notification gap check and has the
+    /// stream poll refresh on its next tick.
+    async fn handle_resume(&self, suspension: Suspension) {
+        tracing::info!(
+            "Resumed after {}s suspended",
+            suspension.duration().num_seconds()
+        );
+        // A poll that already ran after waking left the whole gap to the
+        // dispatcher's own check
+        let polled_since = self
+            .session
+            .last_live_refresh()
+            .await
+            .is_some_and(|at| at > suspension.since);
+        if !polled_since {
+            self.state.record_resume(suspension.duration()).await;
+        }
+        self.resumed.store(true, Ordering::SeqCst);
+    }
+
+    /// Checks whether Twitch can be reached again, ending the outage if so.
+    async fn probe_connectivity(&self, now: DateTime<Utc>) -> bool {
+        if !connectivity::probe(self.client.http()).await {
+            self.state.record_failed_probe(now).await;
+            return false;
+        }
+        if let Some(outage) = self.state.set_online().await {
+            tracing::info!(
+                "Twitch reachable again after {}s offline",
+                (now - outage.since).num_seconds()
+            );
+        }
+        true
+    }
+
+    /// Refreshes the OAuth token once it's due (see `TokenExpiry::next_attempt`).
+    async fn tick_token_refresh(
+        &self,
+        now: DateTime<Utc>,
+        event_tx: &broadcast::Sender<BackendEvent>,
+    ) {
+        // Failures while offline would only end in asking for a new login
+        if !self.state.is_authenticated().await || self.state.outage().await.is_some() {
+            return;
+        }
+        let due = self
+            .state
+            .token_expiry()
+            .await
+            .is_some_and(|expiry| now >= expiry.next_attempt());
+        if !due {
+            return;
+        }
+
+        match self.session.refresh_token_ahead(now).await {
+            // The settings window shows when the token next renews
+            Ok(()) => {
+                let _ = event_tx.send(BackendEvent::AuthStateChanged {
+                    is_authenticated: true,
+                });
+            }
+            Err(e) => tracing::warn!("Failed to refresh token ahead of expiry: {}", e),
+        }
+    }
+
     async fn tick_followed_channels(
         &self,
         now: DateTime<Utc>,
         last_refresh: Option<DateTime<Utc>>,
         interval_secs: u64,
     ) -> bool {
-        if !self.state.is_authenticated().await {
+        if !self.state.is_authenticated().await || self.state.outage().await.is_some() {
             return false;
         }
 
-        let should_refresh = match last_refresh {
-            None => true,
-            Some(last) => (now - last).num_seconds() >= interval_secs as i64,
-        };
-
-        if !should_refresh {
+        if !refresh_due(now, last_refresh, interval_secs) {
             return false;
         }
 
@@ -474,10 +775,89 @@ impl Backend {
             tracing::warn!("Failed to refresh followed channels: {}", e);
             false
         } else {
+            // Profile images for the settings window's followed channel list
+            let user_ids: Vec<String> = self
+                .state
+                .get_followed_channels()
+                .await
+                .into_iter()
+                .map(|c| c.broadcaster_id)
+                .collect();
+            self.ensure_users_cached(&user_ids).await;
+            self.refresh_recent_videos().await;
             true
         }
     }
 
+    /// Fetches the latest past broadcasts of every followed Favourite.
+    ///
+    /// Runs on the followed channels cadence: VODs only appear once a stream
+    /// ends, so there's no need to poll them as often as live streams.
+    async fn refresh_recent_videos(&self) {
+        let settings = self.config.get().streamer_settings;
+        let favourites: Vec<_> = self
+            .state
+            .get_followed_channels()
+            .await
+            .into_iter()
+            .filter(|c| filtering::is_favourite(&c.broadcaster_login, &settings))
+            .collect();
+
+        let mut videos = HashMap::new();
+        for channel in favourites {
+            match self
+                .session
+                .with_auth_retry(|| {
+                    self.client
+                        .get_videos(&channel.broadcaster_id, RECENT_VIDEOS_PER_STREAMER)
+                })
+                .await
+            {
+                Ok(list) if !list.is_empty() => {
+                    videos.insert(channel.broadcaster_login, list);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to get past broadcasts for {}: {}",
+                        channel.broadcaster_login,
+                        e
+                    );
+                }
+            }
+        }
+        self.state.set_recent_videos(videos).await;
+    }
+
+    /// Looks up the past broadcast of each stream that just went offline, for
+    /// the "Recently Ended" menu.
+    async fn find_ended_videos(&self, event: &crate::state::StreamsUpdated) {
+        let offline = event
+            .ended
+            .iter()
+            .filter(|old| !event.streams.iter().any(|s| s.user_id == old.user_id));
+        for stream in offline {
+            match self
+                .session
+                .with_auth_retry(|| self.client.get_videos(&stream.user_id, 1))
+                .await
+            {
+                Ok(videos) => {
+                    if let Some(video) = videos.into_iter().find(|v| v.is_archive_of(stream)) {
+                        self.state.set_ended_video(&stream.user_id, video).await;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to get past broadcast for {}: {}",
+                        stream.user_login,
+                        e
+                    );
+                }
+            }
+        }
+    }
+
     /// Records viewer observations and evaluates hotness for all live streams.
     ///
     /// For newly live streams, populates the hotness cache from historical DB data.
@@ -578,7 +958,11 @@ impl Backend {
                     cached.was_hot = info.is_hot;
 
                     // Edge detection: notify only on not-hot → hot transition
-                    if info.is_hot && !was_hot && cfg.notify_on_hot {
+                    if info.is_hot
+                        && !was_hot
+                        && cfg.notify_on_hot
+                        && filtering::notifies(&stream.user_login, &cfg.streamer_settings, false)
+                    {
                         tracing::info!(
                             "🔥 {} is HOT (z={:.1}σ, {} viewers, avg {:.0})",
                             stream.user_name,
@@ -646,6 +1030,144 @@ impl Backend {
         results
     }
 
+    /// Returns the watch streak `stream` would lose if it ends unwatched, if any.
+    fn streak_at_risk_for(&self, stream: &Stream, now: DateTime<Utc>) -> Option<u32> {
+        let user_id: i64 = stream.user_id.parse().ok()?;
+        let since = now - chrono::Duration::days(STREAK_LOOKBACK_DAYS);
+        let starts = self
+            .db
+            .get_streams_in_range(&[user_id], since, stream.started_at)
+            .map_err(|e| tracing::warn!("Failed to load stream history: {}", e))
+            .ok()?
+            .remove(&user_id)
+            .unwrap_or_default();
+        let watches = self
+            .db
+            .get_watch_times(&stream.user_login, since)
+            .map_err(|e| tracing::warn!("Failed to load watch history: {}", e))
+            .ok()?;
+        streak_at_risk(&starts, stream.started_at, &watches)
+    }
+
+    /// Streaks at risk for live Favourites, keyed by user ID. Empty unless enabled.
+    fn streaks_at_risk(&self, streams: &[Stream]) -> HashMap<String, u32> {
+        let cfg = self.config.get();
+        if !cfg.notify_watch_streak {
+            return HashMap::new();
+        }
+        let now = Utc::now();
+        streams
+            .iter()
+            .filter(|s| filtering::is_favourite(&s.user_login, &cfg.streamer_settings))
+            .filter_map(|s| Some((s.user_id.clone(), self.streak_at_risk_for(s, now)?)))
+            .collect()
+    }
+
+    /// Flags Favourites that just went live so the tray shows the unread icon.
+    ///
+    /// Streams found by the first poll after login aren't news, matching
+    /// the notification rules.
+    async fn mark_unread_favourites(&self, event: &crate::state::StreamsUpdated) {
+        if !self.session.initial_load_done.load(Ordering::SeqCst) {
+            return;
+        }
+        let favourites = {
+            let cfg = self.config.get();
+            event
+                .newly_live
+                .iter()
+                .filter(|s| filtering::is_favourite(&s.user_login, &cfg.streamer_settings))
+                .map(|s| s.user_id.clone())
+                .collect()
+        };
+        self.state.mark_unread(favourites).await;
+    }
+
+    /// Sends one streak reminder per stream once it has been live unwatched for a while.
+    fn remind_watch_streaks(&self, streams: &[Stream]) {
+        let now = Utc::now();
+        let at_risk = self.streaks_at_risk(streams);
+        let mut reminded = self.streak_reminded.lock().unwrap();
+        for stream in streams {
+            let Some(&streak) = at_risk.get(&stream.user_id) else {
+                continue;
+            };
+            if (now - stream.started_at).num_minutes() < STREAK_REMINDER_DELAY_MIN
+                || !reminded.insert(stream.id.clone())
+            {
+                continue;
+            }
+            tracing::info!("{} watch streak of {} at risk", stream.user_name, streak);
+            if let Err(e) = self.notifier.streak_reminder(stream, streak) {
+                tracing::error!("Streak reminder error: {}", e);
+            }
+        }
+        // Forget streams that ended so the set doesn't grow without bound
+        let live_ids: HashSet<&str> = streams.iter().map(|s| s.id.as_str()).collect();
+        reminded.retain(|id| live_ids.contains(id.as_str()));
+    }
+
+    /// Notifies once per stream when a followed or category stream's title
+    /// matches one of `title_alerts`. Silent and Ignored streamers send
+    /// nothing; matches during quiet hours wait for the window to end.
+    async fn send_title_alerts(&self) {
+        let cfg = self.config.get();
+        let alerts = TitleAlerts::new(&cfg.title_alerts);
+        // Before matching, so nothing is marked alerted without being sent
+        if alerts.is_empty()
+            || cfg
+                .quiet_hours
+                .is_some_and(|q| q.contains(Local::now().time()))
+        {
+            return;
+        }
+
+        let mut streams = self.state.get_followed_streams().await;
+        for category_streams in self.state.get_category_streams().await.into_values() {
+            for stream in category_streams {
+                if !streams.iter().any(|s| s.id == stream.id) {
+                    streams.push(stream);
+                }
+            }
+        }
+        streams.retain(|s| filtering::notifies(&s.user_login, &cfg.streamer_settings, false));
+
+        let matches = alerts.new_matches(&streams, &mut self.title_alerted.lock().unwrap());
+        for (stream, pattern) in matches {
+            tracing::info!("{}'s title matched \"{}\"", stream.user_name, pattern);
+            if let Err(e) = self.notifier.title_matched(stream, &pattern) {
+                tracing::error!("Title alert notification error: {}", e);
+            }
+        }
+    }
+
+    /// Notifies for scheduled streams starting within their streamer's
+    /// `schedule_reminder_min`.
+    async fn send_schedule_reminders(&self, reminders: &mut ScheduleReminders) {
+        let settings = self.config.get().streamer_settings;
+        if settings.values().all(|s| s.schedule_reminder_min.is_none()) {
+            return;
+        }
+        let schedules = self.state.get_scheduled_streams().await;
+        let live: HashSet<String> = self
+            .state
+            .get_followed_streams()
+            .await
+            .into_iter()
+            .map(|s| s.user_id)
+            .collect();
+        for schedule in reminders.due(&schedules, &settings, &live, Utc::now()) {
+            tracing::info!(
+                "Reminding of {}'s stream at {}",
+                schedule.broadcaster_name,
+                schedule.start_time
+            );
+            if let Err(e) = self.notifier.scheduled_soon(schedule) {
+                tracing::error!("Schedule reminder notification error: {}", e);
+            }
+        }
+    }
+
     pub(crate) async fn refresh_all_data(&self) {
         self.refresh_followed_streams().await;
         self.refresh_schedules_from_db().await;
@@ -659,13 +1181,34 @@ impl Backend {
             return;
         }
 
-        let mut streams = match self.with_retry(|| self.client.get_followed_streams()).await {
-            Ok(streams) => streams,
+        let result = self
+            .session
+            .with_auth_retry(|| self.client.get_followed_streams_if_changed())
+            .await;
+        let mut streams = match result {
+            Ok(Some(streams)) => streams,
+            // Unchanged since the last poll: nothing to diff or redraw, but
+            // the cached streams were still live just now
+            Ok(None) => {
+                self.state.set_online().await;
+                self.session.record_live_refresh().await;
+                let streams = self.state.get_followed_streams().await;
+                if let Err(e) = self.db.record_streams(&streams) {
+                    tracing::error!("Failed to record stream history: {}", e);
+                }
+                return;
+            }
             Err(e) => {
                 tracing::error!("Failed to get followed streams: {}", e);
+                let now = Utc::now();
+                self.state.record_refresh_failure(now).await;
+                if connectivity::is_offline_error(&e) && self.state.set_offline(now).await {
+                    tracing::warn!("Twitch can't be reached; pausing refreshes until it can");
+                }
                 return;
             }
         };
+        self.state.set_online().await;
 
         // Enrich streams with profile image URLs from the Users API
         self.enrich_with_profile_images(&mut streams).await;
@@ -674,61 +1217,34 @@ impl Backend {
         self.state.set_followed_streams(streams).await;
     }
 
-    /// Ensures all given user IDs have profile images in the cache.
-    /// Fetches any missing ones from the Twitch Users API.
-    async fn ensure_profile_images_cached(&self, user_ids: &[String]) {
-        const CACHE_TTL: Duration = Duration::from_secs(3600);
-
-        if user_ids.is_empty() {
+    /// Ensures all given user IDs are in `users`, fetching missing ones and
+    /// those older than [`USER_REFRESH_AFTER_DAYS`] from the Twitch Users API.
+    async fn ensure_users_cached(&self, user_ids: &[String]) {
+        let now = Utc::now();
+        let stale = stale_user_ids(&self.users.lock().unwrap(), user_ids, now);
+        if stale.is_empty() {
             return;
         }
 
-        let now = Instant::now();
-
-        let uncached_ids: Vec<String> = {
-            let cache = self.profile_image_cache.lock().unwrap();
-            user_ids
-                .iter()
-                .filter(|id| {
-                    cache
-                        .get(id.as_str())
-                        .is_none_or(|(_, fetched_at)| now.duration_since(*fetched_at) >= CACHE_TTL)
-                })
-                .cloned()
-                .collect()
+        let stale_refs: Vec<&str> = stale.iter().map(String::as_str).collect();
+        let fetched = match self
+            .session
+            .with_auth_retry(|| self.client.get_users_by_ids(&stale_refs))
+            .await
+        {
+            Ok(users) => users,
+            Err(e) => {
+                tracing::warn!("Failed to fetch user profiles: {}", e);
+                return;
+            }
         };
 
-        if uncached_ids.is_empty() {
-            return;
-        }
-
-        // Fetch in batches of 100 (Twitch API limit)
-        let uncached_refs: Vec<&str> = uncached_ids
-            .iter()
-            .map(std::string::String::as_str)
-            .collect();
-        let mut fetched = HashMap::new();
-        for chunk in uncached_refs.chunks(100) {
-            match self
-                .with_retry(|| self.client.get_users_by_ids(chunk))
-                .await
-            {
-                Ok(users) => {
-                    for user in users {
-                        fetched.insert(user.id, user.profile_image_url);
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to fetch user profiles: {}", e);
-                }
-            }
+        if let Err(e) = self.db.upsert_users_at(&fetched, now) {
+            tracing::warn!("Failed to save user profiles: {}", e);
         }
-
-        if !fetched.is_empty() {
-            let mut cache = self.profile_image_cache.lock().unwrap();
-            for (id, url) in fetched {
-                cache.insert(id, (url, now));
-            }
+        let mut users = self.users.lock().unwrap();
+        for user in fetched {
+            users.insert(user.id.clone(), (user, now));
         }
     }
 
@@ -767,16 +1283,13 @@ impl Backend {
         let mut fetched = HashMap::new();
         for chunk in uncached_refs.chunks(100) {
             match self
-                .with_retry(|| self.client.get_games_by_ids(chunk))
+                .session
+                .with_auth_retry(|| self.client.get_games_by_ids(chunk))
                 .await
             {
                 Ok(games) => {
                     for game in games {
-                        // Replace template placeholders with fixed dimensions (144x192)
-                        let url = game
-                            .box_art_url
-                            .replace("{width}", "144")
-                            .replace("{height}", "192");
+                        let url = game.box_art(BOX_ART_WIDTH, BOX_ART_HEIGHT);
                         fetched.insert(game.id, url);
                     }
                 }
@@ -796,12 +1309,12 @@ impl Backend {
 
     async fn enrich_with_profile_images(&self, streams: &mut [crate::twitch::Stream]) {
         let user_ids: Vec<String> = streams.iter().map(|s| s.user_id.clone()).collect();
-        self.ensure_profile_images_cached(&user_ids).await;
+        self.ensure_users_cached(&user_ids).await;
 
-        let cache = self.profile_image_cache.lock().unwrap();
+        let users = self.users.lock().unwrap();
         for stream in streams.iter_mut() {
-            if let Some((url, _)) = cache.get(&stream.user_id) {
-                stream.profile_image_url = url.clone();
+            if let Some((user, _)) = users.get(&stream.user_id) {
+                stream.profile_image_url = user.profile_image_url.clone();
             }
         }
     }
@@ -816,16 +1329,36 @@ impl Backend {
             return;
         }
 
-        let language = crate::twitch::system_language();
-        let lang_ref = language.as_deref();
-
         for category in &categories {
             let cat_id = category.id.clone();
-            let mut streams = match self
-                .with_retry(|| self.client.get_streams_by_category(&cat_id, lang_ref))
-                .await
-            {
-                Ok(streams) => streams,
+            let filters = &category.filters;
+            let languages = category_filters::query_languages(filters);
+            let fetched = if category_filters::filters_after_fetch(filters) {
+                self.session
+                    .with_auth_retry(|| {
+                        self.client.get_streams_by_category(
+                            &cat_id,
+                            &languages,
+                            None,
+                            category_filters::FILTERED_CATEGORY_PAGES,
+                        )
+                    })
+                    .await
+                    .map(|(mut streams, _)| {
+                        streams.retain(|s| category_filters::matches(filters, s));
+                        Some(streams)
+                    })
+            } else {
+                self.session
+                    .with_auth_retry(|| {
+                        self.client
+                            .get_streams_by_category_if_changed(&cat_id, &languages)
+                    })
+                    .await
+            };
+            let mut streams = match fetched {
+                Ok(Some(streams)) => streams,
+                Ok(None) => continue,
                 Err(e) => {
                     tracing::error!(
                         "Failed to get category streams for {}: {}",
@@ -837,10 +1370,73 @@ impl Backend {
             };
 
             self.enrich_with_profile_images(&mut streams).await;
+            if let Err(e) = self.db.replace_cached_streams(Some(&category.id), &streams) {
+                tracing::error!("Failed to cache streams for {}: {}", category.name, e);
+            }
 
-            self.state
+            let diff = self
+                .state
                 .set_category_streams(category.id.clone(), streams)
                 .await;
+            self.notify_category_streams(category, &diff).await;
+        }
+    }
+
+    /// Notifies for streams that just showed up in `category` under its
+    /// `small_stream_max_viewers`, and for the new top streams and Favourites
+    /// it opted in to. Muted categories, quiet hours and snoozes send nothing.
+    async fn notify_category_streams(
+        &self,
+        category: &FollowedCategory,
+        diff: &CategoryStreamsDiff,
+    ) {
+        let cfg = self.config.get();
+        let quiet = cfg
+            .quiet_hours
+            .is_some_and(|q| q.contains(Local::now().time()));
+        if category.notifications.enabled == Some(false) || quiet {
+            return;
+        }
+        let now = Utc::now();
+        let snoozes = self.state.notification_snoozes(now).await;
+
+        for (stream, alert) in
+            category_alerts(diff, &category.notifications, &cfg.streamer_settings)
+        {
+            if snoozes.is_snoozed(&stream.user_login, now) {
+                continue;
+            }
+            if let Err(e) = self.notifier.category_alert(&stream, alert) {
+                tracing::error!("Category alert notification error: {}", e);
+            }
+        }
+
+        let Some(max_viewers) = category.notifications.small_stream_max_viewers else {
+            return;
+        };
+        let arrived = diff.arrived();
+        if arrived.is_empty() {
+            return;
+        }
+        let followed_user_ids: HashSet<String> = self
+            .state
+            .get_followed_streams()
+            .await
+            .into_iter()
+            .map(|s| s.user_id)
+            .collect();
+        for stream in small_streams(
+            arrived,
+            max_viewers,
+            &cfg.streamer_settings,
+            &followed_user_ids,
+        ) {
+            if snoozes.is_snoozed(&stream.user_login, now) {
+                continue;
+            }
+            if let Err(e) = self.notifier.small_stream_live(&stream) {
+                tracing::error!("Small stream notification error: {}", e);
+            }
         }
     }
 
@@ -860,13 +1456,48 @@ impl Backend {
                 self.refresh_all_data().await;
                 self.push_display_state(display_tx).await;
             }
+            Err(e) if login_cancelled(&e) => {}
             Err(e) => {
                 tracing::error!("Authentication failed: {}", e);
-                let _ = self.notifier.error(&format!("Authentication failed: {e}"));
+                let _ = self.notifier.error(&login_error_message(&e));
             }
         }
     }
 
+    async fn handle_switch_user(
+        &self,
+        event_tx: &broadcast::Sender<BackendEvent>,
+        display_tx: &watch::Sender<RawDisplayData>,
+    ) {
+        let _ = self.auth_cancel_tx.send(false);
+        let cancel_rx = self.auth_cancel_rx.clone();
+
+        let _ = event_tx.send(BackendEvent::AuthStateChanged {
+            is_authenticated: false,
+        });
+        self.push_display_state(display_tx).await;
+
+        let result = self.session.handle_switch_user(cancel_rx).await;
+        let is_authenticated = self.state.is_authenticated().await;
+        if let Some(e) = result.as_ref().err().filter(|e| !login_cancelled(e)) {
+            tracing::error!("Switch user failed: {}", e);
+            let message = if is_authenticated {
+                "Switch user didn't complete, so you're still logged in to your previous account."
+                    .to_string()
+            } else {
+                login_error_message(e)
+            };
+            let _ = self.notifier.error(&message);
+        }
+        if is_authenticated {
+            let _ = event_tx.send(BackendEvent::AuthStateChanged {
+                is_authenticated: true,
+            });
+            self.refresh_all_data().await;
+        }
+        self.push_display_state(display_tx).await;
+    }
+
     async fn handle_logout(
         &self,
         event_tx: &broadcast::Sender<BackendEvent>,
@@ -948,8 +1579,15 @@ impl Backend {
             })
             .collect();
 
+        let cfg = self.config.get();
         if let Ok(channel_lookup) = self.db.get_followed_channel_lookup() {
-            if let Ok(inferred) = self.db.infer_schedules(&channel_lookup, start_dt, end_dt) {
+            if let Ok(inferred) = self.db.infer_schedules(
+                &channel_lookup,
+                start_dt,
+                end_dt,
+                cfg.schedule_inference_model,
+                cfg.schedule_inference_lookback_weeks,
+            ) {
                 for s in inferred {
                     entries.push(DebugStreamEntry {
                         is_inferred: true,
@@ -964,6 +1602,313 @@ impl Backend {
         entries.sort_by_key(|e| e.started_at);
         entries
     }
+
+    /// Followed broadcasts that were live at the Unix timestamp `at`, oldest first.
+    pub(crate) fn get_live_at(&self, at: i64) -> Vec<crate::app_services::LiveAtEntry> {
+        use crate::app_services::LiveAtEntry;
+
+        let Some(at) = DateTime::from_timestamp(at, 0) else {
+            return Vec::new();
+        };
+        self.db
+            .get_live_at(at, chrono::Duration::hours(HISTORY_MAX_OPEN_STREAM_HOURS))
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to query stream history: {}", e);
+                Vec::new()
+            })
+            .into_iter()
+            .map(|(name, login, started_at, ended_at)| LiveAtEntry {
+                broadcaster_name: name,
+                broadcaster_login: login,
+                started_at,
+                ended_at,
+            })
+            .collect()
+    }
+
+    /// Streaming habits of every followed channel seen live, busiest first.
+    pub(crate) fn get_history_summary(&self) -> Vec<crate::stream_stats::StreamerStats> {
+        let rows = self
+            .db
+            .get_raw_history_in_window(0, i64::MAX)
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to query stream history: {}", e);
+                Vec::new()
+            });
+        let durations = self.get_average_durations_by_login();
+        crate::stream_stats::summarize(&rows, &durations, Utc::now(), &chrono::Local)
+    }
+
+    /// `Database::get_average_duration` for followed channels, keyed by login.
+    fn get_average_durations_by_login(&self) -> HashMap<String, chrono::Duration> {
+        let (durations, channels) = match (
+            self.db.get_average_duration(),
+            self.db.get_followed_channel_lookup(),
+        ) {
+            (Ok(durations), Ok(channels)) => (durations, channels),
+            (Err(e), _) | (_, Err(e)) => {
+                tracing::error!("Failed to query stream durations: {}", e);
+                return HashMap::new();
+            }
+        };
+        durations
+            .into_iter()
+            .filter_map(|(user_id, duration)| {
+                channels
+                    .get(&user_id.to_string())
+                    .map(|c| (c.broadcaster_login.clone(), duration))
+            })
+            .collect()
+    }
+
+    /// Categories from the last month of opened streams that aren't followed yet.
+    pub(crate) fn get_followed_category_suggestions(&self) -> Vec<CategorySuggestion> {
+        let since = Utc::now() - chrono::Duration::days(SUGGESTION_LOOKBACK_DAYS);
+        let watched = self
+            .db
+            .get_watched_categories(
+                since,
+                chrono::Duration::hours(HISTORY_MAX_OPEN_STREAM_HOURS),
+            )
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to query watched categories: {}", e);
+                Vec::new()
+            })
+            .into_iter()
+            .map(|(id, name, count)| CategorySuggestion {
+                id,
+                name,
+                watched_streams: u32::try_from(count).unwrap_or(u32::MAX),
+            })
+            .collect();
+        suggest_categories(watched, &self.config.get())
+    }
+
+    /// Records a stream being opened from the app so favourites can sort by recency.
+    fn record_stream_opened(&self, user_login: &str) {
+        if let Err(e) = self.db.record_watch(user_login, Utc::now()) {
+            tracing::error!("Failed to record watch history: {}", e);
+            return;
+        }
+        self.state.notify_watch_history_changed();
+    }
+
+    /// A followed channel's display name, from its live stream or the follow
+    /// list, falling back to the login.
+    async fn display_name_for(&self, user_login: &str) -> String {
+        if let Some(stream) = self
+            .state
+            .get_followed_streams()
+            .await
+            .into_iter()
+            .find(|s| s.user_login == user_login)
+        {
+            return stream.user_name;
+        }
+        self.state
+            .get_followed_channels()
+            .await
+            .into_iter()
+            .find(|c| c.broadcaster_login == user_login)
+            .map_or_else(|| user_login.to_string(), |c| c.broadcaster_name)
+    }
+
+    /// Gives `logins` `importance`, naming new entries from `names`, and saves
+    /// the config once with every change undoable.
+    fn save_importance(
+        &self,
+        logins: &[String],
+        importance: StreamerImportance,
+        names: &HashMap<String, String>,
+    ) -> anyhow::Result<usize> {
+        let mut config = self.config.get();
+        let previous = config.streamer_settings.clone();
+        let changed = set_importance(&mut config.streamer_settings, logins, importance, |login| {
+            names
+                .get(login)
+                .cloned()
+                .unwrap_or_else(|| login.to_string())
+        });
+        if changed > 0 {
+            let streamer_settings = config.streamer_settings.clone();
+            // Nothing to refresh: importance only changes how fetched streams show
+            self.config.save(config)?;
+            self.settings_undo
+                .lock()
+                .unwrap()
+                .record(&previous, &streamer_settings);
+            self.state.notify_settings_changed();
+            tracing::info!("Set {} streamers to {:?}", changed, importance);
+        }
+        Ok(changed)
+    }
+
+    /// Gathers app state into a redacted zip in the user's downloads folder.
+    async fn create_diagnostics_bundle(&self) -> anyhow::Result<std::path::PathBuf> {
+        use crate::support::{self, DiagnosticsReport, PlatformInfo, TableStats};
+
+        let now = Utc::now();
+        let database = self
+            .db
+            .table_row_counts()
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to read database stats: {}", e);
+                Vec::new()
+            })
+            .into_iter()
+            .map(|(table, rows)| TableStats { table, rows })
+            .collect();
+
+        let report = DiagnosticsReport {
+            generated_at: now,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            platform: PlatformInfo::current(),
+            authenticated: self.state.is_authenticated().await,
+            live_stream_count: self.state.get_followed_streams().await.len(),
+            followed_channel_count: self.state.get_followed_channels().await.len(),
+            schedule_check_interval_sec: self.walker.check_interval_secs().await,
+            conditional_requests: self.client.conditional_request_stats(),
+            notifications: self.notifier.capabilities(),
+            database,
+        };
+        let logs =
+            support::collect_recent_logs(&support::log_dir()?, support::MAX_LOG_BYTES_PER_FILE);
+
+        let dir = match dirs::download_dir() {
+            Some(dir) => dir,
+            None => ConfigManager::config_dir()?,
+        };
+        let path = dir.join(support::bundle_file_name(now));
+        let file = std::fs::File::create(&path)?;
+        support::write_bundle(file, &report, &self.config.get(), &logs)?;
+
+        tracing::info!("Wrote diagnostics bundle to {:?}", path);
+        Ok(path)
+    }
+
+    /// Collects auth, refresh, database and platform health for the settings
+    /// window's Diagnostics tab.
+    pub async fn get_diagnostics(&self) -> crate::support::AppDiagnostics {
+        use crate::support::{self, AppDiagnostics, PlatformInfo};
+
+        let db_path = ConfigManager::config_dir()
+            .map(|dir| dir.join(DB_FILE))
+            .unwrap_or_default();
+        let db_size_bytes = std::fs::metadata(&db_path).ok().map(|m| m.len());
+        let token_expiry = self.state.token_expiry().await;
+
+        AppDiagnostics {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            platform: PlatformInfo::current(),
+            authenticated: self.state.is_authenticated().await,
+            user_login: self.state.user_login().await,
+            token_expires_at: token_expiry.map(|e| e.expires_at),
+            token_refresh_failures: token_expiry.map_or(0, |e| e.failures),
+            last_live_refresh: self.state.last_refresh().await,
+            live_refresh_failures: self.state.refresh_failures().await,
+            last_followed_refresh: self.state.followed_refreshed_at().await,
+            followed_channel_count: self.state.get_followed_channels().await.len(),
+            live_stream_count: self.state.get_followed_streams().await.len(),
+            scheduled_stream_count: self.state.get_scheduled_streams().await.len(),
+            db_path,
+            db_size_bytes,
+            log_dir: support::log_dir().ok(),
+        }
+    }
+
+    /// Writes a settings export (see `data_export`), by default to the
+    /// user's downloads folder.
+    fn export_data(
+        &self,
+        path: Option<std::path::PathBuf>,
+        include_history: bool,
+    ) -> anyhow::Result<std::path::PathBuf> {
+        use crate::data_export::{self, ExportManifest};
+
+        let now = Utc::now();
+        let path = if let Some(path) = path {
+            path
+        } else {
+            let dir = match dirs::download_dir() {
+                Some(dir) => dir,
+                None => ConfigManager::config_dir()?,
+            };
+            dir.join(data_export::export_file_name(now))
+        };
+
+        let history = if include_history {
+            // VACUUM INTO needs a fresh file to write to
+            let copy = ConfigManager::config_dir()?.join("export.db");
+            let _ = std::fs::remove_file(&copy);
+            self.db.export_to(&copy)?;
+            let bytes = std::fs::read(&copy);
+            let _ = std::fs::remove_file(&copy);
+            Some(bytes?)
+        } else {
+            None
+        };
+
+        let file = std::fs::File::create(&path)?;
+        data_export::write_export(
+            file,
+            &ExportManifest::new(now, include_history),
+            &self.config.get(),
+            history.as_deref(),
+        )?;
+
+        tracing::info!("Exported settings to {:?}", path);
+        Ok(path)
+    }
+
+    /// Replaces the local history with an export's `data.db` copy.
+    fn import_history(&self, history: &[u8]) -> anyhow::Result<()> {
+        let copy = ConfigManager::config_dir()?.join("import.db");
+        std::fs::write(&copy, history)?;
+        let result = self.db.import_history_from(&copy);
+        let _ = std::fs::remove_file(&copy);
+        result
+    }
+}
+
+/// Whether a periodic refresh last done at `last` should run again at `now`.
+pub(crate) fn refresh_due(
+    now: DateTime<Utc>,
+    last: Option<DateTime<Utc>>,
+    interval_secs: u64,
+) -> bool {
+    last.is_none_or(|last| (now - last).num_seconds() >= interval_secs as i64)
+}
+
+/// The IDs in `user_ids` missing from `users` or fetched too long ago.
+fn stale_user_ids(users: &CachedUsers, user_ids: &[String], now: DateTime<Utc>) -> Vec<String> {
+    let max_age = chrono::Duration::days(USER_REFRESH_AFTER_DAYS);
+    let mut stale: Vec<String> = user_ids
+        .iter()
+        .filter(|id| {
+            users
+                .get(id.as_str())
+                .is_none_or(|(_, fetched_at)| now - *fetched_at >= max_age)
+        })
+        .cloned()
+        .collect();
+    stale.sort();
+    stale.dedup();
+    stale
+}
+
+/// The notification text for a login that didn't complete.
+fn login_error_message(e: &anyhow::Error) -> String {
+    if matches!(
+        e.downcast_ref::<DeviceFlowError>(),
+        Some(DeviceFlowError::ExpiredToken)
+    ) {
+        format!(
+            "Login code expired {MAX_DEVICE_CODE_ATTEMPTS} times without being \
+             entered. Choose Login to try again."
+        )
+    } else {
+        format!("Authentication failed: {e}")
+    }
 }
 
 #[async_trait::async_trait]
@@ -973,7 +1918,27 @@ impl AppServices for Backend {
     }
 
     async fn save_config(&self, config: crate::config::Config) -> anyhow::Result<()> {
-        self.config.save(config)?;
+        let config = config.validated()?;
+        let current = self.config.get();
+        let streamer_settings = config.streamer_settings.clone();
+        let proxy_url = (config.proxy_url != current.proxy_url).then(|| config.proxy_url.clone());
+        // Moving the token can fail (e.g. no keyring), which rejects the save
+        self.session.store.set_storage(config.token_storage)?;
+        if let Err(e) = self.config.save(config) {
+            if let Err(e) = self.session.store.set_storage(current.token_storage) {
+                tracing::warn!("Failed to move the token back after a failed save: {}", e);
+            }
+            return Err(e);
+        }
+        if let Some(proxy_url) = proxy_url {
+            self.client.http().set_proxy(proxy_url.as_deref())?;
+        }
+        let previous = current.streamer_settings;
+        self.settings_undo
+            .lock()
+            .unwrap()
+            .record(&previous, &streamer_settings);
+        self.state.notify_settings_changed();
         AppServices::refresh_category_streams(self).await;
         AppServices::refresh_schedules_from_db(self).await;
         Ok(())
@@ -983,15 +1948,64 @@ impl AppServices for Backend {
         &self,
         query: &str,
     ) -> Result<Vec<crate::twitch::Category>, crate::twitch::ApiError> {
-        self.client.search_categories(query).await
+        let mut categories = self
+            .session
+            .with_auth_retry(|| self.client.search_categories(query))
+            .await?;
+        for category in &mut categories {
+            category.box_art_url = category.box_art(BOX_ART_WIDTH, BOX_ART_HEIGHT);
+        }
+        Ok(categories)
     }
 
     fn get_followed_categories(&self) -> Vec<crate::config::FollowedCategory> {
         self.config.get().followed_categories
     }
 
-    async fn get_followed_channels(&self) -> Vec<crate::twitch::FollowedChannel> {
-        self.state.get_followed_channels().await
+    async fn get_category_streams_page(
+        &self,
+        category_id: &str,
+        cursor: Option<String>,
+    ) -> Result<CategoryStreamsPage, crate::twitch::ApiError> {
+        let config = self.config.get();
+        let filters = config
+            .followed_categories
+            .iter()
+            .find(|c| c.id == category_id)
+            .map(|c| c.filters.clone())
+            .unwrap_or_default();
+        let languages = category_filters::query_languages(&filters);
+        let (mut streams, cursor) = self
+            .session
+            .with_auth_retry(|| {
+                self.client.get_streams_by_category(
+                    category_id,
+                    &languages,
+                    cursor.as_deref(),
+                    config.category_more_pages,
+                )
+            })
+            .await?;
+        streams.retain(|s| category_filters::matches(&filters, s));
+        self.enrich_with_profile_images(&mut streams).await;
+        Ok(CategoryStreamsPage { streams, cursor })
+    }
+
+    async fn list_followed_channels(
+        &self,
+        query: &crate::followed_list::FollowedQuery,
+    ) -> crate::followed_list::FollowedChannelsPage {
+        let channels = self.state.get_followed_channels().await;
+        let live = self.state.get_followed_streams().await;
+        let profile_images: HashMap<String, String> = self
+            .users
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, (user, _))| (id.clone(), user.profile_image_url.clone()))
+            .collect();
+        let settings = self.config.get().streamer_settings;
+        crate::followed_list::list_followed(&channels, &live, &profile_images, &settings, query)
     }
 
     async fn refresh_category_streams(&self) {
@@ -1013,6 +2027,276 @@ impl AppServices for Backend {
     async fn get_debug_hotness_data(&self) -> Vec<crate::app_services::DebugHotnessEntry> {
         Backend::get_debug_hotness_data(self).await
     }
+
+    async fn create_diagnostics_bundle(&self) -> anyhow::Result<std::path::PathBuf> {
+        Backend::create_diagnostics_bundle(self).await
+    }
+
+    async fn get_diagnostics(&self) -> crate::support::AppDiagnostics {
+        Backend::get_diagnostics(self).await
+    }
+
+    async fn export_data(
+        &self,
+        path: Option<std::path::PathBuf>,
+        include_history: bool,
+    ) -> anyhow::Result<std::path::PathBuf> {
+        Backend::export_data(self, path, include_history)
+    }
+
+    async fn import_data(
+        &self,
+        path: std::path::PathBuf,
+    ) -> anyhow::Result<crate::data_export::ExportManifest> {
+        let file = std::fs::File::open(&path)?;
+        let imported = crate::data_export::read_export(file)?;
+        // Settings first: if they can't be saved, history is left as it was
+        AppServices::save_config(self, imported.config).await?;
+        if let Some(history) = &imported.history {
+            Backend::import_history(self, history)?;
+        }
+        self.state.notify_watch_history_changed();
+        tracing::info!("Imported settings from {:?}", path);
+        Ok(imported.manifest)
+    }
+
+    async fn record_stream_opened(&self, user_login: &str) {
+        Backend::record_stream_opened(self, user_login);
+    }
+
+    async fn set_stream_pinned(&self, user_login: &str, pinned: bool) -> anyhow::Result<()> {
+        let mut config = self.config.get();
+        let changed = if pinned {
+            let pin = PinnedStream {
+                user_login: user_login.to_string(),
+                display_name: self.display_name_for(user_login).await,
+            };
+            pin_stream(&mut config, pin)
+        } else {
+            unpin_stream(&mut config, user_login)
+        };
+        if changed {
+            // Nothing to refresh: pins only rearrange streams already fetched
+            self.config.save(config)?;
+            self.state.notify_settings_changed();
+            tracing::info!(
+                "{} {}",
+                if pinned { "Pinned" } else { "Unpinned" },
+                user_login
+            );
+        }
+        Ok(())
+    }
+
+    async fn snooze_notifications(&self, user_login: Option<String>, duration: SnoozeDuration) {
+        let until = duration.until(&Local::now());
+        tracing::info!(
+            "Snoozed notifications for {} until {}",
+            user_login.as_deref().unwrap_or("everyone"),
+            until
+        );
+        self.state.snooze_notifications(user_login, until).await;
+    }
+
+    async fn resume_notifications(&self, user_login: Option<String>) {
+        self.state.resume_notifications(user_login.as_deref()).await;
+    }
+
+    async fn set_importance_bulk(
+        &self,
+        logins: Vec<String>,
+        importance: StreamerImportance,
+    ) -> anyhow::Result<usize> {
+        let logins = normalize_logins(&logins);
+        let mut names = HashMap::new();
+        for login in &logins {
+            names.insert(login.clone(), self.display_name_for(login).await);
+        }
+
+        self.save_importance(&logins, importance, &names)
+    }
+
+    async fn import_streamer_list(
+        &self,
+        path: std::path::PathBuf,
+        format: StreamerListFormat,
+        importance: StreamerImportance,
+    ) -> anyhow::Result<StreamerListImport> {
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Couldn't read {}", path.display()))?;
+        let logins = parse_streamer_list(&text, format)?;
+        let (valid, mut unresolved): (Vec<String>, Vec<String>) =
+            logins.into_iter().partition(|l| is_valid_login(l));
+
+        let valid_refs: Vec<&str> = valid.iter().map(String::as_str).collect();
+        let users = self
+            .session
+            .with_auth_retry(|| self.client.get_users_by_logins(&valid_refs))
+            .await?;
+        unresolved.extend(unresolved_logins(&valid, &users));
+
+        let now = Utc::now();
+        if let Err(e) = self.db.upsert_users_at(&users, now) {
+            tracing::warn!("Failed to save user profiles: {}", e);
+        }
+        let names: HashMap<String, String> = users
+            .iter()
+            .map(|u| (u.login.clone(), u.display_name.clone()))
+            .collect();
+        let resolved: Vec<String> = users.iter().map(|u| u.login.clone()).collect();
+        {
+            let mut cached = self.users.lock().unwrap();
+            for user in users {
+                cached.insert(user.id.clone(), (user, now));
+            }
+        }
+
+        let changed = self.save_importance(&resolved, importance, &names)?;
+        tracing::info!(
+            "Imported {} streamers from {:?}, {} not found",
+            resolved.len(),
+            path,
+            unresolved.len()
+        );
+        Ok(StreamerListImport {
+            changed,
+            unresolved,
+        })
+    }
+
+    async fn get_live_at(&self, at: i64) -> Vec<crate::app_services::LiveAtEntry> {
+        Backend::get_live_at(self, at)
+    }
+
+    async fn get_history_summary(&self) -> Vec<crate::stream_stats::StreamerStats> {
+        Backend::get_history_summary(self)
+    }
+
+    async fn get_streamer_history(
+        &self,
+        user_login: &str,
+    ) -> Option<crate::stream_stats::StreamerStats> {
+        Backend::get_history_summary(self)
+            .into_iter()
+            .find(|s| s.broadcaster_login == user_login)
+    }
+
+    async fn get_followed_category_suggestions(&self) -> Vec<CategorySuggestion> {
+        Backend::get_followed_category_suggestions(self)
+    }
+
+    async fn accept_category_suggestion(
+        &self,
+        category: crate::config::FollowedCategory,
+    ) -> anyhow::Result<()> {
+        let mut config = self.config.get();
+        if accept_suggestion(&mut config, category) {
+            AppServices::save_config(self, config).await?;
+        }
+        Ok(())
+    }
+
+    async fn dismiss_category_suggestion(&self, category_id: &str) -> anyhow::Result<()> {
+        let mut config = self.config.get();
+        if dismiss_suggestion(&mut config, category_id) {
+            // Nothing to refresh: dismissals only affect suggestions
+            self.config.save(config)?;
+        }
+        Ok(())
+    }
+
+    async fn set_category_notifications(
+        &self,
+        category_id: &str,
+        notifications: crate::config::CategoryNotifications,
+    ) -> anyhow::Result<()> {
+        let mut config = self.config.get();
+        let Some(category) = config
+            .followed_categories
+            .iter_mut()
+            .find(|c| c.id == category_id)
+        else {
+            anyhow::bail!("Category {category_id} is not followed");
+        };
+        category.notifications = notifications;
+        // Nothing to refresh: preferences are read when each notification is sent
+        self.config.save(config)?;
+        Ok(())
+    }
+
+    async fn note_app_version(&self, version: &str) -> anyhow::Result<bool> {
+        let mut config = self.config.get();
+        let seen = mark_version_seen(&mut config, version);
+        if seen != SeenVersion::Same {
+            self.config.save(config)?;
+        }
+        Ok(seen == SeenVersion::Updated)
+    }
+
+    async fn undo_settings_change(&self) -> anyhow::Result<Option<String>> {
+        let Some(change) = self.settings_undo.lock().unwrap().pop() else {
+            return Ok(None);
+        };
+        let mut config = self.config.get();
+        change.clone().restore(&mut config.streamer_settings);
+        if let Err(e) = self.config.save(config) {
+            self.settings_undo.lock().unwrap().push(change);
+            return Err(e);
+        }
+        tracing::info!("Undid settings change for {}", change.user_login);
+        self.state.notify_settings_changed();
+        AppServices::refresh_schedules_from_db(self).await;
+        Ok(Some(change.user_login))
+    }
+
+    async fn get_auth_state(&self) -> AuthStatus {
+        let progress = self.login_progress_rx.borrow().clone();
+        AuthStatus::new(
+            self.state.is_authenticated().await,
+            &self.state.user_login().await,
+            self.state.token_expiry().await,
+            progress.as_ref(),
+        )
+    }
+
+    async fn refresh_on_menu_open(&self) {
+        if !self.state.is_authenticated().await {
+            return;
+        }
+        // Any refresh in the last few seconds counts, including a regular poll
+        let last_refresh = self.session.last_live_refresh().await;
+        if refresh_due(Utc::now(), last_refresh, MENU_OPEN_REFRESH_MIN_SECS) {
+            self.refresh_followed_streams().await;
+        }
+    }
+
+    async fn refresh_now(&self) {
+        if !self.state.is_authenticated().await || !self.state.start_refreshing().await {
+            return;
+        }
+        self.refresh_all_data().await;
+        self.state.finish_refreshing().await;
+    }
+
+    async fn mark_menu_seen(&self) {
+        self.state.mark_menu_seen().await;
+    }
+
+    fn get_autostart(&self) -> bool {
+        crate::autostart::is_enabled()
+    }
+
+    async fn set_autostart(&self, enabled: bool) -> anyhow::Result<()> {
+        crate::autostart::set_enabled(enabled)?;
+        let mut config = self.config.get();
+        config.start_on_login = enabled;
+        self.config.save(config)?;
+        tracing::info!(
+            "Start on login {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        Ok(())
+    }
 }
 
 impl Clone for Backend {
@@ -1033,9 +2317,14 @@ impl Clone for Backend {
             snooze_rx: self.snooze_rx.clone(),
             settings_tx: self.settings_tx.clone(),
             settings_rx: self.settings_rx.clone(),
-            profile_image_cache: self.profile_image_cache.clone(),
+            users: self.users.clone(),
             box_art_cache: self.box_art_cache.clone(),
+            thumbnails: self.thumbnails.clone(),
             hotness_cache: self.hotness_cache.clone(),
+            streak_reminded: self.streak_reminded.clone(),
+            title_alerted: self.title_alerted.clone(),
+            settings_undo: self.settings_undo.clone(),
+            resumed: self.resumed.clone(),
         }
     }
 }
@@ -0,0 +1,116 @@
+//! Setting many streamers' importance at once, for lists of channels brought
+//! over from other tools.
+
+use std::collections::HashMap;
+
+use crate::config::{StreamerImportance, StreamerSettings};
+
+/// Logins as Twitch stores them: trimmed, without a leading `@`, lowercase,
+/// each once, in the order given.
+pub fn normalize_logins(logins: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for login in logins {
+        let login = login.trim().trim_start_matches('@').to_lowercase();
+        if !login.is_empty() && !normalized.contains(&login) {
+            normalized.push(login);
+        }
+    }
+    normalized
+}
+
+/// Gives every login in `logins` (already normalized) `importance`, adding
+/// settings named by `display_name` for streamers without any. Other settings
+/// are kept. Returns how many streamers changed.
+pub fn set_importance(
+    settings: &mut HashMap<String, StreamerSettings>,
+    logins: &[String],
+    importance: StreamerImportance,
+    display_name: impl Fn(&str) -> String,
+) -> usize {
+    let mut changed = 0;
+    for login in logins {
+        match settings.get_mut(login) {
+            Some(existing) if existing.importance == importance => {}
+            Some(existing) => {
+                existing.importance = importance;
+                changed += 1;
+            }
+            None => {
+                settings.insert(
+                    login.clone(),
+                    StreamerSettings {
+                        display_name: display_name(login),
+                        importance,
+                        hotness_z_threshold_override: None,
+                        notification_open_mode: None,
+                        notification_sound: None,
+                        open_with: None,
+                        schedule_reminder_min: None,
+                        notify_on_category_change: true,
+                    },
+                );
+                changed += 1;
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_pasted_logins() {
+        let pasted = ["  Ninja", "@shroud", "", "ninja", "Pokimane "].map(String::from);
+        assert_eq!(
+            normalize_logins(&pasted),
+            vec!["ninja", "shroud", "pokimane"]
+        );
+    }
+
+    #[test]
+    fn sets_importance_keeping_other_settings() {
+        let mut settings = HashMap::new();
+        set_importance(
+            &mut settings,
+            &["ninja".to_string()],
+            StreamerImportance::Silent,
+            str::to_uppercase,
+        );
+        settings.get_mut("ninja").unwrap().schedule_reminder_min = Some(15);
+
+        let logins = ["ninja".to_string(), "shroud".to_string()];
+        let changed = set_importance(
+            &mut settings,
+            &logins,
+            StreamerImportance::Favourite,
+            str::to_uppercase,
+        );
+
+        assert_eq!(changed, 2);
+        let ninja = &settings["ninja"];
+        assert_eq!(ninja.importance, StreamerImportance::Favourite);
+        assert_eq!(ninja.schedule_reminder_min, Some(15));
+        assert_eq!(settings["shroud"].display_name, "SHROUD");
+    }
+
+    #[test]
+    fn unchanged_streamers_are_not_counted() {
+        let mut settings = HashMap::new();
+        let logins = ["ninja".to_string()];
+        set_importance(
+            &mut settings,
+            &logins,
+            StreamerImportance::Ignore,
+            str::to_string,
+        );
+        let changed = set_importance(
+            &mut settings,
+            &logins,
+            StreamerImportance::Ignore,
+            str::to_string,
+        );
+        assert_eq!(changed, 0);
+    }
+}
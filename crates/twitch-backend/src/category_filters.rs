@@ -0,0 +1,99 @@
+//! Per-category stream filters (`FollowedCategory::filters`).
+//!
+//! Languages go into the Helix query. Tags and viewer counts can't, so the
+//! fetched streams are filtered here before they reach `AppState`; a category
+//! with those filters fetches several pages to filter from, since Twitch's
+//! first page is only its 10 biggest streams.
+
+use crate::config::CategoryFilters;
+use crate::twitch::Stream;
+
+/// Pages fetched for a category whose streams are filtered after fetching.
+pub const FILTERED_CATEGORY_PAGES: usize = 5;
+
+/// Languages to ask Helix for: the category's own, or the system language.
+pub fn query_languages(filters: &CategoryFilters) -> Vec<String> {
+    if filters.languages.is_empty() {
+        crate::twitch::system_language().into_iter().collect()
+    } else {
+        filters.languages.clone()
+    }
+}
+
+/// Whether any filter applies after fetching.
+pub fn filters_after_fetch(filters: &CategoryFilters) -> bool {
+    !filters.required_tags.is_empty()
+        || filters.min_viewers.is_some()
+        || filters.max_viewers.is_some()
+}
+
+/// Whether `stream` has every required tag and a viewer count in range.
+pub fn matches(filters: &CategoryFilters, stream: &Stream) -> bool {
+    let has_tags = filters
+        .required_tags
+        .iter()
+        .all(|tag| stream.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+    let enough_viewers = filters
+        .min_viewers
+        .is_none_or(|min| stream.viewer_count >= min);
+    let few_enough_viewers = filters
+        .max_viewers
+        .is_none_or(|max| stream.viewer_count <= max);
+    has_tags && enough_viewers && few_enough_viewers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::make_stream;
+
+    fn stream(viewers: u32, tags: &[&str]) -> Stream {
+        let mut stream = make_stream("1", "alice");
+        stream.viewer_count = viewers;
+        stream.tags = tags.iter().map(ToString::to_string).collect();
+        stream
+    }
+
+    #[test]
+    fn no_filters_match_everything() {
+        let filters = CategoryFilters::default();
+        assert!(!filters_after_fetch(&filters));
+        assert!(matches(&filters, &stream(0, &[])));
+    }
+
+    #[test]
+    fn required_tags_must_all_be_present_ignoring_case() {
+        let filters = CategoryFilters {
+            required_tags: vec!["speedrun".to_string(), "English".to_string()],
+            ..Default::default()
+        };
+        assert!(filters_after_fetch(&filters));
+        assert!(matches(
+            &filters,
+            &stream(5, &["Speedrun", "english", "Any%"])
+        ));
+        assert!(!matches(&filters, &stream(5, &["Speedrun"])));
+    }
+
+    #[test]
+    fn viewer_range_is_inclusive() {
+        let filters = CategoryFilters {
+            min_viewers: Some(10),
+            max_viewers: Some(100),
+            ..Default::default()
+        };
+        assert!(matches(&filters, &stream(10, &[])));
+        assert!(matches(&filters, &stream(100, &[])));
+        assert!(!matches(&filters, &stream(9, &[])));
+        assert!(!matches(&filters, &stream(101, &[])));
+    }
+
+    #[test]
+    fn category_languages_replace_the_system_language() {
+        let filters = CategoryFilters {
+            languages: vec!["de".to_string(), "fr".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(query_languages(&filters), vec!["de", "fr"]);
+    }
+}
@@ -0,0 +1,162 @@
+//! Followed-category suggestions from viewing habits.
+//!
+//! Streams opened from the app (`watch_history`) are matched to the broadcast
+//! they were opened during (`stream_history`), which records the category the
+//! broadcast was last seen in. Categories the user keeps watching but doesn't
+//! follow yet are offered in the settings Categories tab.
+
+use serde::Serialize;
+
+use crate::config::{Config, FollowedCategory};
+
+/// How far back opened streams are considered.
+pub const SUGGESTION_LOOKBACK_DAYS: i64 = 30;
+
+/// Distinct broadcasts watched in a category before it is suggested.
+pub const MIN_WATCHED_STREAMS: u32 = 3;
+
+/// Most suggestions shown at once.
+pub const MAX_SUGGESTIONS: usize = 5;
+
+/// A category the user could follow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CategorySuggestion {
+    pub id: String,
+    pub name: String,
+    /// Distinct broadcasts in this category opened within the lookback window.
+    pub watched_streams: u32,
+}
+
+/// Picks suggestions from per-category watch counts.
+///
+/// Skips categories already followed or previously dismissed, and those
+/// watched fewer than [`MIN_WATCHED_STREAMS`] times. Most-watched first.
+pub fn suggest_categories(
+    watched: Vec<CategorySuggestion>,
+    config: &Config,
+) -> Vec<CategorySuggestion> {
+    let mut suggestions: Vec<CategorySuggestion> = watched
+        .into_iter()
+        .filter(|s| s.watched_streams >= MIN_WATCHED_STREAMS)
+        .filter(|s| !config.followed_categories.iter().any(|c| c.id == s.id))
+        .filter(|s| !config.dismissed_category_suggestions.contains(&s.id))
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.watched_streams
+            .cmp(&a.watched_streams)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// Follows a suggested category. Returns `false` if it was already followed.
+pub fn accept_suggestion(config: &mut Config, category: FollowedCategory) -> bool {
+    if config
+        .followed_categories
+        .iter()
+        .any(|c| c.id == category.id)
+    {
+        return false;
+    }
+    config.followed_categories.push(category);
+    true
+}
+
+/// Stops suggesting a category. Returns `false` if it was already dismissed.
+pub fn dismiss_suggestion(config: &mut Config, category_id: &str) -> bool {
+    if config
+        .dismissed_category_suggestions
+        .iter()
+        .any(|id| id == category_id)
+    {
+        return false;
+    }
+    config
+        .dismissed_category_suggestions
+        .push(category_id.to_string());
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn watched(id: &str, count: u32) -> CategorySuggestion {
+        CategorySuggestion {
+            id: id.to_string(),
+            name: format!("Game {id}"),
+            watched_streams: count,
+        }
+    }
+
+    fn category(id: &str) -> FollowedCategory {
+        FollowedCategory {
+            id: id.to_string(),
+            name: format!("Game {id}"),
+            ..Default::default()
+        }
+    }
+
+    // === suggest_categories tests ===
+
+    #[test]
+    fn most_watched_first() {
+        let result = suggest_categories(
+            vec![watched("1", 3), watched("2", 7), watched("3", 4)],
+            &Config::default(),
+        );
+        let ids: Vec<_> = result.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "3", "1"]);
+    }
+
+    #[test]
+    fn rarely_watched_categories_are_not_suggested() {
+        let result = suggest_categories(
+            vec![watched("1", MIN_WATCHED_STREAMS - 1)],
+            &Config::default(),
+        );
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn followed_and_dismissed_categories_are_skipped() {
+        let config = Config {
+            followed_categories: vec![category("1")],
+            dismissed_category_suggestions: vec!["2".to_string()],
+            ..Config::default()
+        };
+        let result = suggest_categories(
+            vec![watched("1", 9), watched("2", 9), watched("3", 9)],
+            &config,
+        );
+        assert_eq!(result, vec![watched("3", 9)]);
+    }
+
+    #[test]
+    fn capped_at_max_suggestions() {
+        let all = (0..10).map(|i| watched(&i.to_string(), 5)).collect();
+        assert_eq!(
+            suggest_categories(all, &Config::default()).len(),
+            MAX_SUGGESTIONS
+        );
+    }
+
+    // === accept / dismiss tests ===
+
+    #[test]
+    fn accept_adds_category_once() {
+        let mut config = Config::default();
+        assert!(accept_suggestion(&mut config, category("1")));
+        assert!(!accept_suggestion(&mut config, category("1")));
+        assert_eq!(config.followed_categories, vec![category("1")]);
+    }
+
+    #[test]
+    fn dismiss_records_category_once() {
+        let mut config = Config::default();
+        assert!(dismiss_suggestion(&mut config, "1"));
+        assert!(!dismiss_suggestion(&mut config, "1"));
+        assert_eq!(config.dismissed_category_suggestions, vec!["1".to_string()]);
+    }
+}
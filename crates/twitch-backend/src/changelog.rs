@@ -0,0 +1,134 @@
+//! The user-facing changelog behind the "What's New" window.
+//!
+//! `CHANGELOG.md` at the repo root is compiled in, split into releases, and
+//! shown once after the app starts on a version it hasn't run before. The
+//! last version seen is kept in `Config::last_seen_version`.
+
+use serde::Serialize;
+
+use crate::config::Config;
+
+/// The changelog as shipped with this build.
+pub const CHANGELOG: &str = include_str!("../../../CHANGELOG.md");
+
+/// One `## <version>` section of the changelog.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Release {
+    pub version: String,
+    pub changes: Vec<String>,
+}
+
+/// Splits changelog markdown into releases, in file order (newest first).
+///
+/// Only `## ` headings and `- ` bullets are read; anything before the first
+/// heading is ignored.
+pub fn parse_changelog(markdown: &str) -> Vec<Release> {
+    let mut releases: Vec<Release> = Vec::new();
+    for line in markdown.lines() {
+        let line = line.trim();
+        if let Some(version) = line.strip_prefix("## ") {
+            releases.push(Release {
+                version: version.trim().to_string(),
+                changes: Vec::new(),
+            });
+        } else if let Some(change) = line.strip_prefix("- ") {
+            if let Some(release) = releases.last_mut() {
+                release.changes.push(change.trim().to_string());
+            }
+        }
+    }
+    releases
+}
+
+/// How the running version compares to the last one recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeenVersion {
+    Same,
+    /// Nothing recorded yet: a fresh install, or a config from before
+    /// versions were tracked. Nothing to announce.
+    FirstRun,
+    /// A different version ran last; show what's new.
+    Updated,
+}
+
+/// Records `current` as the last-seen version. The config needs saving
+/// unless the result is [`SeenVersion::Same`].
+pub fn mark_version_seen(config: &mut Config, current: &str) -> SeenVersion {
+    let seen = match config.last_seen_version.as_deref() {
+        Some(last) if last == current => return SeenVersion::Same,
+        Some(_) => SeenVersion::Updated,
+        None => SeenVersion::FirstRun,
+    };
+    config.last_seen_version = Some(current.to_string());
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_releases_and_bullets() {
+        let md = "# Changelog\n\nIntro text.\n\n## 0.2.0\n\n- New thing\n- Other thing\n\n## 0.1.0\n\n- First\n";
+        let releases = parse_changelog(md);
+        assert_eq!(
+            releases,
+            vec![
+                Release {
+                    version: "0.2.0".to_string(),
+                    changes: vec!["New thing".to_string(), "Other thing".to_string()],
+                },
+                Release {
+                    version: "0.1.0".to_string(),
+                    changes: vec!["First".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_bullets_before_first_release() {
+        let releases = parse_changelog("- stray\n## 1.0.0\n- kept\n");
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].changes, vec!["kept".to_string()]);
+    }
+
+    #[test]
+    fn embedded_changelog_has_a_release() {
+        let releases = parse_changelog(CHANGELOG);
+        assert!(!releases.is_empty());
+        assert!(!releases[0].changes.is_empty());
+    }
+
+    #[test]
+    fn first_run_records_version_without_announcing() {
+        let mut config = Config::default();
+        assert_eq!(
+            mark_version_seen(&mut config, "0.1.0"),
+            SeenVersion::FirstRun
+        );
+        assert_eq!(config.last_seen_version.as_deref(), Some("0.1.0"));
+    }
+
+    #[test]
+    fn same_version_is_unchanged() {
+        let mut config = Config {
+            last_seen_version: Some("0.1.0".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(mark_version_seen(&mut config, "0.1.0"), SeenVersion::Same);
+    }
+
+    #[test]
+    fn new_version_is_an_update() {
+        let mut config = Config {
+            last_seen_version: Some("0.1.0".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(
+            mark_version_seen(&mut config, "0.2.0"),
+            SeenVersion::Updated
+        );
+        assert_eq!(config.last_seen_version.as_deref(), Some("0.2.0"));
+    }
+}
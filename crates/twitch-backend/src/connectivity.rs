@@ -0,0 +1,156 @@
+//! Offline detection. A live-stream refresh that can't reach Twitch at all
+//! marks the app offline; polling then stops, and a cheap request to
+//! [`PROBE_URL`] checks for the network with growing gaps. The first probe
+//! that gets through brings the app back online and refreshes straight away.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::twitch::http::{HttpClient, HttpMethod, HttpRequest};
+use crate::twitch::ApiError;
+
+/// Answers 401 without a token, which is enough to show Twitch is reachable.
+pub const PROBE_URL: &str = "https://id.twitch.tv/oauth2/validate";
+
+/// Wait before the first probe; doubles with each failed one.
+const PROBE_BASE_SECS: i64 = 5;
+
+/// Longest wait between probes.
+pub const PROBE_MAX_SECS: i64 = 300;
+
+/// Whether Twitch can currently be reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Connectivity {
+    #[default]
+    Online,
+    Offline {
+        since: DateTime<Utc>,
+    },
+}
+
+impl Connectivity {
+    pub fn offline_since(self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Online => None,
+            Self::Offline { since } => Some(since),
+        }
+    }
+}
+
+/// A stretch of being offline and how probing for the network is going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outage {
+    pub since: DateTime<Utc>,
+    /// Probes failed in a row
+    pub failed_probes: u32,
+    last_attempt: DateTime<Utc>,
+}
+
+impl Outage {
+    pub fn new(since: DateTime<Utc>) -> Self {
+        Self {
+            since,
+            failed_probes: 0,
+            last_attempt: since,
+        }
+    }
+
+    /// When to next probe: [`PROBE_BASE_SECS`] after going offline, then
+    /// backing off after each failed probe.
+    pub fn next_probe(&self) -> DateTime<Utc> {
+        self.last_attempt + probe_delay(self.failed_probes)
+    }
+
+    pub fn record_failed_probe(&mut self, at: DateTime<Utc>) {
+        self.failed_probes += 1;
+        self.last_attempt = at;
+    }
+}
+
+fn probe_delay(failed_probes: u32) -> Duration {
+    let doublings = failed_probes.min(10);
+    Duration::seconds((PROBE_BASE_SECS << doublings).min(PROBE_MAX_SECS))
+}
+
+/// Whether `error` means the request never reached Twitch (no network, DNS
+/// failure, connection refused or timed out), as opposed to Twitch answering
+/// with an error.
+pub fn is_offline_error(error: &ApiError) -> bool {
+    let ApiError::Other(error) = error else {
+        return false;
+    };
+    error.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            e.is_connect() || e.is_timeout()
+        } else if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+            matches!(
+                e.kind(),
+                std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::NotConnected
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::NetworkUnreachable
+                    | std::io::ErrorKind::HostUnreachable
+            )
+        } else {
+            false
+        }
+    })
+}
+
+/// Checks whether Twitch answers at all, whatever the status.
+pub async fn probe<H: HttpClient>(http: &H) -> bool {
+    http.send(HttpRequest::new(HttpMethod::Get, PROBE_URL))
+        .await
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twitch::http::mock::MockHttpClient;
+
+    #[test]
+    fn probes_back_off_up_to_the_maximum() {
+        let now = Utc::now();
+        let mut outage = Outage::new(now);
+        assert_eq!(
+            outage.next_probe(),
+            now + Duration::seconds(PROBE_BASE_SECS)
+        );
+
+        outage.record_failed_probe(now);
+        assert_eq!(
+            outage.next_probe(),
+            now + Duration::seconds(PROBE_BASE_SECS * 2)
+        );
+
+        for _ in 0..20 {
+            outage.record_failed_probe(now);
+        }
+        assert_eq!(outage.next_probe(), now + Duration::seconds(PROBE_MAX_SECS));
+        assert_eq!(outage.since, now);
+    }
+
+    #[test]
+    fn only_network_failures_count_as_offline() {
+        let refused = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+        let offline =
+            ApiError::Other(anyhow::Error::new(refused).context("Failed to send request"));
+        assert!(is_offline_error(&offline));
+
+        assert!(!is_offline_error(&ApiError::Unauthorized));
+        assert!(!is_offline_error(&ApiError::Other(anyhow::anyhow!(
+            "API error 500: oops"
+        ))));
+    }
+
+    #[tokio::test]
+    async fn probe_succeeds_on_any_answer() {
+        let reachable = MockHttpClient::new().on_get(PROBE_URL, 401, "");
+        assert!(probe(&reachable).await);
+
+        let unreachable = MockHttpClient::new();
+        assert!(!probe(&unreachable).await);
+    }
+}
@@ -0,0 +1,128 @@
+//! Dispatch for user-defined menu actions (`Config::custom_actions`).
+//!
+//! Frontends decide how to present and confirm an action; this module only
+//! performs it.
+
+use std::hash::{Hash, Hasher};
+use std::process::{Command, Stdio};
+
+use anyhow::Context;
+
+use crate::config::{CustomAction, CustomActionTarget};
+
+/// Builds the platform shell invocation for `command`.
+pub fn shell_command(command: &str) -> (&'static str, [&str; 2]) {
+    if cfg!(target_os = "windows") {
+        ("cmd", ["/C", command])
+    } else {
+        ("sh", ["-c", command])
+    }
+}
+
+/// Identifies `action` by its label and target, so a menu built before the
+/// actions were edited can't run whichever one has since taken its place.
+/// Only stable within one build, which is all a menu item ID needs.
+pub fn action_key(action: &CustomAction) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    action.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// The action in `actions` whose [`action_key`] is `key`.
+pub fn find<'a>(actions: &'a [CustomAction], key: &str) -> Option<&'a CustomAction> {
+    actions.iter().find(|action| action_key(action) == key)
+}
+
+/// Opens the action's URL or spawns its command without waiting for it to exit.
+pub fn run(action: &CustomAction) -> anyhow::Result<()> {
+    match &action.target {
+        CustomActionTarget::Url(url) => {
+            open::that(url).with_context(|| format!("Failed to open {url}"))?;
+        }
+        CustomActionTarget::Command(command) => spawn_shell(command)?,
+    }
+    tracing::info!("Ran custom action '{}'", action.label);
+    Ok(())
+}
+
+/// Starts `command` through the platform shell, detached from our stdio. A
+/// thread waits for it so it doesn't linger as a zombie once it exits.
+pub fn spawn_shell(command: &str) -> anyhow::Result<()> {
+    let (program, args) = shell_command(command);
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run `{command}`"))?;
+    std::thread::spawn(move || {
+        if let Err(e) = child.wait() {
+            tracing::warn!("Failed to wait for a custom command: {}", e);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_command_wraps_in_sh() {
+        assert_eq!(
+            shell_command("obs --startstreaming"),
+            ("sh", ["-c", "obs --startstreaming"])
+        );
+    }
+
+    fn command(label: &str, command: &str) -> CustomAction {
+        CustomAction {
+            label: label.to_string(),
+            target: CustomActionTarget::Command(command.to_string()),
+        }
+    }
+
+    #[test]
+    fn key_changes_with_label_or_target() {
+        let action = command("Start OBS", "obs");
+        assert_eq!(action_key(&action), action_key(&action.clone()));
+        assert_ne!(
+            action_key(&action),
+            action_key(&command("Start OBS", "rm -rf ~"))
+        );
+        assert_ne!(action_key(&action), action_key(&command("Stop OBS", "obs")));
+    }
+
+    #[test]
+    fn find_ignores_position() {
+        let obs = command("Start OBS", "obs");
+        let key = action_key(&obs);
+        let reordered = [command("Other", "true"), obs.clone()];
+        assert_eq!(find(&reordered, &key), Some(&obs));
+        assert_eq!(find(&[command("Start OBS", "obs --edited")], &key), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_spawns_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("ran");
+        let action = CustomAction {
+            label: "Touch".to_string(),
+            target: CustomActionTarget::Command(format!("touch '{}'", marker.display())),
+        };
+
+        run(&action).unwrap();
+
+        // The command is not awaited; give it a moment to finish
+        for _ in 0..50 {
+            if marker.exists() {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        panic!("command did not run");
+    }
+}
@@ -0,0 +1,239 @@
+//! Settings and data export, for moving to another machine.
+//!
+//! An export is a zip holding `manifest.json` (format and app version),
+//! `config.json` (which includes streamer settings and followed categories)
+//! and, optionally, `data.db`, a copy of the SQLite history. The login token
+//! is never exported, whether it's in the keyring or `token.json`.
+
+use std::io::{Read, Seek, Write};
+
+use anyhow::{bail, Context};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{parse_config, Config};
+
+/// Bumped when the archive layout changes incompatibly.
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const CONFIG_FILE: &str = "config.json";
+const HISTORY_FILE: &str = "data.db";
+
+/// Every SQLite database file starts with this.
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Describes an export; written to `manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExportManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at: DateTime<Utc>,
+    pub includes_history: bool,
+}
+
+impl ExportManifest {
+    /// A manifest for an export made by this build at `now`.
+    pub fn new(now: DateTime<Utc>, includes_history: bool) -> Self {
+        Self {
+            format_version: EXPORT_FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at: now,
+            includes_history,
+        }
+    }
+}
+
+/// The validated contents of an export.
+#[derive(Debug)]
+pub struct ImportedData {
+    pub manifest: ExportManifest,
+    /// Migrated to the current config version and validated.
+    pub config: Config,
+    /// The `data.db` copy, when the export included history.
+    pub history: Option<Vec<u8>>,
+}
+
+/// Returns the default file name for an export made at `now`.
+pub fn export_file_name(now: DateTime<Utc>) -> String {
+    format!("twitch-tray-export-{}.zip", now.format("%Y%m%d-%H%M%S"))
+}
+
+/// Writes an export zip to `writer`. `history` is the database file's bytes.
+pub fn write_export<W: Write + Seek>(
+    writer: W,
+    manifest: &ExportManifest,
+    config: &Config,
+    history: Option<&[u8]>,
+) -> anyhow::Result<()> {
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let mut zip = zip::ZipWriter::new(writer);
+
+    zip.start_file(MANIFEST_FILE, options)?;
+    zip.write_all(serde_json::to_string_pretty(manifest)?.as_bytes())?;
+
+    zip.start_file(CONFIG_FILE, options)?;
+    zip.write_all(
+        serde_json::to_string_pretty(config)
+            .context("Failed to serialize config")?
+            .as_bytes(),
+    )?;
+
+    if let Some(history) = history {
+        zip.start_file(HISTORY_FILE, options)?;
+        zip.write_all(history)?;
+    }
+
+    zip.finish()?;
+    Ok(())
+}
+
+/// Reads and validates an export zip.
+///
+/// Fails if it isn't an export, comes from a newer format, or holds a config
+/// or database that can't be used. Older configs are migrated.
+pub fn read_export<R: Read + Seek>(reader: R) -> anyhow::Result<ImportedData> {
+    let mut zip = zip::ZipArchive::new(reader).context("Not a zip file")?;
+
+    let manifest: ExportManifest = serde_json::from_str(
+        &read_string(&mut zip, MANIFEST_FILE).context("Not a Twitch Tray export")?,
+    )
+    .context("Export manifest is invalid")?;
+    if manifest.format_version > EXPORT_FORMAT_VERSION {
+        bail!(
+            "Export is from a newer version of Twitch Tray ({}); update to import it",
+            manifest.app_version
+        );
+    }
+
+    let (config, _) = parse_config(&read_string(&mut zip, CONFIG_FILE)?)?;
+    let config = config
+        .validated()
+        .context("Exported settings can't be used")?;
+
+    let history = if manifest.includes_history {
+        let bytes = read_bytes(&mut zip, HISTORY_FILE)?;
+        if !bytes.starts_with(SQLITE_HEADER) {
+            bail!("Exported history is not a database");
+        }
+        Some(bytes)
+    } else {
+        None
+    };
+
+    Ok(ImportedData {
+        manifest,
+        config,
+        history,
+    })
+}
+
+fn read_bytes<R: Read + Seek>(zip: &mut zip::ZipArchive<R>, name: &str) -> anyhow::Result<Vec<u8>> {
+    let mut file = zip
+        .by_name(name)
+        .with_context(|| format!("Export is missing {name}"))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_string<R: Read + Seek>(zip: &mut zip::ZipArchive<R>, name: &str) -> anyhow::Result<String> {
+    String::from_utf8(read_bytes(zip, name)?).with_context(|| format!("{name} is not text"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    use chrono::TimeZone;
+
+    fn export(manifest: &ExportManifest, config: &Config, history: Option<&[u8]>) -> Vec<u8> {
+        let mut buf = Cursor::new(Vec::new());
+        write_export(&mut buf, manifest, config, history).unwrap();
+        buf.into_inner()
+    }
+
+    fn history_bytes() -> Vec<u8> {
+        let mut bytes = SQLITE_HEADER.to_vec();
+        bytes.extend_from_slice(b"rest of the database");
+        bytes
+    }
+
+    #[test]
+    fn export_round_trips() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 15, 12, 30, 5).unwrap();
+        let manifest = ExportManifest::new(now, true);
+        let config = Config {
+            poll_interval_sec: 90,
+            ..Config::default()
+        };
+
+        let bytes = export(&manifest, &config, Some(&history_bytes()));
+        let imported = read_export(Cursor::new(bytes)).unwrap();
+
+        assert_eq!(imported.manifest, manifest);
+        assert_eq!(imported.config.poll_interval_sec, 90);
+        assert_eq!(imported.history, Some(history_bytes()));
+    }
+
+    #[test]
+    fn history_is_optional() {
+        let manifest = ExportManifest::new(Utc::now(), false);
+        let bytes = export(&manifest, &Config::default(), None);
+        assert!(read_export(Cursor::new(bytes)).unwrap().history.is_none());
+    }
+
+    #[test]
+    fn rejects_newer_formats() {
+        let manifest = ExportManifest {
+            format_version: EXPORT_FORMAT_VERSION + 1,
+            ..ExportManifest::new(Utc::now(), false)
+        };
+        let bytes = export(&manifest, &Config::default(), None);
+        let err = read_export(Cursor::new(bytes)).unwrap_err();
+        assert!(err.to_string().contains("newer version"), "{err}");
+    }
+
+    #[test]
+    fn rejects_invalid_settings_and_history() {
+        let config = Config {
+            poll_interval_sec: 1,
+            ..Config::default()
+        };
+        let bytes = export(&ExportManifest::new(Utc::now(), false), &config, None);
+        assert!(read_export(Cursor::new(bytes)).is_err());
+
+        let bytes = export(
+            &ExportManifest::new(Utc::now(), true),
+            &Config::default(),
+            Some(b"not a database"),
+        );
+        assert!(read_export(Cursor::new(bytes)).is_err());
+    }
+
+    #[test]
+    fn rejects_archives_without_a_manifest() {
+        let mut buf = Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut buf);
+        zip.start_file("notes.txt", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.finish().unwrap();
+
+        let err = read_export(Cursor::new(buf.into_inner())).unwrap_err();
+        assert!(
+            err.to_string().contains("Not a Twitch Tray export"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn export_file_name_includes_timestamp() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 15, 12, 30, 5).unwrap();
+        assert_eq!(
+            export_file_name(now),
+            "twitch-tray-export-20250615-123005.zip"
+        );
+    }
+}
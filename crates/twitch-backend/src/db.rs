@@ -1,12 +1,22 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
 use chrono::{DateTime, Duration, Utc};
 use rusqlite::{Connection, OptionalExtension};
 
+use crate::config::ScheduleInferenceModel;
 use crate::hotness_detection::ViewerObservation;
-use crate::twitch::{FollowedChannel, ScheduledStream, Stream};
+use crate::twitch::{FollowedChannel, ScheduledStream, Stream, User};
+
+/// `(broadcaster_name, broadcaster_login, started_at, ended_at)` row from [`Database::get_live_at`].
+pub type LiveAtRow = (String, String, i64, Option<i64>);
+
+/// `(followed live streams, category streams by category ID)` from [`Database::get_cached_streams`].
+pub type CachedStreams = (Vec<Stream>, HashMap<String, Vec<Stream>>);
+
+/// Users by ID with when they were fetched, from [`Database::get_users`].
+pub type CachedUsers = HashMap<String, (User, DateTime<Utc>)>;
 
 /// Database for recording stream history, followed channels, and schedules.
 #[derive(Clone)]
@@ -34,6 +44,10 @@ impl Database {
             "CREATE TABLE IF NOT EXISTS stream_history (
                 user_id INTEGER NOT NULL,
                 started_at INTEGER NOT NULL,
+                ended_at INTEGER,
+                last_seen_at INTEGER,
+                game_id TEXT,
+                game_name TEXT,
                 UNIQUE(user_id, started_at)
             );
             CREATE INDEX IF NOT EXISTS idx_stream_history_user_id
@@ -78,7 +92,33 @@ impl Database {
             CREATE INDEX IF NOT EXISTS idx_vo_broadcaster_age
                 ON viewer_observations(broadcaster_id, stream_age_min);
             CREATE INDEX IF NOT EXISTS idx_vo_observed_at
-                ON viewer_observations(observed_at);",
+                ON viewer_observations(observed_at);
+
+            CREATE TABLE IF NOT EXISTS watch_history (
+                broadcaster_login TEXT NOT NULL,
+                opened_at         INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_watch_history_login
+                ON watch_history(broadcaster_login, opened_at);
+
+            CREATE TABLE IF NOT EXISTS live_streams (
+                user_id INTEGER NOT NULL,
+                started_at INTEGER NOT NULL,
+                PRIMARY KEY (user_id, started_at)
+            );
+
+            CREATE TABLE IF NOT EXISTS cached_streams (
+                category_id TEXT NOT NULL,
+                stream TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS users (
+                user_id INTEGER PRIMARY KEY,
+                login TEXT NOT NULL,
+                display_name TEXT NOT NULL,
+                profile_image_url TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL
+            );",
         )?;
         // Migrate: add broadcaster_timezone column to followed if missing
         let has_tz_col: bool = conn
@@ -98,26 +138,163 @@ impl Database {
             )?;
         }
 
+        // Migrate: add ended_at column to stream_history if missing
+        let has_ended_at: bool = conn
+            .prepare("SELECT ended_at FROM stream_history LIMIT 0")
+            .is_ok();
+        if !has_ended_at {
+            conn.execute_batch("ALTER TABLE stream_history ADD COLUMN ended_at INTEGER")?;
+        }
+
+        // Migrate: add game columns to stream_history if missing
+        let has_game_id: bool = conn
+            .prepare("SELECT game_id FROM stream_history LIMIT 0")
+            .is_ok();
+        if !has_game_id {
+            conn.execute_batch(
+                "ALTER TABLE stream_history ADD COLUMN game_id TEXT;
+                 ALTER TABLE stream_history ADD COLUMN game_name TEXT;",
+            )?;
+        }
+
+        // Migrate: add last_seen_at column to stream_history if missing
+        let has_last_seen_at: bool = conn
+            .prepare("SELECT last_seen_at FROM stream_history LIMIT 0")
+            .is_ok();
+        if !has_last_seen_at {
+            conn.execute_batch("ALTER TABLE stream_history ADD COLUMN last_seen_at INTEGER")?;
+        }
+
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
         })
     }
 
     /// Records observed live streams into the history database.
-    /// Uses INSERT OR IGNORE so duplicates are silently skipped.
+    ///
+    /// Already-recorded streams keep the category they were last seen in, and
+    /// an end time is cleared if the stream turns up live again (it only
+    /// dropped out of one poll).
     pub fn record_streams(&self, streams: &[Stream]) -> anyhow::Result<()> {
+        self.record_streams_at(streams, Utc::now())
+    }
+
+    /// [`Self::record_streams`], stamping each broadcast's `last_seen_at` with `seen_at`.
+    pub fn record_streams_at(
+        &self,
+        streams: &[Stream],
+        seen_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "INSERT OR IGNORE INTO stream_history (user_id, started_at) VALUES (?1, ?2)",
+            "INSERT INTO stream_history (user_id, started_at, last_seen_at, game_id, game_name)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(user_id, started_at) DO UPDATE
+             SET ended_at = NULL, last_seen_at = excluded.last_seen_at,
+                 game_id = excluded.game_id, game_name = excluded.game_name",
         )?;
         for stream in streams {
             let user_id: i64 = stream.user_id.parse()?;
             let started_at = stream.started_at.timestamp();
-            stmt.execute(rusqlite::params![user_id, started_at])?;
+            stmt.execute(rusqlite::params![
+                user_id,
+                started_at,
+                seen_at.timestamp(),
+                stream.game_id,
+                stream.game_name
+            ])?;
         }
         Ok(())
     }
 
+    /// Marks the given broadcasts as having ended at `ended_at`.
+    ///
+    /// Only sets the end once; a broadcast that was never recorded is skipped.
+    pub fn record_stream_ends(
+        &self,
+        streams: &[Stream],
+        ended_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "UPDATE stream_history SET ended_at = ?3
+             WHERE user_id = ?1 AND started_at = ?2 AND ended_at IS NULL",
+        )?;
+        for stream in streams {
+            let user_id: i64 = stream.user_id.parse()?;
+            stmt.execute(rusqlite::params![
+                user_id,
+                stream.started_at.timestamp(),
+                ended_at.timestamp()
+            ])?;
+        }
+        Ok(())
+    }
+
+    /// Returns each channel's average broadcast length, keyed by user_id.
+    ///
+    /// A broadcast lasts until its recorded end or, if the app was closed
+    /// before it ended, until it was last seen live. Broadcasts still live at
+    /// the last poll aren't over yet and are left out.
+    pub fn get_average_duration(&self) -> anyhow::Result<HashMap<i64, Duration>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT h.user_id, AVG(COALESCE(h.ended_at, h.last_seen_at) - h.started_at)
+             FROM stream_history h
+             WHERE COALESCE(h.ended_at, h.last_seen_at) > h.started_at
+               AND NOT EXISTS (
+                   SELECT 1 FROM live_streams l
+                   WHERE l.user_id = h.user_id AND l.started_at = h.started_at
+               )
+             GROUP BY h.user_id",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?)))?;
+        let mut result = HashMap::new();
+        for row in rows {
+            let (user_id, secs) = row?;
+            result.insert(user_id, Duration::seconds(secs.round() as i64));
+        }
+        Ok(result)
+    }
+
+    /// Returns broadcasts by currently-followed channels that were live at `at`.
+    ///
+    /// Rows are ordered by start. Broadcasts without a recorded end (still live, or the
+    /// app wasn't running when they ended) count as live for up to
+    /// `max_open_duration` after they started.
+    pub fn get_live_at(
+        &self,
+        at: DateTime<Utc>,
+        max_open_duration: Duration,
+    ) -> anyhow::Result<Vec<LiveAtRow>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT f.broadcaster_name, f.broadcaster_login, h.started_at, h.ended_at
+             FROM stream_history h
+             JOIN followed f ON h.user_id = f.broadcaster_id
+             WHERE h.started_at <= ?1
+               AND (h.ended_at > ?1 OR (h.ended_at IS NULL AND h.started_at > ?2))
+             ORDER BY h.started_at",
+        )?;
+        let ts = at.timestamp();
+        let rows = stmt.query_map(
+            rusqlite::params![ts, ts - max_open_duration.num_seconds()],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, Option<i64>>(3)?,
+                ))
+            },
+        )?;
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
     /// Returns stream start times for all given users within the given time range,
     /// grouped by user_id.
     pub fn get_streams_in_range(
@@ -259,9 +436,18 @@ impl Database {
     pub fn get_next_stale_broadcaster(
         &self,
         stale_threshold_secs: i64,
+    ) -> anyhow::Result<Option<(i64, String, String)>> {
+        self.get_next_stale_broadcaster_at(Utc::now(), stale_threshold_secs)
+    }
+
+    /// [`Self::get_next_stale_broadcaster`] as of `now`.
+    pub fn get_next_stale_broadcaster_at(
+        &self,
+        now: DateTime<Utc>,
+        stale_threshold_secs: i64,
     ) -> anyhow::Result<Option<(i64, String, String)>> {
         let conn = self.conn.lock().unwrap();
-        let threshold = Utc::now().timestamp() - stale_threshold_secs;
+        let threshold = now.timestamp() - stale_threshold_secs;
         let mut stmt = conn.prepare(
             "SELECT f.broadcaster_id, f.broadcaster_login, f.broadcaster_name
              FROM followed f
@@ -284,11 +470,19 @@ impl Database {
 
     /// Marks a broadcaster's schedule as just-checked.
     pub fn update_last_checked(&self, broadcaster_id: i64) -> anyhow::Result<()> {
+        self.update_last_checked_at(broadcaster_id, Utc::now())
+    }
+
+    /// Marks a broadcaster's schedule as checked at `now`.
+    pub fn update_last_checked_at(
+        &self,
+        broadcaster_id: i64,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
         let conn = self.conn.lock().unwrap();
-        let now = Utc::now().timestamp();
         conn.execute(
             "UPDATE schedule_last_checked SET last_checked_at = ?1 WHERE broadcaster_id = ?2",
-            rusqlite::params![now, broadcaster_id],
+            rusqlite::params![now.timestamp(), broadcaster_id],
         )?;
         Ok(())
     }
@@ -380,6 +574,7 @@ impl Database {
                 category_id: cat_id.map(|c| c.to_string()),
                 is_recurring: recurring != 0,
                 is_inferred: false,
+                confidence: None,
             });
         }
         Ok(schedules)
@@ -387,14 +582,18 @@ impl Database {
 
     /// Infers future schedules from historical stream data.
     ///
-    /// Thin wrapper around `schedule_inference::infer_schedules`. Loads streams
-    /// from the three lookback windows (3 SQL queries) then delegates to the
-    /// pure function.
+    /// Thin wrapper around the `schedule_inference` model picked by `model`.
+    /// Loads streams from the `lookback_weeks` before `start` (day-of-week) or
+    /// the three weekly lookback windows (3 SQL queries), then delegates to
+    /// the pure function. Predictions end after the channel's average
+    /// broadcast length, when there is one.
     pub fn infer_schedules(
         &self,
         channel_lookup: &HashMap<String, FollowedChannel>,
         start: DateTime<Utc>,
         end: DateTime<Utc>,
+        model: ScheduleInferenceModel,
+        lookback_weeks: u64,
     ) -> anyhow::Result<Vec<ScheduledStream>> {
         let all_user_ids: Vec<i64> = channel_lookup
             .keys()
@@ -405,13 +604,18 @@ impl Database {
             return Ok(Vec::new());
         }
 
-        let mut history: Vec<(i64, i64)> = Vec::new();
+        let ranges: Vec<(DateTime<Utc>, DateTime<Utc>)> = match model {
+            ScheduleInferenceModel::DayOfWeek => {
+                vec![(start - Duration::weeks(lookback_weeks as i64), start)]
+            }
+            ScheduleInferenceModel::WeeklyOffsets => (1..=3i64)
+                .map(|weeks| (start - Duration::weeks(weeks), end - Duration::weeks(weeks)))
+                .collect(),
+        };
 
-        for weeks in 1..=3i64 {
-            let shift = Duration::weeks(weeks);
-            for (uid, timestamps) in
-                self.get_streams_in_range(&all_user_ids, start - shift, end - shift)?
-            {
+        let mut history: Vec<(i64, i64)> = Vec::new();
+        for (from, to) in ranges {
+            for (uid, timestamps) in self.get_streams_in_range(&all_user_ids, from, to)? {
                 for ts in timestamps {
                     history.push((uid, ts.timestamp()));
                 }
@@ -419,14 +623,24 @@ impl Database {
         }
 
         let timezones = self.get_broadcaster_timezones()?;
+        let durations = self.get_average_duration()?;
 
-        Ok(crate::schedule_inference::infer_schedules(
-            &history,
-            channel_lookup,
-            &timezones,
-            start,
-            end,
-        ))
+        let infer = match model {
+            ScheduleInferenceModel::DayOfWeek => {
+                crate::schedule_inference::infer_schedules_by_weekday
+            }
+            ScheduleInferenceModel::WeeklyOffsets => crate::schedule_inference::infer_schedules,
+        };
+        let mut inferred = infer(&history, channel_lookup, &timezones, start, end);
+        for stream in &mut inferred {
+            let duration = stream
+                .broadcaster_id
+                .parse::<i64>()
+                .ok()
+                .and_then(|id| durations.get(&id));
+            stream.end_time = duration.map(|d| stream.start_time + *d);
+        }
+        Ok(inferred)
     }
 
     /// Returns all followed channels as a HashMap keyed by broadcaster_id string.
@@ -457,6 +671,31 @@ impl Database {
         Ok(result)
     }
 
+    /// Returns when each followed channel was last seen live, keyed by login.
+    ///
+    /// That's the latest recorded end, or the start for a broadcast with no
+    /// recorded end. Channels never seen live are left out.
+    pub fn get_last_seen(&self) -> anyhow::Result<HashMap<String, DateTime<Utc>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT f.broadcaster_login, MAX(COALESCE(sh.ended_at, sh.started_at))
+             FROM stream_history sh
+             JOIN followed f ON sh.user_id = f.broadcaster_id
+             GROUP BY f.broadcaster_login",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        let mut result = HashMap::new();
+        for row in rows {
+            let (login, ts) = row?;
+            if let Some(dt) = DateTime::from_timestamp(ts, 0) {
+                result.insert(login, dt);
+            }
+        }
+        Ok(result)
+    }
+
     /// Returns raw stream history rows within `[start, end)` for currently-followed channels.
     ///
     /// Returns `(broadcaster_name, broadcaster_login, started_at_unix)` tuples,
@@ -548,6 +787,337 @@ impl Database {
         }
         Ok(result)
     }
+
+    // === Watch history ===
+
+    /// Records that the user opened a stream from the app.
+    pub fn record_watch(
+        &self,
+        broadcaster_login: &str,
+        opened_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO watch_history (broadcaster_login, opened_at) VALUES (?1, ?2)",
+            rusqlite::params![broadcaster_login, opened_at.timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Returns the most recent time each broadcaster was opened, keyed by login.
+    pub fn get_last_watched(&self) -> anyhow::Result<HashMap<String, DateTime<Utc>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT broadcaster_login, MAX(opened_at) FROM watch_history GROUP BY broadcaster_login",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        let mut result = HashMap::new();
+        for row in rows {
+            let (login, ts) = row?;
+            if let Some(dt) = DateTime::from_timestamp(ts, 0) {
+                result.insert(login, dt);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Returns every time `broadcaster_login` was opened at or after `since`, oldest first.
+    pub fn get_watch_times(
+        &self,
+        broadcaster_login: &str,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<DateTime<Utc>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT opened_at FROM watch_history
+             WHERE broadcaster_login = ?1 AND opened_at >= ?2
+             ORDER BY opened_at",
+        )?;
+        let rows = stmt.query_map(
+            rusqlite::params![broadcaster_login, since.timestamp()],
+            |row| row.get::<_, i64>(0),
+        )?;
+        let mut result = Vec::new();
+        for row in rows {
+            if let Some(dt) = DateTime::from_timestamp(row?, 0) {
+                result.push(dt);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Counts, per category, the distinct broadcasts opened at or after `since`.
+    ///
+    /// An open is attributed to the followed broadcast that was live at the
+    /// time, using the category that broadcast was last seen in. Broadcasts
+    /// without a recorded end cover `max_open_duration` after they started.
+    /// Returns `(game_id, game_name, broadcasts)`, most-watched first.
+    pub fn get_watched_categories(
+        &self,
+        since: DateTime<Utc>,
+        max_open_duration: Duration,
+    ) -> anyhow::Result<Vec<(String, String, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT game_id, MAX(game_name), COUNT(*) FROM (
+                 SELECT DISTINCT h.user_id, h.started_at, h.game_id, h.game_name
+                 FROM watch_history w
+                 JOIN followed f ON f.broadcaster_login = w.broadcaster_login
+                 JOIN stream_history h ON h.user_id = f.broadcaster_id
+                  AND w.opened_at >= h.started_at
+                  AND w.opened_at < COALESCE(h.ended_at, h.started_at + ?2)
+                 WHERE w.opened_at >= ?1 AND h.game_id IS NOT NULL AND h.game_id != ''
+             )
+             GROUP BY game_id
+             ORDER BY COUNT(*) DESC",
+        )?;
+        let rows = stmt.query_map(
+            rusqlite::params![since.timestamp(), max_open_duration.num_seconds()],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            },
+        )?;
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    // === Live streams ===
+
+    /// Replaces the broadcasts live at the last poll, as `(user_id, started_at)`.
+    pub fn replace_live_streams(&self, streams: &[Stream]) -> anyhow::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM live_streams", [])?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO live_streams (user_id, started_at) VALUES (?1, ?2)",
+            )?;
+            for stream in streams {
+                let user_id: i64 = stream.user_id.parse()?;
+                stmt.execute(rusqlite::params![user_id, stream.started_at.timestamp()])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Returns the broadcasts live at the last poll, possibly by a previous run,
+    /// as `(user_id, started_at)`.
+    pub fn get_live_streams(&self) -> anyhow::Result<HashSet<(String, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT user_id, started_at FROM live_streams")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?.to_string(), row.get::<_, i64>(1)?))
+        })?;
+        let mut result = HashSet::new();
+        for row in rows {
+            result.insert(row?);
+        }
+        Ok(result)
+    }
+
+    // === Stream cache ===
+
+    /// Replaces the cached streams for a followed category, or the followed
+    /// live streams when `category_id` is `None`. Shown at the next startup
+    /// until the first refresh.
+    pub fn replace_cached_streams(
+        &self,
+        category_id: Option<&str>,
+        streams: &[Stream],
+    ) -> anyhow::Result<()> {
+        let category_id = category_id.unwrap_or("");
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM cached_streams WHERE category_id = ?1",
+            [category_id],
+        )?;
+        {
+            let mut stmt =
+                tx.prepare("INSERT INTO cached_streams (category_id, stream) VALUES (?1, ?2)")?;
+            for stream in streams {
+                stmt.execute(rusqlite::params![
+                    category_id,
+                    serde_json::to_string(stream)?
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Returns the cached followed live streams and category streams (by
+    /// category ID), as last written by `replace_cached_streams`.
+    pub fn get_cached_streams(&self) -> anyhow::Result<CachedStreams> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT category_id, stream FROM cached_streams")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut followed = Vec::new();
+        let mut categories: HashMap<String, Vec<Stream>> = HashMap::new();
+        for row in rows {
+            let (category_id, json) = row?;
+            let stream: Stream = serde_json::from_str(&json)?;
+            if category_id.is_empty() {
+                followed.push(stream);
+            } else {
+                categories.entry(category_id).or_default().push(stream);
+            }
+        }
+        Ok((followed, categories))
+    }
+
+    // === Users ===
+
+    /// Saves users from the Helix `/users` endpoint, replacing earlier copies.
+    pub fn upsert_users_at(&self, users: &[User], fetched_at: DateTime<Utc>) -> anyhow::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO users
+                 (user_id, login, display_name, profile_image_url, fetched_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for user in users {
+                let user_id: i64 = user.id.parse()?;
+                stmt.execute(rusqlite::params![
+                    user_id,
+                    user.login,
+                    user.display_name,
+                    user.profile_image_url,
+                    fetched_at.timestamp()
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Returns every saved user by ID, with when it was fetched.
+    pub fn get_users(&self) -> anyhow::Result<CachedUsers> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT user_id, login, display_name, profile_image_url, fetched_at FROM users",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let user = User {
+                id: row.get::<_, i64>(0)?.to_string(),
+                login: row.get(1)?,
+                display_name: row.get(2)?,
+                profile_image_url: row.get(3)?,
+            };
+            Ok((user, row.get::<_, i64>(4)?))
+        })?;
+        let mut result = HashMap::new();
+        for row in rows {
+            let (user, fetched_at) = row?;
+            let fetched_at = DateTime::from_timestamp(fetched_at, 0).unwrap_or_default();
+            result.insert(user.id.clone(), (user, fetched_at));
+        }
+        Ok(result)
+    }
+
+    // === Export / import ===
+
+    /// Writes a compacted copy of the whole database to `path`, which must
+    /// not exist yet.
+    pub fn export_to(&self, path: &Path) -> anyhow::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("VACUUM INTO ?1", [path.to_string_lossy()])?;
+        Ok(())
+    }
+
+    /// Replaces the user's own history (`IMPORTED_TABLES`) with the copy in
+    /// the database file at `path`, e.g. one from another machine.
+    ///
+    /// Only columns both databases have are copied, so exports from older
+    /// versions still import. Everything else is refetched from Twitch.
+    pub fn import_history_from(&self, path: &Path) -> anyhow::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("ATTACH DATABASE ?1 AS imported", [path.to_string_lossy()])?;
+        let result = copy_imported_tables(&conn);
+        conn.execute_batch("DETACH DATABASE imported")?;
+        result
+    }
+
+    // === Diagnostics ===
+
+    /// Returns `(table_name, row_count)` for every user table, ordered by name.
+    pub fn table_row_counts(&self) -> anyhow::Result<Vec<(String, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT name FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+             ORDER BY name",
+        )?;
+        let tables: Vec<String> = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        let mut result = Vec::with_capacity(tables.len());
+        for table in tables {
+            // Table names come from sqlite_master, so quoting them is enough
+            let count: i64 =
+                conn.query_row(&format!("SELECT COUNT(*) FROM \"{table}\""), [], |row| {
+                    row.get(0)
+                })?;
+            result.push((table, count));
+        }
+        Ok(result)
+    }
+}
+
+/// Tables `Database::import_history_from` copies: history that only exists
+/// on the machine that recorded it.
+const IMPORTED_TABLES: &[&str] = &["stream_history", "viewer_observations", "watch_history"];
+
+/// Copies `IMPORTED_TABLES` from the attached `imported` database into `main`,
+/// in one transaction.
+fn copy_imported_tables(conn: &Connection) -> anyhow::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    for table in IMPORTED_TABLES {
+        let columns = |schema: &str| -> rusqlite::Result<HashSet<String>> {
+            let mut stmt = tx.prepare(&format!("PRAGMA {schema}.table_info(\"{table}\")"))?;
+            let names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+            names.collect()
+        };
+        let ours = columns("main")?;
+        let mut shared: Vec<String> = columns("imported")?
+            .into_iter()
+            .filter(|c| ours.contains(c))
+            .collect();
+        if shared.is_empty() {
+            continue;
+        }
+        shared.sort();
+        let list = shared
+            .iter()
+            .map(|c| format!("\"{c}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        tx.execute(&format!("DELETE FROM main.\"{table}\""), [])?;
+        tx.execute(
+            &format!(
+                "INSERT OR IGNORE INTO main.\"{table}\" ({list}) SELECT {list} FROM imported.\"{table}\""
+            ),
+            [],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
 }
 
 /// Generates `count` SQL placeholders: "?,?,?"
@@ -594,6 +1164,10 @@ mod tests {
             "CREATE TABLE IF NOT EXISTS stream_history (
                 user_id INTEGER NOT NULL,
                 started_at INTEGER NOT NULL,
+                ended_at INTEGER,
+                last_seen_at INTEGER,
+                game_id TEXT,
+                game_name TEXT,
                 UNIQUE(user_id, started_at)
             );
             CREATE INDEX IF NOT EXISTS idx_stream_history_user_id
@@ -638,7 +1212,33 @@ mod tests {
             CREATE INDEX IF NOT EXISTS idx_vo_broadcaster_age
                 ON viewer_observations(broadcaster_id, stream_age_min);
             CREATE INDEX IF NOT EXISTS idx_vo_observed_at
-                ON viewer_observations(observed_at);",
+                ON viewer_observations(observed_at);
+
+            CREATE TABLE IF NOT EXISTS watch_history (
+                broadcaster_login TEXT NOT NULL,
+                opened_at         INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_watch_history_login
+                ON watch_history(broadcaster_login, opened_at);
+
+            CREATE TABLE IF NOT EXISTS live_streams (
+                user_id INTEGER NOT NULL,
+                started_at INTEGER NOT NULL,
+                PRIMARY KEY (user_id, started_at)
+            );
+
+            CREATE TABLE IF NOT EXISTS cached_streams (
+                category_id TEXT NOT NULL,
+                stream TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS users (
+                user_id INTEGER PRIMARY KEY,
+                login TEXT NOT NULL,
+                display_name TEXT NOT NULL,
+                profile_image_url TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL
+            );",
         )
         .unwrap();
         Database {
@@ -860,6 +1460,7 @@ mod tests {
             category_id: Some("123".to_string()),
             is_recurring: false,
             is_inferred: false,
+            confidence: None,
         }
     }
 
@@ -1059,6 +1660,317 @@ mod tests {
         assert!(rows.is_empty());
     }
 
+    // === Stream end / get_live_at tests ===
+
+    #[test]
+    fn live_at_uses_recorded_end_times() {
+        let db = in_memory_db();
+        db.sync_followed(&[
+            make_channel("100", "StreamerA"),
+            make_channel("200", "StreamerB"),
+        ])
+        .unwrap();
+
+        let base = Utc.with_ymd_and_hms(2025, 6, 15, 18, 0, 0).unwrap();
+        let a = make_test_stream("100", base);
+        let b = make_test_stream("200", base + Duration::hours(1));
+        db.record_streams(&[a.clone(), b.clone()]).unwrap();
+        db.record_stream_ends(&[a], base + Duration::hours(2))
+            .unwrap();
+        db.record_stream_ends(&[b], base + Duration::hours(4))
+            .unwrap();
+
+        let names = |at| -> Vec<String> {
+            db.get_live_at(at, Duration::hours(12))
+                .unwrap()
+                .into_iter()
+                .map(|(name, ..)| name)
+                .collect()
+        };
+        assert_eq!(names(base + Duration::minutes(30)), vec!["StreamerA"]);
+        assert_eq!(
+            names(base + Duration::minutes(90)),
+            vec!["StreamerA", "StreamerB"]
+        );
+        assert_eq!(names(base + Duration::hours(3)), vec!["StreamerB"]);
+        assert!(names(base + Duration::hours(5)).is_empty());
+    }
+
+    #[test]
+    fn live_at_caps_broadcasts_without_an_end() {
+        let db = in_memory_db();
+        db.sync_followed(&[make_channel("100", "StreamerA")])
+            .unwrap();
+        let base = Utc.with_ymd_and_hms(2025, 6, 15, 18, 0, 0).unwrap();
+        db.record_streams(&[make_test_stream("100", base)]).unwrap();
+
+        let rows = db
+            .get_live_at(base + Duration::hours(2), Duration::hours(12))
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].3, None);
+
+        assert!(db
+            .get_live_at(base + Duration::hours(13), Duration::hours(12))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn stream_end_cleared_when_stream_seen_live_again() {
+        let db = in_memory_db();
+        db.sync_followed(&[make_channel("100", "StreamerA")])
+            .unwrap();
+        let base = Utc.with_ymd_and_hms(2025, 6, 15, 18, 0, 0).unwrap();
+        let stream = make_test_stream("100", base);
+        db.record_streams(std::slice::from_ref(&stream)).unwrap();
+        db.record_stream_ends(std::slice::from_ref(&stream), base + Duration::hours(1))
+            .unwrap();
+
+        // Dropped out of one poll, then came back with the same start time
+        db.record_streams(&[stream]).unwrap();
+
+        let rows = db
+            .get_live_at(base + Duration::hours(2), Duration::hours(12))
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].3, None);
+    }
+
+    #[test]
+    fn migration_adds_ended_at_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("data.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE stream_history (
+                user_id INTEGER NOT NULL,
+                started_at INTEGER NOT NULL,
+                UNIQUE(user_id, started_at)
+            );",
+        )
+        .unwrap();
+        drop(conn);
+
+        let db = Database::new(&db_path).unwrap();
+        let stream = make_test_stream("100", Utc::now());
+        db.record_streams(std::slice::from_ref(&stream)).unwrap();
+        db.record_stream_ends(&[stream], Utc::now()).unwrap();
+        assert!(db.get_average_duration().is_ok());
+    }
+
+    #[test]
+    fn average_duration_uses_end_or_last_seen() {
+        let db = in_memory_db();
+        let base = Utc.with_ymd_and_hms(2025, 6, 15, 18, 0, 0).unwrap();
+        let ended = make_test_stream("100", base);
+        let closed_mid_stream = make_test_stream("100", base + Duration::days(1));
+        db.record_streams_at(std::slice::from_ref(&ended), base + Duration::hours(1))
+            .unwrap();
+        db.record_stream_ends(&[ended], base + Duration::hours(2))
+            .unwrap();
+        // App closed an hour in; never saw it end
+        db.record_streams_at(
+            &[closed_mid_stream],
+            base + Duration::days(1) + Duration::hours(1),
+        )
+        .unwrap();
+
+        let durations = db.get_average_duration().unwrap();
+        assert_eq!(durations.get(&100), Some(&Duration::minutes(90)));
+    }
+
+    #[test]
+    fn average_duration_skips_broadcasts_still_live() {
+        let db = in_memory_db();
+        let base = Utc.with_ymd_and_hms(2025, 6, 15, 18, 0, 0).unwrap();
+        let live = make_test_stream("100", base);
+        db.record_streams_at(std::slice::from_ref(&live), base + Duration::hours(3))
+            .unwrap();
+        db.replace_live_streams(&[live]).unwrap();
+
+        assert!(db.get_average_duration().unwrap().is_empty());
+    }
+
+    #[test]
+    fn inferred_schedules_end_after_average_duration() {
+        let db = in_memory_db();
+        db.sync_followed(&[make_channel("100", "StreamerA")])
+            .unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 6, 30, 17, 0, 0).unwrap();
+        for weeks in 1..=2 {
+            let stream = make_test_stream("100", now + Duration::hours(1) - Duration::weeks(weeks));
+            db.record_streams_at(
+                std::slice::from_ref(&stream),
+                stream.started_at + Duration::hours(3),
+            )
+            .unwrap();
+        }
+
+        let lookup = db.get_followed_channel_lookup().unwrap();
+        let inferred = db
+            .infer_schedules(
+                &lookup,
+                now,
+                now + Duration::hours(6),
+                ScheduleInferenceModel::DayOfWeek,
+                8,
+            )
+            .unwrap();
+        assert_eq!(inferred.len(), 1);
+        assert_eq!(
+            inferred[0].end_time,
+            Some(inferred[0].start_time + Duration::hours(3))
+        );
+    }
+
+    // === Export / import tests ===
+
+    #[test]
+    fn exported_history_imports_into_another_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = Utc.with_ymd_and_hms(2025, 6, 15, 18, 0, 0).unwrap();
+
+        let source = Database::new(&dir.path().join("source.db")).unwrap();
+        source
+            .record_streams(&[make_test_stream("100", base)])
+            .unwrap();
+        source.record_watch("streamera", base).unwrap();
+        let export = dir.path().join("export.db");
+        source.export_to(&export).unwrap();
+
+        let target = in_memory_db();
+        target
+            .sync_followed(&[make_channel("100", "StreamerA")])
+            .unwrap();
+        target
+            .record_streams(&[make_test_stream("100", base - Duration::days(1))])
+            .unwrap();
+        target.import_history_from(&export).unwrap();
+
+        let history = target.get_raw_history_in_window(0, i64::MAX).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].2, base.timestamp());
+        assert!(target.get_last_watched().unwrap().contains_key("streamera"));
+        // Followed channels aren't part of the import
+        assert_eq!(target.get_followed_ids().unwrap(), vec![100]);
+    }
+
+    #[test]
+    fn import_copies_only_shared_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.db");
+        let conn = Connection::open(&old).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE stream_history (
+                user_id INTEGER NOT NULL,
+                started_at INTEGER NOT NULL,
+                UNIQUE(user_id, started_at)
+            );
+            INSERT INTO stream_history VALUES (100, 1750000000);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let db = in_memory_db();
+        db.sync_followed(&[make_channel("100", "StreamerA")])
+            .unwrap();
+        db.import_history_from(&old).unwrap();
+
+        let history = db.get_raw_history_in_window(0, i64::MAX).unwrap();
+        assert_eq!(history.len(), 1);
+    }
+
+    // === Watched category tests ===
+
+    fn stream_in_game(user_id: &str, started_at: DateTime<Utc>, game: &str) -> Stream {
+        Stream {
+            game_id: game.to_string(),
+            game_name: format!("Game {game}"),
+            ..make_test_stream(user_id, started_at)
+        }
+    }
+
+    #[test]
+    fn watched_categories_count_distinct_broadcasts() {
+        let db = in_memory_db();
+        db.sync_followed(&[make_channel("100", "StreamerA")])
+            .unwrap();
+        let base = Utc.with_ymd_and_hms(2025, 6, 1, 18, 0, 0).unwrap();
+        let day = Duration::days(1);
+        db.record_streams(&[
+            stream_in_game("100", base, "1"),
+            stream_in_game("100", base + day, "1"),
+            stream_in_game("100", base + day * 2, "2"),
+        ])
+        .unwrap();
+
+        // Opened twice during the first broadcast, once in each of the others
+        for opened in [
+            base + Duration::minutes(5),
+            base + Duration::minutes(90),
+            base + day + Duration::minutes(5),
+            base + day * 2 + Duration::minutes(5),
+        ] {
+            db.record_watch("streamera", opened).unwrap();
+        }
+
+        let rows = db
+            .get_watched_categories(base - day, Duration::hours(12))
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("1".to_string(), "Game 1".to_string(), 2),
+                ("2".to_string(), "Game 2".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn watched_categories_ignore_opens_outside_broadcasts_and_window() {
+        let db = in_memory_db();
+        db.sync_followed(&[make_channel("100", "StreamerA")])
+            .unwrap();
+        let base = Utc.with_ymd_and_hms(2025, 6, 1, 18, 0, 0).unwrap();
+        let stream = stream_in_game("100", base, "1");
+        db.record_streams(std::slice::from_ref(&stream)).unwrap();
+        db.record_stream_ends(&[stream], base + Duration::hours(2))
+            .unwrap();
+
+        db.record_watch("streamera", base - Duration::minutes(5))
+            .unwrap();
+        db.record_watch("streamera", base + Duration::hours(3))
+            .unwrap();
+        // Inside the broadcast, but before `since`
+        db.record_watch("streamera", base + Duration::minutes(5))
+            .unwrap();
+
+        let rows = db
+            .get_watched_categories(base + Duration::minutes(10), Duration::hours(12))
+            .unwrap();
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn record_streams_updates_category_of_live_broadcast() {
+        let db = in_memory_db();
+        db.sync_followed(&[make_channel("100", "StreamerA")])
+            .unwrap();
+        let base = Utc.with_ymd_and_hms(2025, 6, 1, 18, 0, 0).unwrap();
+        db.record_streams(&[stream_in_game("100", base, "1")])
+            .unwrap();
+        db.record_streams(&[stream_in_game("100", base, "2")])
+            .unwrap();
+        db.record_watch("streamera", base + Duration::minutes(5))
+            .unwrap();
+
+        let rows = db
+            .get_watched_categories(base, Duration::hours(12))
+            .unwrap();
+        assert_eq!(rows, vec![("2".to_string(), "Game 2".to_string(), 1)]);
+    }
+
     #[test]
     fn migration_renames_history_db() {
         let dir = tempfile::tempdir().unwrap();
@@ -1229,4 +2141,166 @@ mod tests {
             .unwrap();
         assert!(results.is_empty());
     }
+
+    #[test]
+    fn get_last_seen_uses_latest_end_per_followed_login() {
+        let db = in_memory_db();
+        let base = Utc.with_ymd_and_hms(2025, 6, 15, 12, 0, 0).unwrap();
+        db.sync_followed(&[make_channel("100", "Alpha"), make_channel("200", "Beta")])
+            .unwrap();
+        let earlier = make_test_stream("100", base);
+        let later = make_test_stream("100", base + Duration::days(1));
+        db.record_streams(&[earlier.clone(), later.clone()])
+            .unwrap();
+        db.record_stream_ends(&[earlier], base + Duration::hours(3))
+            .unwrap();
+        db.record_stream_ends(&[later], base + Duration::days(1) + Duration::hours(2))
+            .unwrap();
+        // No recorded end: the start is the last sighting
+        db.record_streams(&[make_test_stream("200", base)]).unwrap();
+        // Not followed
+        db.record_streams(&[make_test_stream("300", base)]).unwrap();
+
+        let seen = db.get_last_seen().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen["alpha"], base + Duration::days(1) + Duration::hours(2));
+        assert_eq!(seen["beta"], base);
+    }
+
+    // === Watch history tests ===
+
+    #[test]
+    fn get_last_watched_returns_latest_open_per_login() {
+        let db = in_memory_db();
+        let base = Utc.with_ymd_and_hms(2025, 6, 15, 12, 0, 0).unwrap();
+        db.record_watch("alpha", base).unwrap();
+        db.record_watch("alpha", base + Duration::hours(2)).unwrap();
+        db.record_watch("beta", base + Duration::hours(1)).unwrap();
+
+        let last = db.get_last_watched().unwrap();
+        assert_eq!(last.len(), 2);
+        assert_eq!(last["alpha"], base + Duration::hours(2));
+        assert_eq!(last["beta"], base + Duration::hours(1));
+    }
+
+    #[test]
+    fn get_last_watched_empty_when_nothing_opened() {
+        let db = in_memory_db();
+        assert!(db.get_last_watched().unwrap().is_empty());
+    }
+
+    #[test]
+    fn get_watch_times_filters_by_login_and_since() {
+        let db = in_memory_db();
+        let base = Utc.with_ymd_and_hms(2025, 6, 15, 12, 0, 0).unwrap();
+        db.record_watch("alpha", base + Duration::hours(2)).unwrap();
+        db.record_watch("alpha", base - Duration::hours(1)).unwrap();
+        db.record_watch("alpha", base).unwrap();
+        db.record_watch("beta", base).unwrap();
+
+        let times = db.get_watch_times("alpha", base).unwrap();
+        assert_eq!(times, vec![base, base + Duration::hours(2)]);
+    }
+
+    // === Live streams tests ===
+
+    #[test]
+    fn live_streams_replaced_each_poll() {
+        let db = in_memory_db();
+        let started = Utc.with_ymd_and_hms(2026, 3, 1, 20, 0, 0).unwrap();
+        db.replace_live_streams(&[
+            make_test_stream("100", started),
+            make_test_stream("200", started),
+        ])
+        .unwrap();
+        db.replace_live_streams(&[make_test_stream("200", started)])
+            .unwrap();
+
+        let live = db.get_live_streams().unwrap();
+        assert_eq!(live.len(), 1);
+        assert!(live.contains(&("200".to_string(), started.timestamp())));
+    }
+
+    #[test]
+    fn cached_streams_replaced_per_list() {
+        let db = in_memory_db();
+        let now = Utc::now();
+        db.replace_cached_streams(None, &[make_test_stream("100", now)])
+            .unwrap();
+        db.replace_cached_streams(Some("509658"), &[make_test_stream("200", now)])
+            .unwrap();
+        db.replace_cached_streams(None, &[make_test_stream("300", now)])
+            .unwrap();
+
+        let (followed, categories) = db.get_cached_streams().unwrap();
+        assert_eq!(followed.len(), 1);
+        assert_eq!(followed[0].user_id, "300");
+        assert_eq!(categories["509658"][0].user_id, "200");
+        assert_eq!(
+            categories["509658"][0].started_at.timestamp(),
+            now.timestamp()
+        );
+    }
+
+    // === User tests ===
+
+    fn make_user(id: &str, name: &str) -> User {
+        User {
+            id: id.to_string(),
+            login: name.to_lowercase(),
+            display_name: name.to_string(),
+            profile_image_url: format!("https://cdn/{id}-300x300.png"),
+        }
+    }
+
+    #[test]
+    fn users_are_replaced_by_id() {
+        let db = in_memory_db();
+        let earlier = Utc::now() - Duration::days(8);
+        let now = Utc::now();
+        db.upsert_users_at(
+            &[make_user("100", "Alice"), make_user("200", "Bob")],
+            earlier,
+        )
+        .unwrap();
+        db.upsert_users_at(&[make_user("100", "AliceRenamed")], now)
+            .unwrap();
+
+        let users = db.get_users().unwrap();
+        assert_eq!(users.len(), 2);
+        let (alice, fetched_at) = &users["100"];
+        assert_eq!(alice.display_name, "AliceRenamed");
+        assert_eq!(alice.profile_image_url, "https://cdn/100-300x300.png");
+        assert_eq!(fetched_at.timestamp(), now.timestamp());
+        assert_eq!(users["200"].1.timestamp(), earlier.timestamp());
+    }
+
+    // === Diagnostics tests ===
+
+    #[test]
+    fn table_row_counts_reports_every_table() {
+        let db = in_memory_db();
+        let now = Utc::now();
+        db.record_streams(&[make_test_stream("100", now), make_test_stream("200", now)])
+            .unwrap();
+
+        let counts = db.table_row_counts().unwrap();
+        let names: Vec<&str> = counts.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "cached_streams",
+                "followed",
+                "live_streams",
+                "schedule_last_checked",
+                "scheduled_streams",
+                "stream_history",
+                "users",
+                "viewer_observations",
+                "watch_history"
+            ]
+        );
+        assert!(counts.contains(&("stream_history".to_string(), 2)));
+        assert!(counts.contains(&("followed".to_string(), 0)));
+    }
 }
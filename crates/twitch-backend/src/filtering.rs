@@ -0,0 +1,114 @@
+//! What each `StreamerImportance` allows, shared by the menus and every
+//! kind of notification so they can't disagree:
+//!
+//! | Importance | Menus        | Notifications                                 |
+//! |------------|--------------|-----------------------------------------------|
+//! | Favourite  | sorted first | always, even in a muted category              |
+//! | Normal     | shown        | unless the category is muted                  |
+//! | Silent     | shown        | none, unless a followed category lets them in |
+//! | Ignore     | hidden       | none                                          |
+//!
+//! Global switches (`notify_on_live`, quiet hours, ...) still apply to all.
+
+use std::collections::HashMap;
+
+use crate::config::{StreamerImportance, StreamerSettings};
+
+/// `user_login`'s importance, `Normal` if they have no settings.
+pub fn importance_of(
+    user_login: &str,
+    settings: &HashMap<String, StreamerSettings>,
+) -> StreamerImportance {
+    settings
+        .get(user_login)
+        .map(|s| s.importance)
+        .unwrap_or_default()
+}
+
+/// Whether `user_login` is a Favourite, sorted ahead of everyone else.
+pub fn is_favourite(user_login: &str, settings: &HashMap<String, StreamerSettings>) -> bool {
+    importance_of(user_login, settings) == StreamerImportance::Favourite
+}
+
+/// Whether `user_login` is listed in menus at all.
+pub fn shows_in_menu(user_login: &str, settings: &HashMap<String, StreamerSettings>) -> bool {
+    importance_of(user_login, settings) != StreamerImportance::Ignore
+}
+
+/// Whether `user_login` may get notifications. `silent_allowed` is true in a
+/// followed category that notifies Silent streamers too.
+pub fn notifies(
+    user_login: &str,
+    settings: &HashMap<String, StreamerSettings>,
+    silent_allowed: bool,
+) -> bool {
+    match importance_of(user_login, settings) {
+        StreamerImportance::Favourite | StreamerImportance::Normal => true,
+        StreamerImportance::Silent => silent_allowed,
+        StreamerImportance::Ignore => false,
+    }
+}
+
+/// Whether `user_login` is still notified in a muted category.
+pub fn ignores_category_mute(
+    user_login: &str,
+    settings: &HashMap<String, StreamerSettings>,
+) -> bool {
+    is_favourite(user_login, settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> HashMap<String, StreamerSettings> {
+        [
+            ("fave", "favourite"),
+            ("normal", "normal"),
+            ("quiet", "silent"),
+            ("hidden", "ignore"),
+        ]
+        .into_iter()
+        .map(|(login, importance)| {
+            let json = format!(r#"{{"display_name": "{login}", "importance": "{importance}"}}"#);
+            (login.to_string(), serde_json::from_str(&json).unwrap())
+        })
+        .collect()
+    }
+
+    #[test]
+    fn unknown_streamers_are_normal() {
+        assert_eq!(
+            importance_of("stranger", &settings()),
+            StreamerImportance::Normal
+        );
+        assert!(shows_in_menu("stranger", &settings()));
+        assert!(notifies("stranger", &settings(), false));
+    }
+
+    #[test]
+    fn only_ignored_streamers_are_hidden_from_menus() {
+        let settings = settings();
+        let shown: Vec<&str> = ["fave", "normal", "quiet", "hidden"]
+            .into_iter()
+            .filter(|login| shows_in_menu(login, &settings))
+            .collect();
+        assert_eq!(shown, vec!["fave", "normal", "quiet"]);
+    }
+
+    #[test]
+    fn silent_streamers_are_notified_only_where_allowed() {
+        let settings = settings();
+        assert!(!notifies("quiet", &settings, false));
+        assert!(notifies("quiet", &settings, true));
+        assert!(!notifies("hidden", &settings, true));
+        assert!(notifies("fave", &settings, false));
+    }
+
+    #[test]
+    fn favourites_ignore_category_mutes() {
+        let settings = settings();
+        assert!(ignores_category_mute("fave", &settings));
+        assert!(!ignores_category_mute("normal", &settings));
+    }
+}
@@ -0,0 +1,243 @@
+//! Searches and sorts followed channels for the settings window's streamer
+//! search.
+//!
+//! Filtering happens here rather than in the settings window so it only
+//! receives the page it shows, not every follow on each keystroke.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{StreamerImportance, StreamerSettings};
+use crate::twitch::{FollowedChannel, Stream};
+
+/// How [`list_followed`] orders channels.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FollowedSort {
+    /// By display name, ignoring case
+    #[default]
+    Name,
+    /// Live channels first, most watched first, then by name
+    Live,
+    /// Most recently followed first
+    RecentlyFollowed,
+}
+
+/// Which followed channels to list. Missing fields list everything by name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct FollowedQuery {
+    /// Matched against login and display name, ignoring case
+    pub search: String,
+    pub sort: FollowedSort,
+    /// Matches to skip, for paging
+    pub offset: usize,
+    /// At most this many matches; `None` returns the rest
+    pub limit: Option<usize>,
+}
+
+/// A followed channel with what the streamer settings editor shows about it.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FollowedChannelEntry {
+    pub broadcaster_id: String,
+    pub broadcaster_login: String,
+    pub broadcaster_name: String,
+    pub followed_at: DateTime<Utc>,
+    /// Viewers if live now, `None` if offline
+    pub viewer_count: Option<u32>,
+    /// Empty until the profile has been fetched
+    pub profile_image_url: String,
+    pub importance: StreamerImportance,
+    /// Whether the channel has an entry in `streamer_settings`
+    pub configured: bool,
+}
+
+/// One page of [`list_followed`] results.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FollowedChannelsPage {
+    pub channels: Vec<FollowedChannelEntry>,
+    /// Matches before paging
+    pub total: usize,
+}
+
+/// Lists the `channels` matching `query`, joined with who's `live`, saved
+/// profile images (by user ID) and streamer settings.
+pub fn list_followed(
+    channels: &[FollowedChannel],
+    live: &[Stream],
+    profile_images: &HashMap<String, String>,
+    settings: &HashMap<String, StreamerSettings>,
+    query: &FollowedQuery,
+) -> FollowedChannelsPage {
+    let search = query.search.trim().to_lowercase();
+    let mut matches: Vec<FollowedChannelEntry> = channels
+        .iter()
+        .filter(|c| {
+            search.is_empty()
+                || c.broadcaster_login.to_lowercase().contains(&search)
+                || c.broadcaster_name.to_lowercase().contains(&search)
+        })
+        .map(|c| {
+            let stream = live.iter().find(|s| s.user_id == c.broadcaster_id);
+            let setting = settings.get(&c.broadcaster_login);
+            FollowedChannelEntry {
+                broadcaster_id: c.broadcaster_id.clone(),
+                broadcaster_login: c.broadcaster_login.clone(),
+                broadcaster_name: c.broadcaster_name.clone(),
+                followed_at: c.followed_at,
+                viewer_count: stream.map(|s| s.viewer_count),
+                profile_image_url: profile_images
+                    .get(&c.broadcaster_id)
+                    .cloned()
+                    .unwrap_or_default(),
+                importance: setting.map(|s| s.importance).unwrap_or_default(),
+                configured: setting.is_some(),
+            }
+        })
+        .collect();
+
+    let by_name = |a: &FollowedChannelEntry, b: &FollowedChannelEntry| {
+        a.broadcaster_name
+            .to_lowercase()
+            .cmp(&b.broadcaster_name.to_lowercase())
+    };
+    match query.sort {
+        FollowedSort::Name => matches.sort_by(by_name),
+        FollowedSort::Live => matches.sort_by(|a, b| {
+            b.viewer_count
+                .cmp(&a.viewer_count)
+                .then_with(|| by_name(a, b))
+        }),
+        FollowedSort::RecentlyFollowed => matches.sort_by_key(|c| std::cmp::Reverse(c.followed_at)),
+    }
+
+    let total = matches.len();
+    let channels = matches
+        .into_iter()
+        .skip(query.offset)
+        .take(query.limit.unwrap_or(usize::MAX))
+        .collect();
+    FollowedChannelsPage { channels, total }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::make_stream;
+    use chrono::Duration;
+
+    fn channel(id: &str, name: &str, days_ago: i64) -> FollowedChannel {
+        FollowedChannel {
+            broadcaster_id: id.to_string(),
+            broadcaster_login: name.to_lowercase(),
+            broadcaster_name: name.to_string(),
+            followed_at: Utc::now() - Duration::days(days_ago),
+        }
+    }
+
+    fn channels() -> Vec<FollowedChannel> {
+        vec![
+            channel("1", "bob", 30),
+            channel("2", "Alice", 10),
+            channel("3", "Carol", 1),
+            channel("4", "Dave", 5),
+        ]
+    }
+
+    fn live(id: &str, viewer_count: u32) -> Stream {
+        Stream {
+            viewer_count,
+            ..make_stream(id, "")
+        }
+    }
+
+    fn names(page: &FollowedChannelsPage) -> Vec<&str> {
+        page.channels
+            .iter()
+            .map(|c| c.broadcaster_name.as_str())
+            .collect()
+    }
+
+    fn list(live_streams: &[Stream], query: &FollowedQuery) -> FollowedChannelsPage {
+        list_followed(
+            &channels(),
+            live_streams,
+            &HashMap::new(),
+            &HashMap::new(),
+            query,
+        )
+    }
+
+    #[test]
+    fn sorts_by_name_ignoring_case() {
+        let page = list(&[], &FollowedQuery::default());
+        assert_eq!(names(&page), vec!["Alice", "bob", "Carol", "Dave"]);
+        assert_eq!(page.total, 4);
+    }
+
+    #[test]
+    fn sorts_live_by_viewers_then_offline_by_name() {
+        let query = FollowedQuery {
+            sort: FollowedSort::Live,
+            ..Default::default()
+        };
+        let page = list(&[live("4", 10), live("3", 500)], &query);
+        assert_eq!(names(&page), vec!["Carol", "Dave", "Alice", "bob"]);
+        assert_eq!(page.channels[0].viewer_count, Some(500));
+        assert_eq!(page.channels[2].viewer_count, None);
+    }
+
+    #[test]
+    fn sorts_by_most_recently_followed() {
+        let query = FollowedQuery {
+            sort: FollowedSort::RecentlyFollowed,
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&list(&[], &query)),
+            vec!["Carol", "Dave", "Alice", "bob"]
+        );
+    }
+
+    #[test]
+    fn searches_login_and_name_then_pages() {
+        let query = FollowedQuery {
+            search: " A ".to_string(),
+            offset: 1,
+            limit: Some(1),
+            ..Default::default()
+        };
+        let page = list(&[], &query);
+        assert_eq!(page.total, 3);
+        assert_eq!(names(&page), vec!["Carol"]);
+    }
+
+    #[test]
+    fn joins_profile_images_and_settings() {
+        let images = HashMap::from([("2".to_string(), "https://example.com/a.png".to_string())]);
+        let settings = HashMap::from([(
+            "alice".to_string(),
+            serde_json::from_str::<StreamerSettings>(
+                r#"{"display_name": "Alice", "importance": "favourite"}"#,
+            )
+            .unwrap(),
+        )]);
+        let page = list_followed(
+            &channels(),
+            &[],
+            &images,
+            &settings,
+            &FollowedQuery::default(),
+        );
+
+        let alice = &page.channels[0];
+        assert_eq!(alice.profile_image_url, "https://example.com/a.png");
+        assert_eq!(alice.importance, StreamerImportance::Favourite);
+        assert!(alice.configured);
+        let bob = &page.channels[1];
+        assert_eq!(bob.importance, StreamerImportance::Normal);
+        assert!(!bob.configured);
+    }
+}
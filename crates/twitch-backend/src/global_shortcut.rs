@@ -0,0 +1,96 @@
+//! Checks `Config::global_shortcut` bindings before they're saved.
+//!
+//! A binding is modifiers and one key joined by `+`, like `Ctrl+Alt+T`, in
+//! the syntax tauri-plugin-global-shortcut parses (ignoring case). Only a
+//! common subset of its keys is accepted, and at least one modifier is
+//! required so the shortcut can't swallow a key other apps need.
+
+const MODIFIERS: &[&str] = &[
+    "ctrl",
+    "control",
+    "alt",
+    "option",
+    "shift",
+    "super",
+    "cmd",
+    "command",
+    "cmdorctrl",
+    "cmdorcontrol",
+    "commandorctrl",
+    "commandorcontrol",
+];
+
+const NAMED_KEYS: &[&str] = &[
+    "space",
+    "enter",
+    "tab",
+    "backspace",
+    "delete",
+    "insert",
+    "home",
+    "end",
+    "pageup",
+    "pagedown",
+    "up",
+    "down",
+    "left",
+    "right",
+];
+
+fn is_modifier(part: &str) -> bool {
+    MODIFIERS.contains(&part.to_ascii_lowercase().as_str())
+}
+
+fn is_key(part: &str) -> bool {
+    let lower = part.to_ascii_lowercase();
+    let single_alphanumeric = lower.len() == 1 && lower.chars().all(|c| c.is_ascii_alphanumeric());
+    let function_key = lower
+        .strip_prefix('f')
+        .and_then(|n| n.parse::<u8>().ok())
+        .is_some_and(|n| (1..=24).contains(&n));
+    single_alphanumeric || function_key || NAMED_KEYS.contains(&lower.as_str())
+}
+
+/// Why `shortcut` can't be used, or `None` if it's a valid binding.
+pub fn shortcut_error(shortcut: &str) -> Option<String> {
+    let parts: Vec<&str> = shortcut.split('+').map(str::trim).collect();
+    let Some((key, modifiers)) = parts.split_last() else {
+        return Some("it is empty".to_string());
+    };
+    if let Some(part) = modifiers.iter().find(|p| !is_modifier(p)) {
+        return Some(format!("{part:?} is not a modifier key"));
+    }
+    if modifiers.is_empty() {
+        return Some("it needs a modifier such as Ctrl or Alt".to_string());
+    }
+    if !is_key(key) {
+        return Some(format!("{key:?} is not a supported key"));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_modifiers_and_a_key() {
+        assert_eq!(shortcut_error("Ctrl+Alt+T"), None);
+        assert_eq!(shortcut_error("cmdorctrl+shift+f12"), None);
+        assert_eq!(shortcut_error("Super + Space"), None);
+    }
+
+    #[test]
+    fn requires_a_modifier() {
+        assert!(shortcut_error("T").unwrap().contains("modifier"));
+    }
+
+    #[test]
+    fn rejects_unknown_parts() {
+        assert!(shortcut_error("Ctrl+Alt").is_some());
+        assert!(shortcut_error("Ctrl+T+Alt").is_some());
+        assert!(shortcut_error("Hyper+T").is_some());
+        assert!(shortcut_error("Ctrl+F25").is_some());
+        assert!(shortcut_error("Ctrl+").is_some());
+    }
+}
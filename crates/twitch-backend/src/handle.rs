@@ -1,24 +1,45 @@
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
 use tokio::sync::{broadcast, mpsc, watch};
 use tokio::task::JoinHandle;
 
 use crate::app_services::AppServices;
+use crate::auth::TokenExpiry;
 use crate::config::{Config, FollowedCategory};
+use crate::connectivity::Connectivity;
 use crate::events::BackendEvent;
-use crate::twitch::{FollowedChannel, ScheduledStream, Stream};
+use crate::notification_snooze::NotificationSnoozes;
+use crate::state::EndedStream;
+use crate::twitch::{FollowedChannel, ScheduledStream, Stream, Video};
 
 /// Raw display data sent by the backend whenever state changes.
 ///
 /// The menu crate subscribes to `BackendHandle.display_rx` and calls
 /// `compute_display_state` to produce a `DisplayState` from this.
 #[derive(Clone, Debug, Default)]
+#[allow(clippy::struct_excessive_bools)] // independent snapshot flags
 pub struct RawDisplayData {
     pub is_authenticated: bool,
+    /// A device code login is in progress.
+    pub authenticating: bool,
+    /// How that login is going, e.g. the code to enter.
+    pub login_progress: Option<LoginProgress>,
     pub live_streams: Vec<Stream>,
     pub scheduled_streams: Vec<ScheduledStream>,
     pub schedules_loaded: bool,
+    /// `scheduled_streams` were read from the DB at startup and haven't been
+    /// refreshed yet this session.
+    pub schedules_cached: bool,
+    /// `live_streams` were read from the DB at startup and haven't been
+    /// refreshed yet this session.
+    pub followed_cached: bool,
+    /// Category IDs whose `category_streams` are still from the DB at startup.
+    pub cached_categories: HashSet<String>,
     pub followed_channels: Vec<FollowedChannel>,
     pub followed_categories: Vec<FollowedCategory>,
     pub category_streams: HashMap<String, Vec<Stream>>,
@@ -27,8 +48,48 @@ pub struct RawDisplayData {
     pub profile_image_urls: HashMap<String, String>,
     /// Cached box art URLs keyed by category/game ID.
     pub box_art_urls: HashMap<String, String>,
+    /// Saved thumbnails of live followed streams keyed by user ID
+    /// (`ThumbnailCache`); missing until a stream's first is downloaded.
+    pub thumbnail_paths: HashMap<String, PathBuf>,
     /// User IDs of streams currently detected as "hot" (significantly above normal viewers).
     pub hot_stream_ids: HashSet<String>,
+    /// Watch streaks at risk for live Favourites, keyed by user ID.
+    /// Empty unless `notify_watch_streak` is enabled.
+    pub streaks_at_risk: HashMap<String, u32>,
+    /// When each broadcaster (by login) was last opened from the app.
+    pub last_watched: HashMap<String, DateTime<Utc>>,
+    /// When each followed broadcaster (by login) was last seen live (`stream_history`).
+    pub last_seen: HashMap<String, DateTime<Utc>>,
+    /// When each broadcaster (by login) was last in a go-live notification this run.
+    pub last_notified: HashMap<String, DateTime<Utc>>,
+    /// A Favourite went live since the menu was last opened.
+    pub has_unread: bool,
+    /// A "Refresh now" from the menu is in flight.
+    pub refreshing: bool,
+    /// Last successful live-stream refresh, `None` before the first.
+    pub last_refresh: Option<DateTime<Utc>>,
+    /// When live-stream refreshes started failing, once enough have failed in
+    /// a row to count as a connection problem.
+    pub connection_problem_since: Option<DateTime<Utc>>,
+    /// Offline while Twitch can't be reached at all; refreshes are paused.
+    pub connectivity: Connectivity,
+    /// Refreshing the session token keeps failing, so the user should log in
+    /// again before API calls start failing.
+    pub token_refresh_failing: bool,
+    /// Required scopes the session token lacks, so the user should log in
+    /// again to grant them.
+    pub missing_scopes: Vec<String>,
+    /// Login whose streamer settings "Undo" would restore, if any.
+    pub undoable_change: Option<String>,
+    /// Favourites' latest past broadcasts keyed by login, newest first.
+    pub recent_videos: HashMap<String, Vec<Video>>,
+    /// Followed streams that went offline within the last hour, newest first.
+    pub recently_ended: Vec<EndedStream>,
+    /// Each pinned channel's stream as last seen live this run, by login
+    /// (`Config::pinned_streams` has the pins themselves).
+    pub pinned_last_live: HashMap<String, Stream>,
+    /// Notifications snoozed from the tray, as of this snapshot.
+    pub notification_snoozes: NotificationSnoozes,
 }
 
 /// Commands sent to the backend auth task.
@@ -36,6 +97,11 @@ pub struct RawDisplayData {
 pub enum AuthCommand {
     Login,
     Logout,
+    /// Log out and straight into a new device flow, falling back to the
+    /// current account if that login doesn't complete.
+    SwitchUser,
+    /// Abandon the device flow of a `Login` or `SwitchUser` in progress.
+    CancelLogin,
 }
 
 /// Progress updates during the OAuth device code login flow.
@@ -45,9 +111,13 @@ pub enum AuthCommand {
 #[derive(Clone, Debug, PartialEq)]
 pub enum LoginProgress {
     /// Device code obtained; user should visit the URI and enter the code shown.
+    ///
+    /// Re-sent with a fresh code if the previous one expires unused.
     PendingCode {
         user_code: String,
         verification_uri: String,
+        /// When this code stops being accepted.
+        expires_at: DateTime<Utc>,
     },
     /// Token confirmed; the user has authorized the application.
     Confirmed,
@@ -55,6 +125,67 @@ pub enum LoginProgress {
     Failed(String),
 }
 
+/// Authentication status as shown by the settings window's banner.
+///
+/// Combines the session state with any login in progress; see [`AuthStatus::new`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum AuthStatus {
+    Unauthenticated,
+    /// A device code is waiting to be entered at `url`.
+    AwaitingDeviceCode {
+        code: String,
+        url: String,
+        expires: DateTime<Utc>,
+    },
+    Authenticated {
+        login: String,
+        /// When the session token expires
+        expires: Option<DateTime<Utc>>,
+        /// When it's next refreshed, ahead of expiring
+        renews: Option<DateTime<Utc>>,
+        /// Refreshing keeps failing: the user should log in again
+        renewal_failing: bool,
+    },
+    /// The last login attempt failed and nobody is logged in.
+    Error {
+        message: String,
+    },
+}
+
+impl AuthStatus {
+    /// A pending device code wins over the session, so a Switch User shows
+    /// its code; a failed login only matters while logged out.
+    pub fn new(
+        authenticated: bool,
+        user_login: &str,
+        token: Option<TokenExpiry>,
+        progress: Option<&LoginProgress>,
+    ) -> Self {
+        match progress {
+            Some(LoginProgress::PendingCode {
+                user_code,
+                verification_uri,
+                expires_at,
+            }) => AuthStatus::AwaitingDeviceCode {
+                code: user_code.clone(),
+                url: verification_uri.clone(),
+                expires: *expires_at,
+            },
+            _ if authenticated => AuthStatus::Authenticated {
+                login: user_login.to_string(),
+                expires: token.map(|t| t.expires_at),
+                renews: token.map(|t| t.next_attempt()),
+                renewal_failing: token.is_some_and(|t| t.failing()),
+            },
+            Some(LoginProgress::Failed(message)) => AuthStatus::Error {
+                message: message.clone(),
+            },
+            _ => AuthStatus::Unauthenticated,
+        }
+    }
+}
+
 /// Everything the app layer needs to interact with the backend.
 ///
 /// Returned from `twitch_backend::start()`.
@@ -73,3 +204,101 @@ pub struct BackendHandle {
     /// Background task handles (so main can join/abort on shutdown).
     pub tasks: Vec<JoinHandle<()>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending() -> LoginProgress {
+        LoginProgress::PendingCode {
+            user_code: "ABC-123".to_string(),
+            verification_uri: "https://www.twitch.tv/activate".to_string(),
+            expires_at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn logged_out_without_progress_is_unauthenticated() {
+        assert_eq!(
+            AuthStatus::new(false, "", None, None),
+            AuthStatus::Unauthenticated
+        );
+    }
+
+    #[test]
+    fn pending_code_is_shown_even_while_logged_in() {
+        let status = AuthStatus::new(true, "alice", None, Some(&pending()));
+        assert_eq!(
+            status,
+            AuthStatus::AwaitingDeviceCode {
+                code: "ABC-123".to_string(),
+                url: "https://www.twitch.tv/activate".to_string(),
+                expires: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn logged_in_reports_login() {
+        let expires = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let status = AuthStatus::new(
+            true,
+            "alice",
+            Some(TokenExpiry::new(expires)),
+            Some(&LoginProgress::Confirmed),
+        );
+        assert_eq!(
+            status,
+            AuthStatus::Authenticated {
+                login: "alice".to_string(),
+                expires: Some(expires),
+                renews: Some(expires - chrono::Duration::minutes(10)),
+                renewal_failing: false,
+            }
+        );
+    }
+
+    #[test]
+    fn logged_in_reports_failing_renewal() {
+        let mut token = TokenExpiry::new(Utc::now());
+        for _ in 0..crate::auth::WARN_AFTER_FAILURES {
+            token.record_failure(Utc::now());
+        }
+        assert!(matches!(
+            AuthStatus::new(true, "alice", Some(token), None),
+            AuthStatus::Authenticated {
+                renewal_failing: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn failed_login_while_logged_out_is_an_error() {
+        let failed = LoginProgress::Failed("denied".to_string());
+        assert_eq!(
+            AuthStatus::new(false, "", None, Some(&failed)),
+            AuthStatus::Error {
+                message: "denied".to_string()
+            }
+        );
+        // A failed switch that kept the old session is still logged in
+        assert!(matches!(
+            AuthStatus::new(true, "alice", None, Some(&failed)),
+            AuthStatus::Authenticated { .. }
+        ));
+    }
+
+    #[test]
+    fn serializes_with_type_tag() {
+        let json = serde_json::to_value(AuthStatus::Authenticated {
+            login: "alice".to_string(),
+            expires: None,
+            renews: None,
+            renewal_failing: false,
+        })
+        .unwrap();
+        assert_eq!(json["type"], "Authenticated");
+        assert_eq!(json["login"], "alice");
+    }
+}
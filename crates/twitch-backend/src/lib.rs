@@ -1,28 +1,60 @@
 // twitch-backend: pure Rust backend crate — no Tauri or GTK dependencies.
-#![forbid(unsafe_code)]
+// OS integrations (keyring, native notifications) sit behind default features;
+// `--no-default-features` builds the core for headless or alternative frontends.
+// The only unsafe code is the macOS and Windows FFI in `power`, which opts in.
+#![deny(unsafe_code)]
 
+pub mod about;
 pub mod app_services;
 pub mod auth;
+pub mod autostart;
+pub mod bulk_importance;
+pub mod category_filters;
+pub mod category_suggestions;
+pub mod changelog;
 pub mod config;
+pub mod connectivity;
+pub mod custom_action;
+pub mod data_export;
 pub mod db;
 pub mod events;
+pub mod filtering;
+pub mod followed_list;
+pub mod global_shortcut;
 pub mod handle;
 pub mod hotness_detection;
+pub mod log_file;
+pub mod log_scrub;
 pub mod notification_dispatcher;
 pub mod notification_filter;
+pub mod notification_snooze;
 pub mod notify;
+pub mod pinned_streams;
+pub mod power;
+pub mod proxy;
 pub mod schedule_inference;
+pub mod schedule_reminder;
 pub mod schedule_walker;
 pub mod session;
+pub mod settings_undo;
 pub mod state;
+pub mod stream_stats;
+pub mod streamer_list;
+pub mod support;
+pub mod thumbnail_cache;
+pub mod title_alerts;
 pub mod twitch;
+pub mod watch_streak;
 
 pub(crate) mod backend;
 
 #[cfg(test)]
 pub(crate) mod test_helpers;
 
+#[cfg(all(test, feature = "soak"))]
+mod soak;
+
 // Primary public API
 pub use backend::start;
 pub use events::BackendEvent;
-pub use handle::{AuthCommand, BackendHandle, LoginProgress, RawDisplayData};
+pub use handle::{AuthCommand, AuthStatus, BackendHandle, LoginProgress, RawDisplayData};
@@ -0,0 +1,205 @@
+//! Log files in `support::log_dir()`, one per day.
+//!
+//! Each frontend writes `<prefix>.<YYYY-MM-DD>.log`, switching to a new file
+//! at local midnight and deleting its own files older than [`KEEP_LOG_DAYS`].
+//! Wrap it in a `Mutex` and `RedactingMakeWriter` to use it as a
+//! `tracing_subscriber` writer.
+//!
+//! The level comes from `Config::log_level` through a reloadable filter (see
+//! [`follow_log_level`]), unless `RUST_LOG` is set.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDate};
+use tokio::sync::watch;
+use tracing_subscriber::{reload, EnvFilter};
+
+use crate::config::LogLevel;
+use crate::handle::RawDisplayData;
+
+/// Days of log files kept, including today's.
+pub const KEEP_LOG_DAYS: u64 = 7;
+
+/// A log file that moves to a new file each day.
+pub struct DailyLogFile {
+    dir: PathBuf,
+    prefix: String,
+    date: NaiveDate,
+    file: File,
+}
+
+impl DailyLogFile {
+    /// Opens (creating `dir` if needed) today's file for `prefix`.
+    pub fn open(dir: &Path, prefix: &str) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        Self::open_on(dir, prefix, Local::now().date_naive())
+    }
+
+    fn open_on(dir: &Path, prefix: &str, date: NaiveDate) -> io::Result<Self> {
+        let file = open_append(&file_path(dir, prefix, date))?;
+        let log = Self {
+            dir: dir.to_path_buf(),
+            prefix: prefix.to_string(),
+            date,
+            file,
+        };
+        log.remove_old_files();
+        Ok(log)
+    }
+
+    /// Switches to `date`'s file if it isn't the current one.
+    fn roll_to(&mut self, date: NaiveDate) -> io::Result<()> {
+        if date != self.date {
+            self.file = open_append(&file_path(&self.dir, &self.prefix, date))?;
+            self.date = date;
+            self.remove_old_files();
+        }
+        Ok(())
+    }
+
+    /// Deletes this prefix's files dated more than `KEEP_LOG_DAYS` before
+    /// the current one. Other files in the directory are left alone.
+    fn remove_old_files(&self) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        let Some(oldest_kept) = self
+            .date
+            .checked_sub_days(chrono::Days::new(KEEP_LOG_DAYS - 1))
+        else {
+            return;
+        };
+        for path in entries.filter_map(Result::ok).map(|e| e.path()) {
+            let date = path
+                .file_name()
+                .and_then(|name| file_date(&name.to_string_lossy(), &self.prefix));
+            if date.is_some_and(|date| date < oldest_kept) {
+                // Logging here could recurse into this writer
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+impl Write for DailyLogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.roll_to(Local::now().date_naive())?;
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// The filter to start logging with: `RUST_LOG` if set, otherwise the default
+/// `Config::log_level` until the config is loaded.
+pub fn initial_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(LogLevel::default().directive()))
+}
+
+/// Keeps `filter` at `Config::log_level` as the config changes. Returns
+/// straight away when `RUST_LOG` is set, which takes precedence.
+pub async fn follow_log_level<S: 'static>(
+    filter: reload::Handle<EnvFilter, S>,
+    mut display_rx: watch::Receiver<RawDisplayData>,
+) {
+    if std::env::var_os(EnvFilter::DEFAULT_ENV).is_some() {
+        return;
+    }
+    let mut current = LogLevel::default();
+    loop {
+        let level = display_rx.borrow_and_update().config.log_level;
+        if level != current {
+            match filter.reload(EnvFilter::new(level.directive())) {
+                Ok(()) => {
+                    tracing::info!("Log level set to {}", level.directive());
+                    current = level;
+                }
+                Err(e) => tracing::warn!("Failed to change log level: {}", e),
+            }
+        }
+        if display_rx.changed().await.is_err() {
+            break;
+        }
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn file_path(dir: &Path, prefix: &str, date: NaiveDate) -> PathBuf {
+    dir.join(format!("{prefix}.{}.log", date.format("%Y-%m-%d")))
+}
+
+/// The date in a `<prefix>.<YYYY-MM-DD>.log` file name.
+fn file_date(name: &str, prefix: &str) -> Option<NaiveDate> {
+    let date = name
+        .strip_prefix(prefix)?
+        .strip_prefix('.')?
+        .strip_suffix(".log")?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    #[test]
+    fn rolls_over_to_a_new_file_each_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = DailyLogFile::open_on(dir.path(), "app", date(1)).unwrap();
+        log.file.write_all(b"first\n").unwrap();
+        log.roll_to(date(2)).unwrap();
+        log.file.write_all(b"second\n").unwrap();
+
+        let read = |day| std::fs::read_to_string(file_path(dir.path(), "app", date(day))).unwrap();
+        assert_eq!(read(1), "first\n");
+        assert_eq!(read(2), "second\n");
+    }
+
+    #[test]
+    fn removes_only_its_own_expired_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "app.2026-03-01.log",
+            "app.2026-03-04.log",
+            "tui.2026-03-01.log",
+            "app.log",
+        ] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+
+        let _log = DailyLogFile::open_on(dir.path(), "app", date(10)).unwrap();
+
+        let mut names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "app.2026-03-04.log",
+                "app.2026-03-10.log",
+                "app.log",
+                "tui.2026-03-01.log"
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_dates_from_its_own_file_names() {
+        assert_eq!(file_date("app.2026-03-04.log", "app"), Some(date(4)));
+        assert_eq!(file_date("app.2026-03-04.log", "tui"), None);
+        assert_eq!(file_date("app.log", "app"), None);
+    }
+}
@@ -0,0 +1,211 @@
+//! Credential scrubbing for the tracing output.
+//!
+//! Tokens can surface in logged errors — a failed refresh echoes the form body,
+//! a `Debug`-printed request carries its `Authorization` header. Frontends wrap
+//! their log writer in [`RedactingMakeWriter`] so every formatted line goes
+//! through [`scrub_line`] before it reaches stderr or a log file.
+
+use std::io::{self, Write};
+
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::support::{redact_text, REDACTED};
+
+/// Twitch OAuth access and refresh tokens are 30 lowercase alphanumerics.
+const TWITCH_TOKEN_LEN: usize = 30;
+
+/// Masks credentials in a single log line.
+///
+/// On top of the keyed/prefixed secrets handled by [`redact_text`], bare words
+/// shaped like a Twitch token are masked, since they show up without context
+/// in some error messages.
+pub fn scrub_line(line: &str) -> String {
+    let redacted = redact_text(line);
+    let mut out = String::with_capacity(redacted.len());
+    let mut word_start = None;
+    for (i, c) in redacted.char_indices() {
+        if c.is_ascii_alphanumeric() {
+            word_start.get_or_insert(i);
+        } else if let Some(start) = word_start.take() {
+            push_word(&mut out, &redacted[start..i]);
+            out.push(c);
+        } else {
+            out.push(c);
+        }
+    }
+    if let Some(start) = word_start {
+        push_word(&mut out, &redacted[start..]);
+    }
+    out
+}
+
+fn push_word(out: &mut String, word: &str) {
+    if looks_like_token(word) {
+        out.push_str(REDACTED);
+    } else {
+        out.push_str(word);
+    }
+}
+
+/// A word of token length mixing lowercase letters and digits.
+///
+/// Requiring both keeps user IDs (all digits) and ordinary words out.
+fn looks_like_token(word: &str) -> bool {
+    word.len() == TWITCH_TOKEN_LEN
+        && word
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        && word.bytes().any(|b| b.is_ascii_digit())
+        && word.bytes().any(|b| b.is_ascii_lowercase())
+}
+
+/// Writer that scrubs each complete line before passing it on.
+///
+/// Partial lines are held back until their newline arrives (or the writer is
+/// flushed/dropped) so a secret split across writes is still caught.
+pub struct RedactingWriter<W: Write> {
+    inner: W,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> RedactingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            pending: Vec::new(),
+        }
+    }
+
+    fn write_lines(&mut self, include_partial: bool) -> io::Result<()> {
+        let end = if include_partial {
+            self.pending.len()
+        } else {
+            match self.pending.iter().rposition(|&b| b == b'\n') {
+                Some(pos) => pos + 1,
+                None => return Ok(()),
+            }
+        };
+        if end == 0 {
+            return Ok(());
+        }
+        let chunk: Vec<u8> = self.pending.drain(..end).collect();
+        let scrubbed = scrub_line(&String::from_utf8_lossy(&chunk));
+        self.inner.write_all(scrubbed.as_bytes())
+    }
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        self.write_lines(false)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_lines(true)?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for RedactingWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// [`MakeWriter`] adapter that wraps every writer in a [`RedactingWriter`].
+///
+/// ```ignore
+/// tracing_subscriber::fmt::layer().with_writer(RedactingMakeWriter::new(std::io::stderr))
+/// ```
+pub struct RedactingMakeWriter<M> {
+    inner: M,
+}
+
+impl<M> RedactingMakeWriter<M> {
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingMakeWriter<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter::new(self.inner.make_writer())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "abcdef0123456789ghijkl01234567";
+
+    // === scrub_line tests ===
+
+    #[test]
+    fn scrubs_failed_refresh_request_url() {
+        let line = format!(
+            "WARN Token refresh failed: error sending request for url \
+             (https://id.twitch.tv/oauth2/token?client_id=abc&grant_type=refresh_token&refresh_token={TOKEN})"
+        );
+        let out = scrub_line(&line);
+        assert!(!out.contains(TOKEN));
+        assert!(out.contains("grant_type=refresh_token"));
+    }
+
+    #[test]
+    fn scrubs_authorization_header_in_debug_output() {
+        let line = format!(
+            r#"DEBUG request headers: {{"client-id": "x", "authorization": "Bearer {TOKEN}"}}"#
+        );
+        assert!(!scrub_line(&line).contains(TOKEN));
+    }
+
+    #[test]
+    fn scrubs_token_json_in_error_body() {
+        let line = format!(
+            r#"ERROR Unexpected response body: {{"access_token":"{TOKEN}","expires_in":14400,"token_type":"bearer"}}"#
+        );
+        let out = scrub_line(&line);
+        assert!(!out.contains(TOKEN));
+        assert!(out.contains("\"expires_in\":14400"));
+    }
+
+    #[test]
+    fn scrubs_bare_token_shaped_words() {
+        let out = scrub_line(&format!("Validation failed for {TOKEN}: 401"));
+        assert_eq!(out, format!("Validation failed for {REDACTED}: 401"));
+    }
+
+    #[test]
+    fn leaves_ids_and_ordinary_text_alone() {
+        let line = "INFO Fetched 123456789012345678901234567890 streams for user 44322889 (lirik)";
+        assert_eq!(scrub_line(line), line);
+    }
+
+    // === RedactingWriter tests ===
+
+    #[test]
+    fn writer_scrubs_secret_split_across_writes() {
+        let mut out = Vec::new();
+        {
+            let mut writer = RedactingWriter::new(&mut out);
+            writer.write_all(b"auth Bearer abc").unwrap();
+            writer.write_all(b"123xyz done\nnext").unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("auth Bearer {REDACTED} done\nnext")
+        );
+    }
+
+    #[test]
+    fn make_writer_wraps_inner_writer() {
+        let make = RedactingMakeWriter::new(std::sync::Mutex::new(Vec::new()));
+        writeln!(make.make_writer(), "refresh_token={TOKEN}").unwrap();
+        let written = String::from_utf8(make.inner.into_inner().unwrap()).unwrap();
+        assert_eq!(written, format!("refresh_token={REDACTED}\n"));
+    }
+}
@@ -5,17 +5,23 @@
 //! that is `Notifier`'s job. It owns the policy of *when* to notify, delegating
 //! the heavy lifting to `filter_notifications`.
 
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, Utc};
 use tokio::sync::broadcast;
 use tokio::task::JoinHandle;
 
-use crate::config::ConfigManager;
-use crate::notification_filter::filter_notifications;
+use crate::config::{Config, ConfigManager};
+use crate::notification_filter::{
+    drop_previously_live, drop_snoozed, filter_notifications, hold_for_summary, mute_categories,
+    quiet_hours_summary, should_batch, suppress_restarts, went_live_names, RecentGoLive,
+};
 use crate::notify::Notifier;
-use crate::state::StreamsUpdated;
+use crate::state::{AppState, StreamsUpdated};
+use crate::thumbnail_cache::ThumbnailCache;
+use crate::twitch::Stream;
 
 /// Listens for `StreamsUpdated` broadcast events and dispatches desktop
 /// notifications according to the current config and notification filter.
@@ -23,6 +29,12 @@ pub struct NotificationDispatcher {
     notifier: Arc<dyn Notifier>,
     config: Arc<ConfigManager>,
     initial_load_done: Arc<AtomicBool>,
+    /// Broadcasts live at the last poll before this run, as `(user_id, started_at)`
+    previously_live: HashSet<(String, i64)>,
+    /// Where go-live notifications are recorded, for `LiveSortMode::RecentlyNotified`
+    state: Option<Arc<AppState>>,
+    /// Downloads go-live thumbnails and avatars for the notifier to attach
+    thumbnails: Option<Arc<ThumbnailCache>>,
 }
 
 impl NotificationDispatcher {
@@ -35,9 +47,35 @@ impl NotificationDispatcher {
             notifier,
             config,
             initial_load_done,
+            previously_live: HashSet::new(),
+            state: None,
+            thumbnails: None,
         }
     }
 
+    /// Never notifies go-lives for these broadcasts, which were already live
+    /// before a restart (from `Database::get_live_streams`).
+    #[must_use]
+    pub fn with_previously_live(mut self, previously_live: HashSet<(String, i64)>) -> Self {
+        self.previously_live = previously_live;
+        self
+    }
+
+    /// Records who was in each go-live notification in `state`.
+    #[must_use]
+    pub fn with_state(mut self, state: Arc<AppState>) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Fetches each go-live's thumbnail before notifying, when the
+    /// notification server shows images, and on Linux the streamer's avatar.
+    #[must_use]
+    pub fn with_thumbnails(mut self, thumbnails: Arc<ThumbnailCache>) -> Self {
+        self.thumbnails = Some(thumbnails);
+        self
+    }
+
     /// Spawns the listener task and returns its handle.
     pub fn start(self: Arc<Self>, rx: broadcast::Receiver<StreamsUpdated>) -> JoinHandle<()> {
         tokio::spawn(async move {
@@ -47,30 +85,77 @@ impl NotificationDispatcher {
 
     pub(crate) async fn listen(&self, mut rx: broadcast::Receiver<StreamsUpdated>) {
         let mut last_event_time: Option<DateTime<Utc>> = None;
+        let mut recent_go_live = RecentGoLive::new();
+        // Go-lives held back during quiet hours for the summary
+        let mut held = Vec::new();
 
         loop {
             match rx.recv().await {
                 Ok(event) => {
                     let now = Utc::now();
                     let cfg = self.config.get();
-                    let decision = filter_notifications(
+                    let (silent_override_categories, muted_categories) = category_rules(&cfg);
+                    let mut decision = filter_notifications(
                         &event,
                         last_event_time,
                         now,
                         cfg.notify_max_gap_min * 60,
                         self.initial_load_done.load(Ordering::SeqCst),
                         &cfg.streamer_settings,
+                        &silent_override_categories,
                     );
                     last_event_time = Some(now);
+                    mute_categories(&mut decision, &muted_categories, &cfg.streamer_settings);
+                    drop_previously_live(&mut decision, &self.previously_live);
+                    if let Some(state) = &self.state {
+                        drop_snoozed(&mut decision, &state.notification_snoozes(now).await, now);
+                    }
 
-                    if cfg.notify_on_live {
-                        for stream in decision.streams_to_notify {
-                            if let Err(e) = self.notifier.stream_live(&stream) {
+                    let quiet = cfg
+                        .quiet_hours
+                        .filter(|q| q.contains(now.with_timezone(&Local).time()));
+                    if quiet.is_none() {
+                        if let Some(message) = quiet_hours_summary(&held) {
+                            if let Err(e) = self
+                                .notifier
+                                .went_live_summary("While notifications were paused", &message)
+                            {
                                 tracing::error!("Notification error: {}", e);
                             }
                         }
+                        held.clear();
                     }
-                    if cfg.notify_on_category {
+
+                    if cfg.notify_on_live {
+                        let streams = suppress_restarts(
+                            decision.streams_to_notify,
+                            &mut recent_go_live,
+                            now,
+                            chrono::Duration::minutes(cfg.notify_restart_window_min as i64),
+                        );
+                        match quiet {
+                            Some(quiet) if quiet.summary => hold_for_summary(&mut held, streams),
+                            Some(_) => {}
+                            None if should_batch(streams.len(), cfg.notify_batch_threshold) => {
+                                self.record_notified(&streams, now).await;
+                                let title = format!("{} streamers went live", streams.len());
+                                let message = went_live_names(&streams).unwrap_or_default();
+                                if let Err(e) = self.notifier.went_live_summary(&title, &message) {
+                                    tracing::error!("Notification error: {}", e);
+                                }
+                            }
+                            None => {
+                                self.record_notified(&streams, now).await;
+                                for stream in streams {
+                                    self.fetch_images(&stream).await;
+                                    if let Err(e) = self.notifier.stream_live(&stream) {
+                                        tracing::error!("Notification error: {}", e);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    if cfg.notify_on_category && quiet.is_none() {
                         for change in decision.categories_to_notify {
                             if let Err(e) = self
                                 .notifier
@@ -90,6 +175,54 @@ impl NotificationDispatcher {
             }
         }
     }
+
+    /// Saves the streamer's avatar for the notification icon (only Linux
+    /// shows one) and, where the server shows images, the stream's thumbnail.
+    async fn fetch_images(&self, stream: &Stream) {
+        let Some(thumbnails) = &self.thumbnails else {
+            return;
+        };
+        if cfg!(target_os = "linux") {
+            if let Err(e) = thumbnails.fetch_avatar(stream).await {
+                tracing::debug!("{:#}", e);
+            }
+        }
+        if !self.notifier.capabilities().images {
+            return;
+        }
+        if let Err(e) = thumbnails.fetch(stream).await {
+            tracing::debug!("{:#}", e);
+        }
+    }
+
+    async fn record_notified(&self, streams: &[Stream], at: DateTime<Utc>) {
+        if let Some(state) = &self.state {
+            let logins = streams.iter().map(|s| s.user_login.clone()).collect();
+            state.record_notified(logins, at).await;
+        }
+    }
+}
+
+/// Game IDs from the followed categories' notification preferences: where
+/// Silent streamers are notified, and where nobody is.
+fn category_rules(cfg: &Config) -> (HashSet<String>, HashSet<String>) {
+    let mut silent_override = HashSet::new();
+    let mut muted = HashSet::new();
+    for category in &cfg.followed_categories {
+        match category.notifications.enabled {
+            Some(false) => {
+                muted.insert(category.id.clone());
+            }
+            Some(true) => {
+                silent_override.insert(category.id.clone());
+            }
+            None if cfg.notify_silent_in_followed_categories => {
+                silent_override.insert(category.id.clone());
+            }
+            None => {}
+        }
+    }
+    (silent_override, muted)
 }
 
 #[cfg(test)]
@@ -124,6 +257,8 @@ mod tests {
             streams: vec![stream.clone()],
             newly_live: vec![stream],
             category_changes: vec![],
+            ended: vec![],
+            suspended: None,
         }
     }
 
@@ -137,6 +272,8 @@ mod tests {
                 stream,
                 old_category: "Old Game".to_string(),
             }],
+            ended: vec![],
+            suspended: None,
         }
     }
 
@@ -181,6 +318,26 @@ mod tests {
         handle.abort();
     }
 
+    #[tokio::test]
+    async fn go_lives_recorded_in_state() {
+        let notifier = Arc::new(RecordingNotifier::new());
+        let config = Arc::new(ConfigManager::with_config(Config::default()));
+        let state = AppState::new();
+
+        let dispatcher =
+            NotificationDispatcher::new(notifier.clone(), config, Arc::new(AtomicBool::new(true)))
+                .with_state(state.clone());
+
+        let (tx, rx) = broadcast::channel(16);
+        let handle = tokio::spawn(async move { dispatcher.listen(rx).await });
+
+        tx.send(make_event("streamer")).unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert!(state.last_notified().await.contains_key("streamer"));
+
+        handle.abort();
+    }
+
     #[tokio::test]
     async fn category_notifications_suppressed_when_config_disabled_without_restart() {
         let notifier = Arc::new(RecordingNotifier::new());
@@ -221,4 +378,238 @@ mod tests {
 
         handle.abort();
     }
+
+    #[tokio::test]
+    async fn silent_streamer_notified_in_followed_category_when_enabled() {
+        use crate::config::{FollowedCategory, StreamerImportance, StreamerSettings};
+
+        let notifier = Arc::new(RecordingNotifier::new());
+        let mut config = Config {
+            notify_silent_in_followed_categories: true,
+            followed_categories: vec![FollowedCategory {
+                id: "game".to_string(),
+                name: "Game".to_string(),
+                ..Default::default()
+            }],
+            ..Config::default()
+        };
+        config.streamer_settings.insert(
+            "streamer".to_string(),
+            StreamerSettings {
+                display_name: "streamer".to_string(),
+                importance: StreamerImportance::Silent,
+                hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: None,
+                open_with: None,
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
+            },
+        );
+        let config = Arc::new(ConfigManager::with_config(config));
+        let initial_load_done = Arc::new(AtomicBool::new(true));
+
+        let dispatcher =
+            NotificationDispatcher::new(notifier.clone(), config.clone(), initial_load_done);
+
+        let (tx, rx) = broadcast::channel(16);
+        let handle = tokio::spawn(async move { dispatcher.listen(rx).await });
+
+        tx.send(make_event("streamer")).unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert_eq!(notifier.notification_count(), 1);
+
+        // Turning the mode off restores normal Silent behaviour
+        let mut cfg = config.get();
+        cfg.notify_silent_in_followed_categories = false;
+        config.set(cfg);
+        notifier.clear();
+
+        tx.send(make_event("streamer")).unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert_eq!(notifier.notification_count(), 0);
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn category_notification_preferences_override_global_rules() {
+        use crate::config::{
+            CategoryNotifications, FollowedCategory, StreamerImportance, StreamerSettings,
+        };
+
+        let notifier = Arc::new(RecordingNotifier::new());
+        let mut config = Config {
+            followed_categories: vec![FollowedCategory {
+                id: "game".to_string(),
+                name: "Game".to_string(),
+                notifications: CategoryNotifications {
+                    enabled: Some(false),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+            ..Config::default()
+        };
+        config.streamer_settings.insert(
+            "quiet".to_string(),
+            StreamerSettings {
+                display_name: "quiet".to_string(),
+                importance: StreamerImportance::Silent,
+                hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: None,
+                open_with: None,
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
+            },
+        );
+        let config = Arc::new(ConfigManager::with_config(config));
+        let initial_load_done = Arc::new(AtomicBool::new(true));
+
+        let dispatcher =
+            NotificationDispatcher::new(notifier.clone(), config.clone(), initial_load_done);
+
+        let (tx, rx) = broadcast::channel(16);
+        let handle = tokio::spawn(async move { dispatcher.listen(rx).await });
+
+        // Muted category: not even Normal streamers notify
+        tx.send(make_event("streamer")).unwrap();
+        tx.send(make_category_event("mover")).unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert_eq!(notifier.notification_count(), 0);
+
+        // Enabled category: Silent streamers notify without the global mode
+        let mut cfg = config.get();
+        cfg.followed_categories[0].notifications.enabled = Some(true);
+        config.set(cfg);
+
+        tx.send(make_event("quiet")).unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert_eq!(notifier.notification_count(), 1);
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn restart_within_window_not_notified_twice() {
+        let notifier = Arc::new(RecordingNotifier::new());
+        let config = Arc::new(ConfigManager::with_config(Config::default()));
+        let initial_load_done = Arc::new(AtomicBool::new(true));
+
+        let dispatcher =
+            NotificationDispatcher::new(notifier.clone(), config.clone(), initial_load_done);
+
+        let (tx, rx) = broadcast::channel(16);
+        let handle = tokio::spawn(async move { dispatcher.listen(rx).await });
+
+        // Same streamer reported newly live twice (connection blip)
+        tx.send(make_event("streamer")).unwrap();
+        tx.send(make_event("streamer")).unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert_eq!(notifier.notification_count(), 1);
+
+        // With suppression disabled the repeat is notified
+        config.set(Config {
+            notify_restart_window_min: 0,
+            ..Config::default()
+        });
+        tx.send(make_event("streamer")).unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert_eq!(notifier.notification_count(), 2);
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn quiet_hours_hold_notifications_until_summary() {
+        use crate::config::QuietHours;
+
+        let notifier = Arc::new(RecordingNotifier::new());
+        let now = Local::now().time();
+        let config = Arc::new(ConfigManager::with_config(Config {
+            quiet_hours: Some(QuietHours {
+                start: now - chrono::Duration::hours(1),
+                end: now + chrono::Duration::hours(1),
+                summary: true,
+            }),
+            ..Config::default()
+        }));
+        let initial_load_done = Arc::new(AtomicBool::new(true));
+
+        let dispatcher =
+            NotificationDispatcher::new(notifier.clone(), config.clone(), initial_load_done);
+
+        let (tx, rx) = broadcast::channel(16);
+        let handle = tokio::spawn(async move { dispatcher.listen(rx).await });
+
+        tx.send(make_event("streamer")).unwrap();
+        tx.send(make_category_event("mover")).unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert_eq!(notifier.notification_count(), 0);
+
+        // The first event outside quiet hours sends the summary
+        config.set(Config::default());
+        tx.send(StreamsUpdated {
+            streams: vec![],
+            newly_live: vec![],
+            category_changes: vec![],
+            ended: vec![],
+            suspended: None,
+        })
+        .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        let sent = notifier.get_notifications();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].message, "streamer went live");
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn many_go_lives_in_one_refresh_are_batched() {
+        let notifier = Arc::new(RecordingNotifier::new());
+        let config = Arc::new(ConfigManager::with_config(Config {
+            notify_batch_threshold: 2,
+            ..Config::default()
+        }));
+        let initial_load_done = Arc::new(AtomicBool::new(true));
+
+        let dispatcher =
+            NotificationDispatcher::new(notifier.clone(), config.clone(), initial_load_done);
+
+        let (tx, rx) = broadcast::channel(16);
+        let handle = tokio::spawn(async move { dispatcher.listen(rx).await });
+
+        let streams: Vec<Stream> = ["alice", "bob", "carol"]
+            .iter()
+            .enumerate()
+            .map(|(i, login)| Stream {
+                user_id: i.to_string(),
+                ..make_stream(login)
+            })
+            .collect();
+        tx.send(StreamsUpdated {
+            streams: streams.clone(),
+            newly_live: streams,
+            category_changes: vec![],
+            ended: vec![],
+            suspended: None,
+        })
+        .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        let sent = notifier.get_notifications();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].title, "3 streamers went live");
+        assert_eq!(sent[0].message, "alice, bob and carol");
+
+        // At the threshold each go-live is notified on its own
+        notifier.clear();
+        tx.send(make_event("dave")).unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert_eq!(notifier.notification_count(), 1);
+        assert_eq!(notifier.get_notifications()[0].title, "dave is now live!");
+
+        handle.abort();
+    }
 }
@@ -1,9 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 
-use crate::config::{StreamerImportance, StreamerSettings};
-use crate::state::{CategoryChange, StreamsUpdated};
+use crate::config::{CategoryNotifications, StreamerSettings};
+use crate::filtering;
+use crate::notification_snooze::NotificationSnoozes;
+use crate::state::{CategoryChange, CategoryStreamsDiff, StreamsUpdated};
 use crate::twitch::Stream;
 
 /// Streams and category changes that should be dispatched to the notifier.
@@ -16,10 +18,15 @@ pub struct NotificationDecision {
 ///
 /// Returns an empty decision when:
 /// - The initial load baseline is not yet complete (avoids startup spam)
-/// - The gap since the previous event exceeds `max_gap_secs` (avoids floods
+/// - The machine was suspended for longer than `max_gap_secs` since the
+///   previous event (`StreamsUpdated::suspended`), or, where suspends aren't
+///   reported, the gap since the previous event exceeds it (avoids floods
 ///   after wake from sleep/suspension)
 ///
-/// Silent and Ignore streamers are always excluded regardless.
+/// Ignore streamers are always excluded. Silent streamers are excluded unless
+/// the stream is in one of `silent_override_categories` (game IDs; pass an
+/// empty set to exclude Silent streamers unconditionally). Category changes
+/// are also dropped for streamers with `notify_on_category_change` off.
 pub fn filter_notifications(
     event: &StreamsUpdated,
     last_event_time: Option<DateTime<Utc>>,
@@ -27,6 +34,7 @@ pub fn filter_notifications(
     max_gap_secs: u64,
     initial_load_done: bool,
     settings: &HashMap<String, StreamerSettings>,
+    silent_override_categories: &HashSet<String>,
 ) -> NotificationDecision {
     let empty = NotificationDecision {
         streams_to_notify: Vec::new(),
@@ -38,6 +46,19 @@ pub fn filter_notifications(
         return empty;
     }
 
+    // Suppress the first update after a long suspend the OS reported, however
+    // soon after waking it was polled.
+    if let Some(suspended) = event.suspended {
+        if suspended.num_seconds() > max_gap_secs as i64 {
+            tracing::info!(
+                "Suppressing notifications: suspended for {}s, over the max of {}s",
+                suspended.num_seconds(),
+                max_gap_secs
+            );
+            return empty;
+        }
+    }
+
     // Suppress everything if the gap since the last event exceeds the threshold.
     // This protects against notification floods on wake from sleep/suspension.
     if let Some(last) = last_event_time {
@@ -52,26 +73,32 @@ pub fn filter_notifications(
         }
     }
 
-    // Filter by streamer importance — Silent and Ignore streamers are never notified.
-    let is_silent_or_ignored = |user_login: &str| -> bool {
-        let importance = settings
-            .get(user_login)
-            .map(|s| s.importance)
-            .unwrap_or_default();
-        importance == StreamerImportance::Silent || importance == StreamerImportance::Ignore
+    // Filter by streamer importance. For category changes `stream` already
+    // carries the new category, so the override applies to switching into one.
+    let should_notify = |stream: &Stream| -> bool {
+        filtering::notifies(
+            &stream.user_login,
+            settings,
+            silent_override_categories.contains(&stream.game_id),
+        )
     };
 
     let streams_to_notify = event
         .newly_live
         .iter()
-        .filter(|s| !is_silent_or_ignored(&s.user_login))
+        .filter(|s| should_notify(s))
         .cloned()
         .collect();
 
     let categories_to_notify = event
         .category_changes
         .iter()
-        .filter(|c| !is_silent_or_ignored(&c.stream.user_login))
+        .filter(|c| should_notify(&c.stream))
+        .filter(|c| {
+            settings
+                .get(&c.stream.user_login)
+                .is_none_or(|s| s.notify_on_category_change)
+        })
         .cloned()
         .collect();
 
@@ -81,10 +108,191 @@ pub fn filter_notifications(
     }
 }
 
+/// Drops notifications for streams in muted categories (game IDs), except
+/// Favourites'. A category change is judged by the category switched into.
+pub fn mute_categories(
+    decision: &mut NotificationDecision,
+    muted: &HashSet<String>,
+    settings: &HashMap<String, StreamerSettings>,
+) {
+    if muted.is_empty() {
+        return;
+    }
+    let keep = |s: &Stream| {
+        !muted.contains(&s.game_id) || filtering::ignores_category_mute(&s.user_login, settings)
+    };
+    decision.streams_to_notify.retain(|s| keep(s));
+    decision.categories_to_notify.retain(|c| keep(&c.stream));
+}
+
+/// Drops go-live and category-change notifications snoozed at `now`.
+pub fn drop_snoozed(
+    decision: &mut NotificationDecision,
+    snoozes: &NotificationSnoozes,
+    now: DateTime<Utc>,
+) {
+    decision
+        .streams_to_notify
+        .retain(|s| !snoozes.is_snoozed(&s.user_login, now));
+    decision
+        .categories_to_notify
+        .retain(|c| !snoozes.is_snoozed(&c.stream.user_login, now));
+}
+
+/// Drops go-lives for broadcasts in `previously_live` (`(user_id, started_at)`
+/// pairs): they were live before the app restarted, so aren't news.
+pub fn drop_previously_live(
+    decision: &mut NotificationDecision,
+    previously_live: &HashSet<(String, i64)>,
+) {
+    if previously_live.is_empty() {
+        return;
+    }
+    decision
+        .streams_to_notify
+        .retain(|s| !previously_live.contains(&(s.user_id.clone(), s.started_at.timestamp())));
+}
+
+/// Last go-live notification per `user_id`: when it was sent and the `game_id` at the time.
+pub type RecentGoLive = HashMap<String, (DateTime<Utc>, String)>;
+
+/// Drops go-live notifications that look like a stream restart.
+///
+/// A connection blip makes Twitch issue a new stream, so the streamer shows up
+/// as newly live again. A stream is suppressed when the same `user_id` was
+/// notified within `window` and is still in the same category. Suppressed
+/// restarts refresh the timestamp, so repeated blips stay quiet for as long as
+/// they keep recurring within the window. A zero `window` disables suppression.
+pub fn suppress_restarts(
+    streams: Vec<Stream>,
+    recent: &mut RecentGoLive,
+    now: DateTime<Utc>,
+    window: Duration,
+) -> Vec<Stream> {
+    if window <= Duration::zero() {
+        return streams;
+    }
+
+    recent.retain(|_, (at, _)| now - *at <= window);
+
+    streams
+        .into_iter()
+        .filter(|s| {
+            let is_restart = recent
+                .get(&s.user_id)
+                .is_some_and(|(_, game_id)| *game_id == s.game_id);
+            if is_restart {
+                tracing::info!(
+                    "Suppressing go-live notification for {}: restarted within {}m",
+                    s.user_login,
+                    window.num_minutes()
+                );
+            }
+            recent.insert(s.user_id.clone(), (now, s.game_id.clone()));
+            !is_restart
+        })
+        .collect()
+}
+
+/// Streams that just showed up in a followed category and are worth a
+/// small stream notification: under `max_viewers`, not Ignored, and not a
+/// followed channel (`followed_user_ids`), which gets its own go-live.
+pub fn small_streams(
+    arrived: Vec<Stream>,
+    max_viewers: u32,
+    settings: &HashMap<String, StreamerSettings>,
+    followed_user_ids: &HashSet<String>,
+) -> Vec<Stream> {
+    arrived
+        .into_iter()
+        .filter(|s| s.viewer_count < max_viewers)
+        .filter(|s| !followed_user_ids.contains(&s.user_id))
+        .filter(|s| filtering::notifies(&s.user_login, settings, false))
+        .collect()
+}
+
+/// Why a stream in a followed category is worth a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CategoryAlert {
+    /// Entered the category's top this many by viewers
+    TopStreams(usize),
+    /// A Favourite showed up in the category
+    Favourite,
+}
+
+/// Streams in a followed category that its `notifications` opt in to
+/// alerts for, one alert per streamer (a Favourite's rather than a top
+/// stream's). Silent and Ignored streamers get none.
+pub fn category_alerts(
+    diff: &CategoryStreamsDiff,
+    notifications: &CategoryNotifications,
+    settings: &HashMap<String, StreamerSettings>,
+) -> Vec<(Stream, CategoryAlert)> {
+    let mut alerts: Vec<(Stream, CategoryAlert)> = Vec::new();
+    if notifications.favourites {
+        alerts.extend(
+            diff.arrived()
+                .into_iter()
+                .filter(|s| filtering::is_favourite(&s.user_login, settings))
+                .map(|s| (s, CategoryAlert::Favourite)),
+        );
+    }
+    if let Some(n) = notifications.top_streams {
+        for stream in diff.entered_top(n) {
+            let seen = alerts.iter().any(|(s, _)| s.user_id == stream.user_id);
+            if !seen && filtering::notifies(&stream.user_login, settings, false) {
+                alerts.push((stream, CategoryAlert::TopStreams(n)));
+            }
+        }
+    }
+    alerts
+}
+
+/// Names shown in a go-live summary before the rest are counted.
+const SUMMARY_NAMES: usize = 3;
+
+/// Adds go-live notifications held back during quiet hours, one per streamer.
+pub fn hold_for_summary(held: &mut Vec<Stream>, streams: Vec<Stream>) {
+    for stream in streams {
+        held.retain(|s| s.user_id != stream.user_id);
+        held.push(stream);
+    }
+}
+
+/// "Alice, Bob, Carol and 2 more", naming the first few streamers and
+/// counting the rest. `None` if there are none.
+pub fn went_live_names(streams: &[Stream]) -> Option<String> {
+    let names: Vec<&str> = streams
+        .iter()
+        .take(SUMMARY_NAMES)
+        .map(|s| s.user_name.as_str())
+        .collect();
+    let more = streams.len().saturating_sub(SUMMARY_NAMES);
+    let listed = match (names.as_slice(), more) {
+        ([], _) => return None,
+        ([only], 0) => (*only).to_string(),
+        ([rest @ .., last], 0) => format!("{} and {last}", rest.join(", ")),
+        (all, more) => format!("{} and {more} more", all.join(", ")),
+    };
+    Some(listed)
+}
+
+/// "Alice, Bob and Carol went live", for the streams held during quiet
+/// hours. `None` if nothing was held.
+pub fn quiet_hours_summary(held: &[Stream]) -> Option<String> {
+    went_live_names(held).map(|names| format!("{names} went live"))
+}
+
+/// Whether one refresh's go-lives should be sent as a single summary:
+/// there are more than `threshold` of them, and `threshold` isn't 0.
+pub fn should_batch(count: usize, threshold: usize) -> bool {
+    threshold > 0 && count > threshold
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::StreamerSettings;
+    use crate::config::{StreamerImportance, StreamerSettings};
     use chrono::Duration;
 
     fn make_stream(user_login: &str) -> Stream {
@@ -112,6 +320,8 @@ mod tests {
             streams: newly_live.clone(),
             newly_live,
             category_changes,
+            ended: vec![],
+            suspended: None,
         }
     }
 
@@ -126,6 +336,11 @@ mod tests {
                 display_name: user_login.to_string(),
                 importance,
                 hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: None,
+                open_with: None,
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
             },
         );
         map
@@ -137,7 +352,15 @@ mod tests {
     fn notifications_suppressed_during_initial_load() {
         let event = make_event(vec![make_stream("streamer")], vec![]);
         let now = Utc::now();
-        let decision = filter_notifications(&event, None, now, 600, false, &HashMap::new());
+        let decision = filter_notifications(
+            &event,
+            None,
+            now,
+            600,
+            false,
+            &HashMap::new(),
+            &HashSet::new(),
+        );
         assert!(decision.streams_to_notify.is_empty());
         assert!(decision.categories_to_notify.is_empty());
     }
@@ -146,7 +369,15 @@ mod tests {
     fn notifications_allowed_after_initial_load() {
         let event = make_event(vec![make_stream("streamer")], vec![]);
         let now = Utc::now();
-        let decision = filter_notifications(&event, None, now, 600, true, &HashMap::new());
+        let decision = filter_notifications(
+            &event,
+            None,
+            now,
+            600,
+            true,
+            &HashMap::new(),
+            &HashSet::new(),
+        );
         assert_eq!(decision.streams_to_notify.len(), 1);
     }
 
@@ -157,7 +388,15 @@ mod tests {
         let event = make_event(vec![make_stream("streamer")], vec![]);
         let now = Utc::now();
         // last_event_time = None → no gap to check
-        let decision = filter_notifications(&event, None, now, 600, true, &HashMap::new());
+        let decision = filter_notifications(
+            &event,
+            None,
+            now,
+            600,
+            true,
+            &HashMap::new(),
+            &HashSet::new(),
+        );
         assert_eq!(decision.streams_to_notify.len(), 1);
     }
 
@@ -166,7 +405,15 @@ mod tests {
         let event = make_event(vec![make_stream("streamer")], vec![]);
         let now = Utc::now();
         let last = now - Duration::seconds(60); // 60s ago, within 600s limit
-        let decision = filter_notifications(&event, Some(last), now, 600, true, &HashMap::new());
+        let decision = filter_notifications(
+            &event,
+            Some(last),
+            now,
+            600,
+            true,
+            &HashMap::new(),
+            &HashSet::new(),
+        );
         assert_eq!(decision.streams_to_notify.len(), 1);
     }
 
@@ -175,7 +422,15 @@ mod tests {
         let event = make_event(vec![make_stream("streamer")], vec![]);
         let now = Utc::now();
         let last = now - Duration::seconds(600); // exactly at 600s limit — not suppressed
-        let decision = filter_notifications(&event, Some(last), now, 600, true, &HashMap::new());
+        let decision = filter_notifications(
+            &event,
+            Some(last),
+            now,
+            600,
+            true,
+            &HashMap::new(),
+            &HashSet::new(),
+        );
         assert_eq!(decision.streams_to_notify.len(), 1);
     }
 
@@ -184,16 +439,55 @@ mod tests {
         let event = make_event(vec![make_stream("streamer")], vec![]);
         let now = Utc::now();
         let last = now - Duration::seconds(601); // 1s over limit
-        let decision = filter_notifications(&event, Some(last), now, 600, true, &HashMap::new());
+        let decision = filter_notifications(
+            &event,
+            Some(last),
+            now,
+            600,
+            true,
+            &HashMap::new(),
+            &HashSet::new(),
+        );
         assert!(decision.streams_to_notify.is_empty());
     }
 
+    #[test]
+    fn notifications_suppressed_after_reported_suspend() {
+        let now = Utc::now();
+        let filter = |suspended| {
+            let event = StreamsUpdated {
+                suspended: Some(suspended),
+                ..make_event(vec![make_stream("streamer")], vec![])
+            };
+            // No previous event, so only the reported suspend counts
+            filter_notifications(
+                &event,
+                None,
+                now,
+                600,
+                true,
+                &HashMap::new(),
+                &HashSet::new(),
+            )
+        };
+        assert!(filter(Duration::seconds(601)).streams_to_notify.is_empty());
+        assert_eq!(filter(Duration::seconds(60)).streams_to_notify.len(), 1);
+    }
+
     #[test]
     fn very_long_gap_suppresses() {
         let event = make_event(vec![make_stream("streamer")], vec![]);
         let now = Utc::now();
         let last = now - Duration::hours(8);
-        let decision = filter_notifications(&event, Some(last), now, 600, true, &HashMap::new());
+        let decision = filter_notifications(
+            &event,
+            Some(last),
+            now,
+            600,
+            true,
+            &HashMap::new(),
+            &HashSet::new(),
+        );
         assert!(decision.streams_to_notify.is_empty());
     }
 
@@ -202,7 +496,15 @@ mod tests {
         let event = make_event(vec![make_stream("streamer")], vec![]);
         let now = Utc::now();
         let last = now - Duration::seconds(180); // 3 min > 2 min limit
-        let decision = filter_notifications(&event, Some(last), now, 120, true, &HashMap::new());
+        let decision = filter_notifications(
+            &event,
+            Some(last),
+            now,
+            120,
+            true,
+            &HashMap::new(),
+            &HashSet::new(),
+        );
         assert!(decision.streams_to_notify.is_empty());
     }
 
@@ -213,7 +515,8 @@ mod tests {
         let event = make_event(vec![make_stream("quietstreamer")], vec![]);
         let now = Utc::now();
         let settings = settings_with("quietstreamer", StreamerImportance::Silent);
-        let decision = filter_notifications(&event, None, now, 600, true, &settings);
+        let decision =
+            filter_notifications(&event, None, now, 600, true, &settings, &HashSet::new());
         assert!(decision.streams_to_notify.is_empty());
     }
 
@@ -222,7 +525,8 @@ mod tests {
         let event = make_event(vec![make_stream("ignoredstreamer")], vec![]);
         let now = Utc::now();
         let settings = settings_with("ignoredstreamer", StreamerImportance::Ignore);
-        let decision = filter_notifications(&event, None, now, 600, true, &settings);
+        let decision =
+            filter_notifications(&event, None, now, 600, true, &settings, &HashSet::new());
         assert!(decision.streams_to_notify.is_empty());
     }
 
@@ -231,7 +535,8 @@ mod tests {
         let event = make_event(vec![make_stream("normalstreamer")], vec![]);
         let now = Utc::now();
         let settings = settings_with("normalstreamer", StreamerImportance::Normal);
-        let decision = filter_notifications(&event, None, now, 600, true, &settings);
+        let decision =
+            filter_notifications(&event, None, now, 600, true, &settings, &HashSet::new());
         assert_eq!(decision.streams_to_notify.len(), 1);
     }
 
@@ -240,7 +545,8 @@ mod tests {
         let event = make_event(vec![make_stream("favstreamer")], vec![]);
         let now = Utc::now();
         let settings = settings_with("favstreamer", StreamerImportance::Favourite);
-        let decision = filter_notifications(&event, None, now, 600, true, &settings);
+        let decision =
+            filter_notifications(&event, None, now, 600, true, &settings, &HashSet::new());
         assert_eq!(decision.streams_to_notify.len(), 1);
     }
 
@@ -249,7 +555,15 @@ mod tests {
         let event = make_event(vec![make_stream("unknownstreamer")], vec![]);
         let now = Utc::now();
         // No settings entry → defaults to Normal → included
-        let decision = filter_notifications(&event, None, now, 600, true, &HashMap::new());
+        let decision = filter_notifications(
+            &event,
+            None,
+            now,
+            600,
+            true,
+            &HashMap::new(),
+            &HashSet::new(),
+        );
         assert_eq!(decision.streams_to_notify.len(), 1);
     }
 
@@ -263,10 +577,37 @@ mod tests {
         let event = make_event(vec![], vec![change]);
         let now = Utc::now();
         let settings = settings_with("quietstreamer", StreamerImportance::Silent);
-        let decision = filter_notifications(&event, None, now, 600, true, &settings);
+        let decision =
+            filter_notifications(&event, None, now, 600, true, &settings, &HashSet::new());
         assert!(decision.categories_to_notify.is_empty());
     }
 
+    #[test]
+    fn category_changes_off_per_streamer_keeps_go_live() {
+        let stream = make_stream("wanderer");
+        let change = CategoryChange {
+            stream: stream.clone(),
+            old_category: "Old Game".to_string(),
+        };
+        let event = make_event(vec![stream], vec![change]);
+        let mut settings = settings_with("wanderer", StreamerImportance::Favourite);
+        settings
+            .get_mut("wanderer")
+            .unwrap()
+            .notify_on_category_change = false;
+        let decision = filter_notifications(
+            &event,
+            None,
+            Utc::now(),
+            600,
+            true,
+            &settings,
+            &HashSet::new(),
+        );
+        assert!(decision.categories_to_notify.is_empty());
+        assert_eq!(decision.streams_to_notify.len(), 1);
+    }
+
     #[test]
     fn mixed_importance_only_normal_notified() {
         let silent = make_stream("silentone");
@@ -280,10 +621,385 @@ mod tests {
                 display_name: "silentone".to_string(),
                 importance: StreamerImportance::Silent,
                 hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: None,
+                open_with: None,
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
             },
         );
-        let decision = filter_notifications(&event, None, now, 600, true, &settings);
+        let decision =
+            filter_notifications(&event, None, now, 600, true, &settings, &HashSet::new());
         assert_eq!(decision.streams_to_notify.len(), 1);
         assert_eq!(decision.streams_to_notify[0].user_login, "normalone");
     }
+
+    // === Followed-category override for Silent streamers ===
+
+    fn override_for(game_id: &str) -> HashSet<String> {
+        HashSet::from([game_id.to_string()])
+    }
+
+    #[test]
+    fn silent_streamer_notified_when_live_in_override_category() {
+        let event = make_event(vec![make_stream("quietstreamer")], vec![]);
+        let settings = settings_with("quietstreamer", StreamerImportance::Silent);
+        let decision = filter_notifications(
+            &event,
+            None,
+            Utc::now(),
+            600,
+            true,
+            &settings,
+            &override_for("game"),
+        );
+        assert_eq!(decision.streams_to_notify.len(), 1);
+    }
+
+    #[test]
+    fn silent_streamer_excluded_outside_override_category() {
+        let event = make_event(vec![make_stream("quietstreamer")], vec![]);
+        let settings = settings_with("quietstreamer", StreamerImportance::Silent);
+        let decision = filter_notifications(
+            &event,
+            None,
+            Utc::now(),
+            600,
+            true,
+            &settings,
+            &override_for("factorio"),
+        );
+        assert!(decision.streams_to_notify.is_empty());
+    }
+
+    #[test]
+    fn silent_streamer_switching_into_override_category_notified() {
+        let mut stream = make_stream("quietstreamer");
+        stream.game_id = "factorio".to_string();
+        let change = CategoryChange {
+            stream,
+            old_category: "Just Chatting".to_string(),
+        };
+        let event = make_event(vec![], vec![change]);
+        let settings = settings_with("quietstreamer", StreamerImportance::Silent);
+        let decision = filter_notifications(
+            &event,
+            None,
+            Utc::now(),
+            600,
+            true,
+            &settings,
+            &override_for("factorio"),
+        );
+        assert_eq!(decision.categories_to_notify.len(), 1);
+    }
+
+    #[test]
+    fn ignored_streamer_excluded_even_in_override_category() {
+        let event = make_event(vec![make_stream("ignoredstreamer")], vec![]);
+        let settings = settings_with("ignoredstreamer", StreamerImportance::Ignore);
+        let decision = filter_notifications(
+            &event,
+            None,
+            Utc::now(),
+            600,
+            true,
+            &settings,
+            &override_for("game"),
+        );
+        assert!(decision.streams_to_notify.is_empty());
+    }
+
+    // === Muted categories ===
+
+    #[test]
+    fn muted_category_drops_live_and_category_change_notifications() {
+        let mut moved = make_stream("mover");
+        moved.game_id = "muted".to_string();
+        let mut live = make_stream("live");
+        live.game_id = "muted".to_string();
+        let mut fave = make_stream("fave");
+        fave.game_id = "muted".to_string();
+        let mut decision = NotificationDecision {
+            streams_to_notify: vec![live, make_stream("elsewhere"), fave],
+            categories_to_notify: vec![CategoryChange {
+                stream: moved,
+                old_category: "Game".to_string(),
+            }],
+        };
+        let settings = settings_with("fave", StreamerImportance::Favourite);
+
+        mute_categories(&mut decision, &override_for("muted"), &settings);
+
+        let logins: Vec<&str> = decision
+            .streams_to_notify
+            .iter()
+            .map(|s| s.user_login.as_str())
+            .collect();
+        assert_eq!(logins, vec!["elsewhere", "fave"]);
+        assert!(decision.categories_to_notify.is_empty());
+    }
+
+    #[test]
+    fn snoozed_streamers_are_dropped_until_the_snooze_ends() {
+        let now = Utc::now();
+        let mut moved = make_stream("mover");
+        moved.game_id = "other".to_string();
+        let decision = || NotificationDecision {
+            streams_to_notify: vec![make_stream("snoozed"), make_stream("awake")],
+            categories_to_notify: vec![CategoryChange {
+                stream: moved.clone(),
+                old_category: "Game".to_string(),
+            }],
+        };
+        let mut snoozes = NotificationSnoozes::default();
+        snoozes
+            .streamers
+            .insert("snoozed".to_string(), now + Duration::hours(1));
+
+        let mut one = decision();
+        drop_snoozed(&mut one, &snoozes, now);
+        assert_eq!(one.streams_to_notify.len(), 1);
+        assert_eq!(one.streams_to_notify[0].user_login, "awake");
+        assert_eq!(one.categories_to_notify.len(), 1);
+
+        snoozes.all_until = Some(now + Duration::hours(1));
+        let mut all = decision();
+        drop_snoozed(&mut all, &snoozes, now);
+        assert!(all.streams_to_notify.is_empty());
+        assert!(all.categories_to_notify.is_empty());
+
+        let mut expired = decision();
+        drop_snoozed(&mut expired, &snoozes, now + Duration::hours(2));
+        assert_eq!(expired.streams_to_notify.len(), 2);
+    }
+
+    // === Restart suppression ===
+
+    #[test]
+    fn first_go_live_is_notified_and_recorded() {
+        let mut recent = RecentGoLive::new();
+        let now = Utc::now();
+        let out = suppress_restarts(
+            vec![make_stream("streamer")],
+            &mut recent,
+            now,
+            Duration::minutes(15),
+        );
+        assert_eq!(out.len(), 1);
+        assert_eq!(recent.get("100"), Some(&(now, "game".to_string())));
+    }
+
+    #[test]
+    fn restart_within_window_same_category_suppressed() {
+        let mut recent = RecentGoLive::new();
+        let now = Utc::now();
+        recent.insert(
+            "100".to_string(),
+            (now - Duration::minutes(5), "game".to_string()),
+        );
+        let out = suppress_restarts(
+            vec![make_stream("streamer")],
+            &mut recent,
+            now,
+            Duration::minutes(15),
+        );
+        assert!(out.is_empty());
+        // Timestamp refreshed so a further blip stays quiet
+        assert_eq!(recent["100"].0, now);
+    }
+
+    #[test]
+    fn restart_in_new_category_notified() {
+        let mut recent = RecentGoLive::new();
+        let now = Utc::now();
+        recent.insert(
+            "100".to_string(),
+            (now - Duration::minutes(5), "other_game".to_string()),
+        );
+        let out = suppress_restarts(
+            vec![make_stream("streamer")],
+            &mut recent,
+            now,
+            Duration::minutes(15),
+        );
+        assert_eq!(out.len(), 1);
+    }
+
+    #[test]
+    fn go_live_after_window_notified() {
+        let mut recent = RecentGoLive::new();
+        let now = Utc::now();
+        recent.insert(
+            "100".to_string(),
+            (now - Duration::minutes(16), "game".to_string()),
+        );
+        let out = suppress_restarts(
+            vec![make_stream("streamer")],
+            &mut recent,
+            now,
+            Duration::minutes(15),
+        );
+        assert_eq!(out.len(), 1);
+    }
+
+    #[test]
+    fn zero_window_disables_restart_suppression() {
+        let mut recent = RecentGoLive::new();
+        let now = Utc::now();
+        recent.insert("100".to_string(), (now, "game".to_string()));
+        let out = suppress_restarts(
+            vec![make_stream("streamer")],
+            &mut recent,
+            now,
+            Duration::zero(),
+        );
+        assert_eq!(out.len(), 1);
+    }
+
+    // === Previously live ===
+
+    #[test]
+    fn broadcasts_live_before_restart_not_notified() {
+        let before = make_stream("before");
+        let restarted = Stream {
+            user_id: "200".to_string(),
+            ..make_stream("restarted")
+        };
+        let previously_live: HashSet<(String, i64)> = [
+            (before.user_id.clone(), before.started_at.timestamp()),
+            // Same streamer, earlier broadcast
+            (
+                restarted.user_id.clone(),
+                restarted.started_at.timestamp() - 3600,
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let mut decision = NotificationDecision {
+            streams_to_notify: vec![before, restarted],
+            categories_to_notify: vec![],
+        };
+
+        drop_previously_live(&mut decision, &previously_live);
+        assert_eq!(decision.streams_to_notify.len(), 1);
+        assert_eq!(decision.streams_to_notify[0].user_login, "restarted");
+    }
+
+    // === Small streams in followed categories ===
+
+    #[test]
+    fn small_streams_under_threshold_from_unfollowed_channels() {
+        let viewers = |login: &str, user_id: &str, viewer_count: u32| Stream {
+            user_id: user_id.to_string(),
+            viewer_count,
+            ..make_stream(login)
+        };
+        let arrived = vec![
+            viewers("tiny", "1", 12),
+            viewers("big", "2", 5000),
+            viewers("followed", "3", 40),
+            viewers("ignored", "4", 8),
+            viewers("quiet", "5", 8),
+        ];
+        let followed: HashSet<String> = ["3".to_string()].into_iter().collect();
+        let mut settings = settings_with("ignored", StreamerImportance::Ignore);
+        settings.extend(settings_with("quiet", StreamerImportance::Silent));
+
+        let out = small_streams(arrived, 200, &settings, &followed);
+        let logins: Vec<&str> = out.iter().map(|s| s.user_login.as_str()).collect();
+        assert_eq!(logins, vec!["tiny"]);
+    }
+
+    #[test]
+    fn category_alerts_for_favourites_and_new_top_streams() {
+        let viewers = |login: &str, user_id: &str, viewer_count: u32| Stream {
+            user_id: user_id.to_string(),
+            viewer_count,
+            ..make_stream(login)
+        };
+        let diff = CategoryStreamsDiff {
+            previous: Some(vec![viewers("leader", "1", 900), viewers("old", "2", 50)]),
+            current: vec![
+                viewers("leader", "1", 900),
+                viewers("fave", "3", 800),
+                viewers("climber", "4", 700),
+                viewers("ignored", "5", 600),
+                viewers("quiet", "6", 500),
+                viewers("old", "2", 50),
+            ],
+        };
+        let mut settings = settings_with("fave", StreamerImportance::Favourite);
+        settings.extend(settings_with("ignored", StreamerImportance::Ignore));
+        settings.extend(settings_with("quiet", StreamerImportance::Silent));
+
+        assert!(category_alerts(&diff, &CategoryNotifications::default(), &settings).is_empty());
+
+        let notifications = CategoryNotifications {
+            top_streams: Some(5),
+            favourites: true,
+            ..Default::default()
+        };
+        let alerts = category_alerts(&diff, &notifications, &settings);
+        let alerts: Vec<(&str, CategoryAlert)> = alerts
+            .iter()
+            .map(|(s, a)| (s.user_login.as_str(), *a))
+            .collect();
+        assert_eq!(
+            alerts,
+            vec![
+                ("fave", CategoryAlert::Favourite),
+                ("climber", CategoryAlert::TopStreams(5)),
+            ]
+        );
+    }
+
+    // === Quiet hours summary ===
+
+    fn named(user_id: &str, name: &str) -> Stream {
+        Stream {
+            user_id: user_id.to_string(),
+            user_name: name.to_string(),
+            ..make_stream(&name.to_lowercase())
+        }
+    }
+
+    #[test]
+    fn held_streams_are_one_per_streamer() {
+        let mut held = Vec::new();
+        hold_for_summary(&mut held, vec![named("1", "Alice"), named("2", "Bob")]);
+        hold_for_summary(&mut held, vec![named("1", "Alice")]);
+
+        let names: Vec<&str> = held.iter().map(|s| s.user_name.as_str()).collect();
+        assert_eq!(names, vec!["Bob", "Alice"]);
+    }
+
+    #[test]
+    fn summary_lists_names_then_counts_the_rest() {
+        assert_eq!(quiet_hours_summary(&[]), None);
+        assert_eq!(
+            quiet_hours_summary(&[named("1", "Alice")]).unwrap(),
+            "Alice went live"
+        );
+        assert_eq!(
+            quiet_hours_summary(&[named("1", "Alice"), named("2", "Bob")]).unwrap(),
+            "Alice and Bob went live"
+        );
+        let five: Vec<Stream> = ["Alice", "Bob", "Carol", "Dan", "Eve"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| named(&i.to_string(), name))
+            .collect();
+        assert_eq!(
+            quiet_hours_summary(&five).unwrap(),
+            "Alice, Bob, Carol and 2 more went live"
+        );
+    }
+
+    #[test]
+    fn batching_needs_more_than_the_threshold() {
+        assert!(!should_batch(3, 3));
+        assert!(should_batch(4, 3));
+        assert!(!should_batch(50, 0));
+    }
 }
@@ -0,0 +1,143 @@
+//! Snoozing notifications from the tray, for everyone or one streamer, until
+//! an expiry time. Snoozes live in `AppState` only, so a restart clears them.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// "Until tomorrow" snoozes end at this hour, local time.
+const TOMORROW_HOUR: u32 = 8;
+
+/// How long a snooze from the tray lasts.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnoozeDuration {
+    OneHour,
+    EightHours,
+    /// Until 8am tomorrow, local time
+    UntilTomorrow,
+}
+
+impl SnoozeDuration {
+    /// In the order the menu lists them.
+    pub const ALL: [Self; 3] = [Self::OneHour, Self::EightHours, Self::UntilTomorrow];
+
+    /// Menu item ID suffix.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::OneHour => "1h",
+            Self::EightHours => "8h",
+            Self::UntilTomorrow => "tomorrow",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.key() == key)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::OneHour => "For 1 hour",
+            Self::EightHours => "For 8 hours",
+            Self::UntilTomorrow => "Until tomorrow",
+        }
+    }
+
+    /// When a snooze started at `now` ends.
+    pub fn until<Tz: TimeZone>(self, now: &DateTime<Tz>) -> DateTime<Utc> {
+        match self {
+            Self::OneHour => now.to_utc() + Duration::hours(1),
+            Self::EightHours => now.to_utc() + Duration::hours(8),
+            Self::UntilTomorrow => {
+                let tomorrow = now.date_naive() + Duration::days(1);
+                let morning =
+                    tomorrow.and_time(NaiveTime::MIN + Duration::hours(TOMORROW_HOUR as i64));
+                now.timezone()
+                    .from_local_datetime(&morning)
+                    .earliest()
+                    // 8am skipped by a DST change: a day from now instead
+                    .map_or_else(|| now.to_utc() + Duration::days(1), |t| t.to_utc())
+            }
+        }
+    }
+}
+
+/// Active snoozes and when they end.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotificationSnoozes {
+    /// Every notification is snoozed until then
+    pub all_until: Option<DateTime<Utc>>,
+    /// Snoozed streamers by login
+    pub streamers: HashMap<String, DateTime<Utc>>,
+}
+
+impl NotificationSnoozes {
+    /// Whether notifications about `user_login` are snoozed at `now`.
+    pub fn is_snoozed(&self, user_login: &str, now: DateTime<Utc>) -> bool {
+        self.all_until.is_some_and(|until| now < until)
+            || self
+                .streamers
+                .get(user_login)
+                .is_some_and(|until| now < *until)
+    }
+
+    /// Forgets snoozes that ended by `now`.
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        self.all_until = self.all_until.filter(|until| now < *until);
+        self.streamers.retain(|_, until| now < *until);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Timelike};
+
+    #[test]
+    fn keys_round_trip() {
+        for duration in SnoozeDuration::ALL {
+            assert_eq!(SnoozeDuration::from_key(duration.key()), Some(duration));
+        }
+        assert_eq!(SnoozeDuration::from_key("2h"), None);
+    }
+
+    #[test]
+    fn until_tomorrow_ends_next_morning_local_time() {
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        let late = tz.with_ymd_and_hms(2026, 3, 10, 23, 30, 0).unwrap();
+        let until = SnoozeDuration::UntilTomorrow.until(&late);
+        let local = until.with_timezone(&tz);
+        assert_eq!(local.date_naive(), late.date_naive() + Duration::days(1));
+        assert_eq!(local.hour(), 8);
+
+        assert_eq!(
+            SnoozeDuration::EightHours.until(&late),
+            late.to_utc() + Duration::hours(8)
+        );
+    }
+
+    #[test]
+    fn snoozes_cover_everyone_or_one_streamer_until_they_end() {
+        let now = Utc::now();
+        let mut snoozes = NotificationSnoozes::default();
+        snoozes
+            .streamers
+            .insert("ninja".to_string(), now + Duration::hours(1));
+        snoozes
+            .streamers
+            .insert("shroud".to_string(), now - Duration::minutes(1));
+
+        assert!(snoozes.is_snoozed("ninja", now));
+        assert!(!snoozes.is_snoozed("shroud", now));
+        assert!(!snoozes.is_snoozed("pokimane", now));
+
+        snoozes.all_until = Some(now + Duration::minutes(5));
+        assert!(snoozes.is_snoozed("pokimane", now));
+        assert!(!snoozes.is_snoozed("pokimane", now + Duration::minutes(5)));
+
+        snoozes.prune(now + Duration::minutes(5));
+        assert_eq!(snoozes.all_until, None);
+        assert_eq!(snoozes.streamers.keys().collect::<Vec<_>>(), vec!["ninja"]);
+    }
+}
@@ -3,14 +3,32 @@
 //! This module provides notification functionality with a trait-based
 //! abstraction for testability.
 
-use chrono::{DateTime, Duration, Utc};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use tokio::sync::mpsc;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
+use crate::config::{
+    Config, ConfigManager, NotificationOpenMode, NotificationSound, NotificationUrgency, OpenWith,
+};
 use crate::hotness_detection::HotnessInfo;
-use crate::twitch::Stream;
+use crate::notification_filter::CategoryAlert;
+use crate::thumbnail_cache::ThumbnailCache;
+use crate::twitch::{ScheduledStream, Stream};
 
 const APP_NAME: &str = "Twitch Tray";
+#[cfg(all(
+    any(target_os = "linux", target_os = "macos"),
+    feature = "desktop-notifications"
+))]
 const NOTIFICATION_TIMEOUT_MS: i32 = 10_000;
+#[cfg(all(
+    any(target_os = "linux", target_os = "macos"),
+    feature = "desktop-notifications"
+))]
 const SNOOZE_DURATION_MIN: i64 = 10;
 
 /// A request to snooze a stream notification and re-notify after a delay
@@ -28,7 +46,206 @@ pub struct StreamerSettingsRequest {
     pub display_name: String,
 }
 
+/// What a notification's "Open Stream" action launches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenTarget {
+    /// Opened in the default browser.
+    Url(String),
+    /// Run through the platform shell.
+    Command(String),
+}
+
+impl OpenTarget {
+    /// Resolves the target for `stream`, preferring the streamer's own
+    /// `notification_open_mode` over the global one.
+    ///
+    /// A `Command` mode with no `pip_command` set falls back to the popout player.
+    pub fn for_stream(config: &Config, stream: &Stream) -> Self {
+        let mode = config
+            .streamer_settings
+            .get(&stream.user_login)
+            .and_then(|s| s.notification_open_mode)
+            .unwrap_or(config.notification_open_mode);
+        let command = config.pip_command.trim();
+        match mode {
+            NotificationOpenMode::Channel => Self::Url(stream.channel_url()),
+            NotificationOpenMode::Command if !command.is_empty() => {
+                let url = stream.channel_url();
+                Self::Command(if command.contains("{url}") {
+                    command.replace("{url}", &url)
+                } else {
+                    format!("{command} {url}")
+                })
+            }
+            NotificationOpenMode::Popout | NotificationOpenMode::Command => {
+                Self::Url(stream.popout_url())
+            }
+        }
+    }
+
+    /// Resolves what opening `user_login`'s stream from the menu launches,
+    /// preferring the streamer's own `open_with` over the global one.
+    ///
+    /// A blank custom command falls back to the browser.
+    pub fn for_watching(config: &Config, user_login: &str) -> Self {
+        let open_with = config
+            .streamer_settings
+            .get(user_login)
+            .and_then(|s| s.open_with.as_ref())
+            .unwrap_or(&config.open_with);
+        let url = format!("https://twitch.tv/{user_login}");
+        match open_with {
+            OpenWith::Streamlink => {
+                Self::Command(format!("streamlink twitch.tv/{user_login} best"))
+            }
+            OpenWith::Command(template) if !template.trim().is_empty() => {
+                let template = template.trim();
+                Self::Command(
+                    if template.contains("{url}") || template.contains("{login}") {
+                        template
+                            .replace("{url}", &url)
+                            .replace("{login}", user_login)
+                    } else {
+                        format!("{template} {url}")
+                    },
+                )
+            }
+            OpenWith::Browser | OpenWith::Command(_) => Self::Url(url),
+        }
+    }
+
+    /// Opens the URL or starts the command without waiting for it.
+    pub fn launch(&self) -> anyhow::Result<()> {
+        match self {
+            Self::Url(url) => open::that(url)?,
+            Self::Command(command) => crate::custom_action::spawn_shell(command)?,
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for OpenTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Url(target) | Self::Command(target) => f.write_str(target),
+        }
+    }
+}
+
+/// How a notification is presented: its freedesktop category, sound, urgency
+/// and image.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotificationHints {
+    pub category: Option<&'static str>,
+    /// Sound name, or a sound file path (see [`NotificationHints::sound_file`]).
+    pub sound: Option<String>,
+    /// Asks the server not to play its default sound either.
+    pub silent: bool,
+    pub urgency: Option<NotificationUrgency>,
+    /// The stream's saved thumbnail (`ThumbnailCache`), shown where the
+    /// server supports images.
+    pub image: Option<PathBuf>,
+    /// The streamer's saved profile image (`ThumbnailCache::cached_avatar`),
+    /// used as the notification icon on Linux.
+    pub icon: Option<PathBuf>,
+}
+
+impl NotificationHints {
+    /// Hints for a notification about `stream`, taking the sound and urgency
+    /// of the followed category it is live in, if any. The streamer's own
+    /// `notification_sound` wins over the category's sound.
+    pub fn for_stream(config: &Config, stream: &Stream, category: &'static str) -> Self {
+        let prefs = config
+            .followed_categories
+            .iter()
+            .find(|c| c.id == stream.game_id)
+            .map(|c| &c.notifications);
+        let category_sound = prefs.and_then(|p| p.sound.as_deref());
+        let streamer_sound = config
+            .streamer_settings
+            .get(&stream.user_login)
+            .and_then(|s| s.notification_sound.as_ref());
+        let (sound, silent) = match streamer_sound {
+            Some(NotificationSound::Silent) => (None, true),
+            Some(NotificationSound::Custom(sound)) if !sound.trim().is_empty() => {
+                (Some(sound.as_str()), false)
+            }
+            _ => (category_sound, false),
+        };
+        Self {
+            category: Some(category),
+            sound: sound
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+            silent,
+            urgency: prefs.and_then(|p| p.urgency),
+            image: None,
+            icon: None,
+        }
+    }
+
+    /// Hints for a notification about a streamer who isn't live yet, so only
+    /// their own `notification_sound` applies.
+    pub fn for_streamer(config: &Config, user_login: &str, category: &'static str) -> Self {
+        let streamer_sound = config
+            .streamer_settings
+            .get(user_login)
+            .and_then(|s| s.notification_sound.as_ref());
+        let (sound, silent) = match streamer_sound {
+            Some(NotificationSound::Silent) => (None, true),
+            Some(NotificationSound::Custom(sound)) if !sound.trim().is_empty() => {
+                (Some(sound.trim().to_string()), false)
+            }
+            _ => (None, false),
+        };
+        Self {
+            category: Some(category),
+            sound,
+            silent,
+            urgency: None,
+            image: None,
+            icon: None,
+        }
+    }
+
+    /// The sound, if it names a file rather than a theme or system sound.
+    pub fn sound_file(&self) -> Option<&str> {
+        self.sound
+            .as_deref()
+            .filter(|s| s.contains('/') || s.contains('\\'))
+    }
+}
+
+/// A stream's free-text fields cut to fit a notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FittedText {
+    user_name: String,
+    game_name: String,
+    title: String,
+}
+
+impl FittedText {
+    /// Names and games take up to `max_width` columns, titles twice that.
+    fn new(stream: &Stream, max_width: usize) -> Self {
+        Self {
+            user_name: truncate_width(&stream.user_name, max_width),
+            game_name: truncate_width(&stream.game_name, max_width),
+            title: truncate_width(&stream.title, max_width * 2),
+        }
+    }
+}
+
 /// Info needed to attach a snooze button to a notification
+///
+/// Action buttons are only wired up by the native Linux and macOS backends.
+#[cfg_attr(
+    not(all(
+        any(target_os = "linux", target_os = "macos"),
+        feature = "desktop-notifications"
+    )),
+    allow(dead_code)
+)]
 struct SnoozeInfo {
     user_id: String,
     user_name: String,
@@ -36,12 +253,111 @@ struct SnoozeInfo {
 }
 
 /// Info needed to attach a settings button to a notification
+#[cfg_attr(
+    not(all(
+        any(target_os = "linux", target_os = "macos"),
+        feature = "desktop-notifications"
+    )),
+    allow(dead_code)
+)]
 struct SettingsInfo {
     user_login: String,
     display_name: String,
     settings_tx: mpsc::UnboundedSender<StreamerSettingsRequest>,
 }
 
+/// What the platform's notification service supports.
+///
+/// Detected once when the notifier is created. Features that depend on a
+/// capability (action buttons, images) are skipped rather than attempted when
+/// it is missing, and the result is included in the diagnostics report.
+#[allow(clippy::struct_excessive_bools)] // independent capability flags
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct NotificationCapabilities {
+    /// Notification server name (e.g. "dunst", "Plasma"), or a description of
+    /// the fallback in use
+    pub server: String,
+    /// Clickable action buttons ("Open Stream", "Snooze 10m")
+    pub actions: bool,
+    /// Images in the notification body or icon
+    pub images: bool,
+    /// Custom notification sounds
+    pub sounds: bool,
+    /// Urgency levels
+    pub urgency: bool,
+}
+
+impl NotificationCapabilities {
+    /// Builds capabilities from a freedesktop.org `GetCapabilities` response.
+    ///
+    /// Urgency is part of the base spec, so any reachable server supports it.
+    pub fn from_freedesktop(server: &str, caps: &[String]) -> Self {
+        let has = |name: &str| caps.iter().any(|c| c == name);
+        Self {
+            server: server.to_string(),
+            actions: has("actions"),
+            images: has("body-images") || has("icon-static"),
+            sounds: has("sound"),
+            urgency: true,
+        }
+    }
+
+    /// Queries the platform notification service.
+    #[cfg(all(target_os = "linux", feature = "desktop-notifications"))]
+    pub fn detect() -> Self {
+        let server = notify_rust::get_server_information()
+            .map(|info| format!("{} {}", info.name, info.version));
+        match (server, notify_rust::get_capabilities()) {
+            (Ok(server), Ok(caps)) => Self::from_freedesktop(&server, &caps),
+            (Err(e), _) | (_, Err(e)) => {
+                tracing::warn!("Notification server unavailable: {}", e);
+                Self {
+                    server: "unavailable".to_string(),
+                    ..Self::default()
+                }
+            }
+        }
+    }
+
+    /// Notification Center, through `NSUserNotification`. Notifications are
+    /// attributed to the app bundle when there is one, otherwise to Finder.
+    #[cfg(all(target_os = "macos", feature = "desktop-notifications"))]
+    pub fn detect() -> Self {
+        let bundle = notify_rust::get_bundle_identifier_or_default(APP_NAME);
+        if let Err(e) = notify_rust::set_application(&bundle) {
+            tracing::warn!("Failed to set notification application {}: {}", bundle, e);
+        }
+        Self {
+            server: format!("Notification Center ({bundle})"),
+            actions: true,
+            sounds: true,
+            ..Self::default()
+        }
+    }
+
+    /// Queries the platform notification service.
+    #[cfg(not(all(
+        any(target_os = "linux", target_os = "macos"),
+        feature = "desktop-notifications"
+    )))]
+    pub fn detect() -> Self {
+        if cfg!(target_os = "macos") {
+            // Built without `desktop-notifications`: osascript notifications
+            // play the default sound but cannot be clicked
+            Self {
+                server: "osascript".to_string(),
+                sounds: true,
+                ..Self::default()
+            }
+        } else {
+            Self {
+                server: "log only".to_string(),
+                ..Self::default()
+            }
+        }
+    }
+}
+
 /// Trait for sending notifications
 ///
 /// This abstraction allows easy mocking of notifications in tests.
@@ -58,14 +374,43 @@ pub trait Notifier: Send + Sync {
     /// Sends a notification when a stream is detected as "hot"
     fn stream_hot(&self, stream: &Stream, info: &HotnessInfo) -> anyhow::Result<()>;
 
+    /// Sends a reminder that watching `stream` keeps a `streak_len`-stream watch streak going
+    fn streak_reminder(&self, stream: &Stream, streak_len: u32) -> anyhow::Result<()>;
+
+    /// Sends a notification when a stream under a followed category's
+    /// `small_stream_max_viewers` shows up
+    fn small_stream_live(&self, stream: &Stream) -> anyhow::Result<()>;
+
+    /// Sends a notification for a followed category's stream that its
+    /// per-category alerts opted in to
+    fn category_alert(&self, stream: &Stream, alert: CategoryAlert) -> anyhow::Result<()>;
+
+    /// Sends a notification when `stream`'s title matches the title alert `pattern`
+    fn title_matched(&self, stream: &Stream, pattern: &str) -> anyhow::Result<()>;
+
+    /// Sends a reminder that `schedule` starts soon
+    fn scheduled_soon(&self, schedule: &ScheduledStream) -> anyhow::Result<()>;
+
+    /// Sends one notification standing in for several go-lives: those held
+    /// during quiet hours, or too many arriving in one refresh
+    fn went_live_summary(&self, title: &str, message: &str) -> anyhow::Result<()>;
+
     /// Sends an error notification
     fn error(&self, message: &str) -> anyhow::Result<()>;
+
+    /// Returns what the notification service supports
+    fn capabilities(&self) -> NotificationCapabilities;
 }
 
 /// Desktop notification implementation
 pub struct DesktopNotifier {
     snooze_tx: mpsc::UnboundedSender<SnoozeRequest>,
     settings_tx: mpsc::UnboundedSender<StreamerSettingsRequest>,
+    capabilities: NotificationCapabilities,
+    /// Read when each notification is sent, for the open target
+    config: Arc<ConfigManager>,
+    /// Where stream thumbnails are saved, for notification images
+    thumbnails: Arc<ThumbnailCache>,
 }
 
 impl DesktopNotifier {
@@ -77,25 +422,39 @@ impl DesktopNotifier {
     pub fn new(
         snooze_tx: mpsc::UnboundedSender<SnoozeRequest>,
         settings_tx: mpsc::UnboundedSender<StreamerSettingsRequest>,
+        config: Arc<ConfigManager>,
+        thumbnails: Arc<ThumbnailCache>,
     ) -> Self {
+        let capabilities = NotificationCapabilities::detect();
+        tracing::info!("Notification capabilities: {:?}", capabilities);
         Self {
             snooze_tx,
             settings_tx,
+            capabilities,
+            config,
+            thumbnails,
         }
     }
 
     /// Platform-specific notification sending
-    #[cfg(target_os = "linux")]
+    ///
+    /// Linux goes through the freedesktop D-Bus server and macOS through
+    /// Notification Center; both report clicks back, so they share the action
+    /// handling.
+    #[cfg(all(
+        any(target_os = "linux", target_os = "macos"),
+        feature = "desktop-notifications"
+    ))]
     fn send_notification(
         &self,
         title: &str,
         message: &str,
-        url: Option<&str>,
-        category: Option<&str>,
+        open: Option<OpenTarget>,
+        hints: NotificationHints,
         snooze_info: Option<SnoozeInfo>,
         settings_info: Option<SettingsInfo>,
     ) -> anyhow::Result<()> {
-        use notify_rust::{Hint, Notification};
+        use notify_rust::Notification;
 
         let mut notification = Notification::new();
         notification
@@ -104,13 +463,48 @@ impl DesktopNotifier {
             .appname(APP_NAME)
             .timeout(NOTIFICATION_TIMEOUT_MS);
 
-        // Set notification category if provided (freedesktop.org spec)
-        // This allows users to configure different notification behaviors per category
-        if let Some(cat) = category {
-            notification.hint(Hint::Category(cat.to_string()));
+        #[cfg(target_os = "linux")]
+        {
+            use notify_rust::{Hint, Urgency};
+
+            // Set notification category if provided (freedesktop.org spec)
+            // This allows users to configure different notification behaviors per category
+            if let Some(cat) = hints.category {
+                notification.hint(Hint::Category(cat.to_string()));
+            }
+            if hints.silent {
+                notification.hint(Hint::SuppressSound(true));
+            } else if let Some(path) = hints.sound_file() {
+                notification.hint(Hint::SoundFile(path.to_string()));
+            } else if let Some(sound) = hints.sound.filter(|_| self.capabilities.sounds) {
+                notification.sound_name(&sound);
+            }
+            if let Some(urgency) = hints.urgency {
+                notification.urgency(match urgency {
+                    NotificationUrgency::Low => Urgency::Low,
+                    NotificationUrgency::Normal => Urgency::Normal,
+                    NotificationUrgency::Critical => Urgency::Critical,
+                });
+            }
+            if let Some(path) = &hints.image {
+                notification.image_path(&path.to_string_lossy());
+            }
+            if let Some(path) = &hints.icon {
+                notification.icon(&path.to_string_lossy());
+            }
+        }
+
+        // Notification Center only plays named system sounds
+        #[cfg(target_os = "macos")]
+        if let Some(path) = hints.sound_file().filter(|_| !hints.silent) {
+            play_sound_file(path);
+        } else if let Some(sound) = hints.sound.as_deref().filter(|_| !hints.silent) {
+            notification.sound_name(sound);
         }
 
-        if let Some(url) = url {
+        // Servers without action support drop the buttons, so don't offer them
+        let open = open.filter(|_| self.capabilities.actions);
+        if let Some(open) = open {
             notification.action("default", "Open Stream");
             if snooze_info.is_some() {
                 notification.action("snooze_10", "Snooze 10m");
@@ -119,18 +513,20 @@ impl DesktopNotifier {
                 notification.action("streamer-settings", "\u{2699}\u{fe0f}");
             }
             let handle = notification.show()?;
-            let url = url.to_string();
             std::thread::spawn(move || {
                 handle.wait_for_action(|action| match action {
                     "default" => {
-                        let _ = open::that(&url);
+                        if let Err(e) = open.launch() {
+                            tracing::warn!("Failed to open {}: {}", open, e);
+                        }
                     }
                     "snooze_10" => {
                         if let Some(info) = &snooze_info {
                             let request = SnoozeRequest {
                                 user_id: info.user_id.clone(),
                                 user_name: info.user_name.clone(),
-                                remind_at: Utc::now() + Duration::minutes(SNOOZE_DURATION_MIN),
+                                remind_at: Utc::now()
+                                    + chrono::Duration::minutes(SNOOZE_DURATION_MIN),
                             };
                             let _ = info.snooze_tx.send(request);
                         }
@@ -148,19 +544,27 @@ impl DesktopNotifier {
                 });
             });
         } else {
-            notification.show()?;
+            let handle = notification.show()?;
+            // Notification Center only delivers once the handle is waited on
+            #[cfg(target_os = "macos")]
+            std::thread::spawn(move || handle.wait_for_action(|_| {}));
+            #[cfg(target_os = "linux")]
+            drop(handle);
         }
 
         Ok(())
     }
 
-    #[cfg(not(target_os = "linux"))]
+    #[cfg(not(all(
+        any(target_os = "linux", target_os = "macos"),
+        feature = "desktop-notifications"
+    )))]
     fn send_notification(
         &self,
         title: &str,
         message: &str,
-        url: Option<&str>,
-        _category: Option<&str>,
+        open: Option<OpenTarget>,
+        #[cfg_attr(target_os = "linux", allow(unused_variables))] hints: NotificationHints,
         _snooze_info: Option<SnoozeInfo>,
         _settings_info: Option<SettingsInfo>,
     ) -> anyhow::Result<()> {
@@ -171,31 +575,49 @@ impl DesktopNotifier {
         // Try to use the system notification mechanism
         #[cfg(target_os = "macos")]
         {
-            // macOS notifications via osascript don't support click actions directly
-            // We show the notification but can't make it clickable without more native code
+            // Built without `desktop-notifications`: osascript notifications
+            // can't be clicked, so log the URL instead
+            // osascript only knows system sound names, so files are played alongside
+            let sound = match (&hints.sound, hints.sound_file()) {
+                (_, Some(path)) => {
+                    play_sound_file(path);
+                    String::new()
+                }
+                (Some(name), None) => format!(" sound name \"{name}\""),
+                (None, None) => String::new(),
+            };
             let _ = std::process::Command::new("osascript")
                 .args([
                     "-e",
                     &format!(
-                        "display notification \"{}\" with title \"{}\"",
-                        message, title
+                        "display notification \"{}\" with title \"{}\"{}",
+                        message, title, sound
                     ),
                 ])
                 .output();
 
             // Log the URL so users know what stream went live
-            if let Some(url) = url {
-                tracing::info!("Stream URL: {}", url);
+            if let Some(open) = &open {
+                tracing::info!("Stream URL: {}", open);
             }
         }
 
+        // Built without `desktop-notifications`: the log line above is all we can do
+        #[cfg(target_os = "linux")]
+        if let Some(open) = &open {
+            tracing::info!("Stream URL: {}", open);
+        }
+
         #[cfg(target_os = "windows")]
         {
             // Windows toast notifications would require additional setup
-            // For now, just log
+            // For now, just log, and play the streamer's sound file if they have one
             tracing::info!("Windows notification: {} - {}", title, message);
-            if let Some(url) = url {
-                tracing::info!("Stream URL: {}", url);
+            if let Some(path) = hints.sound_file() {
+                play_sound_file(path);
+            }
+            if let Some(open) = &open {
+                tracing::info!("Stream URL: {}", open);
             }
         }
 
@@ -203,6 +625,27 @@ impl DesktopNotifier {
     }
 }
 
+/// Plays a sound file without waiting for it to finish.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn play_sound_file(path: &str) {
+    #[cfg(target_os = "macos")]
+    let result = std::process::Command::new("afplay").arg(path).spawn();
+    #[cfg(target_os = "windows")]
+    let result = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "(New-Object Media.SoundPlayer '{}').PlaySync()",
+                path.replace('\'', "''")
+            ),
+        ])
+        .spawn();
+    if let Err(e) = result {
+        tracing::warn!("Failed to play {}: {}", path, e);
+    }
+}
+
 /// Notification categories (freedesktop.org spec)
 /// These allow users to configure different behaviors per notification type at the OS level
 mod categories {
@@ -212,6 +655,16 @@ mod categories {
     pub const CATEGORY_CHANGE: &str = "category.changed";
     /// Category for "stream is hot" notifications
     pub const STREAM_HOT: &str = "presence.hot";
+    /// Category for "watch streak about to lapse" reminders
+    pub const WATCH_STREAK: &str = "presence.streak";
+    /// Category for "scheduled stream starts soon" reminders
+    pub const SCHEDULED_SOON: &str = "presence.scheduled";
+    /// Category for "small stream in a followed category" notifications
+    pub const SMALL_STREAM: &str = "presence.small";
+    /// Category for "new top stream or Favourite in a followed category" notifications
+    pub const CATEGORY_ALERT: &str = "presence.category";
+    /// Category for "stream title matched a title alert" notifications
+    pub const TITLE_MATCH: &str = "presence.title";
 }
 
 impl DesktopNotifier {
@@ -223,6 +676,27 @@ impl DesktopNotifier {
         })
     }
 
+    fn open_target(&self, stream: &Stream) -> OpenTarget {
+        OpenTarget::for_stream(&self.config.get(), stream)
+    }
+
+    /// Name, game and title cut to `max_label_width` (titles to twice that).
+    fn fitted(&self, stream: &Stream) -> FittedText {
+        FittedText::new(stream, self.config.get().max_label_width)
+    }
+
+    fn hints(&self, stream: &Stream, category: &'static str) -> NotificationHints {
+        NotificationHints {
+            image: self
+                .capabilities
+                .images
+                .then(|| self.thumbnails.cached(stream))
+                .flatten(),
+            icon: self.thumbnails.cached_avatar(stream),
+            ..NotificationHints::for_stream(&self.config.get(), stream, category)
+        }
+    }
+
     fn make_settings_info(&self, stream: &Stream) -> Option<SettingsInfo> {
         Some(SettingsInfo {
             user_login: stream.user_login.clone(),
@@ -234,87 +708,268 @@ impl DesktopNotifier {
 
 impl Notifier for DesktopNotifier {
     fn stream_live(&self, stream: &Stream) -> anyhow::Result<()> {
-        let title = format!("{} is now live!", stream.user_name);
-        let message = if stream.title.is_empty() {
-            stream.game_name.clone()
+        let shown = self.fitted(stream);
+        let title = format!("{} is now live!", shown.user_name);
+        let message = if shown.title.is_empty() {
+            shown.game_name
         } else {
-            format!("{} - {}", stream.game_name, truncate(&stream.title, 50))
+            format!("{} - {}", shown.game_name, shown.title)
         };
 
-        let url = stream.channel_url();
+        let open = self.open_target(stream);
         let snooze = self.make_snooze_info(stream);
         let settings = self.make_settings_info(stream);
         self.send_notification(
             &title,
             &message,
-            Some(&url),
-            Some(categories::STREAM_LIVE),
+            Some(open),
+            self.hints(stream, categories::STREAM_LIVE),
             snooze,
             settings,
         )
     }
 
     fn stream_reminder(&self, stream: &Stream) -> anyhow::Result<()> {
-        let title = format!("{} live for {}", stream.user_name, stream.format_duration());
-        let message = if stream.title.is_empty() {
-            stream.game_name.clone()
+        let shown = self.fitted(stream);
+        let title = format!("{} live for {}", shown.user_name, stream.format_duration());
+        let message = if shown.title.is_empty() {
+            shown.game_name
         } else {
-            format!("{} - {}", stream.game_name, truncate(&stream.title, 50))
+            format!("{} - {}", shown.game_name, shown.title)
         };
 
-        let url = stream.channel_url();
+        let open = self.open_target(stream);
         let snooze = self.make_snooze_info(stream);
         let settings = self.make_settings_info(stream);
         self.send_notification(
             &title,
             &message,
-            Some(&url),
-            Some(categories::STREAM_LIVE),
+            Some(open),
+            self.hints(stream, categories::STREAM_LIVE),
             snooze,
             settings,
         )
     }
 
     fn category_changed(&self, stream: &Stream, old_category: &str) -> anyhow::Result<()> {
-        let title = format!("{} changed category", stream.user_name);
-        let message = format!("{} → {}", old_category, stream.game_name);
+        let shown = self.fitted(stream);
+        let title = format!("{} changed category", shown.user_name);
+        let width = self.config.get().max_label_width;
+        let message = format!(
+            "{} → {}",
+            truncate_width(old_category, width),
+            shown.game_name
+        );
+
+        let open = self.open_target(stream);
+        let settings = self.make_settings_info(stream);
+        self.send_notification(
+            &title,
+            &message,
+            Some(open),
+            self.hints(stream, categories::CATEGORY_CHANGE),
+            None,
+            settings,
+        )
+    }
 
-        let url = stream.channel_url();
+    fn small_stream_live(&self, stream: &Stream) -> anyhow::Result<()> {
+        let shown = self.fitted(stream);
+        let title = format!(
+            "{} is streaming {} to {} viewers",
+            shown.user_name, shown.game_name, stream.viewer_count
+        );
+        let message = shown.title;
+
+        let open = self.open_target(stream);
         let settings = self.make_settings_info(stream);
         self.send_notification(
             &title,
             &message,
-            Some(&url),
-            Some(categories::CATEGORY_CHANGE),
+            Some(open),
+            self.hints(stream, categories::SMALL_STREAM),
+            None,
+            settings,
+        )
+    }
+
+    fn category_alert(&self, stream: &Stream, alert: CategoryAlert) -> anyhow::Result<()> {
+        let shown = self.fitted(stream);
+        let title = category_alert_title(&shown.user_name, &shown.game_name, alert);
+        let message = shown.title;
+
+        let open = self.open_target(stream);
+        let settings = self.make_settings_info(stream);
+        self.send_notification(
+            &title,
+            &message,
+            Some(open),
+            self.hints(stream, categories::CATEGORY_ALERT),
+            None,
+            settings,
+        )
+    }
+
+    fn title_matched(&self, stream: &Stream, pattern: &str) -> anyhow::Result<()> {
+        let shown = self.fitted(stream);
+        let width = self.config.get().max_label_width;
+        let title = format!(
+            "{}: \"{}\"",
+            shown.user_name,
+            truncate_width(pattern, width)
+        );
+        let message = format!("{} - {}", shown.game_name, shown.title);
+
+        let open = self.open_target(stream);
+        let settings = self.make_settings_info(stream);
+        self.send_notification(
+            &title,
+            &message,
+            Some(open),
+            self.hints(stream, categories::TITLE_MATCH),
             None,
             settings,
         )
     }
 
     fn stream_hot(&self, stream: &Stream, info: &HotnessInfo) -> anyhow::Result<()> {
+        let shown = self.fitted(stream);
         let title = format!(
             "\u{1f525}\u{1f525}\u{1f525} ({:.1}\u{03c3}) {} on {} IS HOT",
-            info.z_score, stream.user_name, stream.game_name,
+            info.z_score, shown.user_name, shown.game_name,
         );
-        let message = truncate(&stream.title, 80);
+        let message = shown.title;
 
-        let url = stream.channel_url();
+        let open = self.open_target(stream);
         let settings = self.make_settings_info(stream);
         self.send_notification(
             &title,
             &message,
-            Some(&url),
-            Some(categories::STREAM_HOT),
+            Some(open),
+            self.hints(stream, categories::STREAM_HOT),
             None,
             settings,
         )
     }
 
+    fn streak_reminder(&self, stream: &Stream, streak_len: u32) -> anyhow::Result<()> {
+        let shown = self.fitted(stream);
+        let title = format!(
+            "\u{23f3} Keep your {}-stream streak with {}",
+            streak_len, shown.user_name
+        );
+        let message = format!(
+            "Live for {} - {}",
+            stream.format_duration(),
+            shown.game_name
+        );
+
+        let open = self.open_target(stream);
+        let snooze = self.make_snooze_info(stream);
+        self.send_notification(
+            &title,
+            &message,
+            Some(open),
+            self.hints(stream, categories::WATCH_STREAK),
+            snooze,
+            None,
+        )
+    }
+
+    fn scheduled_soon(&self, schedule: &ScheduledStream) -> anyhow::Result<()> {
+        let width = self.config.get().max_label_width;
+        let title = scheduled_soon_title(schedule, width, Utc::now());
+        let category = schedule.category.as_deref().unwrap_or_default();
+        let message = match (category.is_empty(), schedule.title.is_empty()) {
+            (_, true) => truncate_width(category, width),
+            (true, false) => truncate_width(&schedule.title, width * 2),
+            (false, false) => format!(
+                "{} - {}",
+                truncate_width(category, width),
+                truncate_width(&schedule.title, width * 2)
+            ),
+        };
+
+        let settings = SettingsInfo {
+            user_login: schedule.broadcaster_login.clone(),
+            display_name: schedule.broadcaster_name.clone(),
+            settings_tx: self.settings_tx.clone(),
+        };
+        self.send_notification(
+            &title,
+            &message,
+            Some(OpenTarget::Url(schedule.channel_url())),
+            NotificationHints::for_streamer(
+                &self.config.get(),
+                &schedule.broadcaster_login,
+                categories::SCHEDULED_SOON,
+            ),
+            None,
+            Some(settings),
+        )
+    }
+
+    fn went_live_summary(&self, title: &str, message: &str) -> anyhow::Result<()> {
+        self.send_notification(
+            title,
+            message,
+            None,
+            NotificationHints {
+                category: Some(categories::STREAM_LIVE),
+                ..NotificationHints::default()
+            },
+            None,
+            None,
+        )
+    }
+
     fn error(&self, message: &str) -> anyhow::Result<()> {
-        self.send_notification(APP_NAME, message, None, None, None, None)
+        self.send_notification(
+            APP_NAME,
+            message,
+            None,
+            NotificationHints::default(),
+            None,
+            None,
+        )
+    }
+
+    fn capabilities(&self) -> NotificationCapabilities {
+        self.capabilities.clone()
+    }
+}
+
+/// "Ninja is in Fortnite's top 3", or "Ninja is streaming Fortnite" for a
+/// Favourite.
+pub fn category_alert_title(user_name: &str, game_name: &str, alert: CategoryAlert) -> String {
+    match alert {
+        CategoryAlert::TopStreams(1) => format!("{user_name} is the top {game_name} stream"),
+        CategoryAlert::TopStreams(n) => format!("{user_name} is in {game_name}'s top {n}"),
+        CategoryAlert::Favourite => format!("{user_name} is streaming {game_name}"),
     }
 }
 
+/// "Ninja streams in 10 min", or "usually streams" for a predicted schedule.
+pub fn scheduled_soon_title(
+    schedule: &ScheduledStream,
+    width: usize,
+    now: DateTime<Utc>,
+) -> String {
+    // Round up so a reminder fired 9m30s ahead still says 10
+    let minutes = ((schedule.start_time - now).num_seconds() + 59)
+        .div_euclid(60)
+        .max(1);
+    let verb = if schedule.is_inferred {
+        "usually streams"
+    } else {
+        "streams"
+    };
+    format!(
+        "{} {verb} in {minutes} min",
+        truncate_width(&schedule.broadcaster_name, width)
+    )
+}
+
 /// Truncates a string to max byte length with ellipsis, respecting char boundaries
 pub fn truncate(s: &str, max: usize) -> String {
     if s.len() <= max {
@@ -327,6 +982,67 @@ pub fn truncate(s: &str, max: usize) -> String {
     }
 }
 
+/// Truncates a string to `max_width` display columns with ellipsis.
+///
+/// Unlike [`truncate`], wide characters (CJK, most emoji) count as two columns
+/// and zero-width ones as none, so a cut name takes the same space in a menu
+/// whatever script it is written in.
+pub fn truncate_width(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    let (budget, ellipsis) = if max_width <= 3 {
+        (max_width, "")
+    } else {
+        (max_width - 3, "...")
+    };
+    let mut used = 0;
+    let mut out = String::new();
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    out.push_str(ellipsis);
+    out
+}
+
+/// Fills a `live_label_format` template for a live stream.
+///
+/// `{name}`, `{game}` and `{title}` are cut like notifications (titles get
+/// twice `max_width`); `{viewers}` and `{uptime}` are the short forms, like
+/// "1.2k" and "2h 15m". Anything else, unknown tokens included, is kept as is.
+pub fn format_live_label(template: &str, stream: &Stream, max_width: usize) -> String {
+    let fitted = FittedText::new(stream, max_width);
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start..];
+        let token_len = after.find('}').map_or(0, |end| end + 1);
+        let value = match &after[..token_len] {
+            "{name}" => Some(fitted.user_name.clone()),
+            "{game}" => Some(fitted.game_name.clone()),
+            "{title}" => Some(fitted.title.clone()),
+            "{viewers}" => Some(stream.format_viewer_count()),
+            "{uptime}" => Some(stream.format_duration()),
+            _ => None,
+        };
+        if let Some(value) = value {
+            out.push_str(&value);
+            rest = &after[token_len..];
+        } else {
+            out.push('{');
+            rest = &after[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
 /// Recording notifier for testing
 ///
 /// Records all notifications for later verification.
@@ -350,6 +1066,12 @@ pub mod mock {
         StreamReminder,
         CategoryChange,
         StreamHot,
+        StreakReminder,
+        ScheduledSoon,
+        SmallStream,
+        CategoryAlert,
+        TitleMatch,
+        WentLiveSummary,
         Error,
     }
 
@@ -471,6 +1193,92 @@ pub mod mock {
             Ok(())
         }
 
+        fn streak_reminder(&self, stream: &Stream, streak_len: u32) -> anyhow::Result<()> {
+            let title = format!(
+                "\u{23f3} Keep your {}-stream streak with {}",
+                streak_len, stream.user_name
+            );
+
+            self.notifications
+                .write()
+                .unwrap()
+                .push(RecordedNotification {
+                    notification_type: NotificationType::StreakReminder,
+                    title,
+                    message: stream.game_name.clone(),
+                });
+
+            Ok(())
+        }
+
+        fn scheduled_soon(&self, schedule: &ScheduledStream) -> anyhow::Result<()> {
+            self.notifications
+                .write()
+                .unwrap()
+                .push(RecordedNotification {
+                    notification_type: NotificationType::ScheduledSoon,
+                    title: scheduled_soon_title(schedule, usize::MAX, Utc::now()),
+                    message: schedule.title.clone(),
+                });
+
+            Ok(())
+        }
+
+        fn small_stream_live(&self, stream: &Stream) -> anyhow::Result<()> {
+            self.notifications
+                .write()
+                .unwrap()
+                .push(RecordedNotification {
+                    notification_type: NotificationType::SmallStream,
+                    title: format!(
+                        "{} is streaming {} to {} viewers",
+                        stream.user_name, stream.game_name, stream.viewer_count
+                    ),
+                    message: stream.title.clone(),
+                });
+
+            Ok(())
+        }
+
+        fn category_alert(&self, stream: &Stream, alert: CategoryAlert) -> anyhow::Result<()> {
+            self.notifications
+                .write()
+                .unwrap()
+                .push(RecordedNotification {
+                    notification_type: NotificationType::CategoryAlert,
+                    title: category_alert_title(&stream.user_name, &stream.game_name, alert),
+                    message: stream.title.clone(),
+                });
+
+            Ok(())
+        }
+
+        fn title_matched(&self, stream: &Stream, pattern: &str) -> anyhow::Result<()> {
+            self.notifications
+                .write()
+                .unwrap()
+                .push(RecordedNotification {
+                    notification_type: NotificationType::TitleMatch,
+                    title: format!("{}: \"{}\"", stream.user_name, pattern),
+                    message: format!("{} - {}", stream.game_name, stream.title),
+                });
+
+            Ok(())
+        }
+
+        fn went_live_summary(&self, title: &str, message: &str) -> anyhow::Result<()> {
+            self.notifications
+                .write()
+                .unwrap()
+                .push(RecordedNotification {
+                    notification_type: NotificationType::WentLiveSummary,
+                    title: title.to_string(),
+                    message: message.to_string(),
+                });
+
+            Ok(())
+        }
+
         fn error(&self, message: &str) -> anyhow::Result<()> {
             self.notifications
                 .write()
@@ -483,6 +1291,10 @@ pub mod mock {
 
             Ok(())
         }
+
+        fn capabilities(&self) -> NotificationCapabilities {
+            NotificationCapabilities::default()
+        }
     }
 }
 
@@ -490,6 +1302,7 @@ pub mod mock {
 mod tests {
     use super::mock::{NotificationType, RecordingNotifier};
     use super::*;
+    use crate::config::DEFAULT_LIVE_LABEL_FORMAT;
     use chrono::Utc;
 
     fn make_stream(user_name: &str, game_name: &str, title: &str) -> Stream {
@@ -540,6 +1353,23 @@ mod tests {
         assert_eq!(notifications[0].message, "Something went wrong");
     }
 
+    #[test]
+    fn recording_notifier_records_streak_reminder() {
+        let notifier = RecordingNotifier::new();
+        let stream = make_stream("Streamer", "Game", "Title");
+
+        notifier.streak_reminder(&stream, 4).unwrap();
+
+        let notifications = notifier.get_notifications();
+        assert_eq!(
+            notifications[0].notification_type,
+            NotificationType::StreakReminder
+        );
+        assert!(notifications[0]
+            .title
+            .contains("4-stream streak with Streamer"));
+    }
+
     #[test]
     fn recording_notifier_get_by_type() {
         let notifier = RecordingNotifier::new();
@@ -647,4 +1477,494 @@ mod tests {
         assert!(result.len() <= 10);
         assert!(result.ends_with("..."));
     }
+
+    // === truncate_width tests ===
+
+    #[test]
+    fn truncate_width_ascii_matches_truncate() {
+        let long_game = "Counter-Strike: Global Offensive";
+        assert_eq!(truncate_width(long_game, 20), truncate(long_game, 20));
+        assert_eq!(truncate_width("Hello", 5), "Hello");
+    }
+
+    #[test]
+    fn truncate_width_counts_wide_characters_twice() {
+        // 8 CJK characters: 16 columns
+        let name = "\u{65e5}\u{672c}\u{8a9e}\u{306e}\u{540d}\u{524d}\u{3067}\u{3059}";
+        assert_eq!(truncate_width(name, 16), name);
+        let cut = truncate_width(name, 10);
+        assert_eq!(cut, "\u{65e5}\u{672c}\u{8a9e}...");
+        assert!(cut.width() <= 10);
+    }
+
+    #[test]
+    fn truncate_width_never_splits_a_wide_character() {
+        // 7 columns left for text: three 2-column characters fit, a fourth doesn't
+        let cut = truncate_width("\u{1f6a8}\u{1f6a8}\u{1f6a8}\u{1f6a8}\u{1f6a8}\u{1f6a8}", 10);
+        assert_eq!(cut, "\u{1f6a8}\u{1f6a8}\u{1f6a8}...");
+    }
+
+    #[test]
+    fn truncate_width_tiny_limit_has_no_ellipsis() {
+        assert_eq!(truncate_width("Hello", 3), "Hel");
+        assert_eq!(truncate_width("\u{65e5}\u{672c}", 3), "\u{65e5}");
+    }
+
+    #[test]
+    fn live_label_default_format() {
+        let stream = make_stream("Streamer", "Just Chatting", "Title");
+        assert_eq!(
+            format_live_label(DEFAULT_LIVE_LABEL_FORMAT, &stream, 25),
+            "Streamer - Just Chatting (1k, 1h 0m)"
+        );
+    }
+
+    #[test]
+    fn live_label_custom_format_cuts_fields() {
+        let stream = make_stream(
+            "Streamer",
+            "Counter-Strike: Global Offensive",
+            "A long stream title",
+        );
+        assert_eq!(
+            format_live_label("{viewers} {name}: {title} [{game}]", &stream, 10),
+            "1k Streamer: A long stream title [Counter...]"
+        );
+    }
+
+    #[test]
+    fn live_label_keeps_unknown_tokens_and_braces() {
+        let stream = make_stream("{game}", "Game", "Title");
+        assert_eq!(
+            format_live_label("{name} {nope} {", &stream, 25),
+            "{game} {nope} {"
+        );
+    }
+
+    #[test]
+    fn fitted_text_gives_titles_twice_the_width() {
+        let stream = make_stream(
+            "\u{65e5}\u{672c}\u{8a9e}\u{306e}\u{540d}\u{524d}",
+            "Counter-Strike: Global Offensive",
+            "This is a very long title that should be truncated",
+        );
+        let fitted = FittedText::new(&stream, 10);
+        assert_eq!(fitted.user_name, "\u{65e5}\u{672c}\u{8a9e}...");
+        assert_eq!(fitted.game_name, "Counter...");
+        assert_eq!(fitted.title, "This is a very lo...");
+    }
+
+    // === OpenTarget tests ===
+
+    fn config_with_mode(mode: NotificationOpenMode, pip_command: &str) -> Config {
+        Config {
+            notification_open_mode: mode,
+            pip_command: pip_command.to_string(),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn open_target_defaults_to_channel_page() {
+        let stream = make_stream("Alice", "Chess", "");
+        assert_eq!(
+            OpenTarget::for_stream(&Config::default(), &stream),
+            OpenTarget::Url("https://twitch.tv/alice".to_string())
+        );
+    }
+
+    #[test]
+    fn open_target_popout_uses_player_url() {
+        let config = config_with_mode(NotificationOpenMode::Popout, "");
+        let stream = make_stream("Alice", "Chess", "");
+        assert_eq!(
+            OpenTarget::for_stream(&config, &stream),
+            OpenTarget::Url(
+                "https://player.twitch.tv/?channel=alice&parent=twitch.tv&player=popout"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn open_target_command_substitutes_or_appends_url() {
+        let stream = make_stream("Alice", "Chess", "");
+        let with_placeholder = config_with_mode(NotificationOpenMode::Command, "mpv {url} --ontop");
+        assert_eq!(
+            OpenTarget::for_stream(&with_placeholder, &stream),
+            OpenTarget::Command("mpv https://twitch.tv/alice --ontop".to_string())
+        );
+        let without = config_with_mode(NotificationOpenMode::Command, "streamlink");
+        assert_eq!(
+            OpenTarget::for_stream(&without, &stream),
+            OpenTarget::Command("streamlink https://twitch.tv/alice".to_string())
+        );
+    }
+
+    #[test]
+    fn open_target_command_without_command_falls_back_to_popout() {
+        let config = config_with_mode(NotificationOpenMode::Command, "  ");
+        let stream = make_stream("Alice", "Chess", "");
+        assert!(matches!(
+            OpenTarget::for_stream(&config, &stream),
+            OpenTarget::Url(url) if url.contains("player=popout")
+        ));
+    }
+
+    fn config_with_open_with(open_with: OpenWith) -> Config {
+        Config {
+            open_with,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn watching_defaults_to_browser() {
+        assert_eq!(
+            OpenTarget::for_watching(&Config::default(), "alice"),
+            OpenTarget::Url("https://twitch.tv/alice".to_string())
+        );
+    }
+
+    #[test]
+    fn watching_with_streamlink() {
+        assert_eq!(
+            OpenTarget::for_watching(&config_with_open_with(OpenWith::Streamlink), "alice"),
+            OpenTarget::Command("streamlink twitch.tv/alice best".to_string())
+        );
+    }
+
+    #[test]
+    fn watching_with_command_fills_placeholders() {
+        let config = config_with_open_with(OpenWith::Command(
+            "chatterino -c {login} & mpv {url}".to_string(),
+        ));
+        assert_eq!(
+            OpenTarget::for_watching(&config, "alice"),
+            OpenTarget::Command("chatterino -c alice & mpv https://twitch.tv/alice".to_string())
+        );
+
+        let config = config_with_open_with(OpenWith::Command("mpv".to_string()));
+        assert_eq!(
+            OpenTarget::for_watching(&config, "alice"),
+            OpenTarget::Command("mpv https://twitch.tv/alice".to_string())
+        );
+    }
+
+    #[test]
+    fn watching_with_blank_command_uses_browser() {
+        let config = config_with_open_with(OpenWith::Command("  ".to_string()));
+        assert_eq!(
+            OpenTarget::for_watching(&config, "alice"),
+            OpenTarget::Url("https://twitch.tv/alice".to_string())
+        );
+    }
+
+    #[test]
+    fn watching_streamer_override_beats_global() {
+        let mut config = config_with_open_with(OpenWith::Streamlink);
+        config.streamer_settings.insert(
+            "alice".to_string(),
+            crate::config::StreamerSettings {
+                display_name: "Alice".to_string(),
+                importance: crate::config::StreamerImportance::Normal,
+                hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: None,
+                open_with: Some(OpenWith::Browser),
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
+            },
+        );
+        assert_eq!(
+            OpenTarget::for_watching(&config, "alice"),
+            OpenTarget::Url("https://twitch.tv/alice".to_string())
+        );
+        assert_eq!(
+            OpenTarget::for_watching(&config, "bob"),
+            OpenTarget::Command("streamlink twitch.tv/bob best".to_string())
+        );
+    }
+
+    #[test]
+    fn open_target_streamer_override_beats_global_mode() {
+        let mut config = config_with_mode(NotificationOpenMode::Popout, "");
+        config.streamer_settings.insert(
+            "alice".to_string(),
+            crate::config::StreamerSettings {
+                display_name: "Alice".to_string(),
+                importance: crate::config::StreamerImportance::Normal,
+                hotness_z_threshold_override: None,
+                notification_open_mode: Some(NotificationOpenMode::Channel),
+                notification_sound: None,
+                open_with: None,
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
+            },
+        );
+        assert_eq!(
+            OpenTarget::for_stream(&config, &make_stream("Alice", "Chess", "")),
+            OpenTarget::Url("https://twitch.tv/alice".to_string())
+        );
+        assert!(matches!(
+            OpenTarget::for_stream(&config, &make_stream("Bob", "Chess", "")),
+            OpenTarget::Url(url) if url.contains("player=popout")
+        ));
+    }
+
+    // === NotificationHints tests ===
+
+    fn config_with_category(notifications: crate::config::CategoryNotifications) -> Config {
+        Config {
+            followed_categories: vec![crate::config::FollowedCategory {
+                id: "789".to_string(),
+                name: "Chess".to_string(),
+                notifications,
+                ..Default::default()
+            }],
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn hints_use_followed_category_sound_and_urgency() {
+        let config = config_with_category(crate::config::CategoryNotifications {
+            enabled: None,
+            sound: Some("bell".to_string()),
+            urgency: Some(NotificationUrgency::Critical),
+            ..Default::default()
+        });
+        let hints = NotificationHints::for_stream(
+            &config,
+            &make_stream("Alice", "Chess", ""),
+            categories::STREAM_LIVE,
+        );
+        assert_eq!(
+            hints,
+            NotificationHints {
+                category: Some(categories::STREAM_LIVE),
+                sound: Some("bell".to_string()),
+                silent: false,
+                urgency: Some(NotificationUrgency::Critical),
+                image: None,
+                icon: None,
+            }
+        );
+    }
+
+    #[test]
+    fn hints_default_outside_followed_categories() {
+        let mut stream = make_stream("Alice", "Go", "");
+        stream.game_id = "other".to_string();
+        let config = config_with_category(crate::config::CategoryNotifications {
+            enabled: None,
+            sound: Some("bell".to_string()),
+            urgency: Some(NotificationUrgency::Low),
+            ..Default::default()
+        });
+        let hints = NotificationHints::for_stream(&config, &stream, categories::STREAM_LIVE);
+        assert_eq!(hints.sound, None);
+        assert_eq!(hints.urgency, None);
+    }
+
+    #[test]
+    fn hints_ignore_blank_sound() {
+        let config = config_with_category(crate::config::CategoryNotifications {
+            sound: Some("  ".to_string()),
+            ..Default::default()
+        });
+        let hints = NotificationHints::for_stream(
+            &config,
+            &make_stream("Alice", "Chess", ""),
+            categories::STREAM_LIVE,
+        );
+        assert_eq!(hints.sound, None);
+    }
+
+    fn with_streamer_sound(mut config: Config, sound: NotificationSound) -> Config {
+        config.streamer_settings.insert(
+            "alice".to_string(),
+            crate::config::StreamerSettings {
+                display_name: "Alice".to_string(),
+                importance: crate::config::StreamerImportance::Normal,
+                hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: Some(sound),
+                open_with: None,
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn hints_streamer_sound_beats_category_sound() {
+        let config = with_streamer_sound(
+            config_with_category(crate::config::CategoryNotifications {
+                sound: Some("bell".to_string()),
+                ..Default::default()
+            }),
+            NotificationSound::Custom("/home/alice/ding.ogg".to_string()),
+        );
+        let hints = NotificationHints::for_stream(
+            &config,
+            &make_stream("Alice", "Chess", ""),
+            categories::STREAM_LIVE,
+        );
+        assert_eq!(hints.sound.as_deref(), Some("/home/alice/ding.ogg"));
+        assert_eq!(hints.sound_file(), Some("/home/alice/ding.ogg"));
+        assert!(!hints.silent);
+    }
+
+    #[test]
+    fn hints_silent_streamer_drops_category_sound() {
+        let config = with_streamer_sound(
+            config_with_category(crate::config::CategoryNotifications {
+                sound: Some("bell".to_string()),
+                ..Default::default()
+            }),
+            NotificationSound::Silent,
+        );
+        let hints = NotificationHints::for_stream(
+            &config,
+            &make_stream("Alice", "Chess", ""),
+            categories::STREAM_LIVE,
+        );
+        assert_eq!(hints.sound, None);
+        assert!(hints.silent);
+    }
+
+    #[test]
+    fn hints_blank_streamer_sound_keeps_category_sound() {
+        let config = with_streamer_sound(
+            config_with_category(crate::config::CategoryNotifications {
+                sound: Some("bell".to_string()),
+                ..Default::default()
+            }),
+            NotificationSound::Custom(" ".to_string()),
+        );
+        let hints = NotificationHints::for_stream(
+            &config,
+            &make_stream("Alice", "Chess", ""),
+            categories::STREAM_LIVE,
+        );
+        assert_eq!(hints.sound.as_deref(), Some("bell"));
+        assert_eq!(hints.sound_file(), None);
+    }
+
+    #[test]
+    fn streamer_hints_ignore_category_settings() {
+        let config = with_streamer_sound(
+            config_with_category(crate::config::CategoryNotifications {
+                sound: Some("bell".to_string()),
+                urgency: Some(NotificationUrgency::Critical),
+                ..Default::default()
+            }),
+            NotificationSound::Custom("chime".to_string()),
+        );
+        let hints = NotificationHints::for_streamer(&config, "alice", categories::SCHEDULED_SOON);
+        assert_eq!(
+            hints,
+            NotificationHints {
+                category: Some(categories::SCHEDULED_SOON),
+                sound: Some("chime".to_string()),
+                silent: false,
+                urgency: None,
+                image: None,
+                icon: None,
+            }
+        );
+    }
+
+    // === category_alert_title tests ===
+
+    #[test]
+    fn category_alert_title_names_the_rank() {
+        assert_eq!(
+            category_alert_title("Ninja", "Fortnite", CategoryAlert::TopStreams(3)),
+            "Ninja is in Fortnite's top 3"
+        );
+        assert_eq!(
+            category_alert_title("Ninja", "Fortnite", CategoryAlert::TopStreams(1)),
+            "Ninja is the top Fortnite stream"
+        );
+        assert_eq!(
+            category_alert_title("Ninja", "Fortnite", CategoryAlert::Favourite),
+            "Ninja is streaming Fortnite"
+        );
+    }
+
+    // === scheduled_soon_title tests ===
+
+    fn make_schedule(name: &str, start: DateTime<Utc>, is_inferred: bool) -> ScheduledStream {
+        ScheduledStream {
+            id: "s1".to_string(),
+            broadcaster_id: "456".to_string(),
+            broadcaster_name: name.to_string(),
+            broadcaster_login: name.to_lowercase(),
+            title: "Speedruns".to_string(),
+            start_time: start,
+            end_time: None,
+            category: Some("Chess".to_string()),
+            category_id: None,
+            is_recurring: false,
+            is_inferred,
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn scheduled_soon_title_rounds_minutes_up() {
+        let now = Utc::now();
+        let schedule = make_schedule("Alice", now + chrono::Duration::seconds(570), false);
+        assert_eq!(
+            scheduled_soon_title(&schedule, 25, now),
+            "Alice streams in 10 min"
+        );
+    }
+
+    #[test]
+    fn scheduled_soon_title_marks_predictions() {
+        let now = Utc::now();
+        let schedule = make_schedule("Alice", now + chrono::Duration::minutes(5), true);
+        assert_eq!(
+            scheduled_soon_title(&schedule, 25, now),
+            "Alice usually streams in 5 min"
+        );
+    }
+
+    // === Capability tests ===
+
+    fn caps(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn freedesktop_capabilities_full_server() {
+        let c = NotificationCapabilities::from_freedesktop(
+            "Plasma 6.0",
+            &caps(&["actions", "body", "body-images", "sound"]),
+        );
+        assert_eq!(c.server, "Plasma 6.0");
+        assert!(c.actions);
+        assert!(c.images);
+        assert!(c.sounds);
+        assert!(c.urgency);
+    }
+
+    #[test]
+    fn freedesktop_capabilities_minimal_server() {
+        let c = NotificationCapabilities::from_freedesktop("notify-osd 1.0", &caps(&["body"]));
+        assert!(!c.actions);
+        assert!(!c.images);
+        assert!(!c.sounds);
+        assert!(c.urgency);
+    }
+
+    #[test]
+    fn freedesktop_icon_static_counts_as_images() {
+        let c = NotificationCapabilities::from_freedesktop("dunst", &caps(&["icon-static"]));
+        assert!(c.images);
+    }
 }
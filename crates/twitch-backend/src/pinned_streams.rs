@@ -0,0 +1,69 @@
+//! Pinning channels to the top of the menu (`Config::pinned_streams`).
+//!
+//! A pin is by channel, so it outlives the broadcast it was made from: the
+//! entry shows as offline until the channel is live again or it's unpinned.
+
+use crate::config::{Config, PinnedStream};
+
+/// Whether `user_login` is pinned.
+pub fn is_pinned(config: &Config, user_login: &str) -> bool {
+    config
+        .pinned_streams
+        .iter()
+        .any(|p| p.user_login == user_login)
+}
+
+/// Pins a channel below any already pinned. Returns `false` if it already was.
+pub fn pin_stream(config: &mut Config, pin: PinnedStream) -> bool {
+    if is_pinned(config, &pin.user_login) {
+        return false;
+    }
+    config.pinned_streams.push(pin);
+    true
+}
+
+/// Unpins a channel. Returns `false` if it wasn't pinned.
+pub fn unpin_stream(config: &mut Config, user_login: &str) -> bool {
+    let before = config.pinned_streams.len();
+    config.pinned_streams.retain(|p| p.user_login != user_login);
+    config.pinned_streams.len() != before
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pin(login: &str) -> PinnedStream {
+        PinnedStream {
+            user_login: login.to_string(),
+            display_name: login.to_uppercase(),
+        }
+    }
+
+    #[test]
+    fn pins_keep_their_order() {
+        let mut config = Config::default();
+        assert!(pin_stream(&mut config, pin("alice")));
+        assert!(pin_stream(&mut config, pin("bob")));
+        assert!(!pin_stream(&mut config, pin("alice")));
+
+        let logins: Vec<&str> = config
+            .pinned_streams
+            .iter()
+            .map(|p| p.user_login.as_str())
+            .collect();
+        assert_eq!(logins, vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn unpin_removes_only_that_channel() {
+        let mut config = Config::default();
+        pin_stream(&mut config, pin("alice"));
+        pin_stream(&mut config, pin("bob"));
+
+        assert!(unpin_stream(&mut config, "alice"));
+        assert!(!unpin_stream(&mut config, "alice"));
+        assert!(!is_pinned(&config, "alice"));
+        assert!(is_pinned(&config, "bob"));
+    }
+}
@@ -0,0 +1,322 @@
+//! Suspend and resume events from the OS: logind's `PrepareForSleep` signal
+//! on Linux, IOKit system power notifications on macOS and
+//! `PowerRegisterSuspendResumeNotification` on Windows.
+//!
+//! The backend refreshes as soon as the machine wakes and hands the time spent
+//! suspended to the next `StreamsUpdated`, so the catch-up poll's go-lives are
+//! suppressed however soon it lands. Without the `power-events` feature, on
+//! other platforms or when the OS service can't be reached, [`watch`] never
+//! yields and wakes are only noticed from the gap between stream updates
+//! (`notification_filter::filter_notifications`).
+
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::mpsc;
+
+/// A change in the machine's power state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerEvent {
+    /// The machine is about to suspend.
+    Suspending,
+    /// The machine woke from suspend.
+    Resumed,
+}
+
+/// A stretch of time the machine spent suspended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Suspension {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+}
+
+impl Suspension {
+    pub fn duration(&self) -> Duration {
+        self.until - self.since
+    }
+}
+
+/// Pairs [`PowerEvent`]s up into [`Suspension`]s.
+#[derive(Debug, Default)]
+pub struct SleepTracker {
+    suspended_at: Option<DateTime<Utc>>,
+}
+
+impl SleepTracker {
+    /// Takes in `event`, seen at `now`. Returns the suspension a resume ends;
+    /// one with no suspend seen before it (missed, or the listener started
+    /// late) is treated as lasting no time.
+    pub fn observe(&mut self, event: PowerEvent, now: DateTime<Utc>) -> Option<Suspension> {
+        match event {
+            PowerEvent::Suspending => {
+                self.suspended_at = Some(now);
+                None
+            }
+            PowerEvent::Resumed => Some(Suspension {
+                since: self.suspended_at.take().unwrap_or(now),
+                until: now,
+            }),
+        }
+    }
+}
+
+/// Starts listening for suspend and resume. Must be called inside the Tokio
+/// runtime. The receiver closes straight away where events aren't available.
+pub fn watch() -> mpsc::UnboundedReceiver<PowerEvent> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    platform::spawn(tx);
+    rx
+}
+
+#[cfg(all(feature = "power-events", target_os = "linux"))]
+mod platform {
+    use futures_lite::StreamExt;
+    use tokio::sync::mpsc;
+
+    use super::PowerEvent;
+
+    #[zbus::proxy(
+        interface = "org.freedesktop.login1.Manager",
+        default_service = "org.freedesktop.login1",
+        default_path = "/org/freedesktop/login1"
+    )]
+    trait Login1Manager {
+        /// Sent with `true` before suspending and `false` after resuming.
+        #[zbus(signal)]
+        fn prepare_for_sleep(&self, start: bool) -> zbus::Result<()>;
+    }
+
+    pub(super) fn spawn(tx: mpsc::UnboundedSender<PowerEvent>) {
+        tokio::spawn(async move {
+            if let Err(e) = listen(&tx).await {
+                tracing::warn!("Suspend/resume detection unavailable: {:#}", e);
+            }
+        });
+    }
+
+    async fn listen(tx: &mpsc::UnboundedSender<PowerEvent>) -> zbus::Result<()> {
+        let connection = zbus::Connection::system().await?;
+        let manager = Login1ManagerProxy::new(&connection).await?;
+        let mut signals = manager.receive_prepare_for_sleep().await?;
+        tracing::debug!("Listening for suspend/resume from logind");
+        while let Some(signal) = signals.next().await {
+            let event = if signal.args()?.start {
+                PowerEvent::Suspending
+            } else {
+                PowerEvent::Resumed
+            };
+            if tx.send(event).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "power-events", target_os = "macos"))]
+#[allow(unsafe_code)]
+mod platform {
+    use std::ffi::c_void;
+
+    use core_foundation_sys::runloop::{
+        kCFRunLoopCommonModes, CFRunLoopAddSource, CFRunLoopGetCurrent, CFRunLoopRun,
+    };
+    use io_kit_sys::types::{io_connect_t, io_object_t, io_service_t};
+    use io_kit_sys::{IONotificationPortGetRunLoopSource, IONotificationPortRef};
+    use tokio::sync::mpsc;
+
+    use super::PowerEvent;
+
+    // iokit_common_msg() values from IOKit/IOMessage.h
+    const MESSAGE_CAN_SYSTEM_SLEEP: u32 = 0xE000_0270;
+    const MESSAGE_SYSTEM_WILL_SLEEP: u32 = 0xE000_0280;
+    const MESSAGE_SYSTEM_HAS_POWERED_ON: u32 = 0xE000_0300;
+
+    // From IOKit/pwr_mgt/IOPMLib.h, which io-kit-sys doesn't cover
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IORegisterForSystemPower(
+            refcon: *mut c_void,
+            port: *mut IONotificationPortRef,
+            callback: io_kit_sys::IOServiceInterestCallback,
+            notifier: *mut io_object_t,
+        ) -> io_connect_t;
+        fn IOAllowPowerChange(kernel_port: io_connect_t, notification_id: isize) -> i32;
+    }
+
+    /// What the IOKit callback gets as its `refcon`.
+    struct Listener {
+        root_port: io_connect_t,
+        tx: mpsc::UnboundedSender<PowerEvent>,
+    }
+
+    pub(super) fn spawn(tx: mpsc::UnboundedSender<PowerEvent>) {
+        let spawned = std::thread::Builder::new()
+            .name("power-events".to_string())
+            .spawn(move || run(tx));
+        if let Err(e) = spawned {
+            tracing::warn!("Suspend/resume detection unavailable: {}", e);
+        }
+    }
+
+    /// Registers for system power messages and runs this thread's run loop to
+    /// receive them, for the life of the process.
+    fn run(tx: mpsc::UnboundedSender<PowerEvent>) {
+        // Leaked: the callback can fire until the process exits
+        let listener = Box::into_raw(Box::new(Listener { root_port: 0, tx }));
+        let mut port: IONotificationPortRef = std::ptr::null_mut();
+        let mut notifier: io_object_t = 0;
+        // SAFETY: `listener` is never freed, and the callback only runs on
+        // this thread's run loop, after `root_port` is set.
+        unsafe {
+            let root_port =
+                IORegisterForSystemPower(listener.cast(), &mut port, on_message, &mut notifier);
+            if root_port == 0 {
+                tracing::warn!(
+                    "Suspend/resume detection unavailable: IORegisterForSystemPower failed"
+                );
+                return;
+            }
+            (*listener).root_port = root_port;
+            CFRunLoopAddSource(
+                CFRunLoopGetCurrent(),
+                IONotificationPortGetRunLoopSource(port),
+                kCFRunLoopCommonModes,
+            );
+            tracing::debug!("Listening for suspend/resume from IOKit");
+            CFRunLoopRun();
+        }
+    }
+
+    unsafe extern "C" fn on_message(
+        refcon: *mut c_void,
+        _service: io_service_t,
+        message_type: u32,
+        argument: *mut c_void,
+    ) {
+        // SAFETY: `refcon` is the leaked `Listener` from `run`.
+        let listener = unsafe { &*refcon.cast::<Listener>() };
+        match message_type {
+            // Sleep waits up to 30s for every registered client to allow it
+            MESSAGE_CAN_SYSTEM_SLEEP => unsafe {
+                IOAllowPowerChange(listener.root_port, argument as isize);
+            },
+            MESSAGE_SYSTEM_WILL_SLEEP => {
+                let _ = listener.tx.send(PowerEvent::Suspending);
+                unsafe {
+                    IOAllowPowerChange(listener.root_port, argument as isize);
+                }
+            }
+            MESSAGE_SYSTEM_HAS_POWERED_ON => {
+                let _ = listener.tx.send(PowerEvent::Resumed);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(all(feature = "power-events", target_os = "windows"))]
+#[allow(unsafe_code)]
+mod platform {
+    use std::ffi::c_void;
+
+    use tokio::sync::mpsc;
+    use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+    use windows_sys::Win32::System::Power::{
+        PowerRegisterSuspendResumeNotification, DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        DEVICE_NOTIFY_CALLBACK, PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND,
+    };
+
+    use super::PowerEvent;
+
+    pub(super) fn spawn(tx: mpsc::UnboundedSender<PowerEvent>) {
+        // Leaked and never unregistered: the callback can fire until the
+        // process exits
+        let params = Box::into_raw(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+            Callback: Some(on_power_event),
+            Context: Box::into_raw(Box::new(tx)).cast(),
+        }));
+        let mut registration = std::ptr::null_mut();
+        // SAFETY: `params` and its context are never freed.
+        let result = unsafe {
+            PowerRegisterSuspendResumeNotification(
+                DEVICE_NOTIFY_CALLBACK,
+                params.cast(),
+                &mut registration,
+            )
+        };
+        if result == ERROR_SUCCESS {
+            tracing::debug!("Listening for suspend/resume from Windows");
+        } else {
+            tracing::warn!("Suspend/resume detection unavailable: error {}", result);
+        }
+    }
+
+    unsafe extern "system" fn on_power_event(
+        context: *const c_void,
+        event_type: u32,
+        _setting: *const c_void,
+    ) -> u32 {
+        // SAFETY: `context` is the leaked sender from `spawn`.
+        let tx = unsafe { &*context.cast::<mpsc::UnboundedSender<PowerEvent>>() };
+        // PBT_APMRESUMEAUTOMATIC comes with every resume; PBT_APMRESUMESUSPEND
+        // only follows it when the user woke the machine
+        let event = match event_type {
+            PBT_APMSUSPEND => Some(PowerEvent::Suspending),
+            PBT_APMRESUMEAUTOMATIC => Some(PowerEvent::Resumed),
+            _ => None,
+        };
+        if let Some(event) = event {
+            let _ = tx.send(event);
+        }
+        ERROR_SUCCESS
+    }
+}
+
+#[cfg(not(all(
+    feature = "power-events",
+    any(target_os = "linux", target_os = "macos", target_os = "windows")
+)))]
+mod platform {
+    use tokio::sync::mpsc;
+
+    use super::PowerEvent;
+
+    pub(super) fn spawn(_tx: mpsc::UnboundedSender<PowerEvent>) {
+        tracing::debug!("Suspend/resume detection not available in this build");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resume_ends_the_suspension_that_preceded_it() {
+        let mut tracker = SleepTracker::default();
+        let slept = Utc::now();
+        let woke = slept + Duration::hours(8);
+
+        assert_eq!(tracker.observe(PowerEvent::Suspending, slept), None);
+        let suspension = tracker.observe(PowerEvent::Resumed, woke).unwrap();
+        assert_eq!(suspension.since, slept);
+        assert_eq!(suspension.duration(), Duration::hours(8));
+
+        // The next resume doesn't reuse it
+        let later = woke + Duration::hours(1);
+        assert_eq!(
+            tracker
+                .observe(PowerEvent::Resumed, later)
+                .unwrap()
+                .duration(),
+            Duration::zero()
+        );
+    }
+
+    #[tokio::test]
+    async fn watch_starts_inside_the_runtime() {
+        // No logind in CI; whatever happens, nothing is received here
+        let mut rx = watch();
+        assert!(rx.try_recv().is_err());
+    }
+}
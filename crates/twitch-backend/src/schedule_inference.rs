@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
 use chrono_tz::Tz;
 
 use crate::twitch::{FollowedChannel, ScheduledStream};
@@ -10,6 +10,7 @@ use crate::twitch::{FollowedChannel, ScheduledStream};
 /// Uses a weekly-recurrence heuristic: looks at the same time window shifted
 /// back 1, 2, and 3 weeks. A stream is predicted if at least 2 of those 3
 /// lookback windows contain a stream at roughly the same time (within 1 hour).
+/// `confidence` is the share of the 3 weeks that had one.
 ///
 /// `window_start` and `window_end` define the prediction window.
 ///
@@ -126,6 +127,7 @@ pub fn infer_schedules(
                 category_id: None,
                 is_recurring: false,
                 is_inferred: true,
+                confidence: Some(distinct_weeks.len() as f32 / 3.0),
             });
         }
     }
@@ -135,19 +137,141 @@ pub fn infer_schedules(
     inferred
 }
 
+/// Minimum share of observed weeks a weekday slot needs a stream in for
+/// `infer_schedules_by_weekday` to predict it.
+const MIN_WEEKDAY_CONFIDENCE: f32 = 0.5;
+
+/// Infers future schedules from a day-of-week model over all of `history`.
+///
+/// Each channel's starts are grouped by weekday and clustered by time of day
+/// (within 1 hour, as in `infer_schedules`). A slot is predicted on matching
+/// days in the window when it had a stream in at least 2 distinct weeks and
+/// in at least half of the weeks since the channel's first start in
+/// `history`; `confidence` is that share.
+///
+/// `history` contains `(user_id, started_at_unix_timestamp)` pairs from the
+/// lookback period; starts at or after `window_start` are ignored. Weekdays
+/// and times are the streamer's wall clock when `timezones` has theirs, else UTC.
+pub fn infer_schedules_by_weekday(
+    history: &[(i64, i64)],
+    channel_lookup: &HashMap<String, FollowedChannel>,
+    timezones: &HashMap<i64, String>,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> Vec<ScheduledStream> {
+    let mut starts_by_user: HashMap<i64, Vec<DateTime<Utc>>> = HashMap::new();
+    for &(uid, ts) in history {
+        if let Some(dt) = DateTime::from_timestamp(ts, 0) {
+            if dt < window_start {
+                starts_by_user.entry(uid).or_default().push(dt);
+            }
+        }
+    }
+
+    let mut inferred = Vec::new();
+
+    for (user_id, starts) in &starts_by_user {
+        let Some(channel) = channel_lookup.get(&user_id.to_string()) else {
+            continue;
+        };
+        let tz = timezones.get(user_id).and_then(|s| s.parse::<Tz>().ok());
+
+        let first = starts.iter().min().copied().unwrap_or(window_start);
+        let weeks_observed = ((window_start - first).num_seconds() as f64
+            / Duration::weeks(1).num_seconds() as f64)
+            .ceil()
+            .max(1.0) as f32;
+
+        // (seconds since local midnight, weeks before the window) per weekday
+        let mut by_weekday: HashMap<u32, Vec<(i64, usize)>> = HashMap::new();
+        for &start in starts {
+            let local = to_local(start, tz);
+            let weeks_ago = (window_start - start).num_weeks() as usize;
+            by_weekday
+                .entry(local.weekday().num_days_from_monday())
+                .or_default()
+                .push((i64::from(local.num_seconds_from_midnight()), weeks_ago));
+        }
+
+        for (weekday, pairs) in &by_weekday {
+            for cluster in cluster_offsets(pairs, 3600) {
+                let mut distinct_weeks: Vec<usize> = cluster.iter().map(|&(_, w)| w).collect();
+                distinct_weeks.sort_unstable();
+                distinct_weeks.dedup();
+
+                let confidence = (distinct_weeks.len() as f32 / weeks_observed).min(1.0);
+                if distinct_weeks.len() < 2 || confidence < MIN_WEEKDAY_CONFIDENCE {
+                    continue;
+                }
+
+                // Average time of day, rounded to the nearest 15 minutes (900s).
+                let sum: i64 = cluster.iter().map(|&(secs, _)| secs).sum();
+                let avg = sum as f64 / cluster.len() as f64;
+                let rounded = (((avg / 900.0).round() as i64) * 900).min(86_400 - 900);
+                let time = NaiveTime::from_num_seconds_from_midnight_opt(rounded as u32, 0)
+                    .expect("valid time of day");
+
+                let mut date = to_local(window_start, tz).date() - Duration::days(1);
+                let last_date = to_local(window_end, tz).date() + Duration::days(1);
+                while date <= last_date {
+                    if date.weekday().num_days_from_monday() == *weekday {
+                        let predicted_time = from_local(date.and_time(time), tz);
+                        if predicted_time >= window_start && predicted_time <= window_end {
+                            let ts = predicted_time.timestamp();
+                            inferred.push(ScheduledStream {
+                                id: format!("inferred_{user_id}_{ts}"),
+                                broadcaster_id: channel.broadcaster_id.clone(),
+                                broadcaster_name: channel.broadcaster_name.clone(),
+                                broadcaster_login: channel.broadcaster_login.clone(),
+                                title: String::new(),
+                                start_time: predicted_time,
+                                end_time: None,
+                                category: None,
+                                category_id: None,
+                                is_recurring: false,
+                                is_inferred: true,
+                                confidence: Some(confidence),
+                            });
+                        }
+                    }
+                    date += Duration::days(1);
+                }
+            }
+        }
+    }
+
+    inferred.sort_by_key(|s| s.start_time);
+    inferred
+}
+
 /// Projects a UTC stream time forward by `weeks` weeks, preserving wall-clock
 /// time in the streamer's timezone. Without a timezone, adds fixed seconds.
 fn project_forward(time: DateTime<Utc>, weeks: i64, tz: Option<Tz>) -> DateTime<Utc> {
-    let Some(tz) = tz else {
+    if tz.is_none() {
         return time + Duration::weeks(weeks);
+    }
+    let local = to_local(time, tz);
+    from_local(
+        (local.date() + Duration::weeks(weeks)).and_time(local.time()),
+        tz,
+    )
+}
+
+/// The streamer's wall-clock time for `time`, or UTC without a timezone.
+fn to_local(time: DateTime<Utc>, tz: Option<Tz>) -> NaiveDateTime {
+    tz.map_or(time.naive_utc(), |tz| time.with_timezone(&tz).naive_local())
+}
+
+/// Inverse of `to_local`. A wall-clock time skipped by DST moves forward 1 hour.
+fn from_local(local: NaiveDateTime, tz: Option<Tz>) -> DateTime<Utc> {
+    let Some(tz) = tz else {
+        return local.and_utc();
     };
-    let local = time.with_timezone(&tz).naive_local();
-    let target = (local.date() + Duration::weeks(weeks)).and_time(local.time());
-    tz.from_local_datetime(&target)
+    tz.from_local_datetime(&local)
         .earliest()
         .unwrap_or_else(|| {
             // DST gap — time doesn't exist; skip forward 1 hour
-            tz.from_local_datetime(&(target + Duration::hours(1)))
+            tz.from_local_datetime(&(local + Duration::hours(1)))
                 .earliest()
                 .expect("valid time after DST gap adjustment")
         })
@@ -632,6 +756,73 @@ mod tests {
         assert_eq!(with_tz[0].start_time, without_tz[0].start_time);
     }
 
+    // === infer_schedules_by_weekday tests ===
+
+    #[test]
+    fn weekday_model_predicts_regular_slot_with_confidence() {
+        // Wednesday 2025-07-16
+        let now = Utc.with_ymd_and_hms(2025, 7, 16, 14, 0, 0).unwrap();
+        let (start, end) = schedule_window(now);
+
+        // Wednesdays around 15:00 in 3 of the last 4 weeks, one skipped
+        let history = vec![
+            h(100, Utc.with_ymd_and_hms(2025, 6, 18, 15, 10, 0).unwrap()),
+            h(100, Utc.with_ymd_and_hms(2025, 7, 2, 14, 50, 0).unwrap()),
+            h(100, Utc.with_ymd_and_hms(2025, 7, 9, 15, 0, 0).unwrap()),
+        ];
+        let mut channels = HashMap::new();
+        channels.insert("100".to_string(), make_channel("100", "TestStreamer"));
+
+        let result = infer_schedules_by_weekday(&history, &channels, &no_timezones(), start, end);
+        assert_eq!(result.len(), 1);
+        assert!(result[0].is_inferred);
+        assert_eq!(
+            result[0].start_time,
+            Utc.with_ymd_and_hms(2025, 7, 16, 15, 0, 0).unwrap()
+        );
+        assert_eq!(result[0].confidence, Some(0.75));
+    }
+
+    #[test]
+    fn weekday_model_skips_rare_slots() {
+        let now = Utc.with_ymd_and_hms(2025, 7, 16, 14, 0, 0).unwrap();
+        let (start, end) = schedule_window(now);
+
+        // Wednesdays at 15:00 twice in eight weeks
+        let history = vec![
+            h(100, Utc.with_ymd_and_hms(2025, 5, 21, 12, 0, 0).unwrap()),
+            h(100, Utc.with_ymd_and_hms(2025, 6, 4, 15, 0, 0).unwrap()),
+            h(100, Utc.with_ymd_and_hms(2025, 7, 9, 15, 0, 0).unwrap()),
+        ];
+        let mut channels = HashMap::new();
+        channels.insert("100".to_string(), make_channel("100", "TestStreamer"));
+
+        let result = infer_schedules_by_weekday(&history, &channels, &no_timezones(), start, end);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn weekday_model_uses_streamer_wall_clock() {
+        // Thursday 2025-07-17 01:00 UTC is Wednesday 18:00 in Los Angeles
+        let now = Utc.with_ymd_and_hms(2025, 7, 16, 23, 0, 0).unwrap();
+        let (start, end) = schedule_window(now);
+
+        let history = vec![
+            h(100, Utc.with_ymd_and_hms(2025, 7, 3, 1, 0, 0).unwrap()),
+            h(100, Utc.with_ymd_and_hms(2025, 7, 10, 1, 0, 0).unwrap()),
+        ];
+        let mut channels = HashMap::new();
+        channels.insert("100".to_string(), make_channel("100", "TestStreamer"));
+        let timezones = HashMap::from([(100, "America/Los_Angeles".to_string())]);
+
+        let result = infer_schedules_by_weekday(&history, &channels, &timezones, start, end);
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].start_time,
+            Utc.with_ymd_and_hms(2025, 7, 17, 1, 0, 0).unwrap()
+        );
+    }
+
     // === cluster_offsets unit tests ===
 
     #[test]
@@ -0,0 +1,172 @@
+//! Reminders shortly before a streamer's scheduled stream.
+//!
+//! Streamers opt in one at a time with `StreamerSettings::schedule_reminder_min`.
+//! Both Twitch schedules and ones inferred from history count, so a reminder
+//! can come from a prediction. Each slot is announced once: the walker
+//! re-fetching a schedule, or a prediction being replaced by the real entry a
+//! few minutes off, doesn't repeat it.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+
+use crate::config::StreamerSettings;
+use crate::twitch::ScheduledStream;
+
+/// How often the backend looks for due reminders.
+pub const REMINDER_CHECK_SECS: u64 = 30;
+
+/// Starts this close together for one broadcaster are treated as the same slot.
+const SAME_SLOT_SECS: i64 = 3600;
+
+/// Which slots have already been announced.
+#[derive(Debug, Default)]
+pub struct ScheduleReminders {
+    sent: Vec<(String, DateTime<Utc>)>,
+}
+
+impl ScheduleReminders {
+    /// Returns the schedules whose reminder is due at `now`, marking them sent.
+    ///
+    /// A reminder is due once the start is no more than the streamer's
+    /// `schedule_reminder_min` away. Streamers in `live_user_ids` are skipped:
+    /// the go-live notification covers them.
+    pub fn due<'a>(
+        &mut self,
+        schedules: &'a [ScheduledStream],
+        settings: &HashMap<String, StreamerSettings>,
+        live_user_ids: &HashSet<String>,
+        now: DateTime<Utc>,
+    ) -> Vec<&'a ScheduledStream> {
+        // Forget slots long past so the list doesn't grow without bound
+        self.sent
+            .retain(|(_, start)| (now - *start).num_seconds() <= SAME_SLOT_SECS);
+
+        let mut due = Vec::new();
+        for schedule in schedules {
+            let Some(lead_min) = settings
+                .get(&schedule.broadcaster_login)
+                .and_then(|s| s.schedule_reminder_min)
+                .filter(|&m| m > 0)
+            else {
+                continue;
+            };
+            let until = schedule.start_time - now;
+            if until <= chrono::Duration::zero()
+                || until > chrono::Duration::minutes(lead_min as i64)
+                || live_user_ids.contains(&schedule.broadcaster_id)
+                || self.already_sent(schedule)
+            {
+                continue;
+            }
+            self.sent
+                .push((schedule.broadcaster_id.clone(), schedule.start_time));
+            due.push(schedule);
+        }
+        due
+    }
+
+    fn already_sent(&self, schedule: &ScheduledStream) -> bool {
+        self.sent.iter().any(|(id, start)| {
+            *id == schedule.broadcaster_id
+                && (*start - schedule.start_time).num_seconds().abs() <= SAME_SLOT_SECS
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StreamerImportance;
+    use chrono::Duration;
+
+    fn schedule(login: &str, start: DateTime<Utc>) -> ScheduledStream {
+        ScheduledStream {
+            id: format!("{login}-{}", start.timestamp()),
+            broadcaster_id: format!("id-{login}"),
+            broadcaster_name: login.to_string(),
+            broadcaster_login: login.to_string(),
+            title: "Stream".to_string(),
+            start_time: start,
+            end_time: None,
+            category: None,
+            category_id: None,
+            is_recurring: false,
+            is_inferred: false,
+            confidence: None,
+        }
+    }
+
+    fn reminding(login: &str, minutes: Option<u64>) -> HashMap<String, StreamerSettings> {
+        HashMap::from([(
+            login.to_string(),
+            StreamerSettings {
+                display_name: login.to_string(),
+                importance: StreamerImportance::Normal,
+                hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: None,
+                open_with: None,
+                schedule_reminder_min: minutes,
+                notify_on_category_change: true,
+            },
+        )])
+    }
+
+    #[test]
+    fn reminds_once_inside_the_lead_time() {
+        let now = Utc::now();
+        let schedules = vec![schedule("ninja", now + Duration::minutes(10))];
+        let settings = reminding("ninja", Some(15));
+        let mut reminders = ScheduleReminders::default();
+
+        let due = reminders.due(&schedules, &settings, &HashSet::new(), now);
+        assert_eq!(due.len(), 1);
+
+        let again = reminders.due(&schedules, &settings, &HashSet::new(), now);
+        assert!(again.is_empty());
+    }
+
+    #[test]
+    fn not_due_before_the_lead_time_or_without_opting_in() {
+        let now = Utc::now();
+        let schedules = vec![
+            schedule("ninja", now + Duration::minutes(20)),
+            schedule("shroud", now + Duration::minutes(5)),
+        ];
+        let mut settings = reminding("ninja", Some(15));
+        settings.extend(reminding("shroud", None));
+
+        let due = ScheduleReminders::default().due(&schedules, &settings, &HashSet::new(), now);
+
+        assert!(due.is_empty());
+    }
+
+    #[test]
+    fn skips_streamers_already_live() {
+        let now = Utc::now();
+        let schedules = vec![schedule("ninja", now + Duration::minutes(5))];
+        let live = HashSet::from(["id-ninja".to_string()]);
+
+        let due =
+            ScheduleReminders::default().due(&schedules, &reminding("ninja", Some(15)), &live, now);
+
+        assert!(due.is_empty());
+    }
+
+    #[test]
+    fn nearby_start_for_the_same_streamer_is_not_repeated() {
+        let now = Utc::now();
+        let settings = reminding("ninja", Some(15));
+        let mut reminders = ScheduleReminders::default();
+        let mut predicted = schedule("ninja", now + Duration::minutes(10));
+        predicted.is_inferred = true;
+        reminders.due(&[predicted], &settings, &HashSet::new(), now);
+
+        // The real schedule turns up a few minutes later than the prediction
+        let actual = vec![schedule("ninja", now + Duration::minutes(14))];
+        let due = reminders.due(&actual, &settings, &HashSet::new(), now);
+
+        assert!(due.is_empty());
+    }
+}
@@ -1,16 +1,19 @@
 //! Schedule queue walker: checks one broadcaster's schedule per tick.
 //!
 //! Instead of bulk-fetching all channels at once, the walker picks the
-//! most-stale broadcaster every `schedule_check_interval_sec` seconds and
-//! fetches one at a time. This ensures all followed channels eventually get
-//! a fresh schedule, not just the first 50.
+//! most-stale broadcaster each tick and fetches one at a time. This ensures
+//! all followed channels eventually get a fresh schedule, not just the first 50.
+//!
+//! The tick interval is derived from the followed count so a full sweep fits
+//! inside `schedule_stale_hours` (see [`derive_check_interval`]);
+//! `schedule_check_interval_sec` is the fastest it will go.
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use chrono::Utc;
 use tokio::task::JoinHandle;
-use tokio::time::Duration;
+use tokio::time::{Duration, Instant};
 
 use crate::config::ConfigManager;
 use crate::db::Database;
@@ -21,6 +24,24 @@ use crate::twitch::{ScheduleData, ScheduleVacation, ScheduledStream, TwitchClien
 /// Within this many seconds, an inferred schedule is considered a duplicate of an API schedule.
 const SCHEDULE_DEDUP_WINDOW_SECS: i64 = 3600;
 
+/// Share of the staleness window a full sweep is planned to take. The rest
+/// absorbs failed checks (retried next tick) and time spent suspended.
+const SWEEP_BUDGET_PERCENT: u64 = 80;
+
+/// Longest wait between checks however few channels are followed, so a
+/// newly followed channel's schedule shows up reasonably soon.
+pub const MAX_SCHEDULE_CHECK_INTERVAL_SECS: u64 = 900;
+
+/// Seconds between schedule checks so that `followed` channels are each
+/// checked within `stale_hours`, never faster than `min_secs`.
+pub fn derive_check_interval(followed: usize, stale_hours: u64, min_secs: u64) -> u64 {
+    if followed == 0 {
+        return min_secs;
+    }
+    let budget = stale_hours * 3600 * SWEEP_BUDGET_PERCENT / 100 / followed as u64;
+    budget.clamp(min_secs, MAX_SCHEDULE_CHECK_INTERVAL_SECS.max(min_secs))
+}
+
 /// Owns the schedule-refresh queue walk.
 ///
 /// One broadcaster is checked per tick; results are stored in SQLite and read
@@ -53,7 +74,7 @@ impl ScheduleWalker {
     /// Runs one iteration of the schedule queue: fetches the most-stale
     /// broadcaster's schedule and stores the result in the DB.
     pub async fn tick(&self) -> anyhow::Result<()> {
-        if !self.state.is_authenticated().await {
+        if !self.state.is_authenticated().await || self.state.outage().await.is_some() {
             return Ok(());
         }
 
@@ -71,11 +92,10 @@ impl ScheduleWalker {
         let bid_str = bid.to_string();
         tracing::debug!("Checking schedule for {} ({})", bname, bid);
 
-        match crate::twitch::with_retry(
-            || self.client.get_schedule(&bid_str),
-            || self.session.try_refresh_token(),
-        )
-        .await
+        match self
+            .session
+            .with_auth_retry(|| self.client.get_schedule(&bid_str))
+            .await
         {
             Ok(Some(data)) => {
                 // Persist broadcaster timezone if the API returned one
@@ -112,16 +132,41 @@ impl ScheduleWalker {
         Ok(())
     }
 
+    /// The current tick interval in seconds, from the followed count and config.
+    pub async fn check_interval_secs(&self) -> u64 {
+        let config = self.config.get();
+        let followed = self.state.get_followed_channels().await.len();
+        derive_check_interval(
+            followed,
+            config.schedule_stale_hours,
+            config.schedule_check_interval_sec,
+        )
+    }
+
     /// Spawns the schedule walker polling loop.
     ///
-    /// The tick interval is read from config on each iteration so that
-    /// config changes take effect without a restart.
+    /// The tick interval is re-derived on each iteration so that followed-list
+    /// changes take effect without a restart. A config save re-derives it
+    /// mid-wait, so a shorter interval applies straight away rather than after
+    /// the old one runs out.
     pub fn start(self: Arc<Self>) -> JoinHandle<()> {
         tokio::spawn(async move {
+            let mut config_rx = self.config.subscribe();
+            let mut last_interval = None;
             loop {
-                let tick_duration =
-                    Duration::from_secs(self.config.get().schedule_check_interval_sec);
-                tokio::time::sleep(tick_duration).await;
+                let waiting_since = Instant::now();
+                loop {
+                    let interval = self.check_interval_secs().await;
+                    if last_interval != Some(interval) {
+                        tracing::info!("Schedule walker checking one channel every {}s", interval);
+                        last_interval = Some(interval);
+                    }
+                    let due = waiting_since + Duration::from_secs(interval);
+                    tokio::select! {
+                        () = tokio::time::sleep_until(due) => break,
+                        Ok(()) = config_rx.changed() => {}
+                    }
+                }
                 if let Err(e) = self.tick().await {
                     tracing::error!("Schedule walker error: {}", e);
                 }
@@ -130,12 +175,30 @@ impl ScheduleWalker {
     }
 
     /// Reads upcoming schedules from DB, merges with inferred schedules, and updates state.
+    pub async fn refresh_schedules_from_db(&self) {
+        if let Some(schedules) = self.read_schedules_from_db().await {
+            self.state.set_scheduled_streams(schedules).await;
+        }
+    }
+
+    /// Loads schedules stored by a previous run, marked as cached, so the
+    /// menu has something to show before the session is restored.
+    pub async fn load_cached_schedules(&self) {
+        if let Some(schedules) = self.read_schedules_from_db().await {
+            tracing::debug!("Loaded {} cached schedule(s)", schedules.len());
+            self.state.set_cached_scheduled_streams(schedules).await;
+        }
+    }
+
+    /// Reads upcoming schedules from DB merged with inferred schedules, unless
+    /// `show_inferred_schedules` is off.
     ///
     /// Both API and inferred schedules use the same display window:
     /// `[now - schedule_before_now_min, now + schedule_lookahead_hours]`.
     /// Deduplication removes inferred entries that overlap with an API schedule
-    /// for the same broadcaster within 60 minutes.
-    pub async fn refresh_schedules_from_db(&self) {
+    /// for the same broadcaster within 60 minutes. Returns `None` if the DB
+    /// read fails.
+    async fn read_schedules_from_db(&self) -> Option<Vec<ScheduledStream>> {
         let cfg = self.config.get();
         let now = Utc::now();
         let start = now - chrono::Duration::minutes(cfg.schedule_before_now_min as i64);
@@ -145,10 +208,14 @@ impl ScheduleWalker {
             Ok(s) => s,
             Err(e) => {
                 tracing::error!("Failed to read schedules from DB: {}", e);
-                return;
+                return None;
             }
         };
 
+        if !cfg.show_inferred_schedules {
+            return Some(db_schedules);
+        }
+
         // Infer schedules from stream history using the same window
         let channels = self.state.get_followed_channels().await;
         let channel_lookup: HashMap<String, _> = channels
@@ -157,7 +224,13 @@ impl ScheduleWalker {
             .collect();
 
         let mut combined = db_schedules;
-        match self.db.infer_schedules(&channel_lookup, start, end) {
+        match self.db.infer_schedules(
+            &channel_lookup,
+            start,
+            end,
+            cfg.schedule_inference_model,
+            cfg.schedule_inference_lookback_weeks,
+        ) {
             Ok(inferred) => {
                 if !inferred.is_empty() {
                     // Deduplicate: skip inferred schedules that overlap with an
@@ -184,7 +257,7 @@ impl ScheduleWalker {
             }
         }
 
-        self.state.set_scheduled_streams(combined).await;
+        Some(combined)
     }
 }
 
@@ -224,6 +297,7 @@ fn convert_schedule_segments(data: &ScheduleData) -> Vec<ScheduledStream> {
             category_id: seg.category.as_ref().map(|c| c.id.clone()),
             is_recurring: seg.is_recurring,
             is_inferred: false,
+            confidence: None,
         })
         .collect()
 }
@@ -234,6 +308,39 @@ mod tests {
     use crate::twitch::{ScheduleCategory, ScheduleSegment};
     use chrono::{Duration, TimeZone, Utc};
 
+    // === derive_check_interval ===
+
+    #[test]
+    fn large_follow_list_sweeps_within_staleness_window() {
+        let interval = derive_check_interval(1000, 24, 10);
+        assert_eq!(interval, 69);
+        assert!(interval * 1000 <= 24 * 3600);
+    }
+
+    #[test]
+    fn small_follow_list_slows_to_the_maximum() {
+        assert_eq!(
+            derive_check_interval(20, 24, 10),
+            MAX_SCHEDULE_CHECK_INTERVAL_SECS
+        );
+    }
+
+    #[test]
+    fn configured_interval_is_the_floor() {
+        assert_eq!(derive_check_interval(50_000, 24, 10), 10);
+        assert_eq!(derive_check_interval(1000, 24, 120), 120);
+    }
+
+    #[test]
+    fn configured_floor_above_maximum_wins() {
+        assert_eq!(derive_check_interval(20, 24, 1200), 1200);
+    }
+
+    #[test]
+    fn no_followed_channels_uses_configured_interval() {
+        assert_eq!(derive_check_interval(0, 24, 10), 10);
+    }
+
     fn make_schedule_data(
         segments: Vec<ScheduleSegment>,
         vacation: Option<ScheduleVacation>,
@@ -9,11 +9,11 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::{watch, Mutex, RwLock};
 
-use crate::auth::{DeviceFlow, Token, TokenStore, CLIENT_ID};
+use crate::auth::{DeviceFlow, DeviceFlowError, Token, TokenStore, CLIENT_ID};
 use crate::db::Database;
 use crate::handle::LoginProgress;
 use crate::state::AppState;
-use crate::twitch::TwitchClient;
+use crate::twitch::{ApiError, TwitchClient};
 
 /// Manages the auth lifecycle: session restore, login, logout, and token refresh.
 pub struct SessionManager {
@@ -68,7 +68,10 @@ impl SessionManager {
     /// Returns `Err` if no valid token can be obtained.
     pub async fn restore_session(&self) -> anyhow::Result<()> {
         let mut token = self.store.load_token()?;
-        let flow = DeviceFlow::new(CLIENT_ID.to_string());
+        // Validation and the first poll both hit the network; show the last
+        // run's streams meanwhile.
+        self.load_cached_streams().await;
+        let flow = DeviceFlow::new(CLIENT_ID.to_string(), self.client.http().clone());
 
         let needs_refresh = if token.is_expired() {
             tracing::info!("Token expired, attempting refresh...");
@@ -94,6 +97,17 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Puts streams left in SQLite by a previous run into state.
+    async fn load_cached_streams(&self) {
+        match self.db.get_cached_streams() {
+            Ok((followed, categories)) => {
+                tracing::debug!("Loaded {} cached live stream(s)", followed.len());
+                self.state.set_cached_streams(followed, categories).await;
+            }
+            Err(e) => tracing::warn!("Failed to load cached streams: {}", e),
+        }
+    }
+
     /// Sets up the client and state for an authenticated session, then loads
     /// followed channels.
     pub async fn initialize_session(&self, token: &Token) -> anyhow::Result<()> {
@@ -105,6 +119,15 @@ impl SessionManager {
         self.state
             .set_authenticated(true, token.user_id.clone(), token.user_login.clone())
             .await;
+        self.state.set_token_expiry(token.expires_at).await;
+        let missing_scopes = token.missing_scopes();
+        if !missing_scopes.is_empty() {
+            tracing::warn!(
+                "Token lacks scopes {}; a new login is needed",
+                missing_scopes.join(", ")
+            );
+        }
+        self.state.set_missing_scopes(missing_scopes).await;
 
         if let Err(e) = self.load_followed_channels().await {
             tracing::warn!("Failed to load followed channels: {}", e);
@@ -113,14 +136,22 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Runs a Helix call, refreshing the token and retrying once if it's
+    /// rejected. Every API call goes through here.
+    pub async fn with_auth_retry<F, Fut, T>(&self, f: F) -> Result<T, ApiError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, ApiError>>,
+    {
+        crate::twitch::with_retry(f, || self.try_refresh_token()).await
+    }
+
     /// Fetches all followed channels from the API and syncs them to the DB.
     pub async fn load_followed_channels(&self) -> anyhow::Result<()> {
-        let follows = crate::twitch::with_retry(
-            || self.client.get_all_followed_channels(),
-            || self.try_refresh_token(),
-        )
-        .await
-        .map_err(anyhow::Error::from)?;
+        let follows = self
+            .with_auth_retry(|| self.client.get_all_followed_channels())
+            .await
+            .map_err(anyhow::Error::from)?;
 
         self.db.sync_followed(&follows)?;
         let ids = self.db.get_followed_ids()?;
@@ -145,15 +176,44 @@ impl SessionManager {
         }
 
         tracing::info!("Token expired during API call, attempting refresh...");
+        self.refresh_stored_token().await
+    }
+
+    /// Refreshes the OAuth token ahead of its expiry, so API calls don't
+    /// have to fail first. Does nothing if it isn't due at `now`, e.g.
+    /// because another task refreshed it meanwhile.
+    pub async fn refresh_token_ahead(&self, now: DateTime<Utc>) -> anyhow::Result<()> {
+        let _guard = self.refresh_mutex.lock().await;
+
+        let due = self
+            .state
+            .token_expiry()
+            .await
+            .is_some_and(|expiry| now >= expiry.next_attempt());
+        if !due {
+            return Ok(());
+        }
+
+        tracing::info!("Token expires soon, attempting refresh...");
+        let result = self.refresh_stored_token().await;
+        if result.is_err() {
+            self.state.record_token_refresh_failure(now).await;
+        }
+        result
+    }
 
+    /// Swaps the stored refresh token for a new token. Callers hold
+    /// `refresh_mutex`.
+    async fn refresh_stored_token(&self) -> anyhow::Result<()> {
         let token = self.store.load_token()?;
-        let flow = DeviceFlow::new(CLIENT_ID.to_string());
+        let flow = DeviceFlow::new(CLIENT_ID.to_string(), self.client.http().clone());
         let new_token = flow.refresh_token(&token.refresh_token).await?;
 
         self.store.save_token(&new_token)?;
         self.client
             .set_access_token(new_token.access_token.clone())
             .await;
+        self.state.set_token_expiry(new_token.expires_at).await;
 
         tracing::info!("Token refreshed successfully");
         Ok(())
@@ -165,9 +225,10 @@ impl SessionManager {
     /// responsible for spawning this in a background task and for performing
     /// the initial data refresh (`App::refresh_all_data`) on success.
     pub async fn handle_login(&self, cancel: watch::Receiver<bool>) -> anyhow::Result<()> {
-        let flow = DeviceFlow::new(CLIENT_ID.to_string());
+        let flow = DeviceFlow::new(CLIENT_ID.to_string(), self.client.http().clone());
 
-        let token = run_device_flow(
+        self.state.set_authenticating(true).await;
+        let result = run_device_flow(
             flow,
             cancel,
             self.login_progress_tx.clone(),
@@ -177,7 +238,9 @@ impl SessionManager {
                 }
             },
         )
-        .await?;
+        .await;
+        self.state.set_authenticating(false).await;
+        let token = result?;
 
         self.store.save_token(&token)?;
         self.initialize_session(&token).await?;
@@ -192,6 +255,35 @@ impl SessionManager {
             tracing::error!("Failed to delete token: {}", e);
         }
 
+        self.end_session().await;
+    }
+
+    /// Logs out and runs a fresh device flow for another account.
+    ///
+    /// The previous token stays in the store until the new login saves over
+    /// it, so if the login is cancelled or fails the previous session is
+    /// restored. Returns the login error either way; check
+    /// `AppState::is_authenticated` to see whether the restore worked.
+    pub async fn handle_switch_user(&self, cancel: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let had_session = self.state.is_authenticated().await;
+        self.end_session().await;
+
+        let Err(e) = self.handle_login(cancel).await else {
+            return Ok(());
+        };
+        if had_session {
+            match self.restore_session().await {
+                Ok(()) => tracing::info!("Restored previous session after switch user failed"),
+                Err(restore_err) => {
+                    tracing::warn!("Failed to restore previous session: {}", restore_err);
+                }
+            }
+        }
+        Err(e)
+    }
+
+    /// Clears client credentials and app state, leaving the stored token alone.
+    async fn end_session(&self) {
         self.state.clear().await;
         self.client.clear_auth().await;
         self.initial_load_done.store(false, Ordering::SeqCst);
@@ -202,9 +294,12 @@ impl SessionManager {
         self.initial_load_done.store(true, Ordering::SeqCst);
     }
 
-    /// Records the current time as the last successful live-stream refresh.
+    /// Records the current time as the last successful live-stream refresh,
+    /// here for polling and in `AppState` for the menu.
     pub async fn record_live_refresh(&self) {
-        *self.last_live_refresh.write().await = Some(Utc::now());
+        let now = Utc::now();
+        *self.last_live_refresh.write().await = Some(now);
+        self.state.set_last_refresh(now).await;
     }
 
     /// Returns the time of the last successful live-stream refresh.
@@ -215,28 +310,30 @@ impl SessionManager {
 
 /// Runs the device code flow, emitting `LoginProgress` updates on `progress_tx`.
 ///
-/// Calls `on_browser` with the `verification_uri` once the device code is obtained
-/// (e.g. to open the URL in the system browser).
-/// Sends `PendingCode` with both the user code and URI, `Confirmed` on success,
+/// Calls `on_browser` with the `verification_uri` each time a device code is obtained
+/// (e.g. to open the URL in the system browser). That is more than once when an
+/// expired code is replaced.
+/// Sends `PendingCode` with the user code, URI and expiry, `Confirmed` on success,
 /// `Failed` on error. Returns the token on success.
 async fn run_device_flow<H, F>(
     flow: DeviceFlow<H>,
     cancel: watch::Receiver<bool>,
     progress_tx: watch::Sender<Option<LoginProgress>>,
-    on_browser: F,
+    mut on_browser: F,
 ) -> anyhow::Result<Token>
 where
     H: crate::twitch::http::HttpClient,
-    F: FnOnce(&str),
+    F: FnMut(&str),
 {
     let tx_for_callback = progress_tx.clone();
 
     let result = flow
         .authenticate(
-            |user_code, verification_uri| {
+            |user_code, verification_uri, expires_at| {
                 let _ = tx_for_callback.send(Some(LoginProgress::PendingCode {
                     user_code: user_code.to_string(),
                     verification_uri: verification_uri.to_string(),
+                    expires_at,
                 }));
                 on_browser(verification_uri);
             },
@@ -249,6 +346,10 @@ where
             let _ = progress_tx.send(Some(LoginProgress::Confirmed));
             Ok(token)
         }
+        Err(e) if login_cancelled(&e) => {
+            let _ = progress_tx.send(None);
+            Err(e)
+        }
         Err(e) => {
             let _ = progress_tx.send(Some(LoginProgress::Failed(e.to_string())));
             Err(e)
@@ -256,10 +357,18 @@ where
     }
 }
 
+/// Whether a login failed because the user cancelled it.
+pub(crate) fn login_cancelled(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<DeviceFlowError>(),
+        Some(DeviceFlowError::Cancelled)
+    )
+}
+
 impl Clone for SessionManager {
     fn clone(&self) -> Self {
         Self {
-            store: TokenStore::new().expect("Failed to create token store"),
+            store: self.store.clone(),
             client: self.client.clone(),
             state: self.state.clone(),
             db: self.db.clone(),
@@ -384,6 +493,20 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn login_progress_cleared_on_cancel() {
+        let mock = MockHttpClient::new().on_post_json(DEVICE_CODE_URL, &device_code_body("XYZ"));
+        let flow = DeviceFlow::with_http_client("client_id".into(), mock);
+
+        let (progress_tx, progress_rx) = watch::channel(None::<LoginProgress>);
+        let (_cancel_tx, cancel_rx) = watch::channel(true);
+
+        let result = run_device_flow(flow, cancel_rx, progress_tx, |_| {}).await;
+
+        assert!(login_cancelled(&result.unwrap_err()));
+        assert_eq!(*progress_rx.borrow(), None);
+    }
+
     #[tokio::test]
     async fn login_progress_sends_failed_on_error() {
         // access_denied is returned immediately after device code
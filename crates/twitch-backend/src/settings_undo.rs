@@ -0,0 +1,191 @@
+//! Undo for streamer settings changes.
+//!
+//! Every `save_config` records which streamers' settings it changed and what
+//! they were before, so a misclick (say, marking someone Ignore and losing
+//! them from the menu) can be reverted from the tray. Removed settings are
+//! kept here too, which makes removal a soft delete until the entry falls
+//! off the end of the buffer.
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::config::StreamerSettings;
+
+/// How many changes can be undone.
+pub const UNDO_LIMIT: usize = 10;
+
+/// One streamer's settings as they were before a save.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsChange {
+    pub user_login: String,
+    /// `None` if the streamer had no settings entry.
+    pub previous: Option<StreamerSettings>,
+}
+
+impl SettingsChange {
+    /// Puts the previous settings back, removing the entry if there was none.
+    pub fn restore(self, settings: &mut HashMap<String, StreamerSettings>) {
+        match self.previous {
+            Some(previous) => {
+                settings.insert(self.user_login, previous);
+            }
+            None => {
+                settings.remove(&self.user_login);
+            }
+        }
+    }
+}
+
+/// The most recent [`UNDO_LIMIT`] changes, oldest first.
+#[derive(Debug, Default)]
+pub struct UndoBuffer {
+    changes: VecDeque<SettingsChange>,
+}
+
+impl UndoBuffer {
+    /// Records every streamer whose settings differ between `old` and `new`.
+    ///
+    /// A save touching several streamers records one change per streamer, in
+    /// login order, so each is undone separately.
+    pub fn record(
+        &mut self,
+        old: &HashMap<String, StreamerSettings>,
+        new: &HashMap<String, StreamerSettings>,
+    ) {
+        let logins: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+        for login in logins {
+            let previous = old.get(login);
+            if previous != new.get(login) {
+                self.changes.push_back(SettingsChange {
+                    user_login: login.clone(),
+                    previous: previous.cloned(),
+                });
+            }
+        }
+        while self.changes.len() > UNDO_LIMIT {
+            self.changes.pop_front();
+        }
+    }
+
+    /// The change the next undo would revert.
+    pub fn latest(&self) -> Option<&SettingsChange> {
+        self.changes.back()
+    }
+
+    /// Removes and returns the most recent change.
+    pub fn pop(&mut self) -> Option<SettingsChange> {
+        self.changes.pop_back()
+    }
+
+    /// Puts back a change whose undo couldn't be saved.
+    pub fn push(&mut self, change: SettingsChange) {
+        self.changes.push_back(change);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StreamerImportance;
+
+    fn settings(importance: StreamerImportance) -> StreamerSettings {
+        StreamerSettings {
+            display_name: "Ninja".to_string(),
+            importance,
+            hotness_z_threshold_override: None,
+            notification_open_mode: None,
+            notification_sound: None,
+            open_with: None,
+            schedule_reminder_min: None,
+            notify_on_category_change: true,
+        }
+    }
+
+    fn map(entries: &[(&str, StreamerImportance)]) -> HashMap<String, StreamerSettings> {
+        entries
+            .iter()
+            .map(|(login, importance)| (login.to_string(), settings(*importance)))
+            .collect()
+    }
+
+    #[test]
+    fn records_only_changed_streamers() {
+        let mut buffer = UndoBuffer::default();
+        let old = map(&[
+            ("ninja", StreamerImportance::Normal),
+            ("shroud", StreamerImportance::Favourite),
+        ]);
+        let new = map(&[
+            ("ninja", StreamerImportance::Ignore),
+            ("shroud", StreamerImportance::Favourite),
+        ]);
+
+        buffer.record(&old, &new);
+
+        assert_eq!(
+            buffer.pop(),
+            Some(SettingsChange {
+                user_login: "ninja".to_string(),
+                previous: Some(settings(StreamerImportance::Normal)),
+            })
+        );
+        assert_eq!(buffer.pop(), None);
+    }
+
+    #[test]
+    fn undoing_an_added_entry_removes_it() {
+        let mut buffer = UndoBuffer::default();
+        let mut current = map(&[("ninja", StreamerImportance::Ignore)]);
+        buffer.record(&HashMap::new(), &current);
+
+        buffer.pop().unwrap().restore(&mut current);
+
+        assert!(current.is_empty());
+    }
+
+    #[test]
+    fn undoing_a_removal_restores_the_entry() {
+        let mut buffer = UndoBuffer::default();
+        let old = map(&[("ninja", StreamerImportance::Favourite)]);
+        let mut current = HashMap::new();
+        buffer.record(&old, &current);
+
+        buffer.pop().unwrap().restore(&mut current);
+
+        assert_eq!(current, old);
+    }
+
+    #[test]
+    fn unchanged_save_records_nothing() {
+        let mut buffer = UndoBuffer::default();
+        let settings = map(&[("ninja", StreamerImportance::Normal)]);
+        buffer.record(&settings, &settings);
+        assert!(buffer.latest().is_none());
+    }
+
+    #[test]
+    fn keeps_only_the_most_recent_changes() {
+        let mut buffer = UndoBuffer::default();
+        for i in 0..UNDO_LIMIT + 3 {
+            let login = format!("streamer{i}");
+            let new = map(&[(login.as_str(), StreamerImportance::Ignore)]);
+            buffer.record(&HashMap::new(), &new);
+        }
+
+        let mut undone = 0;
+        while buffer.pop().is_some() {
+            undone += 1;
+        }
+        assert_eq!(undone, UNDO_LIMIT);
+    }
+
+    #[test]
+    fn latest_change_is_undone_first() {
+        let mut buffer = UndoBuffer::default();
+        buffer.record(&HashMap::new(), &map(&[("a", StreamerImportance::Ignore)]));
+        buffer.record(&HashMap::new(), &map(&[("b", StreamerImportance::Ignore)]));
+
+        assert_eq!(buffer.latest().unwrap().user_login, "b");
+        assert_eq!(buffer.pop().unwrap().user_login, "b");
+        assert_eq!(buffer.pop().unwrap().user_login, "a");
+    }
+}
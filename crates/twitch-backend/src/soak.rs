@@ -0,0 +1,465 @@
+//! Soak tests: the polling timers driven over simulated days.
+//!
+//! Unit tests check each timing decision at one instant. These step a
+//! simulated clock one second at a time, the way the background tasks tick,
+//! and run the same decisions together: live polling, the followed-channels
+//! refresh, the schedule queue walker, token refresh on 401, offline probing
+//! and notification gap suppression. Scenarios add suspend/resume, API outages and token expiry
+//! mid-cycle, and check the request rate stays inside Twitch's limit.
+//!
+//! Slow (weeks of simulated seconds), so only built with
+//! `cargo test -p twitch-backend --features soak`.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::Range;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+use crate::auth::Token;
+use crate::backend::refresh_due;
+use crate::config::{Config, FollowedCategory};
+use crate::connectivity::{Outage, PROBE_MAX_SECS};
+use crate::db::Database;
+use crate::notification_filter::filter_notifications;
+use crate::schedule_walker::derive_check_interval;
+use crate::state::StreamsUpdated;
+use crate::test_helpers::make_stream;
+use crate::twitch::{with_retry, ApiError, FollowedChannel};
+
+/// Helix rate limit: points per minute per client ID. Every call made here costs 1.
+const HELIX_POINTS_PER_MIN: usize = 800;
+
+/// How long Twitch device-flow access tokens last.
+const TOKEN_LIFETIME_HOURS: i64 = 4;
+
+const DAY_SECS: i64 = 86_400;
+const HOUR_SECS: i64 = 3_600;
+
+/// One simulated install, advanced a second at a time.
+struct Sim {
+    now: DateTime<Utc>,
+    config: Config,
+    db: Database,
+    _dir: tempfile::TempDir,
+    token: RefCell<Token>,
+    /// Seconds (relative to the start) during which every API call fails.
+    outages: Vec<Range<i64>>,
+    start: DateTime<Utc>,
+    /// Set while a failed live poll has the app offline, as `AppState` does.
+    outage: Option<Outage>,
+    /// Set by a resume until the next tick, as `Backend::handle_resume` does.
+    resumed: bool,
+    /// Suspended time for the next stream update, as `AppState::record_resume` keeps it.
+    suspended: Option<Duration>,
+
+    last_live_refresh: Option<DateTime<Utc>>,
+    last_followed_refresh: Option<DateTime<Utc>>,
+    followed: usize,
+    /// Awake seconds until the schedule walker's sleep ends. Tokio timers are
+    /// monotonic, so they don't advance while the machine is suspended.
+    walker_countdown: u64,
+    last_stream_event: Option<DateTime<Utc>>,
+    last_checked: HashMap<i64, DateTime<Utc>>,
+
+    /// Send time of every API call in the last minute.
+    recent_requests: VecDeque<DateTime<Utc>>,
+    peak_requests_per_min: usize,
+    live_polls: u32,
+    token_refreshes: Cell<u32>,
+    /// Stream updates whose notifications the gap check suppressed / let through.
+    suppressed_updates: u32,
+    notified_updates: u32,
+    /// Longest time any followed channel's schedule went unchecked, as seen while awake.
+    worst_schedule_age: Duration,
+}
+
+impl Sim {
+    fn new(followed: usize) -> Self {
+        let start = Utc.with_ymd_and_hms(2026, 1, 5, 0, 0, 0).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("data.db")).unwrap();
+
+        let channels: Vec<FollowedChannel> = (1..=followed)
+            .map(|i| FollowedChannel {
+                broadcaster_id: i.to_string(),
+                broadcaster_login: format!("streamer{i}"),
+                broadcaster_name: format!("Streamer{i}"),
+                followed_at: start,
+            })
+            .collect();
+        db.sync_followed(&channels).unwrap();
+        db.ensure_schedule_queue_entries(&db.get_followed_ids().unwrap())
+            .unwrap();
+
+        let config = Config {
+            followed_categories: vec![
+                FollowedCategory {
+                    id: "509658".to_string(),
+                    name: "Just Chatting".to_string(),
+                    ..Default::default()
+                },
+                FollowedCategory {
+                    id: "743".to_string(),
+                    name: "Chess".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Config::default()
+        };
+
+        Self {
+            now: start,
+            followed,
+            walker_countdown: derive_check_interval(
+                followed,
+                config.schedule_stale_hours,
+                config.schedule_check_interval_sec,
+            ),
+            config,
+            db,
+            _dir: dir,
+            token: RefCell::new(Token {
+                access_token: "access".to_string(),
+                refresh_token: "refresh".to_string(),
+                expires_at: start + Duration::hours(TOKEN_LIFETIME_HOURS),
+                scopes: vec!["user:read:follows".to_string()],
+                user_id: "1".to_string(),
+                user_login: "viewer".to_string(),
+            }),
+            outages: Vec::new(),
+            start,
+            outage: None,
+            resumed: false,
+            suspended: None,
+            last_live_refresh: None,
+            last_followed_refresh: None,
+            last_stream_event: None,
+            last_checked: HashMap::new(),
+            recent_requests: VecDeque::new(),
+            peak_requests_per_min: 0,
+            live_polls: 0,
+            token_refreshes: Cell::new(0),
+            suppressed_updates: 0,
+            notified_updates: 0,
+            worst_schedule_age: Duration::zero(),
+        }
+    }
+
+    /// Runs `secs` seconds with the machine awake.
+    async fn run_awake(&mut self, secs: i64) {
+        for _ in 0..secs {
+            self.tick().await;
+            self.now += Duration::seconds(1);
+        }
+    }
+
+    /// Skips `secs` seconds with the machine suspended: no task runs. The
+    /// OS reports the resume (see `power`).
+    fn suspend(&mut self, secs: i64) {
+        self.now += Duration::seconds(secs);
+        self.resumed = true;
+        self.suspended = Some(self.suspended.unwrap_or_default() + Duration::seconds(secs));
+    }
+
+    /// One pass of every polling task, as their 1s loops would do it.
+    async fn tick(&mut self) {
+        let cfg = self.config.clone();
+
+        // Stream polling task, probing instead while offline; a resume does
+        // either straight away
+        let resumed = std::mem::take(&mut self.resumed);
+        let mut poll_due =
+            resumed || refresh_due(self.now, self.last_live_refresh, cfg.poll_interval_sec);
+        if let Some(mut outage) = self.outage {
+            poll_due = false;
+            if resumed || self.now >= outage.next_probe() {
+                if self.call_api().await {
+                    self.outage = None;
+                    poll_due = true;
+                } else {
+                    outage.record_failed_probe(self.now);
+                    self.outage = Some(outage);
+                }
+            }
+        }
+        if poll_due {
+            self.live_polls += 1;
+            if self.call_api().await {
+                self.last_live_refresh = Some(self.now);
+                self.stream_update(&cfg);
+            } else {
+                self.outage.get_or_insert(Outage::new(self.now));
+            }
+            for _ in &cfg.followed_categories {
+                self.call_api().await;
+            }
+        }
+        let online = self.outage.is_none();
+
+        // Followed channels refresh task
+        if online
+            && refresh_due(
+                self.now,
+                self.last_followed_refresh,
+                cfg.followed_refresh_min * 60,
+            )
+            && self.call_api().await
+        {
+            self.last_followed_refresh = Some(self.now);
+        }
+
+        // Schedule queue walker
+        self.walker_countdown -= 1;
+        if self.walker_countdown == 0 {
+            self.walker_countdown = derive_check_interval(
+                self.followed,
+                cfg.schedule_stale_hours,
+                cfg.schedule_check_interval_sec,
+            );
+            let stale_secs = (cfg.schedule_stale_hours * 3600) as i64;
+            let next = self
+                .db
+                .get_next_stale_broadcaster_at(self.now, stale_secs)
+                .unwrap();
+            if let Some((bid, _, _)) = next.filter(|_| online) {
+                if self.call_api().await {
+                    self.db.update_last_checked_at(bid, self.now).unwrap();
+                    self.last_checked.insert(bid, self.now);
+                }
+            }
+        }
+
+        if (self.now - self.start).num_seconds() % HOUR_SECS == 0 {
+            self.record_schedule_age();
+        }
+    }
+
+    /// A Helix call through `with_retry`: 401 while the token is expired, then
+    /// a refresh and one retry. Returns whether the call succeeded.
+    async fn call_api(&mut self) -> bool {
+        let now = self.now;
+        let offset = (now - self.start).num_seconds();
+        let down = self.outages.iter().any(|o| o.contains(&offset));
+        let sent = Cell::new(0);
+
+        let result = with_retry(
+            || {
+                sent.set(sent.get() + 1);
+                let expired = self.token.borrow().is_expired_at(now);
+                async move {
+                    if down {
+                        Err(ApiError::Other(anyhow::anyhow!("network unreachable")))
+                    } else if expired {
+                        Err(ApiError::Unauthorized)
+                    } else {
+                        Ok(())
+                    }
+                }
+            },
+            || {
+                self.token.borrow_mut().expires_at = now + Duration::hours(TOKEN_LIFETIME_HOURS);
+                self.token_refreshes.set(self.token_refreshes.get() + 1);
+                async { Ok(()) }
+            },
+        )
+        .await;
+
+        for _ in 0..sent.get() {
+            self.recent_requests.push_back(now);
+        }
+        while self
+            .recent_requests
+            .front()
+            .is_some_and(|&t| now - t >= Duration::minutes(1))
+        {
+            self.recent_requests.pop_front();
+        }
+        self.peak_requests_per_min = self.peak_requests_per_min.max(self.recent_requests.len());
+
+        result.is_ok()
+    }
+
+    /// Feeds a poll result with one newly live stream through the gap check,
+    /// as `NotificationDispatcher` does for each `StreamsUpdated`.
+    fn stream_update(&mut self, cfg: &Config) {
+        let event = StreamsUpdated {
+            streams: Vec::new(),
+            newly_live: vec![make_stream("1", "Streamer1")],
+            category_changes: Vec::new(),
+            ended: Vec::new(),
+            suspended: self.suspended.take(),
+        };
+        let decision = filter_notifications(
+            &event,
+            self.last_stream_event,
+            self.now,
+            cfg.notify_max_gap_min * 60,
+            true,
+            &cfg.streamer_settings,
+            &HashSet::new(),
+        );
+        self.last_stream_event = Some(self.now);
+        if decision.streams_to_notify.is_empty() {
+            self.suppressed_updates += 1;
+        } else {
+            self.notified_updates += 1;
+        }
+    }
+
+    fn record_schedule_age(&mut self) {
+        let oldest = self
+            .db
+            .get_followed_ids()
+            .unwrap()
+            .iter()
+            .map(|id| self.last_checked.get(id).copied().unwrap_or(self.start))
+            .min()
+            .unwrap_or(self.now);
+        self.worst_schedule_age = self.worst_schedule_age.max(self.now - oldest);
+    }
+}
+
+/// Awake 16 hours, suspended 8, for `days` days.
+async fn run_nightly_suspend(sim: &mut Sim, days: i64) {
+    for _ in 0..days {
+        sim.run_awake(16 * HOUR_SECS).await;
+        sim.suspend(8 * HOUR_SECS);
+    }
+}
+
+// === steady polling ===
+
+#[tokio::test]
+async fn week_awake_polls_on_interval_within_rate_limit() {
+    let mut sim = Sim::new(300);
+    sim.run_awake(7 * DAY_SECS).await;
+
+    let interval = sim.config.poll_interval_sec as i64;
+    assert_eq!(i64::from(sim.live_polls), 7 * DAY_SECS / interval);
+    assert!(
+        sim.peak_requests_per_min < HELIX_POINTS_PER_MIN,
+        "peak of {} requests/min",
+        sim.peak_requests_per_min
+    );
+    assert_eq!(sim.suppressed_updates, 0);
+}
+
+#[tokio::test]
+async fn each_token_expiry_costs_one_refresh() {
+    let mut sim = Sim::new(50);
+    sim.run_awake(7 * DAY_SECS).await;
+
+    // Refreshed on the first call after each expiry; calls never stall on 401.
+    // The last token issued outlives the run.
+    let expiries = 7 * 24 / TOKEN_LIFETIME_HOURS - 1;
+    assert_eq!(i64::from(sim.token_refreshes.get()), expiries);
+    assert_eq!(i64::from(sim.notified_updates), i64::from(sim.live_polls));
+}
+
+// === suspend / resume ===
+
+#[tokio::test]
+async fn notifications_suppressed_once_per_wake() {
+    let mut sim = Sim::new(50);
+    run_nightly_suspend(&mut sim, 5).await;
+
+    // The first update after each night is a catch-up, not a go-live
+    assert_eq!(sim.suppressed_updates, 4);
+    assert_eq!(
+        sim.suppressed_updates + sim.notified_updates,
+        sim.live_polls
+    );
+}
+
+#[tokio::test]
+async fn polling_resumes_immediately_after_wake() {
+    let mut sim = Sim::new(50);
+    sim.run_awake(HOUR_SECS).await;
+    sim.suspend(8 * HOUR_SECS);
+    let woke_at = sim.now;
+    sim.run_awake(1).await;
+
+    assert_eq!(sim.last_live_refresh, Some(woke_at));
+    // The token lapsed overnight and was refreshed by that first poll
+    assert!(sim.token.borrow().expires_at > woke_at);
+}
+
+#[tokio::test]
+async fn short_suspend_polls_on_wake_without_suppressing() {
+    let mut sim = Sim::new(50);
+    sim.run_awake(HOUR_SECS + 30).await;
+    // Shorter than the poll interval and the notification gap
+    sim.suspend(20);
+    let woke_at = sim.now;
+    let notified = sim.notified_updates;
+    sim.run_awake(1).await;
+
+    assert_eq!(sim.last_live_refresh, Some(woke_at));
+    assert_eq!(sim.notified_updates, notified + 1);
+    assert_eq!(sim.suppressed_updates, 0);
+}
+
+#[tokio::test]
+async fn schedule_queue_stays_within_staleness_across_suspends() {
+    let followed = 300;
+    let mut sim = Sim::new(followed);
+    run_nightly_suspend(&mut sim, 14).await;
+
+    // Each channel is rechecked once stale; a night's suspend and one full
+    // walk of the queue are the most it can fall behind by
+    let interval = derive_check_interval(
+        followed,
+        sim.config.schedule_stale_hours,
+        sim.config.schedule_check_interval_sec,
+    );
+    let walk = Duration::seconds((followed as u64 * interval) as i64);
+    let bound = Duration::hours(sim.config.schedule_stale_hours as i64 + 8) + walk;
+    assert!(
+        sim.worst_schedule_age <= bound,
+        "a schedule went {}h unchecked",
+        sim.worst_schedule_age.num_hours()
+    );
+}
+
+// === outages ===
+
+#[tokio::test]
+async fn outage_retries_stay_within_rate_limit() {
+    let mut sim = Sim::new(300);
+    sim.outages.push(2 * HOUR_SECS..2 * HOUR_SECS + 30 * 60);
+    sim.run_awake(DAY_SECS).await;
+
+    assert!(
+        sim.peak_requests_per_min < HELIX_POINTS_PER_MIN,
+        "peak of {} requests/min",
+        sim.peak_requests_per_min
+    );
+    // Polling picked up again as soon as the API came back
+    let recovered = sim.start + Duration::seconds(2 * HOUR_SECS + 30 * 60);
+    assert!(sim.last_live_refresh.unwrap() >= recovered);
+    assert_eq!(sim.suppressed_updates, 1);
+}
+
+#[tokio::test]
+async fn offline_probes_back_off_and_refresh_on_return() {
+    let mut sim = Sim::new(300);
+    let outage_secs = 2 * HOUR_SECS;
+    sim.outages.push(HOUR_SECS..HOUR_SECS + outage_secs);
+
+    sim.run_awake(HOUR_SECS + outage_secs).await;
+    assert!(sim.outage.is_some());
+    let polls_before = sim.live_polls;
+    // Probes only, backing off to one every few minutes
+    let probes = sim.outage.unwrap().failed_probes;
+    assert!(
+        i64::from(probes) < outage_secs / PROBE_MAX_SECS + 10,
+        "{probes} probes"
+    );
+
+    sim.run_awake(PROBE_MAX_SECS + 1).await;
+    assert!(sim.outage.is_none());
+    assert!(sim.live_polls > polls_before);
+    let recovered = sim.start + Duration::seconds(HOUR_SECS + outage_secs);
+    assert!(sim.last_live_refresh.unwrap() >= recovered);
+}
@@ -1,8 +1,19 @@
+use chrono::{DateTime, Utc};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::{broadcast, watch, RwLock};
 
-use crate::twitch::{FollowedChannel, ScheduledStream, Stream};
+use crate::auth::{TokenExpiry, WARN_AFTER_FAILURES};
+use crate::connectivity::{Connectivity, Outage};
+use crate::notification_snooze::NotificationSnoozes;
+use crate::twitch::{FollowedChannel, ScheduledStream, Stream, Video};
+
+/// Live-stream refreshes that must fail in a row before the menu reports a
+/// connection problem. One failure is usually a blip.
+pub const CONNECTION_PROBLEM_AFTER_FAILURES: u32 = 3;
+
+/// How long a followed stream stays in "Recently Ended" after going offline.
+pub const RECENTLY_ENDED_WINDOW_MIN: i64 = 60;
 
 /// Type of state change
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -11,6 +22,17 @@ pub enum ChangeType {
     ScheduledStreams,
     CategoryStreams,
     Authentication,
+    WatchHistory,
+    Settings,
+    Unread,
+    RecentVideos,
+    RecentlyEnded,
+    Notified,
+    Refreshing,
+    LastRefresh,
+    Connection,
+    Snoozes,
+    Token,
 }
 
 /// A category change event
@@ -26,20 +48,92 @@ pub struct StreamsUpdated {
     pub streams: Vec<Stream>,
     pub newly_live: Vec<Stream>,
     pub category_changes: Vec<CategoryChange>,
+    /// Broadcasts from the previous poll that are no longer live. A restart
+    /// (same user, new `started_at`) ends the old broadcast.
+    pub ended: Vec<Stream>,
+    /// Time the machine spent suspended since the previous update, when the
+    /// OS reported it (see `power`)
+    pub suspended: Option<chrono::Duration>,
+}
+
+/// A followed category's streams before and after `set_category_streams`.
+#[derive(Debug, Clone, Default)]
+pub struct CategoryStreamsDiff {
+    /// `None` for a baseline: the category's first list, or the first since
+    /// the startup cache
+    pub previous: Option<Vec<Stream>>,
+    pub current: Vec<Stream>,
+}
+
+impl CategoryStreamsDiff {
+    /// Streams whose streamer wasn't in the previous list
+    pub fn arrived(&self) -> Vec<Stream> {
+        let Some(previous) = &self.previous else {
+            return Vec::new();
+        };
+        self.current
+            .iter()
+            .filter(|s| !previous.iter().any(|p| p.user_id == s.user_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Streams in the `n` most watched now whose streamer wasn't in the `n`
+    /// most watched before, most watched first
+    pub fn entered_top(&self, n: usize) -> Vec<Stream> {
+        let Some(previous) = &self.previous else {
+            return Vec::new();
+        };
+        let previous_top = top_by_viewers(previous, n);
+        top_by_viewers(&self.current, n)
+            .into_iter()
+            .filter(|s| !previous_top.iter().any(|p| p.user_id == s.user_id))
+            .cloned()
+            .collect()
+    }
+}
+
+fn top_by_viewers(streams: &[Stream], n: usize) -> Vec<&Stream> {
+    let mut sorted: Vec<&Stream> = streams.iter().collect();
+    sorted.sort_by_key(|s| std::cmp::Reverse(s.viewer_count));
+    sorted.truncate(n);
+    sorted
+}
+
+/// A followed stream that went offline within `RECENTLY_ENDED_WINDOW_MIN`.
+#[derive(Debug, Clone)]
+pub struct EndedStream {
+    /// The stream as last seen live
+    pub stream: Stream,
+    pub ended_at: DateTime<Utc>,
+    /// Its past broadcast, once found
+    pub video: Option<Video>,
 }
 
 /// Application state
 #[derive(Default)]
+#[allow(clippy::struct_excessive_bools)] // independent load flags
 struct StateInner {
     // Authentication state
     authenticated: bool,
+    /// A device code login is in progress.
+    authenticating: bool,
     user_id: String,
     user_login: String,
 
     // Stream data
     followed_streams: Vec<Stream>,
+    /// Followed streams have been fetched (or read from the startup cache).
+    followed_loaded: bool,
+    /// Followed streams came from SQLite at startup and haven't been refreshed since.
+    followed_cached: bool,
+    /// Categories whose streams came from SQLite at startup and haven't been
+    /// refreshed since.
+    cached_categories: HashSet<String>,
     scheduled_streams: Vec<ScheduledStream>,
     schedules_loaded: bool,
+    /// Schedules came from SQLite at startup and haven't been refreshed since.
+    schedules_cached: bool,
     followed_channels: Vec<FollowedChannel>,
 
     // Categories being tracked (from followed live streams)
@@ -50,6 +144,45 @@ struct StateInner {
 
     // Streams by followed category (category_id -> streams)
     category_streams: HashMap<String, Vec<Stream>>,
+
+    // Favourites (by user_id) that went live since the menu was last opened
+    unread_live: HashSet<String>,
+
+    // When each broadcaster (by user_login) was last in a go-live notification
+    last_notified: HashMap<String, DateTime<Utc>>,
+
+    // Favourites' latest past broadcasts (user_login -> newest first)
+    recent_videos: HashMap<String, Vec<Video>>,
+
+    // Streams that went offline recently, newest first, one per user
+    recently_ended: Vec<EndedStream>,
+
+    /// Each followed channel's stream as last seen live this run (by
+    /// user_login), so a pinned channel that goes offline keeps its details.
+    last_live: HashMap<String, Stream>,
+
+    /// A "Refresh now" from the menu is in flight.
+    refreshing: bool,
+    /// Last successful live-stream refresh.
+    last_refresh: Option<DateTime<Utc>>,
+    /// Live-stream refreshes failed in a row since the last success.
+    refresh_failures: u32,
+    /// When the current run of failures started.
+    failing_since: Option<DateTime<Utc>>,
+    /// Last time the followed channels were fetched.
+    followed_refreshed_at: Option<DateTime<Utc>>,
+    /// Set while Twitch can't be reached at all.
+    outage: Option<Outage>,
+    /// Suspended time not yet reported in a `StreamsUpdated`.
+    suspended: Option<chrono::Duration>,
+
+    /// Notifications snoozed from the tray.
+    notification_snoozes: NotificationSnoozes,
+
+    /// The session token's expiry and how refreshing it ahead is going.
+    token_expiry: Option<TokenExpiry>,
+    /// Required scopes the session token wasn't granted.
+    missing_scopes: Vec<String>,
 }
 
 /// Thread-safe application state manager
@@ -106,15 +239,43 @@ impl AppState {
         }
     }
 
+    /// Marks a device code login as started or finished
+    pub async fn set_authenticating(&self, authenticating: bool) {
+        let mut state = self.inner.write().await;
+        let changed = state.authenticating != authenticating;
+        state.authenticating = authenticating;
+        drop(state);
+
+        if changed {
+            self.notify_change(ChangeType::Authentication);
+        }
+    }
+
+    /// Returns whether a device code login is in progress
+    pub async fn is_authenticating(&self) -> bool {
+        self.inner.read().await.authenticating
+    }
+
     /// Returns whether the user is authenticated
     pub async fn is_authenticated(&self) -> bool {
         self.inner.read().await.authenticated
     }
 
+    /// Returns the logged-in user's login, empty when logged out
+    pub async fn user_login(&self) -> String {
+        self.inner.read().await.user_login.clone()
+    }
+
     /// Updates the followed live streams and broadcasts changes
     pub async fn set_followed_streams(&self, streams: Vec<Stream>) {
         let mut state = self.inner.write().await;
 
+        // The startup cache is only for display: nothing in it was seen going
+        // live or ending this run
+        if state.followed_cached {
+            state.followed_streams.clear();
+        }
+
         // Build set for comparison
         let old_by_id: HashSet<_> = state
             .followed_streams
@@ -129,6 +290,18 @@ impl AppState {
             .cloned()
             .collect();
 
+        // Find broadcasts that are gone (offline, or replaced by a restart)
+        let ended: Vec<_> = state
+            .followed_streams
+            .iter()
+            .filter(|old| {
+                !streams
+                    .iter()
+                    .any(|s| s.user_id == old.user_id && s.started_at == old.started_at)
+            })
+            .cloned()
+            .collect();
+
         // Find category changes for streams that were already live
         let mut category_changes = Vec::new();
         for stream in &streams {
@@ -158,7 +331,44 @@ impl AppState {
             );
         }
 
+        // Restarts end a broadcast but the streamer is still live
+        let now = Utc::now();
+        let window = chrono::Duration::minutes(RECENTLY_ENDED_WINDOW_MIN);
+        state.recently_ended.retain(|e| {
+            now - e.ended_at <= window && !streams.iter().any(|s| s.user_id == e.stream.user_id)
+        });
+        for stream in ended
+            .iter()
+            .filter(|old| !streams.iter().any(|s| s.user_id == old.user_id))
+        {
+            state
+                .recently_ended
+                .retain(|e| e.stream.user_id != stream.user_id);
+            state.recently_ended.insert(
+                0,
+                EndedStream {
+                    stream: stream.clone(),
+                    ended_at: now,
+                    video: None,
+                },
+            );
+        }
+
+        for stream in &streams {
+            state
+                .last_live
+                .insert(stream.user_login.clone(), stream.clone());
+        }
+
+        // A favourite that went offline unseen is no longer news
+        state
+            .unread_live
+            .retain(|id| streams.iter().any(|s| s.user_id == *id));
+
         state.followed_streams.clone_from(&streams);
+        state.followed_loaded = true;
+        state.followed_cached = false;
+        let suspended = state.suspended.take();
         drop(state);
 
         self.notify_change(ChangeType::FollowedStreams);
@@ -168,9 +378,43 @@ impl AppState {
             streams,
             newly_live,
             category_changes,
+            ended,
+            suspended,
         });
     }
 
+    /// Shows followed and category streams left in SQLite by a previous run
+    /// until the first real refresh replaces them. Ignored once followed
+    /// streams are loaded.
+    pub async fn set_cached_streams(
+        &self,
+        followed: Vec<Stream>,
+        category_streams: HashMap<String, Vec<Stream>>,
+    ) {
+        let mut state = self.inner.write().await;
+        if state.followed_loaded {
+            return;
+        }
+        state.followed_streams = followed;
+        state.followed_loaded = true;
+        state.followed_cached = true;
+        state.cached_categories = category_streams.keys().cloned().collect();
+        state.category_streams = category_streams;
+        drop(state);
+
+        self.notify_change(ChangeType::FollowedStreams);
+    }
+
+    /// Returns whether the followed streams are still the startup cache
+    pub async fn followed_cached(&self) -> bool {
+        self.inner.read().await.followed_cached
+    }
+
+    /// Returns the categories whose streams are still the startup cache
+    pub async fn cached_categories(&self) -> HashSet<String> {
+        self.inner.read().await.cached_categories.clone()
+    }
+
     /// Returns the current followed live streams
     pub async fn get_followed_streams(&self) -> Vec<Stream> {
         self.inner.read().await.followed_streams.clone()
@@ -183,6 +427,7 @@ impl AppState {
         // Only trigger a menu rebuild if the data actually changed.
         // The schedule walker calls this every ~10s; skip notification if unchanged.
         let changed = !state.schedules_loaded
+            || state.schedules_cached
             || state.scheduled_streams.len() != streams.len()
             || state
                 .scheduled_streams
@@ -192,6 +437,7 @@ impl AppState {
 
         state.scheduled_streams = streams;
         state.schedules_loaded = true;
+        state.schedules_cached = false;
         drop(state);
 
         if changed {
@@ -199,20 +445,46 @@ impl AppState {
         }
     }
 
+    /// Shows schedules left in SQLite by a previous run until the first real
+    /// refresh replaces them. Ignored if schedules are already loaded.
+    pub async fn set_cached_scheduled_streams(&self, streams: Vec<ScheduledStream>) {
+        let mut state = self.inner.write().await;
+        if state.schedules_loaded {
+            return;
+        }
+        state.scheduled_streams = streams;
+        state.schedules_loaded = true;
+        state.schedules_cached = true;
+        drop(state);
+
+        self.notify_change(ChangeType::ScheduledStreams);
+    }
+
     /// Returns whether schedules have been fetched at least once
     pub async fn schedules_loaded(&self) -> bool {
         self.inner.read().await.schedules_loaded
     }
 
+    /// Returns whether the scheduled streams are still the startup cache
+    pub async fn schedules_cached(&self) -> bool {
+        self.inner.read().await.schedules_cached
+    }
+
     /// Returns the current scheduled streams
     pub async fn get_scheduled_streams(&self) -> Vec<ScheduledStream> {
         self.inner.read().await.scheduled_streams.clone()
     }
 
-    /// Sets the list of followed channels
+    /// Sets the list of followed channels, freshly fetched from Twitch
     pub async fn set_followed_channels(&self, channels: Vec<FollowedChannel>) {
         let mut state = self.inner.write().await;
         state.followed_channels = channels;
+        state.followed_refreshed_at = Some(Utc::now());
+    }
+
+    /// Returns when the followed channels were last fetched
+    pub async fn followed_refreshed_at(&self) -> Option<DateTime<Utc>> {
+        self.inner.read().await.followed_refreshed_at
     }
 
     /// Returns the list of followed channels
@@ -220,13 +492,29 @@ impl AppState {
         self.inner.read().await.followed_channels.clone()
     }
 
-    /// Updates streams for a specific category
-    pub async fn set_category_streams(&self, category_id: String, streams: Vec<Stream>) {
+    /// Updates streams for a specific category.
+    ///
+    /// Returns the category's previous and new lists. The first list for a
+    /// category (ignoring the startup cache) is the baseline, so has no
+    /// previous list.
+    pub async fn set_category_streams(
+        &self,
+        category_id: String,
+        streams: Vec<Stream>,
+    ) -> CategoryStreamsDiff {
         let mut state = self.inner.write().await;
-        state.category_streams.insert(category_id, streams);
+        let was_cached = state.cached_categories.remove(&category_id);
+        let previous = state
+            .category_streams
+            .insert(category_id, streams.clone())
+            .filter(|_| !was_cached);
         drop(state);
 
         self.notify_change(ChangeType::CategoryStreams);
+        CategoryStreamsDiff {
+            previous,
+            current: streams,
+        }
     }
 
     /// Returns all category streams
@@ -234,6 +522,334 @@ impl AppState {
         self.inner.read().await.category_streams.clone()
     }
 
+    /// Signals that watch history changed so the menu re-sorts.
+    ///
+    /// The history itself lives in the database; nothing is stored here.
+    pub fn notify_watch_history_changed(&self) {
+        self.notify_change(ChangeType::WatchHistory);
+    }
+
+    /// Signals that saved settings changed in a way the menu shows.
+    pub fn notify_settings_changed(&self) {
+        self.notify_change(ChangeType::Settings);
+    }
+
+    /// Flags streams as unread until the menu is next opened.
+    pub async fn mark_unread(&self, user_ids: Vec<String>) {
+        if user_ids.is_empty() {
+            return;
+        }
+        let mut state = self.inner.write().await;
+        let before = state.unread_live.len();
+        state.unread_live.extend(user_ids);
+        let changed = state.unread_live.len() != before;
+        drop(state);
+
+        if changed {
+            self.notify_change(ChangeType::Unread);
+        }
+    }
+
+    /// Clears the unread streams because the menu was opened.
+    pub async fn mark_menu_seen(&self) {
+        let mut state = self.inner.write().await;
+        if state.unread_live.is_empty() {
+            return;
+        }
+        state.unread_live.clear();
+        drop(state);
+
+        self.notify_change(ChangeType::Unread);
+    }
+
+    /// Records that these broadcasters (by login) were just in a go-live
+    /// notification.
+    pub async fn record_notified(&self, user_logins: Vec<String>, at: DateTime<Utc>) {
+        if user_logins.is_empty() {
+            return;
+        }
+        let mut state = self.inner.write().await;
+        for login in user_logins {
+            state.last_notified.insert(login, at);
+        }
+        drop(state);
+
+        self.notify_change(ChangeType::Notified);
+    }
+
+    /// Returns when each broadcaster (by login) was last in a go-live notification
+    pub async fn last_notified(&self) -> HashMap<String, DateTime<Utc>> {
+        self.inner.read().await.last_notified.clone()
+    }
+
+    /// Snoozes notifications about `user_login`, or all of them for `None`,
+    /// until `until`.
+    pub async fn snooze_notifications(&self, user_login: Option<String>, until: DateTime<Utc>) {
+        let mut state = self.inner.write().await;
+        match user_login {
+            Some(login) => {
+                state.notification_snoozes.streamers.insert(login, until);
+            }
+            None => state.notification_snoozes.all_until = Some(until),
+        }
+        drop(state);
+
+        self.notify_change(ChangeType::Snoozes);
+    }
+
+    /// Ends `user_login`'s snooze, or the one for everyone for `None`, early.
+    pub async fn resume_notifications(&self, user_login: Option<&str>) {
+        let mut state = self.inner.write().await;
+        let changed = match user_login {
+            Some(login) => state.notification_snoozes.streamers.remove(login).is_some(),
+            None => state.notification_snoozes.all_until.take().is_some(),
+        };
+        drop(state);
+
+        if changed {
+            self.notify_change(ChangeType::Snoozes);
+        }
+    }
+
+    /// Snoozes still running at `now`.
+    pub async fn notification_snoozes(&self, now: DateTime<Utc>) -> NotificationSnoozes {
+        let mut state = self.inner.write().await;
+        state.notification_snoozes.prune(now);
+        state.notification_snoozes.clone()
+    }
+
+    /// Records the session token's expiry after a login or refresh, ending
+    /// any run of refresh failures.
+    pub async fn set_token_expiry(&self, expires_at: DateTime<Utc>) {
+        self.inner.write().await.token_expiry = Some(TokenExpiry::new(expires_at));
+        self.notify_change(ChangeType::Token);
+    }
+
+    /// Records a failed refresh of the session token.
+    pub async fn record_token_refresh_failure(&self, at: DateTime<Utc>) {
+        let mut state = self.inner.write().await;
+        let Some(expiry) = state.token_expiry.as_mut() else {
+            return;
+        };
+        expiry.record_failure(at);
+        let now_failing = expiry.failures == WARN_AFTER_FAILURES;
+        drop(state);
+
+        if now_failing {
+            self.notify_change(ChangeType::Token);
+        }
+    }
+
+    /// Returns the session token's expiry, `None` when logged out
+    pub async fn token_expiry(&self) -> Option<TokenExpiry> {
+        self.inner.read().await.token_expiry
+    }
+
+    /// Records the required scopes the session token lacks, so the menu can
+    /// ask for a new login
+    pub async fn set_missing_scopes(&self, scopes: Vec<String>) {
+        let mut state = self.inner.write().await;
+        if state.missing_scopes == scopes {
+            return;
+        }
+        state.missing_scopes = scopes;
+        drop(state);
+
+        self.notify_change(ChangeType::Token);
+    }
+
+    /// Returns the required scopes the session token lacks
+    pub async fn missing_scopes(&self) -> Vec<String> {
+        self.inner.read().await.missing_scopes.clone()
+    }
+
+    /// Marks a manual refresh as started. Returns false, changing nothing, if
+    /// one is already running.
+    pub async fn start_refreshing(&self) -> bool {
+        let mut state = self.inner.write().await;
+        if state.refreshing {
+            return false;
+        }
+        state.refreshing = true;
+        drop(state);
+
+        self.notify_change(ChangeType::Refreshing);
+        true
+    }
+
+    /// Marks the manual refresh as finished.
+    pub async fn finish_refreshing(&self) {
+        self.inner.write().await.refreshing = false;
+        self.notify_change(ChangeType::Refreshing);
+    }
+
+    /// Returns whether a manual refresh is in flight
+    pub async fn is_refreshing(&self) -> bool {
+        self.inner.read().await.refreshing
+    }
+
+    /// Records a successful live-stream refresh, so the menu can say how
+    /// fresh its data is.
+    /// Also ends any run of failures.
+    pub async fn set_last_refresh(&self, at: DateTime<Utc>) {
+        let mut state = self.inner.write().await;
+        state.last_refresh = Some(at);
+        state.refresh_failures = 0;
+        state.failing_since = None;
+        drop(state);
+
+        self.notify_change(ChangeType::LastRefresh);
+    }
+
+    /// Records a failed live-stream refresh.
+    pub async fn record_refresh_failure(&self, at: DateTime<Utc>) {
+        let mut state = self.inner.write().await;
+        state.refresh_failures += 1;
+        state.failing_since.get_or_insert(at);
+        let now_failing = state.refresh_failures == CONNECTION_PROBLEM_AFTER_FAILURES;
+        drop(state);
+
+        if now_failing {
+            self.notify_change(ChangeType::Connection);
+        }
+    }
+
+    /// When refreshes started failing, once `CONNECTION_PROBLEM_AFTER_FAILURES`
+    /// have failed in a row. `None` while they're working.
+    pub async fn connection_problem_since(&self) -> Option<DateTime<Utc>> {
+        let state = self.inner.read().await;
+        if state.refresh_failures >= CONNECTION_PROBLEM_AFTER_FAILURES {
+            state.failing_since
+        } else {
+            None
+        }
+    }
+
+    /// Returns the time of the last successful live-stream refresh
+    pub async fn last_refresh(&self) -> Option<DateTime<Utc>> {
+        self.inner.read().await.last_refresh
+    }
+
+    /// Returns how many live-stream refreshes have failed in a row
+    pub async fn refresh_failures(&self) -> u32 {
+        self.inner.read().await.refresh_failures
+    }
+
+    /// Returns whether Twitch can currently be reached
+    pub async fn connectivity(&self) -> Connectivity {
+        match self.inner.read().await.outage {
+            Some(outage) => Connectivity::Offline {
+                since: outage.since,
+            },
+            None => Connectivity::Online,
+        }
+    }
+
+    /// Returns the current outage, `None` while online
+    pub async fn outage(&self) -> Option<Outage> {
+        self.inner.read().await.outage
+    }
+
+    /// Marks Twitch unreachable from `at`. Returns whether this started a
+    /// new outage.
+    pub async fn set_offline(&self, at: DateTime<Utc>) -> bool {
+        let mut state = self.inner.write().await;
+        if state.outage.is_some() {
+            return false;
+        }
+        state.outage = Some(Outage::new(at));
+        drop(state);
+
+        self.notify_change(ChangeType::Connection);
+        true
+    }
+
+    /// Records a probe that still couldn't reach Twitch.
+    pub async fn record_failed_probe(&self, at: DateTime<Utc>) {
+        if let Some(outage) = &mut self.inner.write().await.outage {
+            outage.record_failed_probe(at);
+        }
+    }
+
+    /// Marks Twitch reachable again. Returns the outage this ended, if any.
+    pub async fn set_online(&self) -> Option<Outage> {
+        let outage = self.inner.write().await.outage.take();
+        if outage.is_some() {
+            self.notify_change(ChangeType::Connection);
+        }
+        outage
+    }
+
+    /// Records that the machine woke after `suspended`. The next followed
+    /// streams update carries it (added to any earlier suspend it hasn't
+    /// reported yet).
+    pub async fn record_resume(&self, suspended: chrono::Duration) {
+        let mut state = self.inner.write().await;
+        state.suspended = Some(state.suspended.map_or(suspended, |s| s + suspended));
+    }
+
+    /// Returns whether any stream has gone live since the menu was last opened
+    pub async fn has_unread(&self) -> bool {
+        !self.inner.read().await.unread_live.is_empty()
+    }
+
+    /// Replaces the Favourites' past broadcasts, keyed by login
+    pub async fn set_recent_videos(&self, videos: HashMap<String, Vec<Video>>) {
+        let mut state = self.inner.write().await;
+        if state.recent_videos == videos {
+            return;
+        }
+        state.recent_videos = videos;
+        drop(state);
+
+        self.notify_change(ChangeType::RecentVideos);
+    }
+
+    /// Returns the Favourites' past broadcasts, keyed by login
+    pub async fn get_recent_videos(&self) -> HashMap<String, Vec<Video>> {
+        self.inner.read().await.recent_videos.clone()
+    }
+
+    /// Attaches the past broadcast found for a recently ended stream
+    pub async fn set_ended_video(&self, user_id: &str, video: Video) {
+        let mut state = self.inner.write().await;
+        let Some(entry) = state
+            .recently_ended
+            .iter_mut()
+            .find(|e| e.stream.user_id == user_id)
+        else {
+            return;
+        };
+        entry.video = Some(video);
+        drop(state);
+
+        self.notify_change(ChangeType::RecentlyEnded);
+    }
+
+    /// Returns streams that went offline within `RECENTLY_ENDED_WINDOW_MIN`
+    /// of `now`, newest first
+    pub async fn recently_ended(&self, now: DateTime<Utc>) -> Vec<EndedStream> {
+        let window = chrono::Duration::minutes(RECENTLY_ENDED_WINDOW_MIN);
+        self.inner
+            .read()
+            .await
+            .recently_ended
+            .iter()
+            .filter(|e| now - e.ended_at <= window)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the last live stream seen this run for each of `user_logins`
+    /// that has been live, keyed by login
+    pub async fn last_live(&self, user_logins: &[String]) -> HashMap<String, Stream> {
+        let state = self.inner.read().await;
+        user_logins
+            .iter()
+            .filter_map(|login| Some((login.clone(), state.last_live.get(login)?.clone())))
+            .collect()
+    }
+
     /// Clears all state (used on logout)
     pub async fn clear(&self) {
         let mut state = self.inner.write().await;
@@ -260,7 +876,7 @@ impl Default for AppState {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_helpers::{make_stream, make_stream_with_game};
+    use crate::test_helpers::{make_scheduled, make_stream, make_stream_with_game};
 
     // === set_followed_streams change detection tests ===
 
@@ -304,6 +920,82 @@ mod tests {
         assert!(event.newly_live.is_empty());
     }
 
+    #[tokio::test]
+    async fn offline_and_restarted_streams_reported_as_ended() {
+        let state = AppState::new();
+        let mut rx = state.subscribe_streams();
+
+        let stream_a = make_stream("a", "StreamerA");
+        let stream_b = make_stream("b", "StreamerB");
+        state
+            .set_followed_streams(vec![stream_a.clone(), stream_b.clone()])
+            .await;
+        let first = rx.recv().await.unwrap();
+        assert!(first.ended.is_empty());
+
+        // A goes offline; B restarts with a new start time
+        let mut restarted_b = stream_b.clone();
+        restarted_b.started_at += chrono::Duration::minutes(5);
+        state.set_followed_streams(vec![restarted_b]).await;
+        let event = rx.recv().await.unwrap();
+
+        let ended: Vec<_> = event
+            .ended
+            .iter()
+            .map(|s| (s.user_id.as_str(), s.started_at))
+            .collect();
+        assert_eq!(
+            ended,
+            vec![("a", stream_a.started_at), ("b", stream_b.started_at)]
+        );
+    }
+
+    #[tokio::test]
+    async fn offline_streams_listed_as_recently_ended_until_live_again() {
+        let state = AppState::new();
+        let stream_a = make_stream("a", "StreamerA");
+        let stream_b = make_stream("b", "StreamerB");
+        state
+            .set_followed_streams(vec![stream_a.clone(), stream_b.clone()])
+            .await;
+
+        // A goes offline; B restarts, so is still live
+        let mut restarted_b = stream_b.clone();
+        restarted_b.started_at += chrono::Duration::minutes(5);
+        state.set_followed_streams(vec![restarted_b.clone()]).await;
+
+        let ended = state.recently_ended(Utc::now()).await;
+        let ids: Vec<_> = ended.iter().map(|e| e.stream.user_id.as_str()).collect();
+        assert_eq!(ids, vec!["a"]);
+
+        // Dropped after the window
+        let later = Utc::now() + chrono::Duration::minutes(RECENTLY_ENDED_WINDOW_MIN + 1);
+        assert!(state.recently_ended(later).await.is_empty());
+
+        // and once A is live again
+        state
+            .set_followed_streams(vec![stream_a, restarted_b])
+            .await;
+        assert!(state.recently_ended(Utc::now()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn last_live_remembers_offline_channels() {
+        let state = AppState::new();
+        let stream_a = make_stream("a", "StreamerA");
+        let stream_b = make_stream("b", "StreamerB");
+        state
+            .set_followed_streams(vec![stream_a.clone(), stream_b])
+            .await;
+        state.set_followed_streams(vec![]).await;
+
+        let last_live = state
+            .last_live(&[stream_a.user_login.clone(), "nobody".to_string()])
+            .await;
+        assert_eq!(last_live.len(), 1);
+        assert_eq!(last_live[&stream_a.user_login].title, stream_a.title);
+    }
+
     #[tokio::test]
     async fn initial_load_all_newly_live() {
         let state = AppState::new();
@@ -526,6 +1218,203 @@ mod tests {
         assert!(state.get_followed_streams().await.is_empty());
     }
 
+    // === connection problem tests ===
+
+    #[tokio::test]
+    async fn connection_problem_after_repeated_failures_until_success() {
+        let state = AppState::new();
+        let first = Utc::now();
+        for n in 0..CONNECTION_PROBLEM_AFTER_FAILURES {
+            assert!(state.connection_problem_since().await.is_none());
+            state
+                .record_refresh_failure(first + chrono::Duration::minutes(i64::from(n)))
+                .await;
+        }
+        assert_eq!(state.connection_problem_since().await, Some(first));
+
+        state.set_last_refresh(Utc::now()).await;
+        assert!(state.connection_problem_since().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn refresh_failures_count_until_success() {
+        let state = AppState::new();
+        state.record_refresh_failure(Utc::now()).await;
+        state.record_refresh_failure(Utc::now()).await;
+        assert_eq!(state.refresh_failures().await, 2);
+
+        state.set_last_refresh(Utc::now()).await;
+        assert_eq!(state.refresh_failures().await, 0);
+    }
+
+    #[tokio::test]
+    async fn offline_until_set_online() {
+        let state = AppState::new();
+        let since = Utc::now();
+        assert!(state.set_offline(since).await);
+        assert!(
+            !state
+                .set_offline(since + chrono::Duration::minutes(1))
+                .await
+        );
+        assert_eq!(state.connectivity().await, Connectivity::Offline { since });
+
+        state.record_failed_probe(Utc::now()).await;
+        assert_eq!(state.outage().await.unwrap().failed_probes, 1);
+
+        assert_eq!(state.set_online().await.unwrap().since, since);
+        assert_eq!(state.connectivity().await, Connectivity::Online);
+        assert!(state.set_online().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn next_streams_update_reports_time_suspended_once() {
+        let state = AppState::new();
+        let mut rx = state.subscribe_streams();
+
+        state.record_resume(chrono::Duration::hours(8)).await;
+        state.record_resume(chrono::Duration::minutes(5)).await;
+        state.set_followed_streams(Vec::new()).await;
+        assert_eq!(
+            rx.recv().await.unwrap().suspended,
+            Some(chrono::Duration::minutes(8 * 60 + 5))
+        );
+
+        state.set_followed_streams(Vec::new()).await;
+        assert_eq!(rx.recv().await.unwrap().suspended, None);
+    }
+
+    #[tokio::test]
+    async fn setting_followed_channels_records_when() {
+        let state = AppState::new();
+        assert!(state.followed_refreshed_at().await.is_none());
+        state.set_followed_channels(Vec::new()).await;
+        assert!(state.followed_refreshed_at().await.is_some());
+    }
+
+    // === manual refresh tests ===
+
+    #[tokio::test]
+    async fn only_one_manual_refresh_at_a_time() {
+        let state = AppState::new();
+        assert!(state.start_refreshing().await);
+        assert!(!state.start_refreshing().await);
+        assert!(state.is_refreshing().await);
+
+        state.finish_refreshing().await;
+        assert!(!state.is_refreshing().await);
+        assert!(state.start_refreshing().await);
+    }
+
+    // === cached streams tests ===
+
+    #[tokio::test]
+    async fn cached_streams_shown_until_refreshed() {
+        let state = AppState::new();
+        let categories = HashMap::from([("cat1".to_string(), vec![make_stream("2", "Bob")])]);
+        state
+            .set_cached_streams(vec![make_stream("1", "Alice")], categories)
+            .await;
+
+        assert!(state.followed_cached().await);
+        assert!(state.cached_categories().await.contains("cat1"));
+        assert_eq!(state.get_followed_streams().await.len(), 1);
+
+        state
+            .set_followed_streams(vec![make_stream("1", "Alice")])
+            .await;
+        assert!(!state.followed_cached().await);
+        assert!(state.cached_categories().await.contains("cat1"));
+
+        state
+            .set_category_streams("cat1".to_string(), vec![make_stream("2", "Bob")])
+            .await;
+        assert!(state.cached_categories().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cached_streams_are_not_a_baseline() {
+        let state = AppState::new();
+        let mut rx = state.subscribe_streams();
+        let categories = HashMap::from([("cat1".to_string(), vec![make_stream("2", "Bob")])]);
+        state
+            .set_cached_streams(vec![make_stream("1", "Alice")], categories)
+            .await;
+
+        // Alice went offline while the app was closed: no end is recorded
+        state
+            .set_followed_streams(vec![make_stream("3", "Carol")])
+            .await;
+        let event = rx.try_recv().unwrap();
+        assert!(event.ended.is_empty());
+        assert_eq!(event.newly_live.len(), 1);
+
+        // Carol arriving since the cache isn't news either
+        let arrived = state
+            .set_category_streams("cat1".to_string(), vec![make_stream("3", "Carol")])
+            .await
+            .arrived();
+        assert!(arrived.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cache_does_not_overwrite_refreshed_streams() {
+        let state = AppState::new();
+        state
+            .set_followed_streams(vec![make_stream("1", "Alice")])
+            .await;
+        state
+            .set_cached_streams(vec![make_stream("2", "Bob")], HashMap::new())
+            .await;
+
+        assert!(!state.followed_cached().await);
+        assert_eq!(state.get_followed_streams().await[0].user_login, "alice");
+    }
+
+    // === cached schedules tests ===
+
+    #[tokio::test]
+    async fn cached_schedules_count_as_loaded() {
+        let state = AppState::new();
+        state
+            .set_cached_scheduled_streams(vec![make_scheduled("Alice", 1)])
+            .await;
+
+        assert!(state.schedules_loaded().await);
+        assert!(state.schedules_cached().await);
+        assert_eq!(state.get_scheduled_streams().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn refresh_replaces_cached_schedules_and_notifies() {
+        let state = AppState::new();
+        let scheduled = vec![make_scheduled("Alice", 1)];
+        state.set_cached_scheduled_streams(scheduled.clone()).await;
+        let mut rx = state.subscribe();
+        rx.mark_unchanged();
+
+        // Same rows, but the cached marker still has to clear
+        state.set_scheduled_streams(scheduled).await;
+
+        assert!(!state.schedules_cached().await);
+        assert!(rx.has_changed().unwrap());
+    }
+
+    #[tokio::test]
+    async fn cache_does_not_overwrite_refreshed_schedules() {
+        let state = AppState::new();
+        state
+            .set_scheduled_streams(vec![make_scheduled("Alice", 1)])
+            .await;
+        state
+            .set_cached_scheduled_streams(vec![make_scheduled("Bob", 2)])
+            .await;
+
+        assert!(!state.schedules_cached().await);
+        let scheduled = state.get_scheduled_streams().await;
+        assert_eq!(scheduled[0].broadcaster_name, "Alice");
+    }
+
     // === category streams tests ===
 
     #[tokio::test]
@@ -563,6 +1452,63 @@ mod tests {
         assert!(streams.contains_key("game2"));
     }
 
+    #[tokio::test]
+    async fn set_category_streams_returns_arrivals_after_baseline() {
+        let state = AppState::new();
+
+        let first = make_stream_with_game("1", "game1", "Factorio");
+        let arrived = state
+            .set_category_streams("game1".to_string(), vec![first.clone()])
+            .await
+            .arrived();
+        assert!(arrived.is_empty());
+
+        let second = make_stream_with_game("2", "game1", "Factorio");
+        let arrived = state
+            .set_category_streams("game1".to_string(), vec![first, second])
+            .await
+            .arrived();
+        assert_eq!(arrived.len(), 1);
+        assert_eq!(arrived[0].user_id, "2");
+    }
+
+    #[tokio::test]
+    async fn set_category_streams_finds_streams_entering_the_top() {
+        let state = AppState::new();
+        let viewers = |id: &str, viewer_count: u32| Stream {
+            viewer_count,
+            ..make_stream_with_game(id, "game1", "Factorio")
+        };
+
+        let diff = state
+            .set_category_streams("game1".to_string(), vec![viewers("1", 500)])
+            .await;
+        assert!(diff.entered_top(2).is_empty());
+
+        // 3 overtakes 2, and 4 arrives below the top two
+        state
+            .set_category_streams(
+                "game1".to_string(),
+                vec![viewers("1", 500), viewers("2", 300), viewers("3", 200)],
+            )
+            .await;
+        let diff = state
+            .set_category_streams(
+                "game1".to_string(),
+                vec![
+                    viewers("1", 500),
+                    viewers("3", 400),
+                    viewers("2", 300),
+                    viewers("4", 10),
+                ],
+            )
+            .await;
+        let entered = diff.entered_top(2);
+        let entered: Vec<&str> = entered.iter().map(|s| s.user_id.as_str()).collect();
+        assert_eq!(entered, vec!["3"]);
+        assert_eq!(diff.arrived().len(), 1);
+    }
+
     #[tokio::test]
     async fn category_streams_cleared_on_full_clear() {
         let state = AppState::new();
@@ -577,4 +1523,110 @@ mod tests {
         let streams = state.get_category_streams().await;
         assert!(streams.is_empty());
     }
+
+    // === Unread indicator tests ===
+
+    #[tokio::test]
+    async fn unread_until_menu_seen() {
+        let state = AppState::new();
+        let stream = make_stream("a", "StreamerA");
+        state.set_followed_streams(vec![stream]).await;
+
+        state.mark_unread(vec!["a".to_string()]).await;
+        assert!(state.has_unread().await);
+
+        state.mark_menu_seen().await;
+        assert!(!state.has_unread().await);
+    }
+
+    #[tokio::test]
+    async fn unread_dropped_when_stream_goes_offline() {
+        let state = AppState::new();
+        let stream = make_stream("a", "StreamerA");
+        state.set_followed_streams(vec![stream]).await;
+        state.mark_unread(vec!["a".to_string()]).await;
+
+        state.set_followed_streams(Vec::new()).await;
+
+        assert!(!state.has_unread().await);
+    }
+
+    // === Notification snooze tests ===
+
+    #[tokio::test]
+    async fn snoozes_until_resumed_or_expired() {
+        let state = AppState::new();
+        let now = Utc::now();
+        let mut rx = state.subscribe();
+        state
+            .snooze_notifications(Some("ninja".to_string()), now + chrono::Duration::hours(1))
+            .await;
+        state
+            .snooze_notifications(None, now + chrono::Duration::minutes(5))
+            .await;
+        assert_eq!(*rx.borrow_and_update(), Some(ChangeType::Snoozes));
+
+        let snoozes = state.notification_snoozes(now).await;
+        assert!(snoozes.is_snoozed("shroud", now));
+
+        state.resume_notifications(None).await;
+        let snoozes = state.notification_snoozes(now).await;
+        assert!(!snoozes.is_snoozed("shroud", now));
+        assert!(snoozes.is_snoozed("ninja", now));
+
+        let later = state
+            .notification_snoozes(now + chrono::Duration::hours(2))
+            .await;
+        assert!(later.streamers.is_empty());
+    }
+
+    // === Token expiry tests ===
+
+    #[tokio::test]
+    async fn token_refresh_failures_warn_until_next_expiry() {
+        let state = AppState::new();
+        let now = Utc::now();
+        state.record_token_refresh_failure(now).await;
+        assert_eq!(state.token_expiry().await, None);
+
+        state.set_token_expiry(now).await;
+        let mut rx = state.subscribe();
+        for _ in 0..WARN_AFTER_FAILURES {
+            state.record_token_refresh_failure(now).await;
+        }
+        assert_eq!(*rx.borrow_and_update(), Some(ChangeType::Token));
+        assert!(state.token_expiry().await.unwrap().failing());
+
+        state
+            .set_token_expiry(now + chrono::Duration::hours(4))
+            .await;
+        assert!(!state.token_expiry().await.unwrap().failing());
+    }
+
+    // === Recent videos tests ===
+
+    #[tokio::test]
+    async fn recent_videos_notify_only_when_changed() {
+        let state = AppState::new();
+        let mut rx = state.subscribe();
+
+        state.set_recent_videos(HashMap::new()).await;
+        assert!(!rx.has_changed().unwrap());
+
+        let video = Video {
+            id: "1".to_string(),
+            user_id: "a".to_string(),
+            user_login: "streamera".to_string(),
+            user_name: "StreamerA".to_string(),
+            title: "Yesterday".to_string(),
+            created_at: chrono::Utc::now(),
+            url: "https://www.twitch.tv/videos/1".to_string(),
+            duration: "1h0m0s".to_string(),
+        };
+        let videos = HashMap::from([("streamera".to_string(), vec![video])]);
+        state.set_recent_videos(videos.clone()).await;
+
+        assert_eq!(*rx.borrow_and_update(), Some(ChangeType::RecentVideos));
+        assert_eq!(state.get_recent_videos().await, videos);
+    }
 }
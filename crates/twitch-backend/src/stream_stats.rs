@@ -0,0 +1,183 @@
+//! Streaming habits from `stream_history`, for the settings window: how
+//! often each followed channel streams and when they usually start.
+//!
+//! Only broadcasts seen while the app was running are recorded, so counts are
+//! a lower bound for anyone who streams while it's closed.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+
+/// How many of the most common start hours and weekdays are reported.
+const TYPICAL_COUNT: usize = 3;
+
+/// How many of the latest starts `StreamerStats::recent_starts` keeps.
+const RECENT_STARTS: usize = 10;
+
+/// One followed channel's recorded streaming habits.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct StreamerStats {
+    pub broadcaster_name: String,
+    pub broadcaster_login: String,
+    pub stream_count: usize,
+    pub first_started_at: i64, // Unix timestamp (seconds)
+    pub last_started_at: i64,
+    /// Streams per week since the first recorded stream, over at least a week.
+    pub streams_per_week: f64,
+    /// Local hours (0-23) streams most often start in, most common first.
+    pub typical_start_hours: Vec<u32>,
+    /// Weekdays streams most often start on (0 = Monday), most common first.
+    pub typical_days: Vec<u32>,
+    /// The latest start times, newest first.
+    pub recent_starts: Vec<i64>,
+    /// Average broadcast length (`Database::get_average_duration`), if any
+    /// broadcast has been seen to finish.
+    pub average_duration_mins: Option<i64>,
+}
+
+/// Summarises `(broadcaster_name, broadcaster_login, started_at)` history rows
+/// (`Database::get_raw_history_in_window`) per channel, busiest first.
+/// Hours and weekdays are counted in `tz`; `durations` are average broadcast
+/// lengths by login.
+pub fn summarize<Tz: TimeZone>(
+    rows: &[(String, String, i64)],
+    durations: &HashMap<String, Duration>,
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> Vec<StreamerStats> {
+    let mut by_login: HashMap<&str, (&str, Vec<i64>)> = HashMap::new();
+    for (name, login, started_at) in rows {
+        by_login
+            .entry(login.as_str())
+            .or_insert_with(|| (name.as_str(), Vec::new()))
+            .1
+            .push(*started_at);
+    }
+
+    let mut stats: Vec<StreamerStats> = by_login
+        .into_iter()
+        .map(|(login, (name, mut starts))| {
+            starts.sort_unstable();
+            let first = starts[0];
+            let last = starts[starts.len() - 1];
+
+            let span = now - DateTime::from_timestamp(first, 0).unwrap_or(now);
+            let weeks =
+                (span.num_seconds() as f64 / Duration::weeks(1).num_seconds() as f64).max(1.0);
+
+            let local: Vec<_> = starts
+                .iter()
+                .filter_map(|&ts| DateTime::from_timestamp(ts, 0))
+                .map(|dt| dt.with_timezone(tz))
+                .collect();
+
+            StreamerStats {
+                broadcaster_name: name.to_string(),
+                broadcaster_login: login.to_string(),
+                stream_count: starts.len(),
+                first_started_at: first,
+                last_started_at: last,
+                streams_per_week: starts.len() as f64 / weeks,
+                typical_start_hours: most_common(local.iter().map(Timelike::hour)),
+                typical_days: most_common(
+                    local.iter().map(|dt| dt.weekday().num_days_from_monday()),
+                ),
+                recent_starts: starts.iter().rev().take(RECENT_STARTS).copied().collect(),
+                average_duration_mins: durations.get(login).map(Duration::num_minutes),
+            }
+        })
+        .collect();
+
+    stats.sort_by(|a, b| {
+        b.stream_count.cmp(&a.stream_count).then_with(|| {
+            a.broadcaster_name
+                .to_lowercase()
+                .cmp(&b.broadcaster_name.to_lowercase())
+        })
+    });
+    stats
+}
+
+/// The `TYPICAL_COUNT` most frequent values, most frequent first; ties go
+/// to the smaller value.
+fn most_common(values: impl Iterator<Item = u32>) -> Vec<u32> {
+    let mut counts: HashMap<u32, usize> = HashMap::new();
+    for v in values {
+        *counts.entry(v).or_default() += 1;
+    }
+    let mut ranked: Vec<(u32, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranked
+        .into_iter()
+        .take(TYPICAL_COUNT)
+        .map(|(v, _)| v)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(login: &str, started_at: DateTime<Utc>) -> (String, String, i64) {
+        (
+            login.to_uppercase(),
+            login.to_string(),
+            started_at.timestamp(),
+        )
+    }
+
+    #[test]
+    fn counts_and_typical_times_per_channel() {
+        // Mondays 2024-01-01, 01-08 and 01-15 at 20:00, plus a Wednesday at 18:00
+        let monday = Utc.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+        let rows = vec![
+            row("alice", monday),
+            row("alice", monday + Duration::weeks(1)),
+            row("alice", monday + Duration::weeks(2)),
+            row("alice", monday + Duration::days(2) - Duration::hours(2)),
+            row("bob", monday),
+        ];
+        let now = monday + Duration::weeks(4);
+        let durations = HashMap::from([("alice".to_string(), Duration::minutes(150))]);
+
+        let stats = summarize(&rows, &durations, now, &Utc);
+
+        let logins: Vec<&str> = stats.iter().map(|s| s.broadcaster_login.as_str()).collect();
+        assert_eq!(logins, vec!["alice", "bob"]);
+        let alice = &stats[0];
+        assert_eq!(alice.broadcaster_name, "ALICE");
+        assert_eq!(alice.stream_count, 4);
+        assert!((alice.streams_per_week - 1.0).abs() < f64::EPSILON);
+        assert_eq!(alice.typical_start_hours, vec![20, 18]);
+        assert_eq!(alice.typical_days, vec![0, 2]);
+        assert_eq!(
+            alice.last_started_at,
+            (monday + Duration::weeks(2)).timestamp()
+        );
+        assert_eq!(alice.recent_starts[0], alice.last_started_at);
+        assert_eq!(alice.average_duration_mins, Some(150));
+        assert_eq!(stats[1].average_duration_mins, None);
+    }
+
+    #[test]
+    fn new_channels_count_over_at_least_a_week() {
+        let started = Utc.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+        let rows = vec![row("new", started), row("new", started + Duration::days(1))];
+
+        let stats = summarize(&rows, &HashMap::new(), started + Duration::days(2), &Utc);
+
+        assert!((stats[0].streams_per_week - 2.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn hours_are_counted_in_the_given_timezone() {
+        let started = Utc.with_ymd_and_hms(2024, 1, 1, 23, 0, 0).unwrap();
+        let plus_two = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+
+        let stats = summarize(&[row("late", started)], &HashMap::new(), started, &plus_two);
+
+        assert_eq!(stats[0].typical_start_hours, vec![1]);
+        // Tuesday there
+        assert_eq!(stats[0].typical_days, vec![1]);
+    }
+}
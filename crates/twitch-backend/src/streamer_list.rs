@@ -0,0 +1,193 @@
+//! Reads lists of channels exported from Twitch or other tools, for
+//! `import_streamer_list`.
+//!
+//! CSV takes the column headed like a login (`login`, `channel`, ...) or
+//! else the first one. JSON takes an array of logins, or of objects with a
+//! login-like key, optionally wrapped in an object as Helix responses are
+//! (`{"data": [...]}`).
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::bulk_importance::normalize_logins;
+use crate::twitch::User;
+
+/// Column headers and object keys that hold a channel's login, most specific
+/// first.
+const LOGIN_KEYS: &[&str] = &[
+    "broadcaster_login",
+    "user_login",
+    "login",
+    "channel_login",
+    "channel",
+    "channel_name",
+    "username",
+    "streamer",
+    "name",
+];
+
+/// Keys of an object wrapping the list.
+const LIST_KEYS: &[&str] = &["data", "follows", "channels", "streamers"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamerListFormat {
+    Csv,
+    Json,
+}
+
+/// What `import_streamer_list` did.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct StreamerListImport {
+    /// Streamers whose settings changed
+    pub changed: usize,
+    /// Logins in the list that aren't Twitch channels
+    pub unresolved: Vec<String>,
+}
+
+/// The logins in `text`, normalized as [`normalize_logins`] does.
+pub fn parse_streamer_list(text: &str, format: StreamerListFormat) -> anyhow::Result<Vec<String>> {
+    let logins = match format {
+        StreamerListFormat::Csv => parse_csv(text),
+        StreamerListFormat::Json => parse_json(text)?,
+    };
+    let logins = normalize_logins(&logins);
+    if logins.is_empty() {
+        bail!("No channel names found");
+    }
+    Ok(logins)
+}
+
+/// Whether `login` could be a Twitch login, so is worth asking Helix about.
+pub fn is_valid_login(login: &str) -> bool {
+    (1..=25).contains(&login.len())
+        && login
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// The `logins` that none of the fetched `users` has.
+pub fn unresolved_logins(logins: &[String], users: &[User]) -> Vec<String> {
+    logins
+        .iter()
+        .filter(|login| !users.iter().any(|u| u.login.eq_ignore_ascii_case(login)))
+        .cloned()
+        .collect()
+}
+
+fn parse_csv(text: &str) -> Vec<String> {
+    let rows: Vec<Vec<String>> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.split(',')
+                .map(|cell| cell.trim().trim_matches('"').to_string())
+                .collect()
+        })
+        .collect();
+    let Some(header) = rows.first() else {
+        return Vec::new();
+    };
+    let header_key = |cell: &String| cell.to_lowercase().replace(' ', "_");
+    let login_column = LOGIN_KEYS
+        .iter()
+        .find_map(|key| header.iter().position(|cell| header_key(cell) == *key));
+    let (column, data) = match login_column {
+        Some(column) => (column, &rows[1..]),
+        None => (0, &rows[..]),
+    };
+    data.iter()
+        .filter_map(|row| row.get(column).cloned())
+        .collect()
+}
+
+fn parse_json(text: &str) -> anyhow::Result<Vec<String>> {
+    let value: Value = serde_json::from_str(text).context("Not valid JSON")?;
+    let list = match &value {
+        Value::Array(list) => list,
+        Value::Object(object) => match LIST_KEYS
+            .iter()
+            .find_map(|key| object.get(*key).and_then(Value::as_array))
+        {
+            Some(list) => list,
+            None => bail!("Expected a list of channels"),
+        },
+        _ => bail!("Expected a list of channels"),
+    };
+    Ok(list
+        .iter()
+        .filter_map(|item| match item {
+            Value::String(login) => Some(login.clone()),
+            Value::Object(object) => LOGIN_KEYS
+                .iter()
+                .find_map(|key| object.get(*key).and_then(Value::as_str))
+                .map(str::to_string),
+            _ => None,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_uses_the_login_column() {
+        let csv = "Followed At,Channel Name\n2020-01-01,Ninja\n2021-05-05,\"shroud\"\n";
+        assert_eq!(
+            parse_streamer_list(csv, StreamerListFormat::Csv).unwrap(),
+            vec!["ninja", "shroud"]
+        );
+    }
+
+    #[test]
+    fn csv_without_a_header_uses_the_first_column() {
+        let csv = "ninja\n\n@Pokimane, favourite\n";
+        assert_eq!(
+            parse_streamer_list(csv, StreamerListFormat::Csv).unwrap(),
+            vec!["ninja", "pokimane"]
+        );
+    }
+
+    #[test]
+    fn json_takes_strings_or_objects() {
+        let list = r#"["Ninja", "shroud"]"#;
+        assert_eq!(
+            parse_streamer_list(list, StreamerListFormat::Json).unwrap(),
+            vec!["ninja", "shroud"]
+        );
+
+        let helix = r#"{"data": [{"broadcaster_id": "1", "broadcaster_login": "ninja"}, {"user_login": "shroud"}]}"#;
+        assert_eq!(
+            parse_streamer_list(helix, StreamerListFormat::Json).unwrap(),
+            vec!["ninja", "shroud"]
+        );
+    }
+
+    #[test]
+    fn rejects_lists_without_channels() {
+        assert!(parse_streamer_list("", StreamerListFormat::Csv).is_err());
+        assert!(parse_streamer_list(r#"{"total": 3}"#, StreamerListFormat::Json).is_err());
+        assert!(parse_streamer_list("not json", StreamerListFormat::Json).is_err());
+    }
+
+    #[test]
+    fn valid_logins() {
+        assert!(is_valid_login("ninja_99"));
+        assert!(!is_valid_login("two words"));
+        assert!(!is_valid_login(&"a".repeat(26)));
+    }
+
+    #[test]
+    fn logins_without_a_user_are_unresolved() {
+        let users = vec![User {
+            id: "1".to_string(),
+            login: "ninja".to_string(),
+            display_name: "Ninja".to_string(),
+            profile_image_url: String::new(),
+        }];
+        let logins = ["ninja".to_string(), "gone".to_string()];
+        assert_eq!(unresolved_logins(&logins, &users), vec!["gone"]);
+    }
+}
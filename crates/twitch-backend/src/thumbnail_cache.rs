@@ -0,0 +1,310 @@
+//! Stream preview thumbnails saved to disk, for notification images and the
+//! dashboard window.
+//!
+//! Each followed channel's preview is fetched at `THUMBNAIL_WIDTH` x
+//! `THUMBNAIL_HEIGHT` (the CDN does the resizing, see `render_thumbnail`) and
+//! kept as `thumbnails/<user_id>.jpg` in the config dir. Twitch re-renders a
+//! live preview every few minutes, so files older than `MAX_AGE` are fetched
+//! again, and `prune` removes those of channels that haven't been live for a
+//! day.
+//!
+//! Streamers' profile images are kept alongside as `<user_id>-avatar.png`,
+//! at the small size Twitch serves for avatars, for notification icons.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context;
+
+use crate::config::ConfigManager;
+use crate::twitch::http::{HttpClient, ReqwestClient};
+use crate::twitch::Stream;
+
+/// Thumbnail size (16:9), large enough for a notification image.
+pub const THUMBNAIL_WIDTH: u32 = 320;
+pub const THUMBNAIL_HEIGHT: u32 = 180;
+
+/// A saved thumbnail older than this is fetched again.
+const MAX_AGE: Duration = Duration::from_mins(5);
+
+/// `prune` removes thumbnails older than this.
+const PRUNE_AFTER: Duration = Duration::from_hours(24);
+
+const THUMBNAIL_DIR: &str = "thumbnails";
+
+/// The profile image size Twitch serves that is closest to an icon.
+const AVATAR_SIZE: &str = "70x70";
+
+/// Points a profile image URL at its `AVATAR_SIZE` copy. Twitch's profile
+/// images end in `-300x300.<ext>`, and the other sizes only differ there.
+fn avatar_url(profile_image_url: &str) -> String {
+    profile_image_url.replace("-300x300.", &format!("-{AVATAR_SIZE}."))
+}
+
+/// Returns the directory thumbnails are saved in.
+pub fn thumbnail_dir() -> anyhow::Result<PathBuf> {
+    Ok(ConfigManager::config_dir()?.join(THUMBNAIL_DIR))
+}
+
+/// Whether `name` could be a file `ThumbnailCache` wrote, for serving files
+/// from `thumbnail_dir` by name without allowing paths out of it.
+pub fn is_thumbnail_file_name(name: &str) -> bool {
+    name.strip_suffix(".jpg")
+        .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Downloads and saves stream thumbnails.
+pub struct ThumbnailCache<H: HttpClient = ReqwestClient> {
+    http: H,
+    dir: PathBuf,
+}
+
+impl ThumbnailCache<ReqwestClient> {
+    /// Creates a cache in `thumbnail_dir`, downloading through `http`.
+    pub fn new(http: ReqwestClient) -> anyhow::Result<Self> {
+        Ok(Self::with_http_client(thumbnail_dir()?, http))
+    }
+}
+
+impl<H: HttpClient> ThumbnailCache<H> {
+    /// Creates a cache in `dir` using `http` for downloads.
+    pub fn with_http_client(dir: PathBuf, http: H) -> Self {
+        Self { http, dir }
+    }
+
+    fn path_for(&self, stream: &Stream) -> PathBuf {
+        self.dir.join(format!("{}.jpg", stream.user_id))
+    }
+
+    fn avatar_path_for(&self, stream: &Stream) -> PathBuf {
+        self.dir.join(format!("{}-avatar.png", stream.user_id))
+    }
+
+    /// The streamer's saved profile image, if it was fetched within a day.
+    pub fn cached_avatar(&self, stream: &Stream) -> Option<PathBuf> {
+        let path = self.avatar_path_for(stream);
+        is_fresh(&path, PRUNE_AFTER, SystemTime::now()).then_some(path)
+    }
+
+    /// The stream's saved thumbnail, if it is recent enough to show.
+    pub fn cached(&self, stream: &Stream) -> Option<PathBuf> {
+        self.cached_at(stream, SystemTime::now())
+    }
+
+    pub(crate) fn cached_at(&self, stream: &Stream, now: SystemTime) -> Option<PathBuf> {
+        let path = self.path_for(stream);
+        is_fresh(&path, MAX_AGE, now).then_some(path)
+    }
+
+    /// Saved thumbnails for `streams`, by user ID.
+    pub fn cached_paths(&self, streams: &[Stream]) -> HashMap<String, PathBuf> {
+        let now = SystemTime::now();
+        streams
+            .iter()
+            .filter_map(|s| Some((s.user_id.clone(), self.cached_at(s, now)?)))
+            .collect()
+    }
+
+    /// Returns the stream's thumbnail, downloading it unless a recent one is
+    /// saved.
+    pub async fn fetch(&self, stream: &Stream) -> anyhow::Result<PathBuf> {
+        if let Some(path) = self.cached(stream) {
+            return Ok(path);
+        }
+        if stream.thumbnail_url.is_empty() {
+            anyhow::bail!("{} has no thumbnail", stream.user_login);
+        }
+
+        let bytes = self
+            .http
+            .get_bytes(&stream.thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT))
+            .await
+            .with_context(|| format!("Failed to download {}'s thumbnail", stream.user_login))?;
+        self.save(self.path_for(stream), &bytes)
+    }
+
+    /// Returns the streamer's profile image (`Stream::profile_image_url`,
+    /// filled in from the Users API), downloading it unless one from the
+    /// last day is saved.
+    pub async fn fetch_avatar(&self, stream: &Stream) -> anyhow::Result<PathBuf> {
+        if let Some(path) = self.cached_avatar(stream) {
+            return Ok(path);
+        }
+        if stream.profile_image_url.is_empty() {
+            anyhow::bail!("{} has no profile image", stream.user_login);
+        }
+
+        let bytes = self
+            .http
+            .get_bytes(&avatar_url(&stream.profile_image_url))
+            .await
+            .with_context(|| format!("Failed to download {}'s profile image", stream.user_login))?;
+        self.save(self.avatar_path_for(stream), &bytes)
+    }
+
+    /// Writes to a temporary file first so a reader never sees half an image.
+    fn save(&self, path: PathBuf, bytes: &[u8]) -> anyhow::Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)?;
+        let partial = path.with_extension("part");
+        std::fs::write(&partial, bytes)?;
+        std::fs::rename(&partial, &path)?;
+        Ok(path)
+    }
+
+    /// Fetches thumbnails for `streams`, logging any that fail.
+    pub async fn fetch_all(&self, streams: &[Stream]) {
+        for stream in streams {
+            if let Err(e) = self.fetch(stream).await {
+                tracing::debug!("{:#}", e);
+            }
+        }
+    }
+
+    /// Removes thumbnails that haven't been refreshed for a day.
+    pub fn prune(&self) -> anyhow::Result<usize> {
+        self.prune_at(SystemTime::now())
+    }
+
+    pub(crate) fn prune_at(&self, now: SystemTime) -> anyhow::Result<usize> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry?.path();
+            if !is_fresh(&path, PRUNE_AFTER, now) {
+                std::fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Whether `path` exists and was written within `max_age` of `now`.
+fn is_fresh(path: &Path, max_age: Duration, now: SystemTime) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| now.duration_since(modified).unwrap_or_default() < max_age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twitch::http::mock::MockHttpClient;
+
+    const THUMBNAIL_URL: &str =
+        "https://static-cdn.jtvnw.net/previews-ttv/live_user_alice-{width}x{height}.jpg";
+    const SIZED_URL: &str = "https://static-cdn.jtvnw.net/previews-ttv/live_user_alice-320x180.jpg";
+
+    fn stream() -> Stream {
+        Stream {
+            id: "s1".to_string(),
+            user_id: "123".to_string(),
+            user_login: "alice".to_string(),
+            user_name: "Alice".to_string(),
+            game_id: "game".to_string(),
+            game_name: "Game".to_string(),
+            title: "Title".to_string(),
+            viewer_count: 100,
+            started_at: chrono::Utc::now(),
+            thumbnail_url: THUMBNAIL_URL.to_string(),
+            tags: vec![],
+            profile_image_url: String::new(),
+        }
+    }
+
+    fn cache(dir: &Path, http: MockHttpClient) -> ThumbnailCache<MockHttpClient> {
+        ThumbnailCache::with_http_client(dir.join(THUMBNAIL_DIR), http)
+    }
+
+    fn age(path: &Path, by: Duration) {
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - by).unwrap();
+    }
+
+    #[tokio::test]
+    async fn fetches_sized_thumbnail_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let http = MockHttpClient::new().on_get(SIZED_URL, 200, "jpeg bytes");
+        let cache = cache(dir.path(), http.clone());
+
+        let path = cache.fetch(&stream()).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "jpeg bytes");
+        assert_eq!(path.file_name().unwrap(), "123.jpg");
+
+        cache.fetch(&stream()).await.unwrap();
+        assert_eq!(http.get_requests().len(), 1);
+        assert_eq!(cache.cached(&stream()), Some(path));
+    }
+
+    #[tokio::test]
+    async fn stale_thumbnails_are_fetched_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let http = MockHttpClient::new().on_get(SIZED_URL, 200, "jpeg bytes");
+        let cache = cache(dir.path(), http.clone());
+
+        let path = cache.fetch(&stream()).await.unwrap();
+        age(&path, MAX_AGE + Duration::from_secs(1));
+        assert!(cache.cached(&stream()).is_none());
+
+        cache.fetch(&stream()).await.unwrap();
+        assert_eq!(http.get_requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_download_saves_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let http = MockHttpClient::new().on_get_not_found(SIZED_URL);
+        let cache = cache(dir.path(), http);
+
+        assert!(cache.fetch(&stream()).await.is_err());
+        assert!(cache.cached_paths(&[stream()]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn prune_removes_old_thumbnails() {
+        let dir = tempfile::tempdir().unwrap();
+        let http = MockHttpClient::new().on_get(SIZED_URL, 200, "jpeg bytes");
+        let cache = cache(dir.path(), http);
+        let path = cache.fetch(&stream()).await.unwrap();
+
+        assert_eq!(cache.prune().unwrap(), 0);
+        age(&path, PRUNE_AFTER + Duration::from_secs(1));
+        assert_eq!(cache.prune().unwrap(), 1);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn avatar_fetched_at_icon_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = "https://static-cdn.jtvnw.net/jtv_user_pictures/alice-profile_image-70x70.png";
+        let http = MockHttpClient::new().on_get(small, 200, "png bytes");
+        let cache = cache(dir.path(), http.clone());
+        let mut alice = stream();
+        alice.profile_image_url =
+            "https://static-cdn.jtvnw.net/jtv_user_pictures/alice-profile_image-300x300.png"
+                .to_string();
+
+        assert!(cache.cached_avatar(&alice).is_none());
+        let path = cache.fetch_avatar(&alice).await.unwrap();
+        assert_eq!(path.file_name().unwrap(), "123-avatar.png");
+        cache.fetch_avatar(&alice).await.unwrap();
+        assert_eq!(http.get_requests().len(), 1);
+        assert_eq!(cache.cached_avatar(&alice), Some(path));
+        // The thumbnail is separate
+        assert!(cache.cached(&alice).is_none());
+    }
+
+    #[test]
+    fn only_cache_file_names_are_served() {
+        assert!(is_thumbnail_file_name("123.jpg"));
+        assert!(!is_thumbnail_file_name("../data.db"));
+        assert!(!is_thumbnail_file_name("123.part"));
+        assert!(!is_thumbnail_file_name(".jpg"));
+        assert!(!is_thumbnail_file_name("123-avatar.png"));
+    }
+}
@@ -0,0 +1,168 @@
+//! Stream title alerts: notify when a live title mentions something the user
+//! is watching for, like "drops enabled" or "charity".
+//!
+//! Patterns come from `Config::title_alerts`. Plain text matches anywhere in a
+//! title, ignoring case; a pattern wrapped in slashes (`/drops? on/`) is a
+//! case-insensitive regex.
+
+use std::collections::HashSet;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::twitch::Stream;
+
+/// One parsed `title_alerts` entry.
+#[derive(Debug, Clone)]
+enum Matcher {
+    /// Lowercased text to find
+    Text(String),
+    Regex(Regex),
+}
+
+/// The compiled `title_alerts` patterns.
+#[derive(Debug, Clone, Default)]
+pub struct TitleAlerts {
+    /// (pattern as written, matcher)
+    patterns: Vec<(String, Matcher)>,
+}
+
+/// Parses one pattern. Only `/…/` patterns can fail.
+fn parse(pattern: &str) -> Result<Matcher, regex::Error> {
+    let trimmed = pattern.trim();
+    match trimmed
+        .strip_prefix('/')
+        .and_then(|rest| rest.strip_suffix('/'))
+    {
+        Some(re) => RegexBuilder::new(re)
+            .case_insensitive(true)
+            .build()
+            .map(Matcher::Regex),
+        None => Ok(Matcher::Text(trimmed.to_lowercase())),
+    }
+}
+
+/// Why `pattern` can't be used, for settings validation. `None` if it's fine.
+pub fn pattern_error(pattern: &str) -> Option<String> {
+    parse(pattern).err().map(|e| e.to_string())
+}
+
+impl TitleAlerts {
+    /// Compiles the patterns, skipping blank ones and logging any that
+    /// don't parse (a hand-edited config isn't validated).
+    pub fn new(patterns: &[String]) -> Self {
+        let patterns = patterns
+            .iter()
+            .filter(|p| !p.trim().is_empty())
+            .filter_map(|p| match parse(p) {
+                Ok(matcher) => Some((p.trim().to_string(), matcher)),
+                Err(e) => {
+                    tracing::warn!("Skipping title alert {}: {}", p, e);
+                    None
+                }
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// The first pattern, as written, that `title` matches.
+    pub fn first_match(&self, title: &str) -> Option<&str> {
+        let lower = title.to_lowercase();
+        self.patterns
+            .iter()
+            .find(|(_, matcher)| match matcher {
+                Matcher::Text(text) => lower.contains(text.as_str()),
+                Matcher::Regex(re) => re.is_match(title),
+            })
+            .map(|(pattern, _)| pattern.as_str())
+    }
+
+    /// Streams to alert for, with the pattern each matched. Each stream
+    /// (by `id`) is alerted once: `alerted` remembers them, and forgets
+    /// streams no longer in `streams` so it doesn't grow without bound.
+    pub fn new_matches<'a>(
+        &self,
+        streams: &'a [Stream],
+        alerted: &mut HashSet<String>,
+    ) -> Vec<(&'a Stream, String)> {
+        let live: HashSet<&str> = streams.iter().map(|s| s.id.as_str()).collect();
+        alerted.retain(|id| live.contains(id.as_str()));
+
+        let mut out = Vec::new();
+        for stream in streams {
+            let Some(pattern) = self.first_match(&stream.title) else {
+                continue;
+            };
+            if alerted.insert(stream.id.clone()) {
+                out.push((stream, pattern.to_string()));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn titled(id: &str, title: &str) -> Stream {
+        Stream {
+            id: id.to_string(),
+            user_id: id.to_string(),
+            user_login: format!("user{id}"),
+            user_name: format!("User{id}"),
+            game_id: "game".to_string(),
+            game_name: "Game".to_string(),
+            title: title.to_string(),
+            viewer_count: 100,
+            started_at: Utc::now(),
+            thumbnail_url: String::new(),
+            tags: vec![],
+            profile_image_url: String::new(),
+        }
+    }
+
+    #[test]
+    fn plain_text_matches_ignoring_case() {
+        let alerts = TitleAlerts::new(&["Drops Enabled".to_string()]);
+        assert_eq!(
+            alerts.first_match("!drops enabled all day"),
+            Some("Drops Enabled")
+        );
+        assert_eq!(alerts.first_match("no drops today"), None);
+    }
+
+    #[test]
+    fn slashes_make_a_regex() {
+        let alerts = TitleAlerts::new(&["/charity (stream|marathon)/".to_string()]);
+        assert!(alerts.first_match("CHARITY MARATHON for kids").is_some());
+        assert!(alerts.first_match("charity").is_none());
+    }
+
+    #[test]
+    fn invalid_and_blank_patterns_are_skipped() {
+        assert!(pattern_error("/(unclosed/").is_some());
+        assert!(pattern_error("(plain text is fine").is_none());
+
+        let alerts = TitleAlerts::new(&["/(unclosed/".to_string(), "  ".to_string()]);
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn each_stream_alerted_once_until_it_ends() {
+        let alerts = TitleAlerts::new(&["drops".to_string()]);
+        let mut alerted = HashSet::new();
+        let streams = vec![titled("1", "drops on"), titled("2", "just chatting")];
+
+        assert_eq!(alerts.new_matches(&streams, &mut alerted).len(), 1);
+        assert!(alerts.new_matches(&streams, &mut alerted).is_empty());
+
+        // Ended streams are forgotten
+        alerts.new_matches(&[], &mut alerted);
+        assert_eq!(alerts.new_matches(&streams, &mut alerted).len(), 1);
+    }
+}
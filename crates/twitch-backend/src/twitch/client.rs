@@ -1,17 +1,41 @@
 use anyhow::{Context, Result};
-use reqwest::header::HeaderMap;
+use reqwest::header::{HeaderMap, IF_NONE_MATCH};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use super::http::{HttpClient, ReqwestClient};
+use super::http::{HttpClient, HttpMethod, HttpRequest, HttpResponse, ReqwestClient};
+use super::ratelimit::{
+    backoff_delay, jitter, RateLimiter, BASE_BACKOFF, MAX_ATTEMPTS, MAX_RATE_LIMIT_WAIT_SECS,
+};
 use super::types::{
     Category, FollowedChannel, FollowedChannelsResponse, GamesResponse, ScheduleData,
     ScheduleResponse, SearchCategoriesResponse, Stream, StreamsResponse, User, UsersResponse,
+    Video, VideosResponse,
 };
 use super::ApiError;
 
 const HELIX_BASE_URL: &str = "https://api.twitch.tv/helix";
 
+/// How often conditional requests were answered with 304 Not Modified.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ConditionalRequestStats {
+    /// 304s: the cached ETag still matched and nothing was parsed.
+    pub hits: u64,
+    /// Full responses to requests that could have been conditional.
+    pub misses: u64,
+}
+
+/// ETags of the last full response per endpoint, plus hit/miss counters.
+#[derive(Debug, Default)]
+struct EtagCache {
+    etags: std::sync::Mutex<HashMap<String, String>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
 /// Twitch Helix API client
 ///
 /// Generic over the HTTP client implementation for testability.
@@ -20,21 +44,33 @@ pub struct TwitchClient<H: HttpClient = ReqwestClient> {
     client_id: String,
     access_token: Arc<RwLock<Option<String>>>,
     user_id: Arc<RwLock<Option<String>>>,
+    etag_cache: Arc<EtagCache>,
+    rate_limiter: Arc<RateLimiter>,
+    /// [`BASE_BACKOFF`]; zero in tests so retries don't sleep.
+    retry_base_delay: std::time::Duration,
 }
 
 impl TwitchClient<ReqwestClient> {
-    /// Creates a new Twitch API client with the default HTTP implementation
-    pub fn new(client_id: String) -> Self {
+    /// Creates a new Twitch API client sending requests through `http`
+    pub fn new(client_id: String, http: ReqwestClient) -> Self {
         Self {
-            http: ReqwestClient::new(),
+            http,
             client_id,
             access_token: Arc::new(RwLock::new(None)),
             user_id: Arc::new(RwLock::new(None)),
+            etag_cache: Arc::new(EtagCache::default()),
+            rate_limiter: Arc::new(RateLimiter::default()),
+            retry_base_delay: BASE_BACKOFF,
         }
     }
 }
 
 impl<H: HttpClient> TwitchClient<H> {
+    /// The HTTP client requests go through
+    pub fn http(&self) -> &H {
+        &self.http
+    }
+
     /// Sets the access token for API requests
     pub async fn set_access_token(&self, token: String) {
         let mut guard = self.access_token.write().await;
@@ -73,18 +109,102 @@ impl<H: HttpClient> TwitchClient<H> {
         Ok(headers)
     }
 
-    /// Makes an authenticated GET request to the Helix API
+    /// Starts an authenticated request to a Helix endpoint
+    async fn request(&self, method: HttpMethod, endpoint: &str) -> Result<HttpRequest> {
+        Ok(
+            HttpRequest::new(method, format!("{HELIX_BASE_URL}{endpoint}"))
+                .headers(self.build_headers().await?),
+        )
+    }
+
+    /// Sends a request, waiting out an empty rate limit bucket and retrying
+    /// 429s up to [`MAX_ATTEMPTS`] times with jittered exponential backoff.
+    /// 5xx responses and network errors are only retried for idempotent
+    /// methods, since a POST may already have taken effect.
+    ///
+    /// Returns `ApiError::RateLimited` if the bucket won't refill within
+    /// [`MAX_RATE_LIMIT_WAIT_SECS`], or is still empty after the last attempt.
+    /// Any other response, including a final 5xx, is returned for the caller
+    /// to interpret.
+    async fn send(&self, request: &HttpRequest) -> Result<HttpResponse, ApiError> {
+        let retryable = request.method.is_idempotent();
+        let mut attempt = 0;
+        loop {
+            if let Some(wait) = self.rate_limiter.wait(chrono::Utc::now().timestamp()) {
+                if wait.as_secs() > MAX_RATE_LIMIT_WAIT_SECS {
+                    return Err(ApiError::RateLimited { retry_after: wait });
+                }
+                tracing::warn!("Rate limited by Twitch, waiting {}s", wait.as_secs());
+                tokio::time::sleep(wait).await;
+            }
+
+            attempt += 1;
+            let retries_left = attempt < MAX_ATTEMPTS;
+            let backoff = backoff_delay(attempt, self.retry_base_delay, jitter());
+            match self.http.send(request.clone()).await {
+                Ok(response) => {
+                    if let Some(limit) = response.rate_limit {
+                        self.rate_limiter.record(limit);
+                    }
+                    if response.is_rate_limited() {
+                        if !retries_left {
+                            let retry_after = self
+                                .rate_limiter
+                                .wait(chrono::Utc::now().timestamp())
+                                .unwrap_or(backoff);
+                            return Err(ApiError::RateLimited { retry_after });
+                        }
+                        // Without headers there's no reset to wait for
+                        if response.rate_limit.is_none() {
+                            tokio::time::sleep(backoff).await;
+                        }
+                        continue;
+                    }
+                    if response.is_server_error() && retryable && retries_left {
+                        tracing::warn!(
+                            "API error {} (attempt {}/{}), retrying in {}ms",
+                            response.status,
+                            attempt,
+                            MAX_ATTEMPTS,
+                            backoff.as_millis()
+                        );
+                        tokio::time::sleep(backoff).await;
+                        continue;
+                    }
+                    return Ok(response);
+                }
+                Err(e) if retryable && retries_left => {
+                    tracing::warn!(
+                        "Request failed (attempt {}/{}), retrying in {}ms: {}",
+                        attempt,
+                        MAX_ATTEMPTS,
+                        backoff.as_millis(),
+                        e
+                    );
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Makes an authenticated request to the Helix API, with an optional
+    /// JSON body, and returns the successful response
     ///
     /// Returns `ApiError::Unauthorized` for 401 responses, allowing callers
     /// to handle token refresh and retry.
-    async fn get<T: serde::de::DeserializeOwned + Send>(
+    async fn call(
         &self,
+        method: HttpMethod,
         endpoint: &str,
-    ) -> Result<T, ApiError> {
-        let headers = self.build_headers().await?;
-        let url = format!("{HELIX_BASE_URL}{endpoint}");
+        body: Option<&serde_json::Value>,
+    ) -> Result<HttpResponse, ApiError> {
+        let mut request = self.request(method, endpoint).await?;
+        if let Some(body) = body {
+            request = request.json(body);
+        }
 
-        let response = self.http.get_response(&url, &headers).await?;
+        let response = self.send(&request).await?;
 
         if response.is_unauthorized() {
             return Err(ApiError::Unauthorized);
@@ -98,7 +218,18 @@ impl<H: HttpClient> TwitchClient<H> {
             )));
         }
 
-        Ok(response.json()?)
+        Ok(response)
+    }
+
+    /// Makes an authenticated GET request to the Helix API
+    ///
+    /// Returns `ApiError::Unauthorized` for 401 responses, allowing callers
+    /// to handle token refresh and retry.
+    async fn get<T: serde::de::DeserializeOwned + Send>(
+        &self,
+        endpoint: &str,
+    ) -> Result<T, ApiError> {
+        Ok(self.call(HttpMethod::Get, endpoint, None).await?.json()?)
     }
 
     /// Makes an authenticated GET request that may return 404
@@ -109,10 +240,9 @@ impl<H: HttpClient> TwitchClient<H> {
         &self,
         endpoint: &str,
     ) -> Result<Option<T>, ApiError> {
-        let headers = self.build_headers().await?;
-        let url = format!("{HELIX_BASE_URL}{endpoint}");
+        let request = self.request(HttpMethod::Get, endpoint).await?;
 
-        let response = self.http.get_response(&url, &headers).await?;
+        let response = self.send(&request).await?;
 
         if response.is_unauthorized() {
             return Err(ApiError::Unauthorized);
@@ -130,10 +260,68 @@ impl<H: HttpClient> TwitchClient<H> {
         Ok(Some(response.json()?))
     }
 
-    /// Clears authentication state
+    /// Makes an authenticated GET request, sending the ETag of the last
+    /// full response for this endpoint as `If-None-Match`.
+    ///
+    /// Returns `Ok(None)` on 304 Not Modified, skipping the JSON parse, so
+    /// callers can keep what they already have.
+    async fn get_if_changed<T: serde::de::DeserializeOwned + Send>(
+        &self,
+        endpoint: &str,
+    ) -> Result<Option<T>, ApiError> {
+        let mut request = self.request(HttpMethod::Get, endpoint).await?;
+        let cached_etag = self.etag_cache.etags.lock().unwrap().get(endpoint).cloned();
+        if let Some(value) = cached_etag.and_then(|etag| etag.parse().ok()) {
+            request.headers.insert(IF_NONE_MATCH, value);
+        }
+
+        let response = self.send(&request).await?;
+
+        if response.is_unauthorized() {
+            return Err(ApiError::Unauthorized);
+        }
+
+        if response.is_not_modified() {
+            self.etag_cache.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        }
+
+        if !response.is_success() {
+            return Err(ApiError::Other(anyhow::anyhow!(
+                "API error {}: {}",
+                response.status,
+                response.body
+            )));
+        }
+
+        self.etag_cache.misses.fetch_add(1, Ordering::Relaxed);
+        let data = response.json()?;
+        let mut etags = self.etag_cache.etags.lock().unwrap();
+        match response.etag {
+            Some(etag) => etags.insert(endpoint.to_string(), etag),
+            None => etags.remove(endpoint),
+        };
+        Ok(Some(data))
+    }
+
+    /// Drops the cached ETag for an endpoint so its next request is unconditional
+    fn forget_etag(&self, endpoint: &str) {
+        self.etag_cache.etags.lock().unwrap().remove(endpoint);
+    }
+
+    /// Conditional request hits and misses since startup
+    pub fn conditional_request_stats(&self) -> ConditionalRequestStats {
+        ConditionalRequestStats {
+            hits: self.etag_cache.hits.load(Ordering::Relaxed),
+            misses: self.etag_cache.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Clears authentication state, and the ETags cached for that user
     pub async fn clear_auth(&self) {
         *self.access_token.write().await = None;
         *self.user_id.write().await = None;
+        self.etag_cache.etags.lock().unwrap().clear();
     }
 }
 
@@ -144,6 +332,9 @@ impl<H: HttpClient + Clone> Clone for TwitchClient<H> {
             client_id: self.client_id.clone(),
             access_token: self.access_token.clone(),
             user_id: self.user_id.clone(),
+            etag_cache: self.etag_cache.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+            retry_base_delay: self.retry_base_delay,
         }
     }
 }
@@ -180,6 +371,39 @@ impl<H: HttpClient> TwitchClient<H> {
 
         Ok(all_streams)
     }
+
+    /// Like [`Self::get_followed_streams`], but returns `Ok(None)` when Twitch
+    /// reports the list unchanged since the last call.
+    ///
+    /// Only a list that fits on one page is cached: with more pages a 304 on
+    /// the first says nothing about the rest, so those are always fetched.
+    pub async fn get_followed_streams_if_changed(&self) -> Result<Option<Vec<Stream>>, ApiError> {
+        let user_id = self
+            .get_user_id()
+            .await
+            .context("User ID not set")
+            .map_err(ApiError::Other)?;
+
+        let endpoint = format!("/streams/followed?user_id={user_id}&first=100");
+        let Some(first_page) = self.get_if_changed::<StreamsResponse>(&endpoint).await? else {
+            return Ok(None);
+        };
+
+        let mut all_streams = first_page.data;
+        let mut cursor = first_page.pagination.and_then(|p| p.cursor);
+        if cursor.as_deref().is_some_and(|c| !c.is_empty()) {
+            self.forget_etag(&endpoint);
+        }
+
+        while let Some(c) = cursor.filter(|c| !c.is_empty()) {
+            let endpoint = format!("/streams/followed?user_id={user_id}&first=100&after={c}");
+            let response: StreamsResponse = self.get(&endpoint).await?;
+            all_streams.extend(response.data);
+            cursor = response.pagination.and_then(|p| p.cursor);
+        }
+
+        Ok(Some(all_streams))
+    }
 }
 
 // Channel-related methods
@@ -255,37 +479,107 @@ impl<H: HttpClient> TwitchClient<H> {
         Ok(response.data)
     }
 
-    /// Gets top streams for a specific category/game, optionally filtered by language.
+    /// Gets up to `pages` pages of 10 top streams for a specific category/game,
+    /// starting after `cursor` (or from the top), in any of `languages` (all
+    /// languages when empty).
     ///
-    /// `language` is an ISO 639-1 two-letter code (e.g. "en", "es").
+    /// Returns the streams and the cursor for the page after them, `None`
+    /// once there are no more. `languages` are ISO 639-1 two-letter codes
+    /// (e.g. "en", "es").
     /// Returns `ApiError::Unauthorized` if the token has expired.
     pub async fn get_streams_by_category(
         &self,
         game_id: &str,
-        language: Option<&str>,
-    ) -> Result<Vec<Stream>, ApiError> {
-        let endpoint = match language {
-            Some(lang) => format!("/streams?game_id={game_id}&first=10&language={lang}"),
-            None => format!("/streams?game_id={game_id}&first=10"),
-        };
-        let response: StreamsResponse = self.get(&endpoint).await?;
-        Ok(response.data)
+        languages: &[String],
+        cursor: Option<&str>,
+        pages: usize,
+    ) -> Result<(Vec<Stream>, Option<String>), ApiError> {
+        if cursor.is_none() {
+            // What this returns may replace what the conditional request
+            // last returned, so that one mustn't answer "unchanged" next
+            self.forget_etag(&category_streams_endpoint(game_id, languages));
+        }
+        let mut streams = Vec::new();
+        let mut cursor = cursor.map(str::to_string);
+        for _ in 0..pages {
+            let endpoint = match &cursor {
+                Some(c) => format!(
+                    "{}&after={c}",
+                    category_streams_endpoint(game_id, languages)
+                ),
+                None => category_streams_endpoint(game_id, languages),
+            };
+            let response: StreamsResponse = self.get(&endpoint).await?;
+            streams.extend(response.data);
+            cursor = response.pagination.and_then(|p| p.cursor);
+            if cursor.as_deref().is_none_or(str::is_empty) {
+                return Ok((streams, None));
+            }
+        }
+        Ok((streams, cursor))
+    }
+
+    /// Like [`Self::get_streams_by_category`] for the first page, but returns
+    /// `Ok(None)` when Twitch reports the top streams unchanged since the last call.
+    pub async fn get_streams_by_category_if_changed(
+        &self,
+        game_id: &str,
+        languages: &[String],
+    ) -> Result<Option<Vec<Stream>>, ApiError> {
+        let response: Option<StreamsResponse> = self
+            .get_if_changed(&category_streams_endpoint(game_id, languages))
+            .await?;
+        Ok(response.map(|r| r.data))
+    }
+}
+
+fn category_streams_endpoint(game_id: &str, languages: &[String]) -> String {
+    let mut endpoint = format!("/streams?game_id={game_id}&first=10");
+    for lang in languages {
+        endpoint.push_str("&language=");
+        endpoint.push_str(lang);
     }
+    endpoint
 }
 
 // User-related methods
 impl<H: HttpClient> TwitchClient<H> {
-    /// Gets users by their IDs (up to 100 per request)
+    /// Gets users by their IDs, 100 per request
     ///
     /// Returns `ApiError::Unauthorized` if the token has expired.
     pub async fn get_users_by_ids(&self, user_ids: &[&str]) -> Result<Vec<User>, ApiError> {
-        if user_ids.is_empty() {
-            return Ok(vec![]);
+        self.get_users("id", user_ids).await
+    }
+
+    /// Gets users by their logins, 100 per request. Logins of missing or
+    /// banned accounts are left out of the result.
+    ///
+    /// Returns `ApiError::Unauthorized` if the token has expired.
+    pub async fn get_users_by_logins(&self, logins: &[&str]) -> Result<Vec<User>, ApiError> {
+        self.get_users("login", logins).await
+    }
+
+    async fn get_users(&self, param: &str, values: &[&str]) -> Result<Vec<User>, ApiError> {
+        let mut users = Vec::with_capacity(values.len());
+        // Helix takes at most 100 IDs or logins per request
+        for chunk in values.chunks(100) {
+            let params: Vec<String> = chunk.iter().map(|v| format!("{param}={v}")).collect();
+            let endpoint = format!("/users?{}", params.join("&"));
+            let response: UsersResponse = self.get(&endpoint).await?;
+            users.extend(response.data);
         }
+        Ok(users)
+    }
+}
 
-        let params: Vec<String> = user_ids.iter().map(|id| format!("id={id}")).collect();
-        let endpoint = format!("/users?{}", params.join("&"));
-        let response: UsersResponse = self.get(&endpoint).await?;
+// Video-related methods
+impl<H: HttpClient> TwitchClient<H> {
+    /// Gets a broadcaster's most recent past broadcasts, newest first
+    ///
+    /// Returns `ApiError::Unauthorized` if the token has expired.
+    pub async fn get_videos(&self, user_id: &str, first: usize) -> Result<Vec<Video>, ApiError> {
+        let endpoint = format!("/videos?user_id={user_id}&type=archive&first={first}");
+        let response: VideosResponse = self.get(&endpoint).await?;
         Ok(response.data)
     }
 }
@@ -317,6 +611,9 @@ impl<H: HttpClient> TwitchClient<H> {
             client_id,
             access_token: Arc::new(RwLock::new(None)),
             user_id: Arc::new(RwLock::new(None)),
+            etag_cache: Arc::new(EtagCache::default()),
+            rate_limiter: Arc::new(RateLimiter::default()),
+            retry_base_delay: std::time::Duration::ZERO,
         }
     }
 }
@@ -438,6 +735,170 @@ mod tests {
         assert_eq!(client_id_header.to_str().unwrap(), "my_client_id");
     }
 
+    // === Retry and rate limit tests ===
+
+    #[tokio::test]
+    async fn server_errors_are_retried() {
+        let mock = MockHttpClient::new()
+            .on_get_once(FOLLOWED_URL, 503, "Service Unavailable")
+            .on_get_json(FOLLOWED_URL, &make_streams_response(vec![], None));
+        let client = authed_client(mock.clone()).await;
+
+        client.get_followed_streams().await.unwrap();
+
+        assert_eq!(mock.get_requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn persistent_server_error_gives_up_after_max_attempts() {
+        let mock = MockHttpClient::new().on_get(FOLLOWED_URL, 500, "Internal Server Error");
+        let client = authed_client(mock.clone()).await;
+
+        let result = client.get_followed_streams().await;
+
+        assert!(matches!(result, Err(ApiError::Other(_))));
+        assert_eq!(mock.get_requests().len(), MAX_ATTEMPTS as usize);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_request_is_retried_after_reset() {
+        // Reset already passed, so the retry goes straight out
+        let reset = chrono::Utc::now().timestamp() - 1;
+        let mock = MockHttpClient::new()
+            .on_get_rate_limited_once(FOLLOWED_URL, reset)
+            .on_get_json(FOLLOWED_URL, &make_streams_response(vec![], None));
+        let client = authed_client(mock.clone()).await;
+
+        client.get_followed_streams().await.unwrap();
+
+        assert_eq!(mock.get_requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn distant_rate_limit_reset_surfaces_rate_limited() {
+        let reset = chrono::Utc::now().timestamp() + 600;
+        let mock = MockHttpClient::new()
+            .on_get_rate_limited_once(FOLLOWED_URL, reset)
+            .on_get_json(FOLLOWED_URL, &make_streams_response(vec![], None));
+        let client = authed_client(mock.clone()).await;
+
+        let result = client.get_followed_streams().await;
+
+        let Err(ApiError::RateLimited { retry_after }) = result else {
+            panic!("expected RateLimited, got {result:?}");
+        };
+        assert!(retry_after.as_secs() > MAX_RATE_LIMIT_WAIT_SECS);
+        assert_eq!(mock.get_requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn unauthorized_is_not_retried() {
+        let mock = MockHttpClient::new().on_get(FOLLOWED_URL, 401, "Unauthorized");
+        let client = authed_client(mock.clone()).await;
+
+        let result = client.get_followed_streams().await;
+
+        assert!(matches!(result, Err(ApiError::Unauthorized)));
+        assert_eq!(mock.get_requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn post_server_error_is_not_retried() {
+        let url = "https://api.twitch.tv/helix/streams/markers";
+        let mock = MockHttpClient::new().on(HttpMethod::Post, url, 500, "Internal Server Error");
+        let client = authed_client(mock.clone()).await;
+
+        let body = serde_json::json!({"user_id": "user123"});
+        let result = client
+            .call(HttpMethod::Post, "/streams/markers", Some(&body))
+            .await;
+
+        assert!(matches!(result, Err(ApiError::Other(_))));
+        let requests = mock.get_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].body.as_deref(),
+            Some(r#"{"user_id":"user123"}"#)
+        );
+        assert!(requests[0].headers.contains_key("Authorization"));
+    }
+
+    // === Conditional request tests ===
+
+    const FOLLOWED_URL: &str =
+        "https://api.twitch.tv/helix/streams/followed?user_id=user123&first=100";
+
+    async fn authed_client(mock: MockHttpClient) -> TwitchClient<MockHttpClient> {
+        let client = TwitchClient::with_http_client("test_client_id".to_string(), mock);
+        client.set_access_token("test_token".to_string()).await;
+        client.set_user_id("user123".to_string()).await;
+        client
+    }
+
+    #[tokio::test]
+    async fn unchanged_followed_streams_are_not_modified() {
+        let streams = vec![make_stream("1", "Streamer")];
+        let mock = MockHttpClient::new().on_get_json_with_etag(
+            FOLLOWED_URL,
+            &make_streams_response(streams, None),
+            "\"abc\"",
+        );
+        let client = authed_client(mock.clone()).await;
+
+        let first = client.get_followed_streams_if_changed().await.unwrap();
+        assert_eq!(first.unwrap().len(), 1);
+        assert!(!mock.get_requests()[0].headers.contains_key(IF_NONE_MATCH));
+
+        let second = client.get_followed_streams_if_changed().await.unwrap();
+        assert!(second.is_none());
+        assert_eq!(
+            mock.get_requests()[1].headers.get(IF_NONE_MATCH).unwrap(),
+            "\"abc\""
+        );
+        assert_eq!(
+            client.conditional_request_stats(),
+            ConditionalRequestStats { hits: 1, misses: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn multi_page_followed_streams_are_always_fetched() {
+        let mock = MockHttpClient::new()
+            .on_get_json_with_etag(
+                FOLLOWED_URL,
+                &make_streams_response(vec![make_stream("1", "Streamer1")], Some("page2")),
+                "\"abc\"",
+            )
+            .on_get_json(
+                "https://api.twitch.tv/helix/streams/followed?user_id=user123&first=100&after=page2",
+                &make_streams_response(vec![make_stream("2", "Streamer2")], None),
+            );
+        let client = authed_client(mock).await;
+
+        for _ in 0..2 {
+            let streams = client.get_followed_streams_if_changed().await.unwrap();
+            assert_eq!(streams.unwrap().len(), 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn clear_auth_forgets_etags() {
+        let mock = MockHttpClient::new().on_get_json_with_etag(
+            FOLLOWED_URL,
+            &make_streams_response(vec![make_stream("1", "Streamer")], None),
+            "\"abc\"",
+        );
+        let client = authed_client(mock).await;
+        client.get_followed_streams_if_changed().await.unwrap();
+
+        client.clear_auth().await;
+        client.set_access_token("test_token".to_string()).await;
+        client.set_user_id("user123".to_string()).await;
+
+        let streams = client.get_followed_streams_if_changed().await.unwrap();
+        assert!(streams.is_some());
+    }
+
     // === get_users_by_ids tests ===
 
     #[tokio::test]
@@ -476,6 +937,42 @@ mod tests {
         assert_eq!(result[1].id, "456");
     }
 
+    #[tokio::test]
+    async fn get_users_by_ids_batches_by_100() {
+        let ids: Vec<String> = (0..150).map(|i| i.to_string()).collect();
+        let id_refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let url = |range: std::ops::Range<usize>| {
+            let params: Vec<String> = ids[range].iter().map(|id| format!("id={id}")).collect();
+            format!("https://api.twitch.tv/helix/users?{}", params.join("&"))
+        };
+        let user = |id: &str| User {
+            id: id.to_string(),
+            login: format!("user{id}"),
+            display_name: format!("User{id}"),
+            profile_image_url: String::new(),
+        };
+        let mock = MockHttpClient::new()
+            .on_get_json(
+                &url(0..100),
+                &UsersResponse {
+                    data: vec![user("0")],
+                },
+            )
+            .on_get_json(
+                &url(100..150),
+                &UsersResponse {
+                    data: vec![user("149")],
+                },
+            );
+        let client = authed_client(mock.clone()).await;
+
+        let result = client.get_users_by_ids(&id_refs).await.unwrap();
+
+        let result_ids: Vec<&str> = result.iter().map(|u| u.id.as_str()).collect();
+        assert_eq!(result_ids, vec!["0", "149"]);
+        assert_eq!(mock.get_requests().len(), 2);
+    }
+
     #[tokio::test]
     async fn get_users_by_ids_empty_returns_empty() {
         let mock = MockHttpClient::new();
@@ -486,6 +983,61 @@ mod tests {
         assert!(result.is_empty());
     }
 
+    #[tokio::test]
+    async fn get_users_by_logins_queries_by_login() {
+        let response = UsersResponse {
+            data: vec![User {
+                id: "123".to_string(),
+                login: "streamer1".to_string(),
+                display_name: "Streamer1".to_string(),
+                profile_image_url: String::new(),
+            }],
+        };
+        let mock = MockHttpClient::new().on_get_json(
+            "https://api.twitch.tv/helix/users?login=streamer1&login=gone",
+            &response,
+        );
+        let client = authed_client(mock).await;
+
+        let result = client
+            .get_users_by_logins(&["streamer1", "gone"])
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id, "123");
+    }
+
+    // === get_videos tests ===
+
+    #[tokio::test]
+    async fn get_videos_requests_past_broadcasts() {
+        let response = VideosResponse {
+            data: vec![Video {
+                id: "2001".to_string(),
+                user_id: "123".to_string(),
+                user_login: "streamer1".to_string(),
+                user_name: "Streamer1".to_string(),
+                title: "Speedruns".to_string(),
+                created_at: chrono::Utc::now(),
+                url: "https://www.twitch.tv/videos/2001".to_string(),
+                duration: "2h3m4s".to_string(),
+            }],
+        };
+
+        let mock = MockHttpClient::new().on_get_json(
+            "https://api.twitch.tv/helix/videos?user_id=123&type=archive&first=3",
+            &response,
+        );
+
+        let client = TwitchClient::with_http_client("test_client_id".to_string(), mock);
+        client.set_access_token("test_token".to_string()).await;
+
+        let videos = client.get_videos("123", 3).await.unwrap();
+
+        assert_eq!(videos, response.data);
+    }
+
     // === search_categories tests ===
 
     #[tokio::test]
@@ -554,9 +1106,10 @@ mod tests {
         client.set_access_token("test_token".to_string()).await;
 
         let result = client
-            .get_streams_by_category("509658", None)
+            .get_streams_by_category("509658", &[], None, 1)
             .await
-            .unwrap();
+            .unwrap()
+            .0;
 
         assert_eq!(result.len(), 2);
         assert_eq!(result[0].user_name, "StreamerOne");
@@ -568,7 +1121,7 @@ mod tests {
         let streams = vec![make_stream("1", "StreamerOne")];
 
         let mock = MockHttpClient::new().on_get_json(
-            "https://api.twitch.tv/helix/streams?game_id=509658&first=10&language=en",
+            "https://api.twitch.tv/helix/streams?game_id=509658&first=10&language=en&language=de",
             &make_streams_response(streams, None),
         );
 
@@ -576,9 +1129,10 @@ mod tests {
         client.set_access_token("test_token".to_string()).await;
 
         let result = client
-            .get_streams_by_category("509658", Some("en"))
+            .get_streams_by_category("509658", &["en".to_string(), "de".to_string()], None, 1)
             .await
-            .unwrap();
+            .unwrap()
+            .0;
 
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].user_name, "StreamerOne");
@@ -594,10 +1148,55 @@ mod tests {
         let client = TwitchClient::with_http_client("test_client_id".to_string(), mock);
         client.set_access_token("test_token".to_string()).await;
 
-        let result = client
-            .get_streams_by_category("999999", None)
+        let (result, cursor) = client
+            .get_streams_by_category("999999", &[], None, 1)
             .await
             .unwrap();
         assert!(result.is_empty());
+        assert_eq!(cursor, None);
+    }
+
+    #[tokio::test]
+    async fn get_streams_by_category_pages_after_cursor() {
+        let mock = MockHttpClient::new()
+            .on_get_json(
+                "https://api.twitch.tv/helix/streams?game_id=509658&first=10&after=c1",
+                &make_streams_response(vec![make_stream("11", "Eleven")], Some("c2")),
+            )
+            .on_get_json(
+                "https://api.twitch.tv/helix/streams?game_id=509658&first=10&after=c2",
+                &make_streams_response(vec![make_stream("21", "TwentyOne")], Some("c3")),
+            );
+
+        let client = TwitchClient::with_http_client("test_client_id".to_string(), mock);
+        client.set_access_token("test_token".to_string()).await;
+
+        let (result, cursor) = client
+            .get_streams_by_category("509658", &[], Some("c1"), 2)
+            .await
+            .unwrap();
+
+        let names: Vec<&str> = result.iter().map(|s| s.user_name.as_str()).collect();
+        assert_eq!(names, vec!["Eleven", "TwentyOne"]);
+        assert_eq!(cursor.as_deref(), Some("c3"));
+    }
+
+    #[tokio::test]
+    async fn get_streams_by_category_stops_at_the_last_page() {
+        let mock = MockHttpClient::new().on_get_json(
+            "https://api.twitch.tv/helix/streams?game_id=509658&first=10",
+            &make_streams_response(vec![make_stream("1", "One")], Some("")),
+        );
+
+        let client = TwitchClient::with_http_client("test_client_id".to_string(), mock);
+        client.set_access_token("test_token".to_string()).await;
+
+        let (result, cursor) = client
+            .get_streams_by_category("509658", &[], None, 3)
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(cursor, None);
     }
 }
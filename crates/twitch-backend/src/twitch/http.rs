@@ -2,18 +2,128 @@
 //!
 //! This module provides a trait-based HTTP client that can be easily mocked for testing.
 
+use std::sync::{Arc, RwLock};
+
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use reqwest::header::HeaderMap;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use serde::de::DeserializeOwned;
 
+use crate::proxy;
+
+/// The HTTP methods [`HttpRequest`] can use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+impl HttpMethod {
+    /// Whether sending the request twice has the same effect as once, so a
+    /// failure that may have reached the server can be retried.
+    pub fn is_idempotent(self) -> bool {
+        !matches!(self, HttpMethod::Post)
+    }
+}
+
+impl From<HttpMethod> for reqwest::Method {
+    fn from(method: HttpMethod) -> Self {
+        match method {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Put => reqwest::Method::PUT,
+            HttpMethod::Delete => reqwest::Method::DELETE,
+        }
+    }
+}
+
+/// A request for [`HttpClient::send`].
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: HeaderMap,
+    /// JSON body, sent with `Content-Type: application/json`.
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    /// A request with no headers or body.
+    pub fn new(method: HttpMethod, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: HeaderMap::new(),
+            body: None,
+        }
+    }
+
+    /// Replaces the headers.
+    #[must_use]
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Sets the JSON body.
+    #[must_use]
+    pub fn json(mut self, body: &serde_json::Value) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+}
+
 /// Trait for making HTTP requests
 ///
 /// This abstraction allows easy mocking of HTTP calls in tests.
 #[async_trait]
 pub trait HttpClient: Send + Sync {
+    /// Sends a request and returns the raw response for special handling
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+
     /// Makes a GET request and returns the raw response for special handling
-    async fn get_response(&self, url: &str, headers: &HeaderMap) -> Result<HttpResponse>;
+    async fn get_response(&self, url: &str, headers: &HeaderMap) -> Result<HttpResponse> {
+        self.send(HttpRequest::new(HttpMethod::Get, url).headers(headers.clone()))
+            .await
+    }
+
+    /// Makes a POST request with a JSON body
+    async fn post_json(
+        &self,
+        url: &str,
+        headers: &HeaderMap,
+        body: &serde_json::Value,
+    ) -> Result<HttpResponse> {
+        self.send(
+            HttpRequest::new(HttpMethod::Post, url)
+                .headers(headers.clone())
+                .json(body),
+        )
+        .await
+    }
+
+    /// Makes a PUT request with a JSON body
+    async fn put_json(
+        &self,
+        url: &str,
+        headers: &HeaderMap,
+        body: &serde_json::Value,
+    ) -> Result<HttpResponse> {
+        self.send(
+            HttpRequest::new(HttpMethod::Put, url)
+                .headers(headers.clone())
+                .json(body),
+        )
+        .await
+    }
+
+    /// Makes a DELETE request
+    async fn delete(&self, url: &str, headers: &HeaderMap) -> Result<HttpResponse> {
+        self.send(HttpRequest::new(HttpMethod::Delete, url).headers(headers.clone()))
+            .await
+    }
 
     /// Makes a POST request with form-encoded params and returns the raw response
     async fn post_form_response(
@@ -21,6 +131,9 @@ pub trait HttpClient: Send + Sync {
         url: &str,
         params: Vec<(String, String)>,
     ) -> Result<HttpResponse>;
+
+    /// Downloads a binary body (images), failing on a non-2xx status
+    async fn get_bytes(&self, url: &str) -> Result<Vec<u8>>;
 }
 
 /// Response from an HTTP request
@@ -28,6 +141,30 @@ pub trait HttpClient: Send + Sync {
 pub struct HttpResponse {
     pub status: u16,
     pub body: String,
+    /// The `ETag` header, for conditional requests.
+    pub etag: Option<String>,
+    /// The `Ratelimit-Remaining` / `Ratelimit-Reset` headers, if both were sent.
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// Helix's rate limit bucket as reported on a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Points left in the bucket.
+    pub remaining: u32,
+    /// When the bucket refills, in Unix seconds.
+    pub reset: i64,
+}
+
+impl RateLimit {
+    /// Reads the bucket from response headers.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name)?.to_str().ok();
+        Some(Self {
+            remaining: header("Ratelimit-Remaining")?.parse().ok()?,
+            reset: header("Ratelimit-Reset")?.parse().ok()?,
+        })
+    }
 }
 
 impl HttpResponse {
@@ -46,48 +183,88 @@ impl HttpResponse {
         self.status == 401
     }
 
+    /// Returns true if status is 304, i.e. the `If-None-Match` ETag still matches
+    pub fn is_not_modified(&self) -> bool {
+        self.status == 304
+    }
+
+    /// Returns true if status is 429 Too Many Requests
+    pub fn is_rate_limited(&self) -> bool {
+        self.status == 429
+    }
+
+    /// Returns true if status is in 5xx range, which is worth retrying
+    pub fn is_server_error(&self) -> bool {
+        (500..600).contains(&self.status)
+    }
+
     /// Deserializes the body as JSON
     pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
         serde_json::from_str(&self.body).context("Failed to parse JSON response")
     }
 }
 
-/// Production HTTP client using reqwest
+/// Production HTTP client using reqwest.
+///
+/// Clones share one underlying client, so [`ReqwestClient::set_proxy`]
+/// applies to all of them.
 #[derive(Debug, Clone)]
 pub struct ReqwestClient {
-    inner: reqwest::Client,
+    inner: Arc<RwLock<reqwest::Client>>,
 }
 
 impl ReqwestClient {
-    /// Creates a new reqwest-based HTTP client
-    pub fn new() -> Self {
-        Self {
-            inner: reqwest::Client::new(),
-        }
+    /// Creates a client that goes through `proxy_url` (see [`crate::proxy`]),
+    /// or the environment's proxy when `None`
+    pub fn new(proxy_url: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            inner: Arc::new(RwLock::new(proxy::build_client(proxy_url)?)),
+        })
     }
-}
 
-impl Default for ReqwestClient {
-    fn default() -> Self {
-        Self::new()
+    /// Switches every clone to `proxy_url`. Requests already sent finish on
+    /// the old connection.
+    pub fn set_proxy(&self, proxy_url: Option<&str>) -> Result<()> {
+        let client = proxy::build_client(proxy_url)?;
+        *self.inner.write().unwrap() = client;
+        Ok(())
+    }
+
+    /// The current client (cheap to clone, it's reference counted).
+    fn client(&self) -> reqwest::Client {
+        self.inner.read().unwrap().clone()
     }
 }
 
 #[async_trait]
 impl HttpClient for ReqwestClient {
-    async fn get_response(&self, url: &str, headers: &HeaderMap) -> Result<HttpResponse> {
-        let response = self
-            .inner
-            .get(url)
-            .headers(headers.clone())
-            .send()
-            .await
-            .context("Failed to send request")?;
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut builder = self
+            .client()
+            .request(request.method.into(), &request.url)
+            .headers(request.headers);
+        if let Some(body) = request.body {
+            builder = builder
+                .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+                .body(body);
+        }
+        let response = builder.send().await.context("Failed to send request")?;
 
         let status = response.status().as_u16();
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let rate_limit = RateLimit::from_headers(response.headers());
         let body = response.text().await.unwrap_or_default();
 
-        Ok(HttpResponse { status, body })
+        Ok(HttpResponse {
+            status,
+            body,
+            etag,
+            rate_limit,
+        })
     }
 
     async fn post_form_response(
@@ -96,7 +273,7 @@ impl HttpClient for ReqwestClient {
         params: Vec<(String, String)>,
     ) -> Result<HttpResponse> {
         let response = self
-            .inner
+            .client()
             .post(url)
             .form(&params)
             .send()
@@ -106,31 +283,57 @@ impl HttpClient for ReqwestClient {
         let status = response.status().as_u16();
         let body = response.text().await.unwrap_or_default();
 
-        Ok(HttpResponse { status, body })
+        Ok(HttpResponse {
+            status,
+            body,
+            etag: None,
+            rate_limit: None,
+        })
+    }
+
+    async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self
+            .client()
+            .get(url)
+            .send()
+            .await
+            .context("Failed to send request")?
+            .error_for_status()?;
+        Ok(response
+            .bytes()
+            .await
+            .context("Failed to read response body")?
+            .to_vec())
     }
 }
 
 #[cfg(test)]
 pub mod mock {
     use super::*;
-    use std::collections::HashMap;
+    use std::collections::{HashMap, VecDeque};
     use std::sync::{Arc, RwLock};
 
+    /// Mock responses by request method and URL.
+    type ByRequest<T> = Arc<RwLock<HashMap<(HttpMethod, String), T>>>;
+
     /// Mock HTTP client for testing
     ///
-    /// Allows setting up canned responses for specific URLs.
+    /// Allows setting up canned responses for a method and URL.
     #[derive(Debug, Clone, Default)]
     pub struct MockHttpClient {
-        responses: Arc<RwLock<HashMap<String, MockResponse>>>,
-        responses_post: Arc<RwLock<HashMap<String, MockResponse>>>,
+        responses: ByRequest<MockResponse>,
+        /// Served in order, one per request, before falling back to `responses`.
+        queued: ByRequest<VecDeque<MockResponse>>,
         requests: Arc<RwLock<Vec<RecordedRequest>>>,
     }
 
     /// A recorded HTTP request
     #[derive(Debug, Clone)]
     pub struct RecordedRequest {
+        pub method: HttpMethod,
         pub url: String,
         pub headers: HeaderMap,
+        pub body: Option<String>,
     }
 
     /// A mock response configuration
@@ -138,6 +341,29 @@ pub mod mock {
     struct MockResponse {
         status: u16,
         body: String,
+        /// When set, requests sending this as `If-None-Match` get a 304.
+        etag: Option<String>,
+        rate_limit: Option<RateLimit>,
+    }
+
+    impl MockResponse {
+        fn new(status: u16, body: impl Into<String>) -> Self {
+            Self {
+                status,
+                body: body.into(),
+                etag: None,
+                rate_limit: None,
+            }
+        }
+
+        fn into_response(self) -> HttpResponse {
+            HttpResponse {
+                status: self.status,
+                body: self.body,
+                etag: self.etag,
+                rate_limit: self.rate_limit,
+            }
+        }
     }
 
     impl MockHttpClient {
@@ -146,13 +372,88 @@ pub mod mock {
             Self::default()
         }
 
+        /// Configures a response for requests with `method` to a URL
+        #[must_use]
+        pub fn on(
+            self,
+            method: HttpMethod,
+            url: &str,
+            status: u16,
+            body: impl Into<String>,
+        ) -> Self {
+            self.responses
+                .write()
+                .unwrap()
+                .insert((method, url.to_string()), MockResponse::new(status, body));
+            self
+        }
+
         /// Configures a response for a URL
         pub fn on_get(self, url: &str, status: u16, body: impl Into<String>) -> Self {
+            self.on(HttpMethod::Get, url, status, body)
+        }
+
+        /// Queues a response for the next request to a URL only; later
+        /// requests get the next queued one, then whatever `on_get` set.
+        #[must_use]
+        pub fn on_get_once(self, url: &str, status: u16, body: impl Into<String>) -> Self {
+            self.queue(HttpMethod::Get, url, MockResponse::new(status, body))
+        }
+
+        /// Queues a 429 for the next request to a URL, reporting an empty
+        /// bucket that refills at `reset` (Unix seconds).
+        #[must_use]
+        pub fn on_get_rate_limited_once(self, url: &str, reset: i64) -> Self {
+            self.queue(
+                HttpMethod::Get,
+                url,
+                MockResponse {
+                    rate_limit: Some(RateLimit {
+                        remaining: 0,
+                        reset,
+                    }),
+                    ..MockResponse::new(429, "Too Many Requests")
+                },
+            )
+        }
+
+        /// Queues a response for the next `method` request to a URL only.
+        #[must_use]
+        pub fn on_once(
+            self,
+            method: HttpMethod,
+            url: &str,
+            status: u16,
+            body: impl Into<String>,
+        ) -> Self {
+            self.queue(method, url, MockResponse::new(status, body))
+        }
+
+        fn queue(self, method: HttpMethod, url: &str, response: MockResponse) -> Self {
+            self.queued
+                .write()
+                .unwrap()
+                .entry((method, url.to_string()))
+                .or_default()
+                .push_back(response);
+            self
+        }
+
+        /// Configures a successful JSON response carrying an ETag. Requests
+        /// that send the same ETag in `If-None-Match` get a 304 instead.
+        #[must_use]
+        pub fn on_get_json_with_etag<T: serde::Serialize>(
+            self,
+            url: &str,
+            data: &T,
+            etag: &str,
+        ) -> Self {
+            let body = serde_json::to_string(data).expect("Failed to serialize mock data");
             self.responses.write().unwrap().insert(
-                url.to_string(),
+                (HttpMethod::Get, url.to_string()),
                 MockResponse {
-                    status,
-                    body: body.into(),
+                    etag: Some(etag.to_string()),
+                    ..MockResponse::new(200, body)
                 },
             );
             self
@@ -169,16 +470,9 @@ pub mod mock {
             self.on_get(url, 404, "Not Found")
         }
 
-        /// Configures a response for a POST to a URL
+        /// Configures a response for a POST (form or JSON) to a URL
         pub fn on_post(self, url: &str, status: u16, body: impl Into<String>) -> Self {
-            self.responses_post.write().unwrap().insert(
-                url.to_string(),
-                MockResponse {
-                    status,
-                    body: body.into(),
-                },
-            );
-            self
+            self.on(HttpMethod::Post, url, status, body)
         }
 
         /// Configures a successful JSON response for a POST to a URL
@@ -187,31 +481,57 @@ pub mod mock {
             self.on_post(url, 200, body)
         }
 
-        /// Returns all recorded requests
+        /// Returns all recorded requests (form POSTs aren't recorded)
         pub fn get_requests(&self) -> Vec<RecordedRequest> {
             self.requests.read().unwrap().clone()
         }
+
+        fn response_for(&self, method: HttpMethod, url: &str) -> Result<MockResponse> {
+            let key = (method, url.to_string());
+            let queued = self
+                .queued
+                .write()
+                .unwrap()
+                .get_mut(&key)
+                .and_then(VecDeque::pop_front);
+            if let Some(response) = queued {
+                return Ok(response);
+            }
+            self.responses
+                .read()
+                .unwrap()
+                .get(&key)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No mock response configured for {method:?} {url}"))
+        }
     }
 
     #[async_trait]
     impl HttpClient for MockHttpClient {
-        async fn get_response(&self, url: &str, headers: &HeaderMap) -> Result<HttpResponse> {
-            // Record the request
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+            let response = self.response_for(request.method, &request.url);
+            let if_none_match = request
+                .headers
+                .get(reqwest::header::IF_NONE_MATCH)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
             self.requests.write().unwrap().push(RecordedRequest {
-                url: url.to_string(),
-                headers: headers.clone(),
+                method: request.method,
+                url: request.url,
+                headers: request.headers,
+                body: request.body,
             });
 
-            // Find matching response
-            let responses = self.responses.read().unwrap();
-            let mock_response = responses
-                .get(url)
-                .ok_or_else(|| anyhow::anyhow!("No mock response configured for URL: {}", url))?;
-
-            Ok(HttpResponse {
-                status: mock_response.status,
-                body: mock_response.body.clone(),
-            })
+            let response = response?;
+            if response.etag.is_some() && response.etag == if_none_match {
+                return Ok(HttpResponse {
+                    status: 304,
+                    body: String::new(),
+                    etag: response.etag,
+                    rate_limit: None,
+                });
+            }
+            Ok(response.into_response())
         }
 
         async fn post_form_response(
@@ -219,16 +539,21 @@ pub mod mock {
             url: &str,
             _params: Vec<(String, String)>,
         ) -> Result<HttpResponse> {
-            let responses = self.responses_post.read().unwrap();
-            let mock_response = responses.get(url).ok_or_else(|| {
-                anyhow::anyhow!("No mock POST response configured for URL: {}", url)
-            })?;
-
+            let response = self.response_for(HttpMethod::Post, url)?;
             Ok(HttpResponse {
-                status: mock_response.status,
-                body: mock_response.body.clone(),
+                etag: None,
+                rate_limit: None,
+                ..response.into_response()
             })
         }
+
+        async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+            let response = self.get_response(url, &HeaderMap::new()).await?;
+            if !response.is_success() {
+                anyhow::bail!("HTTP {} for {}", response.status, url);
+            }
+            Ok(response.body.into_bytes())
+        }
     }
 }
 
@@ -277,6 +602,37 @@ mod tests {
             .contains("No mock response configured"));
     }
 
+    #[tokio::test]
+    async fn mock_client_matches_method_and_records_body() {
+        let url = "https://api.example.com/markers";
+        let client = MockHttpClient::new()
+            .on(HttpMethod::Post, url, 200, r#"{"id": 1}"#)
+            .on(HttpMethod::Delete, url, 204, "");
+
+        let created = client
+            .post_json(url, &HeaderMap::new(), &serde_json::json!({"name": "x"}))
+            .await
+            .unwrap();
+        let deleted = client.delete(url, &HeaderMap::new()).await.unwrap();
+
+        assert_eq!(created.body, r#"{"id": 1}"#);
+        assert_eq!(deleted.status, 204);
+        assert!(client.get_response(url, &HeaderMap::new()).await.is_err());
+        let requests = client.get_requests();
+        assert_eq!(requests[0].method, HttpMethod::Post);
+        assert_eq!(requests[0].body.as_deref(), Some(r#"{"name":"x"}"#));
+        assert_eq!(requests[1].method, HttpMethod::Delete);
+        assert_eq!(requests[1].body, None);
+    }
+
+    #[test]
+    fn only_post_is_not_idempotent() {
+        assert!(HttpMethod::Get.is_idempotent());
+        assert!(HttpMethod::Put.is_idempotent());
+        assert!(HttpMethod::Delete.is_idempotent());
+        assert!(!HttpMethod::Post.is_idempotent());
+    }
+
     #[tokio::test]
     async fn mock_client_records_requests() {
         let client = MockHttpClient::new().on_get("https://api.example.com/test", 200, "{}");
@@ -313,33 +669,60 @@ mod tests {
         let response = HttpResponse {
             status: 200,
             body: "{}".to_string(),
+            etag: None,
+            rate_limit: None,
         };
         assert!(response.is_success());
 
         let response = HttpResponse {
             status: 201,
             body: "{}".to_string(),
+            etag: None,
+            rate_limit: None,
         };
         assert!(response.is_success());
 
         let response = HttpResponse {
             status: 404,
             body: "{}".to_string(),
+            etag: None,
+            rate_limit: None,
         };
         assert!(!response.is_success());
 
         let response = HttpResponse {
             status: 500,
             body: "{}".to_string(),
+            etag: None,
+            rate_limit: None,
         };
         assert!(!response.is_success());
     }
 
+    #[test]
+    fn rate_limit_read_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Ratelimit-Remaining", "0".parse().unwrap());
+        headers.insert("Ratelimit-Reset", "1700000000".parse().unwrap());
+        assert_eq!(
+            RateLimit::from_headers(&headers),
+            Some(RateLimit {
+                remaining: 0,
+                reset: 1_700_000_000
+            })
+        );
+
+        headers.remove("Ratelimit-Reset");
+        assert_eq!(RateLimit::from_headers(&headers), None);
+    }
+
     #[test]
     fn http_response_json_parsing() {
         let response = HttpResponse {
             status: 200,
             body: r#"{"name": "test", "value": 42}"#.to_string(),
+            etag: None,
+            rate_limit: None,
         };
 
         let data: TestData = response.json().unwrap();
@@ -1,8 +1,9 @@
 mod client;
 pub mod http;
+mod ratelimit;
 mod types;
 
-pub use client::TwitchClient;
+pub use client::{ConditionalRequestStats, TwitchClient};
 // HttpClient, HttpResponse, ReqwestClient are used internally and in tests
 pub use types::*;
 
@@ -12,6 +13,9 @@ pub enum ApiError {
     /// Token is expired or invalid - can be recovered by refreshing
     #[error("Unauthorized - token expired or invalid")]
     Unauthorized,
+    /// Helix's rate limit bucket is empty and won't refill soon enough to wait for
+    #[error("Rate limited by Twitch, try again in {}s", retry_after.as_secs())]
+    RateLimited { retry_after: std::time::Duration },
     /// Other API or network errors
     #[error("{0}")]
     Other(#[from] anyhow::Error),
@@ -33,6 +37,8 @@ pub fn system_language() -> Option<String> {
 }
 
 /// Calls `f()` and, on [`ApiError::Unauthorized`], calls `refresh()` once and retries.
+/// The app's calls go through `SessionManager::with_auth_retry`, which
+/// supplies the refresh.
 ///
 /// Any other error is returned immediately without retrying.
 /// If the retry also returns `Unauthorized`, that error is surfaced to the caller.
@@ -120,6 +126,35 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn retry_sends_the_refreshed_token() {
+        use crate::twitch::http::mock::MockHttpClient;
+
+        let url = "https://api.twitch.tv/helix/search/categories?query=chat&first=10";
+        let mock = MockHttpClient::new()
+            .on_get_once(url, 401, "Unauthorized")
+            .on_get(url, 200, r#"{"data": []}"#);
+        let client = TwitchClient::with_http_client("client_id".to_string(), mock.clone());
+        client.set_access_token("expired".to_string()).await;
+
+        let result = with_retry(
+            || client.search_categories("chat"),
+            || async {
+                client.set_access_token("fresh".to_string()).await;
+                Ok(())
+            },
+        )
+        .await;
+
+        assert!(result.unwrap().is_empty());
+        let tokens: Vec<String> = mock
+            .get_requests()
+            .iter()
+            .map(|r| r.headers["Authorization"].to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(tokens, vec!["Bearer expired", "Bearer fresh"]);
+    }
+
     #[tokio::test]
     async fn retry_does_not_refresh_on_non_401_error() {
         let refresh_called = Arc::new(AtomicBool::new(false));
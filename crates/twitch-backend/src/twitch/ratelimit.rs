@@ -0,0 +1,111 @@
+//! Rate limiting and retry timing for Helix requests.
+//!
+//! Helix gives each client a bucket of points and reports it on every
+//! response: `Ratelimit-Remaining` points are left, and the bucket refills
+//! at `Ratelimit-Reset` (Unix seconds). Once it is empty, requests get 429
+//! until the reset, so the client waits for it rather than burning retries.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::http::RateLimit;
+
+/// Attempts per request, counting the first, before a 5xx, 429 or network
+/// error is returned to the caller.
+pub const MAX_ATTEMPTS: u32 = 3;
+
+/// Backoff before the first retry; it doubles with each retry after that.
+pub const BASE_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Longest the client will sleep for the bucket to refill. Longer waits
+/// return [`ApiError::RateLimited`](super::ApiError::RateLimited) instead so
+/// a background task isn't stuck on one request.
+pub const MAX_RATE_LIMIT_WAIT_SECS: u64 = 60;
+
+/// The bucket as of the most recent response.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    last: Mutex<Option<RateLimit>>,
+}
+
+impl RateLimiter {
+    /// Remembers the bucket a response reported.
+    pub fn record(&self, limit: RateLimit) {
+        *self.last.lock().unwrap() = Some(limit);
+    }
+
+    /// How long to wait before the next request, if the bucket is empty and
+    /// hasn't reset by `now` (Unix seconds).
+    pub fn wait(&self, now: i64) -> Option<Duration> {
+        let limit = (*self.last.lock().unwrap())?;
+        (limit.remaining == 0 && limit.reset > now)
+            .then(|| Duration::from_secs((limit.reset - now).unsigned_abs()))
+    }
+}
+
+/// Delay before retry number `retry` (1 for the first retry).
+///
+/// Exponential from `base`, with the top half of each step randomised by
+/// `jitter` (0.0..1.0) so clients that failed together don't retry together.
+pub fn backoff_delay(retry: u32, base: Duration, jitter: f64) -> Duration {
+    let step = base * 2u32.saturating_pow(retry.saturating_sub(1));
+    step.mul_f64(0.5 + 0.5 * jitter.clamp(0.0, 1.0))
+}
+
+/// A number in 0.0..1.0 that varies between calls, for [`backoff_delay`].
+///
+/// Retry timing doesn't need a real RNG; the clock's nanoseconds are enough
+/// to spread retries out.
+pub fn jitter() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    f64::from(nanos % 1000) / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_wait_before_any_response() {
+        assert_eq!(RateLimiter::default().wait(100), None);
+    }
+
+    #[test]
+    fn waits_until_reset_when_bucket_is_empty() {
+        let limiter = RateLimiter::default();
+        limiter.record(RateLimit {
+            remaining: 0,
+            reset: 130,
+        });
+        assert_eq!(limiter.wait(100), Some(Duration::from_secs(30)));
+        assert_eq!(limiter.wait(130), None);
+    }
+
+    #[test]
+    fn no_wait_while_points_remain() {
+        let limiter = RateLimiter::default();
+        limiter.record(RateLimit {
+            remaining: 5,
+            reset: 130,
+        });
+        assert_eq!(limiter.wait(100), None);
+    }
+
+    #[test]
+    fn backoff_doubles_within_jitter_bounds() {
+        let base = Duration::from_millis(500);
+        assert_eq!(backoff_delay(1, base, 0.0), Duration::from_millis(250));
+        assert_eq!(backoff_delay(1, base, 1.0), Duration::from_millis(500));
+        assert_eq!(backoff_delay(2, base, 1.0), Duration::from_secs(1));
+        assert_eq!(backoff_delay(3, base, 0.0), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn jitter_is_a_fraction() {
+        let j = jitter();
+        assert!((0.0..1.0).contains(&j));
+    }
+}
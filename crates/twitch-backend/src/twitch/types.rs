@@ -21,6 +21,11 @@ pub struct Stream {
     pub profile_image_url: String,
 }
 
+/// Returns a channel's popout chat URL
+pub fn chat_popout_url(user_login: &str) -> String {
+    format!("https://www.twitch.tv/popout/{user_login}/chat?popout=")
+}
+
 /// Formats a viewer count with k suffix for thousands
 pub fn format_viewer_count(count: u32) -> String {
     if count >= 1000 {
@@ -35,12 +40,57 @@ pub fn format_viewer_count(count: u32) -> String {
     }
 }
 
+/// Box art size used for category art (Twitch's 3:4 portrait ratio).
+pub const BOX_ART_WIDTH: u32 = 144;
+pub const BOX_ART_HEIGHT: u32 = 192;
+
+/// Renders a Twitch CDN image URL at `width` x `height`.
+///
+/// Helix returns stream thumbnails and `/games` box art as templates with
+/// `{width}`/`{height}` placeholders, while `/search/categories` returns box art
+/// already sized (e.g. `.../33214-52x72.jpg`). Both forms are resized; any
+/// other URL is returned unchanged.
+pub fn render_thumbnail(url: &str, width: u32, height: u32) -> String {
+    if url.contains("{width}") || url.contains("{height}") {
+        return url
+            .replace("{width}", &width.to_string())
+            .replace("{height}", &height.to_string());
+    }
+
+    // Pre-sized variant: "<base>-<w>x<h>.<ext>"
+    let Some(dot) = url.rfind('.') else {
+        return url.to_string();
+    };
+    let Some(dash) = url[..dot].rfind('-') else {
+        return url.to_string();
+    };
+    let is_size = url[dash + 1..dot].split_once('x').is_some_and(|(w, h)| {
+        !w.is_empty()
+            && !h.is_empty()
+            && w.bytes().all(|b| b.is_ascii_digit())
+            && h.bytes().all(|b| b.is_ascii_digit())
+    });
+    if is_size {
+        format!("{}-{width}x{height}{}", &url[..dash], &url[dot..])
+    } else {
+        url.to_string()
+    }
+}
+
 impl Stream {
     /// Returns the Twitch channel URL
     pub fn channel_url(&self) -> String {
         format!("https://twitch.tv/{}", self.user_login)
     }
 
+    /// Returns Twitch's popout player URL, which opens just the video
+    pub fn popout_url(&self) -> String {
+        format!(
+            "https://player.twitch.tv/?channel={}&parent=twitch.tv&player=popout",
+            self.user_login
+        )
+    }
+
     /// Returns the duration since the stream started
     pub fn duration(&self) -> chrono::Duration {
         Utc::now().signed_duration_since(self.started_at)
@@ -63,6 +113,11 @@ impl Stream {
     pub fn format_viewer_count(&self) -> String {
         format_viewer_count(self.viewer_count)
     }
+
+    /// Returns the stream preview thumbnail at the given size
+    pub fn thumbnail(&self, width: u32, height: u32) -> String {
+        render_thumbnail(&self.thumbnail_url, width, height)
+    }
 }
 
 /// Represents a scheduled broadcast
@@ -83,9 +138,18 @@ pub struct ScheduledStream {
     pub is_recurring: bool,
     #[serde(default)]
     pub is_inferred: bool,
+    /// For inferred schedules, how often the streamer has streamed at this
+    /// time (0.0-1.0); `None` for schedules from Twitch.
+    #[serde(default)]
+    pub confidence: Option<f32>,
 }
 
 impl ScheduledStream {
+    /// Returns the Twitch channel URL
+    pub fn channel_url(&self) -> String {
+        format!("https://twitch.tv/{}", self.broadcaster_login)
+    }
+
     /// Returns a human-readable start time
     pub fn format_start_time(&self) -> String {
         let now = Local::now();
@@ -202,6 +266,68 @@ pub struct UsersResponse {
     pub data: Vec<User>,
 }
 
+/// A past broadcast (from the /videos endpoint)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Video {
+    pub id: String,
+    pub user_id: String,
+    pub user_login: String,
+    pub user_name: String,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+    pub url: String,
+    /// Twitch's duration string, e.g. `3h8m33s`
+    pub duration: String,
+}
+
+impl Video {
+    /// The duration as `3h 8m`, or `45m` under an hour. Unparseable durations
+    /// are returned as Twitch sent them.
+    pub fn format_duration(&self) -> String {
+        let mut hours = 0;
+        let mut minutes = 0;
+        let mut number = String::new();
+        for c in self.duration.chars() {
+            match c {
+                '0'..='9' => number.push(c),
+                'h' | 'm' | 's' => {
+                    let Ok(value) = number.parse::<u64>() else {
+                        return self.duration.clone();
+                    };
+                    match c {
+                        'h' => hours = value,
+                        'm' => minutes = value,
+                        _ => {}
+                    }
+                    number.clear();
+                }
+                _ => return self.duration.clone(),
+            }
+        }
+        if !number.is_empty() {
+            return self.duration.clone();
+        }
+        if hours > 0 {
+            format!("{hours}h {minutes}m")
+        } else {
+            format!("{minutes}m")
+        }
+    }
+
+    /// Whether this is the archive of `stream`. Twitch creates the archive
+    /// as the broadcast starts, so allow a few minutes either side.
+    pub fn is_archive_of(&self, stream: &Stream) -> bool {
+        self.user_id == stream.user_id
+            && (self.created_at - stream.started_at).num_minutes().abs() <= 5
+    }
+}
+
+/// Videos response from Helix API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideosResponse {
+    pub data: Vec<Video>,
+}
+
 /// Represents a Twitch category/game
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Category {
@@ -211,6 +337,13 @@ pub struct Category {
     pub box_art_url: String,
 }
 
+impl Category {
+    /// Returns the box art at the given size
+    pub fn box_art(&self, width: u32, height: u32) -> String {
+        render_thumbnail(&self.box_art_url, width, height)
+    }
+}
+
 /// Response from search categories endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchCategoriesResponse {
@@ -228,6 +361,46 @@ mod tests {
     use super::*;
     use chrono::{Duration, TimeZone};
 
+    fn video_with_duration(duration: &str) -> Video {
+        Video {
+            id: "1".to_string(),
+            user_id: "456".to_string(),
+            user_login: "testuser".to_string(),
+            user_name: "TestUser".to_string(),
+            title: "Yesterday's stream".to_string(),
+            created_at: Utc::now(),
+            url: "https://www.twitch.tv/videos/1".to_string(),
+            duration: duration.to_string(),
+        }
+    }
+
+    #[test]
+    fn video_duration_formats_hours_and_minutes() {
+        assert_eq!(video_with_duration("3h8m33s").format_duration(), "3h 8m");
+        assert_eq!(video_with_duration("45m2s").format_duration(), "45m");
+        assert_eq!(video_with_duration("12s").format_duration(), "0m");
+    }
+
+    #[test]
+    fn video_is_archive_of_the_stream_it_started_with() {
+        let started_at = Utc.with_ymd_and_hms(2024, 1, 15, 18, 0, 0).unwrap();
+        let stream = stream_started_at(started_at);
+        let mut video = video_with_duration("3h8m33s");
+
+        video.created_at = started_at + Duration::seconds(20);
+        assert!(video.is_archive_of(&stream));
+
+        // Yesterday's broadcast
+        video.created_at = started_at - Duration::days(1);
+        assert!(!video.is_archive_of(&stream));
+    }
+
+    #[test]
+    fn unparseable_video_duration_is_kept() {
+        assert_eq!(video_with_duration("soon").format_duration(), "soon");
+        assert_eq!(video_with_duration("3h8").format_duration(), "3h8");
+    }
+
     /// Helper to create a test stream with specified viewer count
     fn stream_with_viewers(viewer_count: u32) -> Stream {
         Stream {
@@ -278,9 +451,59 @@ mod tests {
             category_id: Some("123".to_string()),
             is_recurring: false,
             is_inferred: false,
+            confidence: None,
         }
     }
 
+    // === render_thumbnail tests ===
+
+    #[test]
+    fn render_thumbnail_fills_template_placeholders() {
+        let url = "https://static-cdn.jtvnw.net/previews-ttv/live_user_ninja-{width}x{height}.jpg";
+        assert_eq!(
+            render_thumbnail(url, 320, 180),
+            "https://static-cdn.jtvnw.net/previews-ttv/live_user_ninja-320x180.jpg"
+        );
+    }
+
+    #[test]
+    fn render_thumbnail_resizes_presized_box_art() {
+        let url = "https://static-cdn.jtvnw.net/ttv-boxart/33214-52x72.jpg";
+        assert_eq!(
+            render_thumbnail(url, BOX_ART_WIDTH, BOX_ART_HEIGHT),
+            "https://static-cdn.jtvnw.net/ttv-boxart/33214-144x192.jpg"
+        );
+    }
+
+    #[test]
+    fn render_thumbnail_resizes_presized_art_with_dashes_in_name() {
+        let url = "https://static-cdn.jtvnw.net/ttv-boxart/Just%20Chatting-some-name-52x72.png";
+        assert_eq!(
+            render_thumbnail(url, 10, 20),
+            "https://static-cdn.jtvnw.net/ttv-boxart/Just%20Chatting-some-name-10x20.png"
+        );
+    }
+
+    #[test]
+    fn render_thumbnail_leaves_other_urls_unchanged() {
+        for url in [
+            "",
+            "https://example.com/box.jpg",
+            "https://example.com/my-game.jpg",
+            "https://example.com/a-x1.jpg",
+            "no-extension-52x72",
+        ] {
+            assert_eq!(render_thumbnail(url, 1, 1), url);
+        }
+    }
+
+    #[test]
+    fn stream_thumbnail_uses_template() {
+        let mut stream = stream_with_viewers(1);
+        stream.thumbnail_url = "https://cdn/live-{width}x{height}.jpg".to_string();
+        assert_eq!(stream.thumbnail(640, 360), "https://cdn/live-640x360.jpg");
+    }
+
     // === format_viewer_count tests ===
 
     #[test]
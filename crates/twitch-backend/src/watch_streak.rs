@@ -0,0 +1,142 @@
+//! Local approximation of Twitch watch streaks.
+//!
+//! Twitch counts consecutive streams a viewer watched on a channel. We can't
+//! read that from the API, so it is reconstructed from what the app already
+//! records: stream start times (`stream_history`) and when the user opened a
+//! channel from the app (`watch_history`). A previous stream counts as watched
+//! if the channel was opened between its start and the next stream's start.
+
+use chrono::{DateTime, Utc};
+
+/// How long a Favourite must be live without being opened before the
+/// streak reminder fires, so the go-live notification gets a chance first.
+pub const STREAK_REMINDER_DELAY_MIN: i64 = 30;
+
+/// How far back stream and watch history are read when counting a streak.
+pub const STREAK_LOOKBACK_DAYS: i64 = 60;
+
+/// Counts consecutive watched streams, newest first.
+///
+/// `previous_starts` are start times of earlier streams (any order), all before
+/// `current_start`. Each stream's window runs until the next stream starts; the
+/// newest previous stream's window ends at `current_start`.
+pub fn watch_streak(
+    previous_starts: &[DateTime<Utc>],
+    current_start: DateTime<Utc>,
+    watches: &[DateTime<Utc>],
+) -> u32 {
+    let mut starts: Vec<DateTime<Utc>> = previous_starts
+        .iter()
+        .copied()
+        .filter(|&s| s < current_start)
+        .collect();
+    starts.sort_unstable();
+    starts.dedup();
+
+    let mut streak = 0;
+    let mut window_end = current_start;
+    for &start in starts.iter().rev() {
+        if !watches.iter().any(|&w| w >= start && w < window_end) {
+            break;
+        }
+        streak += 1;
+        window_end = start;
+    }
+    streak
+}
+
+/// Returns the streak that will lapse if the current stream isn't watched.
+///
+/// `None` when there is no streak to lose or the channel has already been
+/// opened since the current stream started.
+pub fn streak_at_risk(
+    previous_starts: &[DateTime<Utc>],
+    current_start: DateTime<Utc>,
+    watches: &[DateTime<Utc>],
+) -> Option<u32> {
+    if watches.iter().any(|&w| w >= current_start) {
+        return None;
+    }
+    match watch_streak(previous_starts, current_start, watches) {
+        0 => None,
+        n => Some(n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn t(day: i64, hour: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap()
+            + Duration::days(day)
+            + Duration::hours(hour)
+    }
+
+    // === watch_streak tests ===
+
+    #[test]
+    fn no_history_is_no_streak() {
+        assert_eq!(watch_streak(&[], t(5, 20), &[]), 0);
+    }
+
+    #[test]
+    fn counts_consecutive_watched_streams() {
+        let starts = [t(1, 20), t(2, 20), t(3, 20)];
+        let watches = [t(1, 21), t(2, 20), t(3, 22)];
+        assert_eq!(watch_streak(&starts, t(4, 20), &watches), 3);
+    }
+
+    #[test]
+    fn missed_stream_breaks_streak() {
+        let starts = [t(1, 20), t(2, 20), t(3, 20)];
+        // Day 2 missed: only day 3 counts
+        let watches = [t(1, 21), t(3, 21)];
+        assert_eq!(watch_streak(&starts, t(4, 20), &watches), 1);
+    }
+
+    #[test]
+    fn missing_the_latest_stream_means_no_streak() {
+        let starts = [t(1, 20), t(2, 20)];
+        let watches = [t(1, 21)];
+        assert_eq!(watch_streak(&starts, t(3, 20), &watches), 0);
+    }
+
+    #[test]
+    fn watch_before_next_stream_counts_for_previous_one() {
+        // Opened the channel the morning after, before the next stream began
+        let starts = [t(1, 20)];
+        let watches = [t(2, 9)];
+        assert_eq!(watch_streak(&starts, t(2, 20), &watches), 1);
+    }
+
+    #[test]
+    fn duplicate_and_unsorted_starts_are_handled() {
+        let starts = [t(2, 20), t(1, 20), t(2, 20)];
+        let watches = [t(1, 21), t(2, 21)];
+        assert_eq!(watch_streak(&starts, t(3, 20), &watches), 2);
+    }
+
+    // === streak_at_risk tests ===
+
+    #[test]
+    fn at_risk_when_current_stream_not_watched() {
+        let starts = [t(1, 20), t(2, 20)];
+        let watches = [t(1, 21), t(2, 21)];
+        assert_eq!(streak_at_risk(&starts, t(3, 20), &watches), Some(2));
+    }
+
+    #[test]
+    fn not_at_risk_once_current_stream_watched() {
+        let starts = [t(1, 20), t(2, 20)];
+        let watches = [t(1, 21), t(2, 21), t(3, 20)];
+        assert_eq!(streak_at_risk(&starts, t(3, 20), &watches), None);
+    }
+
+    #[test]
+    fn not_at_risk_without_a_streak() {
+        let starts = [t(1, 20)];
+        assert_eq!(streak_at_risk(&starts, t(2, 20), &[]), None);
+    }
+}
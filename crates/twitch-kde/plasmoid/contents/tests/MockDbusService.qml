@@ -14,6 +14,7 @@ QtObject {
     function login() { calls.push("login") }
     function logout() { calls.push("logout") }
     function openStream(userLogin) { calls.push("openStream:" + userLogin) }
+    function openChat(userLogin) { calls.push("openChat:" + userLogin) }
     function openSettings() { calls.push("openSettings") }
     function openStreamerSettings(userLogin, displayName) { calls.push("openStreamerSettings:" + userLogin + ":" + displayName) }
     function cancelLogin() { calls.push("cancelLogin") }
@@ -19,6 +19,7 @@ Item {
         function init() {
             compact.daemonRunning = false
             compact.authenticated = false
+            compact.unread = false
         }
 
         function test_icon_always_visible() {
@@ -49,5 +50,31 @@ Item {
             var icon = findChild(compact, "twitchIcon")
             compare(icon.opacity, 1.0)
         }
+
+        function test_unread_dot_hidden_by_default() {
+            compact.daemonRunning = true
+            compact.authenticated = true
+            wait(10)
+            var dot = findChild(compact, "unreadDot")
+            verify(dot, "unreadDot should exist")
+            verify(!dot.visible, "dot should be hidden with nothing unread")
+        }
+
+        function test_unread_dot_shown_when_unread() {
+            compact.daemonRunning = true
+            compact.authenticated = true
+            compact.unread = true
+            wait(10)
+            var dot = findChild(compact, "unreadDot")
+            verify(dot.visible, "dot should show while something is unread")
+        }
+
+        function test_unread_dot_hidden_when_not_authenticated() {
+            compact.daemonRunning = true
+            compact.unread = true
+            wait(10)
+            var dot = findChild(compact, "unreadDot")
+            verify(!dot.visible, "dot should be hidden when logged out")
+        }
     }
 }
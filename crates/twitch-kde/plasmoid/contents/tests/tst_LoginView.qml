@@ -39,6 +39,7 @@ Item {
             loginView.loginState = "Idle"
             loginView.userCode = ""
             loginView.verificationUri = ""
+            loginView.expiresAt = 0
             loginSpy.clear()
             cancelSpy.clear()
             copySpy.clear()
@@ -66,6 +67,17 @@ Item {
             verify(copyBtn.visible, "copy button should be visible")
         }
 
+        function test_expiry_countdown_shown_when_pending() {
+            loginView.loginState = "PendingCode"
+            loginView.userCode = "ABCD-1234"
+            loginView.expiresAt = Date.now() / 1000 + 125
+            wait(10)
+            var expiry = findChild(loginView, "expiryLabel")
+            verify(expiry, "expiryLabel should exist")
+            verify(expiry.visible, "expiry label should be visible")
+            verify(expiry.text.indexOf("Code expires in 2:0") === 0, expiry.text)
+        }
+
         function test_copy_button_emits_code() {
             loginView.loginState = "PendingCode"
             loginView.userCode = "ABCD-1234"
@@ -6,6 +6,7 @@ Item {
 
     property bool daemonRunning: false
     property bool authenticated: false
+    property bool unread: false
 
     implicitWidth: 24
     implicitHeight: 24
@@ -20,4 +21,16 @@ Item {
         isMask: true
         opacity: (root.daemonRunning && root.authenticated) ? 1.0 : 0.4
     }
+
+    // A Favourite went live since the popup was last opened
+    Rectangle {
+        objectName: "unreadDot"
+        anchors.top: twitchIcon.top
+        anchors.right: twitchIcon.right
+        width: 9
+        height: 9
+        radius: width / 2
+        color: "#eb0400"
+        visible: root.daemonRunning && root.authenticated && root.unread
+    }
 }
@@ -19,8 +19,10 @@ Controls.ScrollView {
     signal cancelLoginRequested()
     signal copyCodeRequested(string code)
     signal logoutRequested()
+    signal switchUserRequested()
     signal settingsRequested()
     signal openStream(string login)
+    signal openChat(string login)
 
 
     Layout.preferredWidth: 320
@@ -51,6 +53,7 @@ Controls.ScrollView {
             loginState: scrollView.plasmoidState.login_state.type
             userCode: scrollView.plasmoidState.login_state.user_code || ""
             verificationUri: scrollView.plasmoidState.login_state.verification_uri || ""
+            expiresAt: scrollView.plasmoidState.login_state.expires_at || 0
             onLoginRequested: scrollView.loginRequested()
             onCancelLoginRequested: scrollView.cancelLoginRequested()
             onCopyCodeRequested: (code) => scrollView.copyCodeRequested(code)
@@ -85,6 +88,7 @@ Controls.ScrollView {
                     isFavourite: modelData.is_favourite
                     isHot: modelData.is_hot
                     onStreamClicked: (login) => scrollView.openStream(login)
+                    onChatClicked: (login) => scrollView.openChat(login)
                 }
             }
 
@@ -110,6 +114,7 @@ Controls.ScrollView {
                         isFavourite: modelData.is_favourite
                         isHot: modelData.is_hot
                         onStreamClicked: (login) => scrollView.openStream(login)
+                        onChatClicked: (login) => scrollView.openChat(login)
                     }
                 }
             }
@@ -206,6 +211,12 @@ Controls.ScrollView {
 
                 Item { Layout.fillWidth: true }
 
+                Controls.Button {
+                    objectName: "switchUserButton"
+                    text: "Switch User"
+                    onClicked: scrollView.switchUserRequested()
+                }
+
                 Controls.Button {
                     objectName: "logoutButton"
                     text: "Logout"
@@ -8,6 +8,9 @@ ColumnLayout {
     property string loginState: "Idle"
     property string userCode: ""
     property string verificationUri: ""
+    // Unix seconds; the daemon swaps in a new code when this passes
+    property real expiresAt: 0
+    property real now: Date.now() / 1000
 
     signal loginRequested()
     signal cancelLoginRequested()
@@ -15,6 +18,22 @@ ColumnLayout {
 
     spacing: 8
 
+    function formatRemaining(seconds) {
+        if (seconds <= 0)
+            return "Code expired \u00B7 Requesting a new one..."
+        var mins = Math.floor(seconds / 60)
+        var secs = Math.floor(seconds % 60)
+        return "Code expires in " + mins + ":" + (secs < 10 ? "0" : "") + secs
+    }
+
+    Timer {
+        interval: 1000
+        repeat: true
+        running: root.loginState === "PendingCode"
+        triggeredOnStart: true
+        onTriggered: root.now = Date.now() / 1000
+    }
+
     // Idle state: Login button
     Controls.Button {
         id: loginButton
@@ -66,6 +85,14 @@ ColumnLayout {
             opacity: 0.7
             Layout.alignment: Qt.AlignHCenter
         }
+
+        Controls.Label {
+            objectName: "expiryLabel"
+            visible: root.expiresAt > 0
+            text: root.formatRemaining(root.expiresAt - root.now)
+            opacity: 0.7
+            Layout.alignment: Qt.AlignHCenter
+        }
     }
 
     // AwaitingConfirmation state: busy indicator
@@ -1,4 +1,5 @@
 import QtQuick
+import QtQuick.Controls as Controls
 
 StreamRow {
     id: root
@@ -13,6 +14,7 @@ StreamRow {
     // isFavourite: inherited
 
     signal streamClicked(string userLogin)
+    signal chatClicked(string userLogin)
 
     login: root.userLogin
     displayName: root.userName
@@ -21,4 +23,24 @@ StreamRow {
     bottomRightText: root.durationFormatted
 
     onClicked_: (login) => root.streamClicked(login)
+
+    TapHandler {
+        acceptedButtons: Qt.RightButton
+        onTapped: contextMenu.popup()
+    }
+
+    Controls.Menu {
+        id: contextMenu
+        objectName: "contextMenu"
+
+        Controls.MenuItem {
+            text: "Open stream"
+            onTriggered: root.streamClicked(root.userLogin)
+        }
+        Controls.MenuItem {
+            objectName: "openChatItem"
+            text: "Open chat"
+            onTriggered: root.chatClicked(root.userLogin)
+        }
+    }
 }
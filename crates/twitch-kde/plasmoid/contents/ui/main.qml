@@ -23,7 +23,8 @@ PlasmoidItem {
         "login_state": { "type": "Idle" },
         "live": { "visible": [], "overflow": [] },
         "categories": [],
-        "schedule": { "lookahead_hours": 24, "loaded": true, "visible": [], "overflow": [] }
+        "schedule": { "lookahead_hours": 24, "loaded": true, "visible": [], "overflow": [] },
+        "unread": false
     })
 
     // D-Bus communication via qdbus6 through the executable DataEngine
@@ -72,6 +73,13 @@ PlasmoidItem {
         )
     }
 
+    // Let the daemon fetch live streams now rather than at its next poll,
+    // and clear the unread dot
+    onExpandedChanged: {
+        if (expanded)
+            root.dbusCall("MenuOpened")
+    }
+
     Timer {
         interval: 1000
         running: true
@@ -82,6 +90,7 @@ PlasmoidItem {
     compactRepresentation: CompactRepresentation {
         daemonRunning: root.daemonRunning
         authenticated: root.state.authenticated
+        unread: root.state.unread
     }
 
     fullRepresentation: FullRepresentation {
@@ -91,8 +100,11 @@ PlasmoidItem {
         onCancelLoginRequested: root.dbusCall("CancelLogin")
         onCopyCodeRequested: (code) => Qt.copyToClipboard(code)
         onLogoutRequested: root.dbusCall("Logout")
+        onSwitchUserRequested: root.dbusCall("SwitchUser")
         onSettingsRequested: root.dbusCall("OpenSettings")
         onOpenStream: (login) =>
             root.dbusCallArgs("OpenStream", "'" + login + "'")
+        onOpenChat: (login) =>
+            root.dbusCallArgs("OpenChat", "'" + login + "'")
     }
 }
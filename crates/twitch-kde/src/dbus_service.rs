@@ -5,7 +5,10 @@ use tokio::sync::{mpsc, watch};
 use zbus::{interface, object_server::SignalContext};
 
 use twitch_backend::{
+    config::Config,
     handle::{LoginProgress, RawDisplayData},
+    notify::OpenTarget,
+    twitch::chat_popout_url,
     AuthCommand,
 };
 
@@ -29,9 +32,13 @@ pub struct DbusService {
     pub state: Arc<Mutex<PlasmoidState>>,
     pub auth_cmd_tx: mpsc::UnboundedSender<AuthCommand>,
     pub window_tx: mpsc::Sender<WindowRequest>,
-    /// Seam for testing; production uses `open::that`.
-    pub open_url: Arc<dyn Fn(&str) + Send + Sync>,
+    /// Seam for testing; production uses `OpenTarget::launch`.
+    pub open: Arc<dyn Fn(OpenTarget) + Send + Sync>,
+    /// Current config, for `open_with`.
+    pub config: Arc<dyn Fn() -> Config + Send + Sync>,
     pub cancel_login_tx: mpsc::Sender<()>,
+    /// Called when the plasmoid expands; production refreshes live streams.
+    pub menu_opened: Arc<dyn Fn() + Send + Sync>,
 }
 
 #[interface(interface = "info.sdufresne.TwitchTray1")]
@@ -52,10 +59,18 @@ impl DbusService {
         self.do_logout();
     }
 
+    async fn switch_user(&self) {
+        self.do_switch_user();
+    }
+
     async fn open_stream(&self, user_login: String) {
         self.do_open_stream(&user_login);
     }
 
+    async fn open_chat(&self, user_login: String) {
+        self.do_open_chat(&user_login);
+    }
+
     async fn open_settings(&self) {
         self.do_open_settings().await;
     }
@@ -69,6 +84,10 @@ impl DbusService {
         self.do_cancel_login().await;
     }
 
+    async fn menu_opened(&self) {
+        self.do_menu_opened();
+    }
+
     /// Emitted whenever `State` changes.
     /// Named `emit_state_changed` in Rust to avoid colliding with the
     /// property-change helper `state_changed` auto-generated by the macro.
@@ -86,8 +105,16 @@ impl DbusService {
         let _ = self.auth_cmd_tx.send(AuthCommand::Logout);
     }
 
+    pub fn do_switch_user(&self) {
+        let _ = self.auth_cmd_tx.send(AuthCommand::SwitchUser);
+    }
+
     pub fn do_open_stream(&self, user_login: &str) {
-        (self.open_url)(&format!("https://twitch.tv/{user_login}"));
+        (self.open)(OpenTarget::for_watching(&(self.config)(), user_login));
+    }
+
+    pub fn do_open_chat(&self, user_login: &str) {
+        (self.open)(OpenTarget::Url(chat_popout_url(user_login)));
     }
 
     pub async fn do_open_settings(&self) {
@@ -107,6 +134,10 @@ impl DbusService {
     pub async fn do_cancel_login(&self) {
         let _ = self.cancel_login_tx.send(()).await;
     }
+
+    pub fn do_menu_opened(&self) {
+        (self.menu_opened)();
+    }
 }
 
 /// Spawns a background task that watches `display_rx` and `login_progress_rx`,
@@ -161,6 +192,7 @@ mod tests {
                 visible: vec![],
                 overflow: vec![],
             },
+            unread: false,
         }
     }
 
@@ -170,6 +202,7 @@ mod tests {
         window_rx: mpsc::Receiver<WindowRequest>,
         cancel_rx: mpsc::Receiver<()>,
         url_rx: std::sync::mpsc::Receiver<String>,
+        menu_opened_rx: std::sync::mpsc::Receiver<()>,
     }
 
     fn make_test_service() -> TestService {
@@ -177,15 +210,20 @@ mod tests {
         let (window_tx, window_rx) = mpsc::channel(4);
         let (cancel_tx, cancel_rx) = mpsc::channel(1);
         let (url_tx, url_rx) = std::sync::mpsc::channel();
+        let (menu_opened_tx, menu_opened_rx) = std::sync::mpsc::channel();
 
         let service = DbusService {
             state: Arc::new(Mutex::new(default_state())),
             auth_cmd_tx: auth_tx,
             window_tx,
-            open_url: Arc::new(move |url: &str| {
-                let _ = url_tx.send(url.to_string());
+            open: Arc::new(move |target: OpenTarget| {
+                let _ = url_tx.send(target.to_string());
             }),
+            config: Arc::new(Config::default),
             cancel_login_tx: cancel_tx,
+            menu_opened: Arc::new(move || {
+                let _ = menu_opened_tx.send(());
+            }),
         };
 
         TestService {
@@ -194,6 +232,7 @@ mod tests {
             window_rx,
             cancel_rx,
             url_rx,
+            menu_opened_rx,
         }
     }
 
@@ -211,6 +250,16 @@ mod tests {
         assert!(matches!(t.auth_rx.try_recv().unwrap(), AuthCommand::Logout));
     }
 
+    #[tokio::test]
+    async fn switch_user_method_sends_auth_command_switch_user() {
+        let mut t = make_test_service();
+        t.service.do_switch_user();
+        assert!(matches!(
+            t.auth_rx.try_recv().unwrap(),
+            AuthCommand::SwitchUser
+        ));
+    }
+
     #[tokio::test]
     async fn open_stream_opens_correct_url() {
         let t = make_test_service();
@@ -219,6 +268,26 @@ mod tests {
         assert_eq!(url, "https://twitch.tv/ninja");
     }
 
+    #[tokio::test]
+    async fn open_stream_uses_open_with() {
+        let mut t = make_test_service();
+        t.service.config = Arc::new(|| Config {
+            open_with: twitch_backend::config::OpenWith::Streamlink,
+            ..Config::default()
+        });
+        t.service.do_open_stream("ninja");
+        let command = t.url_rx.recv().unwrap();
+        assert_eq!(command, "streamlink twitch.tv/ninja best");
+    }
+
+    #[tokio::test]
+    async fn open_chat_opens_popout_chat() {
+        let t = make_test_service();
+        t.service.do_open_chat("ninja");
+        let url = t.url_rx.recv().unwrap();
+        assert_eq!(url, "https://www.twitch.tv/popout/ninja/chat?popout=");
+    }
+
     #[tokio::test]
     async fn open_settings_sends_window_request() {
         let mut t = make_test_service();
@@ -249,4 +318,11 @@ mod tests {
         t.service.do_cancel_login().await;
         assert!(t.cancel_rx.try_recv().is_ok());
     }
+
+    #[tokio::test]
+    async fn menu_opened_calls_refresh_hook() {
+        let t = make_test_service();
+        t.service.do_menu_opened();
+        assert!(t.menu_opened_rx.try_recv().is_ok());
+    }
 }
@@ -7,6 +7,8 @@ pub struct PlasmoidState {
     pub live: LiveSectionDto,
     pub categories: Vec<CategorySectionDto>,
     pub schedule: ScheduleSectionDto,
+    /// A Favourite went live since the plasmoid was last expanded.
+    pub unread: bool,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
@@ -16,6 +18,8 @@ pub enum LoginStateDto {
     PendingCode {
         user_code: String,
         verification_uri: String,
+        /// Unix timestamp (seconds) when the code expires; the QML counts down to it.
+        expires_at: i64,
     },
     AwaitingConfirmation,
 }
@@ -107,6 +111,7 @@ mod tests {
             live: empty_live(),
             categories: vec![],
             schedule: empty_schedule(),
+            unread: false,
         };
         let json = serde_json::to_string(&state).unwrap();
         let parsed: PlasmoidState = serde_json::from_str(&json).unwrap();
@@ -153,6 +158,7 @@ mod tests {
         let state = LoginStateDto::PendingCode {
             user_code: "ABCD-1234".to_string(),
             verification_uri: "https://twitch.tv/activate".to_string(),
+            expires_at: 1_750_000_000,
         };
         let json = serde_json::to_string(&state).unwrap();
         let parsed: LoginStateDto = serde_json::from_str(&json).unwrap();
@@ -8,17 +8,22 @@ use tauri::Manager;
 use tokio::sync::mpsc;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-use twitch_backend::{handle::RawDisplayData, AuthCommand, BackendEvent};
+use twitch_backend::{
+    handle::RawDisplayData, log_scrub::RedactingMakeWriter, AuthCommand, BackendEvent,
+};
 use twitch_kde::{
     dbus_service::{spawn_state_watcher, DbusService, WindowRequest, OBJECT_PATH},
     plasmoid_state::compute_plasmoid_state,
 };
+use twitch_settings_tauri::auth_state::spawn_auth_state_emitter;
 use twitch_settings_tauri::window::{open_settings_window, open_streamer_settings_window};
 
 fn main() {
     tracing_subscriber::registry()
         .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
-        .with(tracing_subscriber::fmt::layer())
+        .with(
+            tracing_subscriber::fmt::layer().with_writer(RedactingMakeWriter::new(std::io::stdout)),
+        )
         .init();
 
     tracing::info!("Starting Twitch KDE daemon");
@@ -33,6 +38,23 @@ fn main() {
             twitch_settings_tauri::commands::is_debug_build,
             twitch_settings_tauri::commands::get_debug_schedule_data,
             twitch_settings_tauri::commands::get_debug_hotness_data,
+            twitch_settings_tauri::commands::create_diagnostics_bundle,
+            twitch_settings_tauri::commands::export_data,
+            twitch_settings_tauri::commands::import_data,
+            twitch_settings_tauri::commands::get_live_at,
+            twitch_settings_tauri::commands::get_history_summary,
+            twitch_settings_tauri::commands::get_streamer_history,
+            twitch_settings_tauri::commands::get_followed_category_suggestions,
+            twitch_settings_tauri::commands::accept_category_suggestion,
+            twitch_settings_tauri::commands::dismiss_category_suggestion,
+            twitch_settings_tauri::commands::set_category_notifications,
+            twitch_settings_tauri::commands::set_importance_bulk,
+            twitch_settings_tauri::commands::import_streamer_list,
+            twitch_settings_tauri::commands::undo_settings_change,
+            twitch_settings_tauri::commands::get_autostart,
+            twitch_settings_tauri::commands::set_autostart,
+            twitch_settings_tauri::commands::get_about_info,
+            twitch_settings_tauri::commands::get_auth_state,
         ])
         .setup(|app| {
             // Enter the Tauri-managed tokio runtime so tokio::spawn works
@@ -41,7 +63,16 @@ fn main() {
 
             let handle = twitch_backend::start().expect("Failed to start backend");
 
+            // Keep the settings window's auth banner current
+            spawn_auth_state_emitter(
+                app.handle().clone(),
+                Arc::clone(&handle.services),
+                handle.login_progress_rx.clone(),
+                handle.event_tx.subscribe(),
+            );
+
             // Store services for Tauri settings commands
+            let services = Arc::clone(&handle.services);
             app.manage(handle.services);
 
             let (window_tx, mut window_rx) = mpsc::channel::<WindowRequest>(4);
@@ -58,10 +89,23 @@ fn main() {
                 state: Arc::clone(&service_state),
                 auth_cmd_tx: handle.auth_cmd_tx.clone(),
                 window_tx: window_tx.clone(),
-                open_url: Arc::new(|url| {
-                    let _ = open::that(url);
+                open: Arc::new(|target| {
+                    if let Err(e) = target.launch() {
+                        tracing::error!("Failed to open {}: {}", target, e);
+                    }
                 }),
+                config: {
+                    let services = Arc::clone(&services);
+                    Arc::new(move || services.get_config())
+                },
                 cancel_login_tx: cancel_tx,
+                menu_opened: Arc::new(move || {
+                    let services = Arc::clone(&services);
+                    tauri::async_runtime::spawn(async move {
+                        services.mark_menu_seen().await;
+                        services.refresh_on_menu_open().await;
+                    });
+                }),
             };
 
             // Connect to the session D-Bus and register the service
@@ -96,12 +140,11 @@ fn main() {
                 signal_ctxt,
             );
 
-            // Cancel login: route to Logout (aborts the in-progress device flow)
+            // Cancel login: aborts the in-progress device flow
             let cancel_auth_tx = handle.auth_cmd_tx.clone();
             tauri::async_runtime::spawn(async move {
                 while cancel_rx.recv().await.is_some() {
-                    tracing::info!("Login cancelled by user");
-                    let _ = cancel_auth_tx.send(AuthCommand::Logout);
+                    let _ = cancel_auth_tx.send(AuthCommand::CancelLogin);
                 }
             });
 
@@ -2,7 +2,8 @@ use std::collections::{HashMap, HashSet};
 
 use chrono::{DateTime, Duration, Utc};
 use twitch_backend::{
-    config::{StreamerImportance, StreamerSettings},
+    config::{FavouriteSortMode, StreamerSettings},
+    filtering::{is_favourite, shows_in_menu},
     handle::{LoginProgress, RawDisplayData},
     twitch::{format_viewer_count, ScheduledStream, Stream},
 };
@@ -15,25 +16,17 @@ use crate::dto::{
 /// A scheduled stream within this many minutes of a live broadcast is hidden from the schedule.
 const LIVE_COVERS_SCHEDULE_WINDOW_MIN: i64 = 60;
 
-fn get_importance(
-    user_login: &str,
-    settings: &HashMap<String, StreamerSettings>,
-) -> StreamerImportance {
-    settings
-        .get(user_login)
-        .map(|s| s.importance)
-        .unwrap_or_default()
-}
-
 fn map_login_state(login_progress: Option<&LoginProgress>) -> LoginStateDto {
     match login_progress {
         None | Some(LoginProgress::Confirmed | LoginProgress::Failed(_)) => LoginStateDto::Idle,
         Some(LoginProgress::PendingCode {
             user_code,
             verification_uri,
+            expires_at,
         }) => LoginStateDto::PendingCode {
             user_code: user_code.clone(),
             verification_uri: verification_uri.clone(),
+            expires_at: expires_at.timestamp(),
         },
     }
 }
@@ -43,7 +36,7 @@ fn live_stream_to_dto(
     settings: &HashMap<String, StreamerSettings>,
     hot_stream_ids: &HashSet<String>,
 ) -> LiveStreamDto {
-    let is_favourite = get_importance(&s.user_login, settings) == StreamerImportance::Favourite;
+    let is_favourite = is_favourite(&s.user_login, settings);
     let is_hot = hot_stream_ids.contains(&s.user_id);
     let viewer_count_formatted = s.format_viewer_count();
     let duration_formatted = s.format_duration();
@@ -65,8 +58,7 @@ fn scheduled_to_dto(
     settings: &HashMap<String, StreamerSettings>,
     profile_image_urls: &HashMap<String, String>,
 ) -> ScheduledStreamDto {
-    let is_favourite =
-        get_importance(&s.broadcaster_login, settings) == StreamerImportance::Favourite;
+    let is_favourite = is_favourite(&s.broadcaster_login, settings);
     let start_time_formatted = s.format_start_time();
     let title = if s.is_inferred {
         String::new()
@@ -121,6 +113,7 @@ pub fn compute_plasmoid_state(
                 visible: vec![],
                 overflow: vec![],
             },
+            unread: false,
         };
     }
 
@@ -129,14 +122,28 @@ pub fn compute_plasmoid_state(
     // --- Live section ---
 
     let mut streams = raw.live_streams;
-    streams.retain(|s| get_importance(&s.user_login, settings) != StreamerImportance::Ignore);
+    let followed_ids: HashSet<String> = streams.iter().map(|s| s.user_id.clone()).collect();
+    streams.retain(|s| shows_in_menu(&s.user_login, settings));
 
     let live_logins: HashSet<String> = streams.iter().map(|s| s.user_login.clone()).collect();
 
+    let sort_by_recency = raw.config.favourite_sort_mode == FavouriteSortMode::RecentlyWatched;
     streams.sort_by(|a, b| {
-        let a_fav = get_importance(&a.user_login, settings) == StreamerImportance::Favourite;
-        let b_fav = get_importance(&b.user_login, settings) == StreamerImportance::Favourite;
-        b_fav.cmp(&a_fav).then(b.viewer_count.cmp(&a.viewer_count))
+        let a_fav = is_favourite(&a.user_login, settings);
+        let b_fav = is_favourite(&b.user_login, settings);
+        b_fav
+            .cmp(&a_fav)
+            .then_with(|| {
+                if sort_by_recency && a_fav && b_fav {
+                    // Most recently watched first; never-watched (None) last
+                    raw.last_watched
+                        .get(&b.user_login)
+                        .cmp(&raw.last_watched.get(&a.user_login))
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .then_with(|| raw.config.live_sort.compare(a, b, &raw.last_notified))
     });
 
     let live_limit = raw.config.live_menu_limit;
@@ -163,16 +170,18 @@ pub fn compute_plasmoid_state(
     let mut categories = vec![];
     for category in &raw.followed_categories {
         if let Some(cat_streams) = raw.category_streams.get(&category.id) {
-            if !cat_streams.is_empty() {
-                let mut sorted = cat_streams.clone();
+            let mut sorted = cat_streams.clone();
+            if raw.config.hide_followed_in_categories {
+                sorted.retain(|s| !followed_ids.contains(&s.user_id));
+            }
+            if !sorted.is_empty() {
                 sorted.sort_by(|a, b| b.viewer_count.cmp(&a.viewer_count));
 
                 let total_viewers: u32 = sorted.iter().map(|s| s.viewer_count).sum();
                 let streams_dto: Vec<CategoryStreamDto> = sorted
                     .into_iter()
                     .map(|s| {
-                        let is_favourite = get_importance(&s.user_login, settings)
-                            == StreamerImportance::Favourite;
+                        let is_favourite = is_favourite(&s.user_login, settings);
                         let viewer_count_formatted = s.format_viewer_count();
                         let duration_formatted = s.format_duration();
                         CategoryStreamDto {
@@ -208,8 +217,7 @@ pub fn compute_plasmoid_state(
     let soon_threshold = now + Duration::minutes(LIVE_COVERS_SCHEDULE_WINDOW_MIN);
 
     let mut scheduled = raw.scheduled_streams;
-    scheduled
-        .retain(|s| get_importance(&s.broadcaster_login, settings) != StreamerImportance::Ignore);
+    scheduled.retain(|s| shows_in_menu(&s.broadcaster_login, settings));
     scheduled.retain(|s| {
         !(live_logins.contains(&s.broadcaster_login) && s.start_time <= soon_threshold)
     });
@@ -241,6 +249,7 @@ pub fn compute_plasmoid_state(
         live,
         categories,
         schedule,
+        unread: raw.has_unread,
     }
 }
 
@@ -295,22 +304,45 @@ mod tests {
             category_id: Some("123".to_string()),
             is_recurring: false,
             is_inferred: false,
+            confidence: None,
         }
     }
 
     fn raw(streams: Vec<Stream>, scheduled: Vec<ScheduledStream>) -> RawDisplayData {
         RawDisplayData {
             is_authenticated: true,
+            authenticating: false,
+            login_progress: None,
             live_streams: streams,
             scheduled_streams: scheduled,
             schedules_loaded: true,
+            schedules_cached: false,
+            followed_cached: false,
+            cached_categories: HashSet::new(),
             followed_channels: vec![],
             followed_categories: vec![],
             category_streams: HashMap::new(),
             config: Config::default(),
             profile_image_urls: HashMap::new(),
             box_art_urls: HashMap::new(),
+            thumbnail_paths: HashMap::new(),
             hot_stream_ids: HashSet::new(),
+            streaks_at_risk: HashMap::new(),
+            last_watched: HashMap::new(),
+            last_seen: HashMap::new(),
+            last_notified: HashMap::new(),
+            undoable_change: None,
+            has_unread: false,
+            refreshing: false,
+            last_refresh: None,
+            connection_problem_since: None,
+            connectivity: Default::default(),
+            token_refresh_failing: false,
+            missing_scopes: Vec::new(),
+            recent_videos: HashMap::new(),
+            recently_ended: Vec::new(),
+            pinned_last_live: HashMap::new(),
+            notification_snoozes: Default::default(),
         }
     }
 
@@ -327,20 +359,47 @@ mod tests {
                 display_name: user_login.to_string(),
                 importance,
                 hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: None,
+                open_with: None,
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
             },
         );
         RawDisplayData {
             is_authenticated: true,
+            authenticating: false,
+            login_progress: None,
             live_streams: streams,
             scheduled_streams: scheduled,
             schedules_loaded: true,
+            schedules_cached: false,
+            followed_cached: false,
+            cached_categories: HashSet::new(),
             followed_channels: vec![],
             followed_categories: vec![],
             category_streams: HashMap::new(),
             config,
             profile_image_urls: HashMap::new(),
             box_art_urls: HashMap::new(),
+            thumbnail_paths: HashMap::new(),
             hot_stream_ids: HashSet::new(),
+            streaks_at_risk: HashMap::new(),
+            last_watched: HashMap::new(),
+            last_seen: HashMap::new(),
+            last_notified: HashMap::new(),
+            undoable_change: None,
+            has_unread: false,
+            refreshing: false,
+            last_refresh: None,
+            connection_problem_since: None,
+            connectivity: Default::default(),
+            token_refresh_failing: false,
+            missing_scopes: Vec::new(),
+            recent_videos: HashMap::new(),
+            recently_ended: Vec::new(),
+            pinned_last_live: HashMap::new(),
+            notification_snoozes: Default::default(),
         }
     }
 
@@ -360,6 +419,16 @@ mod tests {
         assert!(state.schedule.visible.is_empty());
     }
 
+    #[test]
+    fn unread_only_shown_while_logged_in() {
+        let mut raw = raw(vec![], vec![]);
+        raw.has_unread = true;
+        assert!(compute_plasmoid_state(raw.clone(), None, Utc::now()).unread);
+
+        raw.is_authenticated = false;
+        assert!(!compute_plasmoid_state(raw, None, Utc::now()).unread);
+    }
+
     // =========================================================
     // Live section — filtering and sorting
     // =========================================================
@@ -402,6 +471,42 @@ mod tests {
         assert_eq!(state.live.visible[1].user_login, "normal_high");
     }
 
+    #[test]
+    fn live_favourites_sorted_by_watch_recency_when_enabled() {
+        let mut big = make_stream("1", "big");
+        big.viewer_count = 50_000;
+        let mut small = make_stream("2", "small");
+        small.viewer_count = 100;
+
+        let mut raw = raw_with_importance(
+            "big",
+            StreamerImportance::Favourite,
+            vec![big, small],
+            vec![],
+        );
+        raw.config.streamer_settings.insert(
+            "small".to_string(),
+            StreamerSettings {
+                display_name: "small".to_string(),
+                importance: StreamerImportance::Favourite,
+                hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: None,
+                open_with: None,
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
+            },
+        );
+        raw.config.favourite_sort_mode = FavouriteSortMode::RecentlyWatched;
+        raw.last_watched
+            .insert("small".to_string(), Utc::now() - Duration::hours(1));
+
+        let state = compute_plasmoid_state(raw, None, Utc::now());
+
+        assert_eq!(state.live.visible[0].user_login, "small");
+        assert_eq!(state.live.visible[1].user_login, "big");
+    }
+
     #[test]
     fn live_streams_sorted_by_viewers_within_group() {
         let mut low = make_stream("1", "low");
@@ -608,6 +713,7 @@ mod tests {
         raw.followed_categories = vec![FollowedCategory {
             id: cat_id.clone(),
             name: "Minecraft".to_string(),
+            ..Default::default()
         }];
         raw.category_streams = HashMap::from([(cat_id, cat_streams)]);
 
@@ -619,6 +725,26 @@ mod tests {
         assert_eq!(state.categories[0].id, "cat1");
     }
 
+    #[test]
+    fn followed_channels_can_be_hidden_from_categories() {
+        let cat_id = "cat1".to_string();
+        let cat_streams = vec![make_stream("1", "Alice"), make_stream("2", "Bob")];
+
+        let mut raw = raw(vec![make_stream("1", "Alice")], vec![]);
+        raw.config.hide_followed_in_categories = true;
+        raw.followed_categories = vec![FollowedCategory {
+            id: cat_id.clone(),
+            name: "Minecraft".to_string(),
+            ..Default::default()
+        }];
+        raw.category_streams = HashMap::from([(cat_id, cat_streams)]);
+
+        let state = compute_plasmoid_state(raw, None, Utc::now());
+
+        assert_eq!(state.categories[0].streams.len(), 1);
+        assert_eq!(state.categories[0].streams[0].user_name, "Bob");
+    }
+
     #[test]
     fn category_section_includes_box_art_url_from_cache() {
         let cat_id = "cat1".to_string();
@@ -628,6 +754,7 @@ mod tests {
         raw.followed_categories = vec![FollowedCategory {
             id: cat_id.clone(),
             name: "Minecraft".to_string(),
+            ..Default::default()
         }];
         raw.category_streams = HashMap::from([(cat_id.clone(), cat_streams)]);
         raw.box_art_urls =
@@ -650,6 +777,7 @@ mod tests {
         raw.followed_categories = vec![FollowedCategory {
             id: cat_id.clone(),
             name: "Minecraft".to_string(),
+            ..Default::default()
         }];
         raw.category_streams = HashMap::from([(cat_id, cat_streams)]);
 
@@ -667,6 +795,7 @@ mod tests {
         raw.followed_categories = vec![FollowedCategory {
             id: cat_id.clone(),
             name: "Gaming".to_string(),
+            ..Default::default()
         }];
         raw.category_streams = HashMap::from([(cat_id, vec![stream])]);
         raw.config.streamer_settings.insert(
@@ -675,6 +804,11 @@ mod tests {
                 display_name: "favuser".to_string(),
                 importance: StreamerImportance::Favourite,
                 hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: None,
+                open_with: None,
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
             },
         );
 
@@ -691,15 +825,18 @@ mod tests {
     fn login_state_pending_code_propagated() {
         let mut raw = raw(vec![], vec![]);
         raw.is_authenticated = false;
+        let expires_at = Utc::now() + Duration::minutes(10);
         let progress = LoginProgress::PendingCode {
             user_code: "ABCD-1234".to_string(),
             verification_uri: "https://twitch.tv/activate".to_string(),
+            expires_at,
         };
         let state = compute_plasmoid_state(raw, Some(&progress), Utc::now());
 
         assert!(matches!(
             state.login_state,
-            LoginStateDto::PendingCode { ref user_code, .. } if user_code == "ABCD-1234"
+            LoginStateDto::PendingCode { ref user_code, expires_at: ts, .. }
+                if user_code == "ABCD-1234" && ts == expires_at.timestamp()
         ));
     }
 }
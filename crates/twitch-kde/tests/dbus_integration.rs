@@ -6,7 +6,7 @@ use tokio::sync::{mpsc, watch};
 use zbus::zvariant::OwnedValue;
 use zbus::{connection, message, names::BusName, MessageStream};
 
-use twitch_backend::{handle::RawDisplayData, AuthCommand};
+use twitch_backend::{config::Config, handle::RawDisplayData, AuthCommand};
 use twitch_kde::{
     dbus_service::{DbusService, WindowRequest, OBJECT_PATH},
     dto::{LiveSectionDto, LoginStateDto, PlasmoidState, ScheduleSectionDto},
@@ -27,6 +27,7 @@ fn default_state() -> PlasmoidState {
             visible: vec![],
             overflow: vec![],
         },
+        unread: false,
     }
 }
 
@@ -39,8 +40,10 @@ fn make_service(state: PlasmoidState) -> (DbusService, Arc<Mutex<PlasmoidState>>
         state: Arc::clone(&state_arc),
         auth_cmd_tx: auth_tx,
         window_tx,
-        open_url: Arc::new(|_| {}),
+        open: Arc::new(|_| {}),
+        config: Arc::new(Config::default),
         cancel_login_tx: cancel_tx,
+        menu_opened: Arc::new(|| {}),
     };
     (service, state_arc)
 }
@@ -104,8 +107,10 @@ async fn login_method_reachable_over_dbus() {
         state: Arc::new(Mutex::new(default_state())),
         auth_cmd_tx: auth_tx,
         window_tx,
-        open_url: Arc::new(|_| {}),
+        open: Arc::new(|_| {}),
+        config: Arc::new(Config::default),
         cancel_login_tx: cancel_tx,
+        menu_opened: Arc::new(|| {}),
     };
 
     let (_server, client) = make_peer_conns(service).await;
@@ -0,0 +1,651 @@
+//! Dashboard window: the menu's live streams, followed categories and
+//! schedule as thumbnail cards, for keeping on screen.
+//!
+//! It shows the same `DisplayState` as the tray, so ignored streamers, sort
+//! order and schedule filtering match, but without the menu's visible/overflow
+//! split. Clicks reuse the menu item IDs and go through
+//! `window_menu::activate_menu_row`, so Ctrl/Shift/Alt-clicks work here too.
+//!
+//! Followed streams' thumbnails come from the backend's `ThumbnailCache`
+//! once saved, served to the page over the `thumbnail:` URI scheme, and from
+//! the Twitch CDN until then.
+//!
+//! The quick list (`open_quick_list_window`) is a small popup of just the
+//! live streams from the same data, for the global shortcut.
+//!
+//! A category submenu's "Show more…" opens a window of that category's
+//! streams (`open_category_window`), from the top and then a few pages at a
+//! time through `get_category_streams`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::http::{header::CONTENT_TYPE, Request, Response};
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindowBuilder, WindowEvent};
+use tokio::sync::watch;
+use twitch_backend::app_services::{AppServices, CategoryStreamsPage};
+use twitch_backend::handle::RawDisplayData;
+use twitch_backend::thumbnail_cache::{is_thumbnail_file_name, thumbnail_dir};
+use twitch_backend::twitch::{
+    format_viewer_count, render_thumbnail, Stream, BOX_ART_HEIGHT, BOX_ART_WIDTH,
+};
+
+use crate::display_state::{DisplayState, ScheduledEntry};
+use crate::ids;
+
+/// Window label, also used as the event target for data updates.
+const WINDOW_LABEL: &str = "dashboard";
+const WINDOW_WIDTH: f64 = 900.0;
+const WINDOW_HEIGHT: f64 = 700.0;
+const QUICK_LIST_LABEL: &str = "quick-list";
+const QUICK_LIST_WIDTH: f64 = 360.0;
+const QUICK_LIST_HEIGHT: f64 = 480.0;
+/// Category window labels are this followed by the category ID, which
+/// `category.js` reads back.
+const CATEGORY_WINDOW_PREFIX: &str = "category-";
+const CATEGORY_WINDOW_WIDTH: f64 = 900.0;
+const CATEGORY_WINDOW_HEIGHT: f64 = 600.0;
+/// Event carrying the new `MenuData` whenever the menu changes.
+const DATA_UPDATED_EVENT: &str = "menu-data-updated";
+
+/// Stream preview size (16:9).
+const THUMBNAIL_WIDTH: u32 = 320;
+const THUMBNAIL_HEIGHT: u32 = 180;
+
+/// URI scheme serving saved thumbnails to the page (see `serve_thumbnail`).
+pub const THUMBNAIL_SCHEME: &str = "thumbnail";
+
+/// Everything the dashboard shows.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct MenuData {
+    pub authenticated: bool,
+    /// Pinned channels, live or not, above the live streams.
+    pub pinned: Vec<PinnedCard>,
+    /// `"Following Live (3)"`, as in the menu.
+    pub live_header: String,
+    pub live: Vec<StreamCard>,
+    pub categories: Vec<CategoryCard>,
+    /// `"Scheduled (next 6h)"`, as in the menu.
+    pub schedule_header: String,
+    pub schedule: Vec<ScheduleCard>,
+    /// `true` once the first schedule fetch has finished, so an empty
+    /// schedule can say "none" rather than "loading".
+    pub schedules_loaded: bool,
+}
+
+/// A live stream, followed or in a followed category.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct StreamCard {
+    /// Menu item ID to activate on click.
+    pub id: String,
+    pub user_name: String,
+    pub game_name: String,
+    pub title: String,
+    /// Twitch CDN preview.
+    pub thumbnail_url: String,
+    /// The saved copy of the preview, when there is one.
+    pub cached_thumbnail_url: Option<String>,
+    pub profile_image_url: String,
+    pub viewer_count_formatted: String,
+    pub duration_formatted: String,
+    pub is_hot: bool,
+    /// Menu item ID that pins (or unpins) the channel; `None` for category
+    /// streams.
+    pub pin_id: Option<String>,
+}
+
+/// A pinned channel.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct PinnedCard {
+    /// Menu item ID to activate on click.
+    pub id: String,
+    /// The menu label: the stream while live, `"Name - offline"` otherwise.
+    pub label: String,
+    /// Menu item ID that unpins the channel.
+    pub unpin_id: String,
+    /// The stream while it's live.
+    pub stream: Option<StreamCard>,
+}
+
+/// A followed category and its top streams.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct CategoryCard {
+    pub id: String,
+    /// `"Chess (2k)"`, as in the menu.
+    pub header: String,
+    pub box_art_url: String,
+    pub streams: Vec<StreamCard>,
+}
+
+/// One load of a category window's streams.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct CategoryPage {
+    pub streams: Vec<StreamCard>,
+    /// Passed back to load the next streams; `None` once there are no more.
+    pub cursor: Option<String>,
+}
+
+/// An upcoming scheduled (or predicted) stream.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ScheduleCard {
+    /// Menu item ID to activate on click.
+    pub id: String,
+    pub broadcaster_name: String,
+    pub start_time: DateTime<Utc>,
+    pub title: String,
+    pub category: Option<String>,
+    pub profile_image_url: String,
+    pub is_inferred: bool,
+    pub confidence: Option<f32>,
+}
+
+/// The page's URL for a saved thumbnail.
+fn local_thumbnail_url(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    // Windows webviews reach custom schemes over http
+    Some(if cfg!(windows) {
+        format!("http://{THUMBNAIL_SCHEME}.localhost/{name}")
+    } else {
+        format!("{THUMBNAIL_SCHEME}://localhost/{name}")
+    })
+}
+
+fn stream_card(
+    prefix: &str,
+    stream: &Stream,
+    is_hot: bool,
+    thumbnail_paths: &HashMap<String, PathBuf>,
+) -> StreamCard {
+    StreamCard {
+        id: format!("{}{}", prefix, stream.user_login),
+        user_name: stream.user_name.clone(),
+        game_name: stream.game_name.clone(),
+        title: stream.title.clone(),
+        thumbnail_url: stream.thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT),
+        cached_thumbnail_url: thumbnail_paths
+            .get(&stream.user_id)
+            .and_then(|path| local_thumbnail_url(path)),
+        profile_image_url: stream.profile_image_url.clone(),
+        viewer_count_formatted: format_viewer_count(stream.viewer_count),
+        duration_formatted: stream.format_duration(),
+        is_hot,
+        pin_id: None,
+    }
+}
+
+/// A followed live stream's card, with its pin (or unpin) button.
+fn followed_card(stream: &Stream, is_hot: bool, pinned: bool, raw: &RawDisplayData) -> StreamCard {
+    let pin_prefix = if pinned {
+        ids::UNPIN_PREFIX
+    } else {
+        ids::PIN_PREFIX
+    };
+    StreamCard {
+        pin_id: Some(format!("{}{}", pin_prefix, stream.user_login)),
+        ..stream_card(ids::STREAM_PREFIX, stream, is_hot, &raw.thumbnail_paths)
+    }
+}
+
+fn schedule_card(
+    entry: &ScheduledEntry,
+    profile_image_urls: &HashMap<String, String>,
+) -> ScheduleCard {
+    let s = &entry.scheduled;
+    ScheduleCard {
+        id: format!("{}{}", ids::SCHEDULED_PREFIX, s.broadcaster_login),
+        broadcaster_name: s.broadcaster_name.clone(),
+        start_time: s.start_time,
+        title: s.title.clone(),
+        category: s.category.clone(),
+        profile_image_url: profile_image_urls
+            .get(&s.broadcaster_id)
+            .cloned()
+            .unwrap_or_default(),
+        is_inferred: s.is_inferred,
+        confidence: s.confidence,
+    }
+}
+
+/// Builds the dashboard's data from the menu's `DisplayState`, adding box
+/// art, profile images and saved thumbnails from the snapshot it came from.
+pub fn compute_menu_data(state: &DisplayState, raw: &RawDisplayData) -> MenuData {
+    if !state.authenticated {
+        return MenuData::default();
+    }
+
+    let live = &state.live_section;
+    let schedule = &state.schedule_section;
+    MenuData {
+        authenticated: true,
+        pinned: state
+            .pinned
+            .iter()
+            .map(|pinned| {
+                let stream = pinned
+                    .live
+                    .as_ref()
+                    .map(|entry| followed_card(&entry.stream, entry.is_hot, true, raw));
+                PinnedCard {
+                    id: stream.as_ref().map_or_else(
+                        || format!("{}{}", ids::PINNED_PREFIX, pinned.user_login),
+                        |card| card.id.clone(),
+                    ),
+                    label: pinned.label.clone(),
+                    unpin_id: format!("{}{}", ids::UNPIN_PREFIX, pinned.user_login),
+                    stream,
+                }
+            })
+            .collect(),
+        live_header: live.header(),
+        live: live
+            .visible
+            .iter()
+            .chain(&live.overflow)
+            .map(|entry| followed_card(&entry.stream, entry.is_hot, false, raw))
+            .collect(),
+        categories: state
+            .category_sections
+            .iter()
+            .map(|section| CategoryCard {
+                id: section.category_id.clone(),
+                header: section.header.clone(),
+                box_art_url: raw
+                    .box_art_urls
+                    .get(&section.category_id)
+                    .map(|url| render_thumbnail(url, BOX_ART_WIDTH, BOX_ART_HEIGHT))
+                    .unwrap_or_default(),
+                streams: section
+                    .entries
+                    .iter()
+                    .map(|entry| {
+                        stream_card(
+                            ids::CATEGORY_STREAM_PREFIX,
+                            &entry.stream,
+                            false,
+                            &raw.thumbnail_paths,
+                        )
+                    })
+                    .collect(),
+            })
+            .collect(),
+        schedule_header: schedule.header.clone(),
+        schedule: schedule
+            .visible
+            .iter()
+            .chain(&schedule.overflow)
+            .map(|entry| schedule_card(entry, &raw.profile_image_urls))
+            .collect(),
+        schedules_loaded: schedule.schedules_loaded,
+    }
+}
+
+/// Cards for a category window. Only followed streams' thumbnails are
+/// saved, so these all come from the CDN.
+fn category_page(page: &CategoryStreamsPage) -> CategoryPage {
+    CategoryPage {
+        streams: page
+            .streams
+            .iter()
+            .map(|stream| stream_card(ids::CATEGORY_STREAM_PREFIX, stream, false, &HashMap::new()))
+            .collect(),
+        cursor: page.cursor.clone(),
+    }
+}
+
+/// The latest `MenuData`, served to the page when it (re)loads.
+#[derive(Clone, Default)]
+pub struct Dashboard {
+    data: Arc<Mutex<MenuData>>,
+}
+
+impl Dashboard {
+    fn current(&self) -> MenuData {
+        self.data
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    fn set(&self, data: MenuData) {
+        *self
+            .data
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = data;
+    }
+}
+
+/// Starts the dashboard listener task: keeps `dashboard` current with each
+/// display snapshot and pushes it to the dashboard and quick list when open.
+pub fn start_dashboard_listener(
+    mut display_rx: watch::Receiver<RawDisplayData>,
+    dashboard: Arc<Dashboard>,
+    app_handle: AppHandle,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        while display_rx.changed().await.is_ok() {
+            let raw = display_rx.borrow().clone();
+            let state = crate::display_state_from_raw(raw.clone(), Utc::now());
+            let data = compute_menu_data(&state, &raw);
+            dashboard.set(data.clone());
+            for label in [WINDOW_LABEL, QUICK_LIST_LABEL] {
+                if let Err(e) = app_handle.emit_to(label, DATA_UPDATED_EVENT, data.clone()) {
+                    tracing::error!("Failed to update {}: {}", label, e);
+                }
+            }
+        }
+    })
+}
+
+/// Opens the dashboard window, or focuses it if it is already open.
+pub fn open_dashboard_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = window.set_focus();
+        return;
+    }
+
+    match WebviewWindowBuilder::new(
+        app,
+        WINDOW_LABEL,
+        tauri::WebviewUrl::App("dashboard.html".into()),
+    )
+    .title("Twitch Tray Dashboard")
+    .inner_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+    .resizable(true)
+    .center()
+    .build()
+    {
+        Ok(_) => tracing::info!("Dashboard window opened"),
+        Err(e) => tracing::error!("Failed to open dashboard window: {}", e),
+    }
+}
+
+/// Opens the quick list, or focuses it if it is already open.
+///
+/// It floats above other windows without decorations, like a menu, and
+/// closes as soon as it loses focus (`quicklist.js` also closes it once a
+/// stream is opened).
+pub fn open_quick_list_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(QUICK_LIST_LABEL) {
+        let _ = window.set_focus();
+        return;
+    }
+
+    let window = match WebviewWindowBuilder::new(
+        app,
+        QUICK_LIST_LABEL,
+        tauri::WebviewUrl::App("quicklist.html".into()),
+    )
+    .title("Twitch Tray")
+    .inner_size(QUICK_LIST_WIDTH, QUICK_LIST_HEIGHT)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .focused(true)
+    .build()
+    {
+        Ok(window) => window,
+        Err(e) => {
+            tracing::error!("Failed to open quick list window: {}", e);
+            return;
+        }
+    };
+
+    let closing = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Focused(false) = event {
+            let _ = closing.close();
+        }
+    });
+}
+
+/// Opens the window listing more of a followed category's streams, or
+/// focuses it if it is already open.
+pub fn open_category_window(app: &AppHandle, category_id: &str) {
+    let label = format!("{CATEGORY_WINDOW_PREFIX}{category_id}");
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.set_focus();
+        return;
+    }
+
+    let name = app
+        .try_state::<Arc<dyn AppServices>>()
+        .and_then(|services| {
+            services
+                .get_followed_categories()
+                .into_iter()
+                .find(|c| c.id == category_id)
+        })
+        .map_or_else(|| "Category".to_string(), |c| c.name);
+
+    match WebviewWindowBuilder::new(app, &label, tauri::WebviewUrl::App("category.html".into()))
+        .title(name)
+        .inner_size(CATEGORY_WINDOW_WIDTH, CATEGORY_WINDOW_HEIGHT)
+        .resizable(true)
+        .center()
+        .build()
+    {
+        Ok(_) => tracing::info!("Category window opened for {}", category_id),
+        Err(e) => tracing::error!("Failed to open category window: {}", e),
+    }
+}
+
+/// Answers a `THUMBNAIL_SCHEME` request with a file from `thumbnail_dir`.
+pub fn serve_thumbnail(request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let name = request.uri().path().trim_start_matches('/');
+    let image = is_thumbnail_file_name(name)
+        .then(thumbnail_dir)
+        .and_then(Result::ok)
+        .and_then(|dir| std::fs::read(dir.join(name)).ok());
+    let response = match image {
+        Some(bytes) => Response::builder()
+            .header(CONTENT_TYPE, "image/jpeg")
+            .body(bytes),
+        None => Response::builder().status(404).body(Vec::new()),
+    };
+    response.unwrap_or_else(|_| Response::new(Vec::new()))
+}
+
+/// Returns the current dashboard data (called by `dashboard.js` and
+/// `quicklist.js` on load).
+#[tauri::command]
+pub fn get_menu_data(dashboard: State<'_, Arc<Dashboard>>) -> MenuData {
+    dashboard.current()
+}
+
+/// Loads a category window's next streams, after `cursor` or from the top
+/// (called by `category.js`).
+#[tauri::command]
+pub async fn get_category_streams(
+    services: State<'_, Arc<dyn AppServices>>,
+    category_id: String,
+    cursor: Option<String>,
+) -> Result<CategoryPage, String> {
+    services
+        .get_category_streams_page(&category_id, cursor)
+        .await
+        .map(|page| category_page(&page))
+        .map_err(|e| e.to_string())
+}
+
+/// Pins the dashboard above other windows, or unpins it.
+#[tauri::command]
+pub fn set_dashboard_pinned(app: AppHandle, pinned: bool) -> Result<(), String> {
+    let window = app
+        .get_webview_window(WINDOW_LABEL)
+        .ok_or_else(|| "Dashboard is not open".to_string())?;
+    window.set_always_on_top(pinned).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display_state::{CategorySection, CategoryStreamEntry, PinnedEntry, StreamEntry};
+    use crate::test_helpers::{make_scheduled, make_stream};
+
+    fn authenticated() -> DisplayState {
+        DisplayState {
+            authenticated: true,
+            ..DisplayState::unauthenticated()
+        }
+    }
+
+    #[test]
+    fn unauthenticated_is_empty() {
+        let data = compute_menu_data(&DisplayState::unauthenticated(), &RawDisplayData::default());
+        assert_eq!(data, MenuData::default());
+    }
+
+    #[test]
+    fn live_includes_overflow_with_sized_and_saved_thumbnails() {
+        let mut state = authenticated();
+        let mut alice = make_stream("1", "Alice");
+        alice.thumbnail_url = "https://cdn/alice-{width}x{height}.jpg".to_string();
+        state.live_section.visible = vec![StreamEntry {
+            stream: alice,
+            label: "Alice".to_string(),
+            is_hot: true,
+        }];
+        state.live_section.overflow = vec![StreamEntry {
+            stream: make_stream("2", "Bob"),
+            label: "Bob".to_string(),
+            is_hot: false,
+        }];
+
+        let raw = RawDisplayData {
+            thumbnail_paths: HashMap::from([(
+                "1".to_string(),
+                std::path::PathBuf::from("/config/thumbnails/1.jpg"),
+            )]),
+            ..RawDisplayData::default()
+        };
+
+        let data = compute_menu_data(&state, &raw);
+
+        assert_eq!(data.live_header, "Following Live (2)");
+        let ids: Vec<&str> = data.live.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["stream_alice", "stream_bob"]);
+        assert_eq!(data.live[0].thumbnail_url, "https://cdn/alice-320x180.jpg");
+        assert!(data.live[0]
+            .cached_thumbnail_url
+            .as_deref()
+            .is_some_and(|url| url.ends_with("/1.jpg")));
+        assert_eq!(data.live[1].cached_thumbnail_url, None);
+        assert!(data.live[0].is_hot);
+        assert_eq!(data.live[0].pin_id.as_deref(), Some("pin_alice"));
+    }
+
+    #[test]
+    fn pinned_cards_open_and_unpin_the_channel() {
+        let mut state = authenticated();
+        state.pinned = vec![
+            PinnedEntry {
+                user_login: "alice".to_string(),
+                label: "Alice".to_string(),
+                live: Some(StreamEntry {
+                    stream: make_stream("1", "Alice"),
+                    label: "Alice".to_string(),
+                    is_hot: false,
+                }),
+            },
+            PinnedEntry {
+                user_login: "bob".to_string(),
+                label: "Bob - offline".to_string(),
+                live: None,
+            },
+        ];
+
+        let data = compute_menu_data(&state, &RawDisplayData::default());
+
+        let alice = &data.pinned[0];
+        assert_eq!(alice.id, "stream_alice");
+        assert_eq!(alice.unpin_id, "unpin_alice");
+        let card = alice.stream.as_ref().unwrap();
+        assert_eq!(card.pin_id.as_deref(), Some("unpin_alice"));
+        let bob = &data.pinned[1];
+        assert_eq!(bob.id, "pinned_bob");
+        assert_eq!(bob.label, "Bob - offline");
+        assert!(bob.stream.is_none());
+    }
+
+    #[test]
+    fn categories_get_box_art_and_category_stream_ids() {
+        let mut state = authenticated();
+        state.category_sections = vec![CategorySection {
+            category_id: "chess".to_string(),
+            header: "Chess (1k)".to_string(),
+            entries: vec![CategoryStreamEntry {
+                stream: make_stream("3", "Carol"),
+                label: "Carol".to_string(),
+            }],
+            has_more: false,
+        }];
+        let raw = RawDisplayData {
+            box_art_urls: HashMap::from([(
+                "chess".to_string(),
+                "https://cdn/chess-{width}x{height}.jpg".to_string(),
+            )]),
+            ..RawDisplayData::default()
+        };
+
+        let data = compute_menu_data(&state, &raw);
+
+        let chess = &data.categories[0];
+        assert_eq!(chess.header, "Chess (1k)");
+        assert_eq!(chess.box_art_url, "https://cdn/chess-144x192.jpg");
+        assert_eq!(chess.streams[0].id, "cat_stream_carol");
+        assert_eq!(chess.streams[0].pin_id, None);
+    }
+
+    #[test]
+    fn category_pages_use_category_stream_ids_and_cdn_thumbnails() {
+        let mut stream = make_stream("3", "Carol");
+        stream.thumbnail_url = "https://cdn/carol-{width}x{height}.jpg".to_string();
+        let page = CategoryStreamsPage {
+            streams: vec![stream],
+            cursor: Some("next".to_string()),
+        };
+
+        let data = category_page(&page);
+
+        assert_eq!(data.streams[0].id, "cat_stream_carol");
+        assert_eq!(
+            data.streams[0].thumbnail_url,
+            "https://cdn/carol-320x180.jpg"
+        );
+        assert_eq!(data.streams[0].cached_thumbnail_url, None);
+        assert_eq!(data.cursor.as_deref(), Some("next"));
+    }
+
+    #[test]
+    fn schedule_cards_carry_profile_images_and_confidence() {
+        let mut state = authenticated();
+        let mut predicted = make_scheduled("Dave", 2);
+        predicted.is_inferred = true;
+        predicted.confidence = Some(0.75);
+        state.schedule_section.visible = vec![ScheduledEntry {
+            scheduled: predicted,
+            label: "Dave".to_string(),
+            day: None,
+        }];
+        let raw = RawDisplayData {
+            profile_image_urls: HashMap::from([(
+                "dave".to_string(),
+                "https://cdn/dave.png".to_string(),
+            )]),
+            ..RawDisplayData::default()
+        };
+
+        let data = compute_menu_data(&state, &raw);
+
+        let dave = &data.schedule[0];
+        assert_eq!(dave.id, "scheduled_dave");
+        assert_eq!(dave.profile_image_url, "https://cdn/dave.png");
+        assert!(dave.is_inferred);
+        assert_eq!(dave.confidence, Some(0.75));
+    }
+}
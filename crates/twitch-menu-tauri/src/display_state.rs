@@ -1,15 +1,37 @@
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
 
-use twitch_backend::config::{FollowedCategory, StreamerImportance, StreamerSettings};
-use twitch_backend::notify::truncate;
-use twitch_backend::twitch::{format_viewer_count, ScheduledStream, Stream};
+use twitch_backend::config::{
+    CustomAction, CustomActionTarget, FavouriteSortMode, FollowedCategory, IconTheme, LiveSortMode,
+    PinnedStream, StreamerImportance, StreamerSettings,
+};
+use twitch_backend::custom_action;
+use twitch_backend::filtering::{is_favourite, shows_in_menu};
+use twitch_backend::notification_snooze::NotificationSnoozes;
+use twitch_backend::notify::{format_live_label, truncate, truncate_width};
+use twitch_backend::state::EndedStream;
+use twitch_backend::twitch::{format_viewer_count, ScheduledStream, Stream, Video};
+use twitch_backend::LoginProgress;
 
 /// Scheduled stream within this many minutes of a live broadcast is "covered" by the live stream
 /// and hidden from the schedule section.
 const LIVE_COVERS_SCHEDULE_WINDOW_MIN: i64 = 60;
 
+/// Scheduled streams are grouped under day headers once the lookahead exceeds this.
+const GROUP_SCHEDULE_BY_DAY_AFTER_HOURS: u64 = 24;
+
+/// The "Updated" footer item is flagged once the last successful refresh is
+/// this many poll intervals old.
+const STALE_AFTER_POLLS: u64 = 3;
+
+/// Streams listed in each category submenu; Twitch's first page is this size.
+const CATEGORY_MENU_LIMIT: usize = 10;
+
+/// Appended to section headers still showing the startup cache.
+const CACHED_MARKER: &str = " (cached)";
+
 /// A live stream entry ready to be rendered.
 pub struct StreamEntry {
     pub stream: Stream,
@@ -21,23 +43,93 @@ pub struct StreamEntry {
 pub struct LiveSection {
     pub visible: Vec<StreamEntry>,
     pub overflow: Vec<StreamEntry>,
+    /// The streams are last run's, from the startup cache.
+    pub cached: bool,
+}
+
+impl LiveSection {
+    /// Section header: `"Following Live (3)"`, marked while still cached.
+    pub fn header(&self) -> String {
+        let total = self.visible.len() + self.overflow.len();
+        let mut header = if total == 0 {
+            "Following Live".to_string()
+        } else {
+            format!("Following Live ({total})")
+        };
+        if self.cached {
+            header.push_str(CACHED_MARKER);
+        }
+        header
+    }
 }
 
 /// A scheduled stream entry ready to be rendered.
 pub struct ScheduledEntry {
     pub scheduled: ScheduledStream,
     pub label: String,
+    /// Day header (`"Today"`, `"Tomorrow"`, `"Friday"`) when the schedule is
+    /// grouped by day. The label then shows only the time.
+    pub day: Option<String>,
+}
+
+/// The soonest upcoming scheduled stream, used for the header countdown.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NextScheduled {
+    pub broadcaster_name: String,
+    pub start_time: DateTime<Utc>,
 }
 
 /// The scheduled-streams portion of the display.
 pub struct ScheduleSection {
+    /// Header text as of the `now` passed to `compute_display_state`.
+    /// The tray re-renders it from `lookahead_hours`/`next_up` between rebuilds.
     pub header: String,
+    pub lookahead_hours: u64,
+    pub next_up: Option<NextScheduled>,
     pub visible: Vec<ScheduledEntry>,
     pub overflow: Vec<ScheduledEntry>,
     /// `true` once the initial schedule fetch has completed; used to pick the empty label.
     pub schedules_loaded: bool,
 }
 
+/// The "Updated 2m ago" footer item and what it needs to re-render.
+#[derive(Debug, Clone)]
+pub struct RefreshStatus {
+    pub last_refresh: DateTime<Utc>,
+    pub poll_interval_sec: u64,
+    /// Label text as of the `now` passed to `compute_display_state`.
+    pub label: String,
+}
+
+/// A device code waiting to be entered, shown in the login menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginCode {
+    pub code: String,
+    /// `"Enter ABCD-EFGH at twitch.tv/activate"`
+    pub label: String,
+}
+
+impl LoginCode {
+    /// The code `progress` is waiting on, if any.
+    pub fn from_progress(progress: Option<&LoginProgress>) -> Option<Self> {
+        let Some(LoginProgress::PendingCode {
+            user_code,
+            verification_uri,
+            ..
+        }) = progress
+        else {
+            return None;
+        };
+        let site = verification_uri
+            .trim_start_matches("https://")
+            .trim_start_matches("www.");
+        Some(Self {
+            code: user_code.clone(),
+            label: format!("Enter {user_code} at {site}"),
+        })
+    }
+}
+
 /// A single stream within a category section.
 pub struct CategoryStreamEntry {
     pub stream: Stream,
@@ -46,8 +138,72 @@ pub struct CategoryStreamEntry {
 
 /// A followed category and its top streams.
 pub struct CategorySection {
+    pub category_id: String,
     pub header: String,
     pub entries: Vec<CategoryStreamEntry>,
+    /// The category may have more streams than the menu shows, so it gets a
+    /// "Show more…" entry.
+    pub has_more: bool,
+}
+
+/// A pinned channel (`Config::pinned_streams`), listed above Following Live.
+pub struct PinnedEntry {
+    pub user_login: String,
+    /// The live label while live, `"StreamerName - offline"` otherwise.
+    pub label: String,
+    /// The stream while it's live. Live pinned streams aren't repeated in
+    /// the live section.
+    pub live: Option<StreamEntry>,
+}
+
+/// A Favourite who isn't live, for the "Favourites (offline)" submenu.
+pub struct OfflineFavourite {
+    pub user_login: String,
+    pub label: String,
+}
+
+/// A past broadcast in the "Past Broadcasts" submenu.
+pub struct VideoEntry {
+    /// Twitch video ID, used as the menu item ID suffix.
+    pub id: String,
+    pub label: String,
+}
+
+/// A followed stream that went offline in the last hour, for the
+/// "Recently Ended" submenu.
+pub struct RecentlyEndedEntry {
+    pub user_login: String,
+    /// Its past broadcast, when Twitch has one.
+    pub video_id: Option<String>,
+    pub label: String,
+}
+
+impl RecentlyEndedEntry {
+    /// Opens the past broadcast, or the channel page until one is found.
+    pub fn menu_id(&self) -> String {
+        match &self.video_id {
+            Some(id) => format!("{}{}", crate::ids::VIDEO_PREFIX, id),
+            None => format!("{}{}", crate::ids::CHANNEL_PAGE_PREFIX, self.user_login),
+        }
+    }
+}
+
+/// One Favourite's recent VODs.
+pub struct PastBroadcasts {
+    pub display_name: String,
+    pub videos: Vec<VideoEntry>,
+}
+
+/// A user-defined footer item (from `Config::custom_actions`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FooterAction {
+    /// `custom_action::action_key` of the action, used as the menu item ID
+    /// suffix so an edit made after the menu was built can't redirect a click.
+    pub key: String,
+    pub label: String,
+    /// For shell commands: the confirmation item shown in a submenu, so a
+    /// command never runs from a single stray click. `None` for URLs.
+    pub confirm_label: Option<String>,
 }
 
 /// The full computed display state for the tray menu.
@@ -59,9 +215,49 @@ pub struct CategorySection {
 /// and the other fields are ignored.
 pub struct DisplayState {
     pub authenticated: bool,
+    /// A login is in progress: the login menu offers "Cancel login" instead.
+    pub authenticating: bool,
+    /// The code that login waits on, once Twitch has issued one.
+    pub login_code: Option<LoginCode>,
+    /// Pinned channels in the order they were pinned.
+    pub pinned: Vec<PinnedEntry>,
     pub live_section: LiveSection,
     pub schedule_section: ScheduleSection,
     pub category_sections: Vec<CategorySection>,
+    /// Favourites not currently live, most recently seen first.
+    pub offline_favourites: Vec<OfflineFavourite>,
+    /// Followed streams that went offline in the last hour, newest first.
+    pub recently_ended: Vec<RecentlyEndedEntry>,
+    /// Favourites with recent VODs, by display name.
+    pub past_broadcasts: Vec<PastBroadcasts>,
+    pub footer_actions: Vec<FooterAction>,
+    /// Label for the "undo last settings change" footer item, when there is one.
+    pub undo_label: Option<String>,
+    /// A Favourite went live since the menu was last opened.
+    pub unread: bool,
+    /// "Refresh now" is running: show "Refreshing…" in its place.
+    pub refreshing: bool,
+    /// When the data was last refreshed; `None` before the first refresh.
+    pub refresh_status: Option<RefreshStatus>,
+    /// `"⚠ Connection problems since 2:05 PM"` while refreshes are failing.
+    /// The icon shows a warning too.
+    pub connection_problem: Option<String>,
+    /// Tray icon tooltip: `"Twitch Tray"`, or `"Twitch Tray (offline since
+    /// 2:05 PM)"` while Twitch can't be reached.
+    pub tooltip: String,
+    /// Clickable `"⚠ Couldn't renew Twitch login: log in again"` while
+    /// refreshing the session token keeps failing, or a request to log in
+    /// again while it lacks required scopes. The icon shows a warning too.
+    pub login_warning: Option<String>,
+    /// Number of live followed streams to draw on the icon, when enabled.
+    pub live_count: Option<usize>,
+    /// How to colour the icon (`Config::icon_theme`).
+    pub icon_theme: IconTheme,
+    /// `"Notifications snoozed until 2:05 PM"` while every notification is
+    /// snoozed. The footer then offers to resume them instead.
+    pub snooze_label: Option<String>,
+    /// Logins whose notifications are snoozed, for their entries' snooze items.
+    pub snoozed_streamers: HashSet<String>,
 }
 
 impl DisplayState {
@@ -69,17 +265,38 @@ impl DisplayState {
     pub fn unauthenticated() -> Self {
         Self {
             authenticated: false,
+            authenticating: false,
+            login_code: None,
+            pinned: Vec::new(),
             live_section: LiveSection {
                 visible: Vec::new(),
                 overflow: Vec::new(),
+                cached: false,
             },
             schedule_section: ScheduleSection {
                 header: String::new(),
+                lookahead_hours: 0,
+                next_up: None,
                 visible: Vec::new(),
                 overflow: Vec::new(),
                 schedules_loaded: false,
             },
             category_sections: Vec::new(),
+            offline_favourites: Vec::new(),
+            recently_ended: Vec::new(),
+            past_broadcasts: Vec::new(),
+            footer_actions: Vec::new(),
+            undo_label: None,
+            unread: false,
+            refreshing: false,
+            refresh_status: None,
+            connection_problem: None,
+            tooltip: TOOLTIP.to_string(),
+            login_warning: None,
+            live_count: None,
+            icon_theme: IconTheme::default(),
+            snooze_label: None,
+            snoozed_streamers: HashSet::new(),
         }
     }
 }
@@ -94,55 +311,449 @@ pub struct DisplayConfig {
     pub schedule_limit: usize,
     /// User IDs of streams currently detected as "hot" (significantly above normal viewers).
     pub hot_stream_ids: HashSet<String>,
+    /// Watch streaks that lapse unless the stream is opened, keyed by user ID.
+    pub streaks_at_risk: HashMap<String, u32>,
+    /// Order of the live list within the favourite and other groups.
+    pub live_sort: LiveSortMode,
+    /// Secondary ordering applied within the live favourites group.
+    pub favourite_sort_mode: FavouriteSortMode,
+    /// When each broadcaster (by login) was last opened from the app.
+    pub last_watched: HashMap<String, DateTime<Utc>>,
+    /// When each followed broadcaster (by login) was last seen live.
+    pub last_seen: HashMap<String, DateTime<Utc>>,
+    /// When each broadcaster (by login) was last in a go-live notification.
+    pub last_notified: HashMap<String, DateTime<Utc>>,
+    /// User-defined items rendered after Settings.
+    pub custom_actions: Vec<CustomAction>,
+    /// Display columns allowed for each name or game in a label.
+    pub max_label_width: usize,
+    /// Template for live stream labels (`Config::live_label_format`).
+    pub live_label_format: String,
+    /// Login whose settings change can be undone (`RawDisplayData::undoable_change`).
+    pub undoable_change: Option<String>,
+    /// Show the unread icon (`RawDisplayData::has_unread`).
+    pub unread: bool,
+    /// Draw the live stream count on the icon (`Config::tray_icon_live_count`).
+    pub live_count_badge: bool,
+    /// Icon colouring (`Config::icon_theme`).
+    pub icon_theme: IconTheme,
+    /// Favourites' recent VODs by login (`RawDisplayData::recent_videos`).
+    pub recent_videos: HashMap<String, Vec<Video>>,
+    /// Streams that went offline recently (`RawDisplayData::recently_ended`).
+    pub recently_ended: Vec<EndedStream>,
+    /// A "Refresh now" is in flight (`RawDisplayData::refreshing`).
+    pub refreshing: bool,
+    /// Last successful live-stream refresh (`RawDisplayData::last_refresh`).
+    pub last_refresh: Option<DateTime<Utc>>,
+    /// Live stream poll interval, for judging `last_refresh` stale.
+    pub poll_interval_sec: u64,
+    /// When refreshes started failing (`RawDisplayData::connection_problem_since`).
+    pub connection_problem_since: Option<DateTime<Utc>>,
+    /// When Twitch stopped being reachable (`RawDisplayData::connectivity`).
+    pub offline_since: Option<DateTime<Utc>>,
+    /// Refreshing the session token keeps failing
+    /// (`RawDisplayData::token_refresh_failing`).
+    pub token_refresh_failing: bool,
+    /// The session token lacks required scopes (`RawDisplayData::missing_scopes`).
+    pub scopes_missing: bool,
+    /// Live streams are still the startup cache (`RawDisplayData::followed_cached`).
+    pub followed_cached: bool,
+    /// Categories still showing the startup cache (`RawDisplayData::cached_categories`).
+    pub cached_categories: HashSet<String>,
+    /// Channels pinned above the live section (`Config::pinned_streams`).
+    pub pinned_streams: Vec<PinnedStream>,
+    /// Leave followed live channels out of category sections
+    /// (`Config::hide_followed_in_categories`).
+    pub hide_followed_in_categories: bool,
+    /// Pinned channels' last live streams (`RawDisplayData::pinned_last_live`).
+    pub pinned_last_live: HashMap<String, Stream>,
+    /// Notifications snoozed from the tray (`RawDisplayData::notification_snoozes`).
+    pub notification_snoozes: NotificationSnoozes,
 }
 
-fn get_importance(
-    user_login: &str,
-    streamer_settings: &HashMap<String, StreamerSettings>,
-) -> StreamerImportance {
-    streamer_settings
-        .get(user_login)
-        .map(|s| s.importance)
-        .unwrap_or_default()
+/// Orders two live favourites by watch recency when that mode is enabled.
+///
+/// Most recently opened sorts first; never-opened favourites sort last.
+/// Returns `Equal` for everything else so the viewer-count key takes over.
+fn favourite_recency_order(
+    a: &Stream,
+    b: &Stream,
+    both_fav: bool,
+    config: &DisplayConfig,
+) -> Ordering {
+    if !both_fav || config.favourite_sort_mode != FavouriteSortMode::RecentlyWatched {
+        return Ordering::Equal;
+    }
+    let a_last = config.last_watched.get(&a.user_login);
+    let b_last = config.last_watched.get(&b.user_login);
+    b_last.cmp(&a_last)
 }
 
 /// Formats a stream label for the Following Live menu with optional star/fire prefix.
 ///
-/// Format: `"[🔥 ][★ ]StreamerName - GameName (1.2k, 2h 15m)"`
-pub(crate) fn format_stream_label_with_star(s: &Stream, star: bool, hot: bool) -> String {
+/// Format: `"[🔥 ][★ ]<template>"`, with `template` filled by
+/// `format_live_label`; by default `"StreamerName - GameName (1.2k, 2h 15m)"`.
+pub(crate) fn format_stream_label_with_star(
+    s: &Stream,
+    star: bool,
+    hot: bool,
+    template: &str,
+    max_width: usize,
+) -> String {
     let fire = if hot { "\u{1F525} " } else { "" };
     let star_str = if star { "\u{2605} " } else { "" };
     format!(
-        "{}{}{} - {} ({}, {})",
+        "{}{}{}",
         fire,
         star_str,
-        s.user_name,
-        truncate(&s.game_name, 20),
-        s.format_viewer_count(),
-        s.format_duration()
+        format_live_label(template, s, max_width)
     )
 }
 
+/// The untruncated details shown at the top of a live stream's submenu:
+/// title (when set), category, uptime and viewers.
+pub(crate) fn format_stream_details(s: &Stream) -> Vec<String> {
+    let mut details = Vec::new();
+    if !s.title.trim().is_empty() {
+        details.push(s.title.trim().to_string());
+    }
+    if !s.game_name.is_empty() {
+        details.push(s.game_name.clone());
+    }
+    details.push(format!("Live for {}", s.format_duration()));
+    details.push(format!("{} viewers", s.format_viewer_count()));
+    details
+}
+
+/// Prefixes a live label with an hourglass when the viewer's watch streak is at risk.
+///
+/// Format: `"⏳3 <label>"`
+pub(crate) fn with_streak_marker(label: String, streak_at_risk: Option<u32>) -> String {
+    match streak_at_risk {
+        Some(n) => format!("\u{23F3}{n} {label}"),
+        None => label,
+    }
+}
+
 /// Formats a scheduled stream label with optional sparkle/star prefix.
 ///
 /// Format: `"[✨ ][★ ]StreamerName - Tomorrow 3:00 PM"`
-pub(crate) fn format_scheduled_label_with_star(s: &ScheduledStream, star: bool) -> String {
+pub(crate) fn format_scheduled_label_with_star(
+    s: &ScheduledStream,
+    star: bool,
+    max_width: usize,
+) -> String {
     let sparkle = if s.is_inferred { "\u{2728} " } else { "" };
     let star_str = if star { "\u{2605} " } else { "" };
     format!(
         "{}{}{} - {}",
         sparkle,
         star_str,
-        s.broadcaster_name,
+        truncate_width(&s.broadcaster_name, max_width),
         s.format_start_time()
     )
 }
 
+/// Formats a scheduled stream label for use under a day header.
+///
+/// Format: `"[✨ ][★ ]StreamerName - 3:00 PM"`
+pub(crate) fn format_scheduled_time_label_with_star(
+    s: &ScheduledStream,
+    star: bool,
+    max_width: usize,
+) -> String {
+    let sparkle = if s.is_inferred { "\u{2728} " } else { "" };
+    let star_str = if star { "\u{2605} " } else { "" };
+    format!(
+        "{}{}{} - {}",
+        sparkle,
+        star_str,
+        truncate_width(&s.broadcaster_name, max_width),
+        s.start_time.with_timezone(&Local).format("%-I:%M %p")
+    )
+}
+
+/// Formats how long ago something happened: `"<1h ago"`, `"5h ago"` or `"3d ago"`.
+pub fn format_ago(ago: Duration) -> String {
+    match ago.num_hours() {
+        h if h < 1 => "<1h ago".to_string(),
+        h if h < 48 => format!("{h}h ago"),
+        _ => format!("{}d ago", ago.num_days()),
+    }
+}
+
+/// Lists Favourites that aren't in `live_logins`, most recently seen first.
+///
+/// Label format: `"StreamerName - seen 3d ago"`, or `"StreamerName - not seen yet"`
+/// for Favourites the app has never seen live.
+pub fn compute_offline_favourites(
+    live_logins: &HashSet<String>,
+    config: &DisplayConfig,
+    now: DateTime<Utc>,
+) -> Vec<OfflineFavourite> {
+    let mut offline: Vec<(&String, &StreamerSettings, Option<&DateTime<Utc>>)> = config
+        .streamer_settings
+        .iter()
+        .filter(|(login, s)| {
+            s.importance == StreamerImportance::Favourite && !live_logins.contains(*login)
+        })
+        .map(|(login, s)| (login, s, config.last_seen.get(login)))
+        .collect();
+    offline.sort_by(|a, b| {
+        b.2.cmp(&a.2).then_with(|| {
+            a.1.display_name
+                .to_lowercase()
+                .cmp(&b.1.display_name.to_lowercase())
+        })
+    });
+    offline
+        .into_iter()
+        .map(|(login, s, seen)| {
+            let name = truncate_width(&s.display_name, config.max_label_width);
+            let label = match seen {
+                Some(&seen) => format!("{name} - seen {}", format_ago(now - seen)),
+                None => format!("{name} - not seen yet"),
+            };
+            OfflineFavourite {
+                user_login: login.clone(),
+                label,
+            }
+        })
+        .collect()
+}
+
+/// Formats an offline pinned channel, with what it was last streaming when
+/// it has been seen live this run.
+///
+/// Format: `"StreamerName - offline"` or `"StreamerName - offline (was GameName)"`
+pub fn format_pinned_offline_label(
+    pin: &PinnedStream,
+    last_live: Option<&Stream>,
+    max_width: usize,
+) -> String {
+    let name = truncate_width(
+        last_live.map_or(&pin.display_name, |s| &s.user_name),
+        max_width,
+    );
+    match last_live.filter(|s| !s.game_name.is_empty()) {
+        Some(s) => format!(
+            "{name} - offline (was {})",
+            truncate_width(&s.game_name, max_width)
+        ),
+        None => format!("{name} - offline"),
+    }
+}
+
+/// Lists recently ended streams, newest first, skipping Ignore streamers.
+///
+/// Label format: `"StreamerName - GameName (ended 12m ago)"`
+pub fn compute_recently_ended(
+    config: &DisplayConfig,
+    now: DateTime<Utc>,
+) -> Vec<RecentlyEndedEntry> {
+    config
+        .recently_ended
+        .iter()
+        .filter(|e| shows_in_menu(&e.stream.user_login, &config.streamer_settings))
+        .map(|e| RecentlyEndedEntry {
+            user_login: e.stream.user_login.clone(),
+            video_id: e.video.as_ref().map(|v| v.id.clone()),
+            label: format!(
+                "{} - {} (ended {}m ago)",
+                truncate_width(&e.stream.user_name, config.max_label_width),
+                truncate_width(&e.stream.game_name, config.max_label_width),
+                (now - e.ended_at).num_minutes().max(0)
+            ),
+        })
+        .collect()
+}
+
+/// Formats a past broadcast label.
+///
+/// Format: `"Jan 15 - Title (3h 8m)"`, with the title cut to `max_width` columns.
+pub(crate) fn format_video_label(v: &Video, max_width: usize) -> String {
+    format!(
+        "{} - {} ({})",
+        v.created_at.with_timezone(&Local).format("%b %-d"),
+        truncate_width(&v.title, max_width),
+        v.format_duration()
+    )
+}
+
+/// Groups each Favourite's VODs under their display name, sorted by name.
+pub fn compute_past_broadcasts(
+    recent_videos: &HashMap<String, Vec<Video>>,
+    max_label_width: usize,
+) -> Vec<PastBroadcasts> {
+    let mut sections: Vec<PastBroadcasts> = recent_videos
+        .values()
+        .filter(|videos| !videos.is_empty())
+        .map(|videos| PastBroadcasts {
+            display_name: videos[0].user_name.clone(),
+            videos: videos
+                .iter()
+                .map(|v| VideoEntry {
+                    id: v.id.clone(),
+                    label: format_video_label(v, max_label_width),
+                })
+                .collect(),
+        })
+        .collect();
+    sections.sort_by_key(|s| s.display_name.to_lowercase());
+    sections
+}
+
+/// Names the day a scheduled stream starts on, relative to `today`.
+///
+/// Format: `"Today"`, `"Tomorrow"`, a weekday within the coming week
+/// (`"Friday"`), or `"Friday 14 Mar"` beyond that.
+pub fn format_schedule_day(day: NaiveDate, today: NaiveDate) -> String {
+    match (day - today).num_days() {
+        0 => "Today".to_string(),
+        1 => "Tomorrow".to_string(),
+        2..=6 => day.format("%A").to_string(),
+        _ => day.format("%A %-d %b").to_string(),
+    }
+}
+
 /// Formats a stream for a category submenu (no game name since it's implied).
 ///
 /// Format: `"StreamerName (1.2k)"`
-pub(crate) fn format_category_stream_label(s: &Stream) -> String {
-    format!("{} ({})", s.user_name, s.format_viewer_count())
+pub(crate) fn format_category_stream_label(s: &Stream, max_width: usize) -> String {
+    format!(
+        "{} ({})",
+        truncate_width(&s.user_name, max_width),
+        s.format_viewer_count()
+    )
+}
+
+/// Turns configured custom actions into footer items, skipping incomplete ones.
+///
+/// Command confirmation format: `"Run: obs --startstreaming"`
+pub fn compute_footer_actions(actions: &[CustomAction]) -> Vec<FooterAction> {
+    actions
+        .iter()
+        .filter_map(|action| {
+            let label = action.label.trim();
+            let confirm_label = match &action.target {
+                CustomActionTarget::Url(url) if !url.trim().is_empty() => None,
+                CustomActionTarget::Command(cmd) if !cmd.trim().is_empty() => {
+                    Some(format!("Run: {}", truncate(cmd.trim(), 60)))
+                }
+                _ => return None,
+            };
+            (!label.is_empty()).then(|| FooterAction {
+                key: custom_action::action_key(action),
+                label: label.to_string(),
+                confirm_label,
+            })
+        })
+        .collect()
+}
+
+/// Footer label for undoing a streamer's settings change: `"Undo Change to ninja"`
+pub fn format_undo_label(user_login: &str) -> String {
+    format!("Undo Change to {user_login}")
+}
+
+/// Footer label for the last successful refresh: `"Updated just now"` or
+/// `"Updated 2m ago"`, flagged with ⚠ once it's more than
+/// `STALE_AFTER_POLLS` poll intervals old.
+pub fn format_refresh_status(
+    last_refresh: DateTime<Utc>,
+    poll_interval_sec: u64,
+    now: DateTime<Utc>,
+) -> String {
+    let age = now - last_refresh;
+    let mins = age.num_minutes();
+    let ago = if mins < 1 {
+        "just now".to_string()
+    } else if mins < 60 {
+        format!("{mins}m ago")
+    } else {
+        format!("{}h ago", mins / 60)
+    };
+    let stale = age
+        .to_std()
+        .is_ok_and(|age| age.as_secs() > poll_interval_sec * STALE_AFTER_POLLS);
+    if stale {
+        format!("\u{26A0} Updated {ago}")
+    } else {
+        format!("Updated {ago}")
+    }
+}
+
+/// Menu entry while refreshes are failing: `"⚠ Connection problems since 2:05 PM"`
+pub fn format_connection_problem(since: DateTime<Utc>) -> String {
+    format!(
+        "\u{26A0} Connection problems since {}",
+        since.with_timezone(&Local).format("%-I:%M %p")
+    )
+}
+
+/// Tray icon tooltip while online.
+pub const TOOLTIP: &str = "Twitch Tray";
+
+/// Tray icon tooltip, noting when Twitch stopped being reachable.
+pub fn format_tooltip(offline_since: Option<DateTime<Utc>>) -> String {
+    match offline_since {
+        Some(since) => format!(
+            "{TOOLTIP} (offline since {})",
+            since.with_timezone(&Local).format("%-I:%M %p")
+        ),
+        None => TOOLTIP.to_string(),
+    }
+}
+
+/// Menu entry while refreshing the session token keeps failing.
+pub const LOGIN_WARNING: &str = "\u{26A0} Couldn't renew Twitch login: log in again";
+
+/// Menu entry while the session token lacks scopes the app now needs.
+pub const SCOPES_WARNING: &str = "\u{26A0} Log in again to grant new Twitch permissions";
+
+/// Footer label while every notification is snoozed: `"Notifications
+/// snoozed until 2:05 PM"`, with the weekday once it's not today.
+pub fn format_snooze_label(until: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let until = until.with_timezone(&Local);
+    let format = if until.date_naive() == now.with_timezone(&Local).date_naive() {
+        "%-I:%M %p"
+    } else {
+        "%a %-I:%M %p"
+    };
+    format!("Notifications snoozed until {}", until.format(format))
+}
+
+/// Formats the time until a scheduled start: `"<1m"`, `"42m"`, `"2h"` or `"1h 5m"`.
+pub fn format_countdown(until: Duration) -> String {
+    let mins = until.num_minutes();
+    if mins < 1 {
+        return "<1m".to_string();
+    }
+    let (hours, mins) = (mins / 60, mins % 60);
+    match (hours, mins) {
+        (0, m) => format!("{m}m"),
+        (h, 0) => format!("{h}h"),
+        (h, m) => format!("{h}h {m}m"),
+    }
+}
+
+/// Builds the schedule section header, with a countdown to `next_up` if it hasn't started.
+///
+/// Format: `"Scheduled (Next 6h)"` or `"Scheduled (Next 6h) · Next in 42m — StreamerX"`
+pub fn format_schedule_header(
+    lookahead_hours: u64,
+    next_up: Option<&NextScheduled>,
+    now: DateTime<Utc>,
+) -> String {
+    let base = format!("Scheduled (Next {lookahead_hours}h)");
+    match next_up {
+        Some(next) if next.start_time > now => format!(
+            "{base} \u{00B7} Next in {} \u{2014} {}",
+            format_countdown(next.start_time - now),
+            next.broadcaster_name
+        ),
+        _ => base,
+    }
 }
 
 /// Computes a fully resolved, render-ready display state from raw data.
@@ -154,7 +765,7 @@ pub(crate) fn format_category_stream_label(s: &Stream) -> String {
 /// `now` is passed in rather than calling `Utc::now()` directly so the function
 /// is deterministically testable.
 pub fn compute_display_state(
-    mut streams: Vec<Stream>,
+    streams: Vec<Stream>,
     scheduled: Vec<ScheduledStream>,
     schedules_loaded: bool,
     followed_categories: &[FollowedCategory],
@@ -164,19 +775,40 @@ pub fn compute_display_state(
 ) -> DisplayState {
     let settings = &config.streamer_settings;
 
+    // Every followed live channel, before pins and Ignore filtering take any out
+    let followed_ids: HashSet<String> = streams.iter().map(|s| s.user_id.clone()).collect();
+
     // --- Live section ---
 
+    // Pinned streams come out first, before Ignore filtering: a pin is an
+    // explicit request to see the channel
+    let (mut pinned_live, mut streams): (Vec<Stream>, Vec<Stream>) =
+        streams.into_iter().partition(|s| {
+            config
+                .pinned_streams
+                .iter()
+                .any(|p| p.user_login == s.user_login)
+        });
+
     // Filter out Ignore streamers
-    streams.retain(|s| get_importance(&s.user_login, settings) != StreamerImportance::Ignore);
+    streams.retain(|s| shows_in_menu(&s.user_login, settings));
 
     // Remember which broadcasters are live (used for schedule filtering below)
-    let live_logins: HashSet<String> = streams.iter().map(|s| s.user_login.clone()).collect();
+    let live_logins: HashSet<String> = streams
+        .iter()
+        .chain(&pinned_live)
+        .map(|s| s.user_login.clone())
+        .collect();
 
-    // Sort: Favourites first, then by viewer count descending
+    // Sort: Favourites first, then (optionally) favourites by watch recency,
+    // then by the configured live sort
     streams.sort_by(|a, b| {
-        let a_fav = get_importance(&a.user_login, settings) == StreamerImportance::Favourite;
-        let b_fav = get_importance(&b.user_login, settings) == StreamerImportance::Favourite;
-        b_fav.cmp(&a_fav).then(b.viewer_count.cmp(&a.viewer_count))
+        let a_fav = is_favourite(&a.user_login, settings);
+        let b_fav = is_favourite(&b.user_login, settings);
+        b_fav
+            .cmp(&a_fav)
+            .then_with(|| favourite_recency_order(a, b, a_fav && b_fav, config))
+            .then_with(|| config.live_sort.compare(a, b, &config.last_notified))
     });
 
     let (live_visible_raw, live_overflow_raw) = if streams.len() > config.live_limit {
@@ -186,59 +818,100 @@ pub fn compute_display_state(
         (streams, Vec::new())
     };
 
+    let to_stream_entry = |s: Stream| {
+        let is_fav = is_favourite(&s.user_login, settings);
+        let is_hot = config.hot_stream_ids.contains(&s.user_id);
+        let label = with_streak_marker(
+            format_stream_label_with_star(
+                &s,
+                is_fav,
+                is_hot,
+                &config.live_label_format,
+                config.max_label_width,
+            ),
+            config.streaks_at_risk.get(&s.user_id).copied(),
+        );
+        StreamEntry {
+            stream: s,
+            label,
+            is_hot,
+        }
+    };
+
     let live_section = LiveSection {
-        visible: live_visible_raw
-            .into_iter()
-            .map(|s| {
-                let is_fav =
-                    get_importance(&s.user_login, settings) == StreamerImportance::Favourite;
-                let is_hot = config.hot_stream_ids.contains(&s.user_id);
-                let label = format_stream_label_with_star(&s, is_fav, is_hot);
-                StreamEntry {
-                    stream: s,
-                    label,
-                    is_hot,
-                }
-            })
-            .collect(),
-        overflow: live_overflow_raw
-            .into_iter()
-            .map(|s| {
-                let is_fav =
-                    get_importance(&s.user_login, settings) == StreamerImportance::Favourite;
-                let is_hot = config.hot_stream_ids.contains(&s.user_id);
-                let label = format_stream_label_with_star(&s, is_fav, is_hot);
-                StreamEntry {
-                    stream: s,
-                    label,
-                    is_hot,
-                }
-            })
-            .collect(),
+        visible: live_visible_raw.into_iter().map(to_stream_entry).collect(),
+        overflow: live_overflow_raw.into_iter().map(to_stream_entry).collect(),
+        cached: config.followed_cached,
     };
 
+    let pinned: Vec<PinnedEntry> = config
+        .pinned_streams
+        .iter()
+        .map(|pin| {
+            match pinned_live
+                .iter()
+                .position(|s| s.user_login == pin.user_login)
+            {
+                Some(i) => {
+                    let entry = to_stream_entry(pinned_live.swap_remove(i));
+                    PinnedEntry {
+                        user_login: pin.user_login.clone(),
+                        label: entry.label.clone(),
+                        live: Some(entry),
+                    }
+                }
+                None => PinnedEntry {
+                    user_login: pin.user_login.clone(),
+                    label: format_pinned_offline_label(
+                        pin,
+                        config.pinned_last_live.get(&pin.user_login),
+                        config.max_label_width,
+                    ),
+                    live: None,
+                },
+            }
+        })
+        .collect();
+
     // --- Category sections ---
 
     let mut category_sections = Vec::new();
     for category in followed_categories {
         if let Some(cat_streams) = category_streams.get(&category.id) {
-            if !cat_streams.is_empty() {
-                let mut sorted = cat_streams.clone();
+            let mut sorted = cat_streams.clone();
+            if config.hide_followed_in_categories {
+                sorted.retain(|s| !followed_ids.contains(&s.user_id));
+            }
+            if !sorted.is_empty() {
                 sorted.sort_by(|a, b| b.viewer_count.cmp(&a.viewer_count));
-                sorted.truncate(10);
+                // A full first page means Twitch probably has more
+                let has_more = cat_streams.len() >= CATEGORY_MENU_LIMIT;
+                sorted.truncate(CATEGORY_MENU_LIMIT);
 
                 let total_viewers: u32 = sorted.iter().map(|s| s.viewer_count).sum();
-                let header = format!("{} ({})", category.name, format_viewer_count(total_viewers));
+                let mut header = format!(
+                    "{} ({})",
+                    truncate_width(&category.name, config.max_label_width),
+                    format_viewer_count(total_viewers)
+                );
+                if config.cached_categories.contains(&category.id) {
+                    header.push_str(CACHED_MARKER);
+                }
 
                 let entries = sorted
                     .into_iter()
                     .map(|s| {
-                        let label = format_category_stream_label(&s);
+                        let label = format_category_stream_label(&s, config.max_label_width);
                         CategoryStreamEntry { stream: s, label }
                     })
                     .collect();
 
-                category_sections.push(CategorySection { header, entries });
+                category_sections.push(CategorySection {
+                    category_id: category.id.clone(),
+                    header,
+                    entries,
+                    has_more,
+                });
             }
         }
     }
@@ -248,14 +921,49 @@ pub fn compute_display_state(
     // A scheduled stream is "covered" if the broadcaster is live and the
     // scheduled start is within the next 60 minutes — hide it from the schedule list.
     let soon_threshold = now + Duration::minutes(LIVE_COVERS_SCHEDULE_WINDOW_MIN);
-    let schedule_header = format!("Scheduled (Next {}h)", config.schedule_lookahead_hours);
 
     let filtered_scheduled: Vec<_> = scheduled
         .into_iter()
-        .filter(|s| get_importance(&s.broadcaster_login, settings) != StreamerImportance::Ignore)
+        .filter(|s| shows_in_menu(&s.broadcaster_login, settings))
         .filter(|s| !(live_logins.contains(&s.broadcaster_login) && s.start_time <= soon_threshold))
         .collect();
 
+    let next_up = filtered_scheduled
+        .iter()
+        .filter(|s| s.start_time > now)
+        .min_by_key(|s| s.start_time)
+        .map(|s| NextScheduled {
+            broadcaster_name: truncate_width(&s.broadcaster_name, config.max_label_width),
+            start_time: s.start_time,
+        });
+    let schedule_header =
+        format_schedule_header(config.schedule_lookahead_hours, next_up.as_ref(), now);
+
+    // With more than a day of lookahead, entries sit under day headers and
+    // their labels drop the now-redundant day.
+    let today = now.with_timezone(&Local).date_naive();
+    let group_by_day = config.schedule_lookahead_hours > GROUP_SCHEDULE_BY_DAY_AFTER_HOURS;
+    let to_scheduled_entry = |s: ScheduledStream| {
+        let is_fav = is_favourite(&s.broadcaster_login, settings);
+        let (label, day) = if group_by_day {
+            let day = s.start_time.with_timezone(&Local).date_naive();
+            (
+                format_scheduled_time_label_with_star(&s, is_fav, config.max_label_width),
+                Some(format_schedule_day(day, today)),
+            )
+        } else {
+            (
+                format_scheduled_label_with_star(&s, is_fav, config.max_label_width),
+                None,
+            )
+        };
+        ScheduledEntry {
+            scheduled: s,
+            label,
+            day,
+        }
+    };
+
     let (sched_visible_raw, sched_overflow_raw) =
         if filtered_scheduled.len() > config.schedule_limit {
             let (main, over) = filtered_scheduled.split_at(config.schedule_limit);
@@ -266,38 +974,68 @@ pub fn compute_display_state(
 
     let schedule_section = ScheduleSection {
         header: schedule_header,
+        lookahead_hours: config.schedule_lookahead_hours,
+        next_up,
         visible: sched_visible_raw
             .into_iter()
-            .map(|s| {
-                let is_fav =
-                    get_importance(&s.broadcaster_login, settings) == StreamerImportance::Favourite;
-                let label = format_scheduled_label_with_star(&s, is_fav);
-                ScheduledEntry {
-                    scheduled: s,
-                    label,
-                }
-            })
+            .map(&to_scheduled_entry)
             .collect(),
         overflow: sched_overflow_raw
             .into_iter()
-            .map(|s| {
-                let is_fav =
-                    get_importance(&s.broadcaster_login, settings) == StreamerImportance::Favourite;
-                let label = format_scheduled_label_with_star(&s, is_fav);
-                ScheduledEntry {
-                    scheduled: s,
-                    label,
-                }
-            })
+            .map(&to_scheduled_entry)
             .collect(),
         schedules_loaded,
     };
 
+    let live_count = config.live_count_badge.then(|| {
+        live_section.visible.len()
+            + live_section.overflow.len()
+            + pinned.iter().filter(|p| p.live.is_some()).count()
+    });
+
     DisplayState {
         authenticated: true,
+        authenticating: false,
+        login_code: None,
+        pinned,
         live_section,
         schedule_section,
         category_sections,
+        offline_favourites: compute_offline_favourites(&live_logins, config, now),
+        recently_ended: compute_recently_ended(config, now),
+        past_broadcasts: compute_past_broadcasts(&config.recent_videos, config.max_label_width),
+        footer_actions: compute_footer_actions(&config.custom_actions),
+        undo_label: config.undoable_change.as_deref().map(format_undo_label),
+        unread: config.unread,
+        refreshing: config.refreshing,
+        refresh_status: config.last_refresh.map(|last_refresh| RefreshStatus {
+            last_refresh,
+            poll_interval_sec: config.poll_interval_sec,
+            label: format_refresh_status(last_refresh, config.poll_interval_sec, now),
+        }),
+        connection_problem: config
+            .connection_problem_since
+            .map(format_connection_problem),
+        tooltip: format_tooltip(config.offline_since),
+        login_warning: if config.token_refresh_failing {
+            Some(LOGIN_WARNING.to_string())
+        } else if config.scopes_missing {
+            Some(SCOPES_WARNING.to_string())
+        } else {
+            None
+        },
+        live_count,
+        icon_theme: config.icon_theme,
+        snooze_label: config
+            .notification_snoozes
+            .all_until
+            .map(|until| format_snooze_label(until, now)),
+        snoozed_streamers: config
+            .notification_snoozes
+            .streamers
+            .keys()
+            .cloned()
+            .collect(),
     }
 }
 
@@ -305,7 +1043,8 @@ pub fn compute_display_state(
 mod tests {
     use super::*;
     use crate::test_helpers::{make_scheduled, make_stream};
-    use chrono::Duration;
+    use chrono::{Duration, TimeZone};
+    use twitch_backend::config::{DEFAULT_LIVE_LABEL_FORMAT, DEFAULT_MAX_LABEL_WIDTH};
 
     // =========================================================
     // Helpers
@@ -334,6 +1073,34 @@ mod tests {
             live_limit: 10,
             schedule_limit: 5,
             hot_stream_ids: HashSet::new(),
+            streaks_at_risk: HashMap::new(),
+            live_sort: LiveSortMode::Viewers,
+            favourite_sort_mode: FavouriteSortMode::Viewers,
+            last_watched: HashMap::new(),
+            last_seen: HashMap::new(),
+            last_notified: HashMap::new(),
+            custom_actions: Vec::new(),
+            max_label_width: DEFAULT_MAX_LABEL_WIDTH,
+            live_label_format: DEFAULT_LIVE_LABEL_FORMAT.to_string(),
+            undoable_change: None,
+            unread: false,
+            live_count_badge: false,
+            icon_theme: IconTheme::default(),
+            recent_videos: HashMap::new(),
+            recently_ended: Vec::new(),
+            refreshing: false,
+            last_refresh: None,
+            poll_interval_sec: 60,
+            connection_problem_since: None,
+            offline_since: None,
+            token_refresh_failing: false,
+            scopes_missing: false,
+            followed_cached: false,
+            cached_categories: HashSet::new(),
+            pinned_streams: Vec::new(),
+            pinned_last_live: HashMap::new(),
+            hide_followed_in_categories: false,
+            notification_snoozes: NotificationSnoozes::default(),
         }
     }
 
@@ -346,6 +1113,11 @@ mod tests {
                 display_name: user_login.to_string(),
                 importance,
                 hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: None,
+                open_with: None,
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
             },
         );
         DisplayConfig {
@@ -354,6 +1126,34 @@ mod tests {
             live_limit: 10,
             schedule_limit: 5,
             hot_stream_ids: HashSet::new(),
+            streaks_at_risk: HashMap::new(),
+            live_sort: LiveSortMode::Viewers,
+            favourite_sort_mode: FavouriteSortMode::Viewers,
+            last_watched: HashMap::new(),
+            last_seen: HashMap::new(),
+            last_notified: HashMap::new(),
+            custom_actions: Vec::new(),
+            max_label_width: DEFAULT_MAX_LABEL_WIDTH,
+            live_label_format: DEFAULT_LIVE_LABEL_FORMAT.to_string(),
+            undoable_change: None,
+            unread: false,
+            live_count_badge: false,
+            icon_theme: IconTheme::default(),
+            recent_videos: HashMap::new(),
+            recently_ended: Vec::new(),
+            refreshing: false,
+            last_refresh: None,
+            poll_interval_sec: 60,
+            connection_problem_since: None,
+            offline_since: None,
+            token_refresh_failing: false,
+            scopes_missing: false,
+            followed_cached: false,
+            cached_categories: HashSet::new(),
+            pinned_streams: Vec::new(),
+            pinned_last_live: HashMap::new(),
+            hide_followed_in_categories: false,
+            notification_snoozes: NotificationSnoozes::default(),
         }
     }
 
@@ -367,7 +1167,13 @@ mod tests {
         s.game_name = "Fortnite".to_string();
         s.viewer_count = 5000;
         s.started_at = Utc::now() - Duration::hours(2);
-        let label = format_stream_label_with_star(&s, false, false);
+        let label = format_stream_label_with_star(
+            &s,
+            false,
+            false,
+            DEFAULT_LIVE_LABEL_FORMAT,
+            DEFAULT_MAX_LABEL_WIDTH,
+        );
 
         assert!(label.contains("Ninja"), "should contain streamer name");
         assert!(label.contains("Fortnite"), "should contain game name");
@@ -380,16 +1186,41 @@ mod tests {
         let mut s = make_stream("streamer", "Streamer");
         s.game_name = "This Is A Very Long Game Name That Should Be Truncated".to_string();
         s.viewer_count = 1000;
-        let label = format_stream_label_with_star(&s, false, false);
+        let label = format_stream_label_with_star(
+            &s,
+            false,
+            false,
+            DEFAULT_LIVE_LABEL_FORMAT,
+            DEFAULT_MAX_LABEL_WIDTH,
+        );
 
         assert!(label.contains("..."), "long game name should be truncated");
     }
 
+    #[test]
+    fn format_stream_label_cuts_wide_names_by_display_width() {
+        // 20 CJK characters: 40 columns
+        let name = "\u{5b9f}".repeat(20);
+        let s = make_stream("wide", &name);
+        let label = format_stream_label_with_star(&s, false, false, DEFAULT_LIVE_LABEL_FORMAT, 10);
+
+        assert!(
+            label.starts_with(&format!("{}... - ", "\u{5b9f}".repeat(3))),
+            "name should fit 10 columns: {label}"
+        );
+    }
+
     #[test]
     fn format_stream_label_small_viewers_exact() {
         let mut s = make_stream("smallstreamer", "SmallStreamer");
         s.viewer_count = 42;
-        let label = format_stream_label_with_star(&s, false, false);
+        let label = format_stream_label_with_star(
+            &s,
+            false,
+            false,
+            DEFAULT_LIVE_LABEL_FORMAT,
+            DEFAULT_MAX_LABEL_WIDTH,
+        );
 
         assert!(
             label.contains("42"),
@@ -401,8 +1232,20 @@ mod tests {
     #[test]
     fn format_stream_label_star_prefix() {
         let s = make_stream("fav", "Fav");
-        let with_star = format_stream_label_with_star(&s, true, false);
-        let without_star = format_stream_label_with_star(&s, false, false);
+        let with_star = format_stream_label_with_star(
+            &s,
+            true,
+            false,
+            DEFAULT_LIVE_LABEL_FORMAT,
+            DEFAULT_MAX_LABEL_WIDTH,
+        );
+        let without_star = format_stream_label_with_star(
+            &s,
+            false,
+            false,
+            DEFAULT_LIVE_LABEL_FORMAT,
+            DEFAULT_MAX_LABEL_WIDTH,
+        );
 
         assert!(
             with_star.starts_with('\u{2605}'),
@@ -414,6 +1257,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn format_stream_label_uses_template_after_markers() {
+        let mut s = make_stream("fav", "Fav");
+        s.viewer_count = 42;
+        let label = format_stream_label_with_star(&s, true, true, "{name} [{viewers}]", 25);
+        assert_eq!(label, "\u{1F525} \u{2605} Fav [42]");
+    }
+
     // =========================================================
     // format_scheduled_label_with_star
     // =========================================================
@@ -421,7 +1272,7 @@ mod tests {
     #[test]
     fn format_scheduled_label_basic() {
         let sched = make_scheduled("StreamerName", 5);
-        let label = format_scheduled_label_with_star(&sched, false);
+        let label = format_scheduled_label_with_star(&sched, false, DEFAULT_MAX_LABEL_WIDTH);
 
         assert!(
             label.starts_with("StreamerName - "),
@@ -433,7 +1284,7 @@ mod tests {
     #[test]
     fn format_scheduled_label_contains_time() {
         let sched = make_scheduled("TestStreamer", 2);
-        let label = format_scheduled_label_with_star(&sched, false);
+        let label = format_scheduled_label_with_star(&sched, false, DEFAULT_MAX_LABEL_WIDTH);
 
         let has_time = label.contains("Today")
             || label.contains("Tomorrow")
@@ -448,7 +1299,7 @@ mod tests {
     fn format_scheduled_label_sparkle_for_inferred() {
         let mut sched = make_scheduled("Streamer", 3);
         sched.is_inferred = true;
-        let label = format_scheduled_label_with_star(&sched, false);
+        let label = format_scheduled_label_with_star(&sched, false, DEFAULT_MAX_LABEL_WIDTH);
 
         assert!(
             label.starts_with('\u{2728}'),
@@ -459,7 +1310,7 @@ mod tests {
     #[test]
     fn format_scheduled_label_star_for_favourite() {
         let sched = make_scheduled("Streamer", 3);
-        let label = format_scheduled_label_with_star(&sched, true);
+        let label = format_scheduled_label_with_star(&sched, true, DEFAULT_MAX_LABEL_WIDTH);
 
         assert!(label.contains('\u{2605}'), "favourite should contain ★");
     }
@@ -468,7 +1319,7 @@ mod tests {
     fn format_scheduled_label_sparkle_and_star() {
         let mut sched = make_scheduled("Streamer", 3);
         sched.is_inferred = true;
-        let label = format_scheduled_label_with_star(&sched, true);
+        let label = format_scheduled_label_with_star(&sched, true, DEFAULT_MAX_LABEL_WIDTH);
 
         assert!(label.starts_with('\u{2728}'), "should start with ✨");
         assert!(label.contains('\u{2605}'), "should also contain ★");
@@ -509,6 +1360,55 @@ mod tests {
         assert_eq!(all_live[0].stream.user_login, "normaluser");
     }
 
+    fn pin(user_login: &str) -> PinnedStream {
+        PinnedStream {
+            user_login: user_login.to_string(),
+            display_name: user_login.to_string(),
+        }
+    }
+
+    #[test]
+    fn pinned_streams_move_out_of_live_section() {
+        let streams = vec![make_stream("1", "alice"), make_stream("2", "bob")];
+        let (cats, cat_streams) = no_categories();
+        let mut config = config_with_importance("alice", StreamerImportance::Ignore);
+        config.pinned_streams = vec![pin("carol"), pin("alice")];
+        config.live_count_badge = true;
+
+        let state = compute_display_state(
+            streams,
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &config,
+            Utc::now(),
+        );
+
+        let pinned: Vec<_> = state.pinned.iter().map(|p| p.user_login.as_str()).collect();
+        assert_eq!(pinned, vec!["carol", "alice"]);
+        assert!(state.pinned[0].live.is_none(), "carol is offline");
+        assert!(state.pinned[1].live.is_some(), "pins override Ignore");
+        assert_eq!(state.live_section.visible.len(), 1);
+        assert_eq!(state.live_section.visible[0].stream.user_login, "bob");
+        assert_eq!(state.live_count, Some(2));
+    }
+
+    #[test]
+    fn offline_pin_shows_last_category() {
+        let mut last = make_stream("1", "Alice");
+        last.game_name = "Chess".to_string();
+
+        assert_eq!(
+            format_pinned_offline_label(&pin("alice"), Some(&last), DEFAULT_MAX_LABEL_WIDTH),
+            "Alice - offline (was Chess)"
+        );
+        assert_eq!(
+            format_pinned_offline_label(&pin("alice"), None, DEFAULT_MAX_LABEL_WIDTH),
+            "alice - offline"
+        );
+    }
+
     #[test]
     fn live_streams_sorted_favourites_first() {
         // normal_high has more viewers, fav_low is a favourite — fav should appear first
@@ -639,99 +1539,805 @@ mod tests {
     }
 
     // =========================================================
-    // compute_display_state — hotness
+    // compute_display_state — favourite watch recency
     // =========================================================
 
+    /// Config where every given login is a favourite.
+    fn config_with_favourites(logins: &[&str]) -> DisplayConfig {
+        let streamer_settings = logins
+            .iter()
+            .map(|login| {
+                (
+                    (*login).to_string(),
+                    StreamerSettings {
+                        display_name: (*login).to_string(),
+                        importance: StreamerImportance::Favourite,
+                        hotness_z_threshold_override: None,
+                        notification_open_mode: None,
+                        notification_sound: None,
+                        open_with: None,
+                        schedule_reminder_min: None,
+                        notify_on_category_change: true,
+                    },
+                )
+            })
+            .collect();
+        DisplayConfig {
+            streamer_settings,
+            ..default_config()
+        }
+    }
+
+    fn live_logins(state: &DisplayState) -> Vec<&str> {
+        state
+            .live_section
+            .visible
+            .iter()
+            .map(|e| e.stream.user_login.as_str())
+            .collect()
+    }
+
     #[test]
-    fn hot_stream_has_fire_in_label() {
-        let mut s = make_stream("hotuser", "HotUser");
-        s.user_id = "uid_hot".to_string();
+    fn recently_watched_favourite_sorts_before_bigger_favourite() {
+        let now = Utc::now();
+        let big = stream_with_viewers("big", 50_000);
+        let small = stream_with_viewers("small", 100);
         let (cats, cat_streams) = no_categories();
 
         let state = compute_display_state(
-            vec![s],
+            vec![big, small],
             no_scheduled(),
             true,
             &cats,
             &cat_streams,
             &DisplayConfig {
-                hot_stream_ids: HashSet::from(["uid_hot".to_string()]),
-                ..default_config()
+                favourite_sort_mode: FavouriteSortMode::RecentlyWatched,
+                last_watched: HashMap::from([
+                    ("big".to_string(), now - Duration::days(3)),
+                    ("small".to_string(), now - Duration::hours(1)),
+                ]),
+                ..config_with_favourites(&["big", "small"])
             },
-            Utc::now(),
+            now,
         );
 
-        assert!(
-            state.live_section.visible[0].label.contains('\u{1F525}'),
-            "hot stream label should contain \u{1F525}: {}",
-            state.live_section.visible[0].label
-        );
-        assert!(state.live_section.visible[0].is_hot);
+        assert_eq!(live_logins(&state), vec!["small", "big"]);
     }
 
     #[test]
-    fn non_hot_stream_has_no_fire_in_label() {
-        let s = make_stream("cooluser", "CoolUser");
+    fn never_watched_favourite_sorts_after_watched_favourites() {
+        let now = Utc::now();
+        let unwatched = stream_with_viewers("unwatched", 50_000);
+        let watched = stream_with_viewers("watched", 100);
         let (cats, cat_streams) = no_categories();
 
         let state = compute_display_state(
-            vec![s],
+            vec![unwatched, watched],
             no_scheduled(),
             true,
             &cats,
             &cat_streams,
-            &default_config(),
-            Utc::now(),
+            &DisplayConfig {
+                favourite_sort_mode: FavouriteSortMode::RecentlyWatched,
+                last_watched: HashMap::from([("watched".to_string(), now)]),
+                ..config_with_favourites(&["unwatched", "watched"])
+            },
+            now,
         );
 
-        assert!(
-            !state.live_section.visible[0].label.contains('\u{1F525}'),
-            "non-hot stream should not have fire"
-        );
-        assert!(!state.live_section.visible[0].is_hot);
+        assert_eq!(live_logins(&state), vec!["watched", "unwatched"]);
     }
 
-    // =========================================================
+    #[test]
+    fn watch_recency_ignored_in_viewers_mode() {
+        let now = Utc::now();
+        let big = stream_with_viewers("big", 50_000);
+        let small = stream_with_viewers("small", 100);
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![small, big],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                last_watched: HashMap::from([("small".to_string(), now)]),
+                ..config_with_favourites(&["big", "small"])
+            },
+            now,
+        );
+
+        assert_eq!(live_logins(&state), vec!["big", "small"]);
+    }
+
+    #[test]
+    fn watch_recency_does_not_reorder_non_favourites() {
+        let now = Utc::now();
+        let fav = stream_with_viewers("fav", 10);
+        let big = stream_with_viewers("big", 50_000);
+        let small = stream_with_viewers("small", 100);
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![small, big, fav],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                favourite_sort_mode: FavouriteSortMode::RecentlyWatched,
+                last_watched: HashMap::from([("small".to_string(), now)]),
+                ..config_with_favourites(&["fav"])
+            },
+            now,
+        );
+
+        assert_eq!(live_logins(&state), vec!["fav", "big", "small"]);
+    }
+
+    #[test]
+    fn live_sort_uptime_puts_newest_first() {
+        let now = Utc::now();
+        let mut old = stream_with_viewers("old", 50_000);
+        old.started_at = now - Duration::hours(5);
+        let mut new = stream_with_viewers("new", 100);
+        new.started_at = now - Duration::minutes(10);
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![old, new],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                live_sort: LiveSortMode::Uptime,
+                ..default_config()
+            },
+            now,
+        );
+
+        assert_eq!(live_logins(&state), vec!["new", "old"]);
+    }
+
+    #[test]
+    fn live_sort_alphabetical_ignores_case() {
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![
+                stream_with_viewers("zed", 50_000),
+                stream_with_viewers("Bob", 10),
+                stream_with_viewers("alice", 100),
+            ],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                live_sort: LiveSortMode::Alphabetical,
+                ..default_config()
+            },
+            Utc::now(),
+        );
+
+        assert_eq!(live_logins(&state), vec!["alice", "bob", "zed"]);
+    }
+
+    #[test]
+    fn live_sort_recently_notified_then_viewers() {
+        let now = Utc::now();
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![
+                stream_with_viewers("big", 50_000),
+                stream_with_viewers("earlier", 10),
+                stream_with_viewers("latest", 100),
+                stream_with_viewers("small", 5),
+            ],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                live_sort: LiveSortMode::RecentlyNotified,
+                last_notified: HashMap::from([
+                    ("earlier".to_string(), now - Duration::hours(1)),
+                    ("latest".to_string(), now - Duration::minutes(5)),
+                ]),
+                ..default_config()
+            },
+            now,
+        );
+
+        assert_eq!(
+            live_logins(&state),
+            vec!["latest", "earlier", "big", "small"]
+        );
+    }
+
+    #[test]
+    fn live_sort_keeps_favourites_first() {
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![
+                stream_with_viewers("aaron", 10),
+                stream_with_viewers("zoe", 5),
+            ],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                live_sort: LiveSortMode::Alphabetical,
+                ..config_with_favourites(&["zoe"])
+            },
+            Utc::now(),
+        );
+
+        assert_eq!(live_logins(&state), vec!["zoe", "aaron"]);
+    }
+
+    // =========================================================
+    // compute_display_state — hotness
+    // =========================================================
+
+    #[test]
+    fn hot_stream_has_fire_in_label() {
+        let mut s = make_stream("hotuser", "HotUser");
+        s.user_id = "uid_hot".to_string();
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![s],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                hot_stream_ids: HashSet::from(["uid_hot".to_string()]),
+                ..default_config()
+            },
+            Utc::now(),
+        );
+
+        assert!(
+            state.live_section.visible[0].label.contains('\u{1F525}'),
+            "hot stream label should contain \u{1F525}: {}",
+            state.live_section.visible[0].label
+        );
+        assert!(state.live_section.visible[0].is_hot);
+    }
+
+    #[test]
+    fn non_hot_stream_has_no_fire_in_label() {
+        let s = make_stream("cooluser", "CoolUser");
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![s],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &default_config(),
+            Utc::now(),
+        );
+
+        assert!(
+            !state.live_section.visible[0].label.contains('\u{1F525}'),
+            "non-hot stream should not have fire"
+        );
+        assert!(!state.live_section.visible[0].is_hot);
+    }
+
+    #[test]
+    fn stream_details_keep_full_title_and_game() {
+        let mut s = stream_with_viewers("ninja", 1500);
+        s.title = "A very long stream title that would never fit in a menu label".to_string();
+        s.game_name = "Some Game With A Long Name".to_string();
+
+        let details = format_stream_details(&s);
+
+        assert_eq!(details[0], s.title);
+        assert_eq!(details[1], s.game_name);
+        assert!(details[2].starts_with("Live for "));
+        assert_eq!(details[3], "1.5k viewers");
+    }
+
+    #[test]
+    fn stream_details_skip_blank_title() {
+        let mut s = stream_with_viewers("ninja", 10);
+        s.title = "  ".to_string();
+        assert_eq!(format_stream_details(&s)[0], "Test Game");
+    }
+
+    // =========================================================
+    // compute_offline_favourites
+    // =========================================================
+
+    #[test]
+    fn offline_favourites_skip_live_and_sort_by_last_seen() {
+        let now = Utc::now();
+        let (cats, cat_streams) = no_categories();
+        let config = DisplayConfig {
+            last_seen: HashMap::from([
+                ("old".to_string(), now - Duration::days(3)),
+                ("recent".to_string(), now - Duration::hours(5)),
+                ("live".to_string(), now - Duration::days(1)),
+            ]),
+            ..config_with_favourites(&["old", "recent", "live", "never"])
+        };
+
+        let state = compute_display_state(
+            vec![make_stream("live", "live")],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &config,
+            now,
+        );
+
+        let labels: Vec<&str> = state
+            .offline_favourites
+            .iter()
+            .map(|f| f.label.as_str())
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                "recent - seen 5h ago",
+                "old - seen 3d ago",
+                "never - not seen yet"
+            ]
+        );
+        assert_eq!(state.offline_favourites[0].user_login, "recent");
+    }
+
+    #[test]
+    fn format_ago_picks_unit() {
+        assert_eq!(format_ago(Duration::minutes(20)), "<1h ago");
+        assert_eq!(format_ago(Duration::hours(30)), "30h ago");
+        assert_eq!(format_ago(Duration::days(4)), "4d ago");
+    }
+
+    // =========================================================
+    // compute_recently_ended
+    // =========================================================
+
+    #[test]
+    fn recently_ended_links_the_vod_once_found() {
+        let now = Utc::now();
+        let ended = |login: &str, video: Option<Video>| EndedStream {
+            stream: make_stream(login, login),
+            ended_at: now - Duration::minutes(12),
+            video,
+        };
+        let config = DisplayConfig {
+            recently_ended: vec![
+                ended("amy", Some(video("42", "amy", "Last night"))),
+                ended("zed", None),
+                ended("hidden", None),
+            ],
+            ..config_with_importance("hidden", StreamerImportance::Ignore)
+        };
+
+        let entries = compute_recently_ended(&config, now);
+
+        let labels: Vec<&str> = entries.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "amy - Test Game (ended 12m ago)",
+                "zed - Test Game (ended 12m ago)"
+            ]
+        );
+        assert_eq!(entries[0].menu_id(), "video_42");
+        assert_eq!(entries[1].menu_id(), "channel_page_zed");
+    }
+
+    // =========================================================
+    // compute_past_broadcasts
+    // =========================================================
+
+    fn video(id: &str, user_name: &str, title: &str) -> Video {
+        Video {
+            id: id.to_string(),
+            user_id: format!("{user_name}_id"),
+            user_login: user_name.to_lowercase(),
+            user_name: user_name.to_string(),
+            title: title.to_string(),
+            created_at: Utc::now(),
+            url: format!("https://www.twitch.tv/videos/{id}"),
+            duration: "3h8m33s".to_string(),
+        }
+    }
+
+    #[test]
+    fn past_broadcasts_grouped_by_streamer_name() {
+        let recent = HashMap::from([
+            (
+                "zed".to_string(),
+                vec![video("3", "Zed", "Speedruns"), video("4", "Zed", "Chill")],
+            ),
+            ("amy".to_string(), vec![video("1", "amy", "Art stream")]),
+            ("nobody".to_string(), Vec::new()),
+        ]);
+
+        let sections = compute_past_broadcasts(&recent, DEFAULT_MAX_LABEL_WIDTH);
+
+        let names: Vec<&str> = sections.iter().map(|s| s.display_name.as_str()).collect();
+        assert_eq!(names, vec!["amy", "Zed"]);
+        let ids: Vec<&str> = sections[1].videos.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(ids, vec!["3", "4"]);
+        assert!(sections[0].videos[0]
+            .label
+            .ends_with(" - Art stream (3h 8m)"));
+    }
+
+    // =========================================================
+    // compute_footer_actions
+    // =========================================================
+
+    fn custom(label: &str, target: CustomActionTarget) -> CustomAction {
+        CustomAction {
+            label: label.to_string(),
+            target,
+        }
+    }
+
+    #[test]
+    fn footer_url_action_has_no_confirmation() {
+        let actions = compute_footer_actions(&[custom(
+            "Dashboard",
+            CustomActionTarget::Url("https://dashboard.twitch.tv".to_string()),
+        )]);
+        assert_eq!(
+            actions,
+            vec![FooterAction {
+                index: 0,
+                label: "Dashboard".to_string(),
+                confirm_label: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn footer_command_action_requires_confirmation() {
+        let actions = compute_footer_actions(&[custom(
+            "Start OBS",
+            CustomActionTarget::Command("obs --startstreaming".to_string()),
+        )]);
+        assert_eq!(
+            actions[0].confirm_label.as_deref(),
+            Some("Run: obs --startstreaming")
+        );
+    }
+
+    #[test]
+    fn footer_skips_incomplete_actions_and_keys_by_content() {
+        let ok = custom("Ok", CustomActionTarget::Url("https://b".to_string()));
+        let actions = compute_footer_actions(&[
+            custom("", CustomActionTarget::Url("https://a".to_string())),
+            custom("Blank", CustomActionTarget::Command("  ".to_string())),
+            ok.clone(),
+        ]);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].key, custom_action::action_key(&ok));
+    }
+
+    #[test]
+    fn undoable_change_becomes_undo_label() {
+        let (cats, cat_streams) = no_categories();
+        let state = compute_display_state(
+            Vec::new(),
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                undoable_change: Some("ninja".to_string()),
+                ..default_config()
+            },
+            Utc::now(),
+        );
+        assert_eq!(state.undo_label.as_deref(), Some("Undo Change to ninja"));
+    }
+
+    #[test]
+    fn snoozes_become_footer_label_and_snoozed_streamers() {
+        let (cats, cat_streams) = no_categories();
+        let now = Local
+            .with_ymd_and_hms(2026, 3, 10, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut notification_snoozes = NotificationSnoozes::default();
+        notification_snoozes
+            .streamers
+            .insert("ninja".to_string(), now + Duration::hours(1));
+        notification_snoozes.all_until = Some(now + Duration::hours(1));
+        let state = compute_display_state(
+            Vec::new(),
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                notification_snoozes,
+                ..default_config()
+            },
+            now,
+        );
+        assert_eq!(
+            state.snooze_label.as_deref(),
+            Some("Notifications snoozed until 1:00 PM")
+        );
+        assert!(state.snoozed_streamers.contains("ninja"));
+
+        assert_eq!(
+            format_snooze_label(now + Duration::days(1), now),
+            "Notifications snoozed until Wed 12:00 PM"
+        );
+    }
+
+    #[test]
+    fn unread_flag_is_passed_through() {
+        let (cats, cat_streams) = no_categories();
+        let state = compute_display_state(
+            Vec::new(),
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                unread: true,
+                ..default_config()
+            },
+            Utc::now(),
+        );
+        assert!(state.unread);
+    }
+
+    #[test]
+    fn live_count_includes_overflow_and_skips_ignored() {
+        let (cats, cat_streams) = no_categories();
+        let live: Vec<Stream> = (0..4)
+            .map(|i| stream_with_viewers(&format!("s{i}"), 100 - i))
+            .collect();
+        let config = DisplayConfig {
+            live_limit: 2,
+            live_count_badge: true,
+            ..config_with_importance("s3", StreamerImportance::Ignore)
+        };
+        let state = compute_display_state(
+            live.clone(),
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &config,
+            Utc::now(),
+        );
+        assert_eq!(state.live_count, Some(3));
+
+        let state = compute_display_state(
+            live,
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &default_config(),
+            Utc::now(),
+        );
+        assert_eq!(state.live_count, None);
+    }
+
+    // =========================================================
+    // compute_display_state — watch streaks
+    // =========================================================
+
+    #[test]
+    fn streak_at_risk_marks_label() {
+        let mut s = make_stream("favuser", "FavUser");
+        s.user_id = "uid_fav".to_string();
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![s],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                streaks_at_risk: HashMap::from([("uid_fav".to_string(), 3)]),
+                ..default_config()
+            },
+            Utc::now(),
+        );
+
+        assert!(state.live_section.visible[0]
+            .label
+            .starts_with("\u{23F3}3 FavUser"));
+    }
+
+    #[test]
+    fn with_streak_marker_leaves_label_alone_without_streak() {
+        assert_eq!(with_streak_marker("A - B".to_string(), None), "A - B");
+    }
+
+    // =========================================================
     // compute_display_state — schedule section
     // =========================================================
 
     #[test]
-    fn ignore_streamers_filtered_from_schedule() {
-        let scheduled = vec![make_scheduled("ignored_bc", 2)];
+    fn ignore_streamers_filtered_from_schedule() {
+        let scheduled = vec![make_scheduled("ignored_bc", 2)];
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![],
+            scheduled,
+            true,
+            &cats,
+            &cat_streams,
+            &config_with_importance("ignored_bc", StreamerImportance::Ignore),
+            Utc::now(),
+        );
+
+        let total = state.schedule_section.visible.len() + state.schedule_section.overflow.len();
+        assert_eq!(total, 0, "ignored streamer's schedule should be filtered");
+    }
+
+    #[test]
+    fn schedule_hidden_when_broadcaster_live_within_window() {
+        let now = Utc::now();
+        // Broadcast starts 30 minutes from now — within the 60-min window
+        let mut sched = make_scheduled("livebroadcaster", 0);
+        sched.broadcaster_login = "livebroadcaster".to_string();
+        sched.start_time = now + Duration::minutes(30);
+
+        // The broadcaster is live
+        let live_stream = make_stream("lid", "LiveBroadcaster");
+        let mut live = live_stream;
+        live.user_login = "livebroadcaster".to_string();
+
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![live],
+            vec![sched],
+            true,
+            &cats,
+            &cat_streams,
+            &default_config(),
+            now,
+        );
+
+        let total = state.schedule_section.visible.len() + state.schedule_section.overflow.len();
+        assert_eq!(
+            total, 0,
+            "schedule within 60-min window of live stream should be hidden"
+        );
+    }
+
+    #[test]
+    fn schedule_shown_when_broadcaster_live_but_far_in_future() {
+        let now = Utc::now();
+        // Broadcast starts 90 minutes from now — outside the 60-min window
+        let mut sched = make_scheduled("livebc", 0);
+        sched.broadcaster_login = "livebc".to_string();
+        sched.start_time = now + Duration::minutes(90);
+
+        let mut live = make_stream("lid", "LiveBc");
+        live.user_login = "livebc".to_string();
+
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![live],
+            vec![sched],
+            true,
+            &cats,
+            &cat_streams,
+            &default_config(),
+            now,
+        );
+
+        let total = state.schedule_section.visible.len() + state.schedule_section.overflow.len();
+        assert_eq!(total, 1, "schedule outside window should still be shown");
+    }
+
+    #[test]
+    fn schedule_shown_when_broadcaster_not_live() {
+        let sched = make_scheduled("offlinebc", 2);
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![], // no live streams
+            vec![sched],
+            true,
+            &cats,
+            &cat_streams,
+            &default_config(),
+            Utc::now(),
+        );
+
+        let total = state.schedule_section.visible.len() + state.schedule_section.overflow.len();
+        assert_eq!(total, 1, "offline broadcaster's schedule should be shown");
+    }
+
+    #[test]
+    fn schedule_overflow_split_at_limit() {
+        let scheduled: Vec<_> = (0..7)
+            .map(|i| make_scheduled(&format!("bc{i}"), i as i64 + 1))
+            .collect();
+        let (cats, cat_streams) = no_categories();
+
+        let state = compute_display_state(
+            vec![],
+            scheduled,
+            true,
+            &cats,
+            &cat_streams,
+            &default_config(),
+            Utc::now(),
+        );
+
+        assert_eq!(state.schedule_section.visible.len(), 5);
+        assert_eq!(state.schedule_section.overflow.len(), 2);
+    }
+
+    #[test]
+    fn schedules_loaded_false_propagated() {
+        let (cats, cat_streams) = no_categories();
+        let state = compute_display_state(
+            vec![],
+            vec![],
+            false, // not yet loaded
+            &cats,
+            &cat_streams,
+            &default_config(),
+            Utc::now(),
+        );
+
+        assert!(!state.schedule_section.schedules_loaded);
+    }
+
+    #[test]
+    fn schedule_header_includes_lookahead_hours() {
         let (cats, cat_streams) = no_categories();
-
         let state = compute_display_state(
             vec![],
-            scheduled,
+            vec![],
             true,
             &cats,
             &cat_streams,
-            &config_with_importance("ignored_bc", StreamerImportance::Ignore),
+            &DisplayConfig {
+                schedule_lookahead_hours: 12,
+                ..default_config()
+            },
             Utc::now(),
         );
 
-        let total = state.schedule_section.visible.len() + state.schedule_section.overflow.len();
-        assert_eq!(total, 0, "ignored streamer's schedule should be filtered");
+        assert_eq!(state.schedule_section.header, "Scheduled (Next 12h)");
     }
 
     #[test]
-    fn schedule_hidden_when_broadcaster_live_within_window() {
+    fn schedule_header_counts_down_to_soonest_entry() {
         let now = Utc::now();
-        // Broadcast starts 30 minutes from now — within the 60-min window
-        let mut sched = make_scheduled("livebroadcaster", 0);
-        sched.broadcaster_login = "livebroadcaster".to_string();
-        sched.start_time = now + Duration::minutes(30);
-
-        // The broadcaster is live
-        let live_stream = make_stream("lid", "LiveBroadcaster");
-        let mut live = live_stream;
-        live.user_login = "livebroadcaster".to_string();
-
+        let mut later = make_scheduled("Later", 3);
+        later.start_time = now + Duration::hours(3);
+        let mut sooner = make_scheduled("Sooner", 1);
+        sooner.start_time = now + Duration::minutes(42);
         let (cats, cat_streams) = no_categories();
 
         let state = compute_display_state(
-            vec![live],
-            vec![sched],
+            vec![],
+            vec![later, sooner],
             true,
             &cats,
             &cat_streams,
@@ -739,29 +2345,31 @@ mod tests {
             now,
         );
 
-        let total = state.schedule_section.visible.len() + state.schedule_section.overflow.len();
         assert_eq!(
-            total, 0,
-            "schedule within 60-min window of live stream should be hidden"
+            state.schedule_section.header,
+            "Scheduled (Next 6h) \u{00B7} Next in 42m \u{2014} Sooner"
+        );
+        assert_eq!(
+            state
+                .schedule_section
+                .next_up
+                .as_ref()
+                .unwrap()
+                .broadcaster_name,
+            "Sooner"
         );
     }
 
     #[test]
-    fn schedule_shown_when_broadcaster_live_but_far_in_future() {
+    fn schedule_header_skips_entries_already_started() {
         let now = Utc::now();
-        // Broadcast starts 90 minutes from now — outside the 60-min window
-        let mut sched = make_scheduled("livebc", 0);
-        sched.broadcaster_login = "livebc".to_string();
-        sched.start_time = now + Duration::minutes(90);
-
-        let mut live = make_stream("lid", "LiveBc");
-        live.user_login = "livebc".to_string();
-
+        let mut started = make_scheduled("Started", 0);
+        started.start_time = now - Duration::minutes(10);
         let (cats, cat_streams) = no_categories();
 
         let state = compute_display_state(
-            vec![live],
-            vec![sched],
+            vec![],
+            vec![started],
             true,
             &cats,
             &cat_streams,
@@ -769,83 +2377,291 @@ mod tests {
             now,
         );
 
-        let total = state.schedule_section.visible.len() + state.schedule_section.overflow.len();
-        assert_eq!(total, 1, "schedule outside window should still be shown");
+        assert_eq!(state.schedule_section.header, "Scheduled (Next 6h)");
+        assert!(state.schedule_section.next_up.is_none());
     }
 
     #[test]
-    fn schedule_shown_when_broadcaster_not_live() {
-        let sched = make_scheduled("offlinebc", 2);
+    fn schedule_header_ignores_hidden_broadcasters() {
+        let now = Utc::now();
+        let mut ignored = make_scheduled("ignored_bc", 1);
+        ignored.start_time = now + Duration::minutes(5);
         let (cats, cat_streams) = no_categories();
 
         let state = compute_display_state(
-            vec![], // no live streams
-            vec![sched],
+            vec![],
+            vec![ignored],
             true,
             &cats,
             &cat_streams,
-            &default_config(),
+            &config_with_importance("ignored_bc", StreamerImportance::Ignore),
+            now,
+        );
+
+        assert!(state.schedule_section.next_up.is_none());
+    }
+
+    #[test]
+    fn format_schedule_header_updates_as_time_passes() {
+        let now = Utc::now();
+        let next = NextScheduled {
+            broadcaster_name: "StreamerX".to_string(),
+            start_time: now + Duration::minutes(90),
+        };
+        assert_eq!(
+            format_schedule_header(6, Some(&next), now),
+            "Scheduled (Next 6h) \u{00B7} Next in 1h 30m \u{2014} StreamerX"
+        );
+        assert_eq!(
+            format_schedule_header(6, Some(&next), now + Duration::minutes(89)),
+            "Scheduled (Next 6h) \u{00B7} Next in 1m \u{2014} StreamerX"
+        );
+        assert_eq!(
+            format_schedule_header(6, Some(&next), now + Duration::minutes(90)),
+            "Scheduled (Next 6h)"
+        );
+    }
+
+    #[test]
+    fn refresh_status_counts_up_and_flags_stale_data() {
+        let last = Utc::now();
+        assert_eq!(format_refresh_status(last, 60, last), "Updated just now");
+        assert_eq!(
+            format_refresh_status(last, 60, last + Duration::minutes(2)),
+            "Updated 2m ago"
+        );
+        // More than 3 polls behind
+        assert_eq!(
+            format_refresh_status(last, 60, last + Duration::minutes(4)),
+            "\u{26A0} Updated 4m ago"
+        );
+        assert_eq!(
+            format_refresh_status(last, 60, last + Duration::minutes(125)),
+            "\u{26A0} Updated 2h ago"
+        );
+    }
+
+    #[test]
+    fn connection_problem_reported_while_failing() {
+        let since = Utc::now() - Duration::minutes(10);
+        let (cats, cat_streams) = no_categories();
+        let state = compute_display_state(
+            vec![],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                connection_problem_since: Some(since),
+                ..default_config()
+            },
             Utc::now(),
         );
 
-        let total = state.schedule_section.visible.len() + state.schedule_section.overflow.len();
-        assert_eq!(total, 1, "offline broadcaster's schedule should be shown");
+        let label = state.connection_problem.unwrap();
+        assert!(label.starts_with("\u{26A0} Connection problems since "));
+        assert!(label.ends_with(&since.with_timezone(&Local).format("%-I:%M %p").to_string()));
     }
 
     #[test]
-    fn schedule_overflow_split_at_limit() {
-        let scheduled: Vec<_> = (0..7)
-            .map(|i| make_scheduled(&format!("bc{i}"), i as i64 + 1))
-            .collect();
+    fn tooltip_notes_when_offline() {
+        let since = Utc::now() - Duration::minutes(10);
         let (cats, cat_streams) = no_categories();
+        let offline = compute_display_state(
+            vec![],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                offline_since: Some(since),
+                ..default_config()
+            },
+            Utc::now(),
+        );
+        assert_eq!(
+            offline.tooltip,
+            format!(
+                "Twitch Tray (offline since {})",
+                since.with_timezone(&Local).format("%-I:%M %p")
+            )
+        );
 
-        let state = compute_display_state(
+        let online = compute_display_state(
             vec![],
-            scheduled,
+            no_scheduled(),
             true,
             &cats,
             &cat_streams,
             &default_config(),
             Utc::now(),
         );
+        assert_eq!(online.tooltip, TOOLTIP);
+    }
 
-        assert_eq!(state.schedule_section.visible.len(), 5);
-        assert_eq!(state.schedule_section.overflow.len(), 2);
+    #[test]
+    fn login_code_only_while_one_is_pending() {
+        let pending = LoginProgress::PendingCode {
+            user_code: "ABCD-EFGH".to_string(),
+            verification_uri: "https://www.twitch.tv/activate".to_string(),
+            expires_at: Utc::now(),
+        };
+        assert_eq!(
+            LoginCode::from_progress(Some(&pending)),
+            Some(LoginCode {
+                code: "ABCD-EFGH".to_string(),
+                label: "Enter ABCD-EFGH at twitch.tv/activate".to_string(),
+            })
+        );
+        assert_eq!(
+            LoginCode::from_progress(Some(&LoginProgress::Confirmed)),
+            None
+        );
+        assert_eq!(LoginCode::from_progress(None), None);
     }
 
     #[test]
-    fn schedules_loaded_false_propagated() {
+    fn login_warning_only_while_token_refresh_fails() {
+        let (cats, cat_streams) = no_categories();
+        let state = |token_refresh_failing| {
+            compute_display_state(
+                vec![],
+                no_scheduled(),
+                true,
+                &cats,
+                &cat_streams,
+                &DisplayConfig {
+                    token_refresh_failing,
+                    ..default_config()
+                },
+                Utc::now(),
+            )
+        };
+
+        assert_eq!(state(true).login_warning.as_deref(), Some(LOGIN_WARNING));
+        assert_eq!(state(false).login_warning, None);
+    }
+
+    #[test]
+    fn login_warning_while_scopes_are_missing() {
         let (cats, cat_streams) = no_categories();
         let state = compute_display_state(
             vec![],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                scopes_missing: true,
+                ..default_config()
+            },
+            Utc::now(),
+        );
+        assert_eq!(state.login_warning.as_deref(), Some(SCOPES_WARNING));
+    }
+
+    #[test]
+    fn refresh_status_only_after_first_refresh() {
+        let now = Utc::now();
+        let (cats, cat_streams) = no_categories();
+        let state = compute_display_state(
             vec![],
-            false, // not yet loaded
+            no_scheduled(),
+            true,
             &cats,
             &cat_streams,
             &default_config(),
-            Utc::now(),
+            now,
         );
+        assert!(state.refresh_status.is_none());
 
-        assert!(!state.schedule_section.schedules_loaded);
+        let state = compute_display_state(
+            vec![],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                last_refresh: Some(now - Duration::minutes(2)),
+                ..default_config()
+            },
+            now,
+        );
+        assert_eq!(state.refresh_status.unwrap().label, "Updated 2m ago");
     }
 
     #[test]
-    fn schedule_header_includes_lookahead_hours() {
+    fn format_countdown_units() {
+        assert_eq!(format_countdown(Duration::seconds(30)), "<1m");
+        assert_eq!(format_countdown(Duration::minutes(42)), "42m");
+        assert_eq!(format_countdown(Duration::minutes(120)), "2h");
+        assert_eq!(format_countdown(Duration::minutes(65)), "1h 5m");
+    }
+
+    #[test]
+    fn schedule_not_grouped_within_a_day() {
         let (cats, cat_streams) = no_categories();
         let state = compute_display_state(
             vec![],
-            vec![],
+            vec![make_scheduled("Soon", 2)],
             true,
             &cats,
             &cat_streams,
             &DisplayConfig {
-                schedule_lookahead_hours: 12,
+                schedule_lookahead_hours: 24,
                 ..default_config()
             },
             Utc::now(),
         );
 
-        assert_eq!(state.schedule_section.header, "Scheduled (Next 12h)");
+        assert!(state.schedule_section.visible[0].day.is_none());
+    }
+
+    #[test]
+    fn schedule_grouped_by_day_beyond_a_day() {
+        let now = Utc::now();
+        let (cats, cat_streams) = no_categories();
+        let state = compute_display_state(
+            vec![],
+            vec![make_scheduled("Soon", 1), make_scheduled("Later", 30)],
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                schedule_lookahead_hours: 48,
+                ..default_config()
+            },
+            now,
+        );
+
+        let today = now.with_timezone(&Local).date_naive();
+        for entry in &state.schedule_section.visible {
+            let day = entry
+                .scheduled
+                .start_time
+                .with_timezone(&Local)
+                .date_naive();
+            assert_eq!(entry.day, Some(format_schedule_day(day, today)));
+            assert!(!entry.label.contains("Today") && !entry.label.contains("Tomorrow"));
+        }
+    }
+
+    #[test]
+    fn format_schedule_day_names() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap(); // Monday
+        assert_eq!(format_schedule_day(today, today), "Today");
+        assert_eq!(
+            format_schedule_day(today + Duration::days(1), today),
+            "Tomorrow"
+        );
+        assert_eq!(
+            format_schedule_day(today + Duration::days(4), today),
+            "Friday"
+        );
+        assert_eq!(
+            format_schedule_day(today + Duration::days(8), today),
+            "Tuesday 18 Mar"
+        );
     }
 
     #[test]
@@ -903,6 +2719,7 @@ mod tests {
         let cats = vec![FollowedCategory {
             id: "cat1".to_string(),
             name: "Minecraft".to_string(),
+            ..Default::default()
         }];
         let mut cat_streams = HashMap::new();
         cat_streams.insert(
@@ -923,6 +2740,102 @@ mod tests {
         assert_eq!(state.category_sections.len(), 1);
         assert!(state.category_sections[0].header.contains("Minecraft"));
         assert_eq!(state.category_sections[0].entries.len(), 1);
+        assert!(!state.category_sections[0].has_more);
+    }
+
+    #[test]
+    fn full_category_section_has_more() {
+        let cats = vec![FollowedCategory {
+            id: "cat1".to_string(),
+            name: "Minecraft".to_string(),
+            ..Default::default()
+        }];
+        let streams = (0..12)
+            .map(|i| make_stream(&format!("s{i}"), &format!("S{i}")))
+            .collect();
+        let cat_streams = HashMap::from([("cat1".to_string(), streams)]);
+
+        let state = compute_display_state(
+            vec![],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &default_config(),
+            Utc::now(),
+        );
+
+        assert_eq!(
+            state.category_sections[0].entries.len(),
+            CATEGORY_MENU_LIMIT
+        );
+        assert!(state.category_sections[0].has_more);
+    }
+
+    #[test]
+    fn cached_sections_are_marked() {
+        let cats = vec![FollowedCategory {
+            id: "cat1".to_string(),
+            name: "Minecraft".to_string(),
+            ..Default::default()
+        }];
+        let cat_streams = HashMap::from([(
+            "cat1".to_string(),
+            vec![make_stream("mc_streamer", "McStreamer")],
+        )]);
+
+        let state = compute_display_state(
+            vec![make_stream("alice", "Alice")],
+            no_scheduled(),
+            true,
+            &cats,
+            &cat_streams,
+            &DisplayConfig {
+                followed_cached: true,
+                cached_categories: HashSet::from(["cat1".to_string()]),
+                ..default_config()
+            },
+            Utc::now(),
+        );
+
+        assert_eq!(state.live_section.header(), "Following Live (1) (cached)");
+        assert!(state.category_sections[0].header.ends_with(" (cached)"));
+    }
+
+    #[test]
+    fn followed_channels_can_be_hidden_from_categories() {
+        let cats = vec![FollowedCategory {
+            id: "cat1".to_string(),
+            name: "Minecraft".to_string(),
+            ..Default::default()
+        }];
+        let cat_streams = HashMap::from([(
+            "cat1".to_string(),
+            vec![make_stream("alice", "Alice"), make_stream("carol", "Carol")],
+        )]);
+        let compute = |hide| {
+            compute_display_state(
+                vec![make_stream("alice", "Alice")],
+                no_scheduled(),
+                true,
+                &cats,
+                &cat_streams,
+                &DisplayConfig {
+                    hide_followed_in_categories: hide,
+                    ..default_config()
+                },
+                Utc::now(),
+            )
+        };
+
+        assert_eq!(compute(false).category_sections[0].entries.len(), 2);
+        let state = compute(true);
+        let logins: Vec<&str> = state.category_sections[0]
+            .entries
+            .iter()
+            .map(|e| e.stream.user_login.as_str())
+            .collect();
+        assert_eq!(logins, vec!["carol"]);
     }
 
     #[test]
@@ -930,6 +2843,7 @@ mod tests {
         let cats = vec![FollowedCategory {
             id: "cat1".to_string(),
             name: "Minecraft".to_string(),
+            ..Default::default()
         }];
         let cat_streams = HashMap::new(); // no streams for cat1
 
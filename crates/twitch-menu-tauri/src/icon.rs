@@ -0,0 +1,274 @@
+//! Tray icon variants drawn at runtime.
+
+use twitch_backend::config::IconTheme;
+
+/// Glyph colours (RGB) for the single-colour icon themes.
+const DARK_PANEL_GLYPH: [u8; 3] = [0xFF, 0xFF, 0xFF];
+const LIGHT_PANEL_GLYPH: [u8; 3] = [0x1F, 0x1F, 0x23];
+/// Template images only use alpha, so any colour works; black previews best.
+const MONOCHROME_GLYPH: [u8; 3] = [0x00, 0x00, 0x00];
+
+/// Colour of the unread dot (RGBA), Twitch's live red.
+const UNREAD_DOT_COLOUR: [u8; 4] = [0xEB, 0x04, 0x00, 0xFF];
+
+/// Background of the live count badge (RGBA), Twitch purple.
+const BADGE_COLOUR: [u8; 4] = [0x91, 0x46, 0xFF, 0xFF];
+const BADGE_TEXT_COLOUR: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+
+/// Colour of the connection warning triangle (RGBA), amber.
+const WARNING_COLOUR: [u8; 4] = [0xFF, 0xB3, 0x00, 0xFF];
+
+/// The badge shows at most two digits.
+const MAX_BADGE_COUNT: usize = 99;
+
+/// 3×5 pixel digits, one row per entry, the low three bits read left to right.
+const DIGIT_GLYPHS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+
+/// Resolves `IconTheme::Auto` for this platform.
+pub fn resolve_icon_theme(theme: IconTheme) -> IconTheme {
+    match theme {
+        IconTheme::Auto if cfg!(target_os = "macos") => IconTheme::Monochrome,
+        IconTheme::Auto => IconTheme::Colour,
+        theme => theme,
+    }
+}
+
+/// Whether icons for a resolved `theme` are template images, which the
+/// macOS menu bar tints itself.
+pub fn is_template_icon(theme: IconTheme) -> bool {
+    theme == IconTheme::Monochrome && cfg!(target_os = "macos")
+}
+
+/// Recolours an RGBA icon for a resolved `theme`, keeping its alpha so the
+/// shape is unchanged. The colour themes leave it as it is.
+pub fn apply_icon_theme(rgba: &mut [u8], theme: IconTheme) {
+    let glyph = match theme {
+        IconTheme::Auto | IconTheme::Colour => return,
+        IconTheme::Dark => DARK_PANEL_GLYPH,
+        IconTheme::Light => LIGHT_PANEL_GLYPH,
+        IconTheme::Monochrome => MONOCHROME_GLYPH,
+    };
+    for pixel in rgba.chunks_exact_mut(4) {
+        pixel[..3].copy_from_slice(&glyph);
+    }
+}
+
+/// Halves an RGBA icon's opacity: the logged-out look for single-colour
+/// themes, where the grey icon would be indistinguishable.
+pub fn dim(rgba: &mut [u8]) {
+    for pixel in rgba.chunks_exact_mut(4) {
+        pixel[3] /= 2;
+    }
+}
+
+/// Paints the "unread" dot over the top-right corner of an RGBA image.
+///
+/// The dot's diameter is just under half the icon's width so it stays
+/// visible at tray sizes. Buffers that don't match `width`×`height` are
+/// left as they are.
+pub fn add_unread_dot(rgba: &mut [u8], width: u32, height: u32) {
+    let (w, h) = (width as usize, height as usize);
+    if w == 0 || h == 0 || rgba.len() != w * h * 4 {
+        return;
+    }
+    let radius = (w.min(h) as f32 * 0.22).max(2.0);
+    let centre_x = w as f32 - radius - 0.5;
+    let centre_y = radius + 0.5;
+
+    for y in 0..h {
+        for x in 0..w {
+            let dx = x as f32 + 0.5 - centre_x;
+            let dy = y as f32 + 0.5 - centre_y;
+            if dx * dx + dy * dy <= radius * radius {
+                let i = (y * w + x) * 4;
+                rgba[i..i + 4].copy_from_slice(&UNREAD_DOT_COLOUR);
+            }
+        }
+    }
+}
+
+/// Paints a warning triangle over the bottom-left corner of an RGBA image,
+/// for when refreshes are failing.
+///
+/// The triangle is just under half the icon's height. Buffers that don't
+/// match `width`×`height` are left as they are.
+pub fn add_warning_triangle(rgba: &mut [u8], width: u32, height: u32) {
+    let (w, h) = (width as usize, height as usize);
+    if w == 0 || h == 0 || rgba.len() != w * h * 4 {
+        return;
+    }
+    let side = ((w.min(h) as f32 * 0.45) as usize).max(3);
+    let centre_x = side as f32 / 2.0;
+    let top = h - side;
+
+    for y in top..h {
+        // Widens from the apex to the full side at the bottom row
+        let half_width = (y - top) as f32 / 2.0 + 0.5;
+        for x in 0..side.min(w) {
+            if (x as f32 + 0.5 - centre_x).abs() <= half_width {
+                let i = (y * w + x) * 4;
+                rgba[i..i + 4].copy_from_slice(&WARNING_COLOUR);
+            }
+        }
+    }
+}
+
+/// Paints `count` in a badge over the bottom-right corner of an RGBA image.
+///
+/// The badge is half the icon's height, with pixel digits scaled to fit;
+/// counts above 99 show as 99. Nothing is drawn for zero, or for buffers
+/// that don't match `width`×`height`.
+pub fn add_count_badge(rgba: &mut [u8], width: u32, height: u32, count: usize) {
+    let (w, h) = (width as usize, height as usize);
+    if count == 0 || w == 0 || h == 0 || rgba.len() != w * h * 4 {
+        return;
+    }
+    let digits: Vec<usize> = count
+        .min(MAX_BADGE_COUNT)
+        .to_string()
+        .bytes()
+        .map(|b| usize::from(b - b'0'))
+        .collect();
+
+    // One glyph-pixel of padding around the text and between digits
+    let scale = (h / 2 / (GLYPH_HEIGHT + 2)).max(1);
+    let text_width = digits.len() * (GLYPH_WIDTH + 1) - 1;
+    let badge_w = ((text_width + 2) * scale).min(w);
+    let badge_h = ((GLYPH_HEIGHT + 2) * scale).min(h);
+    let (left, top) = (w - badge_w, h - badge_h);
+
+    for y in top..h {
+        for x in left..w {
+            let i = (y * w + x) * 4;
+            rgba[i..i + 4].copy_from_slice(&BADGE_COLOUR);
+        }
+    }
+
+    for (n, &digit) in digits.iter().enumerate() {
+        let glyph_left = left + (1 + n * (GLYPH_WIDTH + 1)) * scale;
+        for (row, bits) in DIGIT_GLYPHS[digit].iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                    continue;
+                }
+                let x0 = glyph_left + col * scale;
+                let y0 = top + (row + 1) * scale;
+                for y in y0..(y0 + scale).min(h) {
+                    for x in x0..(x0 + scale).min(w) {
+                        let i = (y * w + x) * 4;
+                        rgba[i..i + 4].copy_from_slice(&BADGE_TEXT_COLOUR);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(rgba: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * width + x) * 4) as usize;
+        rgba[i..i + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn dot_covers_top_right_corner_only() {
+        let mut rgba = vec![0; 64 * 64 * 4];
+        add_unread_dot(&mut rgba, 64, 64);
+
+        assert_eq!(pixel(&rgba, 64, 50, 13), UNREAD_DOT_COLOUR);
+        assert_eq!(pixel(&rgba, 64, 0, 0), [0; 4]);
+        assert_eq!(pixel(&rgba, 64, 13, 50), [0; 4]);
+        assert_eq!(pixel(&rgba, 64, 63, 63), [0; 4]);
+    }
+
+    #[test]
+    fn warning_covers_bottom_left_corner_only() {
+        let mut rgba = vec![0; 64 * 64 * 4];
+        add_warning_triangle(&mut rgba, 64, 64);
+
+        // 28px triangle: apex at the top centre, full width along the bottom
+        assert_eq!(pixel(&rgba, 64, 14, 37), WARNING_COLOUR);
+        assert_eq!(pixel(&rgba, 64, 0, 63), WARNING_COLOUR);
+        assert_eq!(pixel(&rgba, 64, 27, 63), WARNING_COLOUR);
+        assert_eq!(pixel(&rgba, 64, 0, 37), [0; 4]);
+        assert_eq!(pixel(&rgba, 64, 14, 35), [0; 4]);
+        assert_eq!(pixel(&rgba, 64, 63, 63), [0; 4]);
+    }
+
+    #[test]
+    fn single_colour_themes_keep_the_shape() {
+        let mut rgba = vec![0x91, 0x46, 0xFF, 0xFF, 0x91, 0x46, 0xFF, 0x00];
+        apply_icon_theme(&mut rgba, IconTheme::Dark);
+        assert_eq!(rgba, vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00]);
+
+        dim(&mut rgba);
+        assert_eq!(rgba[3], 0x7F);
+        assert_eq!(rgba[7], 0x00);
+    }
+
+    #[test]
+    fn colour_theme_leaves_icon_alone() {
+        let mut rgba = vec![0x91, 0x46, 0xFF, 0xFF];
+        apply_icon_theme(&mut rgba, IconTheme::Colour);
+        assert_eq!(rgba, vec![0x91, 0x46, 0xFF, 0xFF]);
+        assert_ne!(resolve_icon_theme(IconTheme::Auto), IconTheme::Auto);
+    }
+
+    #[test]
+    fn mismatched_buffer_is_untouched() {
+        let mut rgba = vec![0; 10];
+        add_unread_dot(&mut rgba, 64, 64);
+        add_count_badge(&mut rgba, 64, 64, 3);
+        add_warning_triangle(&mut rgba, 64, 64);
+        assert_eq!(rgba, vec![0; 10]);
+    }
+
+    #[test]
+    fn badge_covers_bottom_right_corner() {
+        let mut rgba = vec![0; 64 * 64 * 4];
+        add_count_badge(&mut rgba, 64, 64, 7);
+
+        // 64px icon: scale 4, so a 5×7 glyph-pixel badge of 20×28
+        assert_eq!(pixel(&rgba, 64, 63, 63), BADGE_COLOUR);
+        assert_eq!(pixel(&rgba, 64, 44, 36), BADGE_COLOUR);
+        assert_eq!(pixel(&rgba, 64, 43, 63), [0; 4]);
+        assert_eq!(pixel(&rgba, 64, 63, 35), [0; 4]);
+        // Top row of the 7 is solid
+        assert_eq!(pixel(&rgba, 64, 48, 40), BADGE_TEXT_COLOUR);
+        assert_eq!(pixel(&rgba, 64, 56, 40), BADGE_TEXT_COLOUR);
+    }
+
+    #[test]
+    fn two_digit_badge_is_wider() {
+        let mut one = vec![0; 64 * 64 * 4];
+        let mut two = vec![0; 64 * 64 * 4];
+        add_count_badge(&mut one, 64, 64, 7);
+        add_count_badge(&mut two, 64, 64, 150);
+
+        assert_eq!(pixel(&one, 64, 40, 63), [0; 4]);
+        assert_eq!(pixel(&two, 64, 40, 63), BADGE_COLOUR);
+    }
+
+    #[test]
+    fn zero_count_draws_nothing() {
+        let mut rgba = vec![0; 64 * 64 * 4];
+        add_count_badge(&mut rgba, 64, 64, 0);
+        assert!(rgba.iter().all(|&b| b == 0));
+    }
+}
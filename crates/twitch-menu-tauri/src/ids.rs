@@ -0,0 +1,47 @@
+//! Menu item IDs shared by the tray menu and the window fallback.
+//!
+//! Both render the same `DisplayState` and route clicks through
+//! `tray::handle_menu_event`, so the IDs must match. Each ID also needs an
+//! entry in `menu_actions::HANDLERS`.
+
+pub const LOGIN: &str = "login";
+pub const CANCEL_LOGIN: &str = "cancel_login";
+/// Followed by the device code to copy.
+pub const COPY_LOGIN_CODE_PREFIX: &str = "copy_login_code_";
+pub const LOGOUT: &str = "logout";
+pub const SWITCH_USER: &str = "switch_user";
+pub const QUIT: &str = "quit";
+pub const SETTINGS: &str = "settings";
+pub const WHATS_NEW: &str = "whats_new";
+pub const ABOUT: &str = "about";
+pub const OPEN_LOGS: &str = "open_logs";
+pub const DIAGNOSTICS_BUNDLE: &str = "diagnostics_bundle";
+pub const DASHBOARD: &str = "dashboard";
+pub const UNDO_SETTINGS: &str = "undo_settings";
+pub const REFRESH: &str = "refresh";
+pub const STREAM_PREFIX: &str = "stream_";
+pub const SCHEDULED_PREFIX: &str = "scheduled_";
+pub const CATEGORY_STREAM_PREFIX: &str = "cat_stream_";
+/// A category submenu's "Show more…", followed by the category ID.
+pub const CATEGORY_MORE_PREFIX: &str = "cat_more_";
+pub const OFFLINE_FAVOURITE_PREFIX: &str = "offline_";
+/// An offline pinned channel. Live pinned channels use `STREAM_PREFIX`.
+pub const PINNED_PREFIX: &str = "pinned_";
+pub const CUSTOM_ACTION_PREFIX: &str = "custom_action_";
+pub const VIDEO_PREFIX: &str = "video_";
+/// Alternate actions for a live stream, shown where clicks carry no modifiers.
+/// Deliberately not `stream_`-prefixed: `stream_chat_x` would also be the
+/// plain entry for a channel named `chat_x`.
+pub const CHAT_PREFIX: &str = "chat_";
+pub const COPY_LINK_PREFIX: &str = "copy_link_";
+pub const CHANNEL_PAGE_PREFIX: &str = "channel_page_";
+pub const STREAMER_SETTINGS_PREFIX: &str = "streamer_settings_";
+pub const PIN_PREFIX: &str = "pin_";
+pub const UNPIN_PREFIX: &str = "unpin_";
+/// The footer's "Snooze notifications" items, followed by a
+/// `SnoozeDuration` key.
+pub const SNOOZE_ALL_PREFIX: &str = "snooze_all_";
+pub const RESUME_NOTIFICATIONS: &str = "resume_notifications";
+/// A stream's snooze items: `snooze_streamer_<duration key>_<login>`.
+pub const SNOOZE_STREAMER_PREFIX: &str = "snooze_streamer_";
+pub const RESUME_STREAMER_PREFIX: &str = "resume_streamer_";
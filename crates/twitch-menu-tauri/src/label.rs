@@ -0,0 +1,84 @@
+//! Escaping for menu labels built from user-generated text.
+//!
+//! Stream titles, streamer names and game names come straight from Twitch and
+//! can contain characters the native menu toolkits treat specially:
+//!
+//! - `&` marks a mnemonic on every platform muda supports (`&&` is a literal `&`).
+//!   muda maps this onto each toolkit's own syntax, so on Linux it already turns
+//!   `_` into GTK's `__` and `&&` into `&`; escaping `_` here would double it.
+//! - Control characters (newlines, tabs) break single-line menu items, and bidi
+//!   overrides can visually reorder the rest of the label.
+//!
+//! Labels are passed as plain text (GTK menu items are not created with
+//! `use_markup`), so `<b>`-style markup renders literally and needs no escaping.
+
+/// Escapes `text` for use as a menu item label.
+pub fn menu_label(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&&"),
+            c if is_bidi_control(c) => {}
+            c if c.is_control() => {
+                // Collapse runs of whitespace-like control chars into one space
+                if !out.ends_with(' ') {
+                    out.push(' ');
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Explicit directional embeddings, overrides and isolates (U+202A–U+202E, U+2066–U+2069).
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_unchanged() {
+        assert_eq!(
+            menu_label("Streamer - Chess (1.2k, 2h 15m)"),
+            "Streamer - Chess (1.2k, 2h 15m)"
+        );
+    }
+
+    #[test]
+    fn ampersand_doubled() {
+        assert_eq!(menu_label("Dungeons & Dragons"), "Dungeons && Dragons");
+    }
+
+    #[test]
+    fn underscore_passed_through() {
+        // muda escapes it for GTK itself
+        assert_eq!(menu_label("cool_streamer"), "cool_streamer");
+    }
+
+    #[test]
+    fn newlines_and_tabs_become_single_space() {
+        assert_eq!(menu_label("line one\r\n\tline two"), "line one line two");
+    }
+
+    #[test]
+    fn bidi_overrides_removed() {
+        assert_eq!(menu_label("abc\u{202E}def\u{2069}"), "abcdef");
+    }
+
+    #[test]
+    fn markup_left_as_literal_text() {
+        assert_eq!(menu_label("<b>bold</b>"), "<b>bold</b>");
+    }
+
+    #[test]
+    fn emoji_preserved() {
+        assert_eq!(
+            menu_label("\u{1F525} \u{2605} 👺IM BACK👺"),
+            "\u{1F525} \u{2605} 👺IM BACK👺"
+        );
+    }
+}
@@ -1,56 +1,128 @@
 // twitch-menu-tauri: Tauri system tray menu implementation.
 // Depends on twitch-backend for domain types; provides the display layer.
 
+pub mod dashboard;
 pub mod display;
 pub mod display_state;
+mod icon;
+mod ids;
+pub mod label;
+pub mod menu_actions;
+pub mod menu_rows;
 pub mod tray;
+pub mod tray_host;
+pub mod window_menu;
 
 #[cfg(test)]
 mod test_helpers;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::watch;
 use twitch_backend::handle::RawDisplayData;
 
 use crate::display::DisplayBackend;
-use crate::display_state::{compute_display_state, DisplayConfig, DisplayState};
+use crate::display_state::{compute_display_state, DisplayConfig, DisplayState, LoginCode};
 use crate::tray::TrayBackend;
 
+/// How often the schedule header countdown is re-rendered between menu rebuilds.
+const SCHEDULE_HEADER_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Starts the display listener task.
 ///
 /// Subscribes to `display_rx` (a watch channel of `RawDisplayData`), converts
-/// each snapshot into a `DisplayState`, and calls `tray_backend.update()`.
+/// each snapshot into a `DisplayState`, and calls `backend.update()` — the
+/// tray, or the menu window when no tray is available.
 /// Returns a `JoinHandle` so the caller can manage the task lifetime.
-pub fn start_listener(
+pub fn start_listener<B: DisplayBackend + 'static>(
     mut display_rx: watch::Receiver<RawDisplayData>,
-    tray_backend: Arc<TrayBackend>,
+    backend: Arc<B>,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         while display_rx.changed().await.is_ok() {
             let raw = display_rx.borrow().clone();
-            let display_config = DisplayConfig {
-                streamer_settings: raw.config.streamer_settings.clone(),
-                schedule_lookahead_hours: raw.config.schedule_lookahead_hours,
-                live_limit: raw.config.live_menu_limit,
-                schedule_limit: raw.config.schedule_menu_limit,
-                hot_stream_ids: raw.hot_stream_ids.clone(),
-            };
-            let state = if raw.is_authenticated {
-                compute_display_state(
-                    raw.live_streams,
-                    raw.scheduled_streams,
-                    raw.schedules_loaded,
-                    &raw.followed_categories,
-                    &raw.category_streams,
-                    &display_config,
-                    Utc::now(),
-                )
-            } else {
-                DisplayState::unauthenticated()
-            };
-            if let Err(e) = tray_backend.update(state) {
-                tracing::error!("Failed to update tray: {}", e);
+            let state = display_state_from_raw(raw, Utc::now());
+            if let Err(e) = backend.update(state) {
+                tracing::error!("Failed to update menu: {}", e);
+            }
+        }
+    })
+}
+
+/// Converts a display snapshot into the `DisplayState` the menu renders.
+pub fn display_state_from_raw(raw: RawDisplayData, now: DateTime<Utc>) -> DisplayState {
+    let display_config = DisplayConfig {
+        streamer_settings: raw.config.streamer_settings.clone(),
+        schedule_lookahead_hours: raw.config.schedule_lookahead_hours,
+        live_limit: raw.config.live_menu_limit,
+        schedule_limit: raw.config.schedule_menu_limit,
+        hot_stream_ids: raw.hot_stream_ids.clone(),
+        streaks_at_risk: raw.streaks_at_risk.clone(),
+        live_sort: raw.config.live_sort,
+        favourite_sort_mode: raw.config.favourite_sort_mode,
+        last_watched: raw.last_watched.clone(),
+        last_seen: raw.last_seen.clone(),
+        last_notified: raw.last_notified.clone(),
+        custom_actions: raw.config.custom_actions.clone(),
+        max_label_width: raw.config.max_label_width,
+        live_label_format: raw.config.live_label_format.clone(),
+        undoable_change: raw.undoable_change.clone(),
+        unread: raw.has_unread,
+        live_count_badge: raw.config.tray_icon_live_count,
+        icon_theme: raw.config.icon_theme,
+        recent_videos: raw.recent_videos.clone(),
+        recently_ended: raw.recently_ended.clone(),
+        refreshing: raw.refreshing,
+        last_refresh: raw.last_refresh,
+        poll_interval_sec: raw.config.poll_interval_sec,
+        connection_problem_since: raw.connection_problem_since,
+        offline_since: raw.connectivity.offline_since(),
+        token_refresh_failing: raw.token_refresh_failing,
+        scopes_missing: !raw.missing_scopes.is_empty(),
+        followed_cached: raw.followed_cached,
+        cached_categories: raw.cached_categories.clone(),
+        pinned_streams: raw.config.pinned_streams.clone(),
+        pinned_last_live: raw.pinned_last_live.clone(),
+        hide_followed_in_categories: raw.config.hide_followed_in_categories,
+        notification_snoozes: raw.notification_snoozes.clone(),
+    };
+    if raw.is_authenticated {
+        compute_display_state(
+            raw.live_streams,
+            raw.scheduled_streams,
+            raw.schedules_loaded,
+            &raw.followed_categories,
+            &raw.category_streams,
+            &display_config,
+            now,
+        )
+    } else {
+        DisplayState {
+            authenticating: raw.authenticating,
+            login_code: LoginCode::from_progress(raw.login_progress.as_ref()),
+            icon_theme: raw.config.icon_theme,
+            ..DisplayState::unauthenticated()
+        }
+    }
+}
+
+/// Starts the schedule header countdown timer.
+///
+/// Only updates the header and "Updated" label text; the rest of the menu is left alone
+/// until the next display snapshot arrives through `start_listener`.
+pub fn start_schedule_header_timer(tray_backend: Arc<TrayBackend>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULE_HEADER_REFRESH_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let now = Utc::now();
+            if let Err(e) = tray_backend.refresh_schedule_header(now) {
+                tracing::error!("Failed to refresh schedule header: {}", e);
+            }
+            if let Err(e) = tray_backend.refresh_status_label(now) {
+                tracing::error!("Failed to refresh status item: {}", e);
             }
         }
     })
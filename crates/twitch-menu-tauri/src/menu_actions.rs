@@ -0,0 +1,556 @@
+//! Typed dispatch table for menu item IDs.
+//!
+//! Every clickable item's ID is registered in [`HANDLERS`] together with the
+//! parser that turns it into a [`MenuAction`]. A handler claims all of its
+//! keys at once, and lookups prefer exact IDs and then the longest matching
+//! prefix, so a new prefix that happens to overlap an existing one can't
+//! silently take over its clicks.
+//!
+//! Where a click reports its modifier keys, [`MenuAction::with_modifiers`]
+//! turns a plain stream click into an alternate action. The tray can't (Tauri
+//! menu events carry only the ID), so it offers the same actions as submenu
+//! children with their own IDs instead.
+
+use serde::Deserialize;
+use twitch_backend::notification_snooze::SnoozeDuration;
+
+use crate::ids;
+
+/// What a menu click asks the app to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuAction {
+    Login,
+    /// Abandon the login in progress.
+    CancelLogin,
+    /// Copy the login's device code to the clipboard.
+    CopyLoginCode(String),
+    Logout,
+    SwitchUser,
+    OpenSettings,
+    /// Open the "What's New" changelog window.
+    OpenChangelog,
+    /// Open the About window.
+    OpenAbout,
+    /// Open the folder the log files are in.
+    OpenLogs,
+    /// Write a diagnostics bundle and copy its path.
+    CreateDiagnosticsBundle,
+    /// Open the dashboard window.
+    OpenDashboard,
+    /// Revert the most recent streamer settings change.
+    UndoSettingsChange,
+    /// Re-fetch everything now instead of waiting for the next poll.
+    RefreshNow,
+    Quit,
+    /// Open a channel: live, scheduled, category or offline Favourite entry.
+    OpenStream(String),
+    /// Open a channel's popout chat.
+    OpenChat(String),
+    /// Copy a channel's URL to the clipboard.
+    CopyLink(String),
+    /// Open a channel's page in the browser, whatever `open_with` says.
+    OpenChannelPage(String),
+    /// Open the settings window on one streamer's settings.
+    OpenStreamerSettings(String),
+    /// Pin a channel to the top of the menu.
+    PinStream(String),
+    /// Remove a channel from the pinned section.
+    UnpinStream(String),
+    /// Open the window listing more of a category's streams, by category ID.
+    OpenCategoryStreams(String),
+    /// Open a past broadcast by video ID.
+    OpenVideo(String),
+    /// Run the custom action with this `custom_action::action_key`.
+    RunCustomAction(String),
+    /// Snooze every notification.
+    SnoozeNotifications(SnoozeDuration),
+    /// End the snooze on every notification early.
+    ResumeNotifications,
+    /// Snooze notifications about one channel.
+    SnoozeStreamer(String, SnoozeDuration),
+    /// End one channel's snooze early.
+    ResumeStreamer(String),
+}
+
+/// Modifier keys held during a click, for surfaces that report them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct ClickModifiers {
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub alt: bool,
+}
+
+impl MenuAction {
+    /// Applies held modifiers: Ctrl copies a stream's link, Shift opens its
+    /// chat and Alt opens its streamer settings, in that order of precedence.
+    /// Other actions are unchanged.
+    #[must_use]
+    pub fn with_modifiers(self, modifiers: ClickModifiers) -> Self {
+        match self {
+            MenuAction::OpenStream(login) if modifiers.ctrl => MenuAction::CopyLink(login),
+            MenuAction::OpenStream(login) if modifiers.shift => MenuAction::OpenChat(login),
+            MenuAction::OpenStream(login) if modifiers.alt => {
+                MenuAction::OpenStreamerSettings(login)
+            }
+            action => action,
+        }
+    }
+}
+
+/// How a registered key matches an ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuKey {
+    /// The whole ID; the parser receives an empty payload.
+    Exact(&'static str),
+    /// The start of the ID; the parser receives the rest.
+    Prefix(&'static str),
+}
+
+/// Parsers for one kind of menu item.
+pub struct MenuHandler {
+    pub keys: &'static [MenuKey],
+    /// Returns `None` for a malformed payload, which is logged like an unknown ID.
+    pub parse: fn(&str) -> Option<MenuAction>,
+}
+
+/// Every menu item ID the app handles.
+pub const HANDLERS: &[MenuHandler] = &[
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::LOGIN)],
+        parse: |_| Some(MenuAction::Login),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::CANCEL_LOGIN)],
+        parse: |_| Some(MenuAction::CancelLogin),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::COPY_LOGIN_CODE_PREFIX)],
+        parse: |code| (!code.is_empty()).then(|| MenuAction::CopyLoginCode(code.to_string())),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::LOGOUT)],
+        parse: |_| Some(MenuAction::Logout),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::SWITCH_USER)],
+        parse: |_| Some(MenuAction::SwitchUser),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::SETTINGS)],
+        parse: |_| Some(MenuAction::OpenSettings),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::WHATS_NEW)],
+        parse: |_| Some(MenuAction::OpenChangelog),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::ABOUT)],
+        parse: |_| Some(MenuAction::OpenAbout),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::OPEN_LOGS)],
+        parse: |_| Some(MenuAction::OpenLogs),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::DIAGNOSTICS_BUNDLE)],
+        parse: |_| Some(MenuAction::CreateDiagnosticsBundle),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::DASHBOARD)],
+        parse: |_| Some(MenuAction::OpenDashboard),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::UNDO_SETTINGS)],
+        parse: |_| Some(MenuAction::UndoSettingsChange),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::REFRESH)],
+        parse: |_| Some(MenuAction::RefreshNow),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::QUIT)],
+        parse: |_| Some(MenuAction::Quit),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::SNOOZE_ALL_PREFIX)],
+        parse: |key| SnoozeDuration::from_key(key).map(MenuAction::SnoozeNotifications),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Exact(ids::RESUME_NOTIFICATIONS)],
+        parse: |_| Some(MenuAction::ResumeNotifications),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::SNOOZE_STREAMER_PREFIX)],
+        parse: parse_snooze_streamer,
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::RESUME_STREAMER_PREFIX)],
+        parse: |login| (!login.is_empty()).then(|| MenuAction::ResumeStreamer(login.to_string())),
+    },
+    MenuHandler {
+        keys: &[
+            MenuKey::Prefix(ids::STREAM_PREFIX),
+            MenuKey::Prefix(ids::SCHEDULED_PREFIX),
+            MenuKey::Prefix(ids::CATEGORY_STREAM_PREFIX),
+            MenuKey::Prefix(ids::OFFLINE_FAVOURITE_PREFIX),
+            MenuKey::Prefix(ids::PINNED_PREFIX),
+        ],
+        parse: parse_user_login,
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::CHAT_PREFIX)],
+        parse: |login| (!login.is_empty()).then(|| MenuAction::OpenChat(login.to_string())),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::COPY_LINK_PREFIX)],
+        parse: |login| (!login.is_empty()).then(|| MenuAction::CopyLink(login.to_string())),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::CHANNEL_PAGE_PREFIX)],
+        parse: |login| (!login.is_empty()).then(|| MenuAction::OpenChannelPage(login.to_string())),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::STREAMER_SETTINGS_PREFIX)],
+        parse: |login| {
+            (!login.is_empty()).then(|| MenuAction::OpenStreamerSettings(login.to_string()))
+        },
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::PIN_PREFIX)],
+        parse: |login| (!login.is_empty()).then(|| MenuAction::PinStream(login.to_string())),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::UNPIN_PREFIX)],
+        parse: |login| (!login.is_empty()).then(|| MenuAction::UnpinStream(login.to_string())),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::CATEGORY_MORE_PREFIX)],
+        parse: |id| (!id.is_empty()).then(|| MenuAction::OpenCategoryStreams(id.to_string())),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::VIDEO_PREFIX)],
+        parse: |id| (!id.is_empty()).then(|| MenuAction::OpenVideo(id.to_string())),
+    },
+    MenuHandler {
+        keys: &[MenuKey::Prefix(ids::CUSTOM_ACTION_PREFIX)],
+        parse: |key| (!key.is_empty()).then(|| MenuAction::RunCustomAction(key.to_string())),
+    },
+];
+
+fn parse_user_login(login: &str) -> Option<MenuAction> {
+    (!login.is_empty()).then(|| MenuAction::OpenStream(login.to_string()))
+}
+
+/// `<duration key>_<login>`; duration keys have no underscores.
+fn parse_snooze_streamer(payload: &str) -> Option<MenuAction> {
+    let (key, login) = payload.split_once('_')?;
+    let duration = SnoozeDuration::from_key(key)?;
+    (!login.is_empty()).then(|| MenuAction::SnoozeStreamer(login.to_string(), duration))
+}
+
+/// Maps a clicked menu item ID to its action using [`HANDLERS`].
+pub fn parse_menu_id(id: &str) -> Option<MenuAction> {
+    dispatch(HANDLERS, id)
+}
+
+fn dispatch(handlers: &[MenuHandler], id: &str) -> Option<MenuAction> {
+    let mut best: Option<(usize, &MenuHandler)> = None;
+    for handler in handlers {
+        for key in handler.keys {
+            match *key {
+                MenuKey::Exact(exact) if exact == id => return (handler.parse)(""),
+                MenuKey::Prefix(prefix)
+                    if id.starts_with(prefix) && best.is_none_or(|(len, _)| prefix.len() > len) =>
+                {
+                    best = Some((prefix.len(), handler));
+                }
+                _ => {}
+            }
+        }
+    }
+    let (len, handler) = best?;
+    (handler.parse)(&id[len..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // =========================================================
+    // Fixed items
+    // =========================================================
+
+    #[test]
+    fn fixed_items_parse_to_their_actions() {
+        assert_eq!(parse_menu_id(ids::LOGIN), Some(MenuAction::Login));
+        assert_eq!(
+            parse_menu_id(ids::CANCEL_LOGIN),
+            Some(MenuAction::CancelLogin)
+        );
+        assert_eq!(
+            parse_menu_id("copy_login_code_ABCD-EFGH"),
+            Some(MenuAction::CopyLoginCode("ABCD-EFGH".to_string()))
+        );
+        assert_eq!(parse_menu_id(ids::LOGOUT), Some(MenuAction::Logout));
+        assert_eq!(
+            parse_menu_id(ids::SWITCH_USER),
+            Some(MenuAction::SwitchUser)
+        );
+        assert_eq!(parse_menu_id(ids::SETTINGS), Some(MenuAction::OpenSettings));
+        assert_eq!(
+            parse_menu_id(ids::WHATS_NEW),
+            Some(MenuAction::OpenChangelog)
+        );
+        assert_eq!(parse_menu_id(ids::ABOUT), Some(MenuAction::OpenAbout));
+        assert_eq!(parse_menu_id(ids::OPEN_LOGS), Some(MenuAction::OpenLogs));
+        assert_eq!(
+            parse_menu_id(ids::DIAGNOSTICS_BUNDLE),
+            Some(MenuAction::CreateDiagnosticsBundle)
+        );
+        assert_eq!(
+            parse_menu_id(ids::DASHBOARD),
+            Some(MenuAction::OpenDashboard)
+        );
+        assert_eq!(
+            parse_menu_id(ids::UNDO_SETTINGS),
+            Some(MenuAction::UndoSettingsChange)
+        );
+        assert_eq!(parse_menu_id(ids::REFRESH), Some(MenuAction::RefreshNow));
+        assert_eq!(parse_menu_id(ids::QUIT), Some(MenuAction::Quit));
+    }
+
+    #[test]
+    fn exact_ids_do_not_match_as_prefixes() {
+        assert_eq!(parse_menu_id("quit_now"), None);
+    }
+
+    // =========================================================
+    // Stream entries
+    // =========================================================
+
+    #[test]
+    fn every_stream_prefix_opens_the_channel() {
+        for prefix in [
+            ids::STREAM_PREFIX,
+            ids::SCHEDULED_PREFIX,
+            ids::CATEGORY_STREAM_PREFIX,
+            ids::OFFLINE_FAVOURITE_PREFIX,
+            ids::PINNED_PREFIX,
+        ] {
+            assert_eq!(
+                parse_menu_id(&format!("{prefix}shroud")),
+                Some(MenuAction::OpenStream("shroud".to_string())),
+                "prefix {prefix}"
+            );
+        }
+    }
+
+    #[test]
+    fn stream_prefix_without_login_is_rejected() {
+        assert_eq!(parse_menu_id(ids::STREAM_PREFIX), None);
+    }
+
+    #[test]
+    fn alternate_stream_ids_parse_to_their_actions() {
+        assert_eq!(
+            parse_menu_id("chat_shroud"),
+            Some(MenuAction::OpenChat("shroud".to_string()))
+        );
+        assert_eq!(
+            parse_menu_id("copy_link_shroud"),
+            Some(MenuAction::CopyLink("shroud".to_string()))
+        );
+        assert_eq!(
+            parse_menu_id("streamer_settings_shroud"),
+            Some(MenuAction::OpenStreamerSettings("shroud".to_string()))
+        );
+        assert_eq!(
+            parse_menu_id("channel_page_shroud"),
+            Some(MenuAction::OpenChannelPage("shroud".to_string()))
+        );
+        assert_eq!(parse_menu_id(ids::CHAT_PREFIX), None);
+        assert_eq!(parse_menu_id(ids::STREAMER_SETTINGS_PREFIX), None);
+    }
+
+    #[test]
+    fn stream_login_starting_with_chat_stays_a_stream() {
+        assert_eq!(
+            parse_menu_id("stream_chat_bot"),
+            Some(MenuAction::OpenStream("chat_bot".to_string()))
+        );
+    }
+
+    #[test]
+    fn pin_ids_parse_to_pin_actions() {
+        assert_eq!(
+            parse_menu_id("pin_shroud"),
+            Some(MenuAction::PinStream("shroud".to_string()))
+        );
+        assert_eq!(
+            parse_menu_id("unpin_shroud"),
+            Some(MenuAction::UnpinStream("shroud".to_string()))
+        );
+        assert_eq!(parse_menu_id(ids::PIN_PREFIX), None);
+    }
+
+    #[test]
+    fn category_more_id_parses_to_open_category_streams() {
+        assert_eq!(
+            parse_menu_id("cat_more_509658"),
+            Some(MenuAction::OpenCategoryStreams("509658".to_string()))
+        );
+        assert_eq!(parse_menu_id(ids::CATEGORY_MORE_PREFIX), None);
+    }
+
+    #[test]
+    fn video_id_parses_to_open_video() {
+        assert_eq!(
+            parse_menu_id("video_2012345678"),
+            Some(MenuAction::OpenVideo("2012345678".to_string()))
+        );
+        assert_eq!(parse_menu_id(ids::VIDEO_PREFIX), None);
+    }
+
+    #[test]
+    fn snooze_ids_parse_to_snooze_actions() {
+        assert_eq!(
+            parse_menu_id("snooze_all_8h"),
+            Some(MenuAction::SnoozeNotifications(SnoozeDuration::EightHours))
+        );
+        assert_eq!(
+            parse_menu_id(ids::RESUME_NOTIFICATIONS),
+            Some(MenuAction::ResumeNotifications)
+        );
+        assert_eq!(
+            parse_menu_id("snooze_streamer_tomorrow_some_one"),
+            Some(MenuAction::SnoozeStreamer(
+                "some_one".to_string(),
+                SnoozeDuration::UntilTomorrow
+            ))
+        );
+        assert_eq!(
+            parse_menu_id("resume_streamer_shroud"),
+            Some(MenuAction::ResumeStreamer("shroud".to_string()))
+        );
+        assert_eq!(parse_menu_id("snooze_all_2h"), None);
+        assert_eq!(parse_menu_id("snooze_streamer_1h_"), None);
+    }
+
+    // =========================================================
+    // Click modifiers
+    // =========================================================
+
+    fn open(login: &str) -> MenuAction {
+        MenuAction::OpenStream(login.to_string())
+    }
+
+    #[test]
+    fn modifiers_pick_alternate_stream_actions() {
+        let ctrl = ClickModifiers {
+            ctrl: true,
+            ..ClickModifiers::default()
+        };
+        let shift = ClickModifiers {
+            shift: true,
+            ..ClickModifiers::default()
+        };
+        assert_eq!(
+            open("a").with_modifiers(ctrl),
+            MenuAction::CopyLink("a".to_string())
+        );
+        assert_eq!(
+            open("a").with_modifiers(shift),
+            MenuAction::OpenChat("a".to_string())
+        );
+        assert_eq!(
+            open("a").with_modifiers(ClickModifiers {
+                ctrl: true,
+                shift: true,
+                ..ClickModifiers::default()
+            }),
+            MenuAction::CopyLink("a".to_string())
+        );
+        assert_eq!(
+            open("a").with_modifiers(ClickModifiers {
+                alt: true,
+                ..ClickModifiers::default()
+            }),
+            MenuAction::OpenStreamerSettings("a".to_string())
+        );
+        assert_eq!(
+            open("a").with_modifiers(ClickModifiers::default()),
+            open("a")
+        );
+    }
+
+    #[test]
+    fn modifiers_leave_other_actions_alone() {
+        let ctrl = ClickModifiers {
+            ctrl: true,
+            ..ClickModifiers::default()
+        };
+        assert_eq!(MenuAction::Quit.with_modifiers(ctrl), MenuAction::Quit);
+    }
+
+    // =========================================================
+    // Custom actions
+    // =========================================================
+
+    #[test]
+    fn custom_action_parses_key() {
+        assert_eq!(
+            parse_menu_id("custom_action_0123abcd"),
+            Some(MenuAction::RunCustomAction("0123abcd".to_string()))
+        );
+    }
+
+    #[test]
+    fn custom_action_without_key_is_rejected() {
+        assert_eq!(parse_menu_id("custom_action_"), None);
+    }
+
+    // =========================================================
+    // Registry
+    // =========================================================
+
+    #[test]
+    fn unknown_id_is_rejected() {
+        assert_eq!(parse_menu_id("not_a_menu_item"), None);
+    }
+
+    #[test]
+    fn registered_keys_are_unique() {
+        let keys: Vec<MenuKey> = HANDLERS
+            .iter()
+            .flat_map(|h| h.keys.iter().copied())
+            .collect();
+        for (i, key) in keys.iter().enumerate() {
+            assert!(!keys[i + 1..].contains(key), "{key:?} registered twice");
+        }
+    }
+
+    #[test]
+    fn longest_prefix_wins_regardless_of_order() {
+        let handlers = [
+            MenuHandler {
+                keys: &[MenuKey::Prefix("a_")],
+                parse: |rest| Some(MenuAction::OpenStream(rest.to_string())),
+            },
+            MenuHandler {
+                keys: &[MenuKey::Prefix("a_b_")],
+                parse: |rest| Some(MenuAction::RunCustomAction(rest.to_string())),
+            },
+        ];
+        assert_eq!(
+            dispatch(&handlers, "a_b_7"),
+            Some(MenuAction::RunCustomAction("7".to_string()))
+        );
+        assert_eq!(
+            dispatch(&handlers, "a_c"),
+            Some(MenuAction::OpenStream("c".to_string()))
+        );
+    }
+}
@@ -0,0 +1,627 @@
+//! Flattens a `DisplayState` into the rows shown by the window fallback.
+//!
+//! The window has room to scroll, so overflow entries are listed inline rather
+//! than behind "More..." submenus. Row IDs are the tray menu IDs, letting a
+//! click go through the same `handle_menu_event` path.
+
+use serde::Serialize;
+use twitch_backend::notification_snooze::SnoozeDuration;
+
+use crate::display_state::{DisplayState, ScheduledEntry};
+use crate::ids;
+
+/// How a row is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MenuRowKind {
+    /// Section title, not clickable.
+    Header,
+    /// Greyed-out text such as "No streams live", not clickable.
+    Placeholder,
+    /// Clickable entry.
+    Item,
+    Separator,
+}
+
+/// One line of the fallback window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MenuRow {
+    pub kind: MenuRowKind,
+    pub label: String,
+    /// Tray menu ID sent back when the row is clicked; `None` unless `kind` is `Item`.
+    pub id: Option<String>,
+    /// Prompt to confirm before acting (custom shell commands).
+    pub confirm: Option<String>,
+}
+
+impl MenuRow {
+    fn header(label: impl Into<String>) -> Self {
+        Self {
+            kind: MenuRowKind::Header,
+            label: label.into(),
+            id: None,
+            confirm: None,
+        }
+    }
+
+    fn placeholder(label: &str) -> Self {
+        Self {
+            kind: MenuRowKind::Placeholder,
+            label: label.to_string(),
+            id: None,
+            confirm: None,
+        }
+    }
+
+    fn item(id: String, label: impl Into<String>) -> Self {
+        Self {
+            kind: MenuRowKind::Item,
+            label: label.into(),
+            id: Some(id),
+            confirm: None,
+        }
+    }
+
+    fn separator() -> Self {
+        Self {
+            kind: MenuRowKind::Separator,
+            label: String::new(),
+            id: None,
+            confirm: None,
+        }
+    }
+}
+
+/// Builds the window rows for `state`, in tray menu order.
+pub fn compute_menu_rows(state: &DisplayState) -> Vec<MenuRow> {
+    if !state.authenticated && state.authenticating {
+        let mut rows = Vec::new();
+        match &state.login_code {
+            Some(code) => {
+                rows.push(MenuRow::placeholder(&code.label));
+                rows.push(MenuRow::item(
+                    format!("{}{}", ids::COPY_LOGIN_CODE_PREFIX, code.code),
+                    "Copy code",
+                ));
+            }
+            None => rows.push(MenuRow::placeholder("Authenticating…")),
+        }
+        rows.push(MenuRow::item(ids::CANCEL_LOGIN.to_string(), "Cancel login"));
+        rows.push(MenuRow::separator());
+        rows.push(MenuRow::item(ids::QUIT.to_string(), "Quit"));
+        return rows;
+    }
+    if !state.authenticated {
+        return vec![
+            MenuRow::item(ids::LOGIN.to_string(), "Login to Twitch"),
+            MenuRow::separator(),
+            MenuRow::item(ids::QUIT.to_string(), "Quit"),
+        ];
+    }
+
+    let mut rows = Vec::new();
+
+    if let Some(warning) = &state.login_warning {
+        rows.push(MenuRow::item(ids::LOGIN.to_string(), warning));
+    }
+    if let Some(problem) = &state.connection_problem {
+        rows.push(MenuRow::placeholder(problem));
+    }
+
+    // === Pinned ===
+    if !state.pinned.is_empty() {
+        rows.push(MenuRow::header("Pinned"));
+        for pinned in &state.pinned {
+            let prefix = if pinned.live.is_some() {
+                ids::STREAM_PREFIX
+            } else {
+                ids::PINNED_PREFIX
+            };
+            let id = format!("{prefix}{}", pinned.user_login);
+            rows.push(MenuRow::item(id, pinned.label.clone()));
+        }
+    }
+
+    // === Following Live section ===
+    let live = &state.live_section;
+    rows.push(MenuRow::header(live.header()));
+    if live.visible.is_empty() && live.overflow.is_empty() {
+        rows.push(MenuRow::placeholder("No streams live"));
+    } else {
+        for entry in live.visible.iter().chain(&live.overflow) {
+            let id = format!("{}{}", ids::STREAM_PREFIX, entry.stream.user_login);
+            rows.push(MenuRow::item(id, entry.label.clone()));
+        }
+    }
+
+    // === Favourites (offline) ===
+    if !state.offline_favourites.is_empty() {
+        rows.push(MenuRow::header("Favourites (offline)"));
+        for favourite in &state.offline_favourites {
+            let id = format!("{}{}", ids::OFFLINE_FAVOURITE_PREFIX, favourite.user_login);
+            rows.push(MenuRow::item(id, favourite.label.clone()));
+        }
+    }
+
+    // === Category sections ===
+    for section in &state.category_sections {
+        rows.push(MenuRow::header(section.header.clone()));
+        for entry in &section.entries {
+            let id = format!("{}{}", ids::CATEGORY_STREAM_PREFIX, entry.stream.user_login);
+            rows.push(MenuRow::item(id, entry.label.clone()));
+        }
+        if section.has_more {
+            let id = format!("{}{}", ids::CATEGORY_MORE_PREFIX, section.category_id);
+            rows.push(MenuRow::item(id, "Show more…"));
+        }
+    }
+
+    // === Scheduled section ===
+    let schedule = &state.schedule_section;
+    rows.push(MenuRow::header(schedule.header.clone()));
+    if schedule.visible.is_empty() && schedule.overflow.is_empty() {
+        rows.push(MenuRow::placeholder(if schedule.schedules_loaded {
+            "No scheduled streams"
+        } else {
+            "Loading..."
+        }));
+    } else {
+        push_scheduled(&mut rows, schedule.visible.iter().chain(&schedule.overflow));
+    }
+
+    // === Recently Ended ===
+    if !state.recently_ended.is_empty() {
+        rows.push(MenuRow::header("Recently Ended"));
+        for entry in &state.recently_ended {
+            rows.push(MenuRow::item(entry.menu_id(), entry.label.clone()));
+        }
+    }
+
+    // === Past Broadcasts ===
+    if !state.past_broadcasts.is_empty() {
+        rows.push(MenuRow::header("Past Broadcasts"));
+        for streamer in &state.past_broadcasts {
+            rows.push(MenuRow::placeholder(&streamer.display_name));
+            for video in &streamer.videos {
+                let id = format!("{}{}", ids::VIDEO_PREFIX, video.id);
+                rows.push(MenuRow::item(id, video.label.clone()));
+            }
+        }
+    }
+
+    // === Updated, Refresh, Dashboard, Settings, Undo, Snooze, What's New, About, custom actions, Switch User, Logout and Quit ===
+    rows.push(MenuRow::separator());
+    if let Some(status) = &state.refresh_status {
+        rows.push(MenuRow::placeholder(&status.label));
+    }
+    rows.push(if state.refreshing {
+        MenuRow::placeholder("Refreshing…")
+    } else {
+        MenuRow::item(ids::REFRESH.to_string(), "Refresh now")
+    });
+    rows.push(MenuRow::item(ids::DASHBOARD.to_string(), "Dashboard"));
+    rows.push(MenuRow::item(ids::SETTINGS.to_string(), "Settings"));
+    if let Some(label) = &state.undo_label {
+        rows.push(MenuRow::item(ids::UNDO_SETTINGS.to_string(), label.clone()));
+    }
+    match &state.snooze_label {
+        Some(label) => {
+            rows.push(MenuRow::placeholder(label));
+            rows.push(MenuRow::item(
+                ids::RESUME_NOTIFICATIONS.to_string(),
+                "Resume notifications",
+            ));
+        }
+        None => {
+            for duration in SnoozeDuration::ALL {
+                let id = format!("{}{}", ids::SNOOZE_ALL_PREFIX, duration.key());
+                let label = format!("Snooze notifications {}", duration.label().to_lowercase());
+                rows.push(MenuRow::item(id, label));
+            }
+        }
+    }
+    rows.push(MenuRow::item(ids::WHATS_NEW.to_string(), "What's New"));
+    rows.push(MenuRow::item(ids::ABOUT.to_string(), "About"));
+    rows.push(MenuRow::item(
+        ids::OPEN_LOGS.to_string(),
+        "Open Logs Folder",
+    ));
+    rows.push(MenuRow::item(
+        ids::DIAGNOSTICS_BUNDLE.to_string(),
+        "Create Diagnostics Bundle",
+    ));
+    for action in &state.footer_actions {
+        let id = format!("{}{}", ids::CUSTOM_ACTION_PREFIX, action.key);
+        rows.push(MenuRow {
+            confirm: action.confirm_label.clone(),
+            ..MenuRow::item(id, action.label.clone())
+        });
+    }
+    rows.push(MenuRow::item(
+        ids::SWITCH_USER.to_string(),
+        "Switch User...",
+    ));
+    rows.push(MenuRow::item(ids::LOGOUT.to_string(), "Logout"));
+    rows.push(MenuRow::item(ids::QUIT.to_string(), "Quit"));
+
+    rows
+}
+
+/// Adds scheduled entries, inserting a placeholder row wherever the day changes.
+fn push_scheduled<'a>(rows: &mut Vec<MenuRow>, entries: impl Iterator<Item = &'a ScheduledEntry>) {
+    let mut current_day = None;
+    for entry in entries {
+        if let Some(day) = entry.day.as_deref().filter(|&d| current_day != Some(d)) {
+            rows.push(MenuRow::placeholder(day));
+            current_day = Some(day);
+        }
+        let id = format!(
+            "{}{}",
+            ids::SCHEDULED_PREFIX,
+            entry.scheduled.broadcaster_login
+        );
+        rows.push(MenuRow::item(id, entry.label.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display_state::{
+        CategorySection, CategoryStreamEntry, FooterAction, LoginCode, OfflineFavourite,
+        PastBroadcasts, PinnedEntry, RecentlyEndedEntry, RefreshStatus, StreamEntry, VideoEntry,
+    };
+    use crate::test_helpers::{make_scheduled, make_stream};
+    use chrono::Utc;
+
+    // =========================================================
+    // Test helpers
+    // =========================================================
+
+    fn authenticated() -> DisplayState {
+        DisplayState {
+            authenticated: true,
+            ..DisplayState::unauthenticated()
+        }
+    }
+
+    fn live_entry(name: &str) -> StreamEntry {
+        StreamEntry {
+            stream: make_stream(name, name),
+            label: name.to_string(),
+            is_hot: false,
+        }
+    }
+
+    fn scheduled_entry(login: &str, day: Option<&str>) -> ScheduledEntry {
+        ScheduledEntry {
+            scheduled: make_scheduled(login, 1),
+            label: login.to_string(),
+            day: day.map(str::to_string),
+        }
+    }
+
+    fn labels(rows: &[MenuRow]) -> Vec<&str> {
+        rows.iter().map(|r| r.label.as_str()).collect()
+    }
+
+    // =========================================================
+    // compute_menu_rows
+    // =========================================================
+
+    #[test]
+    fn unauthenticated_shows_login_and_quit() {
+        let rows = compute_menu_rows(&DisplayState::unauthenticated());
+        let row_ids: Vec<_> = rows.iter().filter_map(|r| r.id.as_deref()).collect();
+        assert_eq!(row_ids, vec![ids::LOGIN, ids::QUIT]);
+    }
+
+    #[test]
+    fn login_in_progress_can_be_cancelled() {
+        let state = DisplayState {
+            authenticating: true,
+            ..DisplayState::unauthenticated()
+        };
+        let rows = compute_menu_rows(&state);
+        assert_eq!(rows[0].kind, MenuRowKind::Placeholder);
+        assert_eq!(rows[0].label, "Authenticating…");
+        let row_ids: Vec<_> = rows.iter().filter_map(|r| r.id.as_deref()).collect();
+        assert_eq!(row_ids, vec![ids::CANCEL_LOGIN, ids::QUIT]);
+    }
+
+    #[test]
+    fn pending_login_code_is_shown_and_copyable() {
+        let state = DisplayState {
+            authenticating: true,
+            login_code: Some(LoginCode {
+                code: "ABCD-EFGH".to_string(),
+                label: "Enter ABCD-EFGH at twitch.tv/activate".to_string(),
+            }),
+            ..DisplayState::unauthenticated()
+        };
+        let rows = compute_menu_rows(&state);
+        assert_eq!(rows[0].label, "Enter ABCD-EFGH at twitch.tv/activate");
+        assert_eq!(rows[1].id.as_deref(), Some("copy_login_code_ABCD-EFGH"));
+        assert_eq!(rows[2].id.as_deref(), Some(ids::CANCEL_LOGIN));
+    }
+
+    #[test]
+    fn live_overflow_is_listed_inline() {
+        let mut state = authenticated();
+        state.live_section.visible = vec![live_entry("Alice")];
+        state.live_section.overflow = vec![live_entry("Bob")];
+
+        let rows = compute_menu_rows(&state);
+
+        assert_eq!(rows[0].label, "Following Live (2)");
+        assert_eq!(rows[1].id.as_deref(), Some("stream_alice"));
+        assert_eq!(rows[2].id.as_deref(), Some("stream_bob"));
+    }
+
+    #[test]
+    fn pinned_section_comes_first() {
+        let mut state = authenticated();
+        state.pinned = vec![
+            PinnedEntry {
+                user_login: "alice".to_string(),
+                label: "Alice".to_string(),
+                live: Some(live_entry("Alice")),
+            },
+            PinnedEntry {
+                user_login: "bob".to_string(),
+                label: "Bob - offline".to_string(),
+                live: None,
+            },
+        ];
+
+        let rows = compute_menu_rows(&state);
+
+        assert_eq!(rows[0].label, "Pinned");
+        assert_eq!(rows[1].id.as_deref(), Some("stream_alice"));
+        assert_eq!(rows[2].id.as_deref(), Some("pinned_bob"));
+        assert_eq!(rows[3].label, "Following Live");
+    }
+
+    #[test]
+    fn empty_sections_show_placeholders() {
+        let mut state = authenticated();
+        state.schedule_section.header = "Scheduled (next 6h)".to_string();
+        state.schedule_section.schedules_loaded = true;
+
+        let rows = compute_menu_rows(&state);
+
+        assert_eq!(
+            labels(&rows[..4]),
+            vec![
+                "Following Live",
+                "No streams live",
+                "Scheduled (next 6h)",
+                "No scheduled streams"
+            ]
+        );
+        assert_eq!(rows[1].kind, MenuRowKind::Placeholder);
+    }
+
+    #[test]
+    fn category_streams_use_category_ids() {
+        let mut state = authenticated();
+        state.category_sections = vec![CategorySection {
+            category_id: "chess".to_string(),
+            header: "Chess (2k)".to_string(),
+            entries: vec![CategoryStreamEntry {
+                stream: make_stream("1", "Carol"),
+                label: "Carol".to_string(),
+            }],
+            has_more: true,
+        }];
+
+        let rows = compute_menu_rows(&state);
+        let header = rows.iter().position(|r| r.label == "Chess (2k)").unwrap();
+
+        assert_eq!(rows[header].kind, MenuRowKind::Header);
+        assert_eq!(rows[header + 1].id.as_deref(), Some("cat_stream_carol"));
+        assert_eq!(rows[header + 2].label, "Show more…");
+        assert_eq!(rows[header + 2].id.as_deref(), Some("cat_more_chess"));
+    }
+
+    #[test]
+    fn schedule_day_headers_span_visible_and_overflow() {
+        let mut state = authenticated();
+        state.schedule_section.visible = vec![scheduled_entry("a", Some("Today"))];
+        state.schedule_section.overflow = vec![
+            scheduled_entry("b", Some("Today")),
+            scheduled_entry("c", Some("Tomorrow")),
+        ];
+
+        let rows = compute_menu_rows(&state);
+        let start = rows.iter().position(|r| r.label == "Today").unwrap();
+
+        assert_eq!(
+            labels(&rows[start..start + 5]),
+            vec!["Today", "a", "b", "Tomorrow", "c"]
+        );
+    }
+
+    #[test]
+    fn offline_favourites_follow_live_section() {
+        let mut state = authenticated();
+        state.offline_favourites = vec![OfflineFavourite {
+            user_login: "dave".to_string(),
+            label: "Dave - seen 2d ago".to_string(),
+        }];
+
+        let rows = compute_menu_rows(&state);
+
+        assert_eq!(
+            labels(&rows[..4]),
+            vec![
+                "Following Live",
+                "No streams live",
+                "Favourites (offline)",
+                "Dave - seen 2d ago"
+            ]
+        );
+        assert_eq!(rows[3].id.as_deref(), Some("offline_dave"));
+    }
+
+    #[test]
+    fn recently_ended_rows_open_the_vod() {
+        let mut state = authenticated();
+        state.recently_ended = vec![RecentlyEndedEntry {
+            user_login: "dave".to_string(),
+            video_id: Some("123".to_string()),
+            label: "Dave - Speedruns (ended 5m ago)".to_string(),
+        }];
+
+        let rows = compute_menu_rows(&state);
+        let header = rows
+            .iter()
+            .position(|r| r.label == "Recently Ended")
+            .unwrap();
+
+        assert_eq!(rows[header + 1].id.as_deref(), Some("video_123"));
+    }
+
+    #[test]
+    fn past_broadcasts_listed_under_streamer_names() {
+        let mut state = authenticated();
+        state.past_broadcasts = vec![PastBroadcasts {
+            display_name: "Dave".to_string(),
+            videos: vec![VideoEntry {
+                id: "123".to_string(),
+                label: "Jan 5 - Speedruns (3h 8m)".to_string(),
+            }],
+        }];
+
+        let rows = compute_menu_rows(&state);
+        let header = rows
+            .iter()
+            .position(|r| r.label == "Past Broadcasts")
+            .unwrap();
+
+        assert_eq!(rows[header + 1].kind, MenuRowKind::Placeholder);
+        assert_eq!(rows[header + 1].label, "Dave");
+        assert_eq!(rows[header + 2].id.as_deref(), Some("video_123"));
+    }
+
+    #[test]
+    fn footer_keeps_menu_order_and_command_confirmation() {
+        let mut state = authenticated();
+        state.footer_actions = vec![FooterAction {
+            key: "0123abcd".to_string(),
+            label: "Start OBS".to_string(),
+            confirm_label: Some("Run: obs".to_string()),
+        }];
+
+        let rows = compute_menu_rows(&state);
+        let footer: Vec<_> = rows.iter().rev().take(13).rev().collect();
+
+        assert_eq!(footer[0].id.as_deref(), Some(ids::DASHBOARD));
+        assert_eq!(footer[1].id.as_deref(), Some(ids::SETTINGS));
+        assert_eq!(footer[2].id.as_deref(), Some("snooze_all_1h"));
+        assert_eq!(footer[4].label, "Snooze notifications until tomorrow");
+        assert_eq!(footer[5].id.as_deref(), Some(ids::WHATS_NEW));
+        assert_eq!(footer[6].id.as_deref(), Some(ids::ABOUT));
+        assert_eq!(footer[7].id.as_deref(), Some(ids::OPEN_LOGS));
+        assert_eq!(footer[8].id.as_deref(), Some(ids::DIAGNOSTICS_BUNDLE));
+        assert_eq!(footer[9].id.as_deref(), Some("custom_action_0123abcd"));
+        assert_eq!(footer[9].confirm.as_deref(), Some("Run: obs"));
+        assert_eq!(footer[10].id.as_deref(), Some(ids::SWITCH_USER));
+        assert_eq!(footer[11].id.as_deref(), Some(ids::LOGOUT));
+        assert_eq!(footer[12].id.as_deref(), Some(ids::QUIT));
+    }
+
+    #[test]
+    fn snoozed_footer_offers_to_resume() {
+        let mut state = authenticated();
+        state.snooze_label = Some("Notifications snoozed until 1:00 PM".to_string());
+        let rows = compute_menu_rows(&state);
+        let resume = rows
+            .iter()
+            .position(|r| r.id.as_deref() == Some(ids::RESUME_NOTIFICATIONS))
+            .unwrap();
+        assert_eq!(
+            rows[resume - 1].label,
+            "Notifications snoozed until 1:00 PM"
+        );
+        assert!(!rows.iter().any(|r| r
+            .id
+            .as_deref()
+            .is_some_and(|id| id.starts_with(ids::SNOOZE_ALL_PREFIX))));
+    }
+
+    #[test]
+    fn refresh_item_disabled_while_refreshing() {
+        let mut state = authenticated();
+        let rows = compute_menu_rows(&state);
+        let settings = rows
+            .iter()
+            .position(|r| r.id.as_deref() == Some(ids::SETTINGS))
+            .unwrap();
+        assert_eq!(rows[settings - 1].id.as_deref(), Some(ids::REFRESH));
+
+        state.refreshing = true;
+        let rows = compute_menu_rows(&state);
+        assert!(!rows.iter().any(|r| r.id.as_deref() == Some(ids::REFRESH)));
+        assert_eq!(rows[settings - 1].kind, MenuRowKind::Placeholder);
+        assert_eq!(rows[settings - 1].label, "Refreshing…");
+    }
+
+    #[test]
+    fn connection_problem_heads_the_menu() {
+        let mut state = authenticated();
+        state.connection_problem = Some("Connection problems since 2:05 PM".to_string());
+        let rows = compute_menu_rows(&state);
+
+        assert_eq!(rows[0].kind, MenuRowKind::Placeholder);
+        assert_eq!(rows[0].label, "Connection problems since 2:05 PM");
+        assert_eq!(rows[1].label, "Following Live");
+    }
+
+    #[test]
+    fn login_warning_heads_the_menu_and_logs_in() {
+        let mut state = authenticated();
+        state.login_warning = Some("Couldn't renew Twitch login".to_string());
+        state.connection_problem = Some("Connection problems since 2:05 PM".to_string());
+        let rows = compute_menu_rows(&state);
+
+        assert_eq!(rows[0].id.as_deref(), Some(ids::LOGIN));
+        assert_eq!(rows[0].label, "Couldn't renew Twitch login");
+        assert_eq!(rows[1].label, "Connection problems since 2:05 PM");
+    }
+
+    #[test]
+    fn refresh_status_sits_above_refresh_item() {
+        let mut state = authenticated();
+        state.refresh_status = Some(RefreshStatus {
+            last_refresh: Utc::now(),
+            poll_interval_sec: 60,
+            label: "Updated just now".to_string(),
+        });
+        let rows = compute_menu_rows(&state);
+        let refresh = rows
+            .iter()
+            .position(|r| r.id.as_deref() == Some(ids::REFRESH))
+            .unwrap();
+        assert_eq!(rows[refresh - 1].kind, MenuRowKind::Placeholder);
+        assert_eq!(rows[refresh - 1].label, "Updated just now");
+    }
+
+    #[test]
+    fn undo_item_follows_settings_when_available() {
+        let mut state = authenticated();
+        let rows = compute_menu_rows(&state);
+        assert!(!rows
+            .iter()
+            .any(|r| r.id.as_deref() == Some(ids::UNDO_SETTINGS)));
+
+        state.undo_label = Some("Undo Change to ninja".to_string());
+        let rows = compute_menu_rows(&state);
+        let settings = rows
+            .iter()
+            .position(|r| r.id.as_deref() == Some(ids::SETTINGS))
+            .unwrap();
+        assert_eq!(rows[settings + 1].id.as_deref(), Some(ids::UNDO_SETTINGS));
+        assert_eq!(rows[settings + 1].label, "Undo Change to ninja");
+    }
+}
@@ -1,14 +1,32 @@
+use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 
+use chrono::{DateTime, Utc};
 use tauri::{
     image::Image,
-    menu::{Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder},
-    tray::{TrayIcon, TrayIconBuilder},
-    AppHandle, Emitter,
+    menu::{Menu, MenuBuilder, MenuItem, MenuItemBuilder, SubmenuBuilder},
+    tray::{MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Emitter, Manager,
 };
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use twitch_backend::app_services::AppServices;
+use twitch_backend::config::IconTheme;
+use twitch_backend::notification_snooze::SnoozeDuration;
+use twitch_backend::notify::OpenTarget;
+use twitch_backend::twitch::chat_popout_url;
 
 use crate::display::DisplayBackend;
-use crate::display_state::DisplayState;
+use crate::display_state::{
+    format_refresh_status, format_schedule_header, format_stream_details, DisplayState, LoginCode,
+    NextScheduled, StreamEntry, TOOLTIP,
+};
+use crate::icon::{
+    add_count_badge, add_unread_dot, add_warning_triangle, apply_icon_theme, dim, is_template_icon,
+    resolve_icon_theme,
+};
+use crate::ids;
+use crate::label::menu_label;
+use crate::menu_actions::{parse_menu_id, ClickModifiers, MenuAction};
 
 const ICON_BYTES: &[u8] = include_bytes!(concat!(
     env!("CARGO_MANIFEST_DIR"),
@@ -19,19 +37,15 @@ const ICON_GREY_BYTES: &[u8] = include_bytes!(concat!(
     "/../twitch-app-tauri/icons/icon_grey.png"
 ));
 
-/// Menu item IDs
-mod ids {
-    pub const LOGIN: &str = "login";
-    pub const LOGOUT: &str = "logout";
-    pub const QUIT: &str = "quit";
-    pub const SETTINGS: &str = "settings";
-    pub const STREAM_PREFIX: &str = "stream_";
-    pub const SCHEDULED_PREFIX: &str = "scheduled_";
-    pub const CATEGORY_STREAM_PREFIX: &str = "cat_stream_";
-}
+/// Emitted when the menu is about to be shown, so live streams can be refreshed.
+pub const MENU_OPENING_EVENT: &str = "menu-opening";
 
-/// Loads an image from embedded PNG bytes
-fn load_icon(bytes: &[u8]) -> tauri::Result<Image<'static>> {
+/// Emitted when the user has opened the menu, which clears the unread icon.
+/// Unlike [`MENU_OPENING_EVENT`], hovering the icon doesn't count.
+pub const MENU_OPENED_EVENT: &str = "menu-opened";
+
+/// Decodes embedded PNG bytes into RGBA pixels and their dimensions
+fn decode_png(bytes: &[u8]) -> tauri::Result<(Vec<u8>, u32, u32)> {
     let decoder = png::Decoder::new(bytes);
     let mut reader = decoder
         .read_info()
@@ -42,7 +56,68 @@ fn load_icon(bytes: &[u8]) -> tauri::Result<Image<'static>> {
         .map_err(|e| tauri::Error::Anyhow(e.into()))?;
     buf.truncate(info.buffer_size());
 
-    Ok(Image::new_owned(buf, info.width, info.height))
+    Ok((buf, info.width, info.height))
+}
+
+/// Loads an image from embedded PNG bytes
+fn load_icon(bytes: &[u8]) -> tauri::Result<Image<'static>> {
+    let (buf, width, height) = decode_png(bytes)?;
+    Ok(Image::new_owned(buf, width, height))
+}
+
+/// The normal icon in a resolved `theme`, with the unread dot until the menu
+/// is opened, the live stream count when that is enabled, and a warning
+/// while refreshes fail
+fn load_live_icon(
+    theme: IconTheme,
+    unread: bool,
+    live_count: Option<usize>,
+    warning: bool,
+) -> tauri::Result<Image<'static>> {
+    let (mut buf, width, height) = decode_png(ICON_BYTES)?;
+    apply_icon_theme(&mut buf, theme);
+    if unread {
+        add_unread_dot(&mut buf, width, height);
+    }
+    if warning {
+        add_warning_triangle(&mut buf, width, height);
+    }
+    if let Some(count) = live_count {
+        add_count_badge(&mut buf, width, height, count);
+    }
+    Ok(Image::new_owned(buf, width, height))
+}
+
+/// The logged-out icon in a resolved `theme`: grey, or dimmed for the
+/// single-colour themes
+fn load_logged_out_icon(theme: IconTheme) -> tauri::Result<Image<'static>> {
+    if matches!(theme, IconTheme::Auto | IconTheme::Colour) {
+        return load_icon(ICON_GREY_BYTES);
+    }
+    let (mut buf, width, height) = decode_png(ICON_BYTES)?;
+    apply_icon_theme(&mut buf, theme);
+    dim(&mut buf);
+    Ok(Image::new_owned(buf, width, height))
+}
+
+/// The live schedule header item and what it needs to re-render its countdown.
+struct ScheduleHeader {
+    item: MenuItem<tauri::Wry>,
+    lookahead_hours: u64,
+    next_up: Option<NextScheduled>,
+}
+
+/// The "Updated 2m ago" footer item and what it needs to re-render.
+struct RefreshStatusItem {
+    item: MenuItem<tauri::Wry>,
+    last_refresh: DateTime<Utc>,
+    poll_interval_sec: u64,
+}
+
+/// Menu items re-rendered between rebuilds, returned by `render_display_state`.
+struct LiveItems {
+    schedule_header: MenuItem<tauri::Wry>,
+    refresh_status: Option<MenuItem<tauri::Wry>>,
 }
 
 /// System tray adapter that implements [`DisplayBackend`].
@@ -56,6 +131,10 @@ pub struct TrayBackend {
     /// Serialises menu rebuilds to prevent concurrent GTK operations which
     /// can crash libayatana-appindicator on Linux.
     rebuild_lock: Arc<Mutex<()>>,
+    /// Header of the current menu's schedule section, refreshed between rebuilds.
+    schedule_header: Arc<Mutex<Option<ScheduleHeader>>>,
+    /// The current menu's "Updated" item, refreshed between rebuilds.
+    refresh_status: Arc<Mutex<Option<RefreshStatusItem>>>,
 }
 
 impl TrayBackend {
@@ -64,17 +143,82 @@ impl TrayBackend {
         Self {
             app_handle,
             rebuild_lock: Arc::new(Mutex::new(())),
+            schedule_header: Arc::new(Mutex::new(None)),
+            refresh_status: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Re-renders the "Updated 2m ago" item without rebuilding the menu, so
+    /// it ages (and turns stale) even when refreshes stop arriving.
+    ///
+    /// No-op before the first refresh or when logged out.
+    pub fn refresh_status_label(&self, now: DateTime<Utc>) -> anyhow::Result<()> {
+        let refresh_status = Arc::clone(&self.refresh_status);
+        self.app_handle
+            .run_on_main_thread(move || {
+                let guard = refresh_status
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                let Some(status) = guard.as_ref() else {
+                    return;
+                };
+                let text =
+                    format_refresh_status(status.last_refresh, status.poll_interval_sec, now);
+                if let Err(e) = status.item.set_text(text) {
+                    tracing::error!("Failed to refresh status item: {}", e);
+                }
+            })
+            .map_err(anyhow::Error::from)
+    }
+
+    /// Re-renders the schedule header countdown without rebuilding the menu.
+    ///
+    /// No-op when the current menu has no schedule section (e.g. logged out).
+    pub fn refresh_schedule_header(&self, now: DateTime<Utc>) -> anyhow::Result<()> {
+        let schedule_header = Arc::clone(&self.schedule_header);
+        self.app_handle
+            .run_on_main_thread(move || {
+                let guard = schedule_header
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                let Some(header) = guard.as_ref() else {
+                    return;
+                };
+                let text =
+                    format_schedule_header(header.lookahead_hours, header.next_up.as_ref(), now);
+                if let Err(e) = header.item.set_text(menu_label(&text)) {
+                    tracing::error!("Failed to refresh schedule header: {}", e);
+                }
+            })
+            .map_err(anyhow::Error::from)
+    }
+
     /// Creates the initial tray icon.
     pub fn create_tray(&self) -> tauri::Result<TrayIcon> {
         let icon = load_icon(ICON_GREY_BYTES)?;
 
         let tray = TrayIconBuilder::with_id("main")
             .icon(icon)
-            .tooltip("Twitch Tray")
+            .tooltip(TOOLTIP)
             .show_menu_on_left_click(true)
+            .on_tray_icon_event(|tray, event| {
+                // Hovering gives the refresh a head start on the click. Linux
+                // reports neither, so the menu there waits for the next poll
+                // and the unread icon clears on the next menu item click.
+                let clicked = matches!(
+                    event,
+                    TrayIconEvent::Click {
+                        button_state: MouseButtonState::Down,
+                        ..
+                    }
+                );
+                if clicked || matches!(event, TrayIconEvent::Enter { .. }) {
+                    tray.app_handle().emit(MENU_OPENING_EVENT, ()).ok();
+                }
+                if clicked {
+                    tray.app_handle().emit(MENU_OPENED_EVENT, ()).ok();
+                }
+            })
             .build(&self.app_handle)?;
 
         Ok(tray)
@@ -96,37 +240,71 @@ impl DisplayBackend for TrayBackend {
 
         let app_handle = self.app_handle.clone();
         let authenticated = state.authenticated;
+        let authenticating = state.authenticating;
+        let login_code = state.login_code.clone();
+        let unread = state.unread;
+        let warning = state.connection_problem.is_some() || state.login_warning.is_some();
+        let theme = resolve_icon_theme(state.icon_theme);
+        let live_count = state.live_count;
+        let tooltip = state.tooltip.clone();
 
         // Build and set menu on the main thread to avoid GTK threading issues.
         // Clone the handle so the closure can own it while we call the method on the original.
         let app_handle_closure = app_handle.clone();
+        let schedule_header = Arc::clone(&self.schedule_header);
+        let refresh_status = Arc::clone(&self.refresh_status);
         app_handle
             .run_on_main_thread(move || {
                 let app_handle = app_handle_closure;
                 let menu_result = if authenticated {
-                    render_display_state(&app_handle, &state)
+                    render_display_state(&app_handle, &state).map(|(menu, live_items)| {
+                        let header = ScheduleHeader {
+                            item: live_items.schedule_header,
+                            lookahead_hours: state.schedule_section.lookahead_hours,
+                            next_up: state.schedule_section.next_up.clone(),
+                        };
+                        let status = live_items
+                            .refresh_status
+                            .zip(state.refresh_status.as_ref())
+                            .map(|(item, status)| RefreshStatusItem {
+                                item,
+                                last_refresh: status.last_refresh,
+                                poll_interval_sec: status.poll_interval_sec,
+                            });
+                        (menu, Some(header), status)
+                    })
                 } else {
-                    build_unauthenticated_menu(&app_handle)
+                    build_unauthenticated_menu(&app_handle, authenticating, login_code.as_ref())
+                        .map(|menu| (menu, None, None))
                 };
 
-                let menu = match menu_result {
+                let (menu, header, status) = match menu_result {
                     Ok(m) => m,
                     Err(e) => {
                         tracing::error!("Failed to build menu: {}", e);
                         return;
                     }
                 };
+                *schedule_header
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner) = header;
+                *refresh_status
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner) = status;
 
                 if let Some(tray) = app_handle.tray_by_id("main") {
                     if let Err(e) = tray.set_menu(Some(menu)) {
                         tracing::error!("Failed to set tray menu: {}", e);
                         return;
                     }
+                    if let Err(e) = tray.set_tooltip(Some(&tooltip)) {
+                        tracing::error!("Failed to set tray tooltip: {}", e);
+                    }
 
                     let icon_result = if authenticated {
-                        load_icon(ICON_BYTES)
+                        load_live_icon(theme, unread, live_count, warning)
                     } else {
-                        load_icon(ICON_GREY_BYTES)
+                        load_logged_out_icon(theme)
                     };
 
                     match icon_result {
@@ -134,6 +312,10 @@ impl DisplayBackend for TrayBackend {
                             if let Err(e) = tray.set_icon(Some(icon)) {
                                 tracing::error!("Failed to set tray icon: {}", e);
                             }
+                            // Only has an effect on macOS
+                            if let Err(e) = tray.set_icon_as_template(is_template_icon(theme)) {
+                                tracing::error!("Failed to set tray icon template: {}", e);
+                            }
                         }
                         Err(e) => {
                             tracing::error!("Failed to load icon: {}", e);
@@ -145,10 +327,39 @@ impl DisplayBackend for TrayBackend {
     }
 }
 
-fn build_unauthenticated_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
-    let login = MenuItemBuilder::with_id(ids::LOGIN, "Login to Twitch").build(app)?;
+fn build_unauthenticated_menu(
+    app: &AppHandle,
+    authenticating: bool,
+    login_code: Option<&LoginCode>,
+) -> tauri::Result<Menu<tauri::Wry>> {
     let quit = MenuItemBuilder::with_id(ids::QUIT, "Quit").build(app)?;
+    if authenticating {
+        let mut items: Vec<Box<dyn tauri::menu::IsMenuItem<tauri::Wry>>> = Vec::new();
+        let status = login_code.map_or("Authenticating…", |c| c.label.as_str());
+        items.push(Box::new(
+            MenuItemBuilder::new(status).enabled(false).build(app)?,
+        ));
+        if let Some(code) = login_code {
+            let id = format!("{}{}", ids::COPY_LOGIN_CODE_PREFIX, code.code);
+            items.push(Box::new(
+                MenuItemBuilder::with_id(id, "Copy code").build(app)?,
+            ));
+        }
+        items.push(Box::new(
+            MenuItemBuilder::with_id(ids::CANCEL_LOGIN, "Cancel login").build(app)?,
+        ));
+        items.push(Box::new(quit));
+        return MenuBuilder::new(app)
+            .items(
+                &items
+                    .iter()
+                    .map(std::convert::AsRef::as_ref)
+                    .collect::<Vec<_>>(),
+            )
+            .build();
+    }
 
+    let login = MenuItemBuilder::with_id(ids::LOGIN, "Login to Twitch").build(app)?;
     MenuBuilder::new(app).items(&[&login, &quit]).build()
 }
 
@@ -156,18 +367,63 @@ fn build_unauthenticated_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>
 ///
 /// This is the only function in `tray/mod.rs` that knows about Tauri types.
 /// All business logic (sorting, filtering, labelling) lives in `display_state.rs`.
-fn render_display_state(app: &AppHandle, state: &DisplayState) -> tauri::Result<Menu<tauri::Wry>> {
+/// Labels containing Twitch-provided text go through `menu_label` here, at the
+/// toolkit boundary, so the pure display state stays unescaped.
+///
+/// Also returns the schedule header and "Updated" items so they can be refreshed in place.
+fn render_display_state(
+    app: &AppHandle,
+    state: &DisplayState,
+) -> tauri::Result<(Menu<tauri::Wry>, LiveItems)> {
     let mut items: Vec<Box<dyn tauri::menu::IsMenuItem<tauri::Wry>>> = Vec::new();
 
+    if let Some(warning) = &state.login_warning {
+        items.push(Box::new(
+            MenuItemBuilder::with_id(ids::LOGIN, warning).build(app)?,
+        ));
+    }
+    if let Some(problem) = &state.connection_problem {
+        items.push(Box::new(
+            MenuItemBuilder::new(problem).enabled(false).build(app)?,
+        ));
+    }
+
+    // === Pinned ===
+    if !state.pinned.is_empty() {
+        items.push(Box::new(
+            MenuItemBuilder::new("Pinned").enabled(false).build(app)?,
+        ));
+        for pinned in &state.pinned {
+            if let Some(entry) = &pinned.live {
+                items.push(live_stream_item(
+                    app,
+                    entry,
+                    true,
+                    &state.snoozed_streamers,
+                )?);
+                continue;
+            }
+            let login = &pinned.user_login;
+            let open =
+                MenuItemBuilder::with_id(format!("{}{login}", ids::PINNED_PREFIX), "Open stream")
+                    .build(app)?;
+            let unpin = MenuItemBuilder::with_id(format!("{}{login}", ids::UNPIN_PREFIX), "Unpin")
+                .build(app)?;
+            items.push(Box::new(
+                SubmenuBuilder::new(app, menu_label(&pinned.label))
+                    .item(&open)
+                    .item(&unpin)
+                    .build()?,
+            ));
+        }
+    }
+
     // === Following Live section ===
     let total_live = state.live_section.visible.len() + state.live_section.overflow.len();
-    let live_title = if total_live == 0 {
-        "Following Live".to_string()
-    } else {
-        format!("Following Live ({total_live})")
-    };
     items.push(Box::new(
-        MenuItemBuilder::new(live_title).enabled(false).build(app)?,
+        MenuItemBuilder::new(state.live_section.header())
+            .enabled(false)
+            .build(app)?,
     ));
 
     if total_live == 0 {
@@ -178,10 +434,12 @@ fn render_display_state(app: &AppHandle, state: &DisplayState) -> tauri::Result<
         ));
     } else {
         for entry in &state.live_section.visible {
-            let id = format!("{}{}", ids::STREAM_PREFIX, entry.stream.user_login);
-            items.push(Box::new(
-                MenuItemBuilder::with_id(id, &entry.label).build(app)?,
-            ));
+            items.push(live_stream_item(
+                app,
+                entry,
+                false,
+                &state.snoozed_streamers,
+            )?);
         }
 
         if !state.live_section.overflow.is_empty() {
@@ -189,15 +447,28 @@ fn render_display_state(app: &AppHandle, state: &DisplayState) -> tauri::Result<
             let mut more_submenu = SubmenuBuilder::new(app, more_label);
 
             for entry in &state.live_section.overflow {
-                let id = format!("{}{}", ids::STREAM_PREFIX, entry.stream.user_login);
-                let item = MenuItemBuilder::with_id(id, &entry.label).build(app)?;
-                more_submenu = more_submenu.item(&item);
+                more_submenu = more_submenu
+                    .item(live_stream_item(app, entry, false, &state.snoozed_streamers)?.as_ref());
             }
 
             items.push(Box::new(more_submenu.build()?));
         }
     }
 
+    // === Favourites (offline) ===
+    if !state.offline_favourites.is_empty() {
+        let mut offline_submenu = SubmenuBuilder::new(
+            app,
+            format!("Favourites (offline) ({})", state.offline_favourites.len()),
+        );
+        for favourite in &state.offline_favourites {
+            let id = format!("{}{}", ids::OFFLINE_FAVOURITE_PREFIX, favourite.user_login);
+            let item = MenuItemBuilder::with_id(id, menu_label(&favourite.label)).build(app)?;
+            offline_submenu = offline_submenu.item(&item);
+        }
+        items.push(Box::new(offline_submenu.build()?));
+    }
+
     // === Category sections ===
     if !state.category_sections.is_empty() {
         items.push(Box::new(
@@ -207,24 +478,28 @@ fn render_display_state(app: &AppHandle, state: &DisplayState) -> tauri::Result<
         ));
 
         for cat_section in &state.category_sections {
-            let mut cat_submenu = SubmenuBuilder::new(app, &cat_section.header);
+            let mut cat_submenu = SubmenuBuilder::new(app, menu_label(&cat_section.header));
 
             for entry in &cat_section.entries {
                 let id = format!("{}{}", ids::CATEGORY_STREAM_PREFIX, entry.stream.user_login);
-                let item = MenuItemBuilder::with_id(id, &entry.label).build(app)?;
+                let item = MenuItemBuilder::with_id(id, menu_label(&entry.label)).build(app)?;
                 cat_submenu = cat_submenu.item(&item);
             }
+            if cat_section.has_more {
+                let id = format!("{}{}", ids::CATEGORY_MORE_PREFIX, cat_section.category_id);
+                let more = MenuItemBuilder::with_id(id, "Show more…").build(app)?;
+                cat_submenu = cat_submenu.separator().item(&more);
+            }
 
             items.push(Box::new(cat_submenu.build()?));
         }
     }
 
     // === Scheduled section ===
-    items.push(Box::new(
-        MenuItemBuilder::new(&state.schedule_section.header)
-            .enabled(false)
-            .build(app)?,
-    ));
+    let schedule_header = MenuItemBuilder::new(menu_label(&state.schedule_section.header))
+        .enabled(false)
+        .build(app)?;
+    items.push(Box::new(schedule_header.clone()));
 
     let total_sched = state.schedule_section.visible.len() + state.schedule_section.overflow.len();
     if total_sched == 0 {
@@ -237,14 +512,24 @@ fn render_display_state(app: &AppHandle, state: &DisplayState) -> tauri::Result<
             MenuItemBuilder::new(label).enabled(false).build(app)?,
         ));
     } else {
+        // Entries are sorted by start time, so a day header goes wherever the day changes
+        let mut current_day = None;
         for entry in &state.schedule_section.visible {
+            if let Some(day) = entry.day.as_deref().filter(|&d| current_day != Some(d)) {
+                items.push(Box::new(
+                    MenuItemBuilder::new(format!("  {day}"))
+                        .enabled(false)
+                        .build(app)?,
+                ));
+                current_day = Some(day);
+            }
             let id = format!(
                 "{}{}",
                 ids::SCHEDULED_PREFIX,
                 entry.scheduled.broadcaster_login
             );
             items.push(Box::new(
-                MenuItemBuilder::with_id(id, &entry.label).build(app)?,
+                MenuItemBuilder::with_id(id, menu_label(&entry.label)).build(app)?,
             ));
         }
 
@@ -252,13 +537,19 @@ fn render_display_state(app: &AppHandle, state: &DisplayState) -> tauri::Result<
             let more_label = format!("More ({})...", state.schedule_section.overflow.len());
             let mut more_submenu = SubmenuBuilder::new(app, more_label);
 
+            let mut current_day = None;
             for entry in &state.schedule_section.overflow {
+                if let Some(day) = entry.day.as_deref().filter(|&d| current_day != Some(d)) {
+                    let header = MenuItemBuilder::new(day).enabled(false).build(app)?;
+                    more_submenu = more_submenu.item(&header);
+                    current_day = Some(day);
+                }
                 let id = format!(
                     "{}{}",
                     ids::SCHEDULED_PREFIX,
                     entry.scheduled.broadcaster_login
                 );
-                let item = MenuItemBuilder::with_id(id, &entry.label).build(app)?;
+                let item = MenuItemBuilder::with_id(id, menu_label(&entry.label)).build(app)?;
                 more_submenu = more_submenu.item(&item);
             }
 
@@ -266,12 +557,118 @@ fn render_display_state(app: &AppHandle, state: &DisplayState) -> tauri::Result<
         }
     }
 
-    // === Settings, Logout and Quit ===
+    // === Recently Ended ===
+    if !state.recently_ended.is_empty() {
+        let mut ended_submenu = SubmenuBuilder::new(app, "Recently Ended");
+        for entry in &state.recently_ended {
+            let item =
+                MenuItemBuilder::with_id(entry.menu_id(), menu_label(&entry.label)).build(app)?;
+            ended_submenu = ended_submenu.item(&item);
+        }
+        items.push(Box::new(ended_submenu.build()?));
+    }
+
+    // === Past Broadcasts ===
+    if !state.past_broadcasts.is_empty() {
+        let mut past_submenu = SubmenuBuilder::new(app, "Past Broadcasts");
+        for streamer in &state.past_broadcasts {
+            let mut streamer_submenu = SubmenuBuilder::new(app, menu_label(&streamer.display_name));
+            for video in &streamer.videos {
+                let id = format!("{}{}", ids::VIDEO_PREFIX, video.id);
+                let item = MenuItemBuilder::with_id(id, menu_label(&video.label)).build(app)?;
+                streamer_submenu = streamer_submenu.item(&item);
+            }
+            past_submenu = past_submenu.item(&streamer_submenu.build()?);
+        }
+        items.push(Box::new(past_submenu.build()?));
+    }
+
+    // === Updated, Refresh, Dashboard, Settings, Undo, Snooze, What's New, About, custom actions, Switch User, Logout and Quit ===
+    let refresh_status = state
+        .refresh_status
+        .as_ref()
+        .map(|status| {
+            MenuItemBuilder::new(&status.label)
+                .enabled(false)
+                .build(app)
+        })
+        .transpose()?;
+    let refresh = if state.refreshing {
+        MenuItemBuilder::new("Refreshing…")
+            .enabled(false)
+            .build(app)?
+    } else {
+        MenuItemBuilder::with_id(ids::REFRESH, "Refresh now").build(app)?
+    };
+    let dashboard = MenuItemBuilder::with_id(ids::DASHBOARD, "Dashboard").build(app)?;
     let settings = MenuItemBuilder::with_id(ids::SETTINGS, "Settings").build(app)?;
+    let whats_new = MenuItemBuilder::with_id(ids::WHATS_NEW, "What's New").build(app)?;
+    let about = MenuItemBuilder::with_id(ids::ABOUT, "About").build(app)?;
+    let troubleshooting = SubmenuBuilder::new(app, "Troubleshooting")
+        .item(&MenuItemBuilder::with_id(ids::OPEN_LOGS, "Open Logs Folder").build(app)?)
+        .item(
+            &MenuItemBuilder::with_id(ids::DIAGNOSTICS_BUNDLE, "Create Diagnostics Bundle")
+                .build(app)?,
+        )
+        .build()?;
+    let switch_user = MenuItemBuilder::with_id(ids::SWITCH_USER, "Switch User...").build(app)?;
     let logout = MenuItemBuilder::with_id(ids::LOGOUT, "Logout").build(app)?;
     let quit = MenuItemBuilder::with_id(ids::QUIT, "Quit").build(app)?;
 
-    MenuBuilder::new(app)
+    let mut footer: Vec<Box<dyn tauri::menu::IsMenuItem<tauri::Wry>>> = Vec::new();
+    if let Some(item) = &refresh_status {
+        footer.push(Box::new(item.clone()));
+    }
+    footer.push(Box::new(refresh));
+    footer.push(Box::new(dashboard));
+    footer.push(Box::new(settings));
+    if let Some(label) = &state.undo_label {
+        footer.push(Box::new(
+            MenuItemBuilder::with_id(ids::UNDO_SETTINGS, menu_label(label)).build(app)?,
+        ));
+    }
+    match &state.snooze_label {
+        Some(label) => {
+            footer.push(Box::new(
+                MenuItemBuilder::new(label).enabled(false).build(app)?,
+            ));
+            footer.push(Box::new(
+                MenuItemBuilder::with_id(ids::RESUME_NOTIFICATIONS, "Resume notifications")
+                    .build(app)?,
+            ));
+        }
+        None => {
+            let mut durations = SubmenuBuilder::new(app, "Snooze notifications");
+            for duration in SnoozeDuration::ALL {
+                let id = format!("{}{}", ids::SNOOZE_ALL_PREFIX, duration.key());
+                durations =
+                    durations.item(&MenuItemBuilder::with_id(id, duration.label()).build(app)?);
+            }
+            footer.push(Box::new(durations.build()?));
+        }
+    }
+    footer.push(Box::new(whats_new));
+    footer.push(Box::new(about));
+    footer.push(Box::new(troubleshooting));
+    for action in &state.footer_actions {
+        let id = format!("{}{}", ids::CUSTOM_ACTION_PREFIX, action.key);
+        let label = menu_label(&action.label);
+        match &action.confirm_label {
+            // Commands sit behind a submenu so they need a deliberate second click
+            Some(confirm) => {
+                let run = MenuItemBuilder::with_id(id, menu_label(confirm)).build(app)?;
+                footer.push(Box::new(
+                    SubmenuBuilder::new(app, label).item(&run).build()?,
+                ));
+            }
+            None => footer.push(Box::new(MenuItemBuilder::with_id(id, label).build(app)?)),
+        }
+    }
+    footer.push(Box::new(switch_user));
+    footer.push(Box::new(logout));
+    footer.push(Box::new(quit));
+
+    let menu = MenuBuilder::new(app)
         .items(
             &items
                 .iter()
@@ -279,45 +676,233 @@ fn render_display_state(app: &AppHandle, state: &DisplayState) -> tauri::Result<
                 .collect::<Vec<_>>(),
         )
         .separator()
-        .items(&[&settings, &logout, &quit])
-        .build()
+        .items(
+            &footer
+                .iter()
+                .map(std::convert::AsRef::as_ref)
+                .collect::<Vec<_>>(),
+        )
+        .build()?;
+
+    Ok((
+        menu,
+        LiveItems {
+            schedule_header,
+            refresh_status,
+        },
+    ))
+}
+
+/// Builds a live stream entry.
+///
+/// The label is truncated, so the submenu starts with the full title and
+/// stream details. Tray clicks don't report modifier keys on any platform, so
+/// the alternate actions that modifier-clicks reach in the window fallback are
+/// offered as children of the entry too, along with pinning it to the top of
+/// the menu (or unpinning it, for an entry in the Pinned section) and
+/// snoozing its notifications (or resuming them, if in `snoozed_streamers`).
+fn live_stream_item(
+    app: &AppHandle,
+    entry: &StreamEntry,
+    pinned: bool,
+    snoozed_streamers: &HashSet<String>,
+) -> tauri::Result<Box<dyn tauri::menu::IsMenuItem<tauri::Wry>>> {
+    let login = &entry.stream.user_login;
+    let mut submenu = SubmenuBuilder::new(app, menu_label(&entry.label));
+    for detail in format_stream_details(&entry.stream) {
+        let item = MenuItemBuilder::new(menu_label(&detail))
+            .enabled(false)
+            .build(app)?;
+        submenu = submenu.item(&item);
+    }
+    let watch = MenuItemBuilder::with_id(format!("{}{login}", ids::STREAM_PREFIX), "Open stream")
+        .build(app)?;
+    let chat =
+        MenuItemBuilder::with_id(format!("{}{login}", ids::CHAT_PREFIX), "Open chat").build(app)?;
+    let channel = MenuItemBuilder::with_id(
+        format!("{}{login}", ids::CHANNEL_PAGE_PREFIX),
+        "Open channel page",
+    )
+    .build(app)?;
+    let copy = MenuItemBuilder::with_id(format!("{}{login}", ids::COPY_LINK_PREFIX), "Copy link")
+        .build(app)?;
+    let pin = if pinned {
+        MenuItemBuilder::with_id(format!("{}{login}", ids::UNPIN_PREFIX), "Unpin").build(app)?
+    } else {
+        MenuItemBuilder::with_id(format!("{}{login}", ids::PIN_PREFIX), "Pin to top").build(app)?
+    };
+    let snooze: Box<dyn tauri::menu::IsMenuItem<tauri::Wry>> = if snoozed_streamers.contains(login)
+    {
+        Box::new(
+            MenuItemBuilder::with_id(
+                format!("{}{login}", ids::RESUME_STREAMER_PREFIX),
+                "Resume notifications",
+            )
+            .build(app)?,
+        )
+    } else {
+        let mut durations = SubmenuBuilder::new(app, "Snooze notifications");
+        for duration in SnoozeDuration::ALL {
+            let id = format!("{}{}_{login}", ids::SNOOZE_STREAMER_PREFIX, duration.key());
+            durations = durations.item(&MenuItemBuilder::with_id(id, duration.label()).build(app)?);
+        }
+        Box::new(durations.build()?)
+    };
+    let settings = MenuItemBuilder::with_id(
+        format!("{}{login}", ids::STREAMER_SETTINGS_PREFIX),
+        "Streamer settings…",
+    )
+    .build(app)?;
+    Ok(Box::new(
+        submenu
+            .separator()
+            .item(&watch)
+            .item(&chat)
+            .item(&channel)
+            .item(&copy)
+            .item(&pin)
+            .separator()
+            .item(snooze.as_ref())
+            .item(&settings)
+            .build()?,
+    ))
 }
 
 /// Handles menu item clicks
 pub fn handle_menu_event(app: &AppHandle, id: &str) {
-    match id {
-        ids::LOGIN => {
+    handle_menu_click(app, id, ClickModifiers::default());
+}
+
+/// Handles a click on a surface that reports modifier keys.
+pub fn handle_menu_click(app: &AppHandle, id: &str, modifiers: ClickModifiers) {
+    // Picking an item proves the menu was seen, even where opening it isn't reported
+    app.emit(MENU_OPENED_EVENT, ()).ok();
+    let Some(action) = parse_menu_id(id) else {
+        tracing::warn!("Unhandled menu item: {}", id);
+        return;
+    };
+    match action.with_modifiers(modifiers) {
+        MenuAction::Login => {
             app.emit("login-requested", ()).ok();
         }
-        ids::LOGOUT => {
+        MenuAction::CancelLogin => {
+            app.emit("login-cancel-requested", ()).ok();
+        }
+        MenuAction::CopyLoginCode(code) => {
+            if let Err(e) = app.clipboard().write_text(code) {
+                tracing::error!("Failed to copy login code: {}", e);
+            }
+        }
+        MenuAction::Logout => {
             app.emit("logout-requested", ()).ok();
         }
-        ids::SETTINGS => {
+        MenuAction::SwitchUser => {
+            app.emit("switch-user-requested", ()).ok();
+        }
+        MenuAction::OpenSettings => {
             twitch_settings_tauri::window::open_settings_window(app);
         }
-        ids::QUIT => {
+        MenuAction::OpenChangelog => {
+            twitch_settings_tauri::window::open_changelog_window(app);
+        }
+        MenuAction::OpenAbout => {
+            twitch_settings_tauri::window::open_about_window(app);
+        }
+        MenuAction::OpenLogs => {
+            if let Err(e) = twitch_backend::support::open_log_dir() {
+                tracing::error!("Failed to open logs folder: {}", e);
+            }
+        }
+        MenuAction::CreateDiagnosticsBundle => {
+            app.emit("diagnostics-bundle-requested", ()).ok();
+        }
+        MenuAction::OpenDashboard => {
+            crate::dashboard::open_dashboard_window(app);
+        }
+        MenuAction::OpenCategoryStreams(category_id) => {
+            crate::dashboard::open_category_window(app, &category_id);
+        }
+        MenuAction::UndoSettingsChange => {
+            app.emit("undo-settings-requested", ()).ok();
+        }
+        MenuAction::RefreshNow => {
+            app.emit("refresh-requested", ()).ok();
+        }
+        MenuAction::Quit => {
             app.exit(0);
         }
-        _ if id.starts_with(ids::STREAM_PREFIX) => {
-            let user_login = &id[ids::STREAM_PREFIX.len()..];
-            open_stream(user_login);
+        MenuAction::OpenStream(user_login) => {
+            open_stream(app, &user_login);
+        }
+        MenuAction::OpenChat(user_login) => {
+            if let Err(e) = open::that(chat_popout_url(&user_login)) {
+                tracing::error!("Failed to open chat: {}", e);
+            }
+        }
+        MenuAction::OpenChannelPage(user_login) => {
+            let url = format!("https://www.twitch.tv/{user_login}/about");
+            if let Err(e) = open::that(&url) {
+                tracing::error!("Failed to open channel page: {}", e);
+            }
+        }
+        MenuAction::CopyLink(user_login) => {
+            let url = format!("https://twitch.tv/{user_login}");
+            if let Err(e) = app.clipboard().write_text(url) {
+                tracing::error!("Failed to copy link: {}", e);
+            }
+        }
+        MenuAction::OpenStreamerSettings(user_login) => {
+            // Item IDs carry only the login, so it doubles as the window title
+            twitch_settings_tauri::window::open_streamer_settings_window(
+                app,
+                &user_login,
+                &user_login,
+            );
+        }
+        MenuAction::PinStream(user_login) => {
+            app.emit("stream-pin-requested", (user_login, true)).ok();
+        }
+        MenuAction::UnpinStream(user_login) => {
+            app.emit("stream-pin-requested", (user_login, false)).ok();
+        }
+        MenuAction::OpenVideo(id) => {
+            let url = format!("https://www.twitch.tv/videos/{id}");
+            if let Err(e) = open::that(&url) {
+                tracing::error!("Failed to open past broadcast: {}", e);
+            }
+        }
+        MenuAction::RunCustomAction(key) => {
+            app.emit("custom-action-requested", key).ok();
+        }
+        MenuAction::SnoozeNotifications(duration) => {
+            app.emit("snooze-requested", (None::<String>, duration))
+                .ok();
+        }
+        MenuAction::ResumeNotifications => {
+            app.emit("resume-notifications-requested", None::<String>)
+                .ok();
         }
-        _ if id.starts_with(ids::SCHEDULED_PREFIX) => {
-            let user_login = &id[ids::SCHEDULED_PREFIX.len()..];
-            open_stream(user_login);
+        MenuAction::SnoozeStreamer(user_login, duration) => {
+            app.emit("snooze-requested", (Some(user_login), duration))
+                .ok();
         }
-        _ if id.starts_with(ids::CATEGORY_STREAM_PREFIX) => {
-            let user_login = &id[ids::CATEGORY_STREAM_PREFIX.len()..];
-            open_stream(user_login);
+        MenuAction::ResumeStreamer(user_login) => {
+            app.emit("resume-notifications-requested", Some(user_login))
+                .ok();
         }
-        _ => {}
     }
 }
 
-/// Opens a Twitch stream in the default browser
-fn open_stream(user_login: &str) {
-    let url = format!("https://twitch.tv/{user_login}");
-    if let Err(e) = open::that(&url) {
-        tracing::error!("Failed to open browser: {}", e);
+/// Opens a Twitch stream with the configured `open_with` and reports it as watched
+fn open_stream(app: &AppHandle, user_login: &str) {
+    let config = app
+        .try_state::<Arc<dyn AppServices>>()
+        .map(|services| services.get_config())
+        .unwrap_or_default();
+    let target = OpenTarget::for_watching(&config, user_login);
+    if let Err(e) = target.launch() {
+        tracing::error!("Failed to open {}: {}", target, e);
+        return;
     }
+    app.emit("stream-opened", user_login).ok();
 }
@@ -0,0 +1,122 @@
+//! Detects Linux desktops that will never show the tray icon.
+//!
+//! Tray icons on Linux are StatusNotifierItems (via libayatana-appindicator),
+//! shown by whatever owns `org.kde.StatusNotifierWatcher` on the session bus.
+//! Without a watcher, registering the icon still succeeds, so
+//! `TrayBackend::create_tray` can't tell it's invisible. GNOME only has one
+//! with the AppIndicator extension; on X11 other desktops may still show the
+//! icon through the legacy XEmbed tray, so only GNOME and Wayland sessions are
+//! treated as trayless.
+
+/// The D-Bus name a tray host owns.
+const WATCHER_NAME: &str = "org.kde.StatusNotifierWatcher";
+
+/// Whether the menu should go in a window because no tray icon would show.
+///
+/// `watcher_running` is `None` when the session bus couldn't be asked; the
+/// tray is assumed to work then.
+pub fn needs_window_fallback(
+    watcher_running: Option<bool>,
+    session_type: Option<&str>,
+    current_desktop: Option<&str>,
+) -> bool {
+    if watcher_running != Some(false) {
+        return false;
+    }
+    let wayland = session_type.is_some_and(|s| s.eq_ignore_ascii_case("wayland"));
+    let gnome = current_desktop
+        .is_some_and(|desktops| desktops.split(':').any(|d| d.eq_ignore_ascii_case("gnome")));
+    wayland || gnome
+}
+
+/// Checks this session for a tray host. Always `false` off Linux, where the
+/// OS provides the tray.
+pub fn tray_host_missing() -> bool {
+    if !cfg!(target_os = "linux") {
+        return false;
+    }
+    let session_type = std::env::var("XDG_SESSION_TYPE").ok();
+    let current_desktop = std::env::var("XDG_CURRENT_DESKTOP").ok();
+    needs_window_fallback(
+        watcher_running(),
+        session_type.as_deref(),
+        current_desktop.as_deref(),
+    )
+}
+
+/// Asks the session bus whether a tray host is running, using `gdbus` (which
+/// ships with GLib, so it's there wherever GTK is).
+fn watcher_running() -> Option<bool> {
+    let output = std::process::Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.DBus",
+            "--object-path",
+            "/org/freedesktop/DBus",
+            "--method",
+            "org.freedesktop.DBus.NameHasOwner",
+            WATCHER_NAME,
+        ])
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            // Prints "(true,)" or "(false,)"
+            Some(String::from_utf8_lossy(&output.stdout).contains("true"))
+        }
+        Ok(output) => {
+            tracing::debug!(
+                "Couldn't check for a tray host: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            None
+        }
+        Err(e) => {
+            tracing::debug!("Couldn't check for a tray host: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gnome_without_a_watcher_falls_back() {
+        assert!(needs_window_fallback(
+            Some(false),
+            Some("x11"),
+            Some("ubuntu:GNOME")
+        ));
+        assert!(!needs_window_fallback(
+            Some(true),
+            Some("wayland"),
+            Some("GNOME")
+        ));
+    }
+
+    #[test]
+    fn wayland_without_a_watcher_falls_back() {
+        assert!(needs_window_fallback(
+            Some(false),
+            Some("wayland"),
+            Some("sway")
+        ));
+    }
+
+    #[test]
+    fn x11_desktops_may_have_an_xembed_tray() {
+        assert!(!needs_window_fallback(
+            Some(false),
+            Some("x11"),
+            Some("XFCE")
+        ));
+    }
+
+    #[test]
+    fn unknown_watcher_keeps_the_tray() {
+        assert!(!needs_window_fallback(None, Some("wayland"), Some("GNOME")));
+    }
+}
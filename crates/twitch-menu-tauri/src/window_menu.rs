@@ -0,0 +1,112 @@
+//! Window fallback for desktops without a system tray.
+//!
+//! Some environments (certain Wayland compositors, WSLg) have no tray host,
+//! so `TrayBackend::create_tray` fails and the app would run invisibly.
+//! `WindowMenuBackend` shows the same menu as a clickable list in a small
+//! persistent window instead. Clicks go through `tray::handle_menu_click`.
+//! GNOME without the AppIndicator extension accepts the tray icon but never
+//! shows it; `tray_host` catches that case before the tray is created.
+
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindowBuilder, WindowEvent};
+
+use crate::display::DisplayBackend;
+use crate::display_state::DisplayState;
+use crate::menu_actions::ClickModifiers;
+use crate::menu_rows::{compute_menu_rows, MenuRow};
+use crate::tray::{handle_menu_click, MENU_OPENED_EVENT, MENU_OPENING_EVENT};
+
+/// Window label, also used as the event target for row updates.
+const WINDOW_LABEL: &str = "menu";
+const WINDOW_WIDTH: f64 = 380.0;
+const WINDOW_HEIGHT: f64 = 640.0;
+/// Event carrying the new `Vec<MenuRow>` whenever the menu changes.
+const ROWS_UPDATED_EVENT: &str = "menu-rows-updated";
+
+/// Display adapter that renders the menu into the fallback window.
+#[derive(Clone)]
+pub struct WindowMenuBackend {
+    app_handle: AppHandle,
+    /// Last rendered rows, served to the page when it (re)loads.
+    rows: Arc<Mutex<Vec<MenuRow>>>,
+}
+
+impl WindowMenuBackend {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            app_handle,
+            rows: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Opens the menu window, or focuses it if it is already open.
+    ///
+    /// It stays on top so it's as reachable as a tray would be. Closing the
+    /// window quits the app: with no tray there would be no way to bring it back.
+    pub fn open(&self) -> tauri::Result<()> {
+        if let Some(window) = self.app_handle.get_webview_window(WINDOW_LABEL) {
+            return window.set_focus();
+        }
+
+        let window = WebviewWindowBuilder::new(
+            &self.app_handle,
+            WINDOW_LABEL,
+            tauri::WebviewUrl::App("menu.html".into()),
+        )
+        .title("Twitch Tray")
+        .inner_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+        .resizable(true)
+        .always_on_top(true)
+        .build()?;
+
+        let app_handle = self.app_handle.clone();
+        window.on_window_event(move |event| match event {
+            WindowEvent::Destroyed => app_handle.exit(0),
+            // Coming back to the window is this surface's "menu opened"
+            WindowEvent::Focused(true) => {
+                app_handle.emit(MENU_OPENING_EVENT, ()).ok();
+                app_handle.emit(MENU_OPENED_EVENT, ()).ok();
+            }
+            _ => {}
+        });
+
+        tracing::info!("Menu window opened");
+        Ok(())
+    }
+
+    fn current_rows(&self) -> Vec<MenuRow> {
+        self.rows
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl DisplayBackend for WindowMenuBackend {
+    fn update(&self, state: DisplayState) -> anyhow::Result<()> {
+        let rows = compute_menu_rows(&state);
+        *self
+            .rows
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = rows.clone();
+        self.app_handle
+            .emit_to(WINDOW_LABEL, ROWS_UPDATED_EVENT, rows)
+            .map_err(anyhow::Error::from)
+    }
+}
+
+/// Returns the current menu rows (called by `menu.js` on load).
+#[tauri::command]
+pub fn get_menu_rows(backend: State<'_, Arc<WindowMenuBackend>>) -> Vec<MenuRow> {
+    backend.current_rows()
+}
+
+/// Handles a click on a menu row, exactly as if the tray item had been chosen.
+///
+/// Unlike the tray, the page reports held modifier keys, so Ctrl/Shift-clicks
+/// on streams reach their alternate actions directly.
+#[tauri::command]
+pub fn activate_menu_row(app: AppHandle, id: String, modifiers: Option<ClickModifiers>) {
+    handle_menu_click(&app, &id, modifiers.unwrap_or_default());
+}
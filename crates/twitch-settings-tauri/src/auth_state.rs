@@ -0,0 +1,48 @@
+//! Pushes authentication status changes to the settings window.
+//!
+//! The page asks for the current status once with `commands::get_auth_state`
+//! and then follows `auth-state-changed` events for its banner.
+
+use std::sync::Arc;
+
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+
+use twitch_backend::app_services::AppServices;
+use twitch_backend::{BackendEvent, LoginProgress};
+
+/// Event carrying the new `AuthStatus`.
+pub const AUTH_STATE_EVENT: &str = "auth-state-changed";
+
+/// Emits the current `AuthStatus` whenever a login progresses or the
+/// session is established or dropped.
+pub fn spawn_auth_state_emitter(
+    app: AppHandle,
+    services: Arc<dyn AppServices>,
+    mut login_progress_rx: watch::Receiver<Option<LoginProgress>>,
+    mut event_rx: broadcast::Receiver<BackendEvent>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                result = login_progress_rx.changed() => {
+                    if result.is_err() {
+                        break;
+                    }
+                }
+                event = event_rx.recv() => match event {
+                    // A lagged receiver may have missed an auth change, so re-send
+                    Ok(BackendEvent::AuthStateChanged { .. }) | Err(RecvError::Lagged(_)) => {}
+                    Ok(_) => continue,
+                    Err(RecvError::Closed) => break,
+                },
+            }
+            let status = services.get_auth_state().await;
+            if let Err(e) = app.emit(AUTH_STATE_EVENT, status) {
+                tracing::warn!("Failed to emit auth state: {}", e);
+            }
+        }
+    })
+}
@@ -1,10 +1,25 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use serde::Serialize;
 use tauri::State;
 
-use twitch_backend::app_services::{AppServices, DebugHotnessEntry, DebugStreamEntry};
-use twitch_backend::config::{Config, FollowedCategory};
-use twitch_backend::twitch::{Category, FollowedChannel};
+use twitch_backend::about::{about_info, AboutInfo};
+use twitch_backend::app_services::{AppServices, DebugHotnessEntry, DebugStreamEntry, LiveAtEntry};
+use twitch_backend::category_suggestions::CategorySuggestion;
+use twitch_backend::changelog::{parse_changelog, Release, CHANGELOG};
+use twitch_backend::config::{
+    CategoryNotifications, Config, FollowedCategory, StreamerImportance, ValidationError,
+    ValidationErrors,
+};
+use twitch_backend::data_export::ExportManifest;
+use twitch_backend::followed_list::{FollowedChannelsPage, FollowedQuery};
+use twitch_backend::proxy::ConnectionTest;
+use twitch_backend::stream_stats::StreamerStats;
+use twitch_backend::streamer_list::{StreamerListFormat, StreamerListImport};
+use twitch_backend::support::{redact_text, AppDiagnostics};
+use twitch_backend::twitch::Category;
+use twitch_backend::AuthStatus;
 
 /// Gets the current configuration.
 #[tauri::command]
@@ -13,13 +28,37 @@ pub fn get_config(app: State<'_, Arc<dyn AppServices>>) -> Config {
     app.get_config()
 }
 
+/// Why `save_config` failed, as the settings window receives it.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SaveConfigError {
+    /// Settings out of range; nothing was saved.
+    Invalid {
+        errors: Vec<ValidationError>,
+    },
+    Failed {
+        message: String,
+    },
+}
+
+impl From<anyhow::Error> for SaveConfigError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<ValidationErrors>() {
+            Ok(invalid) => Self::Invalid { errors: invalid.0 },
+            Err(e) => Self::Failed {
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
 /// Saves the configuration and triggers an immediate data refresh.
 #[tauri::command]
 pub async fn save_config(
     app: State<'_, Arc<dyn AppServices>>,
     config: Config,
-) -> Result<(), String> {
-    app.save_config(config).await.map_err(|e| e.to_string())
+) -> Result<(), SaveConfigError> {
+    app.save_config(config).await.map_err(SaveConfigError::from)
 }
 
 /// Searches for categories by name.
@@ -40,12 +79,119 @@ pub fn get_followed_categories(app: State<'_, Arc<dyn AppServices>>) -> Vec<Foll
     app.get_followed_categories()
 }
 
-/// Gets the list of followed channels from state.
+/// Suggests categories to follow based on the last month of opened streams.
+#[tauri::command]
+pub async fn get_followed_category_suggestions(
+    app: State<'_, Arc<dyn AppServices>>,
+) -> Result<Vec<CategorySuggestion>, String> {
+    Ok(app.get_followed_category_suggestions().await)
+}
+
+/// Follows a suggested category.
+#[tauri::command]
+pub async fn accept_category_suggestion(
+    app: State<'_, Arc<dyn AppServices>>,
+    category: FollowedCategory,
+) -> Result<(), String> {
+    app.accept_category_suggestion(category)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Hides a category from future suggestions.
+#[tauri::command]
+pub async fn dismiss_category_suggestion(
+    app: State<'_, Arc<dyn AppServices>>,
+    category_id: String,
+) -> Result<(), String> {
+    app.dismiss_category_suggestion(&category_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Sets a followed category's notification sound, urgency and on/off override.
+#[tauri::command]
+pub async fn set_category_notifications(
+    app: State<'_, Arc<dyn AppServices>>,
+    category_id: String,
+    notifications: CategoryNotifications,
+) -> Result<(), String> {
+    app.set_category_notifications(&category_id, notifications)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Gives many streamers the same importance in one save, e.g. a list pasted
+/// from another tool. Returns how many streamers changed.
+#[tauri::command]
+pub async fn set_importance_bulk(
+    app: State<'_, Arc<dyn AppServices>>,
+    logins: Vec<String>,
+    importance: StreamerImportance,
+) -> Result<usize, String> {
+    app.set_importance_bulk(logins, importance)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Gives the channels in a CSV or JSON list `importance`, reporting the names
+/// Twitch doesn't know.
+#[tauri::command]
+pub async fn import_streamer_list(
+    app: State<'_, Arc<dyn AppServices>>,
+    path: String,
+    format: StreamerListFormat,
+    importance: StreamerImportance,
+) -> Result<StreamerListImport, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("Choose a file to import".to_string());
+    }
+    app.import_streamer_list(PathBuf::from(path), format, importance)
+        .await
+        .map_err(|e| format!("{e:#}"))
+}
+
+/// Reverts the most recent streamer settings change. Returns the streamer's
+/// login, or `None` when there is nothing to undo.
+#[tauri::command]
+pub async fn undo_settings_change(
+    app: State<'_, Arc<dyn AppServices>>,
+) -> Result<Option<String>, String> {
+    app.undo_settings_change().await.map_err(|e| e.to_string())
+}
+
+/// Returns whether the app is registered to start on login.
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)] // Tauri commands require State by value
+pub fn get_autostart(app: State<'_, Arc<dyn AppServices>>) -> bool {
+    app.get_autostart()
+}
+
+/// Registers or unregisters the app to start on login.
+#[tauri::command]
+pub async fn set_autostart(
+    app: State<'_, Arc<dyn AppServices>>,
+    enabled: bool,
+) -> Result<(), String> {
+    app.set_autostart(enabled).await.map_err(|e| e.to_string())
+}
+
+/// Returns whether the backend is logged in, waiting on a device code, or
+/// failed to log in. Changes are pushed as `auth-state-changed` events.
+#[tauri::command]
+pub async fn get_auth_state(app: State<'_, Arc<dyn AppServices>>) -> Result<AuthStatus, String> {
+    Ok(app.get_auth_state().await)
+}
+
+/// Lists followed channels matching `query`, with live status, profile
+/// image and importance. A missing query lists them all by name.
 #[tauri::command]
 pub async fn get_followed_channels_list(
     app: State<'_, Arc<dyn AppServices>>,
-) -> Result<Vec<FollowedChannel>, String> {
-    Ok(app.get_followed_channels().await)
+    query: Option<FollowedQuery>,
+) -> Result<FollowedChannelsPage, String> {
+    Ok(app.list_followed_channels(&query.unwrap_or_default()).await)
 }
 
 /// Returns true when the binary was compiled with debug assertions enabled.
@@ -56,6 +202,18 @@ pub fn is_debug_build() -> bool {
     cfg!(debug_assertions)
 }
 
+/// Returns the changelog bundled with this build, newest release first.
+#[tauri::command]
+pub fn get_changelog() -> Vec<Release> {
+    parse_changelog(CHANGELOG)
+}
+
+/// Returns version, build and license details for the About window.
+#[tauri::command]
+pub fn get_about_info() -> AboutInfo {
+    about_info()
+}
+
 /// Returns hotness debug data for all currently live streams.
 #[tauri::command]
 pub async fn get_debug_hotness_data(
@@ -74,11 +232,113 @@ pub async fn get_debug_schedule_data(
     Ok(app.get_debug_schedule_data(start, end).await)
 }
 
+/// Returns followed broadcasts that were live at the given Unix timestamp.
+#[tauri::command]
+pub async fn get_live_at(
+    app: State<'_, Arc<dyn AppServices>>,
+    timestamp: i64,
+) -> Result<Vec<LiveAtEntry>, String> {
+    Ok(app.get_live_at(timestamp).await)
+}
+
+/// Returns every followed channel's recorded streaming habits, busiest first.
+#[tauri::command]
+pub async fn get_history_summary(
+    app: State<'_, Arc<dyn AppServices>>,
+) -> Result<Vec<StreamerStats>, String> {
+    Ok(app.get_history_summary().await)
+}
+
+/// Returns one followed channel's recorded streaming habits, or `null` if it
+/// was never seen live.
+#[tauri::command]
+pub async fn get_streamer_history(
+    app: State<'_, Arc<dyn AppServices>>,
+    user_login: String,
+) -> Result<Option<StreamerStats>, String> {
+    Ok(app.get_streamer_history(&user_login).await)
+}
+
+/// Exports settings (and stream history if `include_history`) to a zip and
+/// returns its path. Without a `path` it goes in the downloads folder.
+#[tauri::command]
+pub async fn export_data(
+    app: State<'_, Arc<dyn AppServices>>,
+    path: Option<String>,
+    include_history: bool,
+) -> Result<String, String> {
+    app.export_data(path.map(PathBuf::from), include_history)
+        .await
+        .map(|path| path.display().to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Replaces settings, and history if the export has it, with an export's.
+#[tauri::command]
+pub async fn import_data(
+    app: State<'_, Arc<dyn AppServices>>,
+    path: String,
+) -> Result<ExportManifest, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("Choose an export file to import".to_string());
+    }
+    app.import_data(PathBuf::from(path))
+        .await
+        .map_err(|e| format!("{e:#}"))
+}
+
+/// Creates a redacted diagnostics zip for bug reports and returns its path.
+#[tauri::command]
+pub async fn create_diagnostics_bundle(
+    app: State<'_, Arc<dyn AppServices>>,
+) -> Result<String, String> {
+    app.create_diagnostics_bundle()
+        .await
+        .map(|path| path.display().to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Returns auth, refresh, database and platform health for the Diagnostics
+/// tab.
+#[tauri::command]
+pub async fn get_diagnostics(
+    app: State<'_, Arc<dyn AppServices>>,
+) -> Result<AppDiagnostics, String> {
+    Ok(app.get_diagnostics().await)
+}
+
+/// Opens the folder the log files are written to.
+#[tauri::command]
+pub fn open_log_dir() -> Result<(), String> {
+    twitch_backend::support::open_log_dir().map_err(|e| format!("{e:#}"))
+}
+
+/// The proxy set by `HTTPS_PROXY`/`ALL_PROXY`/`HTTP_PROXY`, password hidden,
+/// used when `proxy_url` is empty.
+#[tauri::command]
+pub fn get_env_proxy() -> Option<String> {
+    twitch_backend::proxy::env_proxy_url().map(|url| redact_text(&url))
+}
+
+/// Checks Twitch can be reached through `proxy_url` as typed in the settings
+/// field (not yet saved), or the environment's proxy when it's empty.
+#[tauri::command]
+pub async fn test_proxy_connection(proxy_url: Option<String>) -> Result<ConnectionTest, String> {
+    let proxy_url = proxy_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty());
+    twitch_backend::proxy::test_connection(proxy_url)
+        .await
+        .map_err(|e| format!("{e:#}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::mock::MockAppServices;
-    use twitch_backend::config::DEFAULT_POLL_INTERVAL_SEC;
+    use twitch_backend::config::{StreamerImportance, StreamerSettings, DEFAULT_POLL_INTERVAL_SEC};
     use twitch_backend::twitch::Category;
 
     // =========================================================
@@ -121,6 +381,38 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn save_config_rejects_out_of_range_values() {
+        let services = MockAppServices::new();
+        let config = Config {
+            poll_interval_sec: 5,
+            ..Config::default()
+        };
+
+        let error = SaveConfigError::from(services.save_config(config).await.unwrap_err());
+
+        let SaveConfigError::Invalid { errors } = error else {
+            panic!("expected validation errors, got {error:?}");
+        };
+        assert_eq!(errors[0].field, "poll_interval_sec");
+        assert_eq!(
+            services.get_config().poll_interval_sec,
+            Config::default().poll_interval_sec
+        );
+        assert_eq!(services.refresh_category_count(), 0);
+    }
+
+    #[test]
+    fn other_save_failures_keep_their_message() {
+        let error = SaveConfigError::from(anyhow::anyhow!("disk full"));
+        assert_eq!(
+            error,
+            SaveConfigError::Failed {
+                message: "disk full".to_string()
+            }
+        );
+    }
+
     #[tokio::test]
     async fn save_config_increments_call_counter() {
         let services = MockAppServices::new();
@@ -167,22 +459,298 @@ mod tests {
     }
 
     // =========================================================
-    // get_followed_channels
+    // set_category_notifications
+    // =========================================================
+
+    #[tokio::test]
+    async fn category_notifications_saved_without_refresh() {
+        let services = MockAppServices::new();
+        let mut config = services.get_config();
+        config.followed_categories.push(FollowedCategory {
+            id: "1".to_string(),
+            name: "Speedrunning".to_string(),
+            ..Default::default()
+        });
+        services.save_config(config).await.unwrap();
+        let refreshes = services.refresh_category_count();
+
+        let notifications = CategoryNotifications {
+            enabled: Some(true),
+            sound: Some("bell".to_string()),
+            urgency: None,
+            small_stream_max_viewers: Some(200),
+            ..Default::default()
+        };
+        services
+            .set_category_notifications("1", notifications.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            services.get_followed_categories()[0].notifications,
+            notifications
+        );
+        assert_eq!(services.refresh_category_count(), refreshes);
+    }
+
+    #[tokio::test]
+    async fn category_notifications_for_unfollowed_category_fail() {
+        let services = MockAppServices::new();
+        let result = services
+            .set_category_notifications("missing", CategoryNotifications::default())
+            .await;
+        assert!(result.is_err());
+    }
+
+    // =========================================================
+    // set_autostart
+    // =========================================================
+
+    #[tokio::test]
+    async fn autostart_toggle_is_saved_without_refresh() {
+        let services = MockAppServices::new();
+        assert!(!services.get_autostart());
+
+        services.set_autostart(true).await.unwrap();
+
+        assert!(services.get_autostart());
+        assert!(services.get_config().start_on_login);
+        assert_eq!(services.save_config_count(), 0);
+        assert_eq!(services.refresh_category_count(), 0);
+    }
+
+    // =========================================================
+    // get_auth_state
+    // =========================================================
+
+    #[tokio::test]
+    async fn auth_state_reflects_backend_status() {
+        let services = MockAppServices::new();
+        assert_eq!(services.get_auth_state().await, AuthStatus::Unauthenticated);
+
+        let status = AuthStatus::Authenticated {
+            login: "alice".to_string(),
+            expires: None,
+            renews: None,
+            renewal_failing: false,
+        };
+        services.set_auth_status(status.clone());
+        assert_eq!(services.get_auth_state().await, status);
+    }
+
+    // =========================================================
+    // undo_settings_change
+    // =========================================================
+
+    fn with_importance(config: &mut Config, login: &str, importance: StreamerImportance) {
+        config.streamer_settings.insert(
+            login.to_string(),
+            StreamerSettings {
+                display_name: login.to_string(),
+                importance,
+                hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: None,
+                open_with: None,
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
+            },
+        );
+    }
+
+    #[tokio::test]
+    async fn undo_restores_previous_importance() {
+        let services = MockAppServices::new();
+        let mut config = services.get_config();
+        with_importance(&mut config, "ninja", StreamerImportance::Favourite);
+        services.save_config(config.clone()).await.unwrap();
+        with_importance(&mut config, "ninja", StreamerImportance::Ignore);
+        services.save_config(config).await.unwrap();
+
+        let undone = services.undo_settings_change().await.unwrap();
+
+        assert_eq!(undone.as_deref(), Some("ninja"));
+        assert_eq!(
+            services.get_config().streamer_settings["ninja"].importance,
+            StreamerImportance::Favourite
+        );
+    }
+
+    #[tokio::test]
+    async fn undo_of_new_entry_removes_it() {
+        let services = MockAppServices::new();
+        let mut config = services.get_config();
+        with_importance(&mut config, "ninja", StreamerImportance::Ignore);
+        services.save_config(config).await.unwrap();
+
+        services.undo_settings_change().await.unwrap();
+
+        assert!(services.get_config().streamer_settings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn undo_with_nothing_to_undo_returns_none() {
+        let services = MockAppServices::new();
+        assert_eq!(services.undo_settings_change().await.unwrap(), None);
+    }
+
+    // =========================================================
+    // category suggestions
     // =========================================================
 
+    fn suggestion(id: &str, watched_streams: u32) -> CategorySuggestion {
+        CategorySuggestion {
+            id: id.to_string(),
+            name: format!("Game {id}"),
+            watched_streams,
+        }
+    }
+
     #[tokio::test]
-    async fn get_followed_channels_returns_configured_channels() {
+    async fn accepted_suggestion_is_followed_and_no_longer_suggested() {
         let services = MockAppServices::new();
-        let channel = FollowedChannel {
-            broadcaster_id: "1".to_string(),
-            broadcaster_login: "streamer".to_string(),
-            broadcaster_name: "Streamer".to_string(),
+        services.set_watched_categories(vec![suggestion("1", 5)]);
+        assert_eq!(services.get_followed_category_suggestions().await.len(), 1);
+
+        services
+            .accept_category_suggestion(FollowedCategory {
+                id: "1".to_string(),
+                name: "Game 1".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(services.get_followed_categories()[0].id, "1");
+        assert_eq!(services.refresh_category_count(), 1);
+        assert!(services
+            .get_followed_category_suggestions()
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn dismissed_suggestion_is_not_suggested_again() {
+        let services = MockAppServices::new();
+        services.set_watched_categories(vec![suggestion("1", 5), suggestion("2", 4)]);
+
+        services.dismiss_category_suggestion("1").await.unwrap();
+
+        let remaining = services.get_followed_category_suggestions().await;
+        assert_eq!(remaining, vec![suggestion("2", 4)]);
+        assert!(services.get_followed_categories().is_empty());
+    }
+
+    // =========================================================
+    // changelog
+    // =========================================================
+
+    #[test]
+    fn get_changelog_lists_a_release() {
+        assert!(!get_changelog().is_empty());
+    }
+
+    // =========================================================
+    // get_about_info
+    // =========================================================
+
+    #[test]
+    fn about_info_reports_version_and_acknowledgements() {
+        let info = get_about_info();
+        assert_eq!(info.version, "0.1.0");
+        assert!(!info.acknowledgements.is_empty());
+    }
+
+    #[tokio::test]
+    async fn whats_new_shown_once_after_version_change() {
+        let services = MockAppServices::new();
+        // Fresh install: record the version, nothing to announce
+        assert!(!services.note_app_version("0.1.0").await.unwrap());
+        assert!(!services.note_app_version("0.1.0").await.unwrap());
+
+        assert!(services.note_app_version("0.2.0").await.unwrap());
+        assert!(!services.note_app_version("0.2.0").await.unwrap());
+        assert_eq!(
+            services.get_config().last_seen_version.as_deref(),
+            Some("0.2.0")
+        );
+    }
+
+    // =========================================================
+    // set_importance_bulk
+    // =========================================================
+
+    #[tokio::test]
+    async fn set_importance_bulk_can_be_undone_per_streamer() {
+        let services = MockAppServices::new();
+        let logins = vec![
+            "Ninja".to_string(),
+            "@shroud".to_string(),
+            "ninja".to_string(),
+        ];
+
+        let changed = services
+            .set_importance_bulk(logins, StreamerImportance::Ignore)
+            .await
+            .unwrap();
+
+        assert_eq!(changed, 2);
+        let settings = services.get_config().streamer_settings;
+        assert_eq!(settings["ninja"].importance, StreamerImportance::Ignore);
+        assert_eq!(settings["shroud"].importance, StreamerImportance::Ignore);
+        assert_eq!(
+            services.undo_settings_change().await.unwrap().as_deref(),
+            Some("shroud")
+        );
+    }
+
+    #[tokio::test]
+    async fn import_streamer_list_reports_names_that_cannot_be_channels() {
+        let services = MockAppServices::new();
+        let path = std::env::temp_dir().join("twitch-tray-streamer-list-test.csv");
+        std::fs::write(&path, "login\nninja\nnot a channel\n").unwrap();
+
+        let imported = services
+            .import_streamer_list(
+                path.clone(),
+                StreamerListFormat::Csv,
+                StreamerImportance::Favourite,
+            )
+            .await
+            .unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(imported.changed, 1);
+        assert_eq!(imported.unresolved, vec!["not a channel"]);
+        assert_eq!(
+            services.get_config().streamer_settings["ninja"].importance,
+            StreamerImportance::Favourite
+        );
+    }
+
+    // =========================================================
+    // list_followed_channels
+    // =========================================================
+
+    #[tokio::test]
+    async fn list_followed_channels_searches_configured_channels() {
+        let services = MockAppServices::new();
+        let channel = |id: &str, name: &str| twitch_backend::twitch::FollowedChannel {
+            broadcaster_id: id.to_string(),
+            broadcaster_login: name.to_lowercase(),
+            broadcaster_name: name.to_string(),
             followed_at: chrono::Utc::now(),
         };
-        services.set_channels(vec![channel]);
-        let channels = services.get_followed_channels().await;
-        assert_eq!(channels.len(), 1);
-        assert_eq!(channels[0].broadcaster_login, "streamer");
+        services.set_channels(vec![channel("1", "Streamer"), channel("2", "Other")]);
+
+        let query = FollowedQuery {
+            search: "stream".to_string(),
+            ..Default::default()
+        };
+        let page = services.list_followed_channels(&query).await;
+        assert_eq!(page.total, 1);
+        assert_eq!(page.channels[0].broadcaster_login, "streamer");
+        assert!(!page.channels[0].configured);
     }
 
     // =========================================================
@@ -196,6 +764,7 @@ mod tests {
         let services = MockAppServices::new();
         let entry = DebugStreamEntry {
             is_inferred: false,
+            confidence: None,
             broadcaster_name: "TestStreamer".to_string(),
             broadcaster_login: "teststreamer".to_string(),
             started_at: 1_000_000,
@@ -250,4 +819,101 @@ mod tests {
         services.get_debug_hotness_data().await;
         assert_eq!(services.hotness_call_count(), 2);
     }
+
+    // =========================================================
+    // get_live_at
+    // =========================================================
+
+    #[tokio::test]
+    async fn live_at_delegates_to_services() {
+        let services = MockAppServices::new();
+        services.set_live_at_entries(vec![LiveAtEntry {
+            broadcaster_name: "NightOwl".to_string(),
+            broadcaster_login: "nightowl".to_string(),
+            started_at: 1_000_000,
+            ended_at: None,
+        }]);
+        let result = services.get_live_at(1_003_600).await;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].broadcaster_login, "nightowl");
+        assert_eq!(result[0].ended_at, None);
+    }
+
+    // =========================================================
+    // get_history_summary / get_streamer_history
+    // =========================================================
+
+    #[tokio::test]
+    async fn streamer_history_found_by_login() {
+        let services = MockAppServices::new();
+        services.set_history_stats(vec![StreamerStats {
+            broadcaster_name: "NightOwl".to_string(),
+            broadcaster_login: "nightowl".to_string(),
+            stream_count: 3,
+            first_started_at: 1_000_000,
+            last_started_at: 2_000_000,
+            streams_per_week: 1.5,
+            typical_start_hours: vec![22],
+            typical_days: vec![4],
+            recent_starts: vec![2_000_000],
+            average_duration_mins: Some(150),
+        }]);
+        assert_eq!(services.get_history_summary().await.len(), 1);
+        let owl = services.get_streamer_history("nightowl").await.unwrap();
+        assert_eq!(owl.stream_count, 3);
+        assert!(services.get_streamer_history("nobody").await.is_none());
+    }
+
+    // =========================================================
+    // create_diagnostics_bundle
+    // =========================================================
+
+    #[tokio::test]
+    async fn diagnostics_bundle_delegates_to_services() {
+        let services = MockAppServices::new();
+        let path = services.create_diagnostics_bundle().await.unwrap();
+        assert!(path.to_string_lossy().ends_with(".zip"));
+        assert_eq!(services.diagnostics_call_count(), 1);
+    }
+
+    // =========================================================
+    // test_proxy_connection
+    // =========================================================
+
+    #[tokio::test]
+    async fn proxy_test_rejects_unusable_url_without_connecting() {
+        let err = test_proxy_connection(Some("ftp://proxy.corp".to_string()))
+            .await
+            .unwrap_err();
+        assert!(err.contains("Proxy URL must start with"), "{err}");
+    }
+
+    // =========================================================
+    // get_diagnostics
+    // =========================================================
+
+    #[tokio::test]
+    async fn diagnostics_delegates_to_services() {
+        let services = MockAppServices::new();
+        let diagnostics = services.get_diagnostics().await;
+        assert!(diagnostics.authenticated);
+        assert!(diagnostics.db_path.ends_with("data.db"));
+    }
+
+    // =========================================================
+    // export_data / import_data
+    // =========================================================
+
+    #[tokio::test]
+    async fn export_defaults_path_and_import_needs_a_file() {
+        let services = MockAppServices::new();
+        let path = services.export_data(None, true).await.unwrap();
+        assert!(path.to_string_lossy().ends_with(".zip"));
+
+        let err = services
+            .import_data(PathBuf::from("/nonexistent/export.zip"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No such file"));
+    }
 }
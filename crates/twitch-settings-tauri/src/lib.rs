@@ -1,6 +1,7 @@
 // twitch-settings-tauri: Tauri settings command handlers.
 // Depends on twitch-backend for domain types and AppServices trait.
 
+pub mod auth_state;
 pub mod commands;
 pub mod window;
 
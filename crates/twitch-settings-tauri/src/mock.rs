@@ -1,11 +1,32 @@
 /// MockAppServices for commands unit tests.
 /// Lives here because #[cfg(test)] code cannot cross crate boundaries.
 use async_trait::async_trait;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Mutex;
-use twitch_backend::app_services::{AppServices, DebugHotnessEntry, DebugStreamEntry};
-use twitch_backend::config::{Config, FollowedCategory};
+use twitch_backend::app_services::{
+    AppServices, CategoryStreamsPage, DebugHotnessEntry, DebugStreamEntry, LiveAtEntry,
+};
+use twitch_backend::bulk_importance::{normalize_logins, set_importance};
+use twitch_backend::category_suggestions::{
+    accept_suggestion, dismiss_suggestion, suggest_categories, CategorySuggestion,
+};
+use twitch_backend::changelog::{mark_version_seen, SeenVersion};
+use twitch_backend::config::{
+    CategoryNotifications, Config, FollowedCategory, PinnedStream, StreamerImportance,
+};
+use twitch_backend::data_export::ExportManifest;
+use twitch_backend::followed_list::{list_followed, FollowedChannelsPage, FollowedQuery};
+use twitch_backend::notification_snooze::SnoozeDuration;
+use twitch_backend::pinned_streams::{pin_stream, unpin_stream};
+use twitch_backend::settings_undo::UndoBuffer;
+use twitch_backend::stream_stats::StreamerStats;
+use twitch_backend::streamer_list::{
+    is_valid_login, parse_streamer_list, StreamerListFormat, StreamerListImport,
+};
+use twitch_backend::support::AppDiagnostics;
 use twitch_backend::twitch::{ApiError, Category, FollowedChannel};
+use twitch_backend::AuthStatus;
 
 pub struct MockAppServices {
     config: Mutex<Config>,
@@ -13,11 +34,17 @@ pub struct MockAppServices {
     channels: Mutex<Vec<FollowedChannel>>,
     debug_entries: Mutex<Vec<DebugStreamEntry>>,
     hotness_entries: Mutex<Vec<DebugHotnessEntry>>,
+    live_at_entries: Mutex<Vec<LiveAtEntry>>,
+    history_stats: Mutex<Vec<StreamerStats>>,
+    watched_categories: Mutex<Vec<CategorySuggestion>>,
     save_config_count: AtomicUsize,
     refresh_category_count: AtomicUsize,
     refresh_schedules_count: AtomicUsize,
     debug_call_count: AtomicUsize,
     hotness_call_count: AtomicUsize,
+    diagnostics_call_count: AtomicUsize,
+    settings_undo: Mutex<UndoBuffer>,
+    auth_status: Mutex<AuthStatus>,
 }
 
 impl MockAppServices {
@@ -28,14 +55,28 @@ impl MockAppServices {
             channels: Mutex::new(Vec::new()),
             debug_entries: Mutex::new(Vec::new()),
             hotness_entries: Mutex::new(Vec::new()),
+            live_at_entries: Mutex::new(Vec::new()),
+            history_stats: Mutex::new(Vec::new()),
+            watched_categories: Mutex::new(Vec::new()),
             save_config_count: AtomicUsize::new(0),
             refresh_category_count: AtomicUsize::new(0),
             refresh_schedules_count: AtomicUsize::new(0),
             debug_call_count: AtomicUsize::new(0),
             hotness_call_count: AtomicUsize::new(0),
+            diagnostics_call_count: AtomicUsize::new(0),
+            settings_undo: Mutex::new(UndoBuffer::default()),
+            auth_status: Mutex::new(AuthStatus::Unauthenticated),
         }
     }
 
+    pub fn set_history_stats(&self, stats: Vec<StreamerStats>) {
+        *self.history_stats.lock().unwrap() = stats;
+    }
+
+    pub fn set_auth_status(&self, status: AuthStatus) {
+        *self.auth_status.lock().unwrap() = status;
+    }
+
     pub fn set_search_results(&self, results: Vec<Category>) {
         *self.search_results.lock().unwrap() = results;
     }
@@ -52,6 +93,14 @@ impl MockAppServices {
         *self.hotness_entries.lock().unwrap() = entries;
     }
 
+    pub fn set_live_at_entries(&self, entries: Vec<LiveAtEntry>) {
+        *self.live_at_entries.lock().unwrap() = entries;
+    }
+
+    pub fn set_watched_categories(&self, watched: Vec<CategorySuggestion>) {
+        *self.watched_categories.lock().unwrap() = watched;
+    }
+
     pub fn save_config_count(&self) -> usize {
         self.save_config_count.load(Ordering::SeqCst)
     }
@@ -71,6 +120,10 @@ impl MockAppServices {
     pub fn hotness_call_count(&self) -> usize {
         self.hotness_call_count.load(Ordering::SeqCst)
     }
+
+    pub fn diagnostics_call_count(&self) -> usize {
+        self.diagnostics_call_count.load(Ordering::SeqCst)
+    }
 }
 
 #[async_trait]
@@ -81,7 +134,15 @@ impl AppServices for MockAppServices {
 
     async fn save_config(&self, config: Config) -> anyhow::Result<()> {
         self.save_config_count.fetch_add(1, Ordering::SeqCst);
-        *self.config.lock().unwrap() = config;
+        let config = config.validated()?;
+        {
+            let mut current = self.config.lock().unwrap();
+            self.settings_undo
+                .lock()
+                .unwrap()
+                .record(&current.streamer_settings, &config.streamer_settings);
+            *current = config;
+        }
         self.refresh_category_streams().await;
         self.refresh_schedules_from_db().await;
         Ok(())
@@ -95,8 +156,26 @@ impl AppServices for MockAppServices {
         self.config.lock().unwrap().followed_categories.clone()
     }
 
-    async fn get_followed_channels(&self) -> Vec<FollowedChannel> {
-        self.channels.lock().unwrap().clone()
+    async fn get_category_streams_page(
+        &self,
+        _category_id: &str,
+        _cursor: Option<String>,
+    ) -> Result<CategoryStreamsPage, ApiError> {
+        Ok(CategoryStreamsPage {
+            streams: Vec::new(),
+            cursor: None,
+        })
+    }
+
+    async fn list_followed_channels(&self, query: &FollowedQuery) -> FollowedChannelsPage {
+        let settings = self.config.lock().unwrap().streamer_settings.clone();
+        list_followed(
+            &self.channels.lock().unwrap(),
+            &[],
+            &std::collections::HashMap::new(),
+            &settings,
+            query,
+        )
     }
 
     async fn refresh_category_streams(&self) {
@@ -116,4 +195,183 @@ impl AppServices for MockAppServices {
         self.hotness_call_count.fetch_add(1, Ordering::SeqCst);
         self.hotness_entries.lock().unwrap().clone()
     }
+
+    async fn create_diagnostics_bundle(&self) -> anyhow::Result<PathBuf> {
+        self.diagnostics_call_count.fetch_add(1, Ordering::SeqCst);
+        Ok(PathBuf::from("/tmp/twitch-tray-diagnostics.zip"))
+    }
+
+    async fn get_diagnostics(&self) -> AppDiagnostics {
+        AppDiagnostics {
+            authenticated: true,
+            followed_channel_count: self.channels.lock().unwrap().len(),
+            db_path: PathBuf::from("/tmp/twitch-tray/data.db"),
+            ..AppDiagnostics::default()
+        }
+    }
+
+    async fn export_data(
+        &self,
+        path: Option<PathBuf>,
+        _include_history: bool,
+    ) -> anyhow::Result<PathBuf> {
+        Ok(path.unwrap_or_else(|| PathBuf::from("/tmp/twitch-tray-export.zip")))
+    }
+
+    async fn import_data(&self, path: PathBuf) -> anyhow::Result<ExportManifest> {
+        if !path.exists() {
+            anyhow::bail!("No such file: {}", path.display());
+        }
+        Ok(ExportManifest::new(chrono::Utc::now(), false))
+    }
+
+    async fn record_stream_opened(&self, _user_login: &str) {}
+
+    async fn set_stream_pinned(&self, user_login: &str, pinned: bool) -> anyhow::Result<()> {
+        let mut config = self.get_config();
+        if pinned {
+            pin_stream(
+                &mut config,
+                PinnedStream {
+                    user_login: user_login.to_string(),
+                    display_name: user_login.to_string(),
+                },
+            );
+        } else {
+            unpin_stream(&mut config, user_login);
+        }
+        *self.config.lock().unwrap() = config;
+        Ok(())
+    }
+
+    async fn snooze_notifications(&self, _user_login: Option<String>, _duration: SnoozeDuration) {}
+
+    async fn resume_notifications(&self, _user_login: Option<String>) {}
+
+    async fn set_importance_bulk(
+        &self,
+        logins: Vec<String>,
+        importance: StreamerImportance,
+    ) -> anyhow::Result<usize> {
+        let mut current = self.config.lock().unwrap();
+        let mut settings = current.streamer_settings.clone();
+        let logins = normalize_logins(&logins);
+        let changed = set_importance(&mut settings, &logins, importance, str::to_string);
+        self.settings_undo
+            .lock()
+            .unwrap()
+            .record(&current.streamer_settings, &settings);
+        current.streamer_settings = settings;
+        Ok(changed)
+    }
+
+    /// Treats every well-formed login as a channel, without asking Twitch.
+    async fn import_streamer_list(
+        &self,
+        path: PathBuf,
+        format: StreamerListFormat,
+        importance: StreamerImportance,
+    ) -> anyhow::Result<StreamerListImport> {
+        let logins = parse_streamer_list(&std::fs::read_to_string(path)?, format)?;
+        let (valid, unresolved): (Vec<String>, Vec<String>) =
+            logins.into_iter().partition(|l| is_valid_login(l));
+        let changed = self.set_importance_bulk(valid, importance).await?;
+        Ok(StreamerListImport {
+            changed,
+            unresolved,
+        })
+    }
+
+    async fn get_live_at(&self, _at: i64) -> Vec<LiveAtEntry> {
+        self.live_at_entries.lock().unwrap().clone()
+    }
+
+    async fn get_history_summary(&self) -> Vec<StreamerStats> {
+        self.history_stats.lock().unwrap().clone()
+    }
+
+    async fn get_streamer_history(&self, user_login: &str) -> Option<StreamerStats> {
+        self.history_stats
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|s| s.broadcaster_login == user_login)
+            .cloned()
+    }
+
+    async fn get_followed_category_suggestions(&self) -> Vec<CategorySuggestion> {
+        let watched = self.watched_categories.lock().unwrap().clone();
+        suggest_categories(watched, &self.get_config())
+    }
+
+    async fn accept_category_suggestion(&self, category: FollowedCategory) -> anyhow::Result<()> {
+        let mut config = self.get_config();
+        if accept_suggestion(&mut config, category) {
+            self.save_config(config).await?;
+        }
+        Ok(())
+    }
+
+    async fn dismiss_category_suggestion(&self, category_id: &str) -> anyhow::Result<()> {
+        let mut config = self.get_config();
+        if dismiss_suggestion(&mut config, category_id) {
+            *self.config.lock().unwrap() = config;
+        }
+        Ok(())
+    }
+
+    async fn set_category_notifications(
+        &self,
+        category_id: &str,
+        notifications: CategoryNotifications,
+    ) -> anyhow::Result<()> {
+        let mut config = self.get_config();
+        let Some(category) = config
+            .followed_categories
+            .iter_mut()
+            .find(|c| c.id == category_id)
+        else {
+            anyhow::bail!("Category {category_id} is not followed");
+        };
+        category.notifications = notifications;
+        *self.config.lock().unwrap() = config;
+        Ok(())
+    }
+
+    async fn note_app_version(&self, version: &str) -> anyhow::Result<bool> {
+        let mut config = self.get_config();
+        let seen = mark_version_seen(&mut config, version);
+        if seen != SeenVersion::Same {
+            *self.config.lock().unwrap() = config;
+        }
+        Ok(seen == SeenVersion::Updated)
+    }
+
+    async fn undo_settings_change(&self) -> anyhow::Result<Option<String>> {
+        let Some(change) = self.settings_undo.lock().unwrap().pop() else {
+            return Ok(None);
+        };
+        let user_login = change.user_login.clone();
+        change.restore(&mut self.config.lock().unwrap().streamer_settings);
+        Ok(Some(user_login))
+    }
+
+    async fn get_auth_state(&self) -> AuthStatus {
+        self.auth_status.lock().unwrap().clone()
+    }
+
+    async fn refresh_on_menu_open(&self) {}
+
+    async fn refresh_now(&self) {}
+
+    async fn mark_menu_seen(&self) {}
+
+    fn get_autostart(&self) -> bool {
+        self.config.lock().unwrap().start_on_login
+    }
+
+    async fn set_autostart(&self, enabled: bool) -> anyhow::Result<()> {
+        self.config.lock().unwrap().start_on_login = enabled;
+        Ok(())
+    }
 }
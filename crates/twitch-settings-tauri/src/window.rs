@@ -3,6 +3,18 @@ use tauri::{AppHandle, Manager, WebviewWindowBuilder};
 /// Width of the settings window in logical pixels
 const SETTINGS_WINDOW_SIZE: f64 = 975.0;
 
+/// Size of the "What's New" window in logical pixels
+const CHANGELOG_WINDOW_WIDTH: f64 = 520.0;
+const CHANGELOG_WINDOW_HEIGHT: f64 = 600.0;
+
+/// Size of the About window in logical pixels
+const ABOUT_WINDOW_WIDTH: f64 = 420.0;
+const ABOUT_WINDOW_HEIGHT: f64 = 560.0;
+
+/// Size of the login code window in logical pixels
+const LOGIN_WINDOW_WIDTH: f64 = 380.0;
+const LOGIN_WINDOW_HEIGHT: f64 = 260.0;
+
 /// Opens the settings window
 pub fn open_settings_window(app: &AppHandle) {
     // Check if window already exists
@@ -24,6 +36,76 @@ pub fn open_settings_window(app: &AppHandle) {
     }
 }
 
+/// Opens the "What's New" window listing changes from the bundled changelog
+pub fn open_changelog_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("changelog") {
+        let _ = window.set_focus();
+        return;
+    }
+
+    match WebviewWindowBuilder::new(
+        app,
+        "changelog",
+        tauri::WebviewUrl::App("changelog.html".into()),
+    )
+    .title("What's New in Twitch Tray")
+    .inner_size(CHANGELOG_WINDOW_WIDTH, CHANGELOG_WINDOW_HEIGHT)
+    .resizable(true)
+    .center()
+    .build()
+    {
+        Ok(_) => tracing::info!("Changelog window opened"),
+        Err(e) => tracing::error!("Failed to open changelog window: {}", e),
+    }
+}
+
+/// Opens the About window: version, build details and license acknowledgements
+pub fn open_about_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("about") {
+        let _ = window.set_focus();
+        return;
+    }
+
+    match WebviewWindowBuilder::new(app, "about", tauri::WebviewUrl::App("about.html".into()))
+        .title("About Twitch Tray")
+        .inner_size(ABOUT_WINDOW_WIDTH, ABOUT_WINDOW_HEIGHT)
+        .resizable(true)
+        .center()
+        .build()
+    {
+        Ok(_) => tracing::info!("About window opened"),
+        Err(e) => tracing::error!("Failed to open about window: {}", e),
+    }
+}
+
+/// Opens the window showing the device code a login waits on, in case the
+/// browser didn't open or the code is needed on another device
+pub fn open_login_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("login") {
+        let _ = window.set_focus();
+        return;
+    }
+
+    match WebviewWindowBuilder::new(app, "login", tauri::WebviewUrl::App("login.html".into()))
+        .title("Log in to Twitch")
+        .inner_size(LOGIN_WINDOW_WIDTH, LOGIN_WINDOW_HEIGHT)
+        .resizable(false)
+        .always_on_top(true)
+        .center()
+        .build()
+    {
+        Ok(_) => tracing::info!("Login window opened"),
+        Err(e) => tracing::error!("Failed to open login window: {}", e),
+    }
+}
+
+/// Closes the login code window once the login has ended
+pub fn close_login_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("login") {
+        let _ = window.close();
+    }
+}
+
 /// Opens a small settings window for a specific streamer
 pub fn open_streamer_settings_window(app: &AppHandle, user_login: &str, display_name: &str) {
     let window_id = format!("streamer-settings-{user_login}");
@@ -0,0 +1,338 @@
+//! TUI application state: cursor position and key handling.
+//!
+//! Key handling is pure — it returns an [`Action`] and `main.rs` performs the
+//! side effects (opening URLs, spawning the player, sending auth commands).
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::view::{Row, TuiView};
+
+/// Player launched by `p` when `TWITCH_TRAY_PLAYER` is unset.
+pub const DEFAULT_PLAYER: &str = "streamlink";
+/// Environment variable overriding the player command; the URL is appended.
+pub const PLAYER_ENV: &str = "TWITCH_TRAY_PLAYER";
+
+/// Side effect requested by a key press.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Quit,
+    OpenInBrowser(String),
+    OpenInPlayer(String),
+    Login,
+    Logout,
+    SwitchUser,
+}
+
+/// Returns the Twitch channel URL for `user_login`.
+pub fn channel_url(user_login: &str) -> String {
+    format!("https://twitch.tv/{user_login}")
+}
+
+/// Builds the player program and arguments for `url`.
+///
+/// With no override this is `streamlink <url> best`. An override is split on
+/// whitespace and gets the URL as its final argument (e.g. `mpv` → `mpv <url>`).
+pub fn player_command(override_cmd: Option<&str>, url: &str) -> (String, Vec<String>) {
+    let mut parts = override_cmd
+        .map(str::split_whitespace)
+        .into_iter()
+        .flatten()
+        .map(str::to_string);
+    match parts.next() {
+        Some(program) => {
+            let mut args: Vec<String> = parts.collect();
+            args.push(url.to_string());
+            (program, args)
+        }
+        None => (
+            DEFAULT_PLAYER.to_string(),
+            vec![url.to_string(), "best".to_string()],
+        ),
+    }
+}
+
+/// Current view plus cursor and status line.
+#[derive(Debug)]
+pub struct App {
+    pub view: TuiView,
+    /// Index into `view.rows`; always a selectable row when `Some`.
+    pub selected: Option<usize>,
+    /// One-line feedback shown in the footer (e.g. a failed player launch).
+    pub status: Option<String>,
+}
+
+impl App {
+    pub fn new(view: TuiView) -> Self {
+        let mut app = Self {
+            view,
+            selected: None,
+            status: None,
+        };
+        app.selected = app.first_selectable();
+        app
+    }
+
+    /// Replaces the view, keeping the cursor on the same channel when it is still listed.
+    pub fn set_view(&mut self, view: TuiView) {
+        let previous = self.selected_login().map(str::to_string);
+        self.view = view;
+        self.selected = previous
+            .and_then(|login| {
+                self.view
+                    .rows
+                    .iter()
+                    .position(|r| r.user_login.as_deref() == Some(login.as_str()))
+            })
+            .or_else(|| self.first_selectable());
+    }
+
+    /// Login of the channel under the cursor.
+    pub fn selected_login(&self) -> Option<&str> {
+        self.selected
+            .and_then(|i| self.view.rows.get(i))
+            .and_then(|r| r.user_login.as_deref())
+    }
+
+    fn first_selectable(&self) -> Option<usize> {
+        self.view.rows.iter().position(Row::is_selectable)
+    }
+
+    fn move_selection(&mut self, forward: bool) {
+        let Some(current) = self.selected else {
+            self.selected = self.first_selectable();
+            return;
+        };
+        let rows = &self.view.rows;
+        let next = if forward {
+            (current + 1..rows.len()).find(|&i| rows[i].is_selectable())
+        } else {
+            (0..current).rev().find(|&i| rows[i].is_selectable())
+        };
+        if let Some(i) = next {
+            self.selected = Some(i);
+        }
+    }
+
+    /// Maps a key press to an action, updating the cursor for navigation keys.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.move_selection(true);
+                Action::None
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.move_selection(false);
+                Action::None
+            }
+            KeyCode::Enter | KeyCode::Char('o') => self
+                .selected_login()
+                .map_or(Action::None, |l| Action::OpenInBrowser(l.to_string())),
+            KeyCode::Char('p') => self
+                .selected_login()
+                .map_or(Action::None, |l| Action::OpenInPlayer(l.to_string())),
+            KeyCode::Char('l') if !self.view.authenticated => Action::Login,
+            KeyCode::Char('L') if self.view.authenticated => Action::Logout,
+            KeyCode::Char('S') if self.view.authenticated => Action::SwitchUser,
+            _ => Action::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::RowKind;
+
+    // =========================================================
+    // Test helpers
+    // =========================================================
+
+    fn row(kind: RowKind, login: Option<&str>) -> Row {
+        Row {
+            kind,
+            text: login.unwrap_or("header").to_string(),
+            user_login: login.map(str::to_string),
+            is_favourite: false,
+        }
+    }
+
+    fn view(rows: Vec<Row>) -> TuiView {
+        TuiView {
+            authenticated: true,
+            pending_login: None,
+            rows,
+        }
+    }
+
+    fn sample_view() -> TuiView {
+        view(vec![
+            row(RowKind::Header, None),
+            row(RowKind::Live, Some("alice")),
+            row(RowKind::Live, Some("bob")),
+            row(RowKind::Header, None),
+            row(RowKind::Scheduled, Some("carol")),
+        ])
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    // =========================================================
+    // Navigation
+    // =========================================================
+
+    #[test]
+    fn starts_on_first_selectable_row() {
+        let app = App::new(sample_view());
+        assert_eq!(app.selected_login(), Some("alice"));
+    }
+
+    #[test]
+    fn navigation_skips_headers() {
+        let mut app = App::new(sample_view());
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.selected_login(), Some("carol"));
+        app.handle_key(key(KeyCode::Up));
+        assert_eq!(app.selected_login(), Some("bob"));
+    }
+
+    #[test]
+    fn navigation_stops_at_ends() {
+        let mut app = App::new(sample_view());
+        app.handle_key(key(KeyCode::Up));
+        assert_eq!(app.selected_login(), Some("alice"));
+        for _ in 0..5 {
+            app.handle_key(key(KeyCode::Down));
+        }
+        assert_eq!(app.selected_login(), Some("carol"));
+    }
+
+    #[test]
+    fn set_view_keeps_selection_on_same_channel() {
+        let mut app = App::new(sample_view());
+        app.handle_key(key(KeyCode::Down));
+        app.set_view(view(vec![
+            row(RowKind::Header, None),
+            row(RowKind::Live, Some("bob")),
+            row(RowKind::Live, Some("alice")),
+        ]));
+        assert_eq!(app.selected_login(), Some("bob"));
+    }
+
+    #[test]
+    fn set_view_falls_back_when_channel_gone() {
+        let mut app = App::new(sample_view());
+        app.set_view(view(vec![
+            row(RowKind::Header, None),
+            row(RowKind::Live, Some("dave")),
+        ]));
+        assert_eq!(app.selected_login(), Some("dave"));
+    }
+
+    // =========================================================
+    // Actions
+    // =========================================================
+
+    #[test]
+    fn enter_opens_selected_in_browser() {
+        let mut app = App::new(sample_view());
+        assert_eq!(
+            app.handle_key(key(KeyCode::Enter)),
+            Action::OpenInBrowser("alice".to_string())
+        );
+    }
+
+    #[test]
+    fn p_opens_selected_in_player() {
+        let mut app = App::new(sample_view());
+        assert_eq!(
+            app.handle_key(key(KeyCode::Char('p'))),
+            Action::OpenInPlayer("alice".to_string())
+        );
+    }
+
+    #[test]
+    fn open_without_selection_is_noop() {
+        let mut app = App::new(view(vec![row(RowKind::Header, None)]));
+        assert_eq!(app.handle_key(key(KeyCode::Enter)), Action::None);
+    }
+
+    #[test]
+    fn quit_keys() {
+        let mut app = App::new(sample_view());
+        assert_eq!(app.handle_key(key(KeyCode::Char('q'))), Action::Quit);
+        assert_eq!(app.handle_key(key(KeyCode::Esc)), Action::Quit);
+        assert_eq!(
+            app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Action::Quit
+        );
+    }
+
+    #[test]
+    fn login_only_when_logged_out_and_logout_only_when_logged_in() {
+        let mut logged_in = App::new(sample_view());
+        assert_eq!(logged_in.handle_key(key(KeyCode::Char('l'))), Action::None);
+        assert_eq!(
+            logged_in.handle_key(key(KeyCode::Char('L'))),
+            Action::Logout
+        );
+
+        let mut logged_out = App::new(TuiView {
+            authenticated: false,
+            pending_login: None,
+            rows: vec![],
+        });
+        assert_eq!(
+            logged_out.handle_key(key(KeyCode::Char('l'))),
+            Action::Login
+        );
+        assert_eq!(logged_out.handle_key(key(KeyCode::Char('L'))), Action::None);
+    }
+
+    #[test]
+    fn switch_user_only_when_logged_in() {
+        let mut logged_in = App::new(sample_view());
+        assert_eq!(
+            logged_in.handle_key(key(KeyCode::Char('S'))),
+            Action::SwitchUser
+        );
+
+        let mut logged_out = App::new(TuiView {
+            authenticated: false,
+            pending_login: None,
+            rows: vec![],
+        });
+        assert_eq!(logged_out.handle_key(key(KeyCode::Char('S'))), Action::None);
+    }
+
+    // =========================================================
+    // Player command
+    // =========================================================
+
+    #[test]
+    fn default_player_is_streamlink_best() {
+        let (program, args) = player_command(None, "https://twitch.tv/alice");
+        assert_eq!(program, "streamlink");
+        assert_eq!(args, vec!["https://twitch.tv/alice", "best"]);
+    }
+
+    #[test]
+    fn player_override_gets_url_appended() {
+        let (program, args) = player_command(Some("mpv --no-terminal"), "https://twitch.tv/a");
+        assert_eq!(program, "mpv");
+        assert_eq!(args, vec!["--no-terminal", "https://twitch.tv/a"]);
+    }
+
+    #[test]
+    fn blank_player_override_uses_default() {
+        let (program, _) = player_command(Some("   "), "https://twitch.tv/a");
+        assert_eq!(program, DEFAULT_PLAYER);
+    }
+}
@@ -0,0 +1,5 @@
+// twitch-tray-tui: terminal frontend over twitch-backend. No Tauri or GTK.
+
+pub mod app;
+pub mod ui;
+pub mod view;
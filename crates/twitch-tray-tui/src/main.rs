@@ -0,0 +1,155 @@
+use std::process::Stdio;
+use std::time::Duration;
+
+use chrono::Utc;
+use ratatui::crossterm::event::{self, Event, KeyEvent, KeyEventKind};
+use tokio::sync::mpsc;
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter};
+
+use twitch_backend::{
+    log_file::{self, DailyLogFile},
+    log_scrub::RedactingMakeWriter,
+    support, AuthCommand,
+};
+use twitch_tray_tui::{
+    app::{channel_url, player_command, Action, App, PLAYER_ENV},
+    ui,
+    view::compute_view,
+};
+
+/// Redraw interval so durations and start times stay current without new data.
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+/// Daily log files are named `tui.<date>.log`.
+const LOG_FILE_PREFIX: &str = "tui";
+
+/// Logs go to a file: anything written to stdout/stderr would corrupt the screen.
+/// Returns the handle `log_file::follow_log_level` changes the level through.
+fn init_logging() -> anyhow::Result<reload::Handle<EnvFilter, tracing_subscriber::Registry>> {
+    let file = DailyLogFile::open(&support::log_dir()?, LOG_FILE_PREFIX)?;
+    let (filter, filter_handle) = reload::Layer::new(log_file::initial_filter());
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(RedactingMakeWriter::new(std::sync::Mutex::new(file))),
+        )
+        .init();
+    Ok(filter_handle)
+}
+
+/// Forwards terminal key presses from a blocking reader thread.
+/// Other terminal events (resize, focus) are sent as `None` to trigger a redraw.
+fn spawn_input_reader(tx: mpsc::UnboundedSender<Option<KeyEvent>>) {
+    std::thread::spawn(move || loop {
+        let msg = match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => Some(key),
+            Ok(Event::Key(_)) => continue,
+            Ok(_) => None,
+            Err(e) => {
+                tracing::error!("Terminal input error: {}", e);
+                return;
+            }
+        };
+        if tx.send(msg).is_err() {
+            return;
+        }
+    });
+}
+
+fn launch_player(user_login: &str) -> anyhow::Result<()> {
+    let override_cmd = std::env::var(PLAYER_ENV).ok();
+    let (program, args) = player_command(override_cmd.as_deref(), &channel_url(user_login));
+    std::process::Command::new(&program)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("{program}: {e}"))?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let log_filter = init_logging()?;
+    tracing::info!("Starting Twitch Tray TUI");
+
+    let handle = twitch_backend::start()?;
+    tokio::spawn(log_file::follow_log_level(
+        log_filter,
+        handle.display_rx.clone(),
+    ));
+    let mut display_rx = handle.display_rx;
+    let mut login_rx = handle.login_progress_rx;
+
+    let (key_tx, mut key_rx) = mpsc::unbounded_channel();
+    spawn_input_reader(key_tx);
+
+    let mut terminal = ratatui::init();
+    let mut app = App::new(compute_view(
+        &display_rx.borrow(),
+        login_rx.borrow().as_ref(),
+        Utc::now(),
+    ));
+    let mut tick = tokio::time::interval(TICK_INTERVAL);
+
+    let result: anyhow::Result<()> = loop {
+        if let Err(e) = terminal.draw(|frame| ui::draw(frame, &app)) {
+            break Err(e.into());
+        }
+
+        tokio::select! {
+            Ok(()) = display_rx.changed() => {}
+            Ok(()) = login_rx.changed() => {}
+            _ = tick.tick() => {}
+            msg = key_rx.recv() => {
+                let Some(msg) = msg else { break Ok(()) };
+                let Some(key) = msg else { continue };
+                match app.handle_key(key) {
+                    Action::None => {}
+                    Action::Quit => break Ok(()),
+                    Action::OpenInBrowser(login) => {
+                        app.status = Some(match open::that(channel_url(&login)) {
+                            Ok(()) => {
+                                handle.services.record_stream_opened(&login).await;
+                                format!("Opened {login}")
+                            }
+                            Err(e) => format!("Failed to open browser: {e}"),
+                        });
+                    }
+                    Action::OpenInPlayer(login) => {
+                        app.status = Some(match launch_player(&login) {
+                            Ok(()) => {
+                                handle.services.record_stream_opened(&login).await;
+                                format!("Playing {login}")
+                            }
+                            Err(e) => format!("Failed to start player: {e}"),
+                        });
+                    }
+                    Action::Login => {
+                        let _ = handle.auth_cmd_tx.send(AuthCommand::Login);
+                    }
+                    Action::Logout => {
+                        let _ = handle.auth_cmd_tx.send(AuthCommand::Logout);
+                    }
+                    Action::SwitchUser => {
+                        let _ = handle.auth_cmd_tx.send(AuthCommand::SwitchUser);
+                    }
+                }
+            }
+        }
+
+        app.set_view(compute_view(
+            &display_rx.borrow(),
+            login_rx.borrow().as_ref(),
+            Utc::now(),
+        ));
+    };
+
+    ratatui::restore();
+    for task in handle.tasks {
+        task.abort();
+    }
+    result
+}
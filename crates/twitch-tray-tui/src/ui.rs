@@ -0,0 +1,145 @@
+//! Rendering of [`App`] with ratatui.
+
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::view::RowKind;
+
+const LOGGED_IN_HELP: &str =
+    "\u{2191}/\u{2193} move  Enter open  p player  S switch user  L logout  q quit";
+const LOGGED_OUT_HELP: &str = "l login  q quit";
+
+/// Draws the whole screen: stream list (or login prompt) and a footer.
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [main, footer] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Twitch Tray ");
+
+    if app.view.authenticated {
+        let items: Vec<ListItem> = app
+            .view
+            .rows
+            .iter()
+            .map(|row| {
+                let style = match row.kind {
+                    RowKind::Header => Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD),
+                    RowKind::Placeholder => Style::default().fg(Color::DarkGray),
+                    RowKind::Live | RowKind::Scheduled if row.is_favourite => {
+                        Style::default().fg(Color::Yellow)
+                    }
+                    RowKind::Live | RowKind::Scheduled => Style::default(),
+                };
+                ListItem::new(Line::styled(row.text.clone(), style))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(app.selected);
+        frame.render_stateful_widget(list, main, &mut state);
+    } else {
+        let lines = match &app.view.pending_login {
+            Some(pending) => vec![
+                Line::from(format!(
+                    "Visit {} and enter the code:",
+                    pending.verification_uri
+                )),
+                Line::from(""),
+                Line::from(Span::styled(
+                    pending.user_code.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                Line::styled(
+                    format!("Code {}", pending.expiry),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ],
+            None => vec![Line::from("Not logged in. Press l to log in to Twitch.")],
+        };
+        frame.render_widget(
+            Paragraph::new(lines).block(block).wrap(Wrap { trim: true }),
+            main,
+        );
+    }
+
+    let help = if app.view.authenticated {
+        LOGGED_IN_HELP
+    } else {
+        LOGGED_OUT_HELP
+    };
+    let footer_text = match &app.status {
+        Some(status) => format!("{status}  |  {help}"),
+        None => help.to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(footer_text).style(Style::default().fg(Color::DarkGray)),
+        footer,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::{PendingLogin, Row, TuiView};
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn render(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(60, 8)).unwrap();
+        terminal.draw(|f| draw(f, app)).unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(ratatui::buffer::Cell::symbol)
+            .collect()
+    }
+
+    #[test]
+    fn renders_pending_login_code() {
+        let app = App::new(TuiView {
+            authenticated: false,
+            pending_login: Some(PendingLogin {
+                user_code: "ABCD-1234".to_string(),
+                verification_uri: "https://twitch.tv/activate".to_string(),
+                expiry: "expires in 9 min".to_string(),
+            }),
+            rows: vec![],
+        });
+        let screen = render(&app);
+        assert!(screen.contains("ABCD-1234"));
+        assert!(screen.contains("Code expires in 9 min"));
+        assert!(screen.contains("l login"));
+    }
+
+    #[test]
+    fn renders_rows_and_status() {
+        let mut app = App::new(TuiView {
+            authenticated: true,
+            pending_login: None,
+            rows: vec![Row {
+                kind: RowKind::Live,
+                text: "Alice - Chess (1k, 1h)".to_string(),
+                user_login: Some("alice".to_string()),
+                is_favourite: false,
+            }],
+        });
+        app.status = Some("Opened alice".to_string());
+        let screen = render(&app);
+        assert!(screen.contains("Alice - Chess"));
+        assert!(screen.contains("Opened alice"));
+    }
+}
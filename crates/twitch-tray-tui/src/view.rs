@@ -0,0 +1,501 @@
+//! Terminal view model: turns `RawDisplayData` into the rows the TUI draws.
+//!
+//! Ordering and filtering mirror the tray menu (`display_state.rs`) and the
+//! plasmoid (`plasmoid_state.rs`): Ignore streamers are hidden, favourites sort
+//! first, and schedules covered by a live broadcast are dropped. There are no
+//! overflow sections — the terminal list scrolls instead.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+use twitch_backend::{
+    config::FavouriteSortMode,
+    filtering::{is_favourite, shows_in_menu},
+    handle::{LoginProgress, RawDisplayData},
+    notify::{format_live_label, truncate_width},
+    twitch::{ScheduledStream, Stream},
+};
+
+/// A scheduled stream within this many minutes of a live broadcast is hidden from the schedule.
+const LIVE_COVERS_SCHEDULE_WINDOW_MIN: i64 = 60;
+
+/// What a row represents, which decides its style and whether it can be selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowKind {
+    Header,
+    Placeholder,
+    Live,
+    Scheduled,
+}
+
+/// One line of the stream list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub kind: RowKind,
+    pub text: String,
+    /// Channel login opened when the row is activated; `None` for headers/placeholders.
+    pub user_login: Option<String>,
+    pub is_favourite: bool,
+}
+
+impl Row {
+    fn header(text: String) -> Self {
+        Self {
+            kind: RowKind::Header,
+            text,
+            user_login: None,
+            is_favourite: false,
+        }
+    }
+
+    fn placeholder(text: &str) -> Self {
+        Self {
+            kind: RowKind::Placeholder,
+            text: format!("  {text}"),
+            user_login: None,
+            is_favourite: false,
+        }
+    }
+
+    /// Whether the cursor can land on this row.
+    pub fn is_selectable(&self) -> bool {
+        self.user_login.is_some()
+    }
+}
+
+/// Device code shown while a login is waiting for the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingLogin {
+    pub user_code: String,
+    pub verification_uri: String,
+    /// Time left on the code, e.g. "expires in 9 min".
+    pub expiry: String,
+}
+
+/// Everything the TUI draws for one snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuiView {
+    pub authenticated: bool,
+    /// Set while a device-code login is waiting.
+    pub pending_login: Option<PendingLogin>,
+    pub rows: Vec<Row>,
+}
+
+/// Format: `"[⏳3 ][🔥 ][★ ]<template>"`, with `template` filled by
+/// `format_live_label`; by default `"StreamerName - GameName (1.2k, 2h 15m)"`.
+fn live_row(
+    s: &Stream,
+    is_favourite: bool,
+    is_hot: bool,
+    streak_at_risk: Option<u32>,
+    template: &str,
+    max_width: usize,
+) -> Row {
+    let streak = streak_at_risk.map_or(String::new(), |n| format!("\u{23F3}{n} "));
+    let fire = if is_hot { "\u{1F525} " } else { "" };
+    let star = if is_favourite { "\u{2605} " } else { "" };
+    Row {
+        kind: RowKind::Live,
+        text: format!(
+            "{streak}{fire}{star}{}",
+            format_live_label(template, s, max_width)
+        ),
+        user_login: Some(s.user_login.clone()),
+        is_favourite,
+    }
+}
+
+/// Format: `"[✨ ][★ ]StreamerName - Tomorrow 3:00 PM"`
+fn scheduled_row(s: &ScheduledStream, is_favourite: bool, max_width: usize) -> Row {
+    let sparkle = if s.is_inferred { "\u{2728} " } else { "" };
+    let star = if is_favourite { "\u{2605} " } else { "" };
+    Row {
+        kind: RowKind::Scheduled,
+        text: format!(
+            "{sparkle}{star}{} - {}",
+            truncate_width(&s.broadcaster_name, max_width),
+            s.format_start_time()
+        ),
+        user_login: Some(s.broadcaster_login.clone()),
+        is_favourite,
+    }
+}
+
+/// Describes how long a device code stays valid.
+///
+/// Whole minutes only: the screen is redrawn every 30 seconds.
+pub fn format_code_expiry(remaining: Duration) -> String {
+    match remaining.num_minutes() {
+        _ if remaining <= Duration::zero() => "expired, requesting a new code".to_string(),
+        0 => "expires in under a minute".to_string(),
+        mins => format!("expires in {mins} min"),
+    }
+}
+
+/// Computes the rows to draw from raw backend data.
+///
+/// `now` is passed in rather than calling `Utc::now()` so the function is
+/// deterministically testable.
+pub fn compute_view(
+    raw: &RawDisplayData,
+    login_progress: Option<&LoginProgress>,
+    now: DateTime<Utc>,
+) -> TuiView {
+    let pending_login = match login_progress {
+        Some(LoginProgress::PendingCode {
+            user_code,
+            verification_uri,
+            expires_at,
+        }) => Some(PendingLogin {
+            user_code: user_code.clone(),
+            verification_uri: verification_uri.clone(),
+            expiry: format_code_expiry(*expires_at - now),
+        }),
+        _ => None,
+    };
+
+    if !raw.is_authenticated {
+        return TuiView {
+            authenticated: false,
+            pending_login,
+            rows: vec![],
+        };
+    }
+
+    let settings = &raw.config.streamer_settings;
+    let is_favourite = |login: &str| is_favourite(login, settings);
+    let mut rows = Vec::new();
+
+    // --- Live section ---
+
+    let mut streams: Vec<&Stream> = raw
+        .live_streams
+        .iter()
+        .filter(|s| shows_in_menu(&s.user_login, settings))
+        .collect();
+    let live_logins: HashSet<&str> = streams.iter().map(|s| s.user_login.as_str()).collect();
+
+    let sort_by_recency = raw.config.favourite_sort_mode == FavouriteSortMode::RecentlyWatched;
+    streams.sort_by(|a, b| {
+        let a_fav = is_favourite(&a.user_login);
+        let b_fav = is_favourite(&b.user_login);
+        b_fav
+            .cmp(&a_fav)
+            .then_with(|| {
+                if sort_by_recency && a_fav && b_fav {
+                    // Most recently watched first; never-watched (None) last
+                    raw.last_watched
+                        .get(&b.user_login)
+                        .cmp(&raw.last_watched.get(&a.user_login))
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .then_with(|| raw.config.live_sort.compare(a, b, &raw.last_notified))
+    });
+
+    let mut live_header = if streams.is_empty() {
+        "Following Live".to_string()
+    } else {
+        format!("Following Live ({})", streams.len())
+    };
+    if raw.followed_cached {
+        live_header.push_str(" (cached)");
+    }
+    rows.push(Row::header(live_header));
+    if streams.is_empty() {
+        rows.push(Row::placeholder("No streams live"));
+    }
+    for s in streams {
+        let hot = raw.hot_stream_ids.contains(&s.user_id);
+        let streak = raw.streaks_at_risk.get(&s.user_id).copied();
+        rows.push(live_row(
+            s,
+            is_favourite(&s.user_login),
+            hot,
+            streak,
+            &raw.config.live_label_format,
+            raw.config.max_label_width,
+        ));
+    }
+
+    // --- Schedule section ---
+
+    let soon_threshold = now + Duration::minutes(LIVE_COVERS_SCHEDULE_WINDOW_MIN);
+    let scheduled: Vec<&ScheduledStream> = raw
+        .scheduled_streams
+        .iter()
+        .filter(|s| shows_in_menu(&s.broadcaster_login, settings))
+        .filter(|s| {
+            !(live_logins.contains(s.broadcaster_login.as_str()) && s.start_time <= soon_threshold)
+        })
+        .collect();
+
+    rows.push(Row::header(format!(
+        "Scheduled (Next {}h)",
+        raw.config.schedule_lookahead_hours
+    )));
+    if scheduled.is_empty() {
+        rows.push(Row::placeholder(if raw.schedules_loaded {
+            "No scheduled streams"
+        } else {
+            "Loading schedules..."
+        }));
+    }
+    for s in scheduled {
+        rows.push(scheduled_row(
+            s,
+            is_favourite(&s.broadcaster_login),
+            raw.config.max_label_width,
+        ));
+    }
+
+    TuiView {
+        authenticated: true,
+        pending_login,
+        rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use twitch_backend::config::{Config, LiveSortMode, StreamerImportance, StreamerSettings};
+
+    // =========================================================
+    // Test helpers
+    // =========================================================
+
+    fn make_stream(user_name: &str, viewer_count: u32) -> Stream {
+        Stream {
+            id: format!("stream_{user_name}"),
+            user_id: format!("id_{user_name}"),
+            user_login: user_name.to_lowercase(),
+            user_name: user_name.to_string(),
+            game_id: "game123".to_string(),
+            game_name: "Test Game".to_string(),
+            title: "Test Stream".to_string(),
+            viewer_count,
+            started_at: Utc::now() - Duration::hours(1),
+            thumbnail_url: String::new(),
+            tags: vec![],
+            profile_image_url: String::new(),
+        }
+    }
+
+    fn make_scheduled(broadcaster_name: &str, start_time: DateTime<Utc>) -> ScheduledStream {
+        ScheduledStream {
+            id: format!("sched_{broadcaster_name}"),
+            broadcaster_id: broadcaster_name.to_lowercase(),
+            broadcaster_name: broadcaster_name.to_string(),
+            broadcaster_login: broadcaster_name.to_lowercase(),
+            title: "Scheduled Stream".to_string(),
+            start_time,
+            end_time: None,
+            category: Some("Gaming".to_string()),
+            category_id: Some("123".to_string()),
+            is_recurring: false,
+            is_inferred: false,
+            confidence: None,
+        }
+    }
+
+    fn raw(streams: Vec<Stream>, scheduled: Vec<ScheduledStream>) -> RawDisplayData {
+        RawDisplayData {
+            is_authenticated: true,
+            live_streams: streams,
+            scheduled_streams: scheduled,
+            schedules_loaded: true,
+            config: Config::default(),
+            ..RawDisplayData::default()
+        }
+    }
+
+    fn with_importance(
+        mut raw: RawDisplayData,
+        login: &str,
+        importance: StreamerImportance,
+    ) -> RawDisplayData {
+        raw.config.streamer_settings.insert(
+            login.to_string(),
+            StreamerSettings {
+                display_name: login.to_string(),
+                importance,
+                hotness_z_threshold_override: None,
+                notification_open_mode: None,
+                notification_sound: None,
+                open_with: None,
+                schedule_reminder_min: None,
+                notify_on_category_change: true,
+            },
+        );
+        raw
+    }
+
+    fn logins(view: &TuiView, kind: RowKind) -> Vec<&str> {
+        view.rows
+            .iter()
+            .filter(|r| r.kind == kind)
+            .filter_map(|r| r.user_login.as_deref())
+            .collect()
+    }
+
+    // =========================================================
+    // Authentication
+    // =========================================================
+
+    #[test]
+    fn unauthenticated_has_no_rows() {
+        let view = compute_view(&RawDisplayData::default(), None, Utc::now());
+        assert!(!view.authenticated);
+        assert!(view.rows.is_empty());
+        assert!(view.pending_login.is_none());
+    }
+
+    #[test]
+    fn pending_login_code_exposed() {
+        let now = Utc::now();
+        let progress = LoginProgress::PendingCode {
+            user_code: "ABCD-1234".to_string(),
+            verification_uri: "https://twitch.tv/activate".to_string(),
+            expires_at: now + Duration::seconds(570),
+        };
+        let view = compute_view(&RawDisplayData::default(), Some(&progress), now);
+        assert_eq!(
+            view.pending_login,
+            Some(PendingLogin {
+                user_code: "ABCD-1234".to_string(),
+                verification_uri: "https://twitch.tv/activate".to_string(),
+                expiry: "expires in 9 min".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn code_expiry_formatting() {
+        assert_eq!(
+            format_code_expiry(Duration::seconds(45)),
+            "expires in under a minute"
+        );
+        assert_eq!(
+            format_code_expiry(Duration::seconds(-5)),
+            "expired, requesting a new code"
+        );
+    }
+
+    // =========================================================
+    // Live section
+    // =========================================================
+
+    #[test]
+    fn live_sorted_favourites_first_then_viewers() {
+        let data = raw(
+            vec![
+                make_stream("Small", 10),
+                make_stream("Big", 5000),
+                make_stream("Fav", 1),
+            ],
+            vec![],
+        );
+        let data = with_importance(data, "fav", StreamerImportance::Favourite);
+        let view = compute_view(&data, None, Utc::now());
+        assert_eq!(logins(&view, RowKind::Live), vec!["fav", "big", "small"]);
+        assert!(view.rows[1].text.starts_with("\u{2605} Fav"));
+    }
+
+    #[test]
+    fn live_sort_applies_after_favourites() {
+        let mut data = raw(
+            vec![
+                make_stream("Zed", 5000),
+                make_stream("Fav", 1),
+                make_stream("Amy", 10),
+            ],
+            vec![],
+        );
+        data.config.live_sort = LiveSortMode::Alphabetical;
+        let data = with_importance(data, "fav", StreamerImportance::Favourite);
+        let view = compute_view(&data, None, Utc::now());
+        assert_eq!(logins(&view, RowKind::Live), vec!["fav", "amy", "zed"]);
+    }
+
+    #[test]
+    fn ignored_streamers_hidden_from_live() {
+        let data = raw(vec![make_stream("Hidden", 100)], vec![]);
+        let data = with_importance(data, "hidden", StreamerImportance::Ignore);
+        let view = compute_view(&data, None, Utc::now());
+        assert!(logins(&view, RowKind::Live).is_empty());
+        assert_eq!(view.rows[1].kind, RowKind::Placeholder);
+    }
+
+    #[test]
+    fn streak_at_risk_marks_row() {
+        let mut data = raw(vec![make_stream("Fav", 10)], vec![]);
+        data.streaks_at_risk.insert("id_Fav".to_string(), 2);
+        let view = compute_view(&data, None, Utc::now());
+        assert!(view.rows[1].text.starts_with("\u{23F3}2 Fav"));
+    }
+
+    #[test]
+    fn long_names_cut_to_configured_width() {
+        let mut data = raw(vec![make_stream(&"\u{5b9f}".repeat(20), 10)], vec![]);
+        data.config.max_label_width = 10;
+        let view = compute_view(&data, None, Utc::now());
+        assert!(view.rows[1]
+            .text
+            .starts_with(&format!("{}... - Test Game", "\u{5b9f}".repeat(3))));
+    }
+
+    #[test]
+    fn live_header_counts_streams() {
+        let data = raw(vec![make_stream("A", 1), make_stream("B", 2)], vec![]);
+        let view = compute_view(&data, None, Utc::now());
+        assert_eq!(view.rows[0].text, "Following Live (2)");
+    }
+
+    #[test]
+    fn live_header_marks_startup_cache() {
+        let data = RawDisplayData {
+            followed_cached: true,
+            ..raw(vec![make_stream("A", 1)], vec![])
+        };
+        let view = compute_view(&data, None, Utc::now());
+        assert_eq!(view.rows[0].text, "Following Live (1) (cached)");
+    }
+
+    // =========================================================
+    // Schedule section
+    // =========================================================
+
+    #[test]
+    fn schedule_hidden_when_same_streamer_live_soon() {
+        let now = Utc::now();
+        let data = raw(
+            vec![make_stream("Live", 100)],
+            vec![
+                make_scheduled("Live", now + Duration::minutes(30)),
+                make_scheduled("Other", now + Duration::hours(2)),
+            ],
+        );
+        let view = compute_view(&data, None, now);
+        assert_eq!(logins(&view, RowKind::Scheduled), vec!["other"]);
+    }
+
+    #[test]
+    fn schedule_placeholder_reflects_loading_state() {
+        let mut data = raw(vec![], vec![]);
+        data.schedules_loaded = false;
+        let view = compute_view(&data, None, Utc::now());
+        assert!(view
+            .rows
+            .iter()
+            .any(|r| r.kind == RowKind::Placeholder && r.text.contains("Loading")));
+    }
+
+    #[test]
+    fn headers_and_placeholders_not_selectable() {
+        let data = raw(vec![], vec![]);
+        let view = compute_view(&data, None, Utc::now());
+        assert!(view.rows.iter().all(|r| !r.is_selectable()));
+    }
+}
@@ -0,0 +1,42 @@
+// About window JavaScript: renders the build details from about.rs
+const { invoke } = window.__TAURI__.core;
+
+const detailsList = document.getElementById('about-details');
+const acknowledgementsList = document.getElementById('acknowledgements');
+
+function addDetail(label, value) {
+  const term = document.createElement('dt');
+  term.textContent = label;
+  const description = document.createElement('dd');
+  description.textContent = value;
+  detailsList.append(term, description);
+}
+
+function renderAbout(info) {
+  addDetail('Version', info.version);
+  addDetail('Commit', info.commit);
+  addDetail('Built', info.build_date);
+  addDetail('Platform', info.platform);
+  addDetail('License', info.license);
+
+  for (const ack of info.acknowledgements) {
+    const item = document.createElement('li');
+    item.textContent = ack.name;
+    item.title = ack.url;
+    const license = document.createElement('span');
+    license.className = 'license';
+    license.textContent = ` (${ack.license})`;
+    item.appendChild(license);
+    acknowledgementsList.appendChild(item);
+  }
+}
+
+async function init() {
+  try {
+    renderAbout(await invoke('get_about_info'));
+  } catch (e) {
+    console.error('Failed to load about info:', e);
+  }
+}
+
+init();
@@ -0,0 +1,103 @@
+// Category window JavaScript: a followed category's streams from dashboard.rs's
+// get_category_streams, opened by "Show more…" in its menu submenu. The
+// category ID is the end of the window label.
+const { invoke } = window.__TAURI__.core;
+const { getCurrentWindow } = window.__TAURI__.window;
+
+const header = document.getElementById('category_header');
+const cards = document.getElementById('category_cards');
+const status = document.getElementById('category_status');
+const moreButton = document.getElementById('more_btn');
+
+const categoryId = getCurrentWindow().label.replace(/^category-/, '');
+let cursor = null;
+// Twitch's pages shift as viewer counts change, so a stream can come round twice
+const shown = new Set();
+
+// Same menu item IDs and modifiers as the dashboard
+async function activate(id, event) {
+  const modifiers = {
+    ctrl: event.ctrlKey || event.metaKey,
+    shift: event.shiftKey,
+    alt: event.altKey,
+  };
+  try {
+    await invoke('activate_menu_row', { id, modifiers });
+  } catch (e) {
+    console.error('Failed to open stream:', e);
+  }
+}
+
+function image(src, className) {
+  const img = document.createElement('img');
+  img.className = className;
+  img.loading = 'lazy';
+  img.alt = '';
+  if (src) {
+    img.src = src;
+  }
+  return img;
+}
+
+function textDiv(text, className) {
+  const div = document.createElement('div');
+  div.className = className;
+  div.textContent = text;
+  return div;
+}
+
+function streamCard(card) {
+  const div = document.createElement('div');
+  div.className = 'dashboard-card';
+  div.title = card.title;
+  div.appendChild(image(card.thumbnail_url, 'dashboard-thumbnail'));
+
+  const info = document.createElement('div');
+  info.className = 'dashboard-card-info';
+  info.appendChild(image(card.profile_image_url, 'dashboard-avatar'));
+  const text = document.createElement('div');
+  text.className = 'dashboard-card-text';
+  text.appendChild(textDiv(card.user_name, 'dashboard-name'));
+  text.appendChild(textDiv(card.title, 'dashboard-title'));
+  text.appendChild(textDiv(
+    `${card.viewer_count_formatted} viewers · ${card.duration_formatted}`,
+    'dashboard-meta',
+  ));
+  info.appendChild(text);
+  div.appendChild(info);
+
+  div.addEventListener('click', (event) => activate(card.id, event));
+  return div;
+}
+
+async function loadMore() {
+  moreButton.disabled = true;
+  status.hidden = false;
+  status.textContent = 'Loading...';
+  try {
+    const page = await invoke('get_category_streams', { categoryId, cursor });
+    for (const card of page.streams) {
+      if (!shown.has(card.id)) {
+        shown.add(card.id);
+        cards.appendChild(streamCard(card));
+      }
+    }
+    cursor = page.cursor;
+    status.hidden = shown.size > 0;
+    status.textContent = 'No streams live';
+    moreButton.hidden = !cursor;
+  } catch (e) {
+    console.error('Failed to load streams:', e);
+    status.textContent = `Couldn't load streams: ${e}`;
+  } finally {
+    moreButton.disabled = false;
+  }
+}
+
+async function init() {
+  header.textContent = await getCurrentWindow().title();
+  moreButton.addEventListener('click', loadMore);
+  await loadMore();
+}
+
+init();
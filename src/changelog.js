@@ -0,0 +1,35 @@
+// "What's New" window JavaScript: renders the changelog parsed by changelog.rs
+const { invoke } = window.__TAURI__.core;
+
+const changelogDiv = document.getElementById('changelog');
+
+function renderChangelog(releases) {
+  changelogDiv.innerHTML = '';
+  for (const release of releases) {
+    const section = document.createElement('section');
+    section.className = 'changelog-release';
+
+    const heading = document.createElement('h3');
+    heading.textContent = release.version;
+    section.appendChild(heading);
+
+    const list = document.createElement('ul');
+    for (const change of release.changes) {
+      const item = document.createElement('li');
+      item.textContent = change;
+      list.appendChild(item);
+    }
+    section.appendChild(list);
+    changelogDiv.appendChild(section);
+  }
+}
+
+async function init() {
+  try {
+    renderChangelog(await invoke('get_changelog'));
+  } catch (e) {
+    console.error('Failed to load changelog:', e);
+  }
+}
+
+init();
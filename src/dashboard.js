@@ -0,0 +1,217 @@
+// Dashboard window JavaScript: renders the MenuData built by dashboard.rs
+const { invoke } = window.__TAURI__.core;
+const { listen } = window.__TAURI__.event;
+
+const loginNotice = document.getElementById('dashboard_login');
+const content = document.getElementById('dashboard_content');
+const pinnedSection = document.getElementById('pinned_section');
+const pinnedCards = document.getElementById('pinned_cards');
+const liveHeader = document.getElementById('live_header');
+const liveCards = document.getElementById('live_cards');
+const categorySections = document.getElementById('category_sections');
+const scheduleHeader = document.getElementById('schedule_header');
+const scheduleList = document.getElementById('schedule_list');
+const pinnedInput = document.getElementById('dashboard_pinned');
+
+// Clicks use the menu's item IDs, so modifiers act as in the menu window
+// (Ctrl copies the link, Shift opens chat, Alt opens streamer settings)
+async function activate(id, event) {
+  const modifiers = {
+    ctrl: event.ctrlKey || event.metaKey,
+    shift: event.shiftKey,
+    alt: event.altKey,
+  };
+  try {
+    await invoke('activate_menu_row', { id, modifiers });
+  } catch (e) {
+    console.error('Failed to open stream:', e);
+  }
+}
+
+function image(src, className) {
+  const img = document.createElement('img');
+  img.className = className;
+  img.loading = 'lazy';
+  img.alt = '';
+  if (src) {
+    img.src = src;
+  }
+  return img;
+}
+
+function textDiv(text, className) {
+  const div = document.createElement('div');
+  div.className = className;
+  div.textContent = text;
+  return div;
+}
+
+// "Pin"/"Unpin" button; the click mustn't also open the stream underneath
+function pinButton(id) {
+  const button = document.createElement('button');
+  button.className = 'dashboard-pin';
+  button.textContent = id.startsWith('unpin_') ? 'Unpin' : 'Pin';
+  button.addEventListener('click', (event) => {
+    event.stopPropagation();
+    activate(id, event);
+  });
+  return button;
+}
+
+function streamCard(card) {
+  const div = document.createElement('div');
+  div.className = 'dashboard-card';
+  div.title = card.title;
+  // Prefer the saved copy, falling back to the CDN if it has since been pruned
+  const thumbnail = image(card.cached_thumbnail_url || card.thumbnail_url, 'dashboard-thumbnail');
+  if (card.cached_thumbnail_url) {
+    thumbnail.addEventListener('error', () => { thumbnail.src = card.thumbnail_url; }, { once: true });
+  }
+  div.appendChild(thumbnail);
+
+  const info = document.createElement('div');
+  info.className = 'dashboard-card-info';
+  info.appendChild(image(card.profile_image_url, 'dashboard-avatar'));
+  const text = document.createElement('div');
+  text.className = 'dashboard-card-text';
+  text.appendChild(textDiv(card.is_hot ? `🔥 ${card.user_name}` : card.user_name, 'dashboard-name'));
+  text.appendChild(textDiv(card.title, 'dashboard-title'));
+  text.appendChild(textDiv(
+    `${card.game_name} · ${card.viewer_count_formatted} viewers · ${card.duration_formatted}`,
+    'dashboard-meta',
+  ));
+  info.appendChild(text);
+  if (card.pin_id) {
+    info.appendChild(pinButton(card.pin_id));
+  }
+  div.appendChild(info);
+
+  div.addEventListener('click', (event) => activate(card.id, event));
+  return div;
+}
+
+// Live pinned channels get a full card, offline ones just their label
+function renderPinned(pinned) {
+  pinnedSection.hidden = pinned.length === 0;
+  pinnedCards.innerHTML = '';
+  for (const entry of pinned) {
+    if (entry.stream) {
+      pinnedCards.appendChild(streamCard(entry.stream));
+      continue;
+    }
+    const div = document.createElement('div');
+    div.className = 'dashboard-card';
+    const info = document.createElement('div');
+    info.className = 'dashboard-card-info';
+    const text = document.createElement('div');
+    text.className = 'dashboard-card-text';
+    text.appendChild(textDiv(entry.label, 'dashboard-name'));
+    info.appendChild(text);
+    info.appendChild(pinButton(entry.unpin_id));
+    div.appendChild(info);
+    div.addEventListener('click', (event) => activate(entry.id, event));
+    pinnedCards.appendChild(div);
+  }
+}
+
+function renderCards(container, cards, emptyText) {
+  container.innerHTML = '';
+  if (cards.length === 0) {
+    container.appendChild(textDiv(emptyText, 'empty-state'));
+    return;
+  }
+  for (const card of cards) {
+    container.appendChild(streamCard(card));
+  }
+}
+
+function renderCategories(categories) {
+  categorySections.innerHTML = '';
+  for (const category of categories) {
+    const section = document.createElement('section');
+    const header = document.createElement('h3');
+    header.className = 'dashboard-category-header';
+    header.appendChild(image(category.box_art_url, 'dashboard-box-art'));
+    header.appendChild(document.createTextNode(category.header));
+    section.appendChild(header);
+
+    const cards = document.createElement('div');
+    cards.className = 'dashboard-cards';
+    renderCards(cards, category.streams, 'No streams live');
+    section.appendChild(cards);
+    categorySections.appendChild(section);
+  }
+}
+
+function formatStart(startTime) {
+  return new Date(startTime).toLocaleString([], {
+    weekday: 'short',
+    hour: 'numeric',
+    minute: '2-digit',
+  });
+}
+
+function renderSchedule(data) {
+  scheduleHeader.textContent = data.schedule_header;
+  scheduleList.innerHTML = '';
+  if (data.schedule.length === 0) {
+    const li = document.createElement('li');
+    li.className = 'empty-state';
+    li.textContent = data.schedules_loaded ? 'No scheduled streams' : 'Loading...';
+    scheduleList.appendChild(li);
+    return;
+  }
+  for (const entry of data.schedule) {
+    const li = document.createElement('li');
+    li.className = 'dashboard-schedule-entry';
+    li.appendChild(image(entry.profile_image_url, 'dashboard-avatar'));
+
+    const text = document.createElement('div');
+    text.className = 'dashboard-card-text';
+    text.appendChild(textDiv(`${formatStart(entry.start_time)} · ${entry.broadcaster_name}`, 'dashboard-name'));
+    if (entry.is_inferred) {
+      const confidence = entry.confidence == null ? '' : ` (${Math.round(entry.confidence * 100)}% likely)`;
+      text.appendChild(textDiv(`Predicted from past streams${confidence}`, 'dashboard-meta'));
+    } else {
+      const details = [entry.title, entry.category].filter(Boolean).join(' · ');
+      text.appendChild(textDiv(details, 'dashboard-meta'));
+    }
+    li.appendChild(text);
+
+    li.addEventListener('click', (event) => activate(entry.id, event));
+    scheduleList.appendChild(li);
+  }
+}
+
+function render(data) {
+  loginNotice.hidden = data.authenticated;
+  content.hidden = !data.authenticated;
+  if (!data.authenticated) {
+    return;
+  }
+  renderPinned(data.pinned);
+  liveHeader.textContent = data.live_header;
+  renderCards(liveCards, data.live, 'No streams live');
+  renderCategories(data.categories);
+  renderSchedule(data);
+}
+
+pinnedInput.addEventListener('change', async () => {
+  try {
+    await invoke('set_dashboard_pinned', { pinned: pinnedInput.checked });
+  } catch (e) {
+    console.error('Failed to pin dashboard:', e);
+    pinnedInput.checked = !pinnedInput.checked;
+  }
+});
+
+async function init() {
+  await listen('menu-data-updated', (event) => render(event.payload));
+  try {
+    render(await invoke('get_menu_data'));
+  } catch (e) {
+    console.error('Failed to load dashboard:', e);
+  }
+}
+
+init();
@@ -0,0 +1,38 @@
+// Login window JavaScript: shows the device code while a login waits on it
+const { invoke } = window.__TAURI__.core;
+const { listen } = window.__TAURI__.event;
+
+const codeEl = document.getElementById('login-code');
+const urlEl = document.getElementById('login-url');
+const expiresEl = document.getElementById('login-expires');
+const copyButton = document.getElementById('copy-code');
+
+function renderLoginCode(state) {
+  // The app closes this window once the login ends
+  if (state.type !== 'AwaitingDeviceCode') return;
+  codeEl.textContent = state.code;
+  urlEl.textContent = state.url;
+  const expires = new Date(state.expires).toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' });
+  expiresEl.textContent = `The code expires at ${expires}; a new one replaces it.`;
+  copyButton.textContent = 'Copy code';
+}
+
+copyButton.addEventListener('click', async () => {
+  try {
+    await navigator.clipboard.writeText(codeEl.textContent);
+    copyButton.textContent = 'Copied';
+  } catch (e) {
+    console.error('Failed to copy code:', e);
+  }
+});
+
+async function init() {
+  try {
+    renderLoginCode(await invoke('get_auth_state'));
+  } catch (e) {
+    console.error('Failed to load auth state:', e);
+  }
+  listen('auth-state-changed', (event) => renderLoginCode(event.payload));
+}
+
+init();
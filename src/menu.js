@@ -0,0 +1,48 @@
+// Menu window JavaScript (fallback when there is no system tray)
+const { invoke } = window.__TAURI__.core;
+const { listen } = window.__TAURI__.event;
+
+const menuRowsList = document.getElementById('menu_rows');
+
+// Render the rows computed by menu_rows.rs
+function renderRows(rows) {
+  menuRowsList.innerHTML = '';
+  for (const row of rows) {
+    const li = document.createElement('li');
+    li.className = `menu-row menu-row-${row.kind}`;
+    li.textContent = row.label;
+    if (row.kind === 'item') {
+      li.addEventListener('click', (event) => activateRow(row, event));
+    }
+    menuRowsList.appendChild(li);
+  }
+}
+
+// Ctrl-click copies a stream's link, Shift-click opens its chat and
+// Alt-click its streamer settings (see menu_actions.rs)
+async function activateRow(row, event) {
+  if (row.confirm && !window.confirm(row.confirm)) {
+    return;
+  }
+  const modifiers = {
+    ctrl: event.ctrlKey || event.metaKey,
+    shift: event.shiftKey,
+    alt: event.altKey,
+  };
+  try {
+    await invoke('activate_menu_row', { id: row.id, modifiers });
+  } catch (e) {
+    console.error('Failed to activate menu row:', e);
+  }
+}
+
+async function init() {
+  await listen('menu-rows-updated', (event) => renderRows(event.payload));
+  try {
+    renderRows(await invoke('get_menu_rows'));
+  } catch (e) {
+    console.error('Failed to load menu:', e);
+  }
+}
+
+init();
@@ -0,0 +1,111 @@
+// Quick list window JavaScript: the live streams from dashboard.rs's MenuData,
+// opened by the global shortcut, with live pinned streams first. Arrow keys and Enter pick a stream, Escape closes.
+const { invoke } = window.__TAURI__.core;
+const { listen } = window.__TAURI__.event;
+const { getCurrentWindow } = window.__TAURI__.window;
+
+const liveHeader = document.getElementById('live_header');
+const liveList = document.getElementById('live_list');
+
+let streams = [];
+let selected = 0;
+
+// Same menu item IDs and modifiers as the dashboard, then get out of the way
+async function activate(id, event) {
+  const modifiers = {
+    ctrl: event.ctrlKey || event.metaKey,
+    shift: event.shiftKey,
+    alt: event.altKey,
+  };
+  try {
+    await invoke('activate_menu_row', { id, modifiers });
+    await getCurrentWindow().close();
+  } catch (e) {
+    console.error('Failed to open stream:', e);
+  }
+}
+
+function textDiv(text, className) {
+  const div = document.createElement('div');
+  div.className = className;
+  div.textContent = text;
+  return div;
+}
+
+function select(index) {
+  selected = index;
+  liveList.querySelectorAll('.quick-list-entry').forEach((li, i) => {
+    li.classList.toggle('selected', i === selected);
+    if (i === selected) {
+      li.scrollIntoView({ block: 'nearest' });
+    }
+  });
+}
+
+function render(data) {
+  liveList.innerHTML = '';
+  if (!data.authenticated) {
+    liveHeader.textContent = 'Twitch Tray';
+    streams = [];
+    liveList.appendChild(textDiv('Log in from the tray menu to see your followed streams.', 'empty-state'));
+    return;
+  }
+
+  liveHeader.textContent = data.live_header;
+  streams = data.pinned.filter((p) => p.stream).map((p) => p.stream).concat(data.live);
+  if (streams.length === 0) {
+    liveList.appendChild(textDiv('No streams live', 'empty-state'));
+    return;
+  }
+  streams.forEach((stream, i) => {
+    const li = document.createElement('li');
+    li.className = 'quick-list-entry';
+    li.title = stream.title;
+
+    const avatar = document.createElement('img');
+    avatar.className = 'dashboard-avatar';
+    avatar.alt = '';
+    if (stream.profile_image_url) {
+      avatar.src = stream.profile_image_url;
+    }
+    li.appendChild(avatar);
+
+    const text = document.createElement('div');
+    text.className = 'dashboard-card-text';
+    text.appendChild(textDiv(stream.is_hot ? `🔥 ${stream.user_name}` : stream.user_name, 'dashboard-name'));
+    text.appendChild(textDiv(`${stream.game_name} · ${stream.viewer_count_formatted} viewers`, 'dashboard-meta'));
+    li.appendChild(text);
+
+    li.addEventListener('click', (event) => activate(stream.id, event));
+    li.addEventListener('mouseenter', () => select(i));
+    liveList.appendChild(li);
+  });
+  select(Math.min(selected, streams.length - 1));
+}
+
+document.addEventListener('keydown', (event) => {
+  if (event.key === 'Escape') {
+    getCurrentWindow().close();
+  } else if (streams.length === 0) {
+    return;
+  } else if (event.key === 'ArrowDown') {
+    event.preventDefault();
+    select((selected + 1) % streams.length);
+  } else if (event.key === 'ArrowUp') {
+    event.preventDefault();
+    select((selected + streams.length - 1) % streams.length);
+  } else if (event.key === 'Enter') {
+    activate(streams[selected].id, event);
+  }
+});
+
+async function init() {
+  await listen('menu-data-updated', (event) => render(event.payload));
+  try {
+    render(await invoke('get_menu_data'));
+  } catch (e) {
+    console.error('Failed to load live streams:', e);
+  }
+}
+
+init();
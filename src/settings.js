@@ -1,5 +1,6 @@
 // Settings page JavaScript
 const { invoke } = window.__TAURI__.core;
+const { listen } = window.__TAURI__.event;
 const { getCurrentWindow } = window.__TAURI__.window;
 
 // Streamer mode detection
@@ -9,7 +10,8 @@ const streamerParam = urlParams.get('streamer');
 // State
 let config = null;
 let searchTimeout = null;
-let followedChannels = [];
+// Followed channels listed so far for the current streamer search
+let streamerResults = [];
 let selectedStreamer = null;
 let streamerSearchTimeout = null;
 
@@ -18,23 +20,79 @@ const tabs = document.querySelectorAll('.tab');
 const panes = document.querySelectorAll('.pane');
 const pollIntervalInput = document.getElementById('poll_interval');
 const notifyMaxGapInput = document.getElementById('notify_max_gap');
+const notifyRestartWindowInput = document.getElementById('notify_restart_window');
+const notifyBatchThresholdInput = document.getElementById('notify_batch_threshold');
 const scheduleLookaheadInput = document.getElementById('schedule_lookahead');
+const showInferredSchedulesInput = document.getElementById('show_inferred_schedules');
+const scheduleInferenceModelInput = document.getElementById('schedule_inference_model');
+const scheduleInferenceLookbackInput = document.getElementById('schedule_inference_lookback');
 const notifyOnLiveInput = document.getElementById('notify_on_live');
 const notifyOnCategoryInput = document.getElementById('notify_on_category');
 const notifyOnHotInput = document.getElementById('notify_on_hot');
+const notifySilentInFollowedCategoriesInput = document.getElementById('notify_silent_in_followed_categories');
+const hideFollowedInCategoriesInput = document.getElementById('hide_followed_in_categories');
+const notifyWatchStreakInput = document.getElementById('notify_watch_streak');
+const quietHoursEnabledInput = document.getElementById('quiet_hours_enabled');
+const quietHoursGroup = document.getElementById('quiet_hours_group');
+const quietHoursStartInput = document.getElementById('quiet_hours_start');
+const quietHoursEndInput = document.getElementById('quiet_hours_end');
+const quietHoursSummaryInput = document.getElementById('quiet_hours_summary');
+const titleAlertsInput = document.getElementById('title_alerts');
+const startLoggedOutInput = document.getElementById('start_logged_out');
+const tokenStorageInput = document.getElementById('token_storage');
+const trayIconLiveCountInput = document.getElementById('tray_icon_live_count');
+const iconThemeInput = document.getElementById('icon_theme');
+const globalShortcutInput = document.getElementById('global_shortcut');
+const globalShortcutActionInput = document.getElementById('global_shortcut_action');
+const startOnLoginInput = document.getElementById('start_on_login');
 const hotnessZThresholdInput = document.getElementById('hotness_z_threshold');
 const hotnessMinObservationsInput = document.getElementById('hotness_min_observations');
 const hotnessMinStreamsInput = document.getElementById('hotness_min_streams');
 const liveMenuLimitInput = document.getElementById('live_menu_limit');
 const scheduleMenuLimitInput = document.getElementById('schedule_menu_limit');
+const categoryMorePagesInput = document.getElementById('category_more_pages');
+const maxLabelWidthInput = document.getElementById('max_label_width');
+const liveLabelFormatInput = document.getElementById('live_label_format');
+const liveSortInput = document.getElementById('live_sort');
+const favouriteSortModeInput = document.getElementById('favourite_sort_mode');
+const notificationOpenModeInput = document.getElementById('notification_open_mode');
+const pipCommandInput = document.getElementById('pip_command');
+const pipCommandGroup = document.getElementById('pip_command_group');
+const openWithInput = document.getElementById('open_with');
+const openWithCommandInput = document.getElementById('open_with_command');
+const openWithCommandGroup = document.getElementById('open_with_command_group');
 const categorySearchInput = document.getElementById('category_search');
 const searchResultsDiv = document.getElementById('search_results');
 const categoryListDiv = document.getElementById('category_list');
 const streamerSearchInput = document.getElementById('streamer_search');
 const streamerSearchResultsDiv = document.getElementById('streamer_search_results');
+const streamerSortSelect = document.getElementById('streamer_sort');
+const bulkLoginsInput = document.getElementById('bulk_logins');
+const bulkImportanceSelect = document.getElementById('bulk_importance');
+const bulkImportanceStatus = document.getElementById('bulk_importance_status');
+const streamerListPathInput = document.getElementById('streamer_list_path');
 const streamerListDiv = document.getElementById('streamer_list');
 const streamerDetailDiv = document.getElementById('streamer_detail');
 const closeBtn = document.getElementById('close_btn');
+const diagnosticsBtn = document.getElementById('diagnostics_btn');
+const diagnosticsStatus = document.getElementById('diagnostics_status');
+const diagnosticsRefreshBtn = document.getElementById('diagnostics_refresh_btn');
+const openLogsBtn = document.getElementById('open_logs_btn');
+const logLevelInput = document.getElementById('log_level');
+const proxyUrlInput = document.getElementById('proxy_url');
+const proxyTestBtn = document.getElementById('proxy_test_btn');
+const proxyTestStatus = document.getElementById('proxy_test_status');
+const exportIncludeHistoryInput = document.getElementById('export_include_history');
+const exportBtn = document.getElementById('export_btn');
+const exportStatus = document.getElementById('export_status');
+const importPathInput = document.getElementById('import_path');
+const importBtn = document.getElementById('import_btn');
+const importStatus = document.getElementById('import_status');
+const categorySuggestionsDiv = document.getElementById('category_suggestions');
+const categorySuggestionListDiv = document.getElementById('category_suggestion_list');
+const historyAtInput = document.getElementById('history_at');
+const historyBtn = document.getElementById('history_btn');
+const authBanner = document.getElementById('auth_banner');
 
 // === Debug tab state ===
 const WEEK_SECS = 7 * 24 * 3600;
@@ -56,8 +114,9 @@ document.addEventListener('DOMContentLoaded', async () => {
   if (streamerParam) {
     enterStreamerMode(streamerParam);
   } else {
-    await loadFollowedChannels();
+    await loadAuthState();
     setupEventListeners();
+    loadCategorySuggestions();
 
     // Show debug tab in debug builds
     try {
@@ -83,29 +142,127 @@ async function loadConfig() {
   }
 }
 
-async function loadFollowedChannels() {
+// === Auth status ===
+
+let authState = { type: 'Unauthenticated' };
+
+async function loadAuthState() {
   try {
-    followedChannels = await invoke('get_followed_channels_list');
+    renderAuthState(await invoke('get_auth_state'));
   } catch (error) {
-    console.error('Failed to load followed channels:', error);
-    followedChannels = [];
+    console.error('Failed to load auth state:', error);
+  }
+
+  listen('auth-state-changed', async (event) => {
+    const wasAuthenticated = authState.type === 'Authenticated';
+    renderAuthState(event.payload);
+    // Followed channels only load once logged in
+    if (!wasAuthenticated && authState.type === 'Authenticated' && streamerSearchResultsDiv.classList.contains('visible')) {
+      await searchStreamers(streamerSearchInput.value.trim());
+    }
+  });
+}
+
+function renderAuthState(state) {
+  authState = state;
+  authBanner.className = 'auth-banner';
+
+  switch (state.type) {
+    case 'Authenticated':
+      if (state.renewal_failing) authBanner.classList.add('error');
+      authBanner.textContent = `Logged in as ${state.login}${formatTokenRenewal(state)}`;
+      break;
+    case 'AwaitingDeviceCode': {
+      const expires = new Date(state.expires).toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' });
+      authBanner.classList.add('awaiting');
+      authBanner.innerHTML = `Enter <code>${escapeHtml(state.code)}</code> at ${escapeHtml(state.url)} to log in (expires ${escapeHtml(expires)})`;
+      break;
+    }
+    case 'Error':
+      authBanner.classList.add('error');
+      authBanner.textContent = `Login failed: ${state.message}`;
+      break;
+    default:
+      authBanner.textContent = 'Not logged in. Log in from the tray menu to search categories and followed channels.';
+  }
+  authBanner.style.display = '';
+
+  const loggedIn = state.type === 'Authenticated';
+  document.querySelectorAll('[data-requires-auth]').forEach(el => {
+    el.classList.toggle('requires-login', !loggedIn);
+    el.querySelectorAll('input').forEach(input => { input.disabled = !loggedIn; });
+  });
+}
+
+// " · login renews in 2h 14m", or a warning while renewing keeps failing
+function formatTokenRenewal(state) {
+  const minsUntil = (time) => Math.max(0, Math.ceil((new Date(time) - Date.now()) / 60000));
+  if (state.renewal_failing && state.expires) {
+    return ` · couldn't renew login (expires in ${formatDurationMins(minsUntil(state.expires))}). Log in again from the tray menu.`;
   }
+  if (state.renews) {
+    return ` · login renews in ${formatDurationMins(minsUntil(state.renews))}`;
+  }
+  return '';
 }
 
+// Keep the renewal countdown current
+setInterval(() => {
+  if (authState.type === 'Authenticated') renderAuthState(authState);
+}, 30000);
+
 function populateForm() {
   if (!config) return;
 
   pollIntervalInput.value = config.poll_interval_sec;
   notifyMaxGapInput.value = config.notify_max_gap_min;
+  notifyRestartWindowInput.value = config.notify_restart_window_min;
+  notifyBatchThresholdInput.value = config.notify_batch_threshold;
   notifyOnLiveInput.checked = config.notify_on_live;
   notifyOnCategoryInput.checked = config.notify_on_category;
   notifyOnHotInput.checked = config.notify_on_hot;
+  notifySilentInFollowedCategoriesInput.checked = config.notify_silent_in_followed_categories;
+  hideFollowedInCategoriesInput.checked = config.hide_followed_in_categories;
+  notifyWatchStreakInput.checked = config.notify_watch_streak;
+  quietHoursEnabledInput.checked = config.quiet_hours != null;
+  if (config.quiet_hours) {
+    quietHoursStartInput.value = config.quiet_hours.start;
+    quietHoursEndInput.value = config.quiet_hours.end;
+    quietHoursSummaryInput.checked = config.quiet_hours.summary;
+  }
+  updateQuietHoursVisibility();
+  titleAlertsInput.value = (config.title_alerts || []).join('\n');
+  startLoggedOutInput.checked = config.start_logged_out;
+  tokenStorageInput.value = config.token_storage || 'auto';
+  logLevelInput.value = config.log_level || 'info';
+  proxyUrlInput.value = config.proxy_url || '';
+  loadEnvProxy();
+  trayIconLiveCountInput.checked = config.tray_icon_live_count;
+  iconThemeInput.value = config.icon_theme || 'auto';
+  globalShortcutInput.value = config.global_shortcut || '';
+  globalShortcutActionInput.value = config.global_shortcut_action || 'dashboard';
+  loadAutostart();
   hotnessZThresholdInput.value = config.hotness_z_threshold;
   hotnessMinObservationsInput.value = config.hotness_min_observations;
   hotnessMinStreamsInput.value = config.hotness_min_streams;
   scheduleLookaheadInput.value = config.schedule_lookahead_hours;
+  showInferredSchedulesInput.checked = config.show_inferred_schedules;
+  scheduleInferenceModelInput.value = config.schedule_inference_model || 'day_of_week';
+  scheduleInferenceLookbackInput.value = config.schedule_inference_lookback_weeks;
   liveMenuLimitInput.value = config.live_menu_limit;
   scheduleMenuLimitInput.value = config.schedule_menu_limit;
+  categoryMorePagesInput.value = config.category_more_pages;
+  maxLabelWidthInput.value = config.max_label_width;
+  liveLabelFormatInput.value = config.live_label_format || '';
+  liveSortInput.value = config.live_sort || 'viewers';
+  favouriteSortModeInput.value = config.favourite_sort_mode || 'viewers';
+  notificationOpenModeInput.value = config.notification_open_mode || 'channel';
+  pipCommandInput.value = config.pip_command || '';
+  updatePipCommandVisibility();
+  const openWith = config.open_with || 'browser';
+  openWithInput.value = openWith.command != null ? 'command' : openWith;
+  openWithCommandInput.value = openWith.command || '';
+  updateOpenWithCommandVisibility();
 
   renderCategoryList();
   renderStreamerList();
@@ -122,14 +279,207 @@ function renderCategoryList() {
     return;
   }
 
-  categoryListDiv.innerHTML = config.followed_categories.map(cat => `
+  categoryListDiv.innerHTML = config.followed_categories.map(cat => {
+    const prefs = cat.notifications || {};
+    const filters = cat.filters || {};
+    const enabled = prefs.enabled === true ? 'on' : prefs.enabled === false ? 'off' : '';
+    return `
     <div class="category-item" data-id="${cat.id}">
       <span class="category-name">${escapeHtml(cat.name)}</span>
+      <span class="category-notifications">
+        <select title="Notifications for followed streamers in this category"
+                onchange="updateCategoryNotifications('${cat.id}', 'enabled', this.value)">
+          <option value="" ${enabled === '' ? 'selected' : ''}>Notify: default</option>
+          <option value="on" ${enabled === 'on' ? 'selected' : ''}>Notify: always (incl. Silent)</option>
+          <option value="off" ${enabled === 'off' ? 'selected' : ''}>Notify: never</option>
+        </select>
+        <select title="Notification urgency"
+                onchange="updateCategoryNotifications('${cat.id}', 'urgency', this.value)">
+          <option value="" ${!prefs.urgency ? 'selected' : ''}>Urgency: default</option>
+          <option value="low" ${prefs.urgency === 'low' ? 'selected' : ''}>Low</option>
+          <option value="normal" ${prefs.urgency === 'normal' ? 'selected' : ''}>Normal</option>
+          <option value="critical" ${prefs.urgency === 'critical' ? 'selected' : ''}>Critical</option>
+        </select>
+        <input type="text" placeholder="Sound name" value="${escapeHtml(prefs.sound || '')}"
+               title="Sound theme name, e.g. message-new-instant (macOS: Glass)"
+               onchange="updateCategoryNotifications('${cat.id}', 'sound', this.value)">
+        <input type="number" min="1" placeholder="Small streams" value="${prefs.small_stream_max_viewers ?? ''}"
+               title="Notify when anyone starts streaming this with fewer viewers than this"
+               onchange="updateCategoryNotifications('${cat.id}', 'small_stream_max_viewers', this.value)">
+        <input type="number" min="1" max="10" placeholder="Top streams" value="${prefs.top_streams ?? ''}"
+               title="Notify when a stream enters this many of the most watched here (up to 10)"
+               onchange="updateCategoryNotifications('${cat.id}', 'top_streams', this.value)">
+        <label title="Notify when a Favourite starts streaming this">
+          <input type="checkbox" ${prefs.favourites ? 'checked' : ''}
+                 onchange="updateCategoryNotifications('${cat.id}', 'favourites', this.checked)">
+          Favourites
+        </label>
+      </span>
       <button class="category-remove" onclick="removeCategory('${cat.id}')">Remove</button>
+      <span class="category-filters">
+        <input type="text" placeholder="Languages" value="${escapeHtml((filters.languages || []).join(', '))}"
+               title="Only list streams in these languages, e.g. en, de (empty: your system language)"
+               onchange="updateCategoryFilters('${cat.id}', 'languages', this.value)">
+        <input type="text" placeholder="Required tags" value="${escapeHtml((filters.required_tags || []).join(', '))}"
+               title="Only list streams with all of these tags, e.g. Speedrun"
+               onchange="updateCategoryFilters('${cat.id}', 'required_tags', this.value)">
+        <input type="number" min="0" placeholder="Min viewers" value="${filters.min_viewers ?? ''}"
+               onchange="updateCategoryFilters('${cat.id}', 'min_viewers', this.value)">
+        <input type="number" min="0" placeholder="Max viewers" value="${filters.max_viewers ?? ''}"
+               onchange="updateCategoryFilters('${cat.id}', 'max_viewers', this.value)">
+      </span>
+    </div>
+  `;
+  }).join('');
+}
+
+// Shows the OS registration rather than the config flag, in case the entry was
+// removed outside the app
+async function loadAutostart() {
+  try {
+    startOnLoginInput.checked = await invoke('get_autostart');
+  } catch (error) {
+    console.error('Failed to load start on login:', error);
+  }
+}
+
+// Shows the environment's proxy, which applies while the field is empty
+async function loadEnvProxy() {
+  try {
+    const envProxy = await invoke('get_env_proxy');
+    if (envProxy) proxyUrlInput.placeholder = `${envProxy} (from environment)`;
+  } catch (error) {
+    console.error('Failed to read proxy environment:', error);
+  }
+}
+
+async function testProxyConnection() {
+  proxyTestBtn.disabled = true;
+  proxyTestStatus.textContent = 'Connecting to Twitch...';
+  try {
+    const result = await invoke('test_proxy_connection', { proxyUrl: proxyUrlInput.value });
+    const via = result.proxy ? ` through ${result.proxy}` : ' directly';
+    proxyTestStatus.textContent = `Reached Twitch${via} in ${result.elapsed_ms} ms`;
+  } catch (error) {
+    console.error('Proxy connection test failed:', error);
+    proxyTestStatus.textContent = `Connection failed: ${error}`;
+  } finally {
+    proxyTestBtn.disabled = false;
+  }
+}
+
+async function updateAutostart(enabled) {
+  try {
+    await invoke('set_autostart', { enabled });
+    config.start_on_login = enabled;
+  } catch (error) {
+    console.error('Failed to change start on login:', error);
+    startOnLoginInput.checked = !enabled;
+  }
+}
+
+// Saved through its own command: save_config would re-fetch category streams
+async function updateCategoryNotifications(id, field, value) {
+  const cat = config.followed_categories.find(c => c.id === id);
+  if (!cat) return;
+  const prefs = {
+    enabled: null, sound: null, urgency: null, small_stream_max_viewers: null,
+    top_streams: null, favourites: false, ...cat.notifications,
+  };
+  if (field === 'enabled') {
+    prefs.enabled = value === 'on' ? true : value === 'off' ? false : null;
+  } else if (field === 'small_stream_max_viewers') {
+    const parsed = parseInt(value, 10);
+    prefs.small_stream_max_viewers = Number.isNaN(parsed) || parsed <= 0 ? null : parsed;
+  } else if (field === 'top_streams') {
+    const parsed = parseInt(value, 10);
+    prefs.top_streams = Number.isNaN(parsed) || parsed <= 0 ? null : Math.min(parsed, 10);
+  } else if (field === 'favourites') {
+    prefs.favourites = value;
+  } else {
+    prefs[field] = value.trim() || null;
+  }
+  cat.notifications = prefs;
+  try {
+    await invoke('set_category_notifications', { categoryId: id, notifications: prefs });
+  } catch (error) {
+    console.error('Failed to save category notifications:', error);
+  }
+}
+
+// Saved with the whole config so the category's streams are re-fetched
+function updateCategoryFilters(id, field, value) {
+  const cat = config.followed_categories.find(c => c.id === id);
+  if (!cat) return;
+  const filters = { languages: [], required_tags: [], min_viewers: null, max_viewers: null, ...cat.filters };
+  if (field === 'min_viewers' || field === 'max_viewers') {
+    const parsed = parseInt(value, 10);
+    filters[field] = Number.isNaN(parsed) || parsed < 0 ? null : parsed;
+  } else {
+    filters[field] = value.split(',').map(item => item.trim()).filter(item => item);
+  }
+  cat.filters = filters;
+  autoSave();
+}
+
+// === Category Suggestions ===
+
+async function loadCategorySuggestions() {
+  try {
+    renderCategorySuggestions(await invoke('get_followed_category_suggestions'));
+  } catch (error) {
+    console.error('Failed to load category suggestions:', error);
+  }
+}
+
+function renderCategorySuggestions(suggestions) {
+  if (suggestions.length === 0) {
+    categorySuggestionsDiv.style.display = 'none';
+    return;
+  }
+
+  categorySuggestionsDiv.style.display = '';
+  categorySuggestionListDiv.innerHTML = suggestions.map(s => `
+    <div class="category-item" data-id="${s.id}">
+      <span class="category-name">${escapeHtml(s.name)}
+        <span class="category-watched">${s.watched_streams} streams watched</span>
+      </span>
+      <span>
+        <button class="category-accept" onclick="acceptSuggestion('${s.id}', '${escapeHtml(s.name)}')">Follow</button>
+        <button class="category-remove" onclick="dismissSuggestion('${s.id}')">Dismiss</button>
+      </span>
     </div>
   `).join('');
 }
 
+// The backend updates the config itself, so pull back the fields it touched
+async function syncSuggestionFields() {
+  const current = await invoke('get_config');
+  config.followed_categories = current.followed_categories;
+  config.dismissed_category_suggestions = current.dismissed_category_suggestions;
+}
+
+async function acceptSuggestion(id, name) {
+  try {
+    await invoke('accept_category_suggestion', { category: { id, name } });
+    await syncSuggestionFields();
+    renderCategoryList();
+  } catch (error) {
+    console.error('Failed to follow suggested category:', error);
+  }
+  await loadCategorySuggestions();
+}
+
+async function dismissSuggestion(id) {
+  try {
+    await invoke('dismiss_category_suggestion', { categoryId: id });
+    await syncSuggestionFields();
+  } catch (error) {
+    console.error('Failed to dismiss category suggestion:', error);
+  }
+  await loadCategorySuggestions();
+}
+
 // === Streamer Settings ===
 
 function importanceIcon(importance) {
@@ -182,7 +532,27 @@ function renderStreamerDetailInto(container) {
   };
 
   const overrideValue = s.hotness_z_threshold_override != null ? s.hotness_z_threshold_override : '';
+  const openModeLabels = {
+    '': 'Global setting',
+    channel: 'Channel page',
+    popout: 'Popout player',
+    command: 'Player command',
+  };
+  const openMode = s.notification_open_mode || '';
+  const sound = s.notification_sound;
+  const soundMode = sound === 'silent' ? 'silent' : (sound && sound.custom != null ? 'custom' : '');
+  const customSound = soundMode === 'custom' ? sound.custom : '';
+  const openWith = s.open_with;
+  const openWithMode = openWith && openWith.command != null ? 'command' : (openWith || '');
+  const openWithCommand = openWithMode === 'command' ? openWith.command : '';
+  const openWithLabels = {
+    '': 'Global setting',
+    browser: 'Browser',
+    streamlink: 'Streamlink',
+    command: 'Custom command',
+  };
   const globalThreshold = config.hotness_z_threshold || 2.0;
+  const reminderValue = s.schedule_reminder_min != null ? s.schedule_reminder_min : '';
 
   container.innerHTML = `
     <div class="detail-header">${importanceIcon(s.importance)}${escapeHtml(s.display_name)}</div>
@@ -201,6 +571,52 @@ function renderStreamerDetailInto(container) {
         onchange="updateStreamerHotnessOverride(this.value)">
       <span class="help-text">Leave empty to use the global threshold. Lower = more sensitive.</span>
     </div>
+    <div class="detail-field" style="margin-top: 16px;">
+      <label for="streamer_open_mode">Notifications Open</label>
+      <select id="streamer_open_mode" onchange="updateStreamerOpenMode(this.value)">
+        ${Object.entries(openModeLabels).map(([value, label]) => `
+          <option value="${value}" ${openMode === value ? 'selected' : ''}>${label}</option>
+        `).join('')}
+      </select>
+    </div>
+    <div class="detail-field" style="margin-top: 16px;">
+      <label for="streamer_sound_mode">Notification Sound</label>
+      <select id="streamer_sound_mode" onchange="updateStreamerSound()">
+        <option value="" ${soundMode === '' ? 'selected' : ''}>Default</option>
+        <option value="silent" ${soundMode === 'silent' ? 'selected' : ''}>Silent</option>
+        <option value="custom" ${soundMode === 'custom' ? 'selected' : ''}>Custom</option>
+      </select>
+      <input type="text" id="streamer_sound" value="${escapeHtml(customSound)}"
+        placeholder="Sound name or file path" style="${soundMode === 'custom' ? '' : 'display: none;'}"
+        onchange="updateStreamerSound()">
+      <span class="help-text">A sound name (e.g. message-new-instant, Glass) or the path of a sound file.</span>
+    </div>
+    <div class="detail-field" style="margin-top: 16px;">
+      <label for="streamer_open_with">Open Stream With</label>
+      <select id="streamer_open_with" onchange="updateStreamerOpenWith()">
+        ${Object.entries(openWithLabels).map(([value, label]) => `
+          <option value="${value}" ${openWithMode === value ? 'selected' : ''}>${label}</option>
+        `).join('')}
+      </select>
+      <input type="text" id="streamer_open_with_command" value="${escapeHtml(openWithCommand)}"
+        placeholder="mpv {url}" style="${openWithMode === 'command' ? '' : 'display: none;'}"
+        onchange="updateStreamerOpenWith()">
+    </div>
+    <div class="detail-field" style="margin-top: 16px;">
+      <label for="streamer_schedule_reminder">Schedule Reminder (minutes before)</label>
+      <input type="number" id="streamer_schedule_reminder" min="1" max="240"
+        value="${reminderValue}" placeholder="Off"
+        onchange="updateStreamerScheduleReminder(this.value)">
+      <span class="help-text">Notifies this long before a scheduled or predicted stream. Leave empty for no reminder.</span>
+    </div>
+    <div class="detail-field checkbox" style="margin-top: 16px;">
+      <label>
+        <input type="checkbox" id="streamer_notify_on_category_change"
+          ${s.notify_on_category_change !== false ? 'checked' : ''}
+          onchange="updateStreamerCategoryChange(this.checked)">
+        Notify on category changes
+      </label>
+    </div>
   `;
   return true;
 }
@@ -311,29 +727,124 @@ function updateStreamerHotnessOverride(value) {
   autoSave();
 }
 
-function searchStreamers(query) {
-  const lowerQuery = query.toLowerCase();
-  const configuredLogins = new Set(Object.keys(config?.streamer_settings || {}));
+function updateStreamerOpenMode(value) {
+  if (!selectedStreamer || !config.streamer_settings[selectedStreamer]) return;
+  config.streamer_settings[selectedStreamer].notification_open_mode = value || null;
+  if (!streamerParam) updatePipCommandVisibility();
+  autoSave();
+}
 
-  const results = followedChannels.filter(ch => {
-    if (configuredLogins.has(ch.broadcaster_login)) return false;
-    return ch.broadcaster_name.toLowerCase().includes(lowerQuery) ||
-           ch.broadcaster_login.toLowerCase().includes(lowerQuery);
-  });
+function updateStreamerSound() {
+  if (!selectedStreamer || !config.streamer_settings[selectedStreamer]) return;
+  const mode = document.getElementById('streamer_sound_mode').value;
+  const input = document.getElementById('streamer_sound');
+  input.style.display = mode === 'custom' ? '' : 'none';
+  let sound = null;
+  if (mode === 'silent') {
+    sound = 'silent';
+  } else if (mode === 'custom') {
+    sound = { custom: input.value.trim() };
+  }
+  config.streamer_settings[selectedStreamer].notification_sound = sound;
+  autoSave();
+}
+
+function updateStreamerOpenWith() {
+  if (!selectedStreamer || !config.streamer_settings[selectedStreamer]) return;
+  const mode = document.getElementById('streamer_open_with').value;
+  const input = document.getElementById('streamer_open_with_command');
+  input.style.display = mode === 'command' ? '' : 'none';
+  config.streamer_settings[selectedStreamer].open_with = mode ? openWithValue(mode, input.value) : null;
+  autoSave();
+}
+
+function updateStreamerScheduleReminder(value) {
+  if (!selectedStreamer || !config.streamer_settings[selectedStreamer]) return;
+  const parsed = parseInt(value, 10);
+  config.streamer_settings[selectedStreamer].schedule_reminder_min =
+    Number.isNaN(parsed) || parsed <= 0 ? null : Math.min(240, parsed);
+  autoSave();
+}
+
+function updateStreamerCategoryChange(checked) {
+  if (!selectedStreamer || !config.streamer_settings[selectedStreamer]) return;
+  config.streamer_settings[selectedStreamer].notify_on_category_change = checked;
+  autoSave();
+}
+
+// Saved in one go by the backend, which also names new entries
+async function applyBulkImportance() {
+  const logins = bulkLoginsInput.value.split(/[\s,]+/).filter(Boolean);
+  if (logins.length === 0) return;
+  try {
+    const changed = await invoke('set_importance_bulk', { logins, importance: bulkImportanceSelect.value });
+    config.streamer_settings = (await invoke('get_config')).streamer_settings;
+    renderStreamerList();
+    bulkImportanceStatus.textContent = `Updated ${changed} streamer${changed === 1 ? '' : 's'}`;
+    bulkLoginsInput.value = '';
+  } catch (error) {
+    console.error('Failed to set importance:', error);
+    bulkImportanceStatus.textContent = 'Failed to save';
+  }
+}
+
+// Channels are looked up on Twitch, so names that aren't channels are listed back
+async function importStreamerList() {
+  const path = streamerListPathInput.value.trim();
+  if (!path) return;
+  const format = path.toLowerCase().endsWith('.json') ? 'json' : 'csv';
+  try {
+    const result = await invoke('import_streamer_list', { path, format, importance: bulkImportanceSelect.value });
+    config.streamer_settings = (await invoke('get_config')).streamer_settings;
+    renderStreamerList();
+    bulkImportanceStatus.textContent = `Updated ${result.changed} streamer${result.changed === 1 ? '' : 's'}` +
+      (result.unresolved.length ? `; not found: ${result.unresolved.join(', ')}` : '');
+    streamerListPathInput.value = '';
+  } catch (error) {
+    console.error('Failed to import streamer list:', error);
+    bulkImportanceStatus.textContent = `Failed to import: ${error}`;
+  }
+}
 
-  if (results.length === 0) {
+const STREAMER_PAGE_SIZE = 20;
+
+// Filtered and sorted by the backend, a page at a time. `offset` > 0 appends
+// the next page to the results shown.
+async function searchStreamers(query, offset = 0) {
+  let page;
+  try {
+    page = await invoke('get_followed_channels_list', {
+      query: { search: query, sort: streamerSortSelect.value, offset, limit: STREAMER_PAGE_SIZE },
+    });
+  } catch (error) {
+    console.error('Failed to list followed channels:', error);
+    streamerSearchResultsDiv.innerHTML = '<div class="search-result-item">Search failed</div>';
+    streamerSearchResultsDiv.classList.add('visible');
+    return;
+  }
+  // A newer search started while this one was waiting
+  if (query !== streamerSearchInput.value.trim()) return;
+
+  streamerResults = offset === 0 ? page.channels : streamerResults.concat(page.channels);
+  if (streamerResults.length === 0) {
     streamerSearchResultsDiv.innerHTML = '<div class="search-result-item">No results found</div>';
     streamerSearchResultsDiv.classList.add('visible');
     return;
   }
 
-  // Limit to top 10
-  const shown = results.slice(0, 10);
-  streamerSearchResultsDiv.innerHTML = shown.map(ch => `
-    <div class="search-result-item" onclick="addStreamer('${escapeHtml(ch.broadcaster_login)}', '${escapeHtml(ch.broadcaster_name)}')">
-      ${escapeHtml(ch.broadcaster_name)}
+  const remaining = page.total - streamerResults.length;
+  streamerSearchResultsDiv.innerHTML = streamerResults.map(ch => `
+    <div class="search-result-item streamer-result" onclick="addStreamer('${escapeHtml(ch.broadcaster_login)}', '${escapeHtml(ch.broadcaster_name)}')">
+      ${ch.profile_image_url ? `<img class="streamer-result-avatar" src="${escapeHtml(ch.profile_image_url)}" alt="">` : '<span class="streamer-result-avatar"></span>'}
+      <span class="streamer-result-name">${importanceIcon(ch.configured ? ch.importance : 'normal')}${escapeHtml(ch.broadcaster_name)}</span>
+      ${ch.viewer_count != null ? `<span class="streamer-result-live">\u25CF ${ch.viewer_count.toLocaleString()}</span>` : ''}
     </div>
-  `).join('');
+  `).join('') + (remaining > 0 ? `
+    <div class="search-result-item streamer-result-more"
+         onclick="event.stopPropagation(); searchStreamers(streamerSearchInput.value.trim(), ${streamerResults.length})">
+      Show ${Math.min(remaining, STREAMER_PAGE_SIZE)} more of ${remaining}
+    </div>
+  ` : '');
   streamerSearchResultsDiv.classList.add('visible');
 }
 
@@ -349,6 +860,19 @@ function setupEventListeners() {
       tab.classList.add('active');
       document.getElementById(targetId).classList.add('active');
 
+      // Default the history query to 9pm last night
+      if (targetId === 'history' && !historyAtInput.value) {
+        const lastNight = new Date();
+        lastNight.setDate(lastNight.getDate() - 1);
+        lastNight.setHours(21, 0, 0, 0);
+        historyAtInput.value = toDateTimeLocal(lastNight);
+        await Promise.all([loadLiveAt(), loadHistorySummary()]);
+      }
+
+      if (targetId === 'diagnostics') {
+        await loadDiagnostics();
+      }
+
       // Load initial debug data on first open, then scroll to now
       if (targetId === 'debug' && !debugDataLoaded) {
         debugDataLoaded = true;
@@ -359,6 +883,12 @@ function setupEventListeners() {
     });
   });
 
+  historyBtn.addEventListener('click', loadLiveAt);
+  diagnosticsRefreshBtn.addEventListener('click', loadDiagnostics);
+  historyAtInput.addEventListener('keydown', (e) => {
+    if (e.key === 'Enter') loadLiveAt();
+  });
+
   // Category search with debounce
   categorySearchInput.addEventListener('input', (e) => {
     const query = e.target.value.trim();
@@ -375,7 +905,7 @@ function setupEventListeners() {
     searchTimeout = setTimeout(() => searchCategories(query), 300);
   });
 
-  // Streamer search with debounce (client-side filtering)
+  // Streamer search with debounce; an empty search lists every follow
   streamerSearchInput.addEventListener('input', (e) => {
     const query = e.target.value.trim();
 
@@ -383,13 +913,16 @@ function setupEventListeners() {
       clearTimeout(streamerSearchTimeout);
     }
 
-    if (query.length < 2) {
-      streamerSearchResultsDiv.classList.remove('visible');
-      return;
-    }
-
     streamerSearchTimeout = setTimeout(() => searchStreamers(query), 150);
   });
+  streamerSearchInput.addEventListener('focus', () => {
+    searchStreamers(streamerSearchInput.value.trim());
+  });
+  streamerSortSelect.addEventListener('change', () => {
+    searchStreamers(streamerSearchInput.value.trim());
+  });
+  document.getElementById('bulk_importance_apply').addEventListener('click', applyBulkImportance);
+  document.getElementById('streamer_list_import').addEventListener('click', importStreamerList);
 
   // Close search results when clicking outside
   document.addEventListener('click', (e) => {
@@ -400,12 +933,116 @@ function setupEventListeners() {
   });
 
   // Auto-save on general settings changes
-  [pollIntervalInput, notifyMaxGapInput, scheduleLookaheadInput, liveMenuLimitInput, scheduleMenuLimitInput, hotnessZThresholdInput, hotnessMinObservationsInput, hotnessMinStreamsInput].forEach(input => {
+  [pollIntervalInput, notifyMaxGapInput, notifyRestartWindowInput, notifyBatchThresholdInput, scheduleLookaheadInput, scheduleInferenceLookbackInput, liveMenuLimitInput, scheduleMenuLimitInput, categoryMorePagesInput, maxLabelWidthInput, liveLabelFormatInput, hotnessZThresholdInput, hotnessMinObservationsInput, hotnessMinStreamsInput, titleAlertsInput, pipCommandInput, openWithCommandInput, globalShortcutInput, proxyUrlInput].forEach(input => {
     input.addEventListener('change', () => autoSave());
   });
-  [notifyOnLiveInput, notifyOnCategoryInput, notifyOnHotInput].forEach(input => {
+  [notifyOnLiveInput, notifyOnCategoryInput, notifyOnHotInput, notifySilentInFollowedCategoriesInput, hideFollowedInCategoriesInput, notifyWatchStreakInput, quietHoursStartInput, quietHoursEndInput, quietHoursSummaryInput, startLoggedOutInput, tokenStorageInput, logLevelInput, trayIconLiveCountInput, iconThemeInput, globalShortcutActionInput, showInferredSchedulesInput, scheduleInferenceModelInput, liveSortInput, favouriteSortModeInput].forEach(input => {
     input.addEventListener('change', () => autoSave());
   });
+  notificationOpenModeInput.addEventListener('change', () => {
+    updatePipCommandVisibility();
+    autoSave();
+  });
+  quietHoursEnabledInput.addEventListener('change', () => {
+    updateQuietHoursVisibility();
+    autoSave();
+  });
+  openWithInput.addEventListener('change', () => {
+    updateOpenWithCommandVisibility();
+    autoSave();
+  });
+  startOnLoginInput.addEventListener('change', () => updateAutostart(startOnLoginInput.checked));
+  diagnosticsBtn.addEventListener('click', () => createDiagnosticsBundle());
+  proxyTestBtn.addEventListener('click', () => testProxyConnection());
+  openLogsBtn.addEventListener('click', async () => {
+    try {
+      await invoke('open_log_dir');
+    } catch (error) {
+      console.error('Failed to open logs folder:', error);
+      diagnosticsStatus.textContent = `Failed to open logs folder: ${error}`;
+    }
+  });
+  exportBtn.addEventListener('click', () => exportData());
+  importBtn.addEventListener('click', () => importData());
+}
+
+// The command only matters when it is chosen globally or for some streamer
+function updatePipCommandVisibility() {
+  const usedByStreamer = Object.values(config?.streamer_settings || {})
+    .some(s => s.notification_open_mode === 'command');
+  const visible = notificationOpenModeInput.value === 'command' || usedByStreamer;
+  pipCommandGroup.style.display = visible ? '' : 'none';
+}
+
+function updateOpenWithCommandVisibility() {
+  openWithCommandGroup.style.display = openWithInput.value === 'command' ? '' : 'none';
+}
+
+function updateQuietHoursVisibility() {
+  quietHoursGroup.style.display = quietHoursEnabledInput.checked ? '' : 'none';
+}
+
+// `quiet_hours` is null when off; a cleared time input keeps quiet hours off
+function quietHoursValue() {
+  const start = quietHoursStartInput.value;
+  const end = quietHoursEndInput.value;
+  if (!quietHoursEnabledInput.checked || !start || !end) return null;
+  return { start, end, summary: quietHoursSummaryInput.checked };
+}
+
+// `open_with` is "browser", "streamlink" or {command: "..."}
+function openWithValue(mode, command) {
+  return mode === 'command' ? { command: command.trim() } : mode;
+}
+
+async function createDiagnosticsBundle() {
+  diagnosticsBtn.disabled = true;
+  try {
+    const path = await invoke('create_diagnostics_bundle');
+    try {
+      await navigator.clipboard.writeText(path);
+      diagnosticsStatus.textContent = `Saved to ${path} (path copied)`;
+    } catch (error) {
+      diagnosticsStatus.textContent = `Saved to ${path}`;
+    }
+  } catch (error) {
+    console.error('Failed to create diagnostics bundle:', error);
+    diagnosticsStatus.textContent = `Failed to create bundle: ${error}`;
+  } finally {
+    diagnosticsBtn.disabled = false;
+  }
+}
+
+async function exportData() {
+  exportBtn.disabled = true;
+  try {
+    const path = await invoke('export_data', { path: null, includeHistory: exportIncludeHistoryInput.checked });
+    exportStatus.textContent = `Saved to ${path}`;
+  } catch (error) {
+    console.error('Failed to export settings:', error);
+    exportStatus.textContent = `Failed to export: ${error}`;
+  } finally {
+    exportBtn.disabled = false;
+  }
+}
+
+async function importData() {
+  const path = importPathInput.value.trim();
+  if (!path) return;
+
+  importBtn.disabled = true;
+  try {
+    const manifest = await invoke('import_data', { path });
+    importStatus.textContent = manifest.includes_history
+      ? `Imported settings and history from ${manifest.exported_at.slice(0, 10)}`
+      : `Imported settings from ${manifest.exported_at.slice(0, 10)}`;
+    await loadConfig();
+  } catch (error) {
+    console.error('Failed to import settings:', error);
+    importStatus.textContent = `Failed to import: ${error}`;
+  } finally {
+    importBtn.disabled = false;
+  }
 }
 
 async function searchCategories(query) {
@@ -483,18 +1120,49 @@ async function autoSave() {
       await invoke('save_config', { config: currentConfig });
     } else {
       // Full settings mode
+      // 0 is a meaningful value here (disables suppression), so only fall back on NaN
+      const restartWindow = parseInt(notifyRestartWindowInput.value, 10);
+      const batchThreshold = parseInt(notifyBatchThresholdInput.value, 10);
+      // Start from the loaded config so fields without a form control survive the save
       const newConfig = {
+        ...config,
         poll_interval_sec: parseInt(pollIntervalInput.value, 10) || 60,
         notify_max_gap_min: parseInt(notifyMaxGapInput.value, 10) || 10,
+        notify_restart_window_min: Number.isNaN(restartWindow) ? 15 : restartWindow,
+        notify_batch_threshold: Number.isNaN(batchThreshold) ? 3 : batchThreshold,
         notify_on_live: notifyOnLiveInput.checked,
         notify_on_category: notifyOnCategoryInput.checked,
         notify_on_hot: notifyOnHotInput.checked,
+        notify_silent_in_followed_categories: notifySilentInFollowedCategoriesInput.checked,
+        hide_followed_in_categories: hideFollowedInCategoriesInput.checked,
+        notify_watch_streak: notifyWatchStreakInput.checked,
+        quiet_hours: quietHoursValue(),
+        title_alerts: titleAlertsInput.value.split('\n').map(line => line.trim()).filter(line => line),
+        start_logged_out: startLoggedOutInput.checked,
+        token_storage: tokenStorageInput.value,
+        log_level: logLevelInput.value,
+        proxy_url: proxyUrlInput.value.trim() || null,
+        tray_icon_live_count: trayIconLiveCountInput.checked,
+        icon_theme: iconThemeInput.value,
+        global_shortcut: globalShortcutInput.value.trim() || null,
+        global_shortcut_action: globalShortcutActionInput.value,
         hotness_z_threshold: parseFloat(hotnessZThresholdInput.value) || 2.0,
         hotness_min_observations: parseInt(hotnessMinObservationsInput.value, 10) || 5,
         hotness_min_streams: parseInt(hotnessMinStreamsInput.value, 10) || 7,
         schedule_lookahead_hours: parseInt(scheduleLookaheadInput.value, 10) || 6,
+        show_inferred_schedules: showInferredSchedulesInput.checked,
+        schedule_inference_model: scheduleInferenceModelInput.value,
+        schedule_inference_lookback_weeks: parseInt(scheduleInferenceLookbackInput.value, 10) || 8,
         live_menu_limit: parseInt(liveMenuLimitInput.value, 10) || 10,
         schedule_menu_limit: parseInt(scheduleMenuLimitInput.value, 10) || 5,
+        category_more_pages: parseInt(categoryMorePagesInput.value, 10) || 3,
+        max_label_width: parseInt(maxLabelWidthInput.value, 10) || 25,
+        live_label_format: liveLabelFormatInput.value.trim() || '{name} - {game} ({viewers}, {uptime})',
+        live_sort: liveSortInput.value,
+        favourite_sort_mode: favouriteSortModeInput.value,
+        notification_open_mode: notificationOpenModeInput.value,
+        pip_command: pipCommandInput.value.trim(),
+        open_with: openWithValue(openWithInput.value, openWithCommandInput.value),
         followed_categories: config.followed_categories || [],
         streamer_settings: config.streamer_settings || {}
       };
@@ -502,20 +1170,38 @@ async function autoSave() {
       // Validate
       newConfig.poll_interval_sec = Math.max(30, Math.min(300, newConfig.poll_interval_sec));
       newConfig.notify_max_gap_min = Math.max(1, Math.min(60, newConfig.notify_max_gap_min));
+      newConfig.notify_restart_window_min = Math.max(0, Math.min(120, newConfig.notify_restart_window_min));
+      newConfig.notify_batch_threshold = Math.max(0, Math.min(20, newConfig.notify_batch_threshold));
       newConfig.hotness_z_threshold = Math.max(0.5, Math.min(5.0, newConfig.hotness_z_threshold));
       newConfig.hotness_min_observations = Math.max(1, Math.min(50, newConfig.hotness_min_observations));
       newConfig.hotness_min_streams = Math.max(1, Math.min(30, newConfig.hotness_min_streams));
       newConfig.schedule_lookahead_hours = Math.max(1, Math.min(72, newConfig.schedule_lookahead_hours));
+      newConfig.schedule_inference_lookback_weeks = Math.max(2, Math.min(52, newConfig.schedule_inference_lookback_weeks));
       newConfig.live_menu_limit = Math.max(1, Math.min(50, newConfig.live_menu_limit));
       newConfig.schedule_menu_limit = Math.max(1, Math.min(20, newConfig.schedule_menu_limit));
+      newConfig.category_more_pages = Math.max(1, Math.min(10, newConfig.category_more_pages));
+      newConfig.max_label_width = Math.max(10, Math.min(80, newConfig.max_label_width));
 
       await invoke('save_config', { config: newConfig });
     }
+    showValidationErrors([]);
   } catch (error) {
-    console.error('Failed to auto-save config:', error);
+    if (error && error.kind === 'invalid') {
+      showValidationErrors(error.errors);
+    } else {
+      console.error('Failed to auto-save config:', error);
+    }
   }
 }
 
+// Lists the settings save_config rejected; an empty list hides the box
+function showValidationErrors(errors) {
+  const list = document.getElementById('validation_errors');
+  if (!list) return;
+  list.innerHTML = errors.map(e => `<li>${escapeHtml(e.message)}</li>`).join('');
+  list.hidden = errors.length === 0;
+}
+
 function escapeHtml(text) {
   const div = document.createElement('div');
   div.textContent = text;
@@ -525,11 +1211,138 @@ function escapeHtml(text) {
 // Make functions available globally for onclick handlers
 window.addCategory = addCategory;
 window.removeCategory = removeCategory;
+window.acceptSuggestion = acceptSuggestion;
+window.dismissSuggestion = dismissSuggestion;
 window.selectStreamer = selectStreamer;
 window.addStreamer = addStreamer;
 window.removeStreamer = removeStreamer;
 window.updateStreamerImportance = updateStreamerImportance;
+window.updateStreamerOpenMode = updateStreamerOpenMode;
+window.updateStreamerSound = updateStreamerSound;
+window.updateStreamerOpenWith = updateStreamerOpenWith;
 window.updateStreamerHotnessOverride = updateStreamerHotnessOverride;
+window.updateStreamerScheduleReminder = updateStreamerScheduleReminder;
+window.updateStreamerCategoryChange = updateStreamerCategoryChange;
+
+// === History tab functions ===
+
+// Formats a Date as the local "YYYY-MM-DDTHH:MM" value a datetime-local input expects
+function toDateTimeLocal(d) {
+  const pad = (n) => String(n).padStart(2, '0');
+  return `${d.getFullYear()}-${pad(d.getMonth() + 1)}-${pad(d.getDate())}T${pad(d.getHours())}:${pad(d.getMinutes())}`;
+}
+
+function formatHistoryTime(secs) {
+  return new Date(secs * 1000).toLocaleString([], {
+    weekday: 'short',
+    hour: '2-digit',
+    minute: '2-digit',
+  });
+}
+
+async function loadLiveAt() {
+  const at = new Date(historyAtInput.value);
+  if (isNaN(at.getTime())) return;
+
+  const tbody = document.getElementById('history-tbody');
+  try {
+    const entries = await invoke('get_live_at', { timestamp: Math.floor(at.getTime() / 1000) });
+    if (entries.length === 0) {
+      tbody.innerHTML = '<tr><td colspan="3" class="history-empty">Nobody you follow was live then</td></tr>';
+      return;
+    }
+    tbody.innerHTML = entries.map(e => `<tr>
+        <td>${escapeHtml(e.broadcaster_name)}</td>
+        <td>${formatHistoryTime(e.started_at)}</td>
+        <td>${e.ended_at != null ? formatHistoryTime(e.ended_at) : '\u2014'}</td>
+      </tr>`).join('');
+  } catch (e) {
+    console.error('Failed to load stream history:', e);
+  }
+}
+
+const WEEKDAYS = ['Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat', 'Sun'];
+
+// "Mon/Wed around 20:00" from a StreamerStats' most common days and hours
+function formatTypicalStart(stats) {
+  const days = stats.typical_days.slice(0, 2).map(d => WEEKDAYS[d]).join('/');
+  const hour = stats.typical_start_hours[0];
+  if (hour == null) return days;
+  return `${days} around ${String(hour).padStart(2, '0')}:00`;
+}
+
+// "2h 30m" from minutes
+function formatDurationMins(mins) {
+  const h = Math.floor(mins / 60);
+  const m = mins % 60;
+  if (h === 0) return `${m}m`;
+  return m === 0 ? `${h}h` : `${h}h ${m}m`;
+}
+
+async function loadHistorySummary() {
+  const tbody = document.getElementById('habits-tbody');
+  try {
+    const stats = await invoke('get_history_summary');
+    if (stats.length === 0) {
+      tbody.innerHTML = '<tr><td colspan="6" class="history-empty">No streams recorded yet</td></tr>';
+      return;
+    }
+    tbody.innerHTML = stats.map(s => `<tr>
+        <td>${escapeHtml(s.broadcaster_name)}</td>
+        <td>${s.stream_count}</td>
+        <td>${s.streams_per_week.toFixed(1)}</td>
+        <td>${formatTypicalStart(s)}</td>
+        <td>${s.average_duration_mins != null ? formatDurationMins(s.average_duration_mins) : '\u2014'}</td>
+        <td>${formatHistoryTime(s.last_started_at)}</td>
+      </tr>`).join('');
+  } catch (e) {
+    console.error('Failed to load streaming habits:', e);
+  }
+}
+
+// === Diagnostics tab functions ===
+
+function formatDiagnosticsTime(iso) {
+  if (!iso) return 'Never';
+  return new Date(iso).toLocaleString();
+}
+
+function formatBytes(bytes) {
+  if (bytes == null) return 'Unknown';
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+async function loadDiagnostics() {
+  const tbody = document.getElementById('diagnostics-tbody');
+  try {
+    const d = await invoke('get_diagnostics');
+    const rows = [
+      ['Version', d.app_version],
+      ['Platform', `${d.platform.os} ${d.platform.arch}`],
+      ['Logged in', d.authenticated ? `Yes (${d.user_login})` : 'No'],
+      ['Token expires', formatDiagnosticsTime(d.token_expires_at)],
+      ['Token refresh failures', d.token_refresh_failures],
+      ['Last live refresh', formatDiagnosticsTime(d.last_live_refresh)],
+      ['Live refresh failures', d.live_refresh_failures],
+      ['Last follows refresh', formatDiagnosticsTime(d.last_followed_refresh)],
+      ['Followed channels', d.followed_channel_count],
+      ['Live streams', d.live_stream_count],
+      ['Scheduled streams', d.scheduled_stream_count],
+      ['Database', d.db_path],
+      ['Database size', formatBytes(d.db_size_bytes)],
+      ['Logs', d.log_dir ?? 'Unknown'],
+    ];
+    tbody.innerHTML = rows.map(([label, value]) => `<tr>
+        <th scope="row">${label}</th>
+        <td>${escapeHtml(String(value))}</td>
+      </tr>`).join('');
+  } catch (e) {
+    console.error('Failed to load diagnostics:', e);
+    tbody.innerHTML = `<tr><td class="history-empty">Failed to load diagnostics: ${escapeHtml(String(e))}</td></tr>`;
+  }
+}
 
 // === Debug tab functions ===
 